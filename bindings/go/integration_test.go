@@ -2,6 +2,7 @@ package t2z
 
 import (
 	"encoding/hex"
+	"strings"
 	"testing"
 
 	"github.com/decred/dcrd/dcrec/secp256k1/v4"
@@ -413,3 +414,37 @@ func TestAppendSignatureInvalidIndex(t *testing.T) {
 	}
 	t.Logf("✓ Got expected error: %v", err)
 }
+
+// TestLongErrorMessage checks that error messages longer than any fixed buffer
+// come through whole
+func TestLongErrorMessage(t *testing.T) {
+	_, pubkey := createTestKeypair()
+
+	var txid [32]byte
+	inputs := []TransparentInput{
+		{
+			Pubkey:       pubkey,
+			TxID:         txid,
+			Vout:         0,
+			Amount:       100_000_000,
+			ScriptPubKey: createP2PKHScript(pubkey),
+		},
+	}
+
+	// The invalid address is echoed in the error message
+	address := "t" + strings.Repeat("x", 1000)
+	request, err := NewTransactionRequest([]Payment{{Address: address, Amount: 50_000_000}})
+	if err != nil {
+		t.Fatalf("Failed to create transaction request: %v", err)
+	}
+	defer request.Free()
+	request.SetTargetHeight(2_500_000)
+
+	_, err = ProposeTransaction(inputs, request)
+	if err == nil {
+		t.Fatal("Expected error for invalid address, got nil")
+	}
+	if !strings.Contains(err.Error(), address) {
+		t.Errorf("Error message was cut short: %v", err)
+	}
+}
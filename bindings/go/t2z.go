@@ -87,7 +87,8 @@ func (r ResultCode) String() string {
 
 // getLastError retrieves the last error message from the Rust library
 func getLastError() string {
-	buf := make([]byte, 512)
+	// Sized by the library, so long messages (e.g. from verification) aren't refused
+	buf := make([]byte, int(C.pczt_get_last_error_len()))
 	code := C.pczt_get_last_error((*C.char)(unsafe.Pointer(&buf[0])), C.size_t(len(buf)))
 	if code != C.SUCCESS {
 		return "Failed to get last error"
@@ -7,13 +7,20 @@ import {
   TransactionRequest,
   proposeTransaction,
   proveTransaction,
+  proveTransactionAsync,
+  ProofProgress,
   getSighash,
   appendSignature,
+  signTransparent,
+  combine,
   finalizeAndExtract,
+  finalizeAndExtractAsync,
   serializePczt,
   parsePczt,
   verifyBeforeSigning,
+  verifyReport,
   signMessage,
+  summarizePczt,
 } from '../src';
 
 // Test keys matching Go and Rust tests
@@ -79,6 +86,31 @@ describe('t2z TypeScript Bindings', () => {
       request.free();
     });
 
+    it('should apply expiry options from the constructor', () => {
+      const payments: Payment[] = [
+        {
+          address: 'tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma',
+          amount: (100_000n).toString(),
+        },
+      ];
+      const inputs: TransparentInput[] = [
+        {
+          pubkey: TEST_PUBLIC_KEY,
+          txid: TEST_TXID,
+          vout: 0,
+          amount: (100_000_000n).toString(),
+          scriptPubKey: TEST_SCRIPT_PUBKEY,
+        },
+      ];
+
+      const request = new TransactionRequest(payments, { targetHeight: 2_500_000, expiryDelta: 100 });
+      expect(summarizePczt(proposeTransaction(inputs, request)).expiry_height).toBe(2_500_100);
+
+      request.setExpiryHeight(0);
+      expect(summarizePczt(proposeTransaction(inputs, request)).expiry_height).toBe(0);
+      request.free();
+    });
+
     it('should reject empty payment array', () => {
       // The C FFI does not allow null payment pointers
       expect(() => new TransactionRequest([])).toThrow(/Null pointer/);
@@ -138,6 +170,32 @@ describe('t2z TypeScript Bindings', () => {
       request.free();
     });
 
+    it('should prove off the event loop with progress', async () => {
+      const request = new TransactionRequest([
+        { address: 'tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma', amount: (100_000n).toString() },
+      ]);
+      const inputs: TransparentInput[] = [
+        {
+          pubkey: TEST_PUBLIC_KEY,
+          txid: TEST_TXID,
+          vout: 0,
+          amount: (100_000_000n).toString(),
+          scriptPubKey: TEST_SCRIPT_PUBKEY,
+        },
+      ];
+
+      const stages: ProofProgress['stage'][] = [];
+      const proved = await proveTransactionAsync(proposeTransaction(inputs, request), (p) => stages.push(p.stage));
+      expect(stages[0]).toBe('proving');
+      expect(stages[stages.length - 1]).toBe('done');
+      expect(getSighash(proved, 0)).toHaveLength(32);
+
+      const txBytes = await finalizeAndExtractAsync(signTransparent(proved, 0, TEST_PRIVATE_KEY));
+      expect(txBytes.length).toBeGreaterThan(0);
+
+      request.free();
+    });
+
     it('should handle multiple inputs', async () => {
       const payments: Payment[] = [
         {
@@ -225,6 +283,42 @@ describe('t2z TypeScript Bindings', () => {
     });
   });
 
+  describe('Combine', () => {
+    it('should merge a proved copy and a signed copy', () => {
+      const request = new TransactionRequest([
+        { address: 'tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma', amount: (100_000n).toString() },
+      ]);
+      const inputs: TransparentInput[] = [
+        {
+          pubkey: TEST_PUBLIC_KEY,
+          txid: TEST_TXID,
+          vout: 0,
+          amount: (100_000_000n).toString(),
+          scriptPubKey: TEST_SCRIPT_PUBKEY,
+        },
+      ];
+
+      // The prover and the signer each work on their own copy of the proposal
+      const proposal = serializePczt(proposeTransaction(inputs, request));
+      const proved = proveTransaction(parsePczt(proposal));
+      const signed = signTransparent(parsePczt(proposal), 0, TEST_PRIVATE_KEY);
+
+      // The signer's copy arrives serialized, as it would from another device
+      const combined = combine([proved, serializePczt(signed)]);
+      signed.free();
+
+      const txBytes = finalizeAndExtract(combined);
+      expect(txBytes.length).toBeGreaterThan(0);
+      expect(() => proved.getHandle()).toThrow('PCZT already freed');
+
+      request.free();
+    });
+
+    it('should reject an empty list', () => {
+      expect(() => combine([])).toThrow('At least one PCZT is required');
+    });
+  });
+
   describe('Error Handling', () => {
     it('should reject invalid input index for getSighash', () => {
       const payments: Payment[] = [
@@ -478,6 +572,56 @@ describe('t2z TypeScript Bindings', () => {
       request.free();
     });
 
+    it('should report every failed check with verifyReport', () => {
+      const payments: Payment[] = [
+        {
+          address: 'tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma',
+          amount: (50_000n).toString(),
+        },
+      ];
+
+      const request = new TransactionRequest(payments);
+
+      const inputs: TransparentInput[] = [
+        {
+          pubkey: TEST_PUBLIC_KEY,
+          txid: TEST_TXID,
+          vout: 0,
+          amount: (100_000_000n).toString(),
+          scriptPubKey: TEST_SCRIPT_PUBKEY,
+        },
+      ];
+
+      const pczt = proposeTransaction(inputs, request);
+      const proved = proveTransaction(pczt);
+
+      // Wrong payment amount and wrong change amount
+      const wrongRequest = new TransactionRequest([
+        {
+          address: 'tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma',
+          amount: (40_000n).toString(),
+        },
+      ]);
+      const wrongChange = [
+        {
+          scriptPubKey: TEST_SCRIPT_PUBKEY,
+          value: (1_000_000n).toString(),
+        },
+      ];
+
+      const report = verifyReport(proved, wrongRequest, wrongChange);
+      const errors = report.findings.filter((f) => f.severity === 'error').map((f) => f.code);
+      expect(errors).toEqual(['CHANGE_MISMATCH', 'OUTPUT_MISMATCH']);
+      expect(report.findings.some((f) => f.severity === 'warning')).toBe(true);
+
+      // The matching request and change leave no errors
+      expect(verifyReport(proved, request, []).findings.every((f) => f.severity === 'warning')).toBe(true);
+
+      proved.free();
+      request.free();
+      wrongRequest.free();
+    });
+
     it('should fail verification with wrong change script', () => {
       const payments: Payment[] = [
         {
@@ -56,23 +56,112 @@
 // Export all FFI bindings from lib.ts
 export {
   ResultCode,
+  ErrorDomain,
   T2zError,
+  InsufficientFunds,
+  ErrorDetails,
   Payment,
   TransparentInput,
   TransparentOutput,
+  ParseOptions,
+  MemoEncoding,
+  Network,
+  TransactionRequestOptions,
+  CoinSelection,
+  ChangePolicy,
+  DustPolicy,
+  FeePolicy,
+  OrchardFlags,
+  TransparentInputInfo,
+  KeyDerivation,
+  OrchardOutputInfo,
+  ReviewDocument,
+  SignedManifest,
+  BlindedReview,
+  ProofProgress,
+  ProposalJson,
+  DecodedTransactionJson,
+  VerificationReportJson,
+  TransactionSummary,
+  BuildInfo,
+  ErrorRecord,
+  PrevoutJson,
+  OrchardOutputJson,
+  AmountUnit,
+  Strictness,
   TransactionRequest,
   PCZT,
   proposeTransaction,
   proposeTransactionWithChange,
+  proposeTransactionFromPool,
   proveTransaction,
+  proveTransactionAsync,
+  proveTransactions,
+  finalizeAndExtractAsync,
   verifyBeforeSigning,
+  verifyReport,
+  verifyExtraction,
+  exportReview,
+  exportBlindedReview,
+  verifyBlindedReview,
+  verifyReview,
+  signManifest,
+  verifyManifest,
   getSighash,
+  exportSighashBatch,
+  validateSighashBatch,
+  getFingerprint,
+  stampReservation,
+  getReservationToken,
+  getLabel,
+  getInputSequence,
+  getInputPubkey,
+  getChangeOwnershipMessage,
+  verifyChangeOwnership,
+  validatePczt,
+  getOrchardOutputs,
+  decodeOrchardOutputs,
+  decodeTransaction,
+  summarizePczt,
   appendSignature,
+  signTransparent,
   combine,
   finalizeAndExtract,
   serializePczt,
   parsePczt,
+  savePczt,
+  loadPczt,
+  isCanonical,
+  registerProprietaryNamespace,
+  isTransparentOnly,
+  getOrchardFlags,
+  getTransparentInputCount,
+  getTransparentInput,
+  getTransparentOutputCount,
+  getTransparentOutput,
+  getOrchardActionCount,
+  hasOrchardProof,
   calculateFee,
+  conventionalFee,
+  estimateTxSize,
+  estimatedFinalSize,
+  MAX_TX_SIZE,
+  DEFAULT_DUST_THRESHOLD,
+  getP2pkhDustThreshold,
+  getBuildInfo,
+  getErrorHistory,
+  scriptToAddress,
+  addressToScript,
+  setParamsDir,
+  useMemoryParamsStore,
+  initProvingKeys,
+  unloadVerifyingKeys,
+  setProverThreads,
+  setAddressCacheCapacity,
+  clearAddressCache,
+  setStrictness,
+  formatAmount,
+  decodeMemo,
 } from './lib';
 
 // Re-export signing utilities
@@ -62,9 +62,58 @@ export enum ResultCode {
   ErrorCombine = 15,
   ErrorFinalization = 16,
   ErrorParse = 17,
+  ErrorInspection = 18,
+  ErrorMemo = 19,
+  ErrorReservation = 20,
+  ErrorFile = 21,
   ErrorNotImplemented = 99,
 }
 
+/**
+ * Subsystem an error comes from, independent of its ResultCode
+ * (see `pczt_error_domain`). Numbers are stable; new domains take the next one.
+ */
+export enum ErrorDomain {
+  Proposal = 1,
+  Policy = 2,
+  Memo = 3,
+  Network = 4,
+  Prover = 5,
+  Verification = 6,
+  Signing = 7,
+  Combine = 8,
+  Update = 9,
+  Reservation = 10,
+  Finalization = 11,
+  Parse = 12,
+  File = 13,
+  Interface = 14,
+}
+
+/**
+ * Amounts reported with an `INSUFFICIENT_FUNDS` error, in zatoshis
+ */
+export interface InsufficientFunds {
+  /** Total of the inputs considered */
+  available: bigint;
+  /** Payments plus fee */
+  required: bigint;
+  /** ZIP-317 fee included in `required` */
+  fee: bigint;
+}
+
+/**
+ * Machine-readable details of an error (see `pczt_get_last_error_details`)
+ */
+export interface ErrorDetails {
+  /** Subsystem the error comes from */
+  domain: ErrorDomain;
+  /** Stable number of the error code */
+  subCode: number;
+  /** Numbers describing the error, in the order documented for its code */
+  context: bigint[];
+}
+
 /**
  * Error class for t2z operations.
  *
@@ -78,6 +127,9 @@ export enum ResultCode {
  *   if (e instanceof T2zError && e.code === ResultCode.ErrorProver) {
  *     console.error('Proof generation failed:', e.message);
  *   }
+ *   if (e instanceof T2zError && e.errorCode === 'INSUFFICIENT_FUNDS') {
+ *     // re-quote the customer, short by e.insufficientFunds.required - e.insufficientFunds.available
+ *   }
  * }
  * ```
  */
@@ -85,10 +137,47 @@ export class T2zError extends Error {
   /** The result code from the native library */
   public readonly code: ResultCode;
 
-  constructor(message: string, code: ResultCode) {
+  /**
+   * Stable, machine-readable error code (e.g. `"INSUFFICIENT_FUNDS"`).
+   * Defined by the Rust core, so codes are identical across all bindings.
+   */
+  public readonly errorCode?: string;
+
+  /** Index of the transparent input the error refers to, if any */
+  public readonly inputIndex?: number;
+
+  /** Amounts of an `INSUFFICIENT_FUNDS` error */
+  public readonly insufficientFunds?: InsufficientFunds;
+
+  /** Stable number of `errorCode`, for looking up localized messages */
+  public readonly subCode?: number;
+
+  /** Subsystem the error comes from (network, policy, ...) */
+  public readonly domain?: ErrorDomain;
+
+  /**
+   * Numbers describing the error, in the order the Rust core documents for its code
+   * (e.g. available, required and fee for `INSUFFICIENT_FUNDS`)
+   */
+  public readonly context: bigint[];
+
+  constructor(
+    message: string,
+    code: ResultCode,
+    inputIndex?: number,
+    errorCode?: string,
+    insufficientFunds?: InsufficientFunds,
+    details?: ErrorDetails
+  ) {
     super(message);
     this.name = 'T2zError';
     this.code = code;
+    this.errorCode = errorCode;
+    this.inputIndex = inputIndex;
+    this.insufficientFunds = insufficientFunds;
+    this.subCode = details?.subCode;
+    this.domain = details?.domain;
+    this.context = details?.context ?? [];
     // Maintains proper stack trace in V8 environments
     if (Error.captureStackTrace) {
       Error.captureStackTrace(this, T2zError);
@@ -108,14 +197,73 @@ const CPayment = koffi.struct('CPayment', {
   message: 'const char*',
 });
 
+const CErrorInfo = koffi.struct('CErrorInfo', {
+  code: 'uint32_t',
+  has_input_index: 'bool',
+  input_index: 'size_t',
+});
+
+const CErrorDetails = koffi.struct('CErrorDetails', {
+  result_code: 'uint32_t',
+  sub_code: 'uint32_t',
+  context_len: 'size_t',
+  context: koffi.array('uint64_t', 3),
+});
+
+const CInsufficientFunds = koffi.struct('CInsufficientFunds', {
+  available: 'uint64_t',
+  required: 'uint64_t',
+  fee: 'uint64_t',
+});
+
 const CTransparentOutput = koffi.struct('CTransparentOutput', {
   script_pub_key: 'const uint8_t*',
   script_pub_key_len: 'size_t',
   value: 'uint64_t',
 });
 
+// CTransparentOutput as filled in by the library: the script pointer is read with
+// copyNative, so it is declared opaque
+const CTransparentOutputView = koffi.struct('CTransparentOutputView', {
+  script_pub_key: 'const void*',
+  script_pub_key_len: 'size_t',
+  value: 'uint64_t',
+});
+
+const CTransparentInputInfo = koffi.struct('CTransparentInputInfo', {
+  prevout_txid: koffi.array('uint8_t', 32),
+  prevout_index: 'uint32_t',
+  sequence: 'uint32_t',
+  value: 'uint64_t',
+  script_pub_key: 'const void*',
+  script_pub_key_len: 'size_t',
+  num_signatures: 'size_t',
+  is_finalized: 'bool',
+});
+
+const COrchardOutput = koffi.struct('COrchardOutput', {
+  action_index: 'size_t',
+  cmx: koffi.array('uint8_t', 32),
+  ephemeral_key: koffi.array('uint8_t', 32),
+  has_value: 'bool',
+  value: 'uint64_t',
+});
+
 // Define FFI functions with proper _out parameters
 const pczt_get_last_error = lib.func('uint32_t pczt_get_last_error(_Out_ char* buffer, size_t buffer_len)');
+const pczt_get_last_error_len = lib.func('size_t pczt_get_last_error_len()');
+const pczt_get_error_history = lib.func(
+  'uint32_t pczt_get_error_history(_Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_get_last_error_code = lib.func('uint32_t pczt_get_last_error_code(_Out_ char* buffer, size_t buffer_len)');
+
+const pczt_get_last_error_info = lib.func('uint32_t pczt_get_last_error_info(_Out_ CErrorInfo* info_out)');
+const pczt_get_last_error_details = lib.func('uint32_t pczt_get_last_error_details(_Out_ CErrorDetails* details_out)');
+const pczt_error_domain = lib.func('uint32_t pczt_error_domain()');
+const pczt_get_last_error_insufficient_funds = lib.func(
+  'bool pczt_get_last_error_insufficient_funds(_Out_ CInsufficientFunds* funds_out)'
+);
 
 const pczt_transaction_request_new = lib.func(
   'uint32_t pczt_transaction_request_new(const CPayment* payments, size_t num_payments, _Out_ void** request_out)'
@@ -131,61 +279,410 @@ const pczt_transaction_request_set_use_mainnet = lib.func(
   'uint32_t pczt_transaction_request_set_use_mainnet(void* request, bool use_mainnet)'
 );
 
+const pczt_transaction_request_set_network = lib.func(
+  'uint32_t pczt_transaction_request_set_network(void* request, uint32_t network)'
+);
+
+const pczt_transaction_request_set_expiry_height = lib.func(
+  'uint32_t pczt_transaction_request_set_expiry_height(void* request, uint32_t expiry_height)'
+);
+
+const pczt_transaction_request_set_expiry_delta = lib.func(
+  'uint32_t pczt_transaction_request_set_expiry_delta(void* request, uint32_t expiry_delta)'
+);
+
+const pczt_transaction_request_set_fee_tolerance = lib.func(
+  'uint32_t pczt_transaction_request_set_fee_tolerance(void* request, uint64_t fee_tolerance)'
+);
+
+const pczt_transaction_request_set_max_fee = lib.func(
+  'uint32_t pczt_transaction_request_set_max_fee(void* request, uint64_t max_fee)'
+);
+
+const pczt_transaction_request_set_change_dust_threshold = lib.func(
+  'uint32_t pczt_transaction_request_set_change_dust_threshold(void* request, uint64_t threshold)'
+);
+
+const pczt_transaction_request_set_dust_policy = lib.func(
+  'uint32_t pczt_transaction_request_set_dust_policy(void* request, uint32_t policy, uint64_t threshold)'
+);
+
+const pczt_transaction_request_set_fee_policy = lib.func(
+  'uint32_t pczt_transaction_request_set_fee_policy(void* request, uint32_t policy, uint64_t fixed_fee, double multiplier)'
+);
+
+const pczt_transaction_request_set_memo_encoding = lib.func(
+  'uint32_t pczt_transaction_request_set_memo_encoding(void* request, uint32_t encoding)'
+);
+
+const pczt_experimental_transaction_request_set_coin_selection = lib.func(
+  'uint32_t pczt_experimental_transaction_request_set_coin_selection(void* request, uint32_t strategy)'
+);
+const pczt_transaction_request_set_sweep = lib.func(
+  'uint32_t pczt_transaction_request_set_sweep(void* request, bool sweep)'
+);
+const pczt_transaction_request_set_change_policy = lib.func(
+  'uint32_t pczt_transaction_request_set_change_policy(void* request, uint32_t policy)'
+);
+
+const COrchardFlags = koffi.struct('COrchardFlags', {
+  spends_enabled: 'bool',
+  outputs_enabled: 'bool',
+});
+
+const pczt_transaction_request_set_orchard_flags = lib.func(
+  'uint32_t pczt_transaction_request_set_orchard_flags(void* request, const COrchardFlags* flags)'
+);
+
+const pczt_transaction_request_set_ovk = lib.func(
+  'uint32_t pczt_transaction_request_set_ovk(void* request, const uint8_t* ovk)'
+);
+
+const pczt_transaction_request_set_payment_ovk = lib.func(
+  'uint32_t pczt_transaction_request_set_payment_ovk(void* request, size_t payment_index, const uint8_t* ovk)'
+);
+
+const pczt_transaction_request_set_payment_script = lib.func(
+  'uint32_t pczt_transaction_request_set_payment_script(void* request, size_t payment_index, const uint8_t* script, size_t script_len)'
+);
+
+const pczt_transaction_request_set_payment_memo_bytes = lib.func(
+  'uint32_t pczt_transaction_request_set_payment_memo_bytes(void* request, size_t payment_index, const uint8_t* memo, size_t memo_len)'
+);
+
+const pczt_transaction_request_set_min_confirmations = lib.func(
+  'uint32_t pczt_transaction_request_set_min_confirmations(void* request, uint32_t min_confirmations)'
+);
+
+const pczt_transaction_request_set_label = lib.func(
+  'uint32_t pczt_transaction_request_set_label(void* request, const char* label)'
+);
+
 const pczt_propose_transaction = lib.func(
   'uint32_t pczt_propose_transaction(const uint8_t* inputs_bytes, size_t inputs_bytes_len, const void* request, const char* change_address, _Out_ void** pczt_out)'
 );
 
+const pczt_propose_transaction_from_pool = lib.func(
+  'uint32_t pczt_propose_transaction_from_pool(const uint8_t* inputs_bytes, size_t inputs_bytes_len, const void* request, const char** change_addresses, size_t num_change_addresses, _Out_ void** pczt_out)'
+);
+
 const pczt_prove_transaction = lib.func('uint32_t pczt_prove_transaction(void* pczt, _Out_ void** pczt_out)');
+const pczt_prove_batch = lib.func(
+  'uint32_t pczt_prove_batch(void** pczts, size_t num_pczts, _Out_ void** pczts_out, _Out_ uint32_t* results_out)'
+);
+
+const pczt_prove_transaction_on_worker = lib.func(
+  'uint32_t pczt_prove_transaction_on_worker(void* pczt, _Out_ void** pczt_out, _Out_ uint8_t* message_buf, size_t message_len, _Out_ uint8_t* code_buf, size_t code_len)'
+);
 
 const pczt_verify_before_signing = lib.func(
   'uint32_t pczt_verify_before_signing(const void* pczt, const void* request, const CTransparentOutput* expected_change, size_t expected_change_len)'
 );
 
+const pczt_verify_report = lib.func(
+  'uint32_t pczt_verify_report(const void* pczt, const void* request, const CTransparentOutput* expected_change, size_t expected_change_len, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_verify_extraction = lib.func(
+  'uint32_t pczt_verify_extraction(const void* pczt, const uint8_t* tx_bytes, size_t tx_bytes_len)'
+);
+
+const pczt_export_review = lib.func(
+  'uint32_t pczt_export_review(const void* pczt, const void* request, uint32_t unit, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_transaction_request_from_json = lib.func(
+  'uint32_t pczt_transaction_request_from_json(const uint8_t* json, size_t json_len, _Out_ void** request_out)'
+);
+
+const pczt_transaction_request_to_json = lib.func(
+  'uint32_t pczt_transaction_request_to_json(const void* request, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_proposal_to_json = lib.func(
+  'uint32_t pczt_proposal_to_json(const void* pczt, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_summarize = lib.func(
+  'uint32_t pczt_summarize(const void* pczt, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_decode_transaction_json = lib.func(
+  'uint32_t pczt_decode_transaction_json(const uint8_t* tx_bytes, size_t tx_bytes_len, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_verify_review = lib.func(
+  'uint32_t pczt_verify_review(const void* pczt, const void* request, const uint8_t* json, size_t json_len)'
+);
+
+const pczt_sign_manifest = lib.func(
+  'uint32_t pczt_sign_manifest(const void* pczt, const uint8_t* signing_key, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_verify_manifest = lib.func(
+  'uint32_t pczt_verify_manifest(const void* pczt, const uint8_t* json, size_t json_len, const uint8_t* trusted_key)'
+);
+
+const pczt_export_blinded_review = lib.func(
+  'uint32_t pczt_export_blinded_review(const void* pczt, const void* request, uint32_t unit, _Out_ void** json_out, _Out_ size_t* json_len_out)'
+);
+
+const pczt_verify_blinded_review = lib.func(
+  'uint32_t pczt_verify_blinded_review(const void* pczt, const void* request, const uint8_t* json, size_t json_len)'
+);
+
 const pczt_get_sighash = lib.func(
   'uint32_t pczt_get_sighash(const void* pczt, size_t input_index, _Out_ uint8_t* sighash_out)'
 );
 
+const pczt_get_input_sequence = lib.func(
+  'uint32_t pczt_get_input_sequence(const void* pczt, size_t input_index, _Out_ uint32_t* sequence_out)'
+);
+
+const pczt_get_input_pubkey = lib.func(
+  'uint32_t pczt_get_input_pubkey(const void* pczt, size_t input_index, _Out_ uint8_t* pubkey_out)'
+);
+
+const pczt_export_sighash_batch = lib.func(
+  'uint32_t pczt_export_sighash_batch(const void* pczt, _Out_ void** bytes_out, _Out_ size_t* bytes_len_out)'
+);
+
+const pczt_validate_sighash_batch = lib.func(
+  'uint32_t pczt_validate_sighash_batch(const uint8_t* bytes, size_t bytes_len, _Out_ size_t* num_inputs_out)'
+);
+
+const pczt_change_ownership_message = lib.func(
+  'uint32_t pczt_change_ownership_message(const void* pczt, size_t output_index, _Out_ uint8_t* message_out)'
+);
+
+const pczt_validate = lib.func('uint32_t pczt_validate(const void* pczt)');
+
+const pczt_verify_change_ownership = lib.func(
+  'uint32_t pczt_verify_change_ownership(const void* pczt, size_t output_index, size_t input_index, const uint8_t* signature)'
+);
+
+const pczt_get_orchard_outputs = lib.func(
+  'uint32_t pczt_get_orchard_outputs(const void* pczt, _Out_ COrchardOutput* outputs_out, size_t capacity, _Out_ size_t* outputs_len_out)'
+);
+
+const pczt_tx_get_orchard_outputs = lib.func(
+  'uint32_t pczt_tx_get_orchard_outputs(const uint8_t* tx_bytes, size_t tx_bytes_len, _Out_ COrchardOutput* outputs_out, size_t capacity, _Out_ size_t* outputs_len_out)'
+);
+
+const pczt_get_fingerprint = lib.func(
+  'uint32_t pczt_get_fingerprint(const void* pczt, _Out_ uint8_t* fingerprint_out)'
+);
+
+const pczt_stamp_reservation = lib.func(
+  'uint32_t pczt_stamp_reservation(void* pczt, _Out_ uint8_t* token_out, _Out_ void** pczt_out)'
+);
+
+const pczt_get_reservation_token = lib.func(
+  'uint32_t pczt_get_reservation_token(const void* pczt, _Out_ uint8_t* token_out, _Out_ bool* found_out)'
+);
+
+const pczt_get_label = lib.func(
+  'uint32_t pczt_get_label(const void* pczt, _Out_ void** label_out, _Out_ size_t* label_len_out)'
+);
+
 const pczt_append_signature = lib.func(
   'uint32_t pczt_append_signature(void* pczt, size_t input_index, const uint8_t* signature, _Out_ void** pczt_out)'
 );
 
+const pczt_sign_transparent = lib.func(
+  'uint32_t pczt_sign_transparent(void* pczt, size_t input_index, const uint8_t* secret_key, _Out_ void** pczt_out)'
+);
+
 const pczt_combine = lib.func('uint32_t pczt_combine(void** pczts, size_t num_pczts, _Out_ void** pczt_out)');
 
-const pczt_finalize_and_extract = lib.func(
-  'uint32_t pczt_finalize_and_extract(void* pczt, _Out_ void** tx_bytes_out, _Out_ size_t* tx_bytes_len_out)'
+const pczt_finalize_and_extract_on_worker = lib.func(
+  'uint32_t pczt_finalize_and_extract_on_worker(void* pczt, _Out_ void** tx_bytes_out, _Out_ size_t* tx_bytes_len_out, _Out_ uint8_t* message_buf, size_t message_len, _Out_ uint8_t* code_buf, size_t code_len)'
 );
 
 const pczt_parse = lib.func(
   'uint32_t pczt_parse(const uint8_t* pczt_bytes, size_t pczt_bytes_len, _Out_ void** pczt_out)'
 );
 
-const pczt_serialize = lib.func(
-  'uint32_t pczt_serialize(const void* pczt, _Out_ void** bytes_out, _Out_ size_t* bytes_len_out)'
+const pczt_save = lib.func('uint32_t pczt_save(const void* pczt, const char* path)');
+const pczt_load = lib.func('uint32_t pczt_load(const char* path, _Out_ void** pczt_out)');
+
+const CParseOptions = koffi.struct('CParseOptions', {
+  require_canonical: 'bool',
+  reject_unknown_fields: 'bool',
+});
+
+const pczt_register_proprietary_namespace = lib.func(
+  'uint32_t pczt_register_proprietary_namespace(const char* namespace)'
+);
+
+const pczt_parse_with_options = lib.func(
+  'uint32_t pczt_parse_with_options(const uint8_t* pczt_bytes, size_t pczt_bytes_len, const CParseOptions* options, _Out_ void** pczt_out)'
+);
+
+const pczt_is_canonical = lib.func(
+  'uint32_t pczt_is_canonical(const uint8_t* pczt_bytes, size_t pczt_bytes_len, _Out_ bool* result_out)'
+);
+
+const pczt_serialize_buffer = lib.func('uint32_t pczt_serialize_buffer(const void* pczt, _Out_ void** buffer_out)');
+const pczt_finalize_and_extract_buffer = lib.func(
+  'uint32_t pczt_finalize_and_extract_buffer(void* pczt, _Out_ void** buffer_out)'
+);
+const pczt_buffer_data = lib.func('const void* pczt_buffer_data(const void* buffer)');
+const pczt_buffer_len = lib.func('size_t pczt_buffer_len(const void* buffer)');
+const pczt_buffer_free = lib.func('void pczt_buffer_free(void* buffer)');
+
+const pczt_get_orchard_flags = lib.func(
+  'uint32_t pczt_get_orchard_flags(const void* pczt, _Out_ bool* has_bundle_out, _Out_ COrchardFlags* flags_out)'
+);
+
+const pczt_get_transparent_input_count = lib.func(
+  'uint32_t pczt_get_transparent_input_count(const void* pczt, _Out_ size_t* count_out)'
+);
+const pczt_get_transparent_input = lib.func(
+  'uint32_t pczt_get_transparent_input(const void* pczt, size_t input_index, _Out_ CTransparentInputInfo* input_out)'
+);
+const pczt_get_transparent_output_count = lib.func(
+  'uint32_t pczt_get_transparent_output_count(const void* pczt, _Out_ size_t* count_out)'
+);
+const pczt_get_transparent_output = lib.func(
+  'uint32_t pczt_get_transparent_output(const void* pczt, size_t output_index, _Out_ CTransparentOutputView* output_out)'
+);
+const pczt_get_orchard_action_count = lib.func(
+  'uint32_t pczt_get_orchard_action_count(const void* pczt, _Out_ size_t* count_out)'
+);
+const pczt_has_orchard_proof = lib.func(
+  'uint32_t pczt_has_orchard_proof(const void* pczt, _Out_ bool* has_proof_out)'
+);
+
+const pczt_is_transparent_only = lib.func(
+  'uint32_t pczt_is_transparent_only(const void* pczt, _Out_ bool* result_out)'
 );
 
 const pczt_free = lib.func('void pczt_free(void* pczt)');
 
+const pczt_decode_memo = lib.func(
+  'uint32_t pczt_decode_memo(const uint8_t* memo_bytes, size_t memo_bytes_len, _Out_ void** text_out, _Out_ size_t* text_len_out)'
+);
+
 const pczt_free_bytes = lib.func('void pczt_free_bytes(void* bytes, size_t len)');
 
 const pczt_calculate_fee = lib.func(
   'uint64_t pczt_calculate_fee(size_t num_transparent_inputs, size_t num_transparent_outputs, size_t num_orchard_outputs)'
 );
 
+const pczt_conventional_fee = lib.func(
+  'uint32_t pczt_conventional_fee(const void* pczt, _Out_ uint64_t* fee_out)'
+);
+
+const pczt_estimate_tx_size = lib.func(
+  'size_t pczt_estimate_tx_size(size_t num_transparent_inputs, size_t num_transparent_outputs, size_t num_orchard_outputs)'
+);
+
+const pczt_estimated_final_size = lib.func(
+  'uint32_t pczt_estimated_final_size(const void* pczt, _Out_ size_t* size_out)'
+);
+
+const pczt_format_amount = lib.func(
+  'uint32_t pczt_format_amount(uint64_t zatoshis, uint32_t unit, _Out_ char* buffer, size_t buffer_len)'
+);
+
+const pczt_p2pkh_dust_threshold = lib.func('uint64_t pczt_p2pkh_dust_threshold()');
+
+const pczt_build_info = lib.func('uint32_t pczt_build_info(_Out_ void** json_out, _Out_ size_t* json_len_out)');
+
+const pczt_script_to_address = lib.func(
+  'uint32_t pczt_script_to_address(const uint8_t* script, size_t script_len, bool use_mainnet, _Out_ char* buffer, size_t buffer_len)'
+);
+
+const pczt_address_to_script = lib.func(
+  'uint32_t pczt_address_to_script(const char* address, _Out_ uint8_t* script_out, size_t script_capacity, _Out_ size_t* script_len_out)'
+);
+
+const pczt_set_strictness = lib.func('uint32_t pczt_set_strictness(uint32_t strictness)');
+
+const pczt_set_params_dir = lib.func('uint32_t pczt_set_params_dir(const char* dir)');
+
+const pczt_use_memory_params_store = lib.func('uint32_t pczt_use_memory_params_store()');
+const pczt_init_proving_keys = lib.func('uint32_t pczt_init_proving_keys()');
+
+const pczt_unload_verifying_keys = lib.func('uint32_t pczt_unload_verifying_keys()');
+const pczt_set_prover_threads = lib.func('uint32_t pczt_set_prover_threads(size_t threads)');
+const pczt_set_address_cache_capacity = lib.func('uint32_t pczt_set_address_cache_capacity(size_t capacity)');
+const pczt_clear_address_cache = lib.func('uint32_t pczt_clear_address_cache()');
+
+// Helper: Copy native bytes into a Buffer with a single copy (decoding to a typed
+// array and wrapping its memory, rather than going through a JS number array)
+function copyNative(ptr: any, len: number): Buffer {
+  if (len === 0) return Buffer.alloc(0);
+  const view: Uint8Array = koffi.decode(ptr, koffi.array('uint8_t', len, 'Typed'));
+  return Buffer.from(view.buffer, view.byteOffset, view.byteLength);
+}
+
+// Helper: Copy out a library-owned buffer (ByteBufferHandle) and free it
+function takeBuffer(buffer: any): Buffer {
+  const result = copyNative(pczt_buffer_data(buffer), Number(pczt_buffer_len(buffer)));
+  pczt_buffer_free(buffer);
+  return result;
+}
+
 // Helper: Get last error message
 function getLastError(): string {
-  const buffer = Buffer.alloc(512);
+  const buffer = Buffer.alloc(Number(pczt_get_last_error_len()));
   pczt_get_last_error(buffer, buffer.length);
   const nullIndex = buffer.indexOf(0);
   return buffer.slice(0, nullIndex > 0 ? nullIndex : buffer.length).toString('utf8');
 }
 
+// Helper: Get the stable code of the last error
+function getLastErrorCode(): string | undefined {
+  const buffer = Buffer.alloc(64);
+  pczt_get_last_error_code(buffer, buffer.length);
+  const nullIndex = buffer.indexOf(0);
+  const code = buffer.slice(0, nullIndex >= 0 ? nullIndex : buffer.length).toString('utf8');
+  return code || undefined;
+}
+
+// Helper: Get the input index the last error refers to, if any
+function getLastErrorInputIndex(): number | undefined {
+  const info: any = {};
+  pczt_get_last_error_info(info);
+  return info.has_input_index ? Number(info.input_index) : undefined;
+}
+
+function getLastErrorDetails(): ErrorDetails | undefined {
+  const details: any = {};
+  pczt_get_last_error_details(details);
+  if (!details.sub_code) return undefined;
+  return {
+    domain: pczt_error_domain() as ErrorDomain,
+    subCode: Number(details.sub_code),
+    context: Array.from(details.context as ArrayLike<number | bigint>)
+      .slice(0, Number(details.context_len))
+      .map((value) => BigInt(value)),
+  };
+}
+
+function getLastErrorInsufficientFunds(): InsufficientFunds | undefined {
+  const funds: any = {};
+  if (!pczt_get_last_error_insufficient_funds(funds)) return undefined;
+  return {
+    available: BigInt(funds.available),
+    required: BigInt(funds.required),
+    fee: BigInt(funds.fee),
+  };
+}
+
 // Helper: Check result code and throw on error
 function checkResult(code: number, operation: string): void {
   if (code !== ResultCode.Success) {
     const errorMsg = getLastError();
     throw new T2zError(
       `${operation} failed: ${errorMsg || `error code ${code}`}`,
-      code as ResultCode
+      code as ResultCode,
+      getLastErrorInputIndex(),
+      getLastErrorCode(),
+      getLastErrorInsufficientFunds(),
+      getLastErrorDetails()
     );
   }
 }
@@ -197,8 +694,11 @@ export interface Payment {
   address: string;
   amount: string; // BigInt as string for FFI compatibility
   memo?: string;
+  memoBytes?: Buffer; // Raw memo of up to 512 bytes, stored as-is (replaces `memo`)
   label?: string;
   message?: string;
+  ovk?: Buffer; // 32-byte Orchard outgoing viewing key (shielded payments only)
+  scriptPubKey?: Buffer; // Raw script to pay instead of `address` (leave address empty)
 }
 
 /**
@@ -210,6 +710,18 @@ export interface TransparentInput {
   vout: number;
   amount: string; // BigInt as string
   scriptPubKey: Buffer;
+  height?: number; // Optional height of the block that mined this UTXO
+  redeemScript?: Buffer; // Redeem script of a P2SH multisig UTXO
+  derivationPath?: KeyDerivation; // Recorded in the PCZT for hardware signers
+}
+
+/**
+ * BIP-32 derivation of a transparent key, so a signer can find it
+ */
+export interface KeyDerivation {
+  pubkey?: Buffer; // 33 bytes compressed (default: the input's pubkey)
+  seedFingerprint: Buffer; // 32-byte ZIP-32 seed fingerprint
+  path: number[]; // Child indices from the seed, hardened with the high bit set (0x80000000)
 }
 
 /**
@@ -220,67 +732,397 @@ export interface TransparentOutput {
   value: string; // BigInt as string
 }
 
-// FinalizationRegistry for automatic cleanup when objects are garbage collected
-const requestRegistry = new FinalizationRegistry((handle: any) => {
-  if (handle) {
-    pczt_transaction_request_free(handle);
-  }
-});
+/**
+ * How memo text is stored in the 512-byte memo field
+ */
+export enum MemoEncoding {
+  /** Plain UTF-8 text, zero-padded to 512 bytes */
+  Padded = 0,
+  /** Deflate-compressed text behind a marker byte (read back with decodeMemo) */
+  Compressed = 1,
+}
 
-const pcztRegistry = new FinalizationRegistry((handle: any) => {
-  if (handle) {
-    pczt_free(handle);
-  }
-});
+/**
+ * Enable bits of an Orchard bundle
+ */
+export interface OrchardFlags {
+  spendsEnabled: boolean;
+  /** Must be true if the transaction has Orchard outputs */
+  outputsEnabled: boolean;
+}
 
 /**
- * Transaction request containing multiple payments
+ * A PCZT's transparent input, as returned by getTransparentInput()
  */
-export class TransactionRequest {
-  private handle: any;
-  private freed = false;
+export interface TransparentInputInfo {
+  txid: Buffer; // 32 bytes, internal byte order
+  vout: number;
+  sequence: number; // 0xFFFFFFFF if the input doesn't set one
+  value: string; // BigInt as string
+  scriptPubKey: Buffer;
+  numSignatures: number;
+  /** Whether the input has its final script_sig */
+  isFinalized: boolean;
+}
 
-  constructor(payments: Payment[]) {
-    // Build C payment array with proper structure
-    const cPayments: any[] = [];
-    for (const p of payments) {
-      cPayments.push({
-        address: p.address,
-        amount: BigInt(p.amount),
-        memo: p.memo ?? null,
-        label: p.label ?? null,
-        message: p.message ?? null,
-      });
-    }
+/**
+ * The chain a transaction is built for
+ */
+export enum Network {
+  Mainnet = 0,
+  Testnet = 1,
+  /** Regtest with every upgrade through NU6 active from height 1 */
+  Regtest = 2,
+}
 
-    const handleOut: any[] = [null];
-    const code = pczt_transaction_request_new(
-      cPayments.length > 0 ? cPayments : null,
-      cPayments.length,
-      handleOut
-    );
-    checkResult(code, 'Create transaction request');
-    this.handle = handleOut[0];
+/**
+ * How a proposal picks inputs from the UTXOs it is given
+ */
+export enum CoinSelection {
+  /** Spend every provided input */
+  All = 0,
+  /** Spend the largest UTXOs first */
+  LargestFirst = 1,
+  /** Spend the smallest UTXOs first, consolidating dust */
+  SmallestFirst = 2,
+  /** Look for a set of inputs that needs no change output, else largest-first */
+  BranchAndBound = 3,
+}
 
-    // Register for automatic cleanup on GC
-    requestRegistry.register(this, this.handle, this);
-  }
+/**
+ * Which pool a proposal's change output goes to
+ */
+export enum ChangePolicy {
+  /** Transparent change output */
+  Transparent = 0,
+  /** Orchard change output (needs a unified change address with an Orchard receiver) */
+  Orchard = 1,
+  /** Orchard if the change address has an Orchard receiver, else transparent */
+  Auto = 2,
+}
 
-  /**
-   * Set the target block height for consensus branch ID selection
-   */
+/**
+ * How a proposal treats transparent outputs below the dust threshold
+ */
+export enum DustPolicy {
+  /** Refuse transparent payments below the threshold, and add dust change to the fee */
+  AddChangeToFee = 0,
+  /** Refuse transparent payments and change below the threshold */
+  Reject = 1,
+}
+
+/**
+ * Dust threshold (in zatoshis) wallets conventionally use for transparent outputs
+ */
+export const DEFAULT_DUST_THRESHOLD = 5460n;
+
+/**
+ * How a proposal's fee follows from the ZIP-317 fee (see setFeePolicy)
+ */
+export enum FeePolicy {
+  /** Pay the ZIP-317 conventional fee */
+  Zip317Standard = 0,
+  /** Pay a fixed number of zatoshis */
+  FixedZatoshis = 1,
+  /** Pay the ZIP-317 fee times a multiplier of at least 1 */
+  Zip317WithMultiplier = 2,
+}
+
+/**
+ * Whether lossy best-effort conversions are allowed (see setStrictness)
+ */
+export enum Strictness {
+  /** Drop or ignore data that can't be represented */
+  Lenient = 0,
+  /** Fail instead of dropping memos, ignoring labels/messages or guessing address types */
+  Strict = 1,
+}
+
+/**
+ * Accounting data for one Orchard action's output
+ */
+export interface OrchardOutputInfo {
+  actionIndex: number;
+  cmx: Buffer; // 32-byte note commitment
+  ephemeralKey: Buffer; // 32-byte ephemeral public key
+  value?: string; // BigInt as string; known for PCZTs only (dummy actions are 0)
+}
+
+/**
+ * Unit in which amounts are rendered for display (see formatAmount)
+ */
+export enum AmountUnit {
+  /** "1.00000000 ZEC (100000000 zatoshis)" */
+  Both = 0,
+  /** "1.00000000 ZEC" */
+  Zec = 1,
+  /** "100000000 zatoshis" */
+  Zatoshis = 2,
+}
+
+/**
+ * Transparent input of a proposal or decoded transaction (JSON schema v1).
+ * Txids are hex in display byte order.
+ */
+export interface PrevoutJson {
+  txid: string;
+  vout: number;
+  /** Value of the spent output in zatoshis (proposals only) */
+  value?: number;
+  sequence: number;
+}
+
+/**
+ * Orchard action output of a proposal or decoded transaction (JSON schema v1)
+ */
+export interface OrchardOutputJson {
+  action_index: number;
+  cmx: string;
+  ephemeral_key: string;
+  /** Note value in zatoshis (proposals only) */
+  value?: number;
+}
+
+/**
+ * Summary of a PCZT (JSON schema v1, see PCZT.toJSON)
+ */
+export interface ProposalJson {
+  version: number;
+  fingerprint: string;
+  consensus_branch_id: number;
+  expiry_height: number;
+  inputs: PrevoutJson[];
+  /** `address` is set for P2PKH and P2SH outputs (see scriptToAddress) */
+  outputs: { value: number; script_pubkey: string; address?: string }[];
+  orchard_outputs: OrchardOutputJson[];
+  fee?: number;
+  /** The request's label (see getLabel) */
+  label?: string;
+}
+
+/**
+ * What a PCZT will do, for a confirmation screen before signing (JSON schema v1,
+ * see summarizePczt)
+ */
+export interface TransactionSummary {
+  version: number;
+  network: 'main' | 'test';
+  expiry_height: number;
+  inputs: { txid: string; vout: number; value: number; address: string | null }[];
+  /**
+   * Orchard padding actions are omitted. Transparent addresses come from the output
+   * scripts, and `is_change` marks transparent outputs back to a spent input's
+   * script; Orchard change is never marked.
+   */
+  outputs: {
+    pool: 'transparent' | 'orchard';
+    address: string | null;
+    value: number | null;
+    is_change: boolean;
+  }[];
+  change: number;
+  /** null if the PCZT's values don't balance */
+  fee: number | null;
+  estimated_size: number;
+  label: string | null;
+}
+
+/**
+ * Every failed pre-signing check (JSON schema v1, see verifyReport).
+ * Errors fail verifyBeforeSigning; warnings are for the reviewer to judge.
+ */
+export interface VerificationReportJson {
+  version: number;
+  findings: { severity: 'warning' | 'error'; code: string; message: string }[];
+}
+
+/**
+ * Summary of a final transaction (JSON schema v1, see decodeTransaction)
+ */
+export interface DecodedTransactionJson {
+  version: number;
+  txid: string;
+  expiry_height: number;
+  inputs: PrevoutJson[];
+  outputs: { value: number; script_pubkey: string }[];
+  orchard_outputs: OrchardOutputJson[];
+  orchard_value_balance: number;
+}
+
+/**
+ * Frozen summary of a proposal for four-eyes approval (see exportReview).
+ * Memos are redacted to their length and SHA-256.
+ */
+export interface ReviewDocument {
+  version: number;
+  network: string;
+  unit: 'Zec' | 'Zatoshis' | 'Both';
+  fingerprint: string;
+  inputs: { txid: string; vout: number; value: number; value_display: string; address: string | null }[];
+  spends: { pool: 'orchard' | 'sapling'; value: number; value_display: string }[];
+  outputs: {
+    pool: string;
+    address: string | null;
+    value: number;
+    value_display: string;
+    payment_index: number | null;
+    memo: { length: number; sha256: string } | null;
+  }[];
+  fee: number;
+  fee_display: string;
+  digest: string;
+}
+
+/**
+ * Inputs, outputs, fee and fingerprint of a proposal, signed with the operator's
+ * Ed25519 key (see signManifest). Keys and signature are hex.
+ */
+export interface SignedManifest {
+  manifest: {
+    version: number;
+    fingerprint: string;
+    inputs: { txid: string; vout: number; value: number; address: string | null }[];
+    outputs: { pool: 'transparent' | 'orchard'; address: string | null; value: number | null }[];
+    fee: number;
+  };
+  /** The manifest's JSON exactly as signed; the signature covers these bytes */
+  payload: string;
+  public_key: string;
+  signature: string;
+}
+
+/**
+ * Review for an approver who may not see individual shielded amounts or memos
+ * (see exportBlindedReview). Orchard output values are null; totals are exact.
+ */
+export interface BlindedReview {
+  version: number;
+  network: string;
+  unit: 'Zec' | 'Zatoshis' | 'Both';
+  fingerprint: string;
+  num_inputs: number;
+  num_shielded_spends: number;
+  total_in: number;
+  total_in_display: string;
+  outputs: {
+    pool: string;
+    address_prefix: string | null;
+    value: number | null;
+    payment_index: number | null;
+  }[];
+  total_transparent: number;
+  total_transparent_display: string;
+  total_orchard: number;
+  total_orchard_display: string;
+  fee: number;
+  fee_display: string;
+  digest: string;
+}
+
+// FinalizationRegistry for automatic cleanup when objects are garbage collected
+const requestRegistry = new FinalizationRegistry((handle: any) => {
+  if (handle) {
+    pczt_transaction_request_free(handle);
+  }
+});
+
+const pcztRegistry = new FinalizationRegistry((handle: any) => {
+  if (handle) {
+    pczt_free(handle);
+  }
+});
+
+/**
+ * Transaction request containing multiple payments
+ */
+/**
+ * Settings applied when a TransactionRequest is created (each has a setter too)
+ */
+export interface TransactionRequestOptions {
+  targetHeight?: number;
+  network?: Network;
+  /** Height after which the transaction can no longer be mined (0 = never expires) */
+  expiryHeight?: number;
+  /** Blocks after the target height until the transaction expires (default 40) */
+  expiryDelta?: number;
+}
+
+export class TransactionRequest {
+  private handle: any;
+  private freed = false;
+
+  constructor(payments: Payment[], options: TransactionRequestOptions = {}) {
+    // Build C payment array with proper structure
+    const cPayments: any[] = [];
+    for (const p of payments) {
+      cPayments.push({
+        address: p.address,
+        amount: BigInt(p.amount),
+        memo: p.memo ?? null,
+        label: p.label ?? null,
+        message: p.message ?? null,
+      });
+    }
+
+    const handleOut: any[] = [null];
+    const code = pczt_transaction_request_new(
+      cPayments.length > 0 ? cPayments : null,
+      cPayments.length,
+      handleOut
+    );
+    checkResult(code, 'Create transaction request');
+    this.handle = handleOut[0];
+
+    // Register for automatic cleanup on GC
+    requestRegistry.register(this, this.handle, this);
+
+    payments.forEach((p, index) => {
+      if (p.ovk) this.setPaymentOvk(index, p.ovk);
+      if (p.scriptPubKey) this.setPaymentScript(index, p.scriptPubKey);
+      if (p.memoBytes) this.setPaymentMemoBytes(index, p.memoBytes);
+    });
+
+    if (options.targetHeight !== undefined) this.setTargetHeight(options.targetHeight);
+    if (options.network !== undefined) this.setNetwork(options.network);
+    if (options.expiryHeight !== undefined) this.setExpiryHeight(options.expiryHeight);
+    if (options.expiryDelta !== undefined) this.setExpiryDelta(options.expiryDelta);
+  }
+
+  /**
+   * Set the target block height for consensus branch ID selection
+   */
   setTargetHeight(height: number): void {
     if (this.freed) throw new Error('TransactionRequest already freed');
     const code = pczt_transaction_request_set_target_height(this.handle, height);
     checkResult(code, 'Set target height');
   }
 
+  /**
+   * Set the height after which the transaction can no longer be mined.
+   * 0 disables expiry; otherwise it must be at least the target height.
+   * Overrides any expiry delta.
+   */
+  setExpiryHeight(height: number): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_expiry_height(this.handle, height);
+    checkResult(code, 'Set expiry height');
+  }
+
+  /**
+   * Set how many blocks after the target height the transaction expires (default 40)
+   */
+  setExpiryDelta(delta: number): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_expiry_delta(this.handle, delta);
+    checkResult(code, 'Set expiry delta');
+  }
+
   /**
    * Set whether to use mainnet parameters for consensus branch ID
    *
    * By default, the library uses mainnet parameters. Set this to false for testnet.
-   * Regtest networks (like Zebra's regtest) typically use mainnet-like branch IDs,
-   * so keep the default (true) for regtest.
+   *
+   * @deprecated Use setNetwork, which also covers regtest and takes precedence.
    */
   setUseMainnet(useMainnet: boolean): void {
     if (this.freed) throw new Error('TransactionRequest already freed');
@@ -288,6 +1130,211 @@ export class TransactionRequest {
     checkResult(code, 'Set use mainnet');
   }
 
+  /**
+   * Set the network the transaction is built for, which selects its consensus
+   * branch ID. Defaults to Network.Mainnet.
+   */
+  setNetwork(network: Network): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_network(this.handle, network);
+    checkResult(code, 'Set network');
+  }
+
+  /**
+   * Set the maximum amount (in zatoshis) by which the implicit fee may exceed
+   * the ZIP-317 fee. Defaults to 0 (the proposal must pay exactly the ZIP-317 fee).
+   */
+  setFeeTolerance(feeTolerance: bigint | string): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_fee_tolerance(this.handle, BigInt(feeTolerance));
+    checkResult(code, 'Set fee tolerance');
+  }
+
+  /**
+   * Set the highest fee (in zatoshis) a proposal may pay; 0 removes the cap.
+   * Proposals above it fail with code FEE_CAP_EXCEEDED.
+   */
+  setMaxFee(maxFee: bigint | string): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_max_fee(this.handle, BigInt(maxFee));
+    checkResult(code, 'Set max fee');
+  }
+
+  /**
+   * Set the threshold (in zatoshis) below which change is added to the fee instead
+   * of getting its own output (setDustPolicy with AddChangeToFee). Defaults to the
+   * P2PKH dust threshold; 0 always keeps change.
+   */
+  setChangeDustThreshold(threshold: bigint | string): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_change_dust_threshold(this.handle, BigInt(threshold));
+    checkResult(code, 'Set change dust threshold');
+  }
+
+  /**
+   * Set how transparent payments and change below the threshold are handled. The
+   * threshold defaults to DEFAULT_DUST_THRESHOLD; 0 makes nothing dust. Proposals that
+   * break the policy fail with DUST_PAYMENT or DUST_CHANGE.
+   */
+  setDustPolicy(policy: DustPolicy, threshold: bigint | string = DEFAULT_DUST_THRESHOLD): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_dust_policy(this.handle, policy, BigInt(threshold));
+    checkResult(code, 'Set dust policy');
+  }
+
+  /**
+   * Set how the fee is derived from the ZIP-317 fee. `fixedFee` (zatoshis) applies to
+   * FixedZatoshis and `multiplier` to Zip317WithMultiplier; a multiplier below 1 makes
+   * proposals fail with INVALID_REQUEST.
+   */
+  setFeePolicy(policy: FeePolicy, fixedFee: bigint | string = 0n, multiplier = 1): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_fee_policy(this.handle, policy, BigInt(fixedFee), multiplier);
+    checkResult(code, 'Set fee policy');
+  }
+
+  /**
+   * Require each input to have at least this many confirmations at the target height
+   * (0 = no check). Needs setTargetHeight() and a `height` on every input.
+   */
+  setMinConfirmations(minConfirmations: number): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_min_confirmations(this.handle, minConfirmations);
+    checkResult(code, 'Set min confirmations');
+  }
+
+  /**
+   * Set an operator label (e.g. a withdrawal batch ID). It is carried in the PCZT,
+   * so every later step can report it with getLabel(). Pass null to clear it.
+   */
+  setLabel(label: string | null): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_label(this.handle, label);
+    checkResult(code, 'Set label');
+  }
+
+  /**
+   * Set how inputs are picked from the UTXOs passed to proposeTransaction.
+   * Defaults to CoinSelection.All (spend every input). Experimental: the
+   * strategies may still change.
+   */
+  setCoinSelection(strategy: CoinSelection): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_experimental_transaction_request_set_coin_selection(this.handle, strategy);
+    checkResult(code, 'Set coin selection');
+  }
+
+  /**
+   * Send everything: spend every input and pay the single payment all of it minus
+   * the fee, without change. The payment's amount is ignored.
+   */
+  setSweep(sweep: boolean): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_sweep(this.handle, sweep);
+    checkResult(code, 'Set sweep');
+  }
+
+  /**
+   * Set which pool the change output goes to.
+   * Defaults to ChangePolicy.Transparent.
+   */
+  setChangePolicy(policy: ChangePolicy): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_change_policy(this.handle, policy);
+    checkResult(code, 'Set change policy');
+  }
+
+  /**
+   * Set the flags of the proposal's Orchard bundle.
+   * Pass null to keep the default (spends and outputs enabled).
+   */
+  setOrchardFlags(flags: OrchardFlags | null): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const cFlags = flags ? { spends_enabled: flags.spendsEnabled, outputs_enabled: flags.outputsEnabled } : null;
+    const code = pczt_transaction_request_set_orchard_flags(this.handle, cFlags);
+    checkResult(code, 'Set Orchard flags');
+  }
+
+  /**
+   * Set the default Orchard outgoing viewing key (32 bytes) for shielded payments.
+   * Pass null to clear it.
+   */
+  setOvk(ovk: Buffer | null): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    if (ovk && ovk.length !== 32) throw new Error('OVK must be 32 bytes');
+    const code = pczt_transaction_request_set_ovk(this.handle, ovk);
+    checkResult(code, 'Set OVK');
+  }
+
+  /**
+   * Set the Orchard outgoing viewing key (32 bytes) for a single payment,
+   * overriding the request default. Pass null to use the default again.
+   */
+  setPaymentOvk(paymentIndex: number, ovk: Buffer | null): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    if (ovk && ovk.length !== 32) throw new Error('OVK must be 32 bytes');
+    const code = pczt_transaction_request_set_payment_ovk(this.handle, paymentIndex, ovk);
+    checkResult(code, 'Set payment OVK');
+  }
+
+  /**
+   * Make a payment pay to a raw scriptPubKey instead of its address.
+   * The script must be a standard P2PKH or P2SH template.
+   */
+  setPaymentScript(paymentIndex: number, script: Buffer): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_payment_script(this.handle, paymentIndex, script, script.length);
+    checkResult(code, 'Set payment script');
+  }
+
+  /**
+   * Give a payment a raw memo of up to 512 bytes, stored as-is instead of
+   * encoding its text memo (e.g. a ZIP-302 arbitrary data memo)
+   */
+  setPaymentMemoBytes(paymentIndex: number, memo: Buffer): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_payment_memo_bytes(this.handle, paymentIndex, memo, memo.length);
+    checkResult(code, 'Set payment memo bytes');
+  }
+
+  /**
+   * Set how payment memos are encoded. Defaults to MemoEncoding.Padded.
+   */
+  setMemoEncoding(encoding: MemoEncoding): void {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const code = pczt_transaction_request_set_memo_encoding(this.handle, encoding);
+    checkResult(code, 'Set memo encoding');
+  }
+
+  /**
+   * Encode this request as a versioned JSON document (`{"version": 1, ...}`)
+   */
+  toJSON(): object {
+    if (this.freed) throw new Error('TransactionRequest already freed');
+    const jsonOut: any[] = [null];
+    const lenOut: number[] = [0];
+    const code = pczt_transaction_request_to_json(this.handle, jsonOut, lenOut);
+    checkResult(code, 'Encode transaction request');
+    return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+  }
+
+  /**
+   * Create a request from a versioned JSON document (string or parsed object).
+   * Unknown fields are ignored; documents with a newer schema version are rejected.
+   */
+  static fromJSON(json: string | object): TransactionRequest {
+    const bytes = Buffer.from(typeof json === 'string' ? json : JSON.stringify(json), 'utf8');
+    const handleOut: any[] = [null];
+    const code = pczt_transaction_request_from_json(bytes, bytes.length, handleOut);
+    checkResult(code, 'Decode transaction request');
+
+    const request = Object.create(TransactionRequest.prototype) as TransactionRequest;
+    request.handle = handleOut[0];
+    request.freed = false;
+    requestRegistry.register(request, request.handle, request);
+    return request;
+  }
+
   /**
    * Explicitly free native resources (optional - GC will handle automatically)
    */
@@ -339,6 +1386,18 @@ export class PCZT {
     return this.handle;
   }
 
+  /**
+   * Summarize this PCZT as a versioned JSON proposal document
+   */
+  toJSON(): ProposalJson {
+    if (this.freed) throw new Error('PCZT already freed');
+    const jsonOut: any[] = [null];
+    const lenOut: number[] = [0];
+    const code = pczt_proposal_to_json(this.handle, jsonOut, lenOut);
+    checkResult(code, 'Encode proposal');
+    return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+  }
+
   /** @internal */
   takeHandle(): any {
     if (this.freed) throw new Error('PCZT already freed');
@@ -350,12 +1409,33 @@ export class PCZT {
   }
 }
 
+/**
+ * Copy JSON text returned by the library and free the native buffer
+ */
+function takeJsonBytes(ptr: any, len: number): string {
+  const json = copyNative(ptr, len).toString('utf8');
+  pczt_free_bytes(ptr, len);
+  return json;
+}
+
 /**
  * Serialize transparent inputs to binary format expected by Rust
  */
 function serializeTransparentInputs(inputs: TransparentInput[]): Buffer {
   const chunks: Buffer[] = [];
 
+  // Use the v2 format only when an input carries optional fields
+  const useV2 = inputs.some(
+    (input) =>
+      input.height !== undefined ||
+      input.redeemScript !== undefined ||
+      input.derivationPath !== undefined
+  );
+  if (useV2) {
+    // Marker (0xFFFF) + version (2)
+    chunks.push(Buffer.from([0xff, 0xff, 2]));
+  }
+
   // Number of inputs (u16 LE)
   const numInputs = Buffer.alloc(2);
   numInputs.writeUInt16LE(inputs.length, 0);
@@ -393,6 +1473,40 @@ function serializeTransparentInputs(inputs: TransparentInput[]): Buffer {
 
     // Script pubkey
     chunks.push(input.scriptPubKey);
+
+    if (useV2) {
+      // Flags + optional fields
+      const hasHeight = input.height !== undefined;
+      const hasRedeemScript = input.redeemScript !== undefined;
+      const derivation = input.derivationPath;
+      chunks.push(
+        Buffer.from([
+          (hasHeight ? 0x02 : 0x00) |
+            (hasRedeemScript ? 0x04 : 0x00) |
+            (derivation !== undefined ? 0x08 : 0x00),
+        ])
+      );
+      if (hasHeight) {
+        const height = Buffer.alloc(4);
+        height.writeUInt32LE(input.height!, 0);
+        chunks.push(height);
+      }
+      if (hasRedeemScript) {
+        const redeemScriptLen = Buffer.alloc(2);
+        redeemScriptLen.writeUInt16LE(input.redeemScript!.length, 0);
+        chunks.push(redeemScriptLen, input.redeemScript!);
+      }
+      if (derivation !== undefined) {
+        const pubkey = derivation.pubkey ?? input.pubkey;
+        if (pubkey.length !== 33 || derivation.seedFingerprint.length !== 32) {
+          throw new Error('Invalid derivation: expected a 33-byte pubkey and a 32-byte seed fingerprint');
+        }
+        const path = Buffer.alloc(1 + 4 * derivation.path.length);
+        path.writeUInt8(derivation.path.length, 0);
+        derivation.path.forEach((index, i) => path.writeUInt32LE(index >>> 0, 1 + 4 * i));
+        chunks.push(pubkey, derivation.seedFingerprint, path);
+      }
+    }
   }
 
   return Buffer.concat(chunks);
@@ -440,6 +1554,30 @@ export function proposeTransactionWithChange(
   return new PCZT(handleOut[0]);
 }
 
+/**
+ * Create a PCZT whose change goes to an address picked at random from a pool
+ */
+export function proposeTransactionFromPool(
+  inputs: TransparentInput[],
+  request: TransactionRequest,
+  changeAddresses: string[]
+): PCZT {
+  const inputBytes = serializeTransparentInputs(inputs);
+  const handleOut: any[] = [null];
+
+  const code = pczt_propose_transaction_from_pool(
+    inputBytes,
+    inputBytes.length,
+    request.getHandle(),
+    changeAddresses,
+    changeAddresses.length,
+    handleOut
+  );
+  checkResult(code, 'Propose transaction from pool');
+
+  return new PCZT(handleOut[0]);
+}
+
 /**
  * Add Orchard proofs to the PCZT.
  *
@@ -455,6 +1593,94 @@ export function proveTransaction(pczt: PCZT): PCZT {
   return new PCZT(handleOut[0]);
 }
 
+/**
+ * Add proofs to many PCZTs in parallel, sharing the cached proving key.
+ *
+ * Consumes every input PCZT. Returns one entry per input, in order: the proved
+ * PCZT, or null if proving it failed (`getLastError()` describes the first failure).
+ */
+export function proveTransactions(pczts: PCZT[]): Array<PCZT | null> {
+  if (pczts.length === 0) return [];
+  const handles = pczts.map((p) => p.takeHandle());
+  const handlesOut: any[] = new Array(pczts.length).fill(null);
+  const resultsOut: number[] = new Array(pczts.length).fill(0);
+  const code = pczt_prove_batch(handles, handles.length, handlesOut, resultsOut);
+  if (code !== ResultCode.Success && code !== ResultCode.ErrorProver) {
+    checkResult(code, 'Prove transactions');
+  }
+  return handlesOut.map((handle, i) => (resultsOut[i] === ResultCode.Success ? new PCZT(handle) : null));
+}
+
+/**
+ * Progress of `proveTransactionAsync()`. The Orchard prover reports no finer
+ * steps, so `proving` is a heartbeat for spinners while the proof runs.
+ */
+export interface ProofProgress {
+  stage: 'proving' | 'done';
+  elapsedMs: number;
+}
+
+/**
+ * Add Orchard proofs on the libuv threadpool instead of the event loop.
+ *
+ * `onProgress` is called with `proving` right away and then every
+ * `progressIntervalMs` until the proof is done, then once with `done`.
+ *
+ * **IMPORTANT:** This function ALWAYS consumes the input PCZT, even on error.
+ */
+export function proveTransactionAsync(
+  pczt: PCZT,
+  onProgress?: (progress: ProofProgress) => void,
+  progressIntervalMs = 250
+): Promise<PCZT> {
+  const handle = pczt.takeHandle();
+  const handleOut: any[] = [null];
+  const messageBuf = Buffer.alloc(512);
+  const codeBuf = Buffer.alloc(64);
+  const started = Date.now();
+
+  const report = (stage: ProofProgress['stage']) => onProgress?.({ stage, elapsedMs: Date.now() - started });
+  report('proving');
+  const timer = onProgress ? setInterval(() => report('proving'), progressIntervalMs) : undefined;
+
+  return new Promise((resolve, reject) => {
+    pczt_prove_transaction_on_worker.async(
+      handle,
+      handleOut,
+      messageBuf,
+      messageBuf.length,
+      codeBuf,
+      codeBuf.length,
+      (err: any, code: number) => {
+        clearInterval(timer);
+        report('done');
+        if (err) {
+          reject(err);
+        } else if (code !== ResultCode.Success) {
+          reject(workerError('Prove transaction', code, messageBuf, codeBuf));
+        } else {
+          resolve(new PCZT(handleOut[0]));
+        }
+      }
+    );
+  });
+}
+
+/**
+ * Build the error of a failed `_on_worker` call. The worker's last error is out of
+ * reach from the event loop, so it comes back in the message and code buffers.
+ */
+function workerError(operation: string, code: number, messageBuf: Buffer, codeBuf: Buffer): T2zError {
+  const text = (buf: Buffer) => buf.slice(0, Math.max(buf.indexOf(0), 0)).toString('utf8');
+  const message = text(messageBuf);
+  return new T2zError(
+    `${operation} failed: ${message || `error code ${code}`}`,
+    code as ResultCode,
+    undefined,
+    text(codeBuf) || undefined
+  );
+}
+
 /**
  * Verify the PCZT before signing
  */
@@ -463,16 +1689,7 @@ export function verifyBeforeSigning(
   request: TransactionRequest,
   expectedChange: TransparentOutput[]
 ): void {
-  // Build C transparent output array
-  const cOutputs: any[] = [];
-  for (const o of expectedChange) {
-    cOutputs.push({
-      script_pub_key: o.scriptPubKey,
-      script_pub_key_len: o.scriptPubKey.length,
-      value: BigInt(o.value),
-    });
-  }
-
+  const cOutputs = toCTransparentOutputs(expectedChange);
   const code = pczt_verify_before_signing(
     pczt.getHandle(),
     request.getHandle(),
@@ -482,6 +1699,150 @@ export function verifyBeforeSigning(
   checkResult(code, 'Verify before signing');
 }
 
+/**
+ * Run every pre-signing check and report all failures instead of the first
+ */
+export function verifyReport(
+  pczt: PCZT,
+  request: TransactionRequest,
+  expectedChange: TransparentOutput[]
+): VerificationReportJson {
+  const cOutputs = toCTransparentOutputs(expectedChange);
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+  const code = pczt_verify_report(
+    pczt.getHandle(),
+    request.getHandle(),
+    cOutputs.length > 0 ? cOutputs : null,
+    cOutputs.length,
+    jsonOut,
+    lenOut
+  );
+  checkResult(code, 'Verify report');
+  return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+}
+
+/** Build the C transparent output array for expected change */
+function toCTransparentOutputs(outputs: TransparentOutput[]): any[] {
+  return outputs.map((o) => ({
+    script_pub_key: o.scriptPubKey,
+    script_pub_key_len: o.scriptPubKey.length,
+    value: BigInt(o.value),
+  }));
+}
+
+/**
+ * Export a review document summarizing a proposal for four-eyes approval.
+ * Display amounts are rendered in `unit` (default: ZEC and zatoshis).
+ */
+export function exportReview(
+  pczt: PCZT,
+  request: TransactionRequest,
+  unit: AmountUnit = AmountUnit.Both
+): ReviewDocument {
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+
+  const code = pczt_export_review(pczt.getHandle(), request.getHandle(), unit, jsonOut, lenOut);
+  checkResult(code, 'Export review');
+
+  // Copy bytes and free native memory
+  const len = lenOut[0];
+  const ptr = jsonOut[0];
+  const json = copyNative(ptr, len).toString('utf8');
+  pczt_free_bytes(ptr, len);
+
+  return JSON.parse(json);
+}
+
+/**
+ * Verify that an approved review document still matches the proposal about to be signed
+ */
+export function verifyReview(pczt: PCZT, request: TransactionRequest, document: ReviewDocument): void {
+  const json = Buffer.from(JSON.stringify(document), 'utf8');
+  const code = pczt_verify_review(pczt.getHandle(), request.getHandle(), json, json.length);
+  checkResult(code, 'Verify review');
+}
+
+/**
+ * Record a proposal's inputs, outputs, fee and fingerprint in a manifest signed
+ * with the operator's 32-byte Ed25519 secret key
+ */
+export function signManifest(pczt: PCZT, signingKey: Buffer): SignedManifest {
+  if (signingKey.length !== 32) {
+    throw new Error(`Invalid signing key length: expected 32, got ${signingKey.length}`);
+  }
+
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+
+  const code = pczt_sign_manifest(pczt.getHandle(), signingKey, jsonOut, lenOut);
+  checkResult(code, 'Sign manifest');
+
+  // Copy bytes and free native memory
+  const len = lenOut[0];
+  const ptr = jsonOut[0];
+  const json = copyNative(ptr, len).toString('utf8');
+  pczt_free_bytes(ptr, len);
+
+  return JSON.parse(json);
+}
+
+/**
+ * Verify that a PCZT is the transaction the operator with Ed25519 public key
+ * `trustedKey` authorized in a signed manifest
+ */
+export function verifyManifest(pczt: PCZT, manifest: SignedManifest, trustedKey: Buffer): void {
+  if (trustedKey.length !== 32) {
+    throw new Error(`Invalid public key length: expected 32, got ${trustedKey.length}`);
+  }
+
+  const json = Buffer.from(JSON.stringify(manifest), 'utf8');
+  const code = pczt_verify_manifest(pczt.getHandle(), json, json.length, trustedKey);
+  checkResult(code, 'Verify manifest');
+}
+
+/**
+ * Export an amount-blinded review for a second approver: aggregate totals and
+ * address prefixes, without individual shielded amounts or memos.
+ */
+export function exportBlindedReview(
+  pczt: PCZT,
+  request: TransactionRequest,
+  unit: AmountUnit = AmountUnit.Both
+): BlindedReview {
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+
+  const code = pczt_export_blinded_review(pczt.getHandle(), request.getHandle(), unit, jsonOut, lenOut);
+  checkResult(code, 'Export blinded review');
+
+  // Copy bytes and free native memory
+  const len = lenOut[0];
+  const ptr = jsonOut[0];
+  const json = copyNative(ptr, len).toString('utf8');
+  pczt_free_bytes(ptr, len);
+
+  return JSON.parse(json);
+}
+
+/**
+ * Verify that an approved blinded review still matches the proposal about to be signed
+ */
+export function verifyBlindedReview(pczt: PCZT, request: TransactionRequest, review: BlindedReview): void {
+  const json = Buffer.from(JSON.stringify(review), 'utf8');
+  const code = pczt_verify_blinded_review(pczt.getHandle(), request.getHandle(), json, json.length);
+  checkResult(code, 'Verify blinded review');
+}
+
+/**
+ * Verify that a final transaction matches the PCZT it was extracted from
+ */
+export function verifyExtraction(pczt: PCZT, txBytes: Buffer): void {
+  const code = pczt_verify_extraction(pczt.getHandle(), txBytes, txBytes.length);
+  checkResult(code, 'Verify extraction');
+}
+
 /**
  * Get signature hash for a transparent input
  */
@@ -492,6 +1853,269 @@ export function getSighash(pczt: PCZT, index: number): Buffer {
   return sighash;
 }
 
+/**
+ * Get the sequence number of a transparent input (0xFFFFFFFF if final/unset)
+ */
+export function getInputSequence(pczt: PCZT, index: number): number {
+  const sequenceOut: number[] = [0];
+  const code = pczt_get_input_sequence(pczt.getHandle(), index, sequenceOut);
+  checkResult(code, 'Get input sequence');
+  return sequenceOut[0];
+}
+
+/**
+ * Get the compressed public key (33 bytes) that controls a transparent input
+ */
+export function getInputPubkey(pczt: PCZT, index: number): Buffer {
+  const pubkey = Buffer.alloc(33);
+  const code = pczt_get_input_pubkey(pczt.getHandle(), index, pubkey);
+  checkResult(code, 'Get input pubkey');
+  return pubkey;
+}
+
+/**
+ * Export every input's sighash and the shared transaction context as one binary
+ * document, so a hardware wallet can sign all inputs in a single session
+ */
+export function exportSighashBatch(pczt: PCZT): Buffer {
+  const bytesOut: any[] = [null];
+  const lenOut: number[] = [0];
+
+  const code = pczt_export_sighash_batch(pczt.getHandle(), bytesOut, lenOut);
+  checkResult(code, 'Export sighash batch');
+
+  // Copy bytes and free native memory
+  const len = lenOut[0];
+  const ptr = bytesOut[0];
+  const result = copyNative(ptr, len);
+  pczt_free_bytes(ptr, len);
+
+  return result;
+}
+
+/**
+ * Check that a sighash batch document is well-formed and internally consistent.
+ * Returns the number of inputs it covers.
+ */
+export function validateSighashBatch(batch: Buffer): number {
+  const numInputsOut: number[] = [0];
+  const code = pczt_validate_sighash_batch(batch, batch.length, numInputsOut);
+  checkResult(code, 'Validate sighash batch');
+  return Number(numInputsOut[0]);
+}
+
+/**
+ * Get the message (32 bytes) the proposer signs with an input key to prove that
+ * transparent output `outputIndex` is change returning to their keys
+ */
+export function getChangeOwnershipMessage(pczt: PCZT, outputIndex: number): Buffer {
+  const message = Buffer.alloc(32);
+  const code = pczt_change_ownership_message(pczt.getHandle(), outputIndex, message);
+  checkResult(code, 'Get change ownership message');
+  return message;
+}
+
+/**
+ * Check that a PCZT received from another party is internally consistent: every
+ * input's hash160 preimage must hash to its script's pubkey hash.
+ * Also run by verifyBeforeSigning in strict mode.
+ */
+export function validatePczt(pczt: PCZT): void {
+  const code = pczt_validate(pczt.getHandle());
+  checkResult(code, 'Validate PCZT');
+}
+
+/**
+ * Verify that transparent output `outputIndex` was endorsed by the key controlling
+ * input `inputIndex`. Throws if the signature does not match.
+ */
+export function verifyChangeOwnership(
+  pczt: PCZT,
+  outputIndex: number,
+  inputIndex: number,
+  signature: Buffer
+): void {
+  if (signature.length !== 64) throw new Error('Signature must be 64 bytes');
+  const code = pczt_verify_change_ownership(pczt.getHandle(), outputIndex, inputIndex, signature);
+  checkResult(code, 'Verify change ownership');
+}
+
+// Helper: Run a two-call (count, then fill) Orchard output query
+function readOrchardOutputs(
+  query: (outputs: any[] | null, capacity: number, lenOut: number[]) => number,
+  operation: string
+): OrchardOutputInfo[] {
+  const lenOut: number[] = [0];
+  checkResult(query(null, 0, lenOut), operation);
+  const count = lenOut[0];
+  if (count === 0) return [];
+
+  const outputs: any[] = Array.from({ length: count }, () => ({}));
+  checkResult(query(outputs, count, lenOut), operation);
+
+  return outputs.map((o) => ({
+    actionIndex: Number(o.action_index),
+    cmx: Buffer.from(o.cmx),
+    ephemeralKey: Buffer.from(o.ephemeral_key),
+    value: o.has_value ? BigInt(o.value).toString() : undefined,
+  }));
+}
+
+/**
+ * Get the note commitment and ephemeral key of each Orchard output in a PCZT,
+ * for matching on-chain actions to payments later
+ */
+export function getOrchardOutputs(pczt: PCZT): OrchardOutputInfo[] {
+  return readOrchardOutputs(
+    (outputs, capacity, lenOut) => pczt_get_orchard_outputs(pczt.getHandle(), outputs, capacity, lenOut),
+    'Get Orchard outputs'
+  );
+}
+
+/**
+ * Get the note commitment and ephemeral key of each Orchard action in a final transaction
+ */
+export function decodeOrchardOutputs(txBytes: Buffer): OrchardOutputInfo[] {
+  return readOrchardOutputs(
+    (outputs, capacity, lenOut) =>
+      pczt_tx_get_orchard_outputs(txBytes, txBytes.length, outputs, capacity, lenOut),
+    'Decode Orchard outputs'
+  );
+}
+
+/**
+ * Summarize what a PCZT will do (inputs, outputs, change, fee, estimated size and
+ * expiry), for a confirmation screen before signing
+ */
+export function summarizePczt(pczt: PCZT): TransactionSummary {
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+  const code = pczt_summarize(pczt.getHandle(), jsonOut, lenOut);
+  checkResult(code, 'Summarize PCZT');
+  return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+}
+
+/**
+ * Decode a serialized final transaction into a versioned JSON document
+ */
+export function decodeTransaction(txBytes: Buffer): DecodedTransactionJson {
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+  const code = pczt_decode_transaction_json(txBytes, txBytes.length, jsonOut, lenOut);
+  checkResult(code, 'Decode transaction');
+  return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+}
+
+/**
+ * How the native library was built; record it in audit logs next to each transaction
+ */
+export interface BuildInfo {
+  version: string;
+  git_commit: string;
+  git_dirty: boolean;
+  features: string[];
+  /** `name=version`, or `name=version#commit` for git dependencies */
+  dependencies: string[];
+  target: string;
+  profile: string;
+}
+
+/**
+ * An error kept in the native library's error history
+ */
+export interface ErrorRecord {
+  /** Milliseconds since the Unix epoch */
+  timestamp_ms: number;
+  /** The native function that raised the error */
+  function: string;
+  /** Domain of the error code (e.g. "NETWORK") */
+  domain: string;
+  /** Stable error code (e.g. "INSUFFICIENT_FUNDS") */
+  code: string;
+  message: string;
+}
+
+/**
+ * Get the last errors the native library raised on this thread, oldest first, to
+ * find the one that mattered in a multi-step pipeline
+ */
+export function getErrorHistory(): ErrorRecord[] {
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+  const code = pczt_get_error_history(jsonOut, lenOut);
+  checkResult(code, 'Get error history');
+  return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+}
+
+/**
+ * Describe how the loaded native library was built
+ */
+export function getBuildInfo(): BuildInfo {
+  const jsonOut: any[] = [null];
+  const lenOut: number[] = [0];
+  const code = pczt_build_info(jsonOut, lenOut);
+  checkResult(code, 'Build info');
+  return JSON.parse(takeJsonBytes(jsonOut[0], lenOut[0]));
+}
+
+/**
+ * Get the fingerprint of the transaction a PCZT represents.
+ *
+ * The fingerprint covers transaction effects only (not signatures or proofs), so
+ * two parties can confirm they hold the same transaction before combining.
+ */
+export function getFingerprint(pczt: PCZT): Buffer {
+  const fingerprint = Buffer.alloc(32);
+  const code = pczt_get_fingerprint(pczt.getHandle(), fingerprint);
+  checkResult(code, 'Get fingerprint');
+  return fingerprint;
+}
+
+/**
+ * Stamp a fresh 16-byte reservation token into a PCZT.
+ *
+ * Lock the PCZT's inputs under the returned token; after a crash, read it back
+ * with `getReservationToken()` to reconcile locks against outstanding PCZTs.
+ *
+ * **IMPORTANT:** This function ALWAYS consumes the input PCZT, even on error.
+ */
+export function stampReservation(pczt: PCZT): { pczt: PCZT; token: Buffer } {
+  const token = Buffer.alloc(16);
+  const handleOut: any[] = [null];
+  const code = pczt_stamp_reservation(pczt.takeHandle(), token, handleOut);
+  checkResult(code, 'Stamp reservation');
+  return { pczt: new PCZT(handleOut[0]), token };
+}
+
+/**
+ * Get the reservation token stamped into a PCZT, or null if it has none.
+ */
+export function getReservationToken(pczt: PCZT): Buffer | null {
+  const token = Buffer.alloc(16);
+  const foundOut: boolean[] = [false];
+  const code = pczt_get_reservation_token(pczt.getHandle(), token, foundOut);
+  checkResult(code, 'Get reservation token');
+  return foundOut[0] ? token : null;
+}
+
+/**
+ * Get the label of the request a PCZT was proposed with, or null if it had none
+ */
+export function getLabel(pczt: PCZT): string | null {
+  const labelOut: any[] = [null];
+  const lenOut: number[] = [0];
+
+  const code = pczt_get_label(pczt.getHandle(), labelOut, lenOut);
+  checkResult(code, 'Get label');
+
+  const len = lenOut[0];
+  const ptr = labelOut[0];
+  if (ptr === null) return null;
+  const label = len > 0 ? copyNative(ptr, len).toString('utf8') : '';
+  pczt_free_bytes(ptr, len);
+  return label;
+}
+
 /**
  * Append an external signature to the PCZT.
  *
@@ -512,20 +2136,57 @@ export function appendSignature(pczt: PCZT, index: number, signature: Buffer): P
 }
 
 /**
- * Combine multiple PCZTs into one.
+ * Sign a transparent input with a 32-byte secp256k1 secret key.
+ *
+ * **IMPORTANT:** This function ALWAYS consumes the input PCZT, even on error.
+ * On error, the input PCZT is invalidated and cannot be reused.
+ * If you need to retry on failure, call `serializePczt()` before this function
+ * to create a backup that can be restored with `parsePczt()`.
+ */
+export function signTransparent(pczt: PCZT, index: number, secretKey: Buffer): PCZT {
+  if (secretKey.length !== 32) {
+    throw new Error(`Invalid secret key length: expected 32, got ${secretKey.length}`);
+  }
+
+  const handleOut: any[] = [null];
+  const code = pczt_sign_transparent(pczt.takeHandle(), index, secretKey, handleOut);
+  checkResult(code, 'Sign transparent input');
+  return new PCZT(handleOut[0]);
+}
+
+/**
+ * Combine multiple PCZTs into one, e.g. a proved copy and a signed copy of the
+ * same proposal.
+ *
+ * Entries may be PCZT objects or serialized PCZTs (as received from another
+ * party); buffers are parsed first.
  *
  * **IMPORTANT:** This function ALWAYS consumes ALL input PCZTs, even on error.
  * On error, all input PCZTs are invalidated and cannot be reused.
  * If you need to retry on failure, call `serializePczt()` on each PCZT before
  * this function to create backups that can be restored with `parsePczt()`.
  */
-export function combine(pczts: PCZT[]): PCZT {
+export function combine(pczts: Array<PCZT | Buffer>): PCZT {
   if (pczts.length === 0) {
     throw new Error('At least one PCZT is required');
   }
 
+  // Parse buffers before taking any handle, consuming the PCZTs if one is invalid
+  const parsed: PCZT[] = [];
+  try {
+    for (const p of pczts) {
+      parsed.push(Buffer.isBuffer(p) ? parsePczt(p) : p);
+    }
+  } catch (e) {
+    parsed.forEach((p) => p.free());
+    pczts.forEach((p) => {
+      if (!Buffer.isBuffer(p)) p.free();
+    });
+    throw e;
+  }
+
   // Extract handles and transfer ownership
-  const handles = pczts.map((p) => p.takeHandle());
+  const handles = parsed.map((p) => p.takeHandle());
   const handleOut: any[] = [null];
 
   const code = pczt_combine(handles, handles.length, handleOut);
@@ -543,50 +2204,229 @@ export function combine(pczts: PCZT[]): PCZT {
  * to create a backup that can be restored with `parsePczt()`.
  */
 export function finalizeAndExtract(pczt: PCZT): Buffer {
-  const bytesOut: any[] = [null];
-  const lenOut: number[] = [0];
+  const bufferOut: any[] = [null];
 
-  const code = pczt_finalize_and_extract(pczt.takeHandle(), bytesOut, lenOut);
+  const code = pczt_finalize_and_extract_buffer(pczt.takeHandle(), bufferOut);
   checkResult(code, 'Finalize and extract');
 
-  // Copy bytes and free native memory
-  const len = lenOut[0];
-  const ptr = bytesOut[0];
-  const result = Buffer.from(koffi.decode(ptr, 'uint8_t', len));
-  pczt_free_bytes(ptr, len);
+  return takeBuffer(bufferOut[0]);
+}
 
-  return result;
+/**
+ * Finalize and extract on the libuv threadpool instead of the event loop.
+ *
+ * The first extraction with Orchard actions builds the Orchard verifying key, which
+ * takes a while; the native layer keeps it for later calls.
+ *
+ * **IMPORTANT:** This function ALWAYS consumes the input PCZT, even on error.
+ */
+export function finalizeAndExtractAsync(pczt: PCZT): Promise<Buffer> {
+  const handle = pczt.takeHandle();
+  const bytesOut: any[] = [null];
+  const lenOut: number[] = [0];
+  const messageBuf = Buffer.alloc(512);
+  const codeBuf = Buffer.alloc(64);
+
+  return new Promise((resolve, reject) => {
+    pczt_finalize_and_extract_on_worker.async(
+      handle,
+      bytesOut,
+      lenOut,
+      messageBuf,
+      messageBuf.length,
+      codeBuf,
+      codeBuf.length,
+      (err: any, code: number) => {
+        if (err) {
+          reject(err);
+        } else if (code !== ResultCode.Success) {
+          reject(workerError('Finalize and extract', code, messageBuf, codeBuf));
+        } else {
+          const len = lenOut[0];
+          const ptr = bytesOut[0];
+          const result = copyNative(ptr, len);
+          pczt_free_bytes(ptr, len);
+          resolve(result);
+        }
+      }
+    );
+  });
 }
 
 /**
  * Serialize PCZT to bytes
  */
 export function serializePczt(pczt: PCZT): Buffer {
-  const bytesOut: any[] = [null];
-  const lenOut: number[] = [0];
+  const bufferOut: any[] = [null];
 
-  const code = pczt_serialize(pczt.getHandle(), bytesOut, lenOut);
+  const code = pczt_serialize_buffer(pczt.getHandle(), bufferOut);
   checkResult(code, 'Serialize PCZT');
 
-  // Copy bytes and free native memory
-  const len = lenOut[0];
-  const ptr = bytesOut[0];
-  const result = Buffer.from(koffi.decode(ptr, 'uint8_t', len));
-  pczt_free_bytes(ptr, len);
+  return takeBuffer(bufferOut[0]);
+}
 
-  return result;
+/**
+ * Options controlling how strictly PCZT bytes are parsed
+ */
+export interface ParseOptions {
+  /** Reject encodings that don't re-serialize to the exact same bytes */
+  requireCanonical?: boolean;
+  /** Also reject proprietary fields outside the recognized namespaces (implies requireCanonical) */
+  rejectUnknownFields?: boolean;
 }
 
 /**
  * Parse PCZT from bytes
  */
-export function parsePczt(bytes: Buffer): PCZT {
+export function parsePczt(bytes: Buffer, options?: ParseOptions): PCZT {
   const handleOut: any[] = [null];
-  const code = pczt_parse(bytes, bytes.length, handleOut);
+  const code = options
+    ? pczt_parse_with_options(
+        bytes,
+        bytes.length,
+        {
+          require_canonical: options.requireCanonical ?? false,
+          reject_unknown_fields: options.rejectUnknownFields ?? false,
+        },
+        handleOut
+      )
+    : pczt_parse(bytes, bytes.length, handleOut);
   checkResult(code, 'Parse PCZT');
   return new PCZT(handleOut[0]);
 }
 
+/**
+ * Save a PCZT to a file atomically (by convention named `*.pczt`).
+ */
+export function savePczt(pczt: PCZT, path: string): void {
+  const code = pczt_save(pczt.getHandle(), path);
+  checkResult(code, 'Save PCZT');
+}
+
+/**
+ * Load a PCZT from a file, rejecting files without the PCZT magic bytes.
+ */
+export function loadPczt(path: string): PCZT {
+  const handleOut: any[] = [null];
+  const code = pczt_load(path, handleOut);
+  checkResult(code, 'Load PCZT');
+  return new PCZT(handleOut[0]);
+}
+
+/**
+ * Recognize a proprietary namespace for parsing with rejectUnknownFields.
+ * Keys equal to the namespace or starting with `namespace.` are accepted.
+ */
+export function registerProprietaryNamespace(namespace: string): void {
+  const code = pczt_register_proprietary_namespace(namespace);
+  checkResult(code, 'Register proprietary namespace');
+}
+
+/**
+ * Check whether PCZT bytes are in canonical form (re-serializing yields identical bytes)
+ */
+export function isCanonical(bytes: Buffer): boolean {
+  const resultOut: boolean[] = [false];
+  const code = pczt_is_canonical(bytes, bytes.length, resultOut);
+  checkResult(code, 'Check canonical PCZT');
+  return resultOut[0];
+}
+
+/**
+ * Get the flags of a PCZT's Orchard bundle, or null if it has no Orchard actions.
+ */
+export function getOrchardFlags(pczt: PCZT): OrchardFlags | null {
+  const hasBundleOut: boolean[] = [false];
+  const flagsOut: any = {};
+  const code = pczt_get_orchard_flags(pczt.getHandle(), hasBundleOut, flagsOut);
+  checkResult(code, 'Get Orchard flags');
+  if (!hasBundleOut[0]) return null;
+  return { spendsEnabled: flagsOut.spends_enabled, outputsEnabled: flagsOut.outputs_enabled };
+}
+
+/**
+ * Get the number of transparent inputs of a PCZT.
+ */
+export function getTransparentInputCount(pczt: PCZT): number {
+  const countOut: number[] = [0];
+  const code = pczt_get_transparent_input_count(pczt.getHandle(), countOut);
+  checkResult(code, 'Get transparent input count');
+  return Number(countOut[0]);
+}
+
+/**
+ * Get the prevout, value, script and signing state of a PCZT's transparent input.
+ */
+export function getTransparentInput(pczt: PCZT, inputIndex: number): TransparentInputInfo {
+  const inputOut: any = {};
+  const code = pczt_get_transparent_input(pczt.getHandle(), inputIndex, inputOut);
+  checkResult(code, 'Get transparent input');
+  return {
+    txid: Buffer.from(inputOut.prevout_txid),
+    vout: inputOut.prevout_index,
+    sequence: inputOut.sequence,
+    value: inputOut.value.toString(),
+    scriptPubKey: copyNative(inputOut.script_pub_key, Number(inputOut.script_pub_key_len)),
+    numSignatures: Number(inputOut.num_signatures),
+    isFinalized: inputOut.is_finalized,
+  };
+}
+
+/**
+ * Get the number of transparent outputs of a PCZT.
+ */
+export function getTransparentOutputCount(pczt: PCZT): number {
+  const countOut: number[] = [0];
+  const code = pczt_get_transparent_output_count(pczt.getHandle(), countOut);
+  checkResult(code, 'Get transparent output count');
+  return Number(countOut[0]);
+}
+
+/**
+ * Get the script and value of a PCZT's transparent output.
+ */
+export function getTransparentOutput(pczt: PCZT, outputIndex: number): TransparentOutput {
+  const outputOut: any = {};
+  const code = pczt_get_transparent_output(pczt.getHandle(), outputIndex, outputOut);
+  checkResult(code, 'Get transparent output');
+  return {
+    scriptPubKey: copyNative(outputOut.script_pub_key, Number(outputOut.script_pub_key_len)),
+    value: outputOut.value.toString(),
+  };
+}
+
+/**
+ * Get the number of Orchard actions of a PCZT, padding included.
+ */
+export function getOrchardActionCount(pczt: PCZT): number {
+  const countOut: number[] = [0];
+  const code = pczt_get_orchard_action_count(pczt.getHandle(), countOut);
+  checkResult(code, 'Get Orchard action count');
+  return Number(countOut[0]);
+}
+
+/**
+ * Check whether a PCZT's Orchard bundle has its proof (false if it has no Orchard actions).
+ */
+export function hasOrchardProof(pczt: PCZT): boolean {
+  const hasProofOut: boolean[] = [false];
+  const code = pczt_has_orchard_proof(pczt.getHandle(), hasProofOut);
+  checkResult(code, 'Check Orchard proof');
+  return hasProofOut[0];
+}
+
+/**
+ * Check whether a PCZT is transparent-only (t→t).
+ *
+ * Transparent-only PCZTs need no proofs, so `proveTransaction()` returns them unchanged.
+ */
+export function isTransparentOnly(pczt: PCZT): boolean {
+  const resultOut: boolean[] = [false];
+  const code = pczt_is_transparent_only(pczt.getHandle(), resultOut);
+  checkResult(code, 'Check transparent-only');
+  return resultOut[0];
+}
+
 /**
  * Calculate the ZIP-317 transaction fee.
  *
@@ -622,3 +2462,174 @@ export function calculateFee(
 ): bigint {
   return BigInt(pczt_calculate_fee(numTransparentInputs, numTransparentOutputs, numOrchardOutputs));
 }
+
+/**
+ * Calculate the ZIP-317 conventional fee from the shape of a PCZT, for verifiers
+ * that receive the PCZT without the original request
+ */
+export function conventionalFee(pczt: PCZT): bigint {
+  const feeOut: any[] = [0];
+  const code = pczt_conventional_fee(pczt.getHandle(), feeOut);
+  checkResult(code, 'Conventional fee');
+  return BigInt(feeOut[0]);
+}
+
+/**
+ * Largest transaction zcashd accepts into its mempool or a block, in bytes
+ */
+export const MAX_TX_SIZE = 2_000_000;
+
+/**
+ * Estimate the serialized size in bytes of a transaction with the given shape
+ * (an upper bound; counts as in calculateFee)
+ */
+export function estimateTxSize(
+  numTransparentInputs: number,
+  numTransparentOutputs: number,
+  numOrchardOutputs: number
+): number {
+  return Number(pczt_estimate_tx_size(numTransparentInputs, numTransparentOutputs, numOrchardOutputs));
+}
+
+/**
+ * Estimate the serialized size in bytes of the transaction a PCZT extracts to,
+ * e.g. to check it against MAX_TX_SIZE or compute the fee per byte
+ */
+export function estimatedFinalSize(pczt: PCZT): number {
+  const sizeOut: any[] = [0];
+  const code = pczt_estimated_final_size(pczt.getHandle(), sizeOut);
+  checkResult(code, 'Estimated final size');
+  return Number(sizeOut[0]);
+}
+
+/**
+ * Decode a memo field (plain text or compressed) into text
+ */
+export function decodeMemo(memo: Buffer): string {
+  const textOut: any[] = [null];
+  const lenOut: number[] = [0];
+
+  const code = pczt_decode_memo(memo, memo.length, textOut, lenOut);
+  checkResult(code, 'Decode memo');
+
+  // Copy bytes and free native memory
+  const len = lenOut[0];
+  const ptr = textOut[0];
+  const result = len > 0 ? copyNative(ptr, len).toString('utf8') : '';
+  pczt_free_bytes(ptr, len);
+
+  return result;
+}
+
+/**
+ * Render an amount for display, identically on every platform regardless of locale
+ *
+ * @example
+ * formatAmount(123450000n, AmountUnit.Zec); // "1.23450000 ZEC"
+ */
+export function formatAmount(zatoshis: bigint | string, unit: AmountUnit = AmountUnit.Both): string {
+  const buffer = Buffer.alloc(64);
+  const code = pczt_format_amount(BigInt(zatoshis), unit, buffer, buffer.length);
+  checkResult(code, 'Format amount');
+  return buffer.toString('utf8', 0, buffer.indexOf(0));
+}
+
+/**
+ * Render a P2PKH or P2SH scriptPubKey as a transparent address, exactly as the
+ * library renders it in reviews and proposals
+ */
+export function scriptToAddress(script: Buffer, useMainnet: boolean): string {
+  const buffer = Buffer.alloc(128);
+  const code = pczt_script_to_address(script, script.length, useMainnet, buffer, buffer.length);
+  checkResult(code, 'Script to address');
+  return buffer.toString('utf8', 0, buffer.indexOf(0));
+}
+
+/**
+ * Get the scriptPubKey a transparent address pays to
+ */
+export function addressToScript(address: string): Buffer {
+  const buffer = Buffer.alloc(64);
+  const lenOut: number[] = [0];
+  const code = pczt_address_to_script(address, buffer, buffer.length, lenOut);
+  checkResult(code, 'Address to script');
+  return buffer.subarray(0, lenOut[0]);
+}
+
+/**
+ * Get the standardness dust threshold (in zatoshis) for a P2PKH output
+ */
+export function getP2pkhDustThreshold(): bigint {
+  return BigInt(pczt_p2pkh_dust_threshold());
+}
+
+/**
+ * Store proving parameters as files in `dir` instead of the default Zcash params
+ * directory (`$ZCASH_PARAMS` or `~/.zcash-params`)
+ */
+export function setParamsDir(dir: string): void {
+  const code = pczt_set_params_dir(dir);
+  checkResult(code, 'Set params dir');
+}
+
+/**
+ * Keep proving parameters in memory only, for hosts without writable storage
+ */
+export function useMemoryParamsStore(): void {
+  const code = pczt_use_memory_params_store();
+  checkResult(code, 'Use memory params store');
+}
+
+/**
+ * Build the Orchard proving key now, so the first `proveTransaction()` doesn't
+ * stall for several seconds. Calling it again is a no-op.
+ */
+export function initProvingKeys(): void {
+  const code = pczt_init_proving_keys();
+  checkResult(code, 'Init proving keys');
+}
+
+/**
+ * Drop the Orchard verifying key that extraction caches between calls, e.g. after
+ * a batch of `finalizeAndExtract()` calls. The next shielded extraction rebuilds it.
+ */
+export function unloadVerifyingKeys(): void {
+  const code = pczt_unload_verifying_keys();
+  checkResult(code, 'Unload verifying keys');
+}
+
+/**
+ * Set how many threads proving may use (0 = no limit, the default). With more
+ * than one, a transaction's Sapling and Orchard proofs are created concurrently.
+ */
+export function setProverThreads(threads: number): void {
+  const code = pczt_set_prover_threads(threads);
+  checkResult(code, 'Set prover threads');
+}
+
+/**
+ * Cache up to `capacity` parsed recipient addresses across proposals, least recently
+ * used first out (0 turns the cache off). Useful when paying the same addresses often.
+ */
+export function setAddressCacheCapacity(capacity: number): void {
+  const code = pczt_set_address_cache_capacity(capacity);
+  checkResult(code, 'Set address cache capacity');
+}
+
+/**
+ * Drop every cached address, keeping the cache's capacity
+ */
+export function clearAddressCache(): void {
+  const code = pczt_clear_address_cache();
+  checkResult(code, 'Clear address cache');
+}
+
+/**
+ * Set the library-wide strictness. In strict mode, memos on transparent payments,
+ * payment labels/messages and unclassifiable addresses are errors instead of
+ * being silently dropped.
+ */
+export function setStrictness(strictness: Strictness): void {
+  const code = pczt_set_strictness(strictness);
+  checkResult(code, 'Set strictness');
+}
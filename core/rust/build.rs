@@ -19,7 +19,84 @@ fn main() {
         .expect("Unable to generate bindings")
         .write_to_file(output_file);
 
+    emit_build_info(&crate_dir);
+
     // Rebuild if the source files change
     println!("cargo:rerun-if-changed=src/");
     println!("cargo:rerun-if-changed=cbindgen.toml");
 }
+
+/// Dependencies whose exact versions are reported by `build_info()`
+const REPORTED_DEPENDENCIES: &[&str] = &[
+    "pczt",
+    "zcash_primitives",
+    "zcash_transparent",
+    "zcash_address",
+    "zcash_protocol",
+    "orchard",
+    "secp256k1",
+];
+
+/// Passes the git commit, enabled features and dependency versions to the crate
+/// as `T2Z_BUILD_*` environment variables (read by `build_info()`)
+fn emit_build_info(crate_dir: &str) {
+    let git_commit = std::process::Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(crate_dir)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    let git_dirty = std::process::Command::new("git")
+        .args(["status", "--porcelain", "--untracked-files=no"])
+        .current_dir(crate_dir)
+        .output()
+        .map(|output| output.status.success() && !output.stdout.is_empty())
+        .unwrap_or(false);
+
+    let mut features: Vec<String> = env::vars()
+        .filter_map(|(key, _)| key.strip_prefix("CARGO_FEATURE_").map(|f| f.to_lowercase().replace('_', "-")))
+        .collect();
+    features.sort();
+
+    // Cargo.lock is next to the manifest, or at the workspace root when built as a member
+    let lockfile = [PathBuf::from(crate_dir).join("Cargo.lock"), PathBuf::from(crate_dir).join("../../Cargo.lock")]
+        .into_iter()
+        .find_map(|path| std::fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let dependencies = locked_versions(&lockfile).join(",");
+
+    println!("cargo:rustc-env=T2Z_BUILD_GIT_COMMIT={}", git_commit);
+    println!("cargo:rustc-env=T2Z_BUILD_GIT_DIRTY={}", git_dirty);
+    println!("cargo:rustc-env=T2Z_BUILD_FEATURES={}", features.join(","));
+    println!("cargo:rustc-env=T2Z_BUILD_DEPENDENCIES={}", dependencies);
+    println!("cargo:rustc-env=T2Z_BUILD_TARGET={}", env::var("TARGET").unwrap_or_default());
+    println!("cargo:rustc-env=T2Z_BUILD_PROFILE={}", env::var("PROFILE").unwrap_or_default());
+    println!("cargo:rerun-if-changed=Cargo.lock");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+}
+
+/// `name=version` (plus `#commit` for git sources) for each reported dependency in a lockfile
+fn locked_versions(lockfile: &str) -> Vec<String> {
+    let mut versions = Vec::new();
+    for package in lockfile.split("[[package]]").skip(1) {
+        let field = |key: &str| {
+            package.lines()
+                .find_map(|line| line.strip_prefix(key))
+                .map(|value| value.trim().trim_matches('"').to_string())
+        };
+        let (Some(name), Some(version)) = (field("name = "), field("version = ")) else { continue };
+        if !REPORTED_DEPENDENCIES.contains(&name.as_str()) {
+            continue;
+        }
+        // Git sources end with "#<commit>"
+        match field("source = ").and_then(|source| source.rsplit_once('#').map(|(_, rev)| rev.to_string())) {
+            Some(rev) => versions.push(format!("{}={}#{}", name, version, rev)),
+            None => versions.push(format!("{}={}", name, version)),
+        }
+    }
+    versions.sort();
+    versions
+}
@@ -0,0 +1,33 @@
+//! Sends a transparent payment with `flow::simple_t2z`
+//!
+//! Run with `cargo run --example simple_t2z`. The input is a made-up UTXO spendable
+//! by a throwaway key, so the printed transaction is well-formed but not broadcastable.
+
+use t2z::types::{script_to_raw, serialize_transparent_inputs, Payment, TransactionRequest, TransparentInput};
+use zcash_transparent::address::TransparentAddress;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let secp = secp256k1::Secp256k1::new();
+    let secret_key = secp256k1::SecretKey::from_slice(&[1u8; 32])?;
+    let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+    let script = TransparentAddress::from_pubkey(&pubkey).script().into();
+
+    let inputs = serialize_transparent_inputs(&[TransparentInput {
+        pubkey,
+        txid: [0u8; 32],
+        vout: 0,
+        amount: 100_000_000,
+        script_pubkey: script_to_raw(&script)?,
+        height: None,
+        redeem_script: None,
+        derivation_path: None,
+    }]).unwrap();
+
+    let request = TransactionRequest::new(vec![
+        Payment::new("tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma".to_string(), 100_000),
+    ]);
+
+    let tx_bytes = t2z::flow::simple_t2z(&inputs, request, None, &secret_key)?;
+    println!("{}", hex::encode(tx_bytes));
+    Ok(())
+}
@@ -29,6 +29,274 @@ namespace t2z {
  */
 #define ZIP317_GRACE_ACTIONS 2
 
+/**
+ * Size of a Zcash memo field in bytes
+ */
+#define MEMO_SIZE 512
+
+/**
+ * First byte of a compressed memo. ZIP-302 reserves 0xFF for memos whose remaining
+ * bytes follow no public specification.
+ */
+#define COMPRESSED_MEMO_MARKER 255
+
+/**
+ * Sequence number of a final transparent input (the default when none is set)
+ */
+#define FINAL_SEQUENCE 4294967295
+
+/**
+ * Highest expiry height a transaction may carry (ZIP 203)
+ */
+#define MAX_EXPIRY_HEIGHT 499999999
+
+/**
+ * Largest transaction zcashd accepts into its mempool or a block, in bytes
+ */
+#define MAX_TX_SIZE 2000000
+
+/**
+ * zcashd's relay fee rate (zatoshis per 1000 bytes) used for the dust standardness rule
+ */
+#define ONE_THIRD_DUST_THRESHOLD_RATE 100
+
+/**
+ * Number of zatoshis in one ZEC
+ */
+#define ZATOSHIS_PER_ZEC 100000000
+
+/**
+ * Version of the `ErrorDomain` namespace, raised when a domain is added
+ */
+#define ERROR_DOMAIN_VERSION 1
+
+/**
+ * Current protocol version
+ */
+#define PROTOCOL_VERSION 1
+
+/**
+ * Largest PCZT either side will accept
+ */
+#define MAX_PCZT_SIZE ((16 * 1024) * 1024)
+
+/**
+ * Connections a `ProverServer` handles at once unless `with_max_connections` says
+ * otherwise. Each may buffer a PCZT of up to `MAX_PCZT_SIZE` bytes.
+ */
+#define DEFAULT_MAX_CONNECTIONS 32
+
+/**
+ * Number of errors each thread keeps for `pczt_get_error_history`
+ */
+#define ERROR_HISTORY_CAPACITY 16
+
+/**
+ * Current version of the JSON schema
+ */
+#define JSON_SCHEMA_VERSION 1
+
+/**
+ * Version of the manifest format
+ */
+#define MANIFEST_VERSION 1
+
+/**
+ * Most Sapling notes `migrate_sapling_to_orchard` spends in one transaction, which
+ * keeps each migration transaction well under the standard size limit
+ */
+#define MIGRATION_NOTES_PER_TRANSACTION 100
+
+/**
+ * Marker in the first two bytes of the versioned (v2+) input format.
+ * A v1 header announcing 65535 inputs can't describe a valid transaction,
+ * so the two formats can't be confused.
+ */
+#define INPUTS_FORMAT_MARKER 65535
+
+/**
+ * Version 2 of the input format (adds per-input optional fields)
+ */
+#define INPUTS_FORMAT_V2 2
+
+/**
+ * v2 optional field flag: the UTXO's block height follows
+ */
+#define INPUT_FLAG_HEIGHT 2
+
+/**
+ * v2 optional field flag: a P2SH redeem script follows
+ */
+#define INPUT_FLAG_REDEEM_SCRIPT 4
+
+/**
+ * v2 optional field flag: a BIP-32 derivation of the signing key follows
+ */
+#define INPUT_FLAG_DERIVATION 8
+
+/**
+ * Current version of the sighash batch format
+ */
+#define SIGHASH_BATCH_VERSION 1
+
+/**
+ * Version of the `ReviewDocument` layout
+ */
+#define REVIEW_DOCUMENT_VERSION 3
+
+/**
+ * Version of the `BlindedReview` layout
+ */
+#define BLINDED_REVIEW_VERSION 2
+
+/**
+ * Dust threshold (in zatoshis) wallets conventionally use for transparent outputs,
+ * stricter than the P2PKH standardness limit of `DustPolicy::default()`
+ */
+#define DEFAULT_DUST_THRESHOLD 5460
+
+/**
+ * C-compatible amount display unit (see `AmountUnit`)
+ */
+typedef enum CAmountUnit {
+  /**
+   * "1.00000000 ZEC (100000000 zatoshis)"
+   */
+  AMOUNT_UNIT_BOTH = 0,
+  /**
+   * "1.00000000 ZEC"
+   */
+  AMOUNT_UNIT_ZEC = 1,
+  /**
+   * "100000000 zatoshis"
+   */
+  AMOUNT_UNIT_ZATOSHIS = 2,
+} CAmountUnit;
+
+/**
+ * C-compatible change output pool (see `ChangePolicy`)
+ */
+typedef enum CChangePolicy {
+  /**
+   * Change goes to a transparent output
+   */
+  CHANGE_POLICY_TRANSPARENT = 0,
+  /**
+   * Change goes to the Orchard receiver of the change address
+   */
+  CHANGE_POLICY_ORCHARD = 1,
+  /**
+   * Orchard if the change address has an Orchard receiver, else transparent
+   */
+  CHANGE_POLICY_AUTO = 2,
+} CChangePolicy;
+
+/**
+ * C-compatible coin selection strategy (see `CoinSelection`)
+ */
+typedef enum CCoinSelection {
+  /**
+   * Spend every provided input
+   */
+  COIN_SELECTION_ALL = 0,
+  /**
+   * Spend the largest UTXOs first
+   */
+  COIN_SELECTION_LARGEST_FIRST = 1,
+  /**
+   * Spend the smallest UTXOs first
+   */
+  COIN_SELECTION_SMALLEST_FIRST = 2,
+  /**
+   * Look for a changeless set of inputs, else largest-first
+   */
+  COIN_SELECTION_BRANCH_AND_BOUND = 3,
+} CCoinSelection;
+
+/**
+ * C-compatible dust handling (see `DustPolicy`)
+ */
+typedef enum CDustPolicy {
+  /**
+   * Refuse transparent payments below the threshold, and add dust change to the fee
+   */
+  DUST_POLICY_ADD_CHANGE_TO_FEE = 0,
+  /**
+   * Refuse transparent payments and change below the threshold
+   */
+  DUST_POLICY_REJECT = 1,
+} CDustPolicy;
+
+/**
+ * C-compatible fee policy (see `FeePolicy`)
+ */
+typedef enum CFeePolicy {
+  /**
+   * Pay the ZIP-317 conventional fee
+   */
+  FEE_POLICY_ZIP317_STANDARD = 0,
+  /**
+   * Pay a fixed number of zatoshis
+   */
+  FEE_POLICY_FIXED_ZATOSHIS = 1,
+  /**
+   * Pay the ZIP-317 fee times a multiplier of at least 1
+   */
+  FEE_POLICY_ZIP317_WITH_MULTIPLIER = 2,
+} CFeePolicy;
+
+/**
+ * C-compatible memo encoding (see `MemoEncoding`)
+ */
+typedef enum CMemoEncoding {
+  /**
+   * Plain UTF-8 text, zero-padded to 512 bytes
+   */
+  MEMO_PADDED = 0,
+  /**
+   * Deflate-compressed text behind a marker byte
+   */
+  MEMO_COMPRESSED = 1,
+} CMemoEncoding;
+
+/**
+ * C-compatible network selection (see `Network`)
+ */
+typedef enum CNetwork {
+  NETWORK_MAINNET = 0,
+  NETWORK_TESTNET = 1,
+  /**
+   * Regtest with every upgrade through NU6 active from height 1
+   */
+  NETWORK_REGTEST = 2,
+} CNetwork;
+
+/**
+ * C-compatible proof priority (see `ProofPriority`)
+ */
+typedef enum CProofPriority {
+  PROOF_PRIORITY_LOW = 0,
+  PROOF_PRIORITY_NORMAL = 1,
+  PROOF_PRIORITY_HIGH = 2,
+} CProofPriority;
+
+/**
+ * C-compatible progress of a submitted PCZT (see `ProofStatus`)
+ */
+typedef enum CProofStatus {
+  PROOF_STATUS_QUEUED = 0,
+  PROOF_STATUS_PROVING = 1,
+  PROOF_STATUS_FINISHED = 2,
+} CProofStatus;
+
+/**
+ * C-compatible strictness (see `Strictness`)
+ */
+typedef enum CStrictness {
+  STRICTNESS_LENIENT = 0,
+  STRICTNESS_STRICT = 1,
+} CStrictness;
+
 /**
  * Result code for FFI functions
  */
@@ -45,135 +313,1142 @@ typedef enum ResultCode {
   ERROR_COMBINE = 15,
   ERROR_FINALIZATION = 16,
   ERROR_PARSE = 17,
+  ERROR_INSPECTION = 18,
+  ERROR_MEMO = 19,
+  ERROR_RESERVATION = 20,
+  ERROR_FILE = 21,
   ERROR_NOT_IMPLEMENTED = 99,
 } ResultCode;
 
+typedef struct Option_PcztWriteFn Option_PcztWriteFn;
+
+typedef struct Option_ProofCallbackFn Option_ProofCallbackFn;
+
+/**
+ * The enable bits of an Orchard bundle
+ *
+ * A bundle with spends disabled may only carry dummy spends, so a transaction
+ * funded entirely from transparent inputs can mark its Orchard bundle outputs-only.
+ */
+typedef struct OrchardFlags OrchardFlags;
+
+/**
+ * C-compatible structured information about the last error
+ */
+typedef struct CErrorInfo {
+  /**
+   * Result code of the failed call
+   */
+  enum ResultCode code;
+  /**
+   * Whether `input_index` is set
+   */
+  bool has_input_index;
+  /**
+   * Index of the transparent input the error refers to (valid if `has_input_index`)
+   */
+  uintptr_t input_index;
+} CErrorInfo;
+
+/**
+ * C-compatible amounts of an insufficient-funds error
+ */
+typedef struct CInsufficientFunds {
+  /**
+   * Total of the inputs considered, in zatoshis
+   */
+  uint64_t available;
+  /**
+   * Payments plus fee, in zatoshis
+   */
+  uint64_t required;
+  /**
+   * ZIP-317 fee included in `required`, in zatoshis
+   */
+  uint64_t fee;
+} CInsufficientFunds;
+
+/**
+ * C-compatible machine-readable details of the last error, for hosts that
+ * localize messages instead of matching on them
+ */
+typedef struct CErrorDetails {
+  /**
+   * Result code of the failed call (e.g. `ErrorProposal`); the error's domain is
+   * `pczt_error_domain`
+   */
+  enum ResultCode result_code;
+  /**
+   * Stable number of the error code (see `ErrorCode::number`), 0 if no error is set
+   */
+  uint32_t sub_code;
+  /**
+   * Number of values set in `context`
+   */
+  uintptr_t context_len;
+  /**
+   * Numbers describing the error, in the order documented for its code by
+   * `FfiError::context` (e.g. available, required and fee for insufficient funds)
+   */
+  uint64_t context[3];
+} CErrorDetails;
+
+/**
+ * C-compatible payment structure
+ */
+typedef struct CPayment {
+  const char *address;
+  uint64_t amount;
+  const char *memo;
+  const char *label;
+  const char *message;
+} CPayment;
+
+/**
+ * Opaque handle to a TransactionRequest object
+ */
+typedef struct TransactionRequestHandle {
+  uint8_t _private[0];
+} TransactionRequestHandle;
+
+/**
+ * C-compatible Orchard bundle flags (see `OrchardFlags`)
+ */
+typedef struct COrchardFlags {
+  bool spends_enabled;
+  bool outputs_enabled;
+} COrchardFlags;
+
+/**
+ * Opaque handle to a PCZT object
+ */
+typedef struct PcztHandle {
+  uint8_t _private[0];
+} PcztHandle;
+
+/**
+ * Opaque handle to a ProverService
+ */
+typedef struct ProverServiceHandle {
+  uint8_t _private[0];
+} ProverServiceHandle;
+
+/**
+ * C-compatible transaction output
+ */
+typedef struct CTransparentOutput {
+  const unsigned char *script_pub_key;
+  uintptr_t script_pub_key_len;
+  uint64_t value;
+} CTransparentOutput;
+
+/**
+ * C-compatible Orchard output accounting data (see `OrchardOutputInfo`)
+ */
+typedef struct COrchardOutput {
+  uintptr_t action_index;
+  uint8_t cmx[32];
+  uint8_t ephemeral_key[32];
+  /**
+   * Whether `value` is known (false for outputs decoded from a final transaction)
+   */
+  bool has_value;
+  uint64_t value;
+} COrchardOutput;
+
+/**
+ * Opaque handle to a byte buffer owned by the library
+ *
+ * Unlike the `*mut u8` outputs freed with `pczt_free_bytes`, the bytes are handed
+ * over as produced, without shrinking them into an exactly-sized allocation first.
+ * Read them with `pczt_buffer_data` / `pczt_buffer_len` and free the handle with
+ * `pczt_buffer_free`.
+ */
+typedef struct ByteBufferHandle {
+  uint8_t _private[0];
+} ByteBufferHandle;
+
+/**
+ * C-compatible PCZT parsing options
+ */
+typedef struct CParseOptions {
+  /**
+   * Reject encodings that don't re-serialize to the exact same bytes
+   */
+  bool require_canonical;
+  /**
+   * Also reject proprietary fields outside the recognized namespaces
+   */
+  bool reject_unknown_fields;
+} CParseOptions;
+
+/**
+ * C-compatible view of a PCZT's transparent input (see `pczt_get_transparent_input`)
+ */
+typedef struct CTransparentInputInfo {
+  /**
+   * Transaction ID of the spent UTXO (internal byte order)
+   */
+  uint8_t prevout_txid[32];
+  uint32_t prevout_index;
+  /**
+   * Sequence number (0xFFFFFFFF if the input doesn't set one)
+   */
+  uint32_t sequence;
+  /**
+   * Value in zatoshis
+   */
+  uint64_t value;
+  /**
+   * The spent UTXO's script_pubkey. Points into the PCZT, and is valid until the
+   * handle is freed or consumed.
+   */
+  const unsigned char *script_pub_key;
+  uintptr_t script_pub_key_len;
+  /**
+   * Number of partial signatures the input carries
+   */
+  uintptr_t num_signatures;
+  /**
+   * Whether the input has its final script_sig
+   */
+  bool is_finalized;
+} CTransparentInputInfo;
+
+
+
+
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Gets the last error message
+ */
+
+enum ResultCode pczt_get_last_error(char *aBuffer,
+                                    uintptr_t aBufferLen)
+;
+
+/**
+ * Gets the buffer size `pczt_get_last_error` needs for the last error message,
+ * including the terminating NUL (1 if no error is set)
+ */
+
+uintptr_t pczt_get_last_error_len(void)
+;
+
+/**
+ * Gets the last error message, truncated to fit `buffer`
+ *
+ * Unlike `pczt_get_last_error`, writes as much of the message as fits (cut at a
+ * UTF-8 char boundary, NUL-terminated) instead of failing. If `truncated_out` is
+ * not null, it is set to whether part of the message was cut off. Fails with
+ * `ErrorBufferTooSmall` only if `buffer_len` is 0.
+ */
+
+enum ResultCode pczt_get_last_error_truncated(char *aBuffer,
+                                              uintptr_t aBufferLen,
+                                              bool *aTruncatedOut)
+;
+
+/**
+ * Gets the stable, machine-readable code of the last error (e.g. "INSUFFICIENT_FUNDS")
+ *
+ * Writes a NUL-terminated string into `buffer`. If no error is set, writes an empty string.
+ */
+
+enum ResultCode pczt_get_last_error_code(char *aBuffer,
+                                         uintptr_t aBufferLen)
+;
+
+/**
+ * Gets structured information about the last error
+ *
+ * If the error refers to a specific transparent input (e.g. a missing signature
+ * during finalization), `has_input_index` is set and `input_index` identifies it,
+ * so hosts can prompt the right signer. If no error is set, `code` is `SUCCESS`.
+ */
+
+enum ResultCode pczt_get_last_error_info(struct CErrorInfo *aInfoOut)
+;
+
+/**
+ * Gets the amounts of the last error if it was `INSUFFICIENT_FUNDS`
+ *
+ * Returns true and fills `funds_out` if the last error on this thread reported a
+ * shortfall, so hosts can show how much more is needed. Returns false otherwise,
+ * leaving `funds_out` untouched.
+ */
+
+bool pczt_get_last_error_insufficient_funds(struct CInsufficientFunds *aFundsOut)
+;
+
+/**
+ * Gets the result code, numeric code and context values of the last error
+ *
+ * If no error is set, `result_code` is `SUCCESS` and `sub_code` and `context_len` are 0.
+ */
+
+enum ResultCode pczt_get_last_error_details(struct CErrorDetails *aDetailsOut)
+;
+
+/**
+ * Gets the version of the error domain namespace (see `ERROR_DOMAIN_VERSION`)
+ *
+ * The version goes up whenever a domain is added, so hosts can tell whether they
+ * know every domain `pczt_error_domain` may return.
+ */
+
+uint32_t pczt_error_domain_version(void)
+;
+
+/**
+ * Gets the domain of the last error (see `ErrorDomain`), or 0 if no error is set
+ *
+ * Domains name the subsystem an error comes from (e.g. 4 for network), independently
+ * of the `ResultCode` the call returned. Together with `pczt_error_domain_code`, they
+ * let new subsystems report precise errors without adding result codes.
+ */
+
+uint32_t pczt_error_domain(void)
+;
+
+/**
+ * Gets the stable number of the last error's code (see `ErrorCode::number`), or 0
+ * if no error is set
+ */
+
+uint32_t pczt_error_domain_code(void)
+;
+
+/**
+ * Gets the last errors raised on this thread, oldest first, as a JSON array of
+ * `{"timestamp_ms", "function", "domain", "code", "message"}` objects
+ *
+ * Up to `ERROR_HISTORY_CAPACITY` errors are kept, so a pipeline can find the error
+ * that mattered even after later steps overwrote the last error. The JSON bytes
+ * must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_get_error_history(uint8_t **aJsonOut,
+                                       uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Sets the crate-wide strictness
+ *
+ * In strict mode, invalid UTF-8 in payment labels and messages, memos on
+ * transparent payments and unclassifiable addresses are errors instead of being
+ * silently dropped. Memos that aren't UTF-8 are refused in either mode.
+ */
+
+enum ResultCode pczt_set_strictness(enum CStrictness aStrictness)
+;
+
+/**
+ * Turns the cache of parsed recipient addresses on with room for `capacity`
+ * addresses, or off with 0
+ *
+ * Services paying the same addresses over and over skip decoding them again on each
+ * proposal. The cache is shared by all threads.
+ */
+
+enum ResultCode pczt_set_address_cache_capacity(uintptr_t aCapacity)
+;
+
+/**
+ * Drops every cached address, keeping the cache's capacity
+ */
+
+enum ResultCode pczt_clear_address_cache(void)
+;
+
+/**
+ * Creates a new transaction request
+ */
+
+enum ResultCode pczt_transaction_request_new(const struct CPayment *aPayments,
+                                             uintptr_t aNumPayments,
+                                             struct TransactionRequestHandle **aRequestOut)
+;
+
+/**
+ * Frees a transaction request
+ */
+
+void pczt_transaction_request_free(struct TransactionRequestHandle *aRequest)
+;
+
+/**
+ * Sets the target height for a transaction request
+ */
+
+enum ResultCode pczt_transaction_request_set_target_height(struct TransactionRequestHandle *aRequest,
+                                                           uint32_t aTargetHeight)
+;
+
+/**
+ * Sets whether to use mainnet parameters for consensus branch ID
+ *
+ * By default, the library uses mainnet parameters. Set this to false for testnet.
+ *
+ * Deprecated: use `pczt_transaction_request_set_network`, which also covers regtest.
+ * A network set there takes precedence over this flag.
+ */
+
+enum ResultCode pczt_transaction_request_set_use_mainnet(struct TransactionRequestHandle *aRequest,
+                                                         bool aUseMainnet)
+;
+
+/**
+ * Sets the network a transaction request is built for
+ *
+ * Selects the consensus branch ID for the target height. Regtest assumes every
+ * upgrade through NU6 is active from height 1; other activation heights can be
+ * given in the `network` field of a JSON request.
+ */
+
+enum ResultCode pczt_transaction_request_set_network(struct TransactionRequestHandle *aRequest,
+                                                     enum CNetwork aNetwork)
+;
+
+/**
+ * Sets the height after which the transaction can no longer be mined
+ *
+ * 0 disables expiry. Otherwise the height must be at least the target height.
+ * Overrides any expiry delta.
+ */
+
+enum ResultCode pczt_transaction_request_set_expiry_height(struct TransactionRequestHandle *aRequest,
+                                                           uint32_t aExpiryHeight)
+;
+
+/**
+ * Sets how many blocks after the target height the transaction expires (default 40)
+ */
+
+enum ResultCode pczt_transaction_request_set_expiry_delta(struct TransactionRequestHandle *aRequest,
+                                                          uint32_t aExpiryDelta)
+;
+
+/**
+ * Sets the fee tolerance for a transaction request
+ *
+ * This is the maximum amount (in zatoshis) by which the implicit fee of a proposal
+ * may exceed the ZIP-317 fee. Defaults to 0.
+ */
+
+enum ResultCode pczt_transaction_request_set_fee_tolerance(struct TransactionRequestHandle *aRequest,
+                                                           uint64_t aFeeTolerance)
+;
+
+/**
+ * Sets the highest fee (in zatoshis) a proposal may pay, or removes the cap with 0
+ *
+ * Proposals whose fee exceeds it fail with `FEE_CAP_EXCEEDED`; the message carries
+ * the computed fee.
+ */
+
+enum ResultCode pczt_transaction_request_set_max_fee(struct TransactionRequestHandle *aRequest,
+                                                     uint64_t aMaxFee)
+;
+
+/**
+ * Sets the threshold below which change is added to the fee instead of getting an output
+ *
+ * Same as `pczt_transaction_request_set_dust_policy` with `DustPolicyAddChangeToFee`.
+ * Defaults to the P2PKH dust threshold (see `pczt_p2pkh_dust_threshold`).
+ * A threshold of 0 always keeps the change output.
+ */
+
+enum ResultCode pczt_transaction_request_set_change_dust_threshold(struct TransactionRequestHandle *aRequest,
+                                                                   uint64_t aThreshold)
+;
+
+/**
+ * Sets how transparent payments and change below `threshold` zatoshis are handled
+ *
+ * Wallets conventionally use 5460 zatoshis (`DEFAULT_DUST_THRESHOLD`). A threshold
+ * of 0 makes nothing dust. Proposals that break the policy fail with
+ * `DUST_PAYMENT` or `DUST_CHANGE`.
+ */
+
+enum ResultCode pczt_transaction_request_set_dust_policy(struct TransactionRequestHandle *aRequest,
+                                                         enum CDustPolicy aPolicy,
+                                                         uint64_t aThreshold)
+;
+
+/**
+ * Sets how the fee is derived from the ZIP-317 fee (the ZIP-317 fee itself by default)
+ *
+ * `fixed_fee` is used by `FeePolicyFixedZatoshis` and `multiplier` by
+ * `FeePolicyZip317WithMultiplier`; the other is ignored. Proposals reject a
+ * multiplier below 1 with `INVALID_REQUEST`.
+ */
+
+enum ResultCode pczt_transaction_request_set_fee_policy(struct TransactionRequestHandle *aRequest,
+                                                        enum CFeePolicy aPolicy,
+                                                        uint64_t aFixedFee,
+                                                        double aMultiplier)
+;
+
+/**
+ * Sets how payment memos are encoded (padded plain text by default)
+ */
+
+enum ResultCode pczt_transaction_request_set_memo_encoding(struct TransactionRequestHandle *aRequest,
+                                                           enum CMemoEncoding aEncoding)
+;
+
+/**
+ * Sets how inputs are picked from the UTXOs passed to `pczt_propose_transaction`
+ *
+ * Defaults to spending every input. With any other strategy the inputs are treated
+ * as a pool, and inputs short of `min_confirmations` are skipped instead of refused.
+ * Experimental (see `experimental`): the strategies may still change.
+ */
+
+enum ResultCode pczt_experimental_transaction_request_set_coin_selection(struct TransactionRequestHandle *aRequest,
+                                                                         enum CCoinSelection aStrategy)
+;
+
+/**
+ * Turns sweep mode on or off (off by default)
+ *
+ * A sweep spends every input and pays the request's single payment everything left
+ * after the fee, without change; the payment's amount is ignored.
+ */
+
+enum ResultCode pczt_transaction_request_set_sweep(struct TransactionRequestHandle *aRequest,
+                                                   bool aSweep)
+;
+
+/**
+ * Sets which pool the change output goes to
+ *
+ * Defaults to transparent. Orchard change requires a unified change address with
+ * an Orchard receiver, passed to `pczt_propose_transaction`.
+ */
+
+enum ResultCode pczt_transaction_request_set_change_policy(struct TransactionRequestHandle *aRequest,
+                                                           enum CChangePolicy aPolicy)
+;
+
+/**
+ * Sets the flags of the proposal's Orchard bundle
+ *
+ * Pass NULL to keep the builder's default (spends and outputs enabled). Transactions
+ * funded only from transparent inputs can disable spends.
+ */
+
+enum ResultCode pczt_transaction_request_set_orchard_flags(struct TransactionRequestHandle *aRequest,
+                                                           const struct COrchardFlags *aFlags)
+;
+
+/**
+ * Sets the default Orchard outgoing viewing key for shielded payments
+ *
+ * Pass NULL to clear it. Individual payments can override it with
+ * `pczt_transaction_request_set_payment_ovk`.
+ */
+
+enum ResultCode pczt_transaction_request_set_ovk(struct TransactionRequestHandle *aRequest,
+                                                 const uint8_t (*aOvk)[32])
+;
+
+/**
+ * Sets the Orchard outgoing viewing key for a single payment
+ *
+ * Pass NULL to fall back to the request's default OVK.
+ */
+
+enum ResultCode pczt_transaction_request_set_payment_ovk(struct TransactionRequestHandle *aRequest,
+                                                         uintptr_t aPaymentIndex,
+                                                         const uint8_t (*aOvk)[32])
+;
+
+/**
+ * Makes a payment pay to a raw scriptPubKey instead of its address
+ *
+ * The payment's address is cleared. The script must be a standard template the
+ * builder supports (P2PKH or P2SH); others fail at proposal time.
+ */
+
+enum ResultCode pczt_transaction_request_set_payment_script(struct TransactionRequestHandle *aRequest,
+                                                            uintptr_t aPaymentIndex,
+                                                            const uint8_t *aScript,
+                                                            uintptr_t aScriptLen)
+;
+
+/**
+ * Gives a payment a raw memo of up to 512 bytes, stored as-is
+ *
+ * Replaces the payment's text memo. Unlike text memos, the bytes need not be UTF-8,
+ * so arbitrary ZIP-302 memos are never dropped. Longer memos fail with `ErrorMemo`.
+ */
+
+enum ResultCode pczt_transaction_request_set_payment_memo_bytes(struct TransactionRequestHandle *aRequest,
+                                                                uintptr_t aPaymentIndex,
+                                                                const uint8_t *aMemo,
+                                                                uintptr_t aMemoLen)
+;
+
+/**
+ * Sets the minimum confirmations each input must have (0 = no check)
+ *
+ * Requires a target height and input heights (inputs format v2).
+ */
+
+enum ResultCode pczt_transaction_request_set_min_confirmations(struct TransactionRequestHandle *aRequest,
+                                                               uint32_t aMinConfirmations)
+;
+
+/**
+ * Sets the operator label carried into the PCZT (see `transaction_label`)
+ *
+ * Pass NULL to clear it.
+ */
+
+enum ResultCode pczt_transaction_request_set_label(struct TransactionRequestHandle *aRequest,
+                                                   const char *aLabel)
+;
+
+/**
+ * Creates a transaction request from a versioned JSON document
+ *
+ * Unknown fields are ignored; documents with a newer schema version are rejected.
+ */
+
+enum ResultCode pczt_transaction_request_from_json(const uint8_t *aJson,
+                                                   uintptr_t aJsonLen,
+                                                   struct TransactionRequestHandle **aRequestOut)
+;
+
+/**
+ * Encodes a transaction request as a versioned JSON document
+ *
+ * The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_transaction_request_to_json(const struct TransactionRequestHandle *aRequest,
+                                                 uint8_t **aJsonOut,
+                                                 uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Proposes a new transaction using serialized input bytes
+ */
+
+enum ResultCode pczt_propose_transaction(const uint8_t *aInputsBytes,
+                                         uintptr_t aInputsBytesLen,
+                                         const struct TransactionRequestHandle *aRequest,
+                                         const char *aChangeAddress,
+                                         struct PcztHandle **aPcztOut)
+;
+
+/**
+ * Proposes a new transaction whose change goes to an address picked at random
+ *
+ * `change_addresses` holds `num_change_addresses` NUL-terminated strings. Callers that
+ * rotate in order track the index themselves and pass it to `pczt_propose_transaction`.
+ */
+
+enum ResultCode pczt_propose_transaction_from_pool(const uint8_t *aInputsBytes,
+                                                   uintptr_t aInputsBytesLen,
+                                                   const struct TransactionRequestHandle *aRequest,
+                                                   const char *const *aChangeAddresses,
+                                                   uintptr_t aNumChangeAddresses,
+                                                   struct PcztHandle **aPcztOut)
+;
+
+/**
+ * Proposes a new transaction from a JSON array of inputs
+ *
+ * Same as `pczt_propose_transaction`, with the inputs given as UTF-8 JSON (see
+ * `parse_transparent_inputs_json`) instead of the binary format.
+ */
+
+enum ResultCode pczt_propose_transaction_json(const uint8_t *aInputsJson,
+                                              uintptr_t aInputsJsonLen,
+                                              const struct TransactionRequestHandle *aRequest,
+                                              const char *aChangeAddress,
+                                              struct PcztHandle **aPcztOut)
+;
+
+/**
+ * Stores proving parameters as files in `dir` (default: the Zcash params directory)
+ */
+
+enum ResultCode pczt_set_params_dir(const char *aDir)
+;
+
+/**
+ * Keeps proving parameters in memory only, for hosts without writable storage
+ */
+
+enum ResultCode pczt_use_memory_params_store(void)
+;
+
+/**
+ * Builds the Orchard proving key now, so the first `pczt_prove_transaction` doesn't
+ * stall for several seconds. Calling it again is a no-op.
+ */
+
+enum ResultCode pczt_init_proving_keys(void)
+;
+
+/**
+ * Drops the Orchard verifying key that `pczt_finalize_and_extract` caches between
+ * calls; the next shielded extraction builds it again
+ */
+
+enum ResultCode pczt_unload_verifying_keys(void)
+;
+
+/**
+ * Sets how many threads proving may use (0 = no limit, the default unless the
+ * library was built with `low-memory`); see `set_prover_threads`
+ */
+
+enum ResultCode pczt_set_prover_threads(uintptr_t aThreads)
+;
+
+/**
+ * Starts a background prover service that keeps the proving key resident
+ *
+ * `workers` threads prove concurrently; up to `queue_capacity` PCZTs may wait.
+ * Stop it with `pczt_prover_service_stop`.
+ */
+
+enum ResultCode pczt_prover_service_start(uintptr_t aWorkers,
+                                          uintptr_t aQueueCapacity,
+                                          struct ProverServiceHandle **aServiceOut)
+;
+
+/**
+ * Queues a PCZT for proving; `callback` is called on a worker thread when done
+ *
+ * Blocks while the queue is full. If `job_id_out` is not null, it receives the job ID
+ * for `pczt_prover_service_status`.
+ *
+ * # Ownership
+ * This function ALWAYS consumes the input PCZT handle, even on error.
+ */
+
+enum ResultCode pczt_prover_service_submit(const struct ProverServiceHandle *aService,
+                                           struct PcztHandle *aPczt,
+                                           enum CProofPriority aPriority,
+                                           struct Option_ProofCallbackFn aCallback,
+                                           void *aUserData,
+                                           uint64_t *aJobIdOut)
+;
+
+/**
+ * Gets the number of PCZTs waiting in each priority lane
+ */
+
+enum ResultCode pczt_prover_service_queue_depth(const struct ProverServiceHandle *aService,
+                                                uintptr_t *aHighOut,
+                                                uintptr_t *aNormalOut,
+                                                uintptr_t *aLowOut)
+;
+
+/**
+ * Gets the progress of a submitted PCZT
+ *
+ * For queued PCZTs, `position_out` receives the number of PCZTs that will be taken
+ * before it; otherwise it is set to 0.
+ */
+
+enum ResultCode pczt_prover_service_status(const struct ProverServiceHandle *aService,
+                                           uint64_t aJobId,
+                                           enum CProofStatus *aStatusOut,
+                                           uintptr_t *aPositionOut)
+;
+
+/**
+ * Stops a prover service after finishing its queued PCZTs, and frees it
+ */
+
+void pczt_prover_service_stop(struct ProverServiceHandle *aService)
+;
+
+/**
+ * Adds proofs to a PCZT.
+ *
+ * # Ownership
+ * This function ALWAYS consumes the input PCZT handle, even on error.
+ * On success, `pczt_out` contains the new PCZT with proofs.
+ * On error, the input handle is invalidated and cannot be reused.
+ *
+ * If you need to retry on failure, call `pczt_serialize()` before this
+ * function to create a backup that can be restored with `pczt_parse()`.
+ */
+
+enum ResultCode pczt_prove_transaction(struct PcztHandle *aPczt,
+                                       struct PcztHandle **aPcztOut)
+;
+
+/**
+ * Adds proofs to many PCZTs in parallel (see `prove_transactions`).
+ *
+ * # Ownership
+ * This function ALWAYS consumes every input handle, even on error.
+ * `pczts_out[i]` receives the proved PCZT for `pczts[i]`, or null if proving it
+ * failed, and `results_out[i]` its result code. Both arrays hold `num_pczts`
+ * entries; `results_out` may be null.
+ *
+ * # Returns
+ * * `ResultCode::Success` if every PCZT was proved
+ * * `ResultCode::ErrorProver` if any failed (the last error describes the first failure)
+ */
+
+enum ResultCode pczt_prove_batch(struct PcztHandle *const *aPczts,
+                                 uintptr_t aNumPczts,
+                                 struct PcztHandle **aPcztsOut,
+                                 enum ResultCode *aResultsOut)
+;
+
+/**
+ * Adds Orchard proofs like `pczt_prove_transaction`, for hosts that call it on a
+ * worker thread (e.g. Node's libuv pool through koffi's async calls)
+ *
+ * The last error is per thread, so a host can't read it after the worker returns.
+ * On failure, the message and the stable error code are written to `message_buf`
+ * and `code_buf` instead (NUL-terminated, truncated to fit; either may be null).
+ *
+ * # Ownership
+ * This function ALWAYS consumes the input PCZT handle, even on error.
+ */
+
+enum ResultCode pczt_prove_transaction_on_worker(struct PcztHandle *aPczt,
+                                                 struct PcztHandle **aPcztOut,
+                                                 char *aMessageBuf,
+                                                 uintptr_t aMessageLen,
+                                                 char *aCodeBuf,
+                                                 uintptr_t aCodeLen)
+;
+
+/**
+ * Verifies the PCZT before signing
+ */
+
+enum ResultCode pczt_verify_before_signing(const struct PcztHandle *aPczt,
+                                           const struct TransactionRequestHandle *aRequest,
+                                           const struct CTransparentOutput *aExpectedChange,
+                                           uintptr_t aExpectedChangeLen)
+;
+
+/**
+ * Runs every pre-signing check and writes all failures as a JSON document
+ * (`json::VerificationReportJson`) to be freed with `pczt_free_bytes`.
+ *
+ * Succeeds whenever the checks could run; a report with errors in it is not a
+ * failure of this call.
+ */
+
+enum ResultCode pczt_verify_report(const struct PcztHandle *aPczt,
+                                   const struct TransactionRequestHandle *aRequest,
+                                   const struct CTransparentOutput *aExpectedChange,
+                                   uintptr_t aExpectedChangeLen,
+                                   uint8_t **aJsonOut,
+                                   uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Verifies that a serialized final transaction matches the PCZT it was extracted from
+ */
+
+enum ResultCode pczt_verify_extraction(const struct PcztHandle *aPczt,
+                                       const uint8_t *aTxBytes,
+                                       uintptr_t aTxBytesLen)
+;
+
+/**
+ * Exports a review document (JSON) summarizing a proposal for four-eyes approval
+ *
+ * Display amounts are rendered in `unit`. The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_export_review(const struct PcztHandle *aPczt,
+                                   const struct TransactionRequestHandle *aRequest,
+                                   enum CAmountUnit aUnit,
+                                   uint8_t **aJsonOut,
+                                   uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Verifies that an approved review document (JSON) still matches the proposal
+ */
+
+enum ResultCode pczt_verify_review(const struct PcztHandle *aPczt,
+                                   const struct TransactionRequestHandle *aRequest,
+                                   const uint8_t *aJson,
+                                   uintptr_t aJsonLen)
+;
+
+/**
+ * Exports an amount-blinded review (JSON) for an approver who may not see individual
+ * shielded amounts or memos
+ *
+ * Display totals are rendered in `unit`. The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_export_blinded_review(const struct PcztHandle *aPczt,
+                                           const struct TransactionRequestHandle *aRequest,
+                                           enum CAmountUnit aUnit,
+                                           uint8_t **aJsonOut,
+                                           uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Verifies that an approved blinded review (JSON) still matches the proposal
+ */
+
+enum ResultCode pczt_verify_blinded_review(const struct PcztHandle *aPczt,
+                                           const struct TransactionRequestHandle *aRequest,
+                                           const uint8_t *aJson,
+                                           uintptr_t aJsonLen)
+;
+
+/**
+ * Exports a manifest (JSON) of a PCZT's inputs, outputs, fee and fingerprint, signed
+ * with the operator's 32-byte Ed25519 secret key
+ *
+ * The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_sign_manifest(const struct PcztHandle *aPczt,
+                                   const uint8_t (*aSigningKey)[32],
+                                   uint8_t **aJsonOut,
+                                   uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Verifies that a PCZT matches a signed manifest (JSON) from the operator whose
+ * 32-byte Ed25519 public key is `trusted_key`
+ */
+
+enum ResultCode pczt_verify_manifest(const struct PcztHandle *aPczt,
+                                     const uint8_t *aJson,
+                                     uintptr_t aJsonLen,
+                                     const uint8_t (*aTrustedKey)[32])
+;
+
+/**
+ * Summarizes a PCZT as a versioned JSON proposal document
+ *
+ * The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_proposal_to_json(const struct PcztHandle *aPczt,
+                                      uint8_t **aJsonOut,
+                                      uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Summarizes a PCZT for a confirmation screen as a versioned JSON document
+ * (`TransactionSummary`) to be freed with `pczt_free_bytes`
+ */
+
+enum ResultCode pczt_summarize(const struct PcztHandle *aPczt,
+                               uint8_t **aJsonOut,
+                               uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Decodes a serialized final transaction into a versioned JSON document
+ *
+ * The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_decode_transaction_json(const uint8_t *aTxBytes,
+                                             uintptr_t aTxBytesLen,
+                                             uint8_t **aJsonOut,
+                                             uintptr_t *aJsonLenOut)
+;
+
+/**
+ * Renders a P2PKH or P2SH script as a transparent address, for confirmation UIs
+ *
+ * The address is written to `buffer` as a NUL-terminated string. Other scripts
+ * fail with `ErrorParse`.
+ */
+
+enum ResultCode pczt_script_to_address(const uint8_t *aScript,
+                                       uintptr_t aScriptLen,
+                                       bool aUseMainnet,
+                                       char *aBuffer,
+                                       uintptr_t aBufferLen)
+;
+
+/**
+ * Returns the scriptPubKey a transparent address pays to
+ *
+ * Writes at most `script_capacity` bytes to `script_out` and the script length to
+ * `script_len_out`. Scripts are at most 25 bytes.
+ */
+
+enum ResultCode pczt_address_to_script(const char *aAddress,
+                                       uint8_t *aScriptOut,
+                                       uintptr_t aScriptCapacity,
+                                       uintptr_t *aScriptLenOut)
+;
+
+/**
+ * Gets the signature hash for an input
+ */
+
+enum ResultCode pczt_get_sighash(const struct PcztHandle *aPczt,
+                                 uintptr_t aInputIndex,
+                                 uint8_t (*aSighashOut)[32])
+;
+
 /**
- * C-compatible payment structure
+ * Gets the sequence number of a transparent input
+ *
+ * Inputs without an explicit sequence number report 0xFFFFFFFF (final).
  */
-typedef struct CPayment {
-  const char *address;
-  uint64_t amount;
-  const char *memo;
-  const char *label;
-  const char *message;
-} CPayment;
+
+enum ResultCode pczt_get_input_sequence(const struct PcztHandle *aPczt,
+                                        uintptr_t aInputIndex,
+                                        uint32_t *aSequenceOut)
+;
 
 /**
- * Opaque handle to a TransactionRequest object
+ * Gets the compressed public key (33 bytes) that controls a transparent input
  */
-typedef struct TransactionRequestHandle {
-  uint8_t _private[0];
-} TransactionRequestHandle;
+
+enum ResultCode pczt_get_input_pubkey(const struct PcztHandle *aPczt,
+                                      uintptr_t aInputIndex,
+                                      uint8_t (*aPubkeyOut)[33])
+;
 
 /**
- * Opaque handle to a PCZT object
+ * Exports every input's sighash and the shared transaction context as one binary document
+ *
+ * Fails with `ErrorParse` if an input count or script is too long for the document's
+ * format. The bytes must be freed with `pczt_free_bytes`.
  */
-typedef struct PcztHandle {
-  uint8_t _private[0];
-} PcztHandle;
+
+enum ResultCode pczt_export_sighash_batch(const struct PcztHandle *aPczt,
+                                          uint8_t **aBytesOut,
+                                          uintptr_t *aBytesLenOut)
+;
 
 /**
- * C-compatible transaction output
+ * Checks that a sighash batch document is well-formed and internally consistent
+ *
+ * On success, writes the number of inputs it covers to `num_inputs_out`.
  */
-typedef struct CTransparentOutput {
-  const unsigned char *script_pub_key;
-  uintptr_t script_pub_key_len;
-  uint64_t value;
-} CTransparentOutput;
 
-#ifdef __cplusplus
-extern "C" {
-#endif // __cplusplus
+enum ResultCode pczt_validate_sighash_batch(const uint8_t *aBytes,
+                                            uintptr_t aBytesLen,
+                                            uintptr_t *aNumInputsOut)
+;
 
 /**
- * Gets the last error message
+ * Gets the message (32 bytes) to sign with an input key to prove a change output is the proposer's
  */
 
-enum ResultCode pczt_get_last_error(char *aBuffer,
-                                    uintptr_t aBufferLen)
+enum ResultCode pczt_change_ownership_message(const struct PcztHandle *aPczt,
+                                              uintptr_t aOutputIndex,
+                                              uint8_t (*aMessageOut)[32])
 ;
 
 /**
- * Creates a new transaction request
+ * Checks that a PCZT received from another party is internally consistent (see `validate_pczt`)
  */
 
-enum ResultCode pczt_transaction_request_new(const struct CPayment *aPayments,
-                                             uintptr_t aNumPayments,
-                                             struct TransactionRequestHandle **aRequestOut)
+enum ResultCode pczt_validate(const struct PcztHandle *aPczt)
 ;
 
 /**
- * Frees a transaction request
+ * Verifies that a transparent change output was endorsed by the key of `input_index`
  */
 
-void pczt_transaction_request_free(struct TransactionRequestHandle *aRequest)
+enum ResultCode pczt_verify_change_ownership(const struct PcztHandle *aPczt,
+                                             uintptr_t aOutputIndex,
+                                             uintptr_t aInputIndex,
+                                             const uint8_t (*aSignature)[64])
 ;
 
 /**
- * Sets the target height for a transaction request
+ * Gets the note commitment and ephemeral key of each Orchard output in a PCZT
+ *
+ * Call with `capacity = 0` to query the number of outputs first.
  */
 
-enum ResultCode pczt_transaction_request_set_target_height(struct TransactionRequestHandle *aRequest,
-                                                           uint32_t aTargetHeight)
+enum ResultCode pczt_get_orchard_outputs(const struct PcztHandle *aPczt,
+                                         struct COrchardOutput *aOutputsOut,
+                                         uintptr_t aCapacity,
+                                         uintptr_t *aOutputsLenOut)
 ;
 
 /**
- * Sets whether to use mainnet parameters for consensus branch ID
+ * Gets the note commitment and ephemeral key of each Orchard action in a final transaction
  *
- * By default, the library uses mainnet parameters. Set this to false for testnet.
- * Regtest networks (like Zebra's regtest) typically use mainnet-like branch IDs,
- * so keep the default (true) for regtest.
+ * Call with `capacity = 0` to query the number of outputs first.
  */
 
-enum ResultCode pczt_transaction_request_set_use_mainnet(struct TransactionRequestHandle *aRequest,
-                                                         bool aUseMainnet)
+enum ResultCode pczt_tx_get_orchard_outputs(const uint8_t *aTxBytes,
+                                            uintptr_t aTxBytesLen,
+                                            struct COrchardOutput *aOutputsOut,
+                                            uintptr_t aCapacity,
+                                            uintptr_t *aOutputsLenOut)
 ;
 
 /**
- * Proposes a new transaction using serialized input bytes
+ * Gets the fingerprint of the transaction a PCZT represents
+ *
+ * The fingerprint covers transaction effects only (not signatures or proofs), so
+ * two parties can confirm they hold the same transaction before combining.
  */
 
-enum ResultCode pczt_propose_transaction(const uint8_t *aInputsBytes,
-                                         uintptr_t aInputsBytesLen,
-                                         const struct TransactionRequestHandle *aRequest,
-                                         const char *aChangeAddress,
-                                         struct PcztHandle **aPcztOut)
+enum ResultCode pczt_get_fingerprint(const struct PcztHandle *aPczt,
+                                     uint8_t (*aFingerprintOut)[32])
 ;
 
 /**
- * Adds proofs to a PCZT.
+ * Stamps a fresh reservation token into a PCZT (see `reservation`).
+ *
+ * The host locks the PCZT's inputs under the token written to `token_out`, and
+ * reads it back with `pczt_get_reservation_token` to release them.
  *
  * # Ownership
  * This function ALWAYS consumes the input PCZT handle, even on error.
- * On success, `pczt_out` contains the new PCZT with proofs.
- * On error, the input handle is invalidated and cannot be reused.
- *
- * If you need to retry on failure, call `pczt_serialize()` before this
- * function to create a backup that can be restored with `pczt_parse()`.
+ * On success, `pczt_out` contains the stamped PCZT.
  */
 
-enum ResultCode pczt_prove_transaction(struct PcztHandle *aPczt,
+enum ResultCode pczt_stamp_reservation(struct PcztHandle *aPczt,
+                                       uint8_t (*aTokenOut)[16],
                                        struct PcztHandle **aPcztOut)
 ;
 
 /**
- * Verifies the PCZT before signing
+ * Reads the reservation token stamped into a PCZT.
+ *
+ * `found_out` is set to false (and `token_out` left untouched) if the PCZT carries
+ * no token.
  */
 
-enum ResultCode pczt_verify_before_signing(const struct PcztHandle *aPczt,
-                                           const struct TransactionRequestHandle *aRequest,
-                                           const struct CTransparentOutput *aExpectedChange,
-                                           uintptr_t aExpectedChangeLen)
+enum ResultCode pczt_get_reservation_token(const struct PcztHandle *aPczt,
+                                           uint8_t (*aTokenOut)[16],
+                                           bool *aFoundOut)
 ;
 
 /**
- * Gets the signature hash for an input
+ * Gets the operator label a PCZT was proposed with
+ *
+ * Writes NULL and a length of 0 if the PCZT has no label. Otherwise the UTF-8 bytes
+ * (not NUL-terminated) must be freed with `pczt_free_bytes`.
  */
 
-enum ResultCode pczt_get_sighash(const struct PcztHandle *aPczt,
-                                 uintptr_t aInputIndex,
-                                 uint8_t (*aSighashOut)[32])
+enum ResultCode pczt_get_label(const struct PcztHandle *aPczt,
+                               uint8_t **aLabelOut,
+                               uintptr_t *aLabelLenOut)
 ;
 
 /**
@@ -194,6 +1469,24 @@ enum ResultCode pczt_append_signature(struct PcztHandle *aPczt,
                                       struct PcztHandle **aPcztOut)
 ;
 
+/**
+ * Signs a transparent input with a 32-byte secp256k1 secret key.
+ *
+ * # Ownership
+ * This function ALWAYS consumes the input PCZT handle, even on error.
+ * On success, `pczt_out` contains the new PCZT with the signature added.
+ * On error, the input handle is invalidated and cannot be reused.
+ *
+ * If you need to retry on failure, call `pczt_serialize()` before this
+ * function to create a backup that can be restored with `pczt_parse()`.
+ */
+
+enum ResultCode pczt_sign_transparent(struct PcztHandle *aPczt,
+                                      uintptr_t aInputIndex,
+                                      const uint8_t (*aSecretKey)[32],
+                                      struct PcztHandle **aPcztOut)
+;
+
 /**
  * Finalizes and extracts the transaction.
  *
@@ -211,6 +1504,35 @@ enum ResultCode pczt_finalize_and_extract(struct PcztHandle *aPczt,
                                           uintptr_t *aTxBytesLenOut)
 ;
 
+/**
+ * Finalizes and extracts like `pczt_finalize_and_extract`, into a library-owned
+ * buffer (see `ByteBufferHandle`)
+ *
+ * # Ownership
+ * This function ALWAYS consumes the input PCZT handle, even on error.
+ */
+
+enum ResultCode pczt_finalize_and_extract_buffer(struct PcztHandle *aPczt,
+                                                 struct ByteBufferHandle **aBufferOut)
+;
+
+/**
+ * Finalizes and extracts like `pczt_finalize_and_extract`, for hosts that call it on
+ * a worker thread (see `pczt_prove_transaction_on_worker` for the error buffers)
+ *
+ * # Ownership
+ * This function ALWAYS consumes the input PCZT handle, even on error.
+ */
+
+enum ResultCode pczt_finalize_and_extract_on_worker(struct PcztHandle *aPczt,
+                                                    uint8_t **aTxBytesOut,
+                                                    uintptr_t *aTxBytesLenOut,
+                                                    char *aMessageBuf,
+                                                    uintptr_t aMessageLen,
+                                                    char *aCodeBuf,
+                                                    uintptr_t aCodeLen)
+;
+
 /**
  * Parses a PCZT from bytes
  */
@@ -220,6 +1542,34 @@ enum ResultCode pczt_parse(const uint8_t *aPcztBytes,
                            struct PcztHandle **aPcztOut)
 ;
 
+/**
+ * Parses a PCZT from bytes with options
+ *
+ * `options` may be null, in which case this behaves like `pczt_parse`.
+ */
+
+enum ResultCode pczt_parse_with_options(const uint8_t *aPcztBytes,
+                                        uintptr_t aPcztBytesLen,
+                                        const struct CParseOptions *aOptions,
+                                        struct PcztHandle **aPcztOut)
+;
+
+/**
+ * Recognizes a proprietary namespace for parsing with `reject_unknown_fields`
+ */
+
+enum ResultCode pczt_register_proprietary_namespace(const char *aNamespace)
+;
+
+/**
+ * Checks whether PCZT bytes are in canonical form
+ */
+
+enum ResultCode pczt_is_canonical(const uint8_t *aPcztBytes,
+                                  uintptr_t aPcztBytesLen,
+                                  bool *aResultOut)
+;
+
 /**
  * Serializes a PCZT to bytes
  */
@@ -229,6 +1579,82 @@ enum ResultCode pczt_serialize(const struct PcztHandle *aPczt,
                                uintptr_t *aBytesLenOut)
 ;
 
+/**
+ * Saves a PCZT to a file, atomically (see `save_pczt`)
+ *
+ * The handle is not consumed. By convention the path ends in `.pczt`.
+ */
+
+enum ResultCode pczt_save(const struct PcztHandle *aPczt,
+                          const char *aPath)
+;
+
+/**
+ * Loads a PCZT from a file (see `load_pczt`)
+ *
+ * Files without the PCZT magic bytes fail with `ErrorFile` (`NOT_A_PCZT_FILE`).
+ */
+
+enum ResultCode pczt_load(const char *aPath,
+                          struct PcztHandle **aPcztOut)
+;
+
+/**
+ * Serializes a PCZT into a library-owned buffer (see `ByteBufferHandle`)
+ *
+ * Produces the same bytes as `pczt_serialize` with one copy fewer, which matters
+ * for multi-megabyte proved PCZTs.
+ */
+
+enum ResultCode pczt_serialize_buffer(const struct PcztHandle *aPczt,
+                                      struct ByteBufferHandle **aBufferOut)
+;
+
+/**
+ * Start of the bytes in a buffer (valid until `pczt_buffer_free`)
+ */
+
+const uint8_t *pczt_buffer_data(const struct ByteBufferHandle *aBuffer)
+;
+
+/**
+ * Number of bytes in a buffer
+ */
+
+uintptr_t pczt_buffer_len(const struct ByteBufferHandle *aBuffer)
+;
+
+/**
+ * Frees a buffer returned by the library
+ */
+
+void pczt_buffer_free(struct ByteBufferHandle *aBuffer)
+;
+
+/**
+ * Writes a serialized PCZT to `write_fn`
+ *
+ * Produces the same bytes as `pczt_serialize`. Returns `ErrorParse` if `write_fn`
+ * aborts.
+ */
+
+enum ResultCode pczt_write(const struct PcztHandle *aPczt,
+                           struct Option_PcztWriteFn aWriteFn,
+                           void *aUserData)
+;
+
+/**
+ * Decodes a memo field (plain text or compressed) into UTF-8 text
+ *
+ * The text is returned without a NUL terminator and must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_decode_memo(const uint8_t *aMemoBytes,
+                                 uintptr_t aMemoBytesLen,
+                                 uint8_t **aTextOut,
+                                 uintptr_t *aTextLenOut)
+;
+
 /**
  * Combines multiple PCZTs into one.
  *
@@ -258,6 +1684,83 @@ enum ResultCode pczt_combine(struct PcztHandle *const *aPczts,
                              struct PcztHandle **aPcztOut)
 ;
 
+/**
+ * Checks whether a PCZT is transparent-only (t→t)
+ *
+ * Transparent-only PCZTs need no proofs; `pczt_prove_transaction` returns them unchanged.
+ */
+
+enum ResultCode pczt_is_transparent_only(const struct PcztHandle *aPczt,
+                                         bool *aResultOut)
+;
+
+/**
+ * Gets the flags of a PCZT's Orchard bundle
+ *
+ * Sets `has_bundle_out` to false (leaving `flags_out` untouched) if the PCZT has no
+ * Orchard actions.
+ */
+
+enum ResultCode pczt_get_orchard_flags(const struct PcztHandle *aPczt,
+                                       bool *aHasBundleOut,
+                                       struct COrchardFlags *aFlagsOut)
+;
+
+/**
+ * Gets the number of transparent inputs of a PCZT
+ */
+
+enum ResultCode pczt_get_transparent_input_count(const struct PcztHandle *aPczt,
+                                                 uintptr_t *aCountOut)
+;
+
+/**
+ * Gets the prevout, value, script and signing state of a transparent input
+ */
+
+enum ResultCode pczt_get_transparent_input(const struct PcztHandle *aPczt,
+                                           uintptr_t aInputIndex,
+                                           struct CTransparentInputInfo *aInputOut)
+;
+
+/**
+ * Gets the number of transparent outputs of a PCZT
+ */
+
+enum ResultCode pczt_get_transparent_output_count(const struct PcztHandle *aPczt,
+                                                  uintptr_t *aCountOut)
+;
+
+/**
+ * Gets the script and value of a transparent output
+ *
+ * `script_pub_key` points into the PCZT, and is valid until the handle is freed or
+ * consumed.
+ */
+
+enum ResultCode pczt_get_transparent_output(const struct PcztHandle *aPczt,
+                                            uintptr_t aOutputIndex,
+                                            struct CTransparentOutput *aOutputOut)
+;
+
+/**
+ * Gets the number of Orchard actions of a PCZT, padding included
+ */
+
+enum ResultCode pczt_get_orchard_action_count(const struct PcztHandle *aPczt,
+                                              uintptr_t *aCountOut)
+;
+
+/**
+ * Checks whether a PCZT's Orchard bundle has its proof
+ *
+ * PCZTs without Orchard actions need no proof and report false.
+ */
+
+enum ResultCode pczt_has_orchard_proof(const struct PcztHandle *aPczt,
+                                       bool *aHasProofOut)
+;
+
 /**
  * Frees a PCZT handle
  */
@@ -273,6 +1776,16 @@ void pczt_free_bytes(uint8_t *aBytes,
                      uintptr_t aLen)
 ;
 
+/**
+ * Describes how the library was built as a JSON document (see `build_info`)
+ *
+ * The JSON bytes must be freed with `pczt_free_bytes`.
+ */
+
+enum ResultCode pczt_build_info(uint8_t **aJsonOut,
+                                uintptr_t *aJsonLenOut)
+;
+
 /**
  * Calculates the ZIP-317 transaction fee.
  *
@@ -300,6 +1813,55 @@ uint64_t pczt_calculate_fee(uintptr_t aNumTransparentInputs,
                             uintptr_t aNumOrchardOutputs)
 ;
 
+/**
+ * Calculates the ZIP-317 conventional fee from the shape of a PCZT
+ *
+ * Needs no transaction request: action counts come from the PCZT itself.
+ */
+
+enum ResultCode pczt_conventional_fee(const struct PcztHandle *aPczt,
+                                      uint64_t *aFeeOut)
+;
+
+/**
+ * Estimates the serialized size in bytes of a transaction with the given shape
+ *
+ * Counts are those of `pczt_calculate_fee`. The estimate is an upper bound; compare
+ * it with `MAX_TX_SIZE` for the mempool limit.
+ */
+
+uintptr_t pczt_estimate_tx_size(uintptr_t aNumTransparentInputs,
+                                uintptr_t aNumTransparentOutputs,
+                                uintptr_t aNumOrchardOutputs)
+;
+
+/**
+ * Estimates the serialized size in bytes of the transaction a PCZT extracts to
+ */
+
+enum ResultCode pczt_estimated_final_size(const struct PcztHandle *aPczt,
+                                          uintptr_t *aSizeOut)
+;
+
+/**
+ * Renders an amount for display, independent of the host's locale
+ *
+ * Writes a NUL-terminated string such as "1.23450000 ZEC (123450000 zatoshis)" into `buffer`.
+ */
+
+enum ResultCode pczt_format_amount(uint64_t aZatoshis,
+                                   enum CAmountUnit aUnit,
+                                   char *aBuffer,
+                                   uintptr_t aBufferLen)
+;
+
+/**
+ * Returns the standardness dust threshold (in zatoshis) for a P2PKH output
+ */
+
+uint64_t pczt_p2pkh_dust_threshold(void)
+;
+
 #ifdef __cplusplus
 } // extern "C"
 #endif // __cplusplus
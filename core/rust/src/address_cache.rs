@@ -0,0 +1,129 @@
+//! Cache of parsed recipient addresses
+//!
+//! Decoding an address (Base58Check or Bech32m, then the unified container) costs far
+//! more than the rest of adding a payment, and hot paths such as exchange withdrawals
+//! pay the same few thousand addresses over and over. With a capacity set by
+//! `set_address_cache_capacity`, every address parsed by a proposal, verification or
+//! `Payment` helper is remembered by its string until it is the least recently used
+//! entry of a full cache. The cache is shared by all threads and off by default.
+//!
+//! Only addresses that decode are cached, so a flood of garbage can evict entries but
+//! never poison them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use zcash_address::{unified, ZcashAddress};
+use zcash_transparent::address::TransparentAddress;
+
+use crate::types::UnifiedAddressWrapper;
+
+/// What a recipient address decodes to
+#[derive(Debug, Clone)]
+pub(crate) enum ParsedAddress {
+    /// A P2PKH or P2SH address
+    Transparent(TransparentAddress),
+    /// A unified address, with its raw Orchard receiver if it has one
+    Unified(Option<[u8; 43]>),
+    /// A valid address of another kind (e.g. Sapling)
+    Other,
+}
+
+impl ParsedAddress {
+    fn parse(address: &str) -> Option<Self> {
+        use zcash_address::unified::Container;
+
+        let address = address.parse::<ZcashAddress>().ok()?;
+        if let Ok(t_addr) = address.clone().convert::<TransparentAddress>() {
+            return Some(ParsedAddress::Transparent(t_addr));
+        }
+        Some(match address.convert::<UnifiedAddressWrapper>() {
+            Ok(wrapper) => ParsedAddress::Unified(wrapper.0.items().into_iter().find_map(|receiver| match receiver {
+                unified::Receiver::Orchard(raw_addr) => Some(raw_addr),
+                _ => None,
+            })),
+            Err(_) => ParsedAddress::Other,
+        })
+    }
+}
+
+/// Least-recently-used map from address strings to their parsed form
+struct AddressCache {
+    capacity: usize,
+    /// Address -> (last use, parsed address)
+    entries: HashMap<String, (u64, ParsedAddress)>,
+    /// Last use -> address, oldest first
+    recency: BTreeMap<u64, String>,
+    clock: u64,
+}
+
+impl AddressCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), recency: BTreeMap::new(), clock: 0 }
+    }
+
+    fn get(&mut self, address: &str) -> Option<ParsedAddress> {
+        self.clock += 1;
+        let (last_use, parsed) = self.entries.get_mut(address)?;
+        let key = self.recency.remove(&*last_use).expect("entries and recency agree");
+        *last_use = self.clock;
+        self.recency.insert(self.clock, key);
+        Some(parsed.clone())
+    }
+
+    fn insert(&mut self, address: &str, parsed: ParsedAddress) {
+        self.clock += 1;
+        if let Some((last_use, _)) = self.entries.insert(address.to_string(), (self.clock, parsed)) {
+            // Another thread cached it first
+            self.recency.remove(&last_use);
+        } else if self.entries.len() > self.capacity {
+            let (_, oldest) = self.recency.pop_first().expect("a full cache has entries");
+            self.entries.remove(&oldest);
+        }
+        self.recency.insert(self.clock, address.to_string());
+    }
+}
+
+/// The cache, or None while it is off
+static ADDRESS_CACHE: Mutex<Option<AddressCache>> = Mutex::new(None);
+
+/// Turns the address cache on with room for `capacity` addresses, or off if
+/// `capacity` is 0. Any cached addresses are dropped.
+pub fn set_address_cache_capacity(capacity: usize) {
+    let mut cache = ADDRESS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    *cache = (capacity > 0).then(|| AddressCache::new(capacity));
+}
+
+/// Drops every cached address, keeping the cache's capacity
+pub fn clear_address_cache() {
+    let mut cache = ADDRESS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(cache) = cache.as_mut() {
+        *cache = AddressCache::new(cache.capacity);
+    }
+}
+
+/// Number of addresses currently cached (0 while the cache is off)
+pub fn address_cache_len() -> usize {
+    let cache = ADDRESS_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.as_ref().map_or(0, |cache| cache.entries.len())
+}
+
+/// Parses `address`, through the cache if it is on. None if it isn't a valid address.
+pub(crate) fn parse_address(address: &str) -> Option<ParsedAddress> {
+    let enabled = match ADDRESS_CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+        Some(cache) => match cache.get(address) {
+            Some(parsed) => return Some(parsed),
+            None => true,
+        },
+        None => false,
+    };
+
+    // Decode without holding the lock, so other threads' hits aren't held up
+    let parsed = ParsedAddress::parse(address)?;
+    if enabled {
+        if let Some(cache) = ADDRESS_CACHE.lock().unwrap_or_else(|e| e.into_inner()).as_mut() {
+            cache.insert(address, parsed.clone());
+        }
+    }
+    Some(parsed)
+}
@@ -0,0 +1,19 @@
+//! Reference command-line tool for the t2z transaction flow (requires the `cli` feature)
+//!
+//! The commands live in `t2z::cli`; this binary only forwards its arguments.
+//!
+//! ```bash
+//! cargo run --features cli --bin t2z-cli -- propose inputs.json request.json --out proposal.pczt
+//! ```
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    match t2z::cli::run(std::env::args().skip(1), &mut std::io::stdout().lock()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
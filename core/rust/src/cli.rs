@@ -0,0 +1,188 @@
+//! Commands of the `t2z-cli` reference tool (requires the `cli` feature)
+//!
+//! Each subcommand is a thin wrapper over one library call, so a PCZT stuck at some
+//! step can be replayed through exactly the code paths a wallet or service uses.
+//! PCZTs are read and written with `load_pczt` and `save_pczt`, as `.pczt` files.
+//! Everything else a command prints or writes (signature hashes, transactions) is
+//! hex text, so a file written by `extract` is what `broadcast` reads.
+//!
+//! The binary only forwards its arguments to `run`, which tests drive directly.
+
+use std::error::Error;
+use std::io::Write;
+
+use zcash_protocol::consensus::NetworkType;
+
+use crate::experimental::lightwalletd::LightwalletdUtxoChecker;
+
+pub const USAGE: &str = "\
+Usage: t2z-cli <command> [args]
+
+Commands:
+  propose <inputs.json> <request.json> [--change <address>] --out <pczt>
+                                      Create a PCZT from JSON inputs and request
+  inspect <pczt>                      Print the PCZT's summary as JSON
+  sighash <pczt> <input>              Print an input's signature hash (hex)
+  append-sig <pczt> <input> <signature> --out <pczt>
+                                      Add a 64-byte compact signature (hex)
+  prove <pczt> --out <pczt>           Add the Orchard proofs
+  combine <pczt>... --out <pczt>      Merge PCZTs signed or proved in parallel
+  extract <pczt> [--out <tx>]         Finalize and print the raw transaction (hex), or
+                                      write it to --out
+  broadcast <tx> --server <url>       Send a transaction written by extract (hex)
+                                      through lightwalletd";
+
+pub type CliResult = Result<(), Box<dyn Error>>;
+
+/// Positional arguments and `--name value` options of a subcommand
+struct Args {
+    positional: Vec<String>,
+    options: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, String> {
+        let mut positional = Vec::new();
+        let mut options = Vec::new();
+        while let Some(arg) = args.next() {
+            match arg.strip_prefix("--") {
+                Some(name) => {
+                    let value = args.next().ok_or_else(|| format!("--{} needs a value", name))?;
+                    options.push((name.to_string(), value));
+                }
+                None => positional.push(arg),
+            }
+        }
+        Ok(Self { positional, options })
+    }
+
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn required_option(&self, name: &str) -> Result<&str, String> {
+        self.option(name).ok_or_else(|| format!("Missing --{}", name))
+    }
+
+    /// The positional arguments, which must number exactly `count`
+    fn exactly(&self, count: usize) -> Result<&[String], String> {
+        if self.positional.len() != count {
+            return Err(format!("Expected {} arguments, got {}", count, self.positional.len()));
+        }
+        Ok(&self.positional)
+    }
+}
+
+/// Runs a command line (without the program name), writing what the command prints
+/// to `out`.
+///
+/// # Returns
+/// * `CliResult` - Success, or the error to report (unknown commands, bad
+///   arguments and failed library calls alike)
+pub fn run(args: impl IntoIterator<Item = String>, out: &mut dyn Write) -> CliResult {
+    let mut args = args.into_iter();
+    let Some(command) = args.next() else {
+        return Err(USAGE.into());
+    };
+    let args = Args::parse(args)?;
+
+    match command.as_str() {
+        "propose" => propose(&args),
+        "inspect" => inspect(&args, out),
+        "sighash" => sighash(&args, out),
+        "append-sig" => append_sig(&args),
+        "prove" => prove(&args),
+        "combine" => combine(&args),
+        "extract" => extract(&args, out),
+        "broadcast" => broadcast(&args, out),
+        "help" | "--help" | "-h" => Ok(writeln!(out, "{}", USAGE)?),
+        _ => Err(format!("Unknown command: {}\n\n{}", command, USAGE).into()),
+    }
+}
+
+fn read_pczt(path: &str) -> Result<pczt::Pczt, Box<dyn Error>> {
+    crate::load_pczt(path).map_err(|e| format!("Failed to load {}: {}", path, e).into())
+}
+
+fn write_pczt(path: &str, pczt: &pczt::Pczt) -> CliResult {
+    crate::save_pczt(path, pczt).map_err(|e| format!("Failed to save {}: {}", path, e))?;
+    Ok(())
+}
+
+fn parse_index(arg: &str) -> Result<usize, String> {
+    arg.parse().map_err(|_| format!("Invalid input index: {}", arg))
+}
+
+fn propose(args: &Args) -> CliResult {
+    let [inputs_path, request_path] = args.exactly(2)? else { unreachable!() };
+    let inputs = crate::json::inputs_from_json(&std::fs::read_to_string(inputs_path)?)?;
+    let request = crate::json::request_from_json(&std::fs::read_to_string(request_path)?)?;
+
+    let inputs = crate::types::serialize_transparent_inputs(&inputs)?;
+    let pczt = crate::propose_transaction(&inputs, request, args.option("change").map(str::to_string))?;
+    write_pczt(args.required_option("out")?, &pczt)
+}
+
+fn inspect(args: &Args, out: &mut dyn Write) -> CliResult {
+    let [path] = args.exactly(1)? else { unreachable!() };
+    let pczt = read_pczt(path)?;
+    writeln!(out, "{}", crate::json::summary_to_json(&crate::summarize_pczt(&pczt)))?;
+    Ok(())
+}
+
+fn sighash(args: &Args, out: &mut dyn Write) -> CliResult {
+    let [path, index] = args.exactly(2)? else { unreachable!() };
+    let sighash = crate::get_sighash(&read_pczt(path)?, parse_index(index)?)?;
+    writeln!(out, "{}", hex::encode(sighash.as_bytes()))?;
+    Ok(())
+}
+
+fn append_sig(args: &Args) -> CliResult {
+    let [path, index, signature] = args.exactly(3)? else { unreachable!() };
+    let signature: [u8; 64] = hex::decode(signature)?
+        .try_into()
+        .map_err(|_| "Signature must be 64 bytes")?;
+    let pczt = crate::append_signature(read_pczt(path)?, parse_index(index)?, signature)?;
+    write_pczt(args.required_option("out")?, &pczt)
+}
+
+fn prove(args: &Args) -> CliResult {
+    let [path] = args.exactly(1)? else { unreachable!() };
+    let pczt = crate::prove_transaction(read_pczt(path)?)?;
+    write_pczt(args.required_option("out")?, &pczt)
+}
+
+fn combine(args: &Args) -> CliResult {
+    if args.positional.is_empty() {
+        return Err("Expected at least one PCZT".into());
+    }
+    let pczts = args.positional.iter().map(|path| read_pczt(path)).collect::<Result<Vec<_>, _>>()?;
+    write_pczt(args.required_option("out")?, &crate::combine(pczts)?)
+}
+
+fn extract(args: &Args, out: &mut dyn Write) -> CliResult {
+    let [path] = args.exactly(1)? else { unreachable!() };
+    let tx_hex = hex::encode(crate::finalize_and_extract(read_pczt(path)?)?);
+    match args.option("out") {
+        Some(tx_path) => std::fs::write(tx_path, format!("{}\n", tx_hex))
+            .map_err(|e| format!("Failed to write {}: {}", tx_path, e))?,
+        None => writeln!(out, "{}", tx_hex)?,
+    }
+    Ok(())
+}
+
+/// Reads a transaction file written by `extract`
+fn read_transaction(path: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let tx_hex = std::fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path, e))?;
+    hex::decode(tx_hex.trim()).map_err(|e| format!("{} is not a hex transaction: {}", path, e).into())
+}
+
+fn broadcast(args: &Args, out: &mut dyn Write) -> CliResult {
+    let [path] = args.exactly(1)? else { unreachable!() };
+    let tx_bytes = read_transaction(path)?;
+    // The network only matters for address lookups, which broadcasting doesn't do
+    let server = LightwalletdUtxoChecker::connect(args.required_option("server")?, NetworkType::Main)?;
+    server.send_transaction(&tx_bytes)?;
+    writeln!(out, "Broadcast {} bytes", tx_bytes.len())?;
+    Ok(())
+}
@@ -0,0 +1,169 @@
+//! Collecting signatures from several parties
+//!
+//! When the transparent inputs of a PCZT belong to different keys (e.g. a treasury
+//! whose UTXOs are spread across departments), a `CoSigningCoordinator` hands each
+//! signer a packet with the sighashes of only their inputs, checks and collects the
+//! returned signatures, and emits the combined PCZT once every input is signed.
+//!
+//! ```ignore
+//! let mut coordinator = CoSigningCoordinator::new(pczt, &[alice, bob])?;
+//! for packet in coordinator.packets() {
+//!     // send packet.batch.to_bytes()? to packet.pubkey's signer ...
+//! }
+//! coordinator.add_signatures(&alice, &alice_signatures)?;
+//! coordinator.add_signed_pczt(bob_pczt)?;
+//! let signed = coordinator.finish()?;
+//! ```
+
+use pczt::Pczt;
+
+use crate::error::CoSigningError;
+use crate::types::SighashBatch;
+
+/// The inputs one signer must sign
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningPacket {
+    /// Compressed public key of the signer
+    pub pubkey: [u8; 33],
+    /// Indices of the signer's inputs in the PCZT, in the order of `batch.inputs`
+    pub input_indices: Vec<usize>,
+    /// Transaction context and sighashes for the signer's inputs only
+    pub batch: SighashBatch,
+}
+
+/// Tracks signatures for a PCZT whose inputs are signed by several parties
+pub struct CoSigningCoordinator {
+    pczt: Pczt,
+    signers: Vec<[u8; 33]>,
+    batch: SighashBatch,
+}
+
+impl CoSigningCoordinator {
+    /// Creates a coordinator for `pczt`, which must be signed by exactly `signers`.
+    ///
+    /// Fails if an input belongs to a key outside `signers`, or if a signer owns no input.
+    pub fn new(pczt: Pczt, signers: &[[u8; 33]]) -> Result<Self, CoSigningError> {
+        let batch = crate::export_sighash_batch(&pczt)?;
+
+        if let Some(index) = batch.inputs.iter().position(|input| !signers.contains(&input.pubkey)) {
+            return Err(CoSigningError::UnexpectedSigner { index });
+        }
+        if let Some(pubkey) = signers.iter().find(|pubkey| !batch.inputs.iter().any(|input| input.pubkey == **pubkey)) {
+            return Err(CoSigningError::SignerWithoutInputs(crate::hex_encode(pubkey)));
+        }
+
+        let mut unique_signers = Vec::with_capacity(signers.len());
+        for pubkey in signers {
+            if !unique_signers.contains(pubkey) {
+                unique_signers.push(*pubkey);
+            }
+        }
+
+        Ok(CoSigningCoordinator { pczt, signers: unique_signers, batch })
+    }
+
+    /// The expected signers, in the order given to `new`
+    pub fn signers(&self) -> &[[u8; 33]] {
+        &self.signers
+    }
+
+    /// The PCZT with every signature collected so far
+    pub fn pczt(&self) -> &Pczt {
+        &self.pczt
+    }
+
+    /// Builds the packet for one signer.
+    pub fn packet(&self, pubkey: &[u8; 33]) -> Result<SigningPacket, CoSigningError> {
+        if !self.signers.contains(pubkey) {
+            return Err(CoSigningError::UnknownSigner(crate::hex_encode(pubkey)));
+        }
+
+        let (input_indices, inputs): (Vec<usize>, Vec<_>) = self.batch.inputs.iter()
+            .enumerate()
+            .filter(|(_, input)| input.pubkey == *pubkey)
+            .map(|(index, input)| (index, input.clone()))
+            .unzip();
+
+        Ok(SigningPacket {
+            pubkey: *pubkey,
+            input_indices,
+            batch: SighashBatch { inputs, ..self.batch.clone() },
+        })
+    }
+
+    /// Builds one packet per signer, in the order of `signers()`.
+    pub fn packets(&self) -> Vec<SigningPacket> {
+        self.signers.iter()
+            .map(|pubkey| self.packet(pubkey).expect("signers are known"))
+            .collect()
+    }
+
+    /// Adds `signer`'s signatures as `(input_index, compact signature)` pairs.
+    ///
+    /// Each signature is verified against its input; if any fails, none are added.
+    pub fn add_signatures(
+        &mut self,
+        signer: &[u8; 33],
+        signatures: &[(usize, [u8; 64])],
+    ) -> Result<(), CoSigningError> {
+        if !self.signers.contains(signer) {
+            return Err(CoSigningError::UnknownSigner(crate::hex_encode(signer)));
+        }
+
+        let mut pczt = self.pczt.clone();
+        for (index, signature) in signatures {
+            let input = self.batch.inputs.get(*index)
+                .ok_or(crate::error::SignatureError::InvalidInputIndex(*index))?;
+            if input.pubkey != *signer {
+                return Err(CoSigningError::WrongSigner { index: *index });
+            }
+            pczt = crate::append_signature(pczt, *index, *signature)?;
+        }
+
+        self.pczt = pczt;
+        Ok(())
+    }
+
+    /// Merges a copy of the PCZT that a signer signed directly (e.g. with the Signer role).
+    pub fn add_signed_pczt(&mut self, signed: Pczt) -> Result<(), CoSigningError> {
+        if crate::pczt_fingerprint(&signed) != self.batch.fingerprint {
+            return Err(CoSigningError::TransactionMismatch);
+        }
+        self.pczt = crate::combine(vec![self.pczt.clone(), signed])?;
+        Ok(())
+    }
+
+    /// Indices of the inputs that are still unsigned
+    pub fn missing_inputs(&self) -> Vec<usize> {
+        self.pczt.transparent().inputs().iter()
+            .zip(&self.batch.inputs)
+            .enumerate()
+            .filter(|(_, (input, expected))| {
+                input.script_sig().is_none() && !input.partial_signatures().contains_key(&expected.pubkey)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Signers that still owe at least one signature
+    pub fn pending_signers(&self) -> Vec<[u8; 33]> {
+        let missing = self.missing_inputs();
+        self.signers.iter()
+            .filter(|pubkey| missing.iter().any(|index| self.batch.inputs[*index].pubkey == **pubkey))
+            .copied()
+            .collect()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.missing_inputs().is_empty()
+    }
+
+    /// Returns the combined PCZT, or `Incomplete` listing the unsigned inputs.
+    pub fn finish(self) -> Result<Pczt, CoSigningError> {
+        let missing = self.missing_inputs();
+        if !missing.is_empty() {
+            return Err(CoSigningError::Incomplete { missing });
+        }
+        Ok(self.pczt)
+    }
+}
@@ -1,5 +1,469 @@
 use thiserror::Error;
 
+/// Stable, machine-readable error codes shared by every binding layer
+///
+/// Each error variant maps to exactly one code. The string form (e.g. `"INSUFFICIENT_FUNDS"`)
+/// is what hosts should match on; it will not change between releases. Each code also has
+/// a stable number (see `number`) for hosts that look up localized messages in tables;
+/// new codes take the next free number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ErrorCode {
+    NoInputs = 1,
+    InvalidRequest = 2,
+    InvalidAddress = 3,
+    InsufficientFunds = 4,
+    FeeCalculation = 5,
+    ExcessiveFee = 6,
+    FeeCapExceeded = 7,
+    DustPayment = 8,
+    DustChange = 9,
+    InsufficientConfirmations = 10,
+    ChainTipUnavailable = 11,
+    InputScriptMismatch = 12,
+    UnsupportedScriptType = 13,
+    StrictModeViolation = 14,
+    PcztCreation = 15,
+    MemoTooLong = 16,
+    InvalidMemo = 17,
+    NoOrchardOutputs = 18,
+    ProvingKeyUnavailable = 19,
+    ProofGenerationFailed = 20,
+    OrchardProofFailed = 21,
+    ProverServiceStopped = 22,
+    ProverQueueFull = 23,
+    UnknownProofJob = 24,
+    RemoteProverFailed = 25,
+    RemoteProverUnauthorized = 26,
+    ProverNotBuilt = 27,
+    RequestMismatch = 28,
+    ChangeMismatch = 29,
+    InvalidFee = 30,
+    OutputMismatch = 31,
+    ExtractionMismatch = 32,
+    ReviewDrift = 33,
+    ChangeOwnershipInvalid = 34,
+    InvalidPreimage = 35,
+    OrchardFlagsMismatch = 36,
+    InvalidInputIndex = 37,
+    InvalidOutputIndex = 38,
+    MissingInputPubkey = 39,
+    MissingInputData = 40,
+    SighashCalculationFailed = 41,
+    SignatureVerificationFailed = 42,
+    InvalidSignatureFormat = 43,
+    MissingPublicKey = 44,
+    InvalidSecretKey = 45,
+    SigningKeyMismatch = 46,
+    NoPczts = 47,
+    PcztDataMismatch = 48,
+    IncompatiblePczts = 49,
+    CombinationFailed = 50,
+    UnexpectedSigner = 51,
+    UnknownSigner = 52,
+    SignerWithoutInputs = 53,
+    WrongSigner = 54,
+    SigningTransactionMismatch = 55,
+    SigningIncomplete = 56,
+    InvalidUpdate = 57,
+    UpdateFailed = 58,
+    ExternalSignerFailed = 59,
+    InputsSpent = 60,
+    UtxoCheckFailed = 61,
+    ReservationMissing = 62,
+    ReservationStoreFailed = 63,
+    MissingSignatures = 64,
+    MissingProofs = 65,
+    SpendFinalizationFailed = 66,
+    InputMissingSignature = 67,
+    InputPubkeyMismatch = 68,
+    UnsupportedInputScript = 69,
+    TransactionExtractionFailed = 70,
+    SerializationFailed = 71,
+    FinalVerificationFailed = 72,
+    ExtractionFailed = 73,
+    InvalidFormat = 74,
+    UnsupportedVersion = 75,
+    CorruptedData = 76,
+    NonCanonical = 77,
+    UnknownField = 78,
+    NullPointer = 79,
+    InvalidUtf8 = 80,
+    BufferTooSmall = 81,
+    NotImplemented = 82,
+    FileIo = 83,
+    NotAPcztFile = 84,
+    ManifestSignatureInvalid = 85,
+    ManifestMismatch = 86,
+}
+
+impl ErrorCode {
+    /// All error codes
+    pub const ALL: &'static [ErrorCode] = &[
+        ErrorCode::NoInputs,
+        ErrorCode::InvalidRequest,
+        ErrorCode::InvalidAddress,
+        ErrorCode::InsufficientFunds,
+        ErrorCode::FeeCalculation,
+        ErrorCode::ExcessiveFee,
+        ErrorCode::FeeCapExceeded,
+        ErrorCode::DustPayment,
+        ErrorCode::DustChange,
+        ErrorCode::InsufficientConfirmations,
+        ErrorCode::ChainTipUnavailable,
+        ErrorCode::InputScriptMismatch,
+        ErrorCode::UnsupportedScriptType,
+        ErrorCode::StrictModeViolation,
+        ErrorCode::PcztCreation,
+        ErrorCode::MemoTooLong,
+        ErrorCode::InvalidMemo,
+        ErrorCode::NoOrchardOutputs,
+        ErrorCode::ProvingKeyUnavailable,
+        ErrorCode::ProofGenerationFailed,
+        ErrorCode::OrchardProofFailed,
+        ErrorCode::ProverServiceStopped,
+        ErrorCode::ProverQueueFull,
+        ErrorCode::UnknownProofJob,
+        ErrorCode::RemoteProverFailed,
+        ErrorCode::RemoteProverUnauthorized,
+        ErrorCode::ProverNotBuilt,
+        ErrorCode::RequestMismatch,
+        ErrorCode::ChangeMismatch,
+        ErrorCode::InvalidFee,
+        ErrorCode::OutputMismatch,
+        ErrorCode::ExtractionMismatch,
+        ErrorCode::ReviewDrift,
+        ErrorCode::ChangeOwnershipInvalid,
+        ErrorCode::InvalidPreimage,
+        ErrorCode::OrchardFlagsMismatch,
+        ErrorCode::InvalidInputIndex,
+        ErrorCode::InvalidOutputIndex,
+        ErrorCode::MissingInputPubkey,
+        ErrorCode::MissingInputData,
+        ErrorCode::SighashCalculationFailed,
+        ErrorCode::SignatureVerificationFailed,
+        ErrorCode::InvalidSignatureFormat,
+        ErrorCode::MissingPublicKey,
+        ErrorCode::InvalidSecretKey,
+        ErrorCode::SigningKeyMismatch,
+        ErrorCode::NoPczts,
+        ErrorCode::PcztDataMismatch,
+        ErrorCode::IncompatiblePczts,
+        ErrorCode::CombinationFailed,
+        ErrorCode::UnexpectedSigner,
+        ErrorCode::UnknownSigner,
+        ErrorCode::SignerWithoutInputs,
+        ErrorCode::WrongSigner,
+        ErrorCode::SigningTransactionMismatch,
+        ErrorCode::SigningIncomplete,
+        ErrorCode::InvalidUpdate,
+        ErrorCode::UpdateFailed,
+        ErrorCode::ExternalSignerFailed,
+        ErrorCode::InputsSpent,
+        ErrorCode::UtxoCheckFailed,
+        ErrorCode::ReservationMissing,
+        ErrorCode::ReservationStoreFailed,
+        ErrorCode::MissingSignatures,
+        ErrorCode::MissingProofs,
+        ErrorCode::SpendFinalizationFailed,
+        ErrorCode::InputMissingSignature,
+        ErrorCode::InputPubkeyMismatch,
+        ErrorCode::UnsupportedInputScript,
+        ErrorCode::TransactionExtractionFailed,
+        ErrorCode::SerializationFailed,
+        ErrorCode::FinalVerificationFailed,
+        ErrorCode::ExtractionFailed,
+        ErrorCode::InvalidFormat,
+        ErrorCode::UnsupportedVersion,
+        ErrorCode::CorruptedData,
+        ErrorCode::NonCanonical,
+        ErrorCode::UnknownField,
+        ErrorCode::NullPointer,
+        ErrorCode::InvalidUtf8,
+        ErrorCode::BufferTooSmall,
+        ErrorCode::NotImplemented,
+        ErrorCode::FileIo,
+        ErrorCode::NotAPcztFile,
+        ErrorCode::ManifestSignatureInvalid,
+        ErrorCode::ManifestMismatch,
+    ];
+
+    /// Returns the stable number of this code
+    pub fn number(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Returns the stable string form of this code
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::NoInputs => "NO_INPUTS",
+            ErrorCode::InvalidRequest => "INVALID_REQUEST",
+            ErrorCode::InvalidAddress => "INVALID_ADDRESS",
+            ErrorCode::InsufficientFunds => "INSUFFICIENT_FUNDS",
+            ErrorCode::FeeCalculation => "FEE_CALCULATION",
+            ErrorCode::ExcessiveFee => "EXCESSIVE_FEE",
+            ErrorCode::FeeCapExceeded => "FEE_CAP_EXCEEDED",
+            ErrorCode::DustPayment => "DUST_PAYMENT",
+            ErrorCode::DustChange => "DUST_CHANGE",
+            ErrorCode::InsufficientConfirmations => "INSUFFICIENT_CONFIRMATIONS",
+            ErrorCode::ChainTipUnavailable => "CHAIN_TIP_UNAVAILABLE",
+            ErrorCode::InputScriptMismatch => "INPUT_SCRIPT_MISMATCH",
+            ErrorCode::UnsupportedScriptType => "UNSUPPORTED_SCRIPT_TYPE",
+            ErrorCode::StrictModeViolation => "STRICT_MODE_VIOLATION",
+            ErrorCode::PcztCreation => "PCZT_CREATION",
+            ErrorCode::MemoTooLong => "MEMO_TOO_LONG",
+            ErrorCode::InvalidMemo => "INVALID_MEMO",
+            ErrorCode::NoOrchardOutputs => "NO_ORCHARD_OUTPUTS",
+            ErrorCode::ProvingKeyUnavailable => "PROVING_KEY_UNAVAILABLE",
+            ErrorCode::ProofGenerationFailed => "PROOF_GENERATION_FAILED",
+            ErrorCode::OrchardProofFailed => "ORCHARD_PROOF_FAILED",
+            ErrorCode::ProverServiceStopped => "PROVER_SERVICE_STOPPED",
+            ErrorCode::ProverQueueFull => "PROVER_QUEUE_FULL",
+            ErrorCode::UnknownProofJob => "UNKNOWN_PROOF_JOB",
+            ErrorCode::RemoteProverFailed => "REMOTE_PROVER_FAILED",
+            ErrorCode::RemoteProverUnauthorized => "REMOTE_PROVER_UNAUTHORIZED",
+            ErrorCode::ProverNotBuilt => "PROVER_NOT_BUILT",
+            ErrorCode::RequestMismatch => "REQUEST_MISMATCH",
+            ErrorCode::ChangeMismatch => "CHANGE_MISMATCH",
+            ErrorCode::InvalidFee => "INVALID_FEE",
+            ErrorCode::OutputMismatch => "OUTPUT_MISMATCH",
+            ErrorCode::ExtractionMismatch => "EXTRACTION_MISMATCH",
+            ErrorCode::ReviewDrift => "REVIEW_DRIFT",
+            ErrorCode::ChangeOwnershipInvalid => "CHANGE_OWNERSHIP_INVALID",
+            ErrorCode::InvalidPreimage => "INVALID_PREIMAGE",
+            ErrorCode::OrchardFlagsMismatch => "ORCHARD_FLAGS_MISMATCH",
+            ErrorCode::InvalidInputIndex => "INVALID_INPUT_INDEX",
+            ErrorCode::InvalidOutputIndex => "INVALID_OUTPUT_INDEX",
+            ErrorCode::MissingInputPubkey => "MISSING_INPUT_PUBKEY",
+            ErrorCode::MissingInputData => "MISSING_INPUT_DATA",
+            ErrorCode::SighashCalculationFailed => "SIGHASH_CALCULATION_FAILED",
+            ErrorCode::SignatureVerificationFailed => "SIGNATURE_VERIFICATION_FAILED",
+            ErrorCode::InvalidSignatureFormat => "INVALID_SIGNATURE_FORMAT",
+            ErrorCode::MissingPublicKey => "MISSING_PUBLIC_KEY",
+            ErrorCode::InvalidSecretKey => "INVALID_SECRET_KEY",
+            ErrorCode::SigningKeyMismatch => "SIGNING_KEY_MISMATCH",
+            ErrorCode::NoPczts => "NO_PCZTS",
+            ErrorCode::PcztDataMismatch => "PCZT_DATA_MISMATCH",
+            ErrorCode::IncompatiblePczts => "INCOMPATIBLE_PCZTS",
+            ErrorCode::CombinationFailed => "COMBINATION_FAILED",
+            ErrorCode::UnexpectedSigner => "UNEXPECTED_SIGNER",
+            ErrorCode::UnknownSigner => "UNKNOWN_SIGNER",
+            ErrorCode::SignerWithoutInputs => "SIGNER_WITHOUT_INPUTS",
+            ErrorCode::WrongSigner => "WRONG_SIGNER",
+            ErrorCode::SigningTransactionMismatch => "SIGNING_TRANSACTION_MISMATCH",
+            ErrorCode::SigningIncomplete => "SIGNING_INCOMPLETE",
+            ErrorCode::InvalidUpdate => "INVALID_UPDATE",
+            ErrorCode::UpdateFailed => "UPDATE_FAILED",
+            ErrorCode::ExternalSignerFailed => "EXTERNAL_SIGNER_FAILED",
+            ErrorCode::InputsSpent => "INPUTS_SPENT",
+            ErrorCode::UtxoCheckFailed => "UTXO_CHECK_FAILED",
+            ErrorCode::ReservationMissing => "RESERVATION_MISSING",
+            ErrorCode::ReservationStoreFailed => "RESERVATION_STORE_FAILED",
+            ErrorCode::MissingSignatures => "MISSING_SIGNATURES",
+            ErrorCode::MissingProofs => "MISSING_PROOFS",
+            ErrorCode::SpendFinalizationFailed => "SPEND_FINALIZATION_FAILED",
+            ErrorCode::InputMissingSignature => "INPUT_MISSING_SIGNATURE",
+            ErrorCode::InputPubkeyMismatch => "INPUT_PUBKEY_MISMATCH",
+            ErrorCode::UnsupportedInputScript => "UNSUPPORTED_INPUT_SCRIPT",
+            ErrorCode::TransactionExtractionFailed => "TRANSACTION_EXTRACTION_FAILED",
+            ErrorCode::SerializationFailed => "SERIALIZATION_FAILED",
+            ErrorCode::FinalVerificationFailed => "FINAL_VERIFICATION_FAILED",
+            ErrorCode::ExtractionFailed => "EXTRACTION_FAILED",
+            ErrorCode::InvalidFormat => "INVALID_FORMAT",
+            ErrorCode::UnsupportedVersion => "UNSUPPORTED_VERSION",
+            ErrorCode::CorruptedData => "CORRUPTED_DATA",
+            ErrorCode::NonCanonical => "NON_CANONICAL",
+            ErrorCode::UnknownField => "UNKNOWN_FIELD",
+            ErrorCode::NullPointer => "NULL_POINTER",
+            ErrorCode::InvalidUtf8 => "INVALID_UTF8",
+            ErrorCode::BufferTooSmall => "BUFFER_TOO_SMALL",
+            ErrorCode::NotImplemented => "NOT_IMPLEMENTED",
+            ErrorCode::FileIo => "FILE_IO",
+            ErrorCode::NotAPcztFile => "NOT_A_PCZT_FILE",
+            ErrorCode::ManifestSignatureInvalid => "MANIFEST_SIGNATURE_INVALID",
+            ErrorCode::ManifestMismatch => "MANIFEST_MISMATCH",
+        }
+    }
+
+    /// Returns the domain this code belongs to
+    pub fn domain(&self) -> ErrorDomain {
+        match self {
+            ErrorCode::NoInputs
+            | ErrorCode::InvalidRequest
+            | ErrorCode::InvalidAddress
+            | ErrorCode::InsufficientFunds
+            | ErrorCode::FeeCalculation
+            | ErrorCode::InputScriptMismatch
+            | ErrorCode::UnsupportedScriptType
+            | ErrorCode::PcztCreation
+            | ErrorCode::NoOrchardOutputs => ErrorDomain::Proposal,
+            ErrorCode::ExcessiveFee
+            | ErrorCode::FeeCapExceeded
+            | ErrorCode::DustPayment
+            | ErrorCode::DustChange
+            | ErrorCode::InsufficientConfirmations
+            | ErrorCode::StrictModeViolation => ErrorDomain::Policy,
+            ErrorCode::MemoTooLong | ErrorCode::InvalidMemo => ErrorDomain::Memo,
+            ErrorCode::ChainTipUnavailable
+            | ErrorCode::RemoteProverFailed
+            | ErrorCode::RemoteProverUnauthorized
+            | ErrorCode::InputsSpent
+            | ErrorCode::UtxoCheckFailed => ErrorDomain::Network,
+            ErrorCode::ProvingKeyUnavailable
+            | ErrorCode::ProofGenerationFailed
+            | ErrorCode::OrchardProofFailed
+            | ErrorCode::ProverServiceStopped
+            | ErrorCode::ProverQueueFull
+            | ErrorCode::UnknownProofJob
+            | ErrorCode::ProverNotBuilt => ErrorDomain::Prover,
+            ErrorCode::RequestMismatch
+            | ErrorCode::ChangeMismatch
+            | ErrorCode::InvalidFee
+            | ErrorCode::OutputMismatch
+            | ErrorCode::ExtractionMismatch
+            | ErrorCode::ReviewDrift
+            | ErrorCode::ChangeOwnershipInvalid
+            | ErrorCode::InvalidPreimage
+            | ErrorCode::OrchardFlagsMismatch
+            | ErrorCode::ManifestSignatureInvalid
+            | ErrorCode::ManifestMismatch => ErrorDomain::Verification,
+            ErrorCode::InvalidInputIndex
+            | ErrorCode::InvalidOutputIndex
+            | ErrorCode::MissingInputPubkey
+            | ErrorCode::MissingInputData
+            | ErrorCode::SighashCalculationFailed
+            | ErrorCode::SignatureVerificationFailed
+            | ErrorCode::InvalidSignatureFormat
+            | ErrorCode::MissingPublicKey
+            | ErrorCode::InvalidSecretKey
+            | ErrorCode::SigningKeyMismatch
+            | ErrorCode::UnexpectedSigner
+            | ErrorCode::UnknownSigner
+            | ErrorCode::SignerWithoutInputs
+            | ErrorCode::WrongSigner
+            | ErrorCode::SigningTransactionMismatch
+            | ErrorCode::SigningIncomplete
+            | ErrorCode::ExternalSignerFailed => ErrorDomain::Signing,
+            ErrorCode::NoPczts
+            | ErrorCode::PcztDataMismatch
+            | ErrorCode::IncompatiblePczts
+            | ErrorCode::CombinationFailed => ErrorDomain::Combine,
+            ErrorCode::InvalidUpdate | ErrorCode::UpdateFailed => ErrorDomain::Update,
+            ErrorCode::ReservationMissing | ErrorCode::ReservationStoreFailed => ErrorDomain::Reservation,
+            ErrorCode::MissingSignatures
+            | ErrorCode::MissingProofs
+            | ErrorCode::SpendFinalizationFailed
+            | ErrorCode::InputMissingSignature
+            | ErrorCode::InputPubkeyMismatch
+            | ErrorCode::UnsupportedInputScript
+            | ErrorCode::TransactionExtractionFailed
+            | ErrorCode::SerializationFailed
+            | ErrorCode::FinalVerificationFailed
+            | ErrorCode::ExtractionFailed => ErrorDomain::Finalization,
+            ErrorCode::InvalidFormat
+            | ErrorCode::UnsupportedVersion
+            | ErrorCode::CorruptedData
+            | ErrorCode::NonCanonical
+            | ErrorCode::UnknownField => ErrorDomain::Parse,
+            ErrorCode::FileIo | ErrorCode::NotAPcztFile => ErrorDomain::File,
+            ErrorCode::NullPointer
+            | ErrorCode::InvalidUtf8
+            | ErrorCode::BufferTooSmall
+            | ErrorCode::NotImplemented => ErrorDomain::Interface,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Version of the `ErrorDomain` namespace, raised when a domain is added
+pub const ERROR_DOMAIN_VERSION: u32 = 1;
+
+/// The subsystem an `ErrorCode` belongs to
+///
+/// Domains group codes by where they come from, independently of the C `ResultCode`
+/// a call returns, so a new subsystem gets its own domain instead of a new result
+/// code. Numbers are stable; 0 is reserved for "no error" and new domains take the
+/// next free number, bumping `ERROR_DOMAIN_VERSION`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ErrorDomain {
+    /// Building a proposal from inputs and a request
+    Proposal = 1,
+    /// Limits the request or the crate's settings impose (fee caps, dust, strict mode)
+    Policy = 2,
+    Memo = 3,
+    /// Talking to lightwalletd or a remote prover
+    Network = 4,
+    Prover = 5,
+    Verification = 6,
+    /// Sighashes, signatures and signer coordination
+    Signing = 7,
+    Combine = 8,
+    Update = 9,
+    Reservation = 10,
+    Finalization = 11,
+    Parse = 12,
+    File = 13,
+    /// Misuse of the C API (null pointers, invalid UTF-8, small buffers)
+    Interface = 14,
+}
+
+impl ErrorDomain {
+    /// All error domains
+    pub const ALL: &'static [ErrorDomain] = &[
+        ErrorDomain::Proposal,
+        ErrorDomain::Policy,
+        ErrorDomain::Memo,
+        ErrorDomain::Network,
+        ErrorDomain::Prover,
+        ErrorDomain::Verification,
+        ErrorDomain::Signing,
+        ErrorDomain::Combine,
+        ErrorDomain::Update,
+        ErrorDomain::Reservation,
+        ErrorDomain::Finalization,
+        ErrorDomain::Parse,
+        ErrorDomain::File,
+        ErrorDomain::Interface,
+    ];
+
+    /// Returns the stable number of this domain
+    pub fn number(&self) -> u32 {
+        *self as u32
+    }
+
+    /// Returns the stable string form of this domain
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorDomain::Proposal => "PROPOSAL",
+            ErrorDomain::Policy => "POLICY",
+            ErrorDomain::Memo => "MEMO",
+            ErrorDomain::Network => "NETWORK",
+            ErrorDomain::Prover => "PROVER",
+            ErrorDomain::Verification => "VERIFICATION",
+            ErrorDomain::Signing => "SIGNING",
+            ErrorDomain::Combine => "COMBINE",
+            ErrorDomain::Update => "UPDATE",
+            ErrorDomain::Reservation => "RESERVATION",
+            ErrorDomain::Finalization => "FINALIZATION",
+            ErrorDomain::Parse => "PARSE",
+            ErrorDomain::File => "FILE",
+            ErrorDomain::Interface => "INTERFACE",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorDomain {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Errors that can occur during transaction proposal
 #[derive(Error, Debug)]
 pub enum ProposalError {
@@ -12,17 +476,68 @@ pub enum ProposalError {
     #[error("Invalid address: {0}")]
     InvalidAddress(String),
 
-    #[error("Insufficient funds")]
-    InsufficientFunds,
+    #[error("Insufficient funds: {available} zatoshis available, {required} required (including a fee of {fee})")]
+    InsufficientFunds {
+        available: u64,
+        required: u64,
+        fee: u64,
+    },
 
     #[error("Fee calculation error: {0}")]
     FeeCalculation(String),
 
+    #[error("Implicit fee of {implicit_fee} zatoshis exceeds the ZIP-317 fee of {expected_fee} zatoshis by more than {tolerance}")]
+    ExcessiveFee {
+        implicit_fee: u64,
+        expected_fee: u64,
+        tolerance: u64,
+    },
+
+    #[error("Fee of {fee} zatoshis exceeds the request's maximum of {max_fee}")]
+    FeeCapExceeded { fee: u64, max_fee: u64 },
+
+    #[error("Payment {index} of {amount} zatoshis is below the dust threshold of {threshold}")]
+    DustPayment { index: usize, amount: u64, threshold: u64 },
+
+    #[error("Change of {change} zatoshis is below the dust threshold of {threshold}")]
+    DustChange { change: u64, threshold: u64 },
+
     #[error("Not implemented")]
     NotImplemented,
 
     #[error("PCZT creation error: {0}")]
     PcztCreation(String),
+
+    #[error("Inputs {excluded:?} have fewer than {min_confirmations} confirmations (or no known height)")]
+    InsufficientConfirmations {
+        excluded: Vec<usize>,
+        min_confirmations: u32,
+    },
+
+    #[error("Could not fetch the chain tip: {0}")]
+    ChainTipUnavailable(String),
+
+    #[error("Input {index}'s scriptPubKey does not pay to the hash of its pubkey or redeem script")]
+    InputScriptMismatch { index: usize },
+
+    #[error("Input {index} pays to a {kind:?} script, which can't be signed and finalized")]
+    UnsupportedScriptType { index: usize, kind: crate::types::ScriptKind },
+
+    #[error("Memo error: {0}")]
+    Memo(#[from] MemoError),
+
+    #[error("Refused in strict mode: {0}")]
+    StrictMode(String),
+}
+
+/// Errors that can occur when encoding or decoding memos
+#[derive(Error, Debug)]
+pub enum MemoError {
+    #[error("Memo of {len} bytes does not fit in {max} bytes")]
+    TooLong { len: usize, max: usize },
+
+    #[error("Invalid memo: {0}")]
+    InvalidMemo(String),
 }
 
 /// Errors that can occur during proving
@@ -40,6 +555,24 @@ pub enum ProverError {
     #[error("Orchard proof creation failed: {0}")]
     OrchardProof(String),
 
+    #[error("Prover service has stopped")]
+    ServiceStopped,
+
+    #[error("Prover service queue is full")]
+    QueueFull,
+
+    #[error("Unknown proof job: {0}")]
+    UnknownJob(u64),
+
+    #[error("Remote prover failed: {0}")]
+    Remote(String),
+
+    #[error("Remote prover rejected the auth token")]
+    RemoteUnauthorized,
+
+    #[error("Orchard proving is not built into this library (enable the `orchard-prover` feature)")]
+    NotBuilt,
+
     #[error("Not implemented")]
     NotImplemented,
 }
@@ -56,9 +589,33 @@ pub enum VerificationFailure {
     #[error("Invalid fee")]
     InvalidFee,
 
+    #[error("Fee of {actual} zatoshis doesn't follow the request's fee policy, which charges {expected}")]
+    FeePolicyMismatch { expected: u64, actual: u64 },
+
     #[error("Output mismatch: {0}")]
     OutputMismatch(String),
 
+    #[error("Extracted transaction does not match PCZT: {0}")]
+    ExtractionMismatch(String),
+
+    #[error("Review document does not match the proposal: {0}")]
+    ReviewDrift(String),
+
+    #[error("Change ownership proof is invalid: {0}")]
+    ChangeOwnership(String),
+
+    #[error("Input {index} carries a hash160 preimage that does not match its hash")]
+    InvalidPreimage { index: usize },
+
+    #[error("Orchard flags are {actual:#04b}, expected {expected:#04b}")]
+    OrchardFlagsMismatch { expected: u8, actual: u8 },
+
+    #[error("Manifest signature is invalid: {0}")]
+    ManifestSignature(String),
+
+    #[error("Manifest does not match the PCZT: {0}")]
+    ManifestMismatch(String),
+
     #[error("Not implemented")]
     NotImplemented,
 }
@@ -94,10 +651,117 @@ pub enum SignatureError {
     #[error("Missing public key")]
     MissingPublicKey,
 
+    #[error("Invalid secret key")]
+    InvalidSecretKey,
+
+    #[error("Secret key does not sign for input {index}")]
+    KeyMismatch { index: usize },
+
     #[error("Not implemented")]
     NotImplemented,
 }
 
+/// Errors that can occur while coordinating signatures from several signers
+#[derive(Error, Debug)]
+pub enum CoSigningError {
+    #[error("Input {index} belongs to a key that is not an expected signer")]
+    UnexpectedSigner { index: usize },
+
+    #[error("Not an expected signer: {0}")]
+    UnknownSigner(String),
+
+    #[error("Signer {0} owns no inputs")]
+    SignerWithoutInputs(String),
+
+    #[error("Input {index} belongs to a different signer")]
+    WrongSigner { index: usize },
+
+    #[error("Signed PCZT is for a different transaction")]
+    TransactionMismatch,
+
+    #[error("Inputs {missing:?} are not signed yet")]
+    Incomplete { missing: Vec<usize> },
+
+    #[error("Sighash error: {0}")]
+    Sighash(#[from] SighashError),
+
+    #[error("Signature error: {0}")]
+    Signature(#[from] SignatureError),
+
+    #[error("Combine error: {0}")]
+    Combine(#[from] CombineError),
+}
+
+/// Errors that can occur when updating a PCZT
+#[derive(Error, Debug)]
+pub enum UpdateError {
+    #[error("Invalid input index: {0}")]
+    InvalidInputIndex(usize),
+
+    #[error("Invalid output index: {0}")]
+    InvalidOutputIndex(usize),
+
+    #[error("Invalid update: {0}")]
+    InvalidUpdate(String),
+
+    #[error("Update failed: {0}")]
+    Failed(String),
+}
+
+/// Errors from the end-to-end sequences in `flow`, tagged with the failing step
+#[derive(Error, Debug)]
+pub enum FlowError {
+    #[error("Proposal error: {0}")]
+    Proposal(#[from] ProposalError),
+
+    #[error("Verification error: {0}")]
+    Verification(#[from] VerificationFailure),
+
+    #[error("Prover error: {0}")]
+    Prover(#[from] ProverError),
+
+    #[error("Sighash error: {0}")]
+    Sighash(#[from] SighashError),
+
+    #[error("Signature error: {0}")]
+    Signature(#[from] SignatureError),
+
+    #[error("Co-signing error: {0}")]
+    CoSigning(#[from] CoSigningError),
+
+    #[error("Combine error: {0}")]
+    Combine(#[from] CombineError),
+
+    #[error("Finalization error: {0}")]
+    Finalization(#[from] FinalizationError),
+
+    #[error("External signer failed: {0}")]
+    Signer(String),
+}
+
+/// Errors that can occur when re-validating inputs before broadcast
+#[derive(Error, Debug)]
+pub enum RevalidationError {
+    #[error("Inputs {indices:?} are no longer unspent")]
+    InputsSpent { indices: Vec<usize> },
+
+    #[error("Could not check input {index}: {message}")]
+    CheckFailed { index: usize, message: String },
+}
+
+/// Errors from input reservations in `reservation`
+#[derive(Error, Debug)]
+pub enum ReservationError {
+    #[error("PCZT carries no reservation token")]
+    NoToken,
+
+    #[error("Reservation store error: {0}")]
+    Store(String),
+
+    #[error("Update error: {0}")]
+    Update(#[from] UpdateError),
+}
+
 /// Errors that can occur during PCZT combination
 #[derive(Error, Debug)]
 pub enum CombineError {
@@ -129,6 +793,15 @@ pub enum FinalizationError {
     #[error("Spend finalization failed: {0}")]
     SpendFinalization(String),
 
+    #[error("Input {index} is missing a signature")]
+    InputMissingSignature { index: usize },
+
+    #[error("Input {index} is signed by a pubkey that does not match its script_pubkey")]
+    InputPubkeyMismatch { index: usize },
+
+    #[error("Input {index} has an unsupported script_pubkey")]
+    UnsupportedInputScript { index: usize },
+
     #[error("Transaction extraction failed: {0}")]
     TransactionExtraction(String),
 
@@ -145,6 +818,19 @@ pub enum FinalizationError {
     NotImplemented,
 }
 
+/// Errors that can occur when inspecting a PCZT
+#[derive(Error, Debug)]
+pub enum InspectionError {
+    #[error("Invalid input index: {0}")]
+    InvalidInputIndex(usize),
+
+    #[error("Invalid output index: {0}")]
+    InvalidOutputIndex(usize),
+
+    #[error("Input {0} has no valid pubkey preimage")]
+    MissingInputPubkey(usize),
+}
+
 /// Errors that can occur during PCZT parsing
 #[derive(Error, Debug)]
 pub enum ParseError {
@@ -156,6 +842,25 @@ pub enum ParseError {
 
     #[error("Corrupted data")]
     CorruptedData,
+
+    #[error("Non-canonical encoding: re-serializing the PCZT produces different bytes")]
+    NonCanonical,
+
+    #[error("Unrecognized field: {0}")]
+    UnknownField(String),
+}
+
+/// Errors that can occur saving or loading PCZT files
+#[derive(Error, Debug)]
+pub enum FileError {
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("{0} is not a PCZT file (missing magic bytes)")]
+    NotAPczt(String),
+
+    #[error("Parse error: {0}")]
+    Parse(#[from] ParseError),
 }
 
 /// Generic error type for FFI boundary
@@ -194,6 +899,356 @@ pub enum FfiError {
     #[error("Parse error: {0}")]
     Parse(#[from] ParseError),
 
+    #[error("Inspection error: {0}")]
+    Inspection(#[from] InspectionError),
+
+    #[error("Memo error: {0}")]
+    Memo(#[from] MemoError),
+
+    #[error("Reservation error: {0}")]
+    Reservation(#[from] ReservationError),
+
+    #[error("File error: {0}")]
+    File(#[from] FileError),
+
     #[error("Not implemented: {0}")]
     NotImplemented(String),
 }
+
+impl InspectionError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            InspectionError::InvalidInputIndex(_) => ErrorCode::InvalidInputIndex,
+            InspectionError::InvalidOutputIndex(_) => ErrorCode::InvalidOutputIndex,
+            InspectionError::MissingInputPubkey(_) => ErrorCode::MissingInputPubkey,
+        }
+    }
+}
+
+impl FfiError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FfiError::NullPointer => ErrorCode::NullPointer,
+            FfiError::InvalidUtf8 => ErrorCode::InvalidUtf8,
+            FfiError::BufferTooSmall => ErrorCode::BufferTooSmall,
+            FfiError::Proposal(e) => e.code(),
+            FfiError::Prover(e) => e.code(),
+            FfiError::Verification(e) => e.code(),
+            FfiError::Sighash(e) => e.code(),
+            FfiError::Signature(e) => e.code(),
+            FfiError::Combine(e) => e.code(),
+            FfiError::Finalization(e) => e.code(),
+            FfiError::Parse(e) => e.code(),
+            FfiError::Inspection(e) => e.code(),
+            FfiError::Memo(e) => e.code(),
+            FfiError::Reservation(e) => e.code(),
+            FfiError::File(e) => e.code(),
+            FfiError::NotImplemented(_) => ErrorCode::NotImplemented,
+        }
+    }
+
+    /// Returns the index of the transparent input this error refers to, if any
+    pub fn input_index(&self) -> Option<usize> {
+        match self {
+            FfiError::Sighash(SighashError::InvalidInputIndex(index))
+            | FfiError::Signature(SignatureError::InvalidInputIndex(index))
+            | FfiError::Signature(SignatureError::KeyMismatch { index })
+            | FfiError::Inspection(InspectionError::InvalidInputIndex(index))
+            | FfiError::Inspection(InspectionError::MissingInputPubkey(index))
+            | FfiError::Verification(VerificationFailure::InvalidPreimage { index })
+            | FfiError::Finalization(FinalizationError::InputMissingSignature { index })
+            | FfiError::Finalization(FinalizationError::InputPubkeyMismatch { index })
+            | FfiError::Finalization(FinalizationError::UnsupportedInputScript { index })
+            | FfiError::Proposal(ProposalError::InputScriptMismatch { index })
+            | FfiError::Proposal(ProposalError::UnsupportedScriptType { index, .. }) => Some(*index),
+            // Report the first excluded input; the message lists all of them
+            FfiError::Proposal(ProposalError::InsufficientConfirmations { excluded, .. }) => {
+                excluded.first().copied()
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns `(available, required, fee)` if this is an insufficient-funds error
+    pub fn insufficient_funds(&self) -> Option<(u64, u64, u64)> {
+        match self {
+            FfiError::Proposal(ProposalError::InsufficientFunds { available, required, fee }) => {
+                Some((*available, *required, *fee))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the numbers describing this error, for hosts that format their own
+    /// (e.g. localized) messages. Depending on the error code:
+    ///
+    /// * `INSUFFICIENT_FUNDS` - available, required, fee
+    /// * `EXCESSIVE_FEE` - implicit fee, ZIP-317 fee, tolerance
+    /// * `FEE_CAP_EXCEEDED` - fee, maximum fee
+    /// * `DUST_PAYMENT` - payment index, amount, threshold
+    /// * `DUST_CHANGE` - change, threshold
+    /// * `INSUFFICIENT_CONFIRMATIONS` - first excluded input, number of excluded
+    ///   inputs, required confirmations
+    /// * `MEMO_TOO_LONG` - memo length, maximum length
+    /// * `INVALID_FEE` (fee policy mismatch) - expected fee, actual fee
+    /// * `ORCHARD_FLAGS_MISMATCH` - expected flags, actual flags
+    /// * errors about one transparent input - its index (see `input_index`)
+    ///
+    /// Other errors have no context values.
+    pub fn context(&self) -> Vec<u64> {
+        match self {
+            FfiError::Proposal(ProposalError::InsufficientFunds { available, required, fee }) => {
+                vec![*available, *required, *fee]
+            }
+            FfiError::Proposal(ProposalError::ExcessiveFee { implicit_fee, expected_fee, tolerance }) => {
+                vec![*implicit_fee, *expected_fee, *tolerance]
+            }
+            FfiError::Proposal(ProposalError::FeeCapExceeded { fee, max_fee }) => vec![*fee, *max_fee],
+            FfiError::Proposal(ProposalError::DustPayment { index, amount, threshold }) => {
+                vec![*index as u64, *amount, *threshold]
+            }
+            FfiError::Proposal(ProposalError::DustChange { change, threshold }) => vec![*change, *threshold],
+            FfiError::Proposal(ProposalError::InsufficientConfirmations { excluded, min_confirmations }) => {
+                let first = excluded.first().map_or(0, |&index| index as u64);
+                vec![first, excluded.len() as u64, u64::from(*min_confirmations)]
+            }
+            FfiError::Memo(MemoError::TooLong { len, max })
+            | FfiError::Proposal(ProposalError::Memo(MemoError::TooLong { len, max })) => {
+                vec![*len as u64, *max as u64]
+            }
+            FfiError::Verification(VerificationFailure::FeePolicyMismatch { expected, actual }) => {
+                vec![*expected, *actual]
+            }
+            FfiError::Verification(VerificationFailure::OrchardFlagsMismatch { expected, actual }) => {
+                vec![u64::from(*expected), u64::from(*actual)]
+            }
+            _ => self.input_index().map(|index| vec![index as u64]).unwrap_or_default(),
+        }
+    }
+}
+
+impl ProposalError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ProposalError::NoInputs => ErrorCode::NoInputs,
+            ProposalError::InvalidRequest(_) => ErrorCode::InvalidRequest,
+            ProposalError::InvalidAddress(_) => ErrorCode::InvalidAddress,
+            ProposalError::InsufficientFunds { .. } => ErrorCode::InsufficientFunds,
+            ProposalError::FeeCalculation(_) => ErrorCode::FeeCalculation,
+            ProposalError::ExcessiveFee { .. } => ErrorCode::ExcessiveFee,
+            ProposalError::FeeCapExceeded { .. } => ErrorCode::FeeCapExceeded,
+            ProposalError::DustPayment { .. } => ErrorCode::DustPayment,
+            ProposalError::DustChange { .. } => ErrorCode::DustChange,
+            ProposalError::InsufficientConfirmations { .. } => ErrorCode::InsufficientConfirmations,
+            ProposalError::ChainTipUnavailable(_) => ErrorCode::ChainTipUnavailable,
+            ProposalError::InputScriptMismatch { .. } => ErrorCode::InputScriptMismatch,
+            ProposalError::UnsupportedScriptType { .. } => ErrorCode::UnsupportedScriptType,
+            ProposalError::NotImplemented => ErrorCode::NotImplemented,
+            ProposalError::PcztCreation(_) => ErrorCode::PcztCreation,
+            ProposalError::Memo(e) => e.code(),
+            ProposalError::StrictMode(_) => ErrorCode::StrictModeViolation,
+        }
+    }
+}
+
+impl CoSigningError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CoSigningError::UnexpectedSigner { .. } => ErrorCode::UnexpectedSigner,
+            CoSigningError::UnknownSigner(_) => ErrorCode::UnknownSigner,
+            CoSigningError::SignerWithoutInputs(_) => ErrorCode::SignerWithoutInputs,
+            CoSigningError::WrongSigner { .. } => ErrorCode::WrongSigner,
+            CoSigningError::TransactionMismatch => ErrorCode::SigningTransactionMismatch,
+            CoSigningError::Incomplete { .. } => ErrorCode::SigningIncomplete,
+            CoSigningError::Sighash(e) => e.code(),
+            CoSigningError::Signature(e) => e.code(),
+            CoSigningError::Combine(e) => e.code(),
+        }
+    }
+}
+
+impl UpdateError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            UpdateError::InvalidInputIndex(_) => ErrorCode::InvalidInputIndex,
+            UpdateError::InvalidOutputIndex(_) => ErrorCode::InvalidOutputIndex,
+            UpdateError::InvalidUpdate(_) => ErrorCode::InvalidUpdate,
+            UpdateError::Failed(_) => ErrorCode::UpdateFailed,
+        }
+    }
+}
+
+impl FlowError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FlowError::Proposal(e) => e.code(),
+            FlowError::Verification(e) => e.code(),
+            FlowError::Prover(e) => e.code(),
+            FlowError::Sighash(e) => e.code(),
+            FlowError::Signature(e) => e.code(),
+            FlowError::CoSigning(e) => e.code(),
+            FlowError::Combine(e) => e.code(),
+            FlowError::Finalization(e) => e.code(),
+            FlowError::Signer(_) => ErrorCode::ExternalSignerFailed,
+        }
+    }
+}
+
+impl RevalidationError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            RevalidationError::InputsSpent { .. } => ErrorCode::InputsSpent,
+            RevalidationError::CheckFailed { .. } => ErrorCode::UtxoCheckFailed,
+        }
+    }
+}
+
+impl ReservationError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ReservationError::NoToken => ErrorCode::ReservationMissing,
+            ReservationError::Store(_) => ErrorCode::ReservationStoreFailed,
+            ReservationError::Update(e) => e.code(),
+        }
+    }
+}
+
+impl MemoError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            MemoError::TooLong { .. } => ErrorCode::MemoTooLong,
+            MemoError::InvalidMemo(_) => ErrorCode::InvalidMemo,
+        }
+    }
+}
+
+impl ProverError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ProverError::NoOrchardOutputs => ErrorCode::NoOrchardOutputs,
+            ProverError::ProvingKeyUnavailable => ErrorCode::ProvingKeyUnavailable,
+            ProverError::ProofGenerationFailed(_) => ErrorCode::ProofGenerationFailed,
+            ProverError::OrchardProof(_) => ErrorCode::OrchardProofFailed,
+            ProverError::ServiceStopped => ErrorCode::ProverServiceStopped,
+            ProverError::QueueFull => ErrorCode::ProverQueueFull,
+            ProverError::UnknownJob(_) => ErrorCode::UnknownProofJob,
+            ProverError::Remote(_) => ErrorCode::RemoteProverFailed,
+            ProverError::RemoteUnauthorized => ErrorCode::RemoteProverUnauthorized,
+            ProverError::NotBuilt => ErrorCode::ProverNotBuilt,
+            ProverError::NotImplemented => ErrorCode::NotImplemented,
+        }
+    }
+}
+
+impl VerificationFailure {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            VerificationFailure::RequestMismatch => ErrorCode::RequestMismatch,
+            VerificationFailure::ChangeMismatch => ErrorCode::ChangeMismatch,
+            VerificationFailure::InvalidFee | VerificationFailure::FeePolicyMismatch { .. } => ErrorCode::InvalidFee,
+            VerificationFailure::OutputMismatch(_) => ErrorCode::OutputMismatch,
+            VerificationFailure::ExtractionMismatch(_) => ErrorCode::ExtractionMismatch,
+            VerificationFailure::ReviewDrift(_) => ErrorCode::ReviewDrift,
+            VerificationFailure::ChangeOwnership(_) => ErrorCode::ChangeOwnershipInvalid,
+            VerificationFailure::InvalidPreimage { .. } => ErrorCode::InvalidPreimage,
+            VerificationFailure::OrchardFlagsMismatch { .. } => ErrorCode::OrchardFlagsMismatch,
+            VerificationFailure::ManifestSignature(_) => ErrorCode::ManifestSignatureInvalid,
+            VerificationFailure::ManifestMismatch(_) => ErrorCode::ManifestMismatch,
+            VerificationFailure::NotImplemented => ErrorCode::NotImplemented,
+        }
+    }
+}
+
+impl SighashError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SighashError::InvalidInputIndex(_) => ErrorCode::InvalidInputIndex,
+            SighashError::MissingInputData => ErrorCode::MissingInputData,
+            SighashError::CalculationFailed(_) => ErrorCode::SighashCalculationFailed,
+            SighashError::NotImplemented => ErrorCode::NotImplemented,
+        }
+    }
+}
+
+impl SignatureError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            SignatureError::InvalidInputIndex(_) => ErrorCode::InvalidInputIndex,
+            SignatureError::VerificationFailed => ErrorCode::SignatureVerificationFailed,
+            SignatureError::InvalidFormat => ErrorCode::InvalidSignatureFormat,
+            SignatureError::MissingPublicKey => ErrorCode::MissingPublicKey,
+            SignatureError::InvalidSecretKey => ErrorCode::InvalidSecretKey,
+            SignatureError::KeyMismatch { .. } => ErrorCode::SigningKeyMismatch,
+            SignatureError::NotImplemented => ErrorCode::NotImplemented,
+        }
+    }
+}
+
+impl CombineError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            CombineError::NoPczts => ErrorCode::NoPczts,
+            CombineError::DataMismatch => ErrorCode::PcztDataMismatch,
+            CombineError::IncompatiblePczts(_) => ErrorCode::IncompatiblePczts,
+            CombineError::CombinationFailed(_) => ErrorCode::CombinationFailed,
+            CombineError::NotImplemented => ErrorCode::NotImplemented,
+        }
+    }
+}
+
+impl FinalizationError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FinalizationError::MissingSignatures => ErrorCode::MissingSignatures,
+            FinalizationError::MissingProofs => ErrorCode::MissingProofs,
+            FinalizationError::SpendFinalization(_) => ErrorCode::SpendFinalizationFailed,
+            FinalizationError::InputMissingSignature { .. } => ErrorCode::InputMissingSignature,
+            FinalizationError::InputPubkeyMismatch { .. } => ErrorCode::InputPubkeyMismatch,
+            FinalizationError::UnsupportedInputScript { .. } => ErrorCode::UnsupportedInputScript,
+            FinalizationError::TransactionExtraction(_) => ErrorCode::TransactionExtractionFailed,
+            FinalizationError::Serialization(_) => ErrorCode::SerializationFailed,
+            FinalizationError::VerificationFailed(_) => ErrorCode::FinalVerificationFailed,
+            FinalizationError::ExtractionFailed(_) => ErrorCode::ExtractionFailed,
+            FinalizationError::NotImplemented => ErrorCode::NotImplemented,
+        }
+    }
+}
+
+impl ParseError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            ParseError::InvalidFormat(_) => ErrorCode::InvalidFormat,
+            ParseError::UnsupportedVersion => ErrorCode::UnsupportedVersion,
+            ParseError::CorruptedData => ErrorCode::CorruptedData,
+            ParseError::NonCanonical => ErrorCode::NonCanonical,
+            ParseError::UnknownField(_) => ErrorCode::UnknownField,
+        }
+    }
+}
+
+impl FileError {
+    /// Returns the stable error code for this error
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            FileError::Io(_) => ErrorCode::FileIo,
+            FileError::NotAPczt(_) => ErrorCode::NotAPcztFile,
+            FileError::Parse(e) => e.code(),
+        }
+    }
+}
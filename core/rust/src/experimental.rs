@@ -0,0 +1,19 @@
+//! Subsystems whose API may still change between minor releases
+//!
+//! Everything outside this module is the stable surface: breaking changes to it only
+//! come with a new major version. The modules here are newer and still evolving, so
+//! adopters can tell at the import path which calls they may need to revisit on an
+//! upgrade. A module leaves `experimental` once its API is frozen.
+//!
+//! The C API marks the matching functions with a `pczt_experimental_` prefix.
+
+pub mod coin_selection;
+
+#[cfg(feature = "proposal")]
+pub mod batching;
+
+#[cfg(feature = "lightwalletd")]
+pub mod lightwalletd;
+
+#[cfg(feature = "remote-prover")]
+pub mod remote_prover;
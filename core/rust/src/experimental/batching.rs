@@ -0,0 +1,238 @@
+//! Splitting large shielded payouts into several transactions
+//!
+//! An Orchard bundle is proved with a single proof over all of its actions, and the
+//! builder has no knob to split it. Proving time and memory grow with the number of
+//! actions, so a payout to hundreds of unified addresses makes one very slow proof
+//! that can't use more than one prover. `plan_orchard_batches` instead splits the
+//! payments into transactions of at most `max_orchard_actions` actions each, which can
+//! be proved in parallel or on different workers, and reports what that costs in fees.
+
+use crate::error::ProposalError;
+use crate::types::{self, CoinSelection, TransactionRequest, TransparentInput};
+use crate::{
+    change_is_orchard, change_output_size, orchard_bundle_actions, plan_change_in_pool, transparent_input_actions,
+    transparent_payment_output_sizes, ChangeOutput, ShieldedSpends,
+};
+
+/// One transaction of an `OrchardBatchPlan`
+#[derive(Debug, Clone)]
+pub struct OrchardBatch {
+    /// Indices of the inputs it spends, into the serialized inputs that were planned
+    pub input_indices: Vec<usize>,
+    /// Indices of the payments it makes, into the planned request's payments
+    pub payment_indices: Vec<usize>,
+    /// The request to propose it with: the template's settings and its payments
+    pub request: TransactionRequest,
+    /// Orchard actions in its bundle (payments, Orchard change and padding)
+    pub orchard_actions: usize,
+    /// Its ZIP-317 fee in zatoshis
+    pub fee: u64,
+}
+
+/// How `plan_orchard_batches` split a request, and what the split costs
+#[derive(Debug, Clone)]
+pub struct OrchardBatchPlan {
+    /// The transactions, in payment order
+    pub batches: Vec<OrchardBatch>,
+    /// Inputs no batch needed
+    pub unused_input_indices: Vec<usize>,
+    /// Orchard actions of the same payments in a single transaction
+    pub single_transaction_actions: usize,
+    /// Fee of the same payments in a single transaction, taking inputs in order
+    /// until they cover it as each batch does
+    pub single_transaction_fee: u64,
+}
+
+impl OrchardBatchPlan {
+    /// Sum of the batches' fees
+    pub fn total_fee(&self) -> u64 {
+        self.batches.iter().map(|batch| batch.fee).sum()
+    }
+
+    /// What splitting costs over a single transaction (repeated grace actions, change
+    /// outputs and padding)
+    pub fn extra_fee(&self) -> u64 {
+        self.total_fee().saturating_sub(self.single_transaction_fee)
+    }
+
+    /// Actions of the largest proof. With the batches proved in parallel, proving
+    /// time is bounded by this instead of `single_transaction_actions`.
+    pub fn largest_batch_actions(&self) -> usize {
+        self.batches.iter().map(|batch| batch.orchard_actions).max().unwrap_or(0)
+    }
+}
+
+/// Splits a request's payments into transactions of at most `max_orchard_actions`
+/// Orchard actions each.
+///
+/// Payments keep their order; transparent payments ride along in whichever batch they
+/// fall in. Inputs are assigned in order, each batch taking inputs until they cover its
+/// payments and fee, with change going to `change_address` as in `propose_transaction`.
+///
+/// # Arguments
+/// * `inputs_to_spend` - Serialized transparent inputs (see `propose_transaction`)
+/// * `transaction_request` - The payments to split; its coin selection is ignored
+/// * `change_address` - The change address each batch will be proposed with
+/// * `max_orchard_actions` - Largest bundle to prove (at least 2, the fewest actions a
+///   bundle is padded to)
+///
+/// # Returns
+/// * `Result<OrchardBatchPlan, ProposalError>` - The plan, `InsufficientFunds` if the
+///   inputs run out before the last batch is covered, or `InvalidRequest`
+pub fn plan_orchard_batches(
+    inputs_to_spend: &[u8],
+    transaction_request: &TransactionRequest,
+    change_address: Option<&str>,
+    max_orchard_actions: usize,
+) -> Result<OrchardBatchPlan, ProposalError> {
+    if max_orchard_actions < 2 {
+        return Err(ProposalError::InvalidRequest(
+            "Orchard batches need room for at least 2 actions".to_string()
+        ));
+    }
+    if transaction_request.payments.is_empty() {
+        return Err(ProposalError::InvalidRequest("No payments provided".to_string()));
+    }
+
+    let inputs = types::parse_transparent_inputs(inputs_to_spend)
+        .map_err(|e| ProposalError::InvalidRequest(format!("Failed to parse inputs: {}", e)))?;
+    if inputs.is_empty() {
+        return Err(ProposalError::NoInputs);
+    }
+
+    let orchard_change = change_is_orchard(transaction_request, change_address)?;
+    let change = if orchard_change {
+        ChangeOutput::Orchard
+    } else {
+        ChangeOutput::Transparent { size: change_output_size(change_address) }
+    };
+    // Orchard change takes an action of its own in every batch
+    let max_orchard_payments = max_orchard_actions - usize::from(orchard_change);
+
+    let is_unified: Vec<bool> = transaction_request.payments.iter().map(|p| p.is_unified()).collect();
+    let mut payment_groups: Vec<Vec<usize>> = vec![Vec::new()];
+    let mut group_orchard = 0;
+    for (index, &unified) in is_unified.iter().enumerate() {
+        if unified {
+            if group_orchard == max_orchard_payments {
+                payment_groups.push(Vec::new());
+                group_orchard = 0;
+            }
+            group_orchard += 1;
+        }
+        payment_groups.last_mut().expect("starts with a group").push(index);
+    }
+
+    let (_, single_transaction_fee) = take_inputs(transaction_request, &inputs, &mut (0..inputs.len()), change)?;
+    let num_orchard_payments = is_unified.iter().filter(|&&unified| unified).count();
+
+    let mut remaining = 0..inputs.len();
+    let mut batches = Vec::with_capacity(payment_groups.len());
+    for payment_indices in payment_groups {
+        let request = TransactionRequest {
+            payments: payment_indices.iter().map(|&i| transaction_request.payments[i].clone()).collect(),
+            coin_selection: CoinSelection::All,
+            sweep: false,
+            ..transaction_request.clone()
+        };
+        let group_unified: Vec<bool> = payment_indices.iter().map(|&i| is_unified[i]).collect();
+
+        let (input_indices, fee) = take_inputs(&request, &inputs, &mut remaining, change)?;
+
+        let orchard_outputs = group_unified.iter().filter(|&&unified| unified).count();
+        batches.push(OrchardBatch {
+            input_indices,
+            payment_indices,
+            request,
+            orchard_actions: orchard_bundle_actions(0, orchard_outputs + usize::from(orchard_change)),
+            fee,
+        });
+    }
+
+    Ok(OrchardBatchPlan {
+        batches,
+        unused_input_indices: remaining.collect(),
+        single_transaction_actions: orchard_bundle_actions(0, num_orchard_payments + usize::from(orchard_change)),
+        single_transaction_fee,
+    })
+}
+
+/// Takes inputs from `remaining` in order until they cover `request`, returning their
+/// indices and the fee
+fn take_inputs(
+    request: &TransactionRequest,
+    inputs: &[TransparentInput],
+    remaining: &mut std::ops::Range<usize>,
+    change: ChangeOutput,
+) -> Result<(Vec<usize>, u64), ProposalError> {
+    let mut input_indices = Vec::new();
+    loop {
+        let Some(index) = remaining.next() else {
+            // Report the shortfall with every remaining input
+            let taken: Vec<TransparentInput> = input_indices.iter().map(|&i| inputs[i].clone()).collect();
+            return Err(batch_fee(request, &taken, change)
+                .err()
+                .unwrap_or(ProposalError::NoInputs));
+        };
+        input_indices.push(index);
+        let taken: Vec<TransparentInput> = input_indices.iter().map(|&i| inputs[i].clone()).collect();
+        if let Ok(fee) = batch_fee(request, &taken, change) {
+            return Ok((input_indices, fee));
+        }
+    }
+}
+
+/// ZIP-317 fee of proposing `request` from `inputs`, as `propose_transaction` plans it
+fn batch_fee(
+    request: &TransactionRequest,
+    inputs: &[TransparentInput],
+    change: ChangeOutput,
+) -> Result<u64, ProposalError> {
+    let total_input = inputs.iter()
+        .try_fold(0u64, |total, input| total.checked_add(input.amount))
+        .ok_or_else(|| ProposalError::FeeCalculation("Input total overflows".to_string()))?;
+    let num_orchard_outputs = request.payments.iter().filter(|p| p.is_unified()).count();
+
+    let plan = plan_change_in_pool(
+        transparent_input_actions(inputs),
+        ShieldedSpends::default(),
+        &transparent_payment_output_sizes(&request.payments),
+        num_orchard_outputs,
+        total_input,
+        request.total_amount(),
+        request.dust_policy,
+        change,
+        request.fee_policy,
+    )?;
+    Ok(plan.fee)
+}
+
+/// Plans a split with `plan_orchard_batches` and proposes every batch.
+///
+/// # Returns
+/// * `Result<(OrchardBatchPlan, Vec<Pczt>), ProposalError>` - The plan and one PCZT
+///   per batch, in the same order
+pub fn propose_orchard_batches(
+    inputs_to_spend: &[u8],
+    transaction_request: &TransactionRequest,
+    change_address: Option<&str>,
+    max_orchard_actions: usize,
+) -> Result<(OrchardBatchPlan, Vec<pczt::Pczt>), ProposalError> {
+    let plan = plan_orchard_batches(inputs_to_spend, transaction_request, change_address, max_orchard_actions)?;
+    let inputs = types::parse_transparent_inputs(inputs_to_spend)
+        .map_err(|e| ProposalError::InvalidRequest(format!("Failed to parse inputs: {}", e)))?;
+
+    let pczts = plan.batches.iter()
+        .map(|batch| {
+            let batch_inputs: Vec<TransparentInput> = batch.input_indices.iter().map(|&i| inputs[i].clone()).collect();
+            let batch_inputs = types::serialize_transparent_inputs(&batch_inputs)
+                .map_err(|e| ProposalError::InvalidRequest(format!("Failed to serialize inputs: {}", e)))?;
+            crate::propose_transaction(
+                &batch_inputs,
+                batch.request.clone(),
+                change_address.map(str::to_string),
+            )
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok((plan, pczts))
+}
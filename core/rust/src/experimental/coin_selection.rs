@@ -0,0 +1,205 @@
+//! Picking inputs from a UTXO pool
+//!
+//! With a `CoinSelection` other than `All` on the request, `propose_transaction`
+//! treats its inputs as a pool and spends only the subset chosen here. A subset is
+//! acceptable when it covers the payments plus the ZIP-317 fee for the resulting
+//! shape, and the proposal's fee invariant holds (i.e. no more than `fee_tolerance`
+//! would be left to the miner, and no more than the request's `max_fee` is paid).
+
+use crate::error::ProposalError;
+use crate::types::{CoinSelection, TransactionRequest, TransparentInput};
+use crate::ChangeOutput;
+
+/// Upper bound on the subsets branch-and-bound visits before falling back
+const BNB_MAX_TRIES: usize = 100_000;
+
+/// Output shape of a request, which the fee of each candidate subset depends on
+#[derive(Debug, Clone)]
+struct Target<'a> {
+    request: &'a TransactionRequest,
+    /// Serialized sizes of the transparent payment outputs
+    transparent_payment_outputs: Vec<usize>,
+    num_orchard_outputs: usize,
+    total_payments: u64,
+    change: ChangeOutput,
+}
+
+impl<'a> Target<'a> {
+    fn new(request: &'a TransactionRequest, change_address: Option<&str>) -> Result<Self, ProposalError> {
+        let change = if crate::change_is_orchard(request, change_address)? {
+            ChangeOutput::Orchard
+        } else {
+            ChangeOutput::Transparent { size: crate::change_output_size(change_address) }
+        };
+        Ok(Target {
+            request,
+            transparent_payment_outputs: crate::transparent_payment_output_sizes(&request.payments),
+            num_orchard_outputs: request.payments.iter().filter(|p| p.is_unified()).count(),
+            total_payments: request.total_amount(),
+            change,
+        })
+    }
+
+    /// Fee for `num_inputs` inputs and no change output, under the request's fee policy
+    fn fee_without_change(&self, num_inputs: usize) -> u64 {
+        self.request.fee_policy.fee(crate::zip317_fee(
+            num_inputs,
+            &self.transparent_payment_outputs,
+            crate::ShieldedSpends::default(),
+            self.num_orchard_outputs,
+        ))
+    }
+
+    /// Checks a subset the way the proposal will, returning its change plan
+    fn evaluate(&self, num_inputs: usize, total_input: u64) -> Result<crate::ChangePlan, ProposalError> {
+        let plan = crate::plan_change_in_pool(
+            num_inputs,
+            crate::ShieldedSpends::default(),
+            &self.transparent_payment_outputs,
+            self.num_orchard_outputs,
+            total_input,
+            self.total_payments,
+            self.request.dust_policy,
+            self.change,
+            self.request.fee_policy,
+        )?;
+        crate::check_dust_change(self.request, &plan, self.change == ChangeOutput::Orchard)?;
+        crate::check_fee_invariant(total_input, self.total_payments, plan.change, plan.fee, self.request.fee_tolerance)?;
+        if let Some(max_fee) = self.request.max_fee {
+            let implicit_fee = total_input.saturating_sub(self.total_payments.saturating_add(plan.change));
+            if implicit_fee > max_fee {
+                return Err(ProposalError::FeeCapExceeded { fee: implicit_fee, max_fee });
+            }
+        }
+        Ok(plan)
+    }
+}
+
+/// Chooses which of `inputs` to spend for `request`, following `request.coin_selection`.
+///
+/// # Arguments
+/// * `inputs` - The UTXO pool
+/// * `request` - The payments, dust and change policies, and fee tolerance to select for
+/// * `change_address` - The change address the proposal will use, which decides the
+///   change output's pool under `ChangePolicy::Auto`
+///
+/// # Returns
+/// * `Result<Vec<usize>, ProposalError>` - Indices of the chosen inputs in ascending
+///   order, or `InsufficientFunds` if the whole pool can't pay for the request
+pub fn select_inputs(
+    inputs: &[TransparentInput],
+    request: &TransactionRequest,
+    change_address: Option<&str>,
+) -> Result<Vec<usize>, ProposalError> {
+    let target = Target::new(request, change_address)?;
+
+    let mut selected = match request.coin_selection {
+        CoinSelection::All => return Ok((0..inputs.len()).collect()),
+        CoinSelection::LargestFirst => accumulate(inputs, &target, |a, b| b.cmp(&a))?,
+        CoinSelection::SmallestFirst => accumulate(inputs, &target, |a, b| a.cmp(&b))?,
+        CoinSelection::BranchAndBound => match branch_and_bound(inputs, &target) {
+            Some(selected) => selected,
+            None => accumulate(inputs, &target, |a, b| b.cmp(&a))?,
+        },
+    };
+
+    selected.sort_unstable();
+    Ok(selected)
+}
+
+/// Adds inputs in `order` of amount until the subset is acceptable.
+fn accumulate(
+    inputs: &[TransparentInput],
+    target: &Target,
+    order: impl Fn(u64, u64) -> std::cmp::Ordering,
+) -> Result<Vec<usize>, ProposalError> {
+    let mut sorted: Vec<usize> = (0..inputs.len()).collect();
+    sorted.sort_by(|a, b| order(inputs[*a].amount, inputs[*b].amount));
+
+    let mut total_input = 0u64;
+    let fee = target.fee_without_change(0);
+    let mut last_error = ProposalError::InsufficientFunds {
+        available: 0,
+        required: target.total_payments.saturating_add(fee),
+        fee,
+    };
+    for (count, index) in sorted.iter().enumerate() {
+        total_input = total_input.saturating_add(inputs[*index].amount);
+        match target.evaluate(count + 1, total_input) {
+            Ok(_) => return Ok(sorted[..=count].to_vec()),
+            // A remainder too small for change but too large to give away may turn
+            // into change with one more input
+            Err(e) => last_error = e,
+        }
+    }
+
+    Err(last_error)
+}
+
+/// Depth-first search for the subset that needs no change output and leaves the
+/// least to the miner, or None if there is none within `BNB_MAX_TRIES`. Waste above
+/// the fee is only accepted where the proposal would pay it: as dust folded into the
+/// fee, or within `fee_tolerance`.
+fn branch_and_bound(inputs: &[TransparentInput], target: &Target) -> Option<Vec<usize>> {
+    let mut sorted: Vec<usize> = (0..inputs.len()).collect();
+    sorted.sort_by(|a, b| inputs[*b].amount.cmp(&inputs[*a].amount));
+    let amounts: Vec<u64> = sorted.iter().map(|index| inputs[*index].amount).collect();
+
+    // remaining[i] = sum of amounts[i..]
+    let mut remaining = vec![0u64; amounts.len() + 1];
+    for i in (0..amounts.len()).rev() {
+        remaining[i] = remaining[i + 1].saturating_add(amounts[i]);
+    }
+
+    let mut search = Search {
+        amounts: &amounts,
+        remaining: &remaining,
+        target,
+        tries: 0,
+        chosen: Vec::new(),
+        best: None,
+    };
+    search.visit(0, 0);
+
+    search.best.map(|(_, positions)| positions.into_iter().map(|position| sorted[position]).collect())
+}
+
+struct Search<'a> {
+    amounts: &'a [u64],
+    remaining: &'a [u64],
+    target: &'a Target<'a>,
+    tries: usize,
+    /// Positions (into `amounts`) on the current branch
+    chosen: Vec<usize>,
+    /// Least waste found so far, with its positions
+    best: Option<(u64, Vec<usize>)>,
+}
+
+impl Search<'_> {
+    fn visit(&mut self, position: usize, total_input: u64) {
+        self.tries += 1;
+        if self.tries > BNB_MAX_TRIES || self.best.as_ref().is_some_and(|(waste, _)| *waste == 0) {
+            return;
+        }
+
+        let needed = self.target.total_payments.saturating_add(self.target.fee_without_change(self.chosen.len()));
+        if total_input >= needed {
+            // Covered: adding more inputs only adds waste or a change output
+            if let Ok(plan) = self.target.evaluate(self.chosen.len(), total_input) {
+                let waste = total_input - needed;
+                if plan.change == 0 && self.best.as_ref().map_or(true, |(best, _)| waste < *best) {
+                    self.best = Some((waste, self.chosen.clone()));
+                }
+            }
+            return;
+        }
+        if position == self.amounts.len() || total_input.saturating_add(self.remaining[position]) < needed {
+            return;
+        }
+
+        self.chosen.push(position);
+        self.visit(position + 1, total_input.saturating_add(self.amounts[position]));
+        self.chosen.pop();
+        self.visit(position + 1, total_input);
+    }
+}
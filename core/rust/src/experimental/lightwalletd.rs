@@ -0,0 +1,121 @@
+//! lightwalletd-backed UTXO checks, discovery and chain tip (requires the `lightwalletd` feature)
+
+use tonic::transport::Channel;
+use zcash_address::ZcashAddress;
+use zcash_client_backend::proto::service::{
+    compact_tx_streamer_client::CompactTxStreamerClient, ChainSpec, GetAddressUtxosArg, RawTransaction,
+};
+use zcash_protocol::consensus::NetworkType;
+
+use crate::types::{AddressUtxo, DiscoveredInput, InputOutpoint};
+use crate::xpub::ExtendedPubKey;
+use crate::{ChainTipSource, UtxoChecker, UtxoSource};
+
+/// A `UtxoChecker` that asks a lightwalletd server for each input address's UTXOs.
+///
+/// Only P2PKH inputs are supported, since lookups go by address.
+pub struct LightwalletdUtxoChecker {
+    runtime: tokio::runtime::Runtime,
+    client: CompactTxStreamerClient<Channel>,
+    network: NetworkType,
+}
+
+impl LightwalletdUtxoChecker {
+    /// Connects to a lightwalletd server, e.g. `https://zec.rocks:443`.
+    pub fn connect(endpoint: &str, network: NetworkType) -> Result<Self, String> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .map_err(|e| format!("Failed to start runtime: {}", e))?;
+        let client = runtime
+            .block_on(CompactTxStreamerClient::connect(endpoint.to_string()))
+            .map_err(|e| format!("Failed to connect to {}: {}", endpoint, e))?;
+
+        Ok(Self { runtime, client, network })
+    }
+
+    /// Finds the UTXOs of an account key on this server (see `xpub::discover_utxos`).
+    pub fn discover_utxos(&self, xpub: &ExtendedPubKey, gap_limit: u32) -> Result<Vec<DiscoveredInput>, String> {
+        crate::xpub::discover_utxos(xpub, gap_limit, self)
+    }
+
+    /// Submits a raw transaction (e.g. from `finalize_and_extract`) to the server's mempool.
+    pub fn send_transaction(&self, tx_bytes: &[u8]) -> Result<(), String> {
+        let request = RawTransaction { data: tx_bytes.to_vec(), height: 0 };
+        let mut client = self.client.clone();
+        let reply = self.runtime
+            .block_on(client.send_transaction(request))
+            .map_err(|e| format!("SendTransaction failed: {}", e))?
+            .into_inner();
+
+        if reply.error_code != 0 {
+            return Err(format!("Server rejected the transaction ({}): {}", reply.error_code, reply.error_message));
+        }
+        Ok(())
+    }
+}
+
+impl UtxoChecker for LightwalletdUtxoChecker {
+    fn is_unspent(&self, outpoint: &InputOutpoint, height: u32) -> Result<bool, String> {
+        let pubkey_hash = crate::p2pkh_pubkey_hash(&outpoint.script_pubkey)
+            .ok_or_else(|| "Only P2PKH inputs can be looked up".to_string())?;
+        let address = ZcashAddress::from_transparent_p2pkh(self.network, pubkey_hash).encode();
+
+        let request = GetAddressUtxosArg {
+            addresses: vec![address],
+            start_height: 0,
+            max_entries: 0,
+        };
+        let mut client = self.client.clone();
+        let reply = self.runtime
+            .block_on(client.get_address_utxos(request))
+            .map_err(|e| format!("GetAddressUtxos failed: {}", e))?
+            .into_inner();
+
+        Ok(reply.address_utxos.iter().any(|utxo| {
+            utxo.txid.as_slice() == outpoint.txid.as_slice()
+                && utxo.index == outpoint.vout as i32
+                && utxo.height <= u64::from(height)
+        }))
+    }
+}
+
+impl UtxoSource for LightwalletdUtxoChecker {
+    fn address_utxos(&self, addresses: &[String]) -> Result<Vec<AddressUtxo>, String> {
+        let request = GetAddressUtxosArg {
+            addresses: addresses.to_vec(),
+            start_height: 0,
+            max_entries: 0,
+        };
+        let mut client = self.client.clone();
+        let reply = self.runtime
+            .block_on(client.get_address_utxos(request))
+            .map_err(|e| format!("GetAddressUtxos failed: {}", e))?
+            .into_inner();
+
+        reply.address_utxos.into_iter()
+            .map(|utxo| {
+                Ok(AddressUtxo {
+                    txid: utxo.txid.as_slice().try_into()
+                        .map_err(|_| format!("Server returned a {}-byte txid", utxo.txid.len()))?,
+                    vout: u32::try_from(utxo.index).map_err(|_| format!("Server returned output index {}", utxo.index))?,
+                    amount: u64::try_from(utxo.value_zat).map_err(|_| format!("Server returned value {}", utxo.value_zat))?,
+                    height: u32::try_from(utxo.height).ok(),
+                    address: utxo.address,
+                })
+            })
+            .collect()
+    }
+}
+
+impl ChainTipSource for LightwalletdUtxoChecker {
+    fn chain_tip(&self) -> Result<u32, String> {
+        let mut client = self.client.clone();
+        let block = self.runtime
+            .block_on(client.get_latest_block(ChainSpec {}))
+            .map_err(|e| format!("GetLatestBlock failed: {}", e))?
+            .into_inner();
+
+        u32::try_from(block.height).map_err(|_| format!("Server returned tip height {}", block.height))
+    }
+}
@@ -0,0 +1,244 @@
+//! Proving on a remote machine (requires the `remote-prover` feature)
+//!
+//! A constrained host proposes and signs locally and sends the PCZT to a
+//! `ProverServer` on a machine with the CPU and memory for Orchard proving.
+//!
+//! Protocol (one request per TCP connection, all integers little-endian):
+//! - request:  [magic: "T2ZP"] [version: 1 byte = 1] [token_len: u16] [token]
+//!   [pczt_len: u32] [serialized PCZT]
+//! - response: [magic: "T2ZP"] [status: 1 byte] [payload_len: u32] [payload]
+//!
+//! On success the payload is the proved PCZT; otherwise it is a UTF-8 error message.
+//! The transport is plain TCP: run it over a private network, a VPN or a TLS tunnel.
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::Duration;
+
+use pczt::Pczt;
+use subtle::ConstantTimeEq;
+
+use crate::error::ProverError;
+use crate::prover_service::{ProverService, ProverServiceConfig};
+
+/// Magic bytes at the start of every request and response
+pub const PROTOCOL_MAGIC: [u8; 4] = *b"T2ZP";
+
+/// Current protocol version
+pub const PROTOCOL_VERSION: u8 = 1;
+
+/// Largest PCZT either side will accept
+pub const MAX_PCZT_SIZE: usize = 16 * 1024 * 1024;
+
+/// Connections a `ProverServer` handles at once unless `with_max_connections` says
+/// otherwise. Each may buffer a PCZT of up to `MAX_PCZT_SIZE` bytes.
+pub const DEFAULT_MAX_CONNECTIONS: usize = 32;
+
+const STATUS_OK: u8 = 0;
+const STATUS_UNAUTHORIZED: u8 = 1;
+const STATUS_BAD_REQUEST: u8 = 2;
+const STATUS_PROVER_ERROR: u8 = 3;
+
+/// Client for a remote `ProverServer`
+#[derive(Debug, Clone)]
+pub struct RemoteProver {
+    addr: SocketAddr,
+    token: Vec<u8>,
+    timeout: Duration,
+}
+
+impl RemoteProver {
+    /// Creates a client for the server at `addr` (e.g. `"prover.internal:7878"`).
+    ///
+    /// Proving can take a while on a busy server, so the default timeout is 5 minutes.
+    pub fn new(addr: impl ToSocketAddrs, token: impl Into<Vec<u8>>) -> Result<Self, ProverError> {
+        let addr = addr.to_socket_addrs()
+            .map_err(|e| ProverError::Remote(format!("Invalid address: {}", e)))?
+            .next()
+            .ok_or_else(|| ProverError::Remote("Address did not resolve".to_string()))?;
+        Ok(RemoteProver { addr, token: token.into(), timeout: Duration::from_secs(300) })
+    }
+
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sends a PCZT to the server and returns it with proofs added.
+    pub fn prove(&self, pczt: Pczt) -> Result<Pczt, ProverError> {
+        let io_error = |e: std::io::Error| ProverError::Remote(format!("Connection failed: {}", e));
+
+        let mut stream = TcpStream::connect_timeout(&self.addr, self.timeout).map_err(io_error)?;
+        stream.set_read_timeout(Some(self.timeout)).map_err(io_error)?;
+        stream.set_write_timeout(Some(self.timeout)).map_err(io_error)?;
+
+        let pczt_bytes = crate::serialize_pczt(&pczt);
+        if pczt_bytes.len() > MAX_PCZT_SIZE || self.token.len() > u16::MAX as usize {
+            return Err(ProverError::Remote("Request too large".to_string()));
+        }
+
+        let mut request = Vec::with_capacity(pczt_bytes.len() + self.token.len() + 11);
+        request.extend_from_slice(&PROTOCOL_MAGIC);
+        request.push(PROTOCOL_VERSION);
+        request.extend_from_slice(&(self.token.len() as u16).to_le_bytes());
+        request.extend_from_slice(&self.token);
+        request.extend_from_slice(&(pczt_bytes.len() as u32).to_le_bytes());
+        request.extend_from_slice(&pczt_bytes);
+        stream.write_all(&request).map_err(io_error)?;
+
+        let mut header = [0u8; 9];
+        stream.read_exact(&mut header).map_err(io_error)?;
+        if header[..4] != PROTOCOL_MAGIC {
+            return Err(ProverError::Remote("Not a prover server response".to_string()));
+        }
+        let payload = read_payload(&mut stream, &header[5..9]).map_err(io_error)?;
+
+        match header[4] {
+            STATUS_OK => crate::parse_pczt(&payload)
+                .map_err(|e| ProverError::Remote(format!("Invalid proved PCZT: {}", e))),
+            STATUS_UNAUTHORIZED => Err(ProverError::RemoteUnauthorized),
+            _ => Err(ProverError::Remote(String::from_utf8_lossy(&payload).into_owned())),
+        }
+    }
+}
+
+/// Server that proves PCZTs for authenticated `RemoteProver` clients
+pub struct ProverServer {
+    listener: TcpListener,
+    token: Arc<Vec<u8>>,
+    service: Arc<ProverService>,
+    max_connections: usize,
+}
+
+impl ProverServer {
+    /// Binds to `addr`; only requests carrying `token` are proved.
+    ///
+    /// Proofs run on a `ProverService`, so `config` bounds concurrency and queueing.
+    /// At most `DEFAULT_MAX_CONNECTIONS` connections are handled at once.
+    pub fn bind(
+        addr: impl ToSocketAddrs,
+        token: impl Into<Vec<u8>>,
+        config: ProverServiceConfig,
+    ) -> std::io::Result<Self> {
+        Ok(ProverServer {
+            listener: TcpListener::bind(addr)?,
+            token: Arc::new(token.into()),
+            service: Arc::new(ProverService::start(config)),
+            max_connections: DEFAULT_MAX_CONNECTIONS,
+        })
+    }
+
+    /// Sets how many connections are handled at once (at least 1). Further clients
+    /// wait in the listen backlog until a connection closes.
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = max_connections.max(1);
+        self
+    }
+
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Accepts connections forever, handling each on its own thread, with at most
+    /// `max_connections` threads at a time.
+    pub fn serve(self) -> std::io::Result<()> {
+        let slots = Arc::new(ConnectionSlots { active: Mutex::new(0), freed: Condvar::new() });
+        loop {
+            // Stop accepting while every slot is taken
+            let slot = slots.acquire(self.max_connections);
+            let (stream, _) = self.listener.accept()?;
+            let token = Arc::clone(&self.token);
+            let service = Arc::clone(&self.service);
+            std::thread::spawn(move || {
+                let _slot = slot;
+                let _ = handle_connection(stream, &token, &service);
+            });
+        }
+    }
+}
+
+/// Counts the connections a `ProverServer` is handling
+struct ConnectionSlots {
+    active: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl ConnectionSlots {
+    /// Waits until fewer than `max` connections are active and takes a slot
+    fn acquire(self: &Arc<Self>, max: usize) -> ConnectionSlot {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        while *active >= max {
+            active = self.freed.wait(active).unwrap_or_else(|e| e.into_inner());
+        }
+        *active += 1;
+        ConnectionSlot(Arc::clone(self))
+    }
+}
+
+/// A taken connection slot, given back when dropped
+struct ConnectionSlot(Arc<ConnectionSlots>);
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        *self.0.active.lock().unwrap_or_else(|e| e.into_inner()) -= 1;
+        self.0.freed.notify_one();
+    }
+}
+
+fn handle_connection(mut stream: TcpStream, token: &[u8], service: &ProverService) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(60)))?;
+
+    let (status, payload) = match read_request(&mut stream, token) {
+        Ok(pczt_bytes) => match crate::parse_pczt(&pczt_bytes) {
+            Ok(pczt) => match service.submit(pczt).and_then(|ticket| ticket.wait()) {
+                Ok(proved) => (STATUS_OK, crate::serialize_pczt(&proved)),
+                Err(e) => (STATUS_PROVER_ERROR, e.to_string().into_bytes()),
+            },
+            Err(e) => (STATUS_BAD_REQUEST, e.to_string().into_bytes()),
+        },
+        Err((status, message)) => (status, message.into_bytes()),
+    };
+
+    let mut response = Vec::with_capacity(payload.len() + 9);
+    response.extend_from_slice(&PROTOCOL_MAGIC);
+    response.push(status);
+    response.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    response.extend_from_slice(&payload);
+    stream.write_all(&response)
+}
+
+/// Reads a request, checking its token before accepting the serialized PCZT
+fn read_request(stream: &mut TcpStream, expected_token: &[u8]) -> Result<Vec<u8>, (u8, String)> {
+    let io_error = |e: std::io::Error| (STATUS_BAD_REQUEST, format!("Failed to read request: {}", e));
+
+    let mut header = [0u8; 7];
+    stream.read_exact(&mut header).map_err(io_error)?;
+    if header[..4] != PROTOCOL_MAGIC {
+        return Err((STATUS_BAD_REQUEST, "Not a prover request".to_string()));
+    }
+    if header[4] != PROTOCOL_VERSION {
+        return Err((STATUS_BAD_REQUEST, format!("Unsupported protocol version: {}", header[4])));
+    }
+
+    let mut token = vec![0u8; u16::from_le_bytes([header[5], header[6]]) as usize];
+    stream.read_exact(&mut token).map_err(io_error)?;
+    if !bool::from(token.as_slice().ct_eq(expected_token)) {
+        return Err((STATUS_UNAUTHORIZED, "Invalid token".to_string()));
+    }
+
+    let mut len = [0u8; 4];
+    stream.read_exact(&mut len).map_err(io_error)?;
+    read_payload(stream, &len).map_err(io_error)
+}
+
+/// Reads a u32-length-prefixed payload of at most `MAX_PCZT_SIZE` bytes
+fn read_payload(stream: &mut TcpStream, len: &[u8]) -> std::io::Result<Vec<u8>> {
+    let len = u32::from_le_bytes(len.try_into().expect("4 bytes")) as usize;
+    if len > MAX_PCZT_SIZE {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, "Payload too large"));
+    }
+    let mut payload = vec![0u8; len];
+    stream.read_exact(&mut payload)?;
+    Ok(payload)
+}
@@ -1,4 +1,5 @@
 use std::ffi::{CStr, CString};
+use std::ffi::c_void;
 use std::os::raw::{c_char, c_uchar};
 use std::ptr;
 use std::slice;
@@ -6,8 +7,10 @@ use std::slice;
 use crate::error::*;
 use crate::types::*;
 use crate::*;
+use crate::prover_service::{ProofPriority, ProofStatus, ProverService, ProverServiceConfig};
 
 use pczt::Pczt;
+use zcash_protocol::consensus::NetworkType;
 
 /// Result code for FFI functions
 #[repr(C)]
@@ -25,6 +28,10 @@ pub enum ResultCode {
     ErrorCombine = 15,
     ErrorFinalization = 16,
     ErrorParse = 17,
+    ErrorInspection = 18,
+    ErrorMemo = 19,
+    ErrorReservation = 20,
+    ErrorFile = 21,
     ErrorNotImplemented = 99,
 }
 
@@ -40,6 +47,97 @@ pub struct TransactionRequestHandle {
     _private: [u8; 0],
 }
 
+/// Opaque handle to a ProverService
+#[repr(C)]
+pub struct ProverServiceHandle {
+    _private: [u8; 0],
+}
+
+// ============================================================================
+// PCZT handles
+//
+// A PCZT handle is a `Box<Pczt>`. Every function creates and consumes handles
+// through these two helpers.
+// ============================================================================
+
+/// Moves a PCZT into a new handle
+fn pczt_into_handle(pczt: Pczt) -> *mut PcztHandle {
+    Box::into_raw(Box::new(pczt)) as *mut PcztHandle
+}
+
+/// Takes the PCZT out of a handle
+///
+/// # Safety
+/// `handle` must be a live, non-null PCZT handle; it is invalid afterwards.
+unsafe fn pczt_from_handle(handle: *mut PcztHandle) -> Pczt {
+    *Box::from_raw(handle as *mut Pczt)
+}
+
+/// Opaque handle to a byte buffer owned by the library
+///
+/// Unlike the `*mut u8` outputs freed with `pczt_free_bytes`, the bytes are handed
+/// over as produced, without shrinking them into an exactly-sized allocation first.
+/// Read them with `pczt_buffer_data` / `pczt_buffer_len` and free the handle with
+/// `pczt_buffer_free`.
+#[repr(C)]
+pub struct ByteBufferHandle {
+    _private: [u8; 0],
+}
+
+/// C-compatible proof priority (see `ProofPriority`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CProofPriority {
+    ProofPriorityLow = 0,
+    ProofPriorityNormal = 1,
+    ProofPriorityHigh = 2,
+}
+
+impl From<CProofPriority> for ProofPriority {
+    fn from(priority: CProofPriority) -> Self {
+        match priority {
+            CProofPriority::ProofPriorityLow => ProofPriority::Low,
+            CProofPriority::ProofPriorityNormal => ProofPriority::Normal,
+            CProofPriority::ProofPriorityHigh => ProofPriority::High,
+        }
+    }
+}
+
+/// C-compatible progress of a submitted PCZT (see `ProofStatus`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CProofStatus {
+    ProofStatusQueued = 0,
+    ProofStatusProving = 1,
+    ProofStatusFinished = 2,
+}
+
+/// C-compatible strictness (see `Strictness`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CStrictness {
+    StrictnessLenient = 0,
+    StrictnessStrict = 1,
+}
+
+impl From<CStrictness> for Strictness {
+    fn from(strictness: CStrictness) -> Self {
+        match strictness {
+            CStrictness::StrictnessLenient => Strictness::Lenient,
+            CStrictness::StrictnessStrict => Strictness::Strict,
+        }
+    }
+}
+
+/// Receives a background proof result on a prover worker thread
+///
+/// On success `pczt` is the proved PCZT, owned by the callee. On error `pczt` is null
+/// and the error can be read with `pczt_get_last_error` from within the callback.
+pub type ProofCallbackFn = extern "C" fn(user_data: *mut c_void, code: ResultCode, pczt: *mut PcztHandle);
+
+/// Receives a chunk of a serialized PCZT (see `pczt_write`); returns false to abort
+pub type PcztWriteFn = extern "C" fn(user_data: *mut c_void, bytes: *const u8, len: usize) -> bool;
+
 /// C-compatible payment structure
 #[repr(C)]
 pub struct CPayment {
@@ -58,14 +156,282 @@ pub struct CTransparentOutput {
     pub value: u64,
 }
 
+/// C-compatible view of a PCZT's transparent input (see `pczt_get_transparent_input`)
+#[repr(C)]
+pub struct CTransparentInputInfo {
+    /// Transaction ID of the spent UTXO (internal byte order)
+    pub prevout_txid: [u8; 32],
+    pub prevout_index: u32,
+    /// Sequence number (0xFFFFFFFF if the input doesn't set one)
+    pub sequence: u32,
+    /// Value in zatoshis
+    pub value: u64,
+    /// The spent UTXO's script_pubkey. Points into the PCZT, and is valid until the
+    /// handle is freed or consumed.
+    pub script_pub_key: *const c_uchar,
+    pub script_pub_key_len: usize,
+    /// Number of partial signatures the input carries
+    pub num_signatures: usize,
+    /// Whether the input has its final script_sig
+    pub is_finalized: bool,
+}
+
+/// C-compatible structured information about the last error
+#[repr(C)]
+pub struct CErrorInfo {
+    /// Result code of the failed call
+    pub code: ResultCode,
+    /// Whether `input_index` is set
+    pub has_input_index: bool,
+    /// Index of the transparent input the error refers to (valid if `has_input_index`)
+    pub input_index: usize,
+}
+
+/// C-compatible amounts of an insufficient-funds error
+#[repr(C)]
+pub struct CInsufficientFunds {
+    /// Total of the inputs considered, in zatoshis
+    pub available: u64,
+    /// Payments plus fee, in zatoshis
+    pub required: u64,
+    /// ZIP-317 fee included in `required`, in zatoshis
+    pub fee: u64,
+}
+
+/// C-compatible machine-readable details of the last error, for hosts that
+/// localize messages instead of matching on them
+#[repr(C)]
+pub struct CErrorDetails {
+    /// Result code of the failed call (e.g. `ErrorProposal`); the error's domain is
+    /// `pczt_error_domain`
+    pub result_code: ResultCode,
+    /// Stable number of the error code (see `ErrorCode::number`), 0 if no error is set
+    pub sub_code: u32,
+    /// Number of values set in `context`
+    pub context_len: usize,
+    /// Numbers describing the error, in the order documented for its code by
+    /// `FfiError::context` (e.g. available, required and fee for insufficient funds)
+    pub context: [u64; 3],
+}
+
+/// The last error raised on this thread
+struct LastError {
+    message: String,
+    code: ResultCode,
+    error_code: ErrorCode,
+    input_index: Option<usize>,
+    insufficient_funds: Option<(u64, u64, u64)>,
+    context: Vec<u64>,
+}
+
+/// C-compatible PCZT parsing options
+#[repr(C)]
+pub struct CParseOptions {
+    /// Reject encodings that don't re-serialize to the exact same bytes
+    pub require_canonical: bool,
+    /// Also reject proprietary fields outside the recognized namespaces
+    pub reject_unknown_fields: bool,
+}
+
+/// C-compatible Orchard output accounting data (see `OrchardOutputInfo`)
+#[repr(C)]
+pub struct COrchardOutput {
+    pub action_index: usize,
+    pub cmx: [u8; 32],
+    pub ephemeral_key: [u8; 32],
+    /// Whether `value` is known (false for outputs decoded from a final transaction)
+    pub has_value: bool,
+    pub value: u64,
+}
+
+/// C-compatible amount display unit (see `AmountUnit`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CAmountUnit {
+    /// "1.00000000 ZEC (100000000 zatoshis)"
+    AmountUnitBoth = 0,
+    /// "1.00000000 ZEC"
+    AmountUnitZec = 1,
+    /// "100000000 zatoshis"
+    AmountUnitZatoshis = 2,
+}
+
+impl From<CAmountUnit> for AmountUnit {
+    fn from(unit: CAmountUnit) -> Self {
+        match unit {
+            CAmountUnit::AmountUnitBoth => AmountUnit::Both,
+            CAmountUnit::AmountUnitZec => AmountUnit::Zec,
+            CAmountUnit::AmountUnitZatoshis => AmountUnit::Zatoshis,
+        }
+    }
+}
+
+/// C-compatible memo encoding (see `MemoEncoding`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CMemoEncoding {
+    /// Plain UTF-8 text, zero-padded to 512 bytes
+    MemoPadded = 0,
+    /// Deflate-compressed text behind a marker byte
+    MemoCompressed = 1,
+}
+
+/// C-compatible coin selection strategy (see `CoinSelection`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CCoinSelection {
+    /// Spend every provided input
+    CoinSelectionAll = 0,
+    /// Spend the largest UTXOs first
+    CoinSelectionLargestFirst = 1,
+    /// Spend the smallest UTXOs first
+    CoinSelectionSmallestFirst = 2,
+    /// Look for a changeless set of inputs, else largest-first
+    CoinSelectionBranchAndBound = 3,
+}
+
+/// C-compatible network selection (see `Network`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CNetwork {
+    NetworkMainnet = 0,
+    NetworkTestnet = 1,
+    /// Regtest with every upgrade through NU6 active from height 1
+    NetworkRegtest = 2,
+}
+
+/// C-compatible Orchard bundle flags (see `OrchardFlags`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct COrchardFlags {
+    pub spends_enabled: bool,
+    pub outputs_enabled: bool,
+}
+
+/// C-compatible change output pool (see `ChangePolicy`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CChangePolicy {
+    /// Change goes to a transparent output
+    ChangePolicyTransparent = 0,
+    /// Change goes to the Orchard receiver of the change address
+    ChangePolicyOrchard = 1,
+    /// Orchard if the change address has an Orchard receiver, else transparent
+    ChangePolicyAuto = 2,
+}
+
+/// C-compatible dust handling (see `DustPolicy`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CDustPolicy {
+    /// Refuse transparent payments below the threshold, and add dust change to the fee
+    DustPolicyAddChangeToFee = 0,
+    /// Refuse transparent payments and change below the threshold
+    DustPolicyReject = 1,
+}
+
+/// C-compatible fee policy (see `FeePolicy`)
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CFeePolicy {
+    /// Pay the ZIP-317 conventional fee
+    FeePolicyZip317Standard = 0,
+    /// Pay a fixed number of zatoshis
+    FeePolicyFixedZatoshis = 1,
+    /// Pay the ZIP-317 fee times a multiplier of at least 1
+    FeePolicyZip317WithMultiplier = 2,
+}
+
+/// Number of errors each thread keeps for `pczt_get_error_history`
+pub const ERROR_HISTORY_CAPACITY: usize = 16;
+
+/// An error in the history, as reported by `pczt_get_error_history`
+#[derive(serde::Serialize)]
+struct ErrorRecord {
+    /// When the error was raised, in milliseconds since the Unix epoch
+    timestamp_ms: u64,
+    /// The FFI function (or its helper) that raised it
+    function: &'static str,
+    /// The domain of the error code (see `ErrorDomain`)
+    domain: &'static str,
+    /// The stable error code (see `ErrorCode`)
+    code: &'static str,
+    message: String,
+}
+
 thread_local! {
-    static LAST_ERROR: std::cell::RefCell<Option<String>> = std::cell::RefCell::new(None);
+    static LAST_ERROR: std::cell::RefCell<Option<LastError>> = std::cell::RefCell::new(None);
+    static ERROR_HISTORY: std::cell::RefCell<std::collections::VecDeque<ErrorRecord>> =
+        std::cell::RefCell::new(std::collections::VecDeque::with_capacity(ERROR_HISTORY_CAPACITY));
+}
+
+/// Maps an error to the result code returned alongside it
+fn result_code_for(err: &FfiError) -> ResultCode {
+    match err {
+        FfiError::NullPointer => ResultCode::ErrorNullPointer,
+        FfiError::InvalidUtf8 => ResultCode::ErrorInvalidUtf8,
+        FfiError::BufferTooSmall => ResultCode::ErrorBufferTooSmall,
+        FfiError::Proposal(_) => ResultCode::ErrorProposal,
+        FfiError::Prover(_) => ResultCode::ErrorProver,
+        FfiError::Verification(_) => ResultCode::ErrorVerification,
+        FfiError::Sighash(_) => ResultCode::ErrorSighash,
+        FfiError::Signature(_) => ResultCode::ErrorSignature,
+        FfiError::Combine(_) => ResultCode::ErrorCombine,
+        FfiError::Finalization(_) => ResultCode::ErrorFinalization,
+        FfiError::Parse(_) => ResultCode::ErrorParse,
+        FfiError::Inspection(_) => ResultCode::ErrorInspection,
+        FfiError::Memo(_) => ResultCode::ErrorMemo,
+        FfiError::Reservation(_) => ResultCode::ErrorReservation,
+        FfiError::File(_) => ResultCode::ErrorFile,
+        FfiError::NotImplemented(_) => ResultCode::ErrorNotImplemented,
+    }
+}
+
+/// Sets the last error, recording the calling function in the error history
+macro_rules! set_last_error {
+    ($err:expr) => {{
+        fn here() {}
+        set_last_error_in(std::any::type_name_of_val(&here), $err)
+    }};
 }
 
-/// Sets the last error message
-fn set_last_error(err: FfiError) {
+/// Sets the last error message and appends it to the error history
+///
+/// `path` is the type name of a function item nested in the caller (see the
+/// `set_last_error!` macro), from which the caller's name is taken.
+fn set_last_error_in(path: &'static str, err: FfiError) {
+    let function = path.trim_end_matches("::here")
+        .trim_end_matches("::{{closure}}")
+        .rsplit("::")
+        .next()
+        .unwrap_or(path);
+    let timestamp_ms = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis() as u64);
+
+    let last_error = LastError {
+        message: err.to_string(),
+        code: result_code_for(&err),
+        error_code: err.code(),
+        input_index: err.input_index(),
+        insufficient_funds: err.insufficient_funds(),
+        context: err.context(),
+    };
+    ERROR_HISTORY.with(|history| {
+        let mut history = history.borrow_mut();
+        if history.len() == ERROR_HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(ErrorRecord {
+            timestamp_ms,
+            function,
+            domain: last_error.error_code.domain().as_str(),
+            code: last_error.error_code.as_str(),
+            message: last_error.message.clone(),
+        });
+    });
     LAST_ERROR.with(|e| {
-        *e.borrow_mut() = Some(err.to_string());
+        *e.borrow_mut() = Some(last_error);
     });
 }
 
@@ -80,8 +446,8 @@ pub unsafe extern "C" fn pczt_get_last_error(
     }
 
     LAST_ERROR.with(|e| {
-        if let Some(ref err_msg) = *e.borrow() {
-            let c_str = match CString::new(err_msg.as_str()) {
+        if let Some(ref last_error) = *e.borrow() {
+            let c_str = match CString::new(last_error.message.as_str()) {
                 Ok(s) => s,
                 Err(_) => return ResultCode::ErrorInvalidUtf8,
             };
@@ -101,6 +467,229 @@ pub unsafe extern "C" fn pczt_get_last_error(
     })
 }
 
+/// Gets the buffer size `pczt_get_last_error` needs for the last error message,
+/// including the terminating NUL (1 if no error is set)
+#[no_mangle]
+pub extern "C" fn pczt_get_last_error_len() -> usize {
+    LAST_ERROR.with(|e| match *e.borrow() {
+        Some(ref last_error) => last_error.message.len() + 1,
+        None => 1,
+    })
+}
+
+/// Gets the last error message, truncated to fit `buffer`
+///
+/// Unlike `pczt_get_last_error`, writes as much of the message as fits (cut at a
+/// UTF-8 char boundary, NUL-terminated) instead of failing. If `truncated_out` is
+/// not null, it is set to whether part of the message was cut off. Fails with
+/// `ErrorBufferTooSmall` only if `buffer_len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_last_error_truncated(
+    buffer: *mut c_char,
+    buffer_len: usize,
+    truncated_out: *mut bool,
+) -> ResultCode {
+    if buffer.is_null() {
+        return ResultCode::ErrorNullPointer;
+    }
+    if buffer_len == 0 {
+        return ResultCode::ErrorBufferTooSmall;
+    }
+
+    LAST_ERROR.with(|e| {
+        let last_error = e.borrow();
+        let message = last_error.as_ref().map_or("", |last_error| last_error.message.as_str());
+        write_truncated(buffer, buffer_len, message);
+        if !truncated_out.is_null() {
+            *truncated_out = message.len() + 1 > buffer_len;
+        }
+        ResultCode::Success
+    })
+}
+
+/// Gets the stable, machine-readable code of the last error (e.g. "INSUFFICIENT_FUNDS")
+///
+/// Writes a NUL-terminated string into `buffer`. If no error is set, writes an empty string.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_last_error_code(
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> ResultCode {
+    if buffer.is_null() {
+        return ResultCode::ErrorNullPointer;
+    }
+
+    LAST_ERROR.with(|e| {
+        let code = match *e.borrow() {
+            Some(ref last_error) => last_error.error_code.as_str(),
+            None => "",
+        };
+
+        // Codes are ASCII without interior NULs
+        let bytes = code.as_bytes();
+        if bytes.len() + 1 > buffer_len {
+            return ResultCode::ErrorBufferTooSmall;
+        }
+
+        ptr::copy_nonoverlapping(bytes.as_ptr() as *const c_char, buffer, bytes.len());
+        *buffer.add(bytes.len()) = 0;
+        ResultCode::Success
+    })
+}
+
+/// Gets structured information about the last error
+///
+/// If the error refers to a specific transparent input (e.g. a missing signature
+/// during finalization), `has_input_index` is set and `input_index` identifies it,
+/// so hosts can prompt the right signer. If no error is set, `code` is `SUCCESS`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_last_error_info(info_out: *mut CErrorInfo) -> ResultCode {
+    if info_out.is_null() {
+        return ResultCode::ErrorNullPointer;
+    }
+
+    LAST_ERROR.with(|e| {
+        let info = match *e.borrow() {
+            Some(ref last_error) => CErrorInfo {
+                code: last_error.code,
+                has_input_index: last_error.input_index.is_some(),
+                input_index: last_error.input_index.unwrap_or(0),
+            },
+            None => CErrorInfo {
+                code: ResultCode::Success,
+                has_input_index: false,
+                input_index: 0,
+            },
+        };
+        *info_out = info;
+        ResultCode::Success
+    })
+}
+
+/// Gets the amounts of the last error if it was `INSUFFICIENT_FUNDS`
+///
+/// Returns true and fills `funds_out` if the last error on this thread reported a
+/// shortfall, so hosts can show how much more is needed. Returns false otherwise,
+/// leaving `funds_out` untouched.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_last_error_insufficient_funds(funds_out: *mut CInsufficientFunds) -> bool {
+    if funds_out.is_null() {
+        return false;
+    }
+
+    LAST_ERROR.with(|e| match *e.borrow() {
+        Some(LastError { insufficient_funds: Some((available, required, fee)), .. }) => {
+            *funds_out = CInsufficientFunds { available, required, fee };
+            true
+        }
+        _ => false,
+    })
+}
+
+/// Gets the result code, numeric code and context values of the last error
+///
+/// If no error is set, `result_code` is `SUCCESS` and `sub_code` and `context_len` are 0.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_last_error_details(details_out: *mut CErrorDetails) -> ResultCode {
+    if details_out.is_null() {
+        return ResultCode::ErrorNullPointer;
+    }
+
+    LAST_ERROR.with(|e| {
+        let mut details = CErrorDetails {
+            result_code: ResultCode::Success,
+            sub_code: 0,
+            context_len: 0,
+            context: [0; 3],
+        };
+        if let Some(ref last_error) = *e.borrow() {
+            details.result_code = last_error.code;
+            details.sub_code = last_error.error_code.number();
+            details.context_len = last_error.context.len().min(details.context.len());
+            details.context[..details.context_len].copy_from_slice(&last_error.context[..details.context_len]);
+        }
+        *details_out = details;
+        ResultCode::Success
+    })
+}
+
+/// Gets the version of the error domain namespace (see `ERROR_DOMAIN_VERSION`)
+///
+/// The version goes up whenever a domain is added, so hosts can tell whether they
+/// know every domain `pczt_error_domain` may return.
+#[no_mangle]
+pub extern "C" fn pczt_error_domain_version() -> u32 {
+    ERROR_DOMAIN_VERSION
+}
+
+/// Gets the domain of the last error (see `ErrorDomain`), or 0 if no error is set
+///
+/// Domains name the subsystem an error comes from (e.g. 4 for network), independently
+/// of the `ResultCode` the call returned. Together with `pczt_error_domain_code`, they
+/// let new subsystems report precise errors without adding result codes.
+#[no_mangle]
+pub extern "C" fn pczt_error_domain() -> u32 {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(0, |last_error| last_error.error_code.domain().number()))
+}
+
+/// Gets the stable number of the last error's code (see `ErrorCode::number`), or 0
+/// if no error is set
+#[no_mangle]
+pub extern "C" fn pczt_error_domain_code() -> u32 {
+    LAST_ERROR.with(|e| e.borrow().as_ref().map_or(0, |last_error| last_error.error_code.number()))
+}
+
+/// Gets the last errors raised on this thread, oldest first, as a JSON array of
+/// `{"timestamp_ms", "function", "domain", "code", "message"}` objects
+///
+/// Up to `ERROR_HISTORY_CAPACITY` errors are kept, so a pipeline can find the error
+/// that mattered even after later steps overwrote the last error. The JSON bytes
+/// must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_error_history(
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if json_out.is_null() || json_len_out.is_null() {
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let json = ERROR_HISTORY.with(|history| {
+        serde_json::to_string(&*history.borrow()).expect("error records serialize")
+    });
+    write_json_out(json, json_out, json_len_out);
+    ResultCode::Success
+}
+
+/// Sets the crate-wide strictness
+///
+/// In strict mode, invalid UTF-8 in payment labels and messages, memos on
+/// transparent payments and unclassifiable addresses are errors instead of being
+/// silently dropped. Memos that aren't UTF-8 are refused in either mode.
+#[no_mangle]
+pub extern "C" fn pczt_set_strictness(strictness: CStrictness) -> ResultCode {
+    set_strictness(strictness.into());
+    ResultCode::Success
+}
+
+/// Turns the cache of parsed recipient addresses on with room for `capacity`
+/// addresses, or off with 0
+///
+/// Services paying the same addresses over and over skip decoding them again on each
+/// proposal. The cache is shared by all threads.
+#[no_mangle]
+pub extern "C" fn pczt_set_address_cache_capacity(capacity: usize) -> ResultCode {
+    crate::address_cache::set_address_cache_capacity(capacity);
+    ResultCode::Success
+}
+
+/// Drops every cached address, keeping the cache's capacity
+#[no_mangle]
+pub extern "C" fn pczt_clear_address_cache() -> ResultCode {
+    crate::address_cache::clear_address_cache();
+    ResultCode::Success
+}
+
 /// Creates a new transaction request
 #[no_mangle]
 pub unsafe extern "C" fn pczt_transaction_request_new(
@@ -109,7 +698,7 @@ pub unsafe extern "C" fn pczt_transaction_request_new(
     request_out: *mut *mut TransactionRequestHandle,
 ) -> ResultCode {
     if payments.is_null() || request_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
@@ -118,14 +707,14 @@ pub unsafe extern "C" fn pczt_transaction_request_new(
 
     for c_payment in payments_slice {
         if c_payment.address.is_null() {
-            set_last_error(FfiError::NullPointer);
+            set_last_error!(FfiError::NullPointer);
             return ResultCode::ErrorNullPointer;
         }
 
         let address = match CStr::from_ptr(c_payment.address).to_str() {
             Ok(s) => s.to_string(),
             Err(_) => {
-                set_last_error(FfiError::InvalidUtf8);
+                set_last_error!(FfiError::InvalidUtf8);
                 return ResultCode::ErrorInvalidUtf8;
             }
         };
@@ -133,20 +722,36 @@ pub unsafe extern "C" fn pczt_transaction_request_new(
         let mut payment = Payment::new(address, c_payment.amount);
 
         if !c_payment.memo.is_null() {
-            if let Ok(memo) = CStr::from_ptr(c_payment.memo).to_str() {
-                payment = payment.with_memo(memo.to_string());
+            // Dropping a memo would send the payment without it, so this fails in
+            // either mode; raw memos go through `pczt_transaction_request_set_payment_memo_bytes`
+            match CStr::from_ptr(c_payment.memo).to_str() {
+                Ok(memo) => payment = payment.with_memo(memo.to_string()),
+                Err(_) => {
+                    set_last_error!(FfiError::InvalidUtf8);
+                    return ResultCode::ErrorInvalidUtf8;
+                }
             }
         }
 
         if !c_payment.label.is_null() {
-            if let Ok(label) = CStr::from_ptr(c_payment.label).to_str() {
-                payment = payment.with_label(label.to_string());
+            match CStr::from_ptr(c_payment.label).to_str() {
+                Ok(label) => payment = payment.with_label(label.to_string()),
+                Err(_) if strictness() == Strictness::Strict => {
+                    set_last_error!(FfiError::InvalidUtf8);
+                    return ResultCode::ErrorInvalidUtf8;
+                }
+                Err(_) => {} // Lenient: ignore the invalid string
             }
         }
 
         if !c_payment.message.is_null() {
-            if let Ok(message) = CStr::from_ptr(c_payment.message).to_str() {
-                payment = payment.with_message(message.to_string());
+            match CStr::from_ptr(c_payment.message).to_str() {
+                Ok(message) => payment = payment.with_message(message.to_string()),
+                Err(_) if strictness() == Strictness::Strict => {
+                    set_last_error!(FfiError::InvalidUtf8);
+                    return ResultCode::ErrorInvalidUtf8;
+                }
+                Err(_) => {} // Lenient: ignore the invalid string
             }
         }
 
@@ -174,7 +779,7 @@ pub unsafe extern "C" fn pczt_transaction_request_set_target_height(
     target_height: u32,
 ) -> ResultCode {
     if request.is_null() {
-        set_last_error(FfiError::NullPointer);
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
@@ -186,15 +791,17 @@ pub unsafe extern "C" fn pczt_transaction_request_set_target_height(
 /// Sets whether to use mainnet parameters for consensus branch ID
 ///
 /// By default, the library uses mainnet parameters. Set this to false for testnet.
-/// Regtest networks (like Zebra's regtest) typically use mainnet-like branch IDs,
-/// so keep the default (true) for regtest.
+///
+/// Deprecated: use `pczt_transaction_request_set_network`, which also covers regtest.
+/// A network set there takes precedence over this flag.
 #[no_mangle]
+#[allow(deprecated)] // Sets the deprecated use_mainnet field for existing callers
 pub unsafe extern "C" fn pczt_transaction_request_set_use_mainnet(
     request: *mut TransactionRequestHandle,
     use_mainnet: bool,
 ) -> ResultCode {
     if request.is_null() {
-        set_last_error(FfiError::NullPointer);
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
@@ -203,309 +810,2337 @@ pub unsafe extern "C" fn pczt_transaction_request_set_use_mainnet(
     ResultCode::Success
 }
 
-/// Proposes a new transaction using serialized input bytes
+/// Sets the network a transaction request is built for
+///
+/// Selects the consensus branch ID for the target height. Regtest assumes every
+/// upgrade through NU6 is active from height 1; other activation heights can be
+/// given in the `network` field of a JSON request.
 #[no_mangle]
-pub unsafe extern "C" fn pczt_propose_transaction(
-    inputs_bytes: *const u8,
-    inputs_bytes_len: usize,
-    request: *const TransactionRequestHandle,
-    change_address: *const c_char,  // nullable
-    pczt_out: *mut *mut PcztHandle,
+pub unsafe extern "C" fn pczt_transaction_request_set_network(
+    request: *mut TransactionRequestHandle,
+    network: CNetwork,
 ) -> ResultCode {
-    if inputs_bytes.is_null() || request.is_null() || pczt_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let inputs_slice = slice::from_raw_parts(inputs_bytes, inputs_bytes_len);
-    let tx_request = &*(request as *const TransactionRequest);
-
-    // Parse optional change address
-    let change_addr = if change_address.is_null() {
-        None
-    } else {
-        match CStr::from_ptr(change_address).to_str() {
-            Ok(s) => Some(s.to_string()),
-            Err(_) => {
-                set_last_error(FfiError::InvalidUtf8);
-                return ResultCode::ErrorInvalidUtf8;
-            }
-        }
-    };
-
-    match propose_transaction(inputs_slice, tx_request.clone(), change_addr) {
-        Ok(pczt) => {
-            let boxed_pczt = Box::new(pczt);
-            *pczt_out = Box::into_raw(boxed_pczt) as *mut PcztHandle;
-            ResultCode::Success
-        }
-        Err(e) => {
-            set_last_error(FfiError::Proposal(e));
-            ResultCode::ErrorProposal
-        }
-    }
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.network = Some(match network {
+        CNetwork::NetworkMainnet => Network::Mainnet,
+        CNetwork::NetworkTestnet => Network::Testnet,
+        CNetwork::NetworkRegtest => Network::Regtest(RegtestActivations::default()),
+    });
+    ResultCode::Success
 }
 
-/// Adds proofs to a PCZT.
+/// Sets the height after which the transaction can no longer be mined
 ///
-/// # Ownership
-/// This function ALWAYS consumes the input PCZT handle, even on error.
-/// On success, `pczt_out` contains the new PCZT with proofs.
-/// On error, the input handle is invalidated and cannot be reused.
+/// 0 disables expiry. Otherwise the height must be at least the target height.
+/// Overrides any expiry delta.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_expiry_height(
+    request: *mut TransactionRequestHandle,
+    expiry_height: u32,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.expiry_height = Some(expiry_height);
+    ResultCode::Success
+}
+
+/// Sets how many blocks after the target height the transaction expires (default 40)
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_expiry_delta(
+    request: *mut TransactionRequestHandle,
+    expiry_delta: u32,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.expiry_delta = Some(expiry_delta);
+    ResultCode::Success
+}
+
+/// Sets the fee tolerance for a transaction request
+///
+/// This is the maximum amount (in zatoshis) by which the implicit fee of a proposal
+/// may exceed the ZIP-317 fee. Defaults to 0.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_fee_tolerance(
+    request: *mut TransactionRequestHandle,
+    fee_tolerance: u64,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.fee_tolerance = fee_tolerance;
+    ResultCode::Success
+}
+
+/// Sets the highest fee (in zatoshis) a proposal may pay, or removes the cap with 0
+///
+/// Proposals whose fee exceeds it fail with `FEE_CAP_EXCEEDED`; the message carries
+/// the computed fee.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_max_fee(
+    request: *mut TransactionRequestHandle,
+    max_fee: u64,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.max_fee = if max_fee == 0 { None } else { Some(max_fee) };
+    ResultCode::Success
+}
+
+/// Sets the threshold below which change is added to the fee instead of getting an output
+///
+/// Same as `pczt_transaction_request_set_dust_policy` with `DustPolicyAddChangeToFee`.
+/// Defaults to the P2PKH dust threshold (see `pczt_p2pkh_dust_threshold`).
+/// A threshold of 0 always keeps the change output.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_change_dust_threshold(
+    request: *mut TransactionRequestHandle,
+    threshold: u64,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.dust_policy = DustPolicy::AddChangeToFee { threshold };
+    ResultCode::Success
+}
+
+/// Sets how transparent payments and change below `threshold` zatoshis are handled
+///
+/// Wallets conventionally use 5460 zatoshis (`DEFAULT_DUST_THRESHOLD`). A threshold
+/// of 0 makes nothing dust. Proposals that break the policy fail with
+/// `DUST_PAYMENT` or `DUST_CHANGE`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_dust_policy(
+    request: *mut TransactionRequestHandle,
+    policy: CDustPolicy,
+    threshold: u64,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.dust_policy = match policy {
+        CDustPolicy::DustPolicyAddChangeToFee => DustPolicy::AddChangeToFee { threshold },
+        CDustPolicy::DustPolicyReject => DustPolicy::Reject { threshold },
+    };
+    ResultCode::Success
+}
+
+/// Sets how the fee is derived from the ZIP-317 fee (the ZIP-317 fee itself by default)
+///
+/// `fixed_fee` is used by `FeePolicyFixedZatoshis` and `multiplier` by
+/// `FeePolicyZip317WithMultiplier`; the other is ignored. Proposals reject a
+/// multiplier below 1 with `INVALID_REQUEST`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_fee_policy(
+    request: *mut TransactionRequestHandle,
+    policy: CFeePolicy,
+    fixed_fee: u64,
+    multiplier: f64,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.fee_policy = match policy {
+        CFeePolicy::FeePolicyZip317Standard => FeePolicy::Zip317Standard,
+        CFeePolicy::FeePolicyFixedZatoshis => FeePolicy::FixedZatoshis(fixed_fee),
+        CFeePolicy::FeePolicyZip317WithMultiplier => FeePolicy::Zip317WithMultiplier(multiplier),
+    };
+    ResultCode::Success
+}
+
+/// Sets how payment memos are encoded (padded plain text by default)
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_memo_encoding(
+    request: *mut TransactionRequestHandle,
+    encoding: CMemoEncoding,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.memo_encoding = match encoding {
+        CMemoEncoding::MemoPadded => MemoEncoding::Padded,
+        CMemoEncoding::MemoCompressed => MemoEncoding::Compressed,
+    };
+    ResultCode::Success
+}
+
+/// Sets how inputs are picked from the UTXOs passed to `pczt_propose_transaction`
+///
+/// Defaults to spending every input. With any other strategy the inputs are treated
+/// as a pool, and inputs short of `min_confirmations` are skipped instead of refused.
+/// Experimental (see `experimental`): the strategies may still change.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_experimental_transaction_request_set_coin_selection(
+    request: *mut TransactionRequestHandle,
+    strategy: CCoinSelection,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.coin_selection = match strategy {
+        CCoinSelection::CoinSelectionAll => CoinSelection::All,
+        CCoinSelection::CoinSelectionLargestFirst => CoinSelection::LargestFirst,
+        CCoinSelection::CoinSelectionSmallestFirst => CoinSelection::SmallestFirst,
+        CCoinSelection::CoinSelectionBranchAndBound => CoinSelection::BranchAndBound,
+    };
+    ResultCode::Success
+}
+
+/// Turns sweep mode on or off (off by default)
+///
+/// A sweep spends every input and pays the request's single payment everything left
+/// after the fee, without change; the payment's amount is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_sweep(
+    request: *mut TransactionRequestHandle,
+    sweep: bool,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.sweep = sweep;
+    ResultCode::Success
+}
+
+/// Sets which pool the change output goes to
+///
+/// Defaults to transparent. Orchard change requires a unified change address with
+/// an Orchard receiver, passed to `pczt_propose_transaction`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_change_policy(
+    request: *mut TransactionRequestHandle,
+    policy: CChangePolicy,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.change_policy = match policy {
+        CChangePolicy::ChangePolicyTransparent => ChangePolicy::Transparent,
+        CChangePolicy::ChangePolicyOrchard => ChangePolicy::Orchard,
+        CChangePolicy::ChangePolicyAuto => ChangePolicy::Auto,
+    };
+    ResultCode::Success
+}
+
+/// Sets the flags of the proposal's Orchard bundle
+///
+/// Pass NULL to keep the builder's default (spends and outputs enabled). Transactions
+/// funded only from transparent inputs can disable spends.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_orchard_flags(
+    request: *mut TransactionRequestHandle,
+    flags: *const COrchardFlags,  // nullable
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.orchard_flags = flags.as_ref().map(|flags| OrchardFlags {
+        spends_enabled: flags.spends_enabled,
+        outputs_enabled: flags.outputs_enabled,
+    });
+    ResultCode::Success
+}
+
+/// Sets the default Orchard outgoing viewing key for shielded payments
+///
+/// Pass NULL to clear it. Individual payments can override it with
+/// `pczt_transaction_request_set_payment_ovk`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_ovk(
+    request: *mut TransactionRequestHandle,
+    ovk: *const [u8; 32],  // nullable
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.ovk = if ovk.is_null() { None } else { Some(*ovk) };
+    ResultCode::Success
+}
+
+/// Sets the Orchard outgoing viewing key for a single payment
+///
+/// Pass NULL to fall back to the request's default OVK.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_payment_ovk(
+    request: *mut TransactionRequestHandle,
+    payment_index: usize,
+    ovk: *const [u8; 32],  // nullable
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    let Some(payment) = tx_request.payments.get_mut(payment_index) else {
+        set_last_error!(FfiError::Proposal(ProposalError::InvalidRequest(
+            format!("Invalid payment index: {}", payment_index)
+        )));
+        return ResultCode::ErrorProposal;
+    };
+    payment.ovk = if ovk.is_null() { None } else { Some(*ovk) };
+    ResultCode::Success
+}
+
+/// Makes a payment pay to a raw scriptPubKey instead of its address
+///
+/// The payment's address is cleared. The script must be a standard template the
+/// builder supports (P2PKH or P2SH); others fail at proposal time.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_payment_script(
+    request: *mut TransactionRequestHandle,
+    payment_index: usize,
+    script: *const u8,
+    script_len: usize,
+) -> ResultCode {
+    if request.is_null() || script.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    let Some(payment) = tx_request.payments.get_mut(payment_index) else {
+        set_last_error!(FfiError::Proposal(ProposalError::InvalidRequest(
+            format!("Invalid payment index: {}", payment_index)
+        )));
+        return ResultCode::ErrorProposal;
+    };
+    payment.address.clear();
+    payment.script_pubkey = Some(slice::from_raw_parts(script, script_len).to_vec());
+    ResultCode::Success
+}
+
+/// Gives a payment a raw memo of up to 512 bytes, stored as-is
+///
+/// Replaces the payment's text memo. Unlike text memos, the bytes need not be UTF-8,
+/// so arbitrary ZIP-302 memos are never dropped. Longer memos fail with `ErrorMemo`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_payment_memo_bytes(
+    request: *mut TransactionRequestHandle,
+    payment_index: usize,
+    memo: *const u8,
+    memo_len: usize,
+) -> ResultCode {
+    if request.is_null() || memo.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+    if memo_len > MEMO_SIZE {
+        set_last_error!(FfiError::Memo(MemoError::TooLong { len: memo_len, max: MEMO_SIZE }));
+        return ResultCode::ErrorMemo;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    let Some(payment) = tx_request.payments.get_mut(payment_index) else {
+        set_last_error!(FfiError::Proposal(ProposalError::InvalidRequest(
+            format!("Invalid payment index: {}", payment_index)
+        )));
+        return ResultCode::ErrorProposal;
+    };
+    payment.memo = None;
+    payment.memo_bytes = Some(slice::from_raw_parts(memo, memo_len).to_vec());
+    ResultCode::Success
+}
+
+/// Sets the minimum confirmations each input must have (0 = no check)
+///
+/// Requires a target height and input heights (inputs format v2).
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_min_confirmations(
+    request: *mut TransactionRequestHandle,
+    min_confirmations: u32,
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    tx_request.min_confirmations = min_confirmations;
+    ResultCode::Success
+}
+
+/// Sets the operator label carried into the PCZT (see `transaction_label`)
+///
+/// Pass NULL to clear it.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_set_label(
+    request: *mut TransactionRequestHandle,
+    label: *const c_char,  // nullable
+) -> ResultCode {
+    if request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &mut *(request as *mut TransactionRequest);
+    if label.is_null() {
+        tx_request.label = None;
+        return ResultCode::Success;
+    }
+    match CStr::from_ptr(label).to_str() {
+        Ok(label) => tx_request.label = Some(label.to_string()),
+        Err(_) => {
+            set_last_error!(FfiError::InvalidUtf8);
+            return ResultCode::ErrorInvalidUtf8;
+        }
+    }
+    ResultCode::Success
+}
+
+/// Creates a transaction request from a versioned JSON document
+///
+/// Unknown fields are ignored; documents with a newer schema version are rejected.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_from_json(
+    json: *const u8,
+    json_len: usize,
+    request_out: *mut *mut TransactionRequestHandle,
+) -> ResultCode {
+    if json.is_null() || request_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let json_str = match std::str::from_utf8(slice::from_raw_parts(json, json_len)) {
+        Ok(s) => s,
+        Err(_) => {
+            set_last_error!(FfiError::InvalidUtf8);
+            return ResultCode::ErrorInvalidUtf8;
+        }
+    };
+
+    match crate::json::request_from_json(json_str) {
+        Ok(request) => {
+            *request_out = Box::into_raw(Box::new(request)) as *mut TransactionRequestHandle;
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Encodes a transaction request as a versioned JSON document
+///
+/// The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_transaction_request_to_json(
+    request: *const TransactionRequestHandle,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if request.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_request = &*(request as *const TransactionRequest);
+    write_json_out(crate::json::request_to_json(tx_request), json_out, json_len_out);
+    ResultCode::Success
+}
+
+/// Writes JSON text to an output buffer to be freed with `pczt_free_bytes`
+unsafe fn write_json_out(json: String, json_out: *mut *mut u8, json_len_out: *mut usize) {
+    let len = json.len();
+    let mut boxed_bytes = json.into_bytes().into_boxed_slice();
+    *json_out = boxed_bytes.as_mut_ptr();
+    *json_len_out = len;
+    std::mem::forget(boxed_bytes); // Prevent deallocation
+}
+
+/// Proposes a new transaction using serialized input bytes
+#[cfg(feature = "proposal")]
+#[no_mangle]
+pub unsafe extern "C" fn pczt_propose_transaction(
+    inputs_bytes: *const u8,
+    inputs_bytes_len: usize,
+    request: *const TransactionRequestHandle,
+    change_address: *const c_char,  // nullable
+    pczt_out: *mut *mut PcztHandle,
+) -> ResultCode {
+    if inputs_bytes.is_null() || request.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let inputs_slice = slice::from_raw_parts(inputs_bytes, inputs_bytes_len);
+    let tx_request = &*(request as *const TransactionRequest);
+
+    // Parse optional change address
+    let change_addr = if change_address.is_null() {
+        None
+    } else {
+        match CStr::from_ptr(change_address).to_str() {
+            Ok(s) => Some(s.to_string()),
+            Err(_) => {
+                set_last_error!(FfiError::InvalidUtf8);
+                return ResultCode::ErrorInvalidUtf8;
+            }
+        }
+    };
+
+    match propose_transaction(inputs_slice, tx_request.clone(), change_addr) {
+        Ok(pczt) => {
+            *pczt_out = pczt_into_handle(pczt);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Proposal(e));
+            ResultCode::ErrorProposal
+        }
+    }
+}
+
+/// Proposes a new transaction whose change goes to an address picked at random
+///
+/// `change_addresses` holds `num_change_addresses` NUL-terminated strings. Callers that
+/// rotate in order track the index themselves and pass it to `pczt_propose_transaction`.
+#[cfg(feature = "proposal")]
+#[no_mangle]
+pub unsafe extern "C" fn pczt_propose_transaction_from_pool(
+    inputs_bytes: *const u8,
+    inputs_bytes_len: usize,
+    request: *const TransactionRequestHandle,
+    change_addresses: *const *const c_char,
+    num_change_addresses: usize,
+    pczt_out: *mut *mut PcztHandle,
+) -> ResultCode {
+    if inputs_bytes.is_null() || request.is_null() || change_addresses.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let mut addresses = Vec::with_capacity(num_change_addresses);
+    for &address in slice::from_raw_parts(change_addresses, num_change_addresses) {
+        if address.is_null() {
+            set_last_error!(FfiError::NullPointer);
+            return ResultCode::ErrorNullPointer;
+        }
+        match CStr::from_ptr(address).to_str() {
+            Ok(s) => addresses.push(s.to_string()),
+            Err(_) => {
+                set_last_error!(FfiError::InvalidUtf8);
+                return ResultCode::ErrorInvalidUtf8;
+            }
+        }
+    }
+
+    let inputs_slice = slice::from_raw_parts(inputs_bytes, inputs_bytes_len);
+    let tx_request = &*(request as *const TransactionRequest);
+
+    let result = ChangeAddressPool::random(addresses)
+        .and_then(|pool| propose_transaction_from_pool(inputs_slice, tx_request.clone(), &pool));
+    match result {
+        Ok(pczt) => {
+            *pczt_out = pczt_into_handle(pczt);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Proposal(e));
+            ResultCode::ErrorProposal
+        }
+    }
+}
+
+/// Proposes a new transaction from a JSON array of inputs
+///
+/// Same as `pczt_propose_transaction`, with the inputs given as UTF-8 JSON (see
+/// `parse_transparent_inputs_json`) instead of the binary format.
+#[cfg(feature = "proposal")]
+#[no_mangle]
+pub unsafe extern "C" fn pczt_propose_transaction_json(
+    inputs_json: *const u8,
+    inputs_json_len: usize,
+    request: *const TransactionRequestHandle,
+    change_address: *const c_char,  // nullable
+    pczt_out: *mut *mut PcztHandle,
+) -> ResultCode {
+    if inputs_json.is_null() || request.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let json_str = match std::str::from_utf8(slice::from_raw_parts(inputs_json, inputs_json_len)) {
+        Ok(s) => s,
+        Err(_) => {
+            set_last_error!(FfiError::InvalidUtf8);
+            return ResultCode::ErrorInvalidUtf8;
+        }
+    };
+
+    let inputs = match parse_transparent_inputs_json(json_str) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            set_last_error!(FfiError::Proposal(ProposalError::InvalidRequest(
+                format!("Failed to parse inputs: {}", e)
+            )));
+            return ResultCode::ErrorProposal;
+        }
+    };
+    let inputs_bytes = match serialize_transparent_inputs(&inputs) {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            return ResultCode::ErrorParse;
+        }
+    };
+
+    pczt_propose_transaction(inputs_bytes.as_ptr(), inputs_bytes.len(), request, change_address, pczt_out)
+}
+
+/// Stores proving parameters as files in `dir` (default: the Zcash params directory)
+#[no_mangle]
+pub unsafe extern "C" fn pczt_set_params_dir(dir: *const c_char) -> ResultCode {
+    if dir.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let dir = match CStr::from_ptr(dir).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_last_error!(FfiError::InvalidUtf8);
+            return ResultCode::ErrorInvalidUtf8;
+        }
+    };
+
+    params::set_params_store(std::sync::Arc::new(params::FilesystemParamsStore::new(dir)));
+    ResultCode::Success
+}
+
+/// Keeps proving parameters in memory only, for hosts without writable storage
+#[no_mangle]
+pub extern "C" fn pczt_use_memory_params_store() -> ResultCode {
+    params::set_params_store(std::sync::Arc::new(params::MemoryParamsStore::new()));
+    ResultCode::Success
+}
+
+/// Builds the Orchard proving key now, so the first `pczt_prove_transaction` doesn't
+/// stall for several seconds. Calling it again is a no-op.
+#[no_mangle]
+pub extern "C" fn pczt_init_proving_keys() -> ResultCode {
+    match crate::init_proving_keys() {
+        Ok(()) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Prover(e));
+            ResultCode::ErrorProver
+        }
+    }
+}
+
+/// Drops the Orchard verifying key that `pczt_finalize_and_extract` caches between
+/// calls; the next shielded extraction builds it again
+#[no_mangle]
+pub extern "C" fn pczt_unload_verifying_keys() -> ResultCode {
+    crate::unload_verifying_keys();
+    ResultCode::Success
+}
+
+/// Sets how many threads proving may use (0 = no limit, the default unless the
+/// library was built with `low-memory`); see `set_prover_threads`
+#[no_mangle]
+pub extern "C" fn pczt_set_prover_threads(threads: usize) -> ResultCode {
+    crate::set_prover_threads(threads);
+    ResultCode::Success
+}
+
+/// Starts a background prover service that keeps the proving key resident
+///
+/// `workers` threads prove concurrently; up to `queue_capacity` PCZTs may wait.
+/// Stop it with `pczt_prover_service_stop`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prover_service_start(
+    workers: usize,
+    queue_capacity: usize,
+    service_out: *mut *mut ProverServiceHandle,
+) -> ResultCode {
+    if service_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let service = ProverService::start(ProverServiceConfig { workers, queue_capacity });
+    *service_out = Box::into_raw(Box::new(service)) as *mut ProverServiceHandle;
+    ResultCode::Success
+}
+
+/// Queues a PCZT for proving; `callback` is called on a worker thread when done
+///
+/// Blocks while the queue is full. If `job_id_out` is not null, it receives the job ID
+/// for `pczt_prover_service_status`.
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prover_service_submit(
+    service: *const ProverServiceHandle,
+    pczt: *mut PcztHandle,
+    priority: CProofPriority,
+    callback: Option<ProofCallbackFn>,
+    user_data: *mut c_void,
+    job_id_out: *mut u64,
+) -> ResultCode {
+    if service.is_null() || pczt.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+    let rust_pczt = pczt_from_handle(pczt);
+    let Some(callback) = callback else {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    };
+
+    let service = &*(service as *const ProverService);
+
+    // The host guarantees user_data may be used from the worker thread
+    struct UserData(*mut c_void);
+    unsafe impl Send for UserData {}
+    let user_data = UserData(user_data);
+
+    let on_done = Box::new(move |result: Result<Pczt, ProverError>| {
+        let user_data = user_data;
+        match result {
+            Ok(proved) => {
+                let handle = pczt_into_handle(proved);
+                callback(user_data.0, ResultCode::Success, handle);
+            }
+            Err(e) => {
+                set_last_error!(FfiError::Prover(e));
+                callback(user_data.0, ResultCode::ErrorProver, ptr::null_mut());
+            }
+        }
+    });
+
+    match service.submit_with_callback(rust_pczt, priority.into(), on_done) {
+        Ok(job_id) => {
+            if !job_id_out.is_null() {
+                *job_id_out = job_id;
+            }
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Prover(e));
+            ResultCode::ErrorProver
+        }
+    }
+}
+
+/// Gets the number of PCZTs waiting in each priority lane
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prover_service_queue_depth(
+    service: *const ProverServiceHandle,
+    high_out: *mut usize,
+    normal_out: *mut usize,
+    low_out: *mut usize,
+) -> ResultCode {
+    if service.is_null() || high_out.is_null() || normal_out.is_null() || low_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let service = &*(service as *const ProverService);
+    let [high, normal, low] = service.queue_depth();
+    *high_out = high;
+    *normal_out = normal;
+    *low_out = low;
+    ResultCode::Success
+}
+
+/// Gets the progress of a submitted PCZT
+///
+/// For queued PCZTs, `position_out` receives the number of PCZTs that will be taken
+/// before it; otherwise it is set to 0.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prover_service_status(
+    service: *const ProverServiceHandle,
+    job_id: u64,
+    status_out: *mut CProofStatus,
+    position_out: *mut usize,
+) -> ResultCode {
+    if service.is_null() || status_out.is_null() || position_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let service = &*(service as *const ProverService);
+    let (status, position) = match service.status(job_id) {
+        Some(ProofStatus::Queued { position, .. }) => (CProofStatus::ProofStatusQueued, position),
+        Some(ProofStatus::Proving) => (CProofStatus::ProofStatusProving, 0),
+        Some(ProofStatus::Finished) => (CProofStatus::ProofStatusFinished, 0),
+        None => {
+            set_last_error!(FfiError::Prover(ProverError::UnknownJob(job_id)));
+            return ResultCode::ErrorProver;
+        }
+    };
+    *status_out = status;
+    *position_out = position;
+    ResultCode::Success
+}
+
+/// Stops a prover service after finishing its queued PCZTs, and frees it
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prover_service_stop(service: *mut ProverServiceHandle) {
+    if !service.is_null() {
+        Box::from_raw(service as *mut ProverService).stop();
+    }
+}
+
+/// Adds proofs to a PCZT.
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+/// On success, `pczt_out` contains the new PCZT with proofs.
+/// On error, the input handle is invalidated and cannot be reused.
+///
+/// If you need to retry on failure, call `pczt_serialize()` before this
+/// function to create a backup that can be restored with `pczt_parse()`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prove_transaction(
+    pczt: *mut PcztHandle,
+    pczt_out: *mut *mut PcztHandle,
+) -> ResultCode {
+    if pczt.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = pczt_from_handle(pczt);
+
+    match prove_transaction(rust_pczt) {
+        Ok(proved_pczt) => {
+            *pczt_out = pczt_into_handle(proved_pczt);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Prover(e));
+            ResultCode::ErrorProver
+        }
+    }
+}
+
+/// Adds proofs to many PCZTs in parallel (see `prove_transactions`).
+///
+/// # Ownership
+/// This function ALWAYS consumes every input handle, even on error.
+/// `pczts_out[i]` receives the proved PCZT for `pczts[i]`, or null if proving it
+/// failed, and `results_out[i]` its result code. Both arrays hold `num_pczts`
+/// entries; `results_out` may be null.
+///
+/// # Returns
+/// * `ResultCode::Success` if every PCZT was proved
+/// * `ResultCode::ErrorProver` if any failed (the last error describes the first failure)
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prove_batch(
+    pczts: *const *mut PcztHandle,
+    num_pczts: usize,
+    pczts_out: *mut *mut PcztHandle,
+    results_out: *mut ResultCode,  // nullable
+) -> ResultCode {
+    if pczts.is_null() || pczts_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    // Take ownership of every handle before checking for nulls, so none leaks
+    let pczt_ptrs = slice::from_raw_parts(pczts, num_pczts);
+    let rust_pczts: Vec<Pczt> = pczt_ptrs.iter()
+        .filter(|ptr| !ptr.is_null())
+        .map(|&ptr| pczt_from_handle(ptr))
+        .collect();
+    if rust_pczts.len() != num_pczts {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let outputs = slice::from_raw_parts_mut(pczts_out, num_pczts);
+    let mut first_error = None;
+    for (i, result) in prove_transactions(rust_pczts).into_iter().enumerate() {
+        let code = match result {
+            Ok(proved) => {
+                outputs[i] = pczt_into_handle(proved);
+                ResultCode::Success
+            }
+            Err(e) => {
+                outputs[i] = ptr::null_mut();
+                first_error.get_or_insert(e);
+                ResultCode::ErrorProver
+            }
+        };
+        if !results_out.is_null() {
+            *results_out.add(i) = code;
+        }
+    }
+
+    match first_error {
+        None => ResultCode::Success,
+        Some(e) => {
+            set_last_error!(FfiError::Prover(e));
+            ResultCode::ErrorProver
+        }
+    }
+}
+
+/// Adds Orchard proofs like `pczt_prove_transaction`, for hosts that call it on a
+/// worker thread (e.g. Node's libuv pool through koffi's async calls)
+///
+/// The last error is per thread, so a host can't read it after the worker returns.
+/// On failure, the message and the stable error code are written to `message_buf`
+/// and `code_buf` instead (NUL-terminated, truncated to fit; either may be null).
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_prove_transaction_on_worker(
+    pczt: *mut PcztHandle,
+    pczt_out: *mut *mut PcztHandle,
+    message_buf: *mut c_char,
+    message_len: usize,
+    code_buf: *mut c_char,
+    code_len: usize,
+) -> ResultCode {
+    let result = pczt_prove_transaction(pczt, pczt_out);
+    if result != ResultCode::Success {
+        write_last_error_to(message_buf, message_len, code_buf, code_len);
+    }
+    result
+}
+
+/// Copies the calling thread's last error into the buffers of an `_on_worker` call
+unsafe fn write_last_error_to(message_buf: *mut c_char, message_len: usize, code_buf: *mut c_char, code_len: usize) {
+    LAST_ERROR.with(|e| {
+        if let Some(ref last_error) = *e.borrow() {
+            write_truncated(message_buf, message_len, &last_error.message);
+            write_truncated(code_buf, code_len, last_error.error_code.as_str());
+        }
+    });
+}
+
+/// Copies `text` into a C buffer as a NUL-terminated string, cut at a char boundary to fit
+unsafe fn write_truncated(buffer: *mut c_char, buffer_len: usize, text: &str) {
+    if buffer.is_null() || buffer_len == 0 {
+        return;
+    }
+    let mut end = text.len().min(buffer_len - 1);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    ptr::copy_nonoverlapping(text.as_ptr() as *const c_char, buffer, end);
+    *buffer.add(end) = 0;
+}
+
+/// Verifies the PCZT before signing
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_before_signing(
+    pczt: *const PcztHandle,
+    request: *const TransactionRequestHandle,
+    expected_change: *const CTransparentOutput,
+    expected_change_len: usize,
+) -> ResultCode {
+    if pczt.is_null() || request.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_request = &*(request as *const TransactionRequest);
+    let change_outputs = match expected_change_outputs(expected_change, expected_change_len) {
+        Ok(outputs) => outputs,
+        Err(code) => return code,
+    };
+
+    match verify_before_signing(rust_pczt, tx_request, &change_outputs) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Runs every pre-signing check and writes all failures as a JSON document
+/// (`json::VerificationReportJson`) to be freed with `pczt_free_bytes`.
+///
+/// Succeeds whenever the checks could run; a report with errors in it is not a
+/// failure of this call.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_report(
+    pczt: *const PcztHandle,
+    request: *const TransactionRequestHandle,
+    expected_change: *const CTransparentOutput,
+    expected_change_len: usize,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || request.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_request = &*(request as *const TransactionRequest);
+    let change_outputs = match expected_change_outputs(expected_change, expected_change_len) {
+        Ok(outputs) => outputs,
+        Err(code) => return code,
+    };
+
+    let report = verify_report(rust_pczt, tx_request, &change_outputs);
+    write_json_out(crate::json::verification_report_to_json(&report), json_out, json_len_out);
+    ResultCode::Success
+}
+
+/// Parses the expected change outputs passed to the verification functions
+unsafe fn expected_change_outputs(
+    expected_change: *const CTransparentOutput,
+    expected_change_len: usize,
+) -> Result<Vec<zcash_transparent::bundle::TxOut>, ResultCode> {
+    let mut change_outputs = Vec::new();
+    if !expected_change.is_null() && expected_change_len > 0 {
+        let change_slice = slice::from_raw_parts(expected_change, expected_change_len);
+
+        for c_output in change_slice {
+            if c_output.script_pub_key.is_null() {
+                set_last_error!(FfiError::NullPointer);
+                return Err(ResultCode::ErrorNullPointer);
+            }
+
+            let script_bytes = slice::from_raw_parts(
+                c_output.script_pub_key,
+                c_output.script_pub_key_len
+            );
+
+            let script = match script_from_raw(script_bytes) {
+                Ok(s) => s,
+                Err(_) => {
+                    set_last_error!(FfiError::Verification(
+                        crate::error::VerificationFailure::OutputMismatch("Invalid script".to_string())
+                    ));
+                    return Err(ResultCode::ErrorVerification);
+                }
+            };
+
+            let value = match zcash_protocol::value::Zatoshis::from_u64(c_output.value) {
+                Ok(v) => v,
+                Err(_) => {
+                    set_last_error!(FfiError::Verification(
+                        crate::error::VerificationFailure::OutputMismatch("Invalid value".to_string())
+                    ));
+                    return Err(ResultCode::ErrorVerification);
+                }
+            };
+
+            change_outputs.push(zcash_transparent::bundle::TxOut::new(value, script));
+        }
+    }
+    Ok(change_outputs)
+}
+
+/// Verifies that a serialized final transaction matches the PCZT it was extracted from
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_extraction(
+    pczt: *const PcztHandle,
+    tx_bytes: *const u8,
+    tx_bytes_len: usize,
+) -> ResultCode {
+    if pczt.is_null() || tx_bytes.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_slice = slice::from_raw_parts(tx_bytes, tx_bytes_len);
+
+    match verify_extraction(rust_pczt, tx_slice) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Exports a review document (JSON) summarizing a proposal for four-eyes approval
+///
+/// Display amounts are rendered in `unit`. The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_export_review(
+    pczt: *const PcztHandle,
+    request: *const TransactionRequestHandle,
+    unit: CAmountUnit,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || request.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_request = &*(request as *const TransactionRequest);
+
+    match export_review_with_unit(rust_pczt, tx_request, unit.into()) {
+        Ok(document) => {
+            let json = serde_json::to_vec(&document).expect("review document serializes");
+            let len = json.len();
+            let mut boxed_bytes = json.into_boxed_slice();
+            *json_out = boxed_bytes.as_mut_ptr();
+            *json_len_out = len;
+            std::mem::forget(boxed_bytes); // Prevent deallocation
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Verifies that an approved review document (JSON) still matches the proposal
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_review(
+    pczt: *const PcztHandle,
+    request: *const TransactionRequestHandle,
+    json: *const u8,
+    json_len: usize,
+) -> ResultCode {
+    if pczt.is_null() || request.is_null() || json.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_request = &*(request as *const TransactionRequest);
+    let json_slice = slice::from_raw_parts(json, json_len);
+
+    let document: ReviewDocument = match serde_json::from_slice(json_slice) {
+        Ok(document) => document,
+        Err(e) => {
+            set_last_error!(FfiError::Parse(ParseError::InvalidFormat(
+                format!("Invalid review document: {}", e)
+            )));
+            return ResultCode::ErrorParse;
+        }
+    };
+
+    match verify_review(rust_pczt, tx_request, &document) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Exports an amount-blinded review (JSON) for an approver who may not see individual
+/// shielded amounts or memos
+///
+/// Display totals are rendered in `unit`. The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_export_blinded_review(
+    pczt: *const PcztHandle,
+    request: *const TransactionRequestHandle,
+    unit: CAmountUnit,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || request.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_request = &*(request as *const TransactionRequest);
+
+    match export_blinded_review(rust_pczt, tx_request, unit.into()) {
+        Ok(review) => {
+            let json = serde_json::to_vec(&review).expect("blinded review serializes");
+            let len = json.len();
+            let mut boxed_bytes = json.into_boxed_slice();
+            *json_out = boxed_bytes.as_mut_ptr();
+            *json_len_out = len;
+            std::mem::forget(boxed_bytes); // Prevent deallocation
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Verifies that an approved blinded review (JSON) still matches the proposal
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_blinded_review(
+    pczt: *const PcztHandle,
+    request: *const TransactionRequestHandle,
+    json: *const u8,
+    json_len: usize,
+) -> ResultCode {
+    if pczt.is_null() || request.is_null() || json.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let tx_request = &*(request as *const TransactionRequest);
+    let json_slice = slice::from_raw_parts(json, json_len);
+
+    let review: BlindedReview = match serde_json::from_slice(json_slice) {
+        Ok(review) => review,
+        Err(e) => {
+            set_last_error!(FfiError::Parse(ParseError::InvalidFormat(
+                format!("Invalid blinded review: {}", e)
+            )));
+            return ResultCode::ErrorParse;
+        }
+    };
+
+    match verify_blinded_review(rust_pczt, tx_request, &review) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Exports a manifest (JSON) of a PCZT's inputs, outputs, fee and fingerprint, signed
+/// with the operator's 32-byte Ed25519 secret key
+///
+/// The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_sign_manifest(
+    pczt: *const PcztHandle,
+    signing_key: *const [u8; 32],
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || signing_key.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+
+    match manifest::sign_manifest(rust_pczt, &*signing_key) {
+        Ok(signed) => {
+            let json = serde_json::to_string(&signed).expect("manifest serializes");
+            write_json_out(json, json_out, json_len_out);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Verifies that a PCZT matches a signed manifest (JSON) from the operator whose
+/// 32-byte Ed25519 public key is `trusted_key`
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_manifest(
+    pczt: *const PcztHandle,
+    json: *const u8,
+    json_len: usize,
+    trusted_key: *const [u8; 32],
+) -> ResultCode {
+    if pczt.is_null() || json.is_null() || trusted_key.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let json_slice = slice::from_raw_parts(json, json_len);
+
+    let signed: manifest::SignedManifest = match serde_json::from_slice(json_slice) {
+        Ok(signed) => signed,
+        Err(e) => {
+            set_last_error!(FfiError::Parse(ParseError::InvalidFormat(
+                format!("Invalid manifest: {}", e)
+            )));
+            return ResultCode::ErrorParse;
+        }
+    };
+
+    match manifest::verify_manifest(rust_pczt, &signed, &*trusted_key) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Summarizes a PCZT as a versioned JSON proposal document
+///
+/// The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_proposal_to_json(
+    pczt: *const PcztHandle,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    write_json_out(crate::json::proposal_to_json(rust_pczt), json_out, json_len_out);
+    ResultCode::Success
+}
+
+/// Summarizes a PCZT for a confirmation screen as a versioned JSON document
+/// (`TransactionSummary`) to be freed with `pczt_free_bytes`
+#[no_mangle]
+pub unsafe extern "C" fn pczt_summarize(
+    pczt: *const PcztHandle,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    write_json_out(crate::json::summary_to_json(&summarize_pczt(rust_pczt)), json_out, json_len_out);
+    ResultCode::Success
+}
+
+/// Decodes a serialized final transaction into a versioned JSON document
+///
+/// The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_decode_transaction_json(
+    tx_bytes: *const u8,
+    tx_bytes_len: usize,
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if tx_bytes.is_null() || json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_slice = slice::from_raw_parts(tx_bytes, tx_bytes_len);
+    match crate::json::decode_transaction_json(tx_slice) {
+        Ok(json) => {
+            write_json_out(json, json_out, json_len_out);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Renders a P2PKH or P2SH script as a transparent address, for confirmation UIs
+///
+/// The address is written to `buffer` as a NUL-terminated string. Other scripts
+/// fail with `ErrorParse`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_script_to_address(
+    script: *const u8,
+    script_len: usize,
+    use_mainnet: bool,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> ResultCode {
+    if script.is_null() || buffer.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let network = if use_mainnet { NetworkType::Main } else { NetworkType::Test };
+    let address = match script_to_address(slice::from_raw_parts(script, script_len), network) {
+        Some(address) => address,
+        None => {
+            set_last_error!(FfiError::Parse(ParseError::InvalidFormat(
+                "Script is not P2PKH or P2SH".to_string(),
+            )));
+            return ResultCode::ErrorParse;
+        }
+    };
+
+    // Encoded addresses are ASCII without interior NULs
+    let bytes = address.as_bytes();
+    if bytes.len() + 1 > buffer_len {
+        set_last_error!(FfiError::BufferTooSmall);
+        return ResultCode::ErrorBufferTooSmall;
+    }
+
+    ptr::copy_nonoverlapping(bytes.as_ptr() as *const c_char, buffer, bytes.len());
+    *buffer.add(bytes.len()) = 0;
+    ResultCode::Success
+}
+
+/// Returns the scriptPubKey a transparent address pays to
+///
+/// Writes at most `script_capacity` bytes to `script_out` and the script length to
+/// `script_len_out`. Scripts are at most 25 bytes.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_address_to_script(
+    address: *const c_char,
+    script_out: *mut u8,
+    script_capacity: usize,
+    script_len_out: *mut usize,
+) -> ResultCode {
+    if address.is_null() || script_out.is_null() || script_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let address = match CStr::from_ptr(address).to_str() {
+        Ok(s) => s,
+        Err(_) => {
+            set_last_error!(FfiError::InvalidUtf8);
+            return ResultCode::ErrorInvalidUtf8;
+        }
+    };
+
+    let script = match address_to_script(address) {
+        Some(script) => script,
+        None => {
+            set_last_error!(FfiError::Parse(ParseError::InvalidFormat(
+                format!("Not a transparent address: {}", address),
+            )));
+            return ResultCode::ErrorParse;
+        }
+    };
+
+    if script.len() > script_capacity {
+        set_last_error!(FfiError::BufferTooSmall);
+        return ResultCode::ErrorBufferTooSmall;
+    }
+
+    ptr::copy_nonoverlapping(script.as_ptr(), script_out, script.len());
+    *script_len_out = script.len();
+    ResultCode::Success
+}
+
+/// Gets the signature hash for an input
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_sighash(
+    pczt: *const PcztHandle,
+    input_index: usize,
+    sighash_out: *mut [u8; 32],
+) -> ResultCode {
+    if pczt.is_null() || sighash_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+
+    match get_sighash(rust_pczt, input_index) {
+        Ok(sighash) => {
+            *sighash_out = *sighash.as_bytes();
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Sighash(e));
+            ResultCode::ErrorSighash
+        }
+    }
+}
+
+/// Gets the sequence number of a transparent input
+///
+/// Inputs without an explicit sequence number report 0xFFFFFFFF (final).
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_input_sequence(
+    pczt: *const PcztHandle,
+    input_index: usize,
+    sequence_out: *mut u32,
+) -> ResultCode {
+    if pczt.is_null() || sequence_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+
+    match get_input_sequence(rust_pczt, input_index) {
+        Ok(sequence) => {
+            *sequence_out = sequence;
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Inspection(e));
+            ResultCode::ErrorInspection
+        }
+    }
+}
+
+/// Gets the compressed public key (33 bytes) that controls a transparent input
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_input_pubkey(
+    pczt: *const PcztHandle,
+    input_index: usize,
+    pubkey_out: *mut [u8; 33],
+) -> ResultCode {
+    if pczt.is_null() || pubkey_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+
+    match get_input_pubkey(rust_pczt, input_index) {
+        Ok(pubkey) => {
+            *pubkey_out = pubkey;
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Inspection(e));
+            ResultCode::ErrorInspection
+        }
+    }
+}
+
+/// Exports every input's sighash and the shared transaction context as one binary document
+///
+/// Fails with `ErrorParse` if an input count or script is too long for the document's
+/// format. The bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_export_sighash_batch(
+    pczt: *const PcztHandle,
+    bytes_out: *mut *mut u8,
+    bytes_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || bytes_out.is_null() || bytes_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+
+    let serialized = match export_sighash_batch(rust_pczt) {
+        Ok(batch) => batch.to_bytes(),
+        Err(e) => {
+            set_last_error!(FfiError::Sighash(e));
+            return ResultCode::ErrorSighash;
+        }
+    };
+    match serialized {
+        Ok(serialized) => {
+            let len = serialized.len();
+            let mut boxed_bytes = serialized.into_boxed_slice();
+            *bytes_out = boxed_bytes.as_mut_ptr();
+            *bytes_len_out = len;
+            std::mem::forget(boxed_bytes); // Prevent deallocation
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Checks that a sighash batch document is well-formed and internally consistent
+///
+/// On success, writes the number of inputs it covers to `num_inputs_out`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_validate_sighash_batch(
+    bytes: *const u8,
+    bytes_len: usize,
+    num_inputs_out: *mut usize,
+) -> ResultCode {
+    if bytes.is_null() || num_inputs_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let data = slice::from_raw_parts(bytes, bytes_len);
+
+    match SighashBatch::from_bytes(data) {
+        Ok(batch) => {
+            *num_inputs_out = batch.inputs.len();
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Gets the message (32 bytes) to sign with an input key to prove a change output is the proposer's
+#[no_mangle]
+pub unsafe extern "C" fn pczt_change_ownership_message(
+    pczt: *const PcztHandle,
+    output_index: usize,
+    message_out: *mut [u8; 32],
+) -> ResultCode {
+    if pczt.is_null() || message_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+
+    match change_ownership_message(rust_pczt, output_index) {
+        Ok(message) => {
+            *message_out = message;
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Inspection(e));
+            ResultCode::ErrorInspection
+        }
+    }
+}
+
+/// Checks that a PCZT received from another party is internally consistent (see `validate_pczt`)
+#[no_mangle]
+pub unsafe extern "C" fn pczt_validate(pczt: *const PcztHandle) -> ResultCode {
+    if pczt.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    match validate_pczt(rust_pczt) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Verifies that a transparent change output was endorsed by the key of `input_index`
+#[no_mangle]
+pub unsafe extern "C" fn pczt_verify_change_ownership(
+    pczt: *const PcztHandle,
+    output_index: usize,
+    input_index: usize,
+    signature: *const [u8; 64],
+) -> ResultCode {
+    if pczt.is_null() || signature.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let proof = ChangeOwnershipProof { output_index, input_index, signature: *signature };
+
+    match verify_change_ownership(rust_pczt, &proof) {
+        Ok(_) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::Verification(e));
+            ResultCode::ErrorVerification
+        }
+    }
+}
+
+/// Copies Orchard output accounting data into a caller-provided array
+///
+/// `outputs_len_out` always receives the number of outputs. If `capacity` is too
+/// small, nothing is copied and `ErrorBufferTooSmall` is returned.
+unsafe fn write_orchard_outputs(
+    infos: &[OrchardOutputInfo],
+    outputs_out: *mut COrchardOutput,
+    capacity: usize,
+    outputs_len_out: *mut usize,
+) -> ResultCode {
+    *outputs_len_out = infos.len();
+    if infos.is_empty() {
+        return ResultCode::Success;
+    }
+    if outputs_out.is_null() || capacity < infos.len() {
+        set_last_error!(FfiError::BufferTooSmall);
+        return ResultCode::ErrorBufferTooSmall;
+    }
+
+    let out = slice::from_raw_parts_mut(outputs_out, infos.len());
+    for (slot, info) in out.iter_mut().zip(infos) {
+        *slot = COrchardOutput {
+            action_index: info.action_index,
+            cmx: info.cmx,
+            ephemeral_key: info.ephemeral_key,
+            has_value: info.value.is_some(),
+            value: info.value.unwrap_or(0),
+        };
+    }
+    ResultCode::Success
+}
+
+/// Gets the note commitment and ephemeral key of each Orchard output in a PCZT
+///
+/// Call with `capacity = 0` to query the number of outputs first.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_orchard_outputs(
+    pczt: *const PcztHandle,
+    outputs_out: *mut COrchardOutput,  // nullable when capacity is 0
+    capacity: usize,
+    outputs_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || outputs_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    write_orchard_outputs(&get_orchard_outputs(rust_pczt), outputs_out, capacity, outputs_len_out)
+}
+
+/// Gets the note commitment and ephemeral key of each Orchard action in a final transaction
+///
+/// Call with `capacity = 0` to query the number of outputs first.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_tx_get_orchard_outputs(
+    tx_bytes: *const u8,
+    tx_bytes_len: usize,
+    outputs_out: *mut COrchardOutput,  // nullable when capacity is 0
+    capacity: usize,
+    outputs_len_out: *mut usize,
+) -> ResultCode {
+    if tx_bytes.is_null() || outputs_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let tx_slice = slice::from_raw_parts(tx_bytes, tx_bytes_len);
+    match decode_orchard_outputs(tx_slice) {
+        Ok(infos) => write_orchard_outputs(&infos, outputs_out, capacity, outputs_len_out),
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Gets the fingerprint of the transaction a PCZT represents
+///
+/// The fingerprint covers transaction effects only (not signatures or proofs), so
+/// two parties can confirm they hold the same transaction before combining.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_fingerprint(
+    pczt: *const PcztHandle,
+    fingerprint_out: *mut [u8; 32],
+) -> ResultCode {
+    if pczt.is_null() || fingerprint_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *fingerprint_out = crate::pczt_fingerprint(rust_pczt);
+    ResultCode::Success
+}
+
+/// Stamps a fresh reservation token into a PCZT (see `reservation`).
+///
+/// The host locks the PCZT's inputs under the token written to `token_out`, and
+/// reads it back with `pczt_get_reservation_token` to release them.
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+/// On success, `pczt_out` contains the stamped PCZT.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_stamp_reservation(
+    pczt: *mut PcztHandle,
+    token_out: *mut [u8; 16],
+    pczt_out: *mut *mut PcztHandle,
+) -> ResultCode {
+    if pczt.is_null() || token_out.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = pczt_from_handle(pczt);
+    let token = crate::reservation::ReservationToken::generate();
+
+    match crate::reservation::stamp_reservation(rust_pczt, token) {
+        Ok(stamped) => {
+            *token_out = token.0;
+            *pczt_out = pczt_into_handle(stamped);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Reservation(e));
+            ResultCode::ErrorReservation
+        }
+    }
+}
+
+/// Reads the reservation token stamped into a PCZT.
+///
+/// `found_out` is set to false (and `token_out` left untouched) if the PCZT carries
+/// no token.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_reservation_token(
+    pczt: *const PcztHandle,
+    token_out: *mut [u8; 16],
+    found_out: *mut bool,
+) -> ResultCode {
+    if pczt.is_null() || token_out.is_null() || found_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    match crate::reservation::reservation_token(rust_pczt) {
+        Some(token) => {
+            *token_out = token.0;
+            *found_out = true;
+        }
+        None => *found_out = false,
+    }
+    ResultCode::Success
+}
+
+/// Gets the operator label a PCZT was proposed with
+///
+/// Writes NULL and a length of 0 if the PCZT has no label. Otherwise the UTF-8 bytes
+/// (not NUL-terminated) must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_label(
+    pczt: *const PcztHandle,
+    label_out: *mut *mut u8,
+    label_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || label_out.is_null() || label_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    match crate::transaction_label(rust_pczt) {
+        Some(label) => write_json_out(label, label_out, label_len_out),
+        None => {
+            *label_out = ptr::null_mut();
+            *label_len_out = 0;
+        }
+    }
+    ResultCode::Success
+}
+
+/// Appends a signature to the PCZT.
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+/// On success, `pczt_out` contains the new PCZT with the signature appended.
+/// On error, the input handle is invalidated and cannot be reused.
 ///
 /// If you need to retry on failure, call `pczt_serialize()` before this
 /// function to create a backup that can be restored with `pczt_parse()`.
 #[no_mangle]
-pub unsafe extern "C" fn pczt_prove_transaction(
+pub unsafe extern "C" fn pczt_append_signature(
     pczt: *mut PcztHandle,
+    input_index: usize,
+    signature: *const [u8; 64],
     pczt_out: *mut *mut PcztHandle,
 ) -> ResultCode {
-    if pczt.is_null() || pczt_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if pczt.is_null() || signature.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let rust_pczt = Box::from_raw(pczt as *mut Pczt);
+    let rust_pczt = pczt_from_handle(pczt);
+    let sig = *signature;
 
-    match prove_transaction(*rust_pczt) {
-        Ok(proved_pczt) => {
-            let boxed_pczt = Box::new(proved_pczt);
-            *pczt_out = Box::into_raw(boxed_pczt) as *mut PcztHandle;
+    match append_signature(rust_pczt, input_index, sig) {
+        Ok(signed_pczt) => {
+            *pczt_out = pczt_into_handle(signed_pczt);
             ResultCode::Success
         }
         Err(e) => {
-            set_last_error(FfiError::Prover(e));
-            ResultCode::ErrorProver
+            set_last_error!(FfiError::Signature(e));
+            ResultCode::ErrorSignature
         }
     }
 }
 
-/// Verifies the PCZT before signing
+/// Signs a transparent input with a 32-byte secp256k1 secret key.
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+/// On success, `pczt_out` contains the new PCZT with the signature added.
+/// On error, the input handle is invalidated and cannot be reused.
+///
+/// If you need to retry on failure, call `pczt_serialize()` before this
+/// function to create a backup that can be restored with `pczt_parse()`.
 #[no_mangle]
-pub unsafe extern "C" fn pczt_verify_before_signing(
-    pczt: *const PcztHandle,
-    request: *const TransactionRequestHandle,
-    expected_change: *const CTransparentOutput,
-    expected_change_len: usize,
+pub unsafe extern "C" fn pczt_sign_transparent(
+    pczt: *mut PcztHandle,
+    input_index: usize,
+    secret_key: *const [u8; 32],
+    pczt_out: *mut *mut PcztHandle,
 ) -> ResultCode {
-    if pczt.is_null() || request.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if pczt.is_null() || secret_key.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let rust_pczt = &*(pczt as *const Pczt);
-    let tx_request = &*(request as *const TransactionRequest);
+    let rust_pczt = pczt_from_handle(pczt);
 
-    // Parse expected change outputs
-    let mut change_outputs = Vec::new();
-    if !expected_change.is_null() && expected_change_len > 0 {
-        let change_slice = slice::from_raw_parts(expected_change, expected_change_len);
+    match sign_transaction(rust_pczt, input_index, &*secret_key) {
+        Ok(signed_pczt) => {
+            *pczt_out = pczt_into_handle(signed_pczt);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Signature(e));
+            ResultCode::ErrorSignature
+        }
+    }
+}
 
-        for c_output in change_slice {
-            if c_output.script_pub_key.is_null() {
-                set_last_error(FfiError::NullPointer);
-                return ResultCode::ErrorNullPointer;
-            }
+/// Finalizes and extracts the transaction.
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+/// On success, `tx_bytes_out` and `tx_bytes_len_out` contain the transaction bytes.
+/// On error, the input handle is invalidated and cannot be reused.
+///
+/// If you need to retry on failure, call `pczt_serialize()` before this
+/// function to create a backup that can be restored with `pczt_parse()`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_finalize_and_extract(
+    pczt: *mut PcztHandle,
+    tx_bytes_out: *mut *mut u8,
+    tx_bytes_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || tx_bytes_out.is_null() || tx_bytes_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
 
-            let script_bytes = slice::from_raw_parts(
-                c_output.script_pub_key,
-                c_output.script_pub_key_len
-            );
+    let rust_pczt = pczt_from_handle(pczt);
 
-            // Parse script with CompactSize prefix
-            use zcash_encoding::CompactSize;
-            let mut script_with_prefix = Vec::new();
-            if let Err(_) = CompactSize::write(&mut script_with_prefix, script_bytes.len()) {
-                set_last_error(FfiError::Verification(
-                    crate::error::VerificationFailure::OutputMismatch("Invalid script length".to_string())
-                ));
-                return ResultCode::ErrorVerification;
-            }
-            script_with_prefix.extend_from_slice(script_bytes);
+    match finalize_and_extract(rust_pczt) {
+        Ok(tx_bytes) => {
+            let len = tx_bytes.len();
+            let mut boxed_bytes = tx_bytes.into_boxed_slice();
+            *tx_bytes_out = boxed_bytes.as_mut_ptr();
+            *tx_bytes_len_out = len;
+            std::mem::forget(boxed_bytes); // Prevent deallocation
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Finalization(e));
+            ResultCode::ErrorFinalization
+        }
+    }
+}
 
-            let script = match zcash_transparent::address::Script::read(&script_with_prefix[..]) {
-                Ok(s) => s,
-                Err(_) => {
-                    set_last_error(FfiError::Verification(
-                        crate::error::VerificationFailure::OutputMismatch("Invalid script".to_string())
-                    ));
-                    return ResultCode::ErrorVerification;
-                }
-            };
+/// Finalizes and extracts like `pczt_finalize_and_extract`, into a library-owned
+/// buffer (see `ByteBufferHandle`)
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_finalize_and_extract_buffer(
+    pczt: *mut PcztHandle,
+    buffer_out: *mut *mut ByteBufferHandle,
+) -> ResultCode {
+    if pczt.is_null() || buffer_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
 
-            let value = match zcash_protocol::value::Zatoshis::from_u64(c_output.value) {
-                Ok(v) => v,
-                Err(_) => {
-                    set_last_error(FfiError::Verification(
-                        crate::error::VerificationFailure::OutputMismatch("Invalid value".to_string())
-                    ));
-                    return ResultCode::ErrorVerification;
-                }
-            };
+    let rust_pczt = pczt_from_handle(pczt);
 
-            change_outputs.push(zcash_transparent::bundle::TxOut::new(value, script));
+    match finalize_and_extract(rust_pczt) {
+        Ok(tx_bytes) => {
+            *buffer_out = Box::into_raw(Box::new(tx_bytes)) as *mut ByteBufferHandle;
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Finalization(e));
+            ResultCode::ErrorFinalization
         }
     }
+}
 
-    match verify_before_signing(rust_pczt, tx_request, &change_outputs) {
-        Ok(_) => ResultCode::Success,
+/// Finalizes and extracts like `pczt_finalize_and_extract`, for hosts that call it on
+/// a worker thread (see `pczt_prove_transaction_on_worker` for the error buffers)
+///
+/// # Ownership
+/// This function ALWAYS consumes the input PCZT handle, even on error.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_finalize_and_extract_on_worker(
+    pczt: *mut PcztHandle,
+    tx_bytes_out: *mut *mut u8,
+    tx_bytes_len_out: *mut usize,
+    message_buf: *mut c_char,
+    message_len: usize,
+    code_buf: *mut c_char,
+    code_len: usize,
+) -> ResultCode {
+    let result = pczt_finalize_and_extract(pczt, tx_bytes_out, tx_bytes_len_out);
+    if result != ResultCode::Success {
+        write_last_error_to(message_buf, message_len, code_buf, code_len);
+    }
+    result
+}
+
+/// Parses a PCZT from bytes
+#[no_mangle]
+pub unsafe extern "C" fn pczt_parse(
+    pczt_bytes: *const u8,
+    pczt_bytes_len: usize,
+    pczt_out: *mut *mut PcztHandle,
+) -> ResultCode {
+    if pczt_bytes.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let bytes = slice::from_raw_parts(pczt_bytes, pczt_bytes_len);
+
+    match parse_pczt(bytes) {
+        Ok(pczt) => {
+            *pczt_out = pczt_into_handle(pczt);
+            ResultCode::Success
+        }
         Err(e) => {
-            set_last_error(FfiError::Verification(e));
-            ResultCode::ErrorVerification
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
         }
     }
 }
 
-/// Gets the signature hash for an input
+/// Parses a PCZT from bytes with options
+///
+/// `options` may be null, in which case this behaves like `pczt_parse`.
 #[no_mangle]
-pub unsafe extern "C" fn pczt_get_sighash(
-    pczt: *const PcztHandle,
-    input_index: usize,
-    sighash_out: *mut [u8; 32],
+pub unsafe extern "C" fn pczt_parse_with_options(
+    pczt_bytes: *const u8,
+    pczt_bytes_len: usize,
+    options: *const CParseOptions,  // nullable
+    pczt_out: *mut *mut PcztHandle,
 ) -> ResultCode {
-    if pczt.is_null() || sighash_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if pczt_bytes.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let rust_pczt = &*(pczt as *const Pczt);
+    let bytes = slice::from_raw_parts(pczt_bytes, pczt_bytes_len);
+    let parse_options = if options.is_null() {
+        ParseOptions::default()
+    } else {
+        ParseOptions {
+            require_canonical: (*options).require_canonical,
+            reject_unknown_fields: (*options).reject_unknown_fields,
+        }
+    };
 
-    match get_sighash(rust_pczt, input_index) {
-        Ok(sighash) => {
-            *sighash_out = *sighash.as_bytes();
+    match parse_pczt_with_options(bytes, parse_options) {
+        Ok(pczt) => {
+            *pczt_out = pczt_into_handle(pczt);
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Recognizes a proprietary namespace for parsing with `reject_unknown_fields`
+#[no_mangle]
+pub unsafe extern "C" fn pczt_register_proprietary_namespace(namespace: *const c_char) -> ResultCode {
+    if namespace.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    match CStr::from_ptr(namespace).to_str() {
+        Ok(namespace) => {
+            register_proprietary_namespace(namespace);
+            ResultCode::Success
+        }
+        Err(_) => {
+            set_last_error!(FfiError::InvalidUtf8);
+            ResultCode::ErrorInvalidUtf8
+        }
+    }
+}
+
+/// Checks whether PCZT bytes are in canonical form
+#[no_mangle]
+pub unsafe extern "C" fn pczt_is_canonical(
+    pczt_bytes: *const u8,
+    pczt_bytes_len: usize,
+    result_out: *mut bool,
+) -> ResultCode {
+    if pczt_bytes.is_null() || result_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let bytes = slice::from_raw_parts(pczt_bytes, pczt_bytes_len);
+
+    match is_canonical(bytes) {
+        Ok(canonical) => {
+            *result_out = canonical;
             ResultCode::Success
         }
         Err(e) => {
-            set_last_error(FfiError::Sighash(e));
-            ResultCode::ErrorSighash
+            set_last_error!(FfiError::Parse(e));
+            ResultCode::ErrorParse
+        }
+    }
+}
+
+/// Serializes a PCZT to bytes
+#[no_mangle]
+pub unsafe extern "C" fn pczt_serialize(
+    pczt: *const PcztHandle,
+    bytes_out: *mut *mut u8,
+    bytes_len_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || bytes_out.is_null() || bytes_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let serialized = serialize_pczt(rust_pczt);
+
+    let len = serialized.len();
+    let mut boxed_bytes = serialized.into_boxed_slice();
+    *bytes_out = boxed_bytes.as_mut_ptr();
+    *bytes_len_out = len;
+    std::mem::forget(boxed_bytes); // Prevent deallocation
+
+    ResultCode::Success
+}
+
+/// Saves a PCZT to a file, atomically (see `save_pczt`)
+///
+/// The handle is not consumed. By convention the path ends in `.pczt`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_save(
+    pczt: *const PcztHandle,
+    path: *const c_char,
+) -> ResultCode {
+    if pczt.is_null() || path.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        set_last_error!(FfiError::InvalidUtf8);
+        return ResultCode::ErrorInvalidUtf8;
+    };
+    let rust_pczt = &*(pczt as *const Pczt);
+    match save_pczt(path, rust_pczt) {
+        Ok(()) => ResultCode::Success,
+        Err(e) => {
+            set_last_error!(FfiError::File(e));
+            ResultCode::ErrorFile
         }
     }
 }
 
-/// Appends a signature to the PCZT.
-///
-/// # Ownership
-/// This function ALWAYS consumes the input PCZT handle, even on error.
-/// On success, `pczt_out` contains the new PCZT with the signature appended.
-/// On error, the input handle is invalidated and cannot be reused.
+/// Loads a PCZT from a file (see `load_pczt`)
 ///
-/// If you need to retry on failure, call `pczt_serialize()` before this
-/// function to create a backup that can be restored with `pczt_parse()`.
+/// Files without the PCZT magic bytes fail with `ErrorFile` (`NOT_A_PCZT_FILE`).
 #[no_mangle]
-pub unsafe extern "C" fn pczt_append_signature(
-    pczt: *mut PcztHandle,
-    input_index: usize,
-    signature: *const [u8; 64],
+pub unsafe extern "C" fn pczt_load(
+    path: *const c_char,
     pczt_out: *mut *mut PcztHandle,
 ) -> ResultCode {
-    if pczt.is_null() || signature.is_null() || pczt_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if path.is_null() || pczt_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let rust_pczt = Box::from_raw(pczt as *mut Pczt);
-    let sig = *signature;
-
-    match append_signature(*rust_pczt, input_index, sig) {
-        Ok(signed_pczt) => {
-            let boxed_pczt = Box::new(signed_pczt);
-            *pczt_out = Box::into_raw(boxed_pczt) as *mut PcztHandle;
+    let Ok(path) = CStr::from_ptr(path).to_str() else {
+        set_last_error!(FfiError::InvalidUtf8);
+        return ResultCode::ErrorInvalidUtf8;
+    };
+    match load_pczt(path) {
+        Ok(pczt) => {
+            *pczt_out = pczt_into_handle(pczt);
             ResultCode::Success
         }
         Err(e) => {
-            set_last_error(FfiError::Signature(e));
-            ResultCode::ErrorSignature
+            set_last_error!(FfiError::File(e));
+            ResultCode::ErrorFile
         }
     }
 }
 
-/// Finalizes and extracts the transaction.
-///
-/// # Ownership
-/// This function ALWAYS consumes the input PCZT handle, even on error.
-/// On success, `tx_bytes_out` and `tx_bytes_len_out` contain the transaction bytes.
-/// On error, the input handle is invalidated and cannot be reused.
+/// Serializes a PCZT into a library-owned buffer (see `ByteBufferHandle`)
 ///
-/// If you need to retry on failure, call `pczt_serialize()` before this
-/// function to create a backup that can be restored with `pczt_parse()`.
+/// Produces the same bytes as `pczt_serialize` with one copy fewer, which matters
+/// for multi-megabyte proved PCZTs.
 #[no_mangle]
-pub unsafe extern "C" fn pczt_finalize_and_extract(
-    pczt: *mut PcztHandle,
-    tx_bytes_out: *mut *mut u8,
-    tx_bytes_len_out: *mut usize,
+pub unsafe extern "C" fn pczt_serialize_buffer(
+    pczt: *const PcztHandle,
+    buffer_out: *mut *mut ByteBufferHandle,
 ) -> ResultCode {
-    if pczt.is_null() || tx_bytes_out.is_null() || tx_bytes_len_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if pczt.is_null() || buffer_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let rust_pczt = Box::from_raw(pczt as *mut Pczt);
+    let rust_pczt = &*(pczt as *const Pczt);
+    *buffer_out = Box::into_raw(Box::new(serialize_pczt(rust_pczt))) as *mut ByteBufferHandle;
+    ResultCode::Success
+}
 
-    match finalize_and_extract(*rust_pczt) {
-        Ok(tx_bytes) => {
-            let len = tx_bytes.len();
-            let mut boxed_bytes = tx_bytes.into_boxed_slice();
-            *tx_bytes_out = boxed_bytes.as_mut_ptr();
-            *tx_bytes_len_out = len;
-            std::mem::forget(boxed_bytes); // Prevent deallocation
-            ResultCode::Success
-        }
-        Err(e) => {
-            set_last_error(FfiError::Finalization(e));
-            ResultCode::ErrorFinalization
-        }
+/// Start of the bytes in a buffer (valid until `pczt_buffer_free`)
+#[no_mangle]
+pub unsafe extern "C" fn pczt_buffer_data(buffer: *const ByteBufferHandle) -> *const u8 {
+    if buffer.is_null() {
+        return ptr::null();
     }
+    (*(buffer as *const Vec<u8>)).as_ptr()
 }
 
-/// Parses a PCZT from bytes
+/// Number of bytes in a buffer
 #[no_mangle]
-pub unsafe extern "C" fn pczt_parse(
-    pczt_bytes: *const u8,
-    pczt_bytes_len: usize,
-    pczt_out: *mut *mut PcztHandle,
+pub unsafe extern "C" fn pczt_buffer_len(buffer: *const ByteBufferHandle) -> usize {
+    if buffer.is_null() {
+        return 0;
+    }
+    (*(buffer as *const Vec<u8>)).len()
+}
+
+/// Frees a buffer returned by the library
+#[no_mangle]
+pub unsafe extern "C" fn pczt_buffer_free(buffer: *mut ByteBufferHandle) {
+    if !buffer.is_null() {
+        drop(Box::from_raw(buffer as *mut Vec<u8>));
+    }
+}
+
+/// Writes a serialized PCZT to `write_fn`
+///
+/// Produces the same bytes as `pczt_serialize`. Returns `ErrorParse` if `write_fn`
+/// aborts.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_write(
+    pczt: *const PcztHandle,
+    write_fn: Option<PcztWriteFn>,
+    user_data: *mut c_void,
 ) -> ResultCode {
-    if pczt_bytes.is_null() || pczt_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    let Some(write_fn) = write_fn else {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    };
+    if pczt.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let bytes = slice::from_raw_parts(pczt_bytes, pczt_bytes_len);
+    struct CallbackWriter {
+        write_fn: PcztWriteFn,
+        user_data: *mut c_void,
+    }
 
-    match parse_pczt(bytes) {
-        Ok(pczt) => {
-            let boxed_pczt = Box::new(pczt);
-            *pczt_out = Box::into_raw(boxed_pczt) as *mut PcztHandle;
-            ResultCode::Success
+    impl std::io::Write for CallbackWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if (self.write_fn)(self.user_data, buf.as_ptr(), buf.len()) {
+                Ok(buf.len())
+            } else {
+                Err(std::io::Error::new(std::io::ErrorKind::Other, "Write aborted by caller"))
+            }
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
         }
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    match write_pczt(rust_pczt, CallbackWriter { write_fn, user_data }) {
+        Ok(()) => ResultCode::Success,
         Err(e) => {
-            set_last_error(FfiError::Parse(e));
+            set_last_error!(FfiError::Parse(ParseError::InvalidFormat(format!("Failed to write PCZT: {}", e))));
             ResultCode::ErrorParse
         }
     }
 }
 
-/// Serializes a PCZT to bytes
+/// Decodes a memo field (plain text or compressed) into UTF-8 text
+///
+/// The text is returned without a NUL terminator and must be freed with `pczt_free_bytes`.
 #[no_mangle]
-pub unsafe extern "C" fn pczt_serialize(
-    pczt: *const PcztHandle,
-    bytes_out: *mut *mut u8,
-    bytes_len_out: *mut usize,
+pub unsafe extern "C" fn pczt_decode_memo(
+    memo_bytes: *const u8,
+    memo_bytes_len: usize,
+    text_out: *mut *mut u8,
+    text_len_out: *mut usize,
 ) -> ResultCode {
-    if pczt.is_null() || bytes_out.is_null() || bytes_len_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+    if memo_bytes.is_null() || text_out.is_null() || text_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
-    let rust_pczt = &*(pczt as *const Pczt);
-    let serialized = serialize_pczt(rust_pczt);
-
-    let len = serialized.len();
-    let mut boxed_bytes = serialized.into_boxed_slice();
-    *bytes_out = boxed_bytes.as_mut_ptr();
-    *bytes_len_out = len;
-    std::mem::forget(boxed_bytes); // Prevent deallocation
+    let memo_slice = slice::from_raw_parts(memo_bytes, memo_bytes_len);
 
-    ResultCode::Success
+    match decode_memo(memo_slice) {
+        Ok(text) => {
+            let len = text.len();
+            let mut boxed_bytes = text.into_bytes().into_boxed_slice();
+            *text_out = boxed_bytes.as_mut_ptr();
+            *text_len_out = len;
+            std::mem::forget(boxed_bytes); // Prevent deallocation
+            ResultCode::Success
+        }
+        Err(e) => {
+            set_last_error!(FfiError::Memo(e));
+            ResultCode::ErrorMemo
+        }
+    }
 }
 
 /// Combines multiple PCZTs into one.
@@ -536,46 +3171,214 @@ pub unsafe extern "C" fn pczt_combine(
     pczt_out: *mut *mut PcztHandle,
 ) -> ResultCode {
     if pczts.is_null() || pczt_out.is_null() {
-        set_last_error(FfiError::NullPointer);
+        set_last_error!(FfiError::NullPointer);
         return ResultCode::ErrorNullPointer;
     }
 
     if num_pczts == 0 {
-        set_last_error(FfiError::Combine(crate::error::CombineError::NoPczts));
+        set_last_error!(FfiError::Combine(crate::error::CombineError::NoPczts));
         return ResultCode::ErrorCombine;
     }
 
-    // Collect PCZT handles into Vec, taking ownership
+    // Take ownership of every handle before checking for nulls, so none leaks
     let pczt_ptrs = slice::from_raw_parts(pczts, num_pczts);
-    let mut rust_pczts = Vec::with_capacity(num_pczts);
-
-    for &ptr in pczt_ptrs {
-        if ptr.is_null() {
-            set_last_error(FfiError::NullPointer);
-            return ResultCode::ErrorNullPointer;
-        }
-        // Take ownership of each PCZT
-        rust_pczts.push(*Box::from_raw(ptr as *mut Pczt));
+    let rust_pczts: Vec<Pczt> = pczt_ptrs.iter()
+        .filter(|ptr| !ptr.is_null())
+        .map(|&ptr| pczt_from_handle(ptr))
+        .collect();
+    if rust_pczts.len() != num_pczts {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
     }
 
     match combine(rust_pczts) {
         Ok(combined) => {
-            let boxed = Box::new(combined);
-            *pczt_out = Box::into_raw(boxed) as *mut PcztHandle;
+            *pczt_out = pczt_into_handle(combined);
             ResultCode::Success
         }
         Err(e) => {
-            set_last_error(FfiError::Combine(e));
+            set_last_error!(FfiError::Combine(e));
             ResultCode::ErrorCombine
         }
     }
 }
 
+/// Checks whether a PCZT is transparent-only (t→t)
+///
+/// Transparent-only PCZTs need no proofs; `pczt_prove_transaction` returns them unchanged.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_is_transparent_only(
+    pczt: *const PcztHandle,
+    result_out: *mut bool,
+) -> ResultCode {
+    if pczt.is_null() || result_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *result_out = is_transparent_only(rust_pczt);
+    ResultCode::Success
+}
+
+/// Gets the flags of a PCZT's Orchard bundle
+///
+/// Sets `has_bundle_out` to false (leaving `flags_out` untouched) if the PCZT has no
+/// Orchard actions.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_orchard_flags(
+    pczt: *const PcztHandle,
+    has_bundle_out: *mut bool,
+    flags_out: *mut COrchardFlags,
+) -> ResultCode {
+    if pczt.is_null() || has_bundle_out.is_null() || flags_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    match get_orchard_flags(rust_pczt) {
+        Some(flags) => {
+            *has_bundle_out = true;
+            *flags_out = COrchardFlags {
+                spends_enabled: flags.spends_enabled,
+                outputs_enabled: flags.outputs_enabled,
+            };
+        }
+        None => *has_bundle_out = false,
+    }
+    ResultCode::Success
+}
+
+/// Gets the number of transparent inputs of a PCZT
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_transparent_input_count(
+    pczt: *const PcztHandle,
+    count_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || count_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *count_out = rust_pczt.transparent().inputs().len();
+    ResultCode::Success
+}
+
+/// Gets the prevout, value, script and signing state of a transparent input
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_transparent_input(
+    pczt: *const PcztHandle,
+    input_index: usize,
+    input_out: *mut CTransparentInputInfo,
+) -> ResultCode {
+    if pczt.is_null() || input_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let Some(input) = rust_pczt.transparent().inputs().get(input_index) else {
+        set_last_error!(FfiError::Inspection(InspectionError::InvalidInputIndex(input_index)));
+        return ResultCode::ErrorInspection;
+    };
+    *input_out = CTransparentInputInfo {
+        prevout_txid: *input.prevout_txid(),
+        prevout_index: *input.prevout_index(),
+        sequence: input.sequence().unwrap_or(FINAL_SEQUENCE),
+        value: *input.value(),
+        script_pub_key: input.script_pubkey().as_ptr(),
+        script_pub_key_len: input.script_pubkey().len(),
+        num_signatures: input.partial_signatures().len(),
+        is_finalized: input.script_sig().is_some(),
+    };
+    ResultCode::Success
+}
+
+/// Gets the number of transparent outputs of a PCZT
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_transparent_output_count(
+    pczt: *const PcztHandle,
+    count_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || count_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *count_out = rust_pczt.transparent().outputs().len();
+    ResultCode::Success
+}
+
+/// Gets the script and value of a transparent output
+///
+/// `script_pub_key` points into the PCZT, and is valid until the handle is freed or
+/// consumed.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_transparent_output(
+    pczt: *const PcztHandle,
+    output_index: usize,
+    output_out: *mut CTransparentOutput,
+) -> ResultCode {
+    if pczt.is_null() || output_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    let Some(output) = rust_pczt.transparent().outputs().get(output_index) else {
+        set_last_error!(FfiError::Inspection(InspectionError::InvalidOutputIndex(output_index)));
+        return ResultCode::ErrorInspection;
+    };
+    *output_out = CTransparentOutput {
+        script_pub_key: output.script_pubkey().as_ptr(),
+        script_pub_key_len: output.script_pubkey().len(),
+        value: *output.value(),
+    };
+    ResultCode::Success
+}
+
+/// Gets the number of Orchard actions of a PCZT, padding included
+#[no_mangle]
+pub unsafe extern "C" fn pczt_get_orchard_action_count(
+    pczt: *const PcztHandle,
+    count_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || count_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *count_out = rust_pczt.orchard().actions().len();
+    ResultCode::Success
+}
+
+/// Checks whether a PCZT's Orchard bundle has its proof
+///
+/// PCZTs without Orchard actions need no proof and report false.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_has_orchard_proof(
+    pczt: *const PcztHandle,
+    has_proof_out: *mut bool,
+) -> ResultCode {
+    if pczt.is_null() || has_proof_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *has_proof_out = rust_pczt.orchard().zkproof().is_some();
+    ResultCode::Success
+}
+
 /// Frees a PCZT handle
 #[no_mangle]
 pub unsafe extern "C" fn pczt_free(pczt: *mut PcztHandle) {
     if !pczt.is_null() {
-        drop(Box::from_raw(pczt as *mut Pczt));
+        drop(pczt_from_handle(pczt));
     }
 }
 
@@ -587,6 +3390,24 @@ pub unsafe extern "C" fn pczt_free_bytes(bytes: *mut u8, len: usize) {
     }
 }
 
+/// Describes how the library was built as a JSON document (see `build_info`)
+///
+/// The JSON bytes must be freed with `pczt_free_bytes`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_build_info(
+    json_out: *mut *mut u8,
+    json_len_out: *mut usize,
+) -> ResultCode {
+    if json_out.is_null() || json_len_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let json = serde_json::to_string(&build_info()).expect("BuildInfo serializes");
+    write_json_out(json, json_out, json_len_out);
+    ResultCode::Success
+}
+
 /// Calculates the ZIP-317 transaction fee.
 ///
 /// This is a pure function with no side effects - it simply computes the fee
@@ -614,3 +3435,84 @@ pub extern "C" fn pczt_calculate_fee(
 ) -> u64 {
     crate::calculate_fee(num_transparent_inputs, num_transparent_outputs, num_orchard_outputs)
 }
+
+/// Calculates the ZIP-317 conventional fee from the shape of a PCZT
+///
+/// Needs no transaction request: action counts come from the PCZT itself.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_conventional_fee(
+    pczt: *const PcztHandle,
+    fee_out: *mut u64,
+) -> ResultCode {
+    if pczt.is_null() || fee_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *fee_out = conventional_fee(rust_pczt);
+    ResultCode::Success
+}
+
+/// Estimates the serialized size in bytes of a transaction with the given shape
+///
+/// Counts are those of `pczt_calculate_fee`. The estimate is an upper bound; compare
+/// it with `MAX_TX_SIZE` for the mempool limit.
+#[no_mangle]
+pub extern "C" fn pczt_estimate_tx_size(
+    num_transparent_inputs: usize,
+    num_transparent_outputs: usize,
+    num_orchard_outputs: usize,
+) -> usize {
+    crate::estimate_tx_size(num_transparent_inputs, num_transparent_outputs, num_orchard_outputs)
+}
+
+/// Estimates the serialized size in bytes of the transaction a PCZT extracts to
+#[no_mangle]
+pub unsafe extern "C" fn pczt_estimated_final_size(
+    pczt: *const PcztHandle,
+    size_out: *mut usize,
+) -> ResultCode {
+    if pczt.is_null() || size_out.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    let rust_pczt = &*(pczt as *const Pczt);
+    *size_out = estimated_final_size(rust_pczt);
+    ResultCode::Success
+}
+
+/// Renders an amount for display, independent of the host's locale
+///
+/// Writes a NUL-terminated string such as "1.23450000 ZEC (123450000 zatoshis)" into `buffer`.
+#[no_mangle]
+pub unsafe extern "C" fn pczt_format_amount(
+    zatoshis: u64,
+    unit: CAmountUnit,
+    buffer: *mut c_char,
+    buffer_len: usize,
+) -> ResultCode {
+    if buffer.is_null() {
+        set_last_error!(FfiError::NullPointer);
+        return ResultCode::ErrorNullPointer;
+    }
+
+    // Formatted amounts are ASCII without interior NULs
+    let text = format_amount(zatoshis, unit.into());
+    let bytes = text.as_bytes();
+    if bytes.len() + 1 > buffer_len {
+        set_last_error!(FfiError::BufferTooSmall);
+        return ResultCode::ErrorBufferTooSmall;
+    }
+
+    ptr::copy_nonoverlapping(bytes.as_ptr() as *const c_char, buffer, bytes.len());
+    *buffer.add(bytes.len()) = 0;
+    ResultCode::Success
+}
+
+/// Returns the standardness dust threshold (in zatoshis) for a P2PKH output
+#[no_mangle]
+pub extern "C" fn pczt_p2pkh_dust_threshold() -> u64 {
+    crate::p2pkh_dust_threshold()
+}
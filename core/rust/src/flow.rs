@@ -0,0 +1,153 @@
+//! Supported end-to-end sequences as library code
+//!
+//! Each function runs one complete flow through the PCZT roles, in the order the
+//! crate supports. Hosts can call them directly or read them as the reference for
+//! wiring the steps themselves; because they are compiled and tested with the
+//! crate, a change to any step's behavior shows up here first.
+//!
+//! - `simple_t2z`: one party holds the key and does everything
+//! - `hw_wallet_sign`: signatures come from an external device in one batch
+//! - `multi_party`: inputs belong to several signers, proving runs alongside signing
+
+use pczt::Pczt;
+use secp256k1::{Message, Secp256k1, SecretKey};
+
+use crate::cosigning::CoSigningCoordinator;
+use crate::error::{FlowError, ProverError};
+use crate::types::SighashBatch;
+#[cfg(feature = "proposal")]
+use crate::types::TransactionRequest;
+
+/// Produces signatures for a `SighashBatch`, e.g. a hardware wallet
+///
+/// Implemented for closures `Fn(&SighashBatch) -> Result<Vec<[u8; 64]>, String>`.
+pub trait BatchSigner {
+    /// Returns one compact ECDSA signature per input of `batch`, in order.
+    fn sign_batch(&self, batch: &SighashBatch) -> Result<Vec<[u8; 64]>, String>;
+}
+
+impl<F> BatchSigner for F
+where
+    F: Fn(&SighashBatch) -> Result<Vec<[u8; 64]>, String>,
+{
+    fn sign_batch(&self, batch: &SighashBatch) -> Result<Vec<[u8; 64]>, String> {
+        self(batch)
+    }
+}
+
+/// Proposes, proves, signs and extracts a transaction whose inputs are all
+/// controlled by `secret_key`.
+///
+/// Transparent sighashes don't cover proofs, so inputs are signed while a shielded
+/// transaction is proved on another thread. The extracted transaction is checked
+/// against the PCZT before it is returned.
+///
+/// ```no_run
+/// # fn main() -> Result<(), t2z::error::FlowError> {
+/// use t2z::types::{Payment, TransactionRequest};
+///
+/// # let inputs: Vec<u8> = Vec::new();
+/// let secret_key = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+/// let request = TransactionRequest::new(vec![
+///     Payment::new("tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma".to_string(), 100_000),
+/// ]);
+/// let tx_bytes = t2z::flow::simple_t2z(&inputs, request, None, &secret_key)?;
+/// # let _ = tx_bytes;
+/// # Ok(())
+/// # }
+/// ```
+#[cfg(feature = "proposal")]
+pub fn simple_t2z(
+    inputs: &[u8],
+    request: TransactionRequest,
+    change_address: Option<String>,
+    secret_key: &SecretKey,
+) -> Result<Vec<u8>, FlowError> {
+    let pczt = crate::propose_transaction(inputs, request.clone(), change_address)?;
+    crate::verify_before_signing(&pczt, &request, &[])?;
+
+    if crate::is_transparent_only(&pczt) {
+        return finalize_checked(sign_with_key(pczt, secret_key)?);
+    }
+
+    let (proved, signed) = prove_while(pczt.clone(), || sign_with_key(pczt, secret_key))?;
+    finalize_checked(crate::combine(vec![proved, signed])?)
+}
+
+/// Signs every transparent input of a PCZT with one batch from `device`.
+///
+/// The device receives the `SighashBatch` (see `SighashBatch::to_bytes` for its
+/// wire form); each returned signature is verified as it is appended.
+pub fn hw_wallet_sign(pczt: Pczt, device: &dyn BatchSigner) -> Result<Pczt, FlowError> {
+    let batch = crate::export_sighash_batch(&pczt)?;
+    let signatures = device.sign_batch(&batch).map_err(FlowError::Signer)?;
+    if signatures.len() != batch.inputs.len() {
+        return Err(FlowError::Signer(format!(
+            "Device returned {} signatures for {} inputs", signatures.len(), batch.inputs.len()
+        )));
+    }
+
+    signatures.into_iter()
+        .enumerate()
+        .try_fold(pczt, |pczt, (index, signature)| Ok(crate::append_signature(pczt, index, signature)?))
+}
+
+/// Collects signatures from several signers while the PCZT is proved on another
+/// thread, then combines, finalizes and extracts the transaction.
+///
+/// Each signer receives a packet with only their inputs (see `CoSigningCoordinator`).
+pub fn multi_party(pczt: Pczt, signers: &[([u8; 33], &dyn BatchSigner)]) -> Result<Vec<u8>, FlowError> {
+    let pubkeys: Vec<[u8; 33]> = signers.iter().map(|(pubkey, _)| *pubkey).collect();
+    let mut coordinator = CoSigningCoordinator::new(pczt.clone(), &pubkeys)?;
+
+    // Proving doesn't depend on signatures, so run it while signers work
+    let (proved, signed) = prove_while(pczt, || {
+        for (pubkey, signer) in signers {
+            let packet = coordinator.packet(pubkey)?;
+            let signatures = signer.sign_batch(&packet.batch).map_err(FlowError::Signer)?;
+            if signatures.len() != packet.input_indices.len() {
+                return Err(FlowError::Signer(format!(
+                    "Signer returned {} signatures for {} inputs", signatures.len(), packet.input_indices.len()
+                )));
+            }
+            let indexed: Vec<(usize, [u8; 64])> = packet.input_indices.into_iter().zip(signatures).collect();
+            coordinator.add_signatures(pubkey, &indexed)?;
+        }
+        Ok(coordinator.finish()?)
+    })?;
+
+    finalize_checked(crate::combine(vec![proved, signed])?)
+}
+
+/// Proves `pczt` on another thread while `sign` runs on this one.
+///
+/// Proofs can't be cancelled, so the prover is joined before returning even when
+/// signing fails; no thread outlives the call. Signing errors take precedence.
+fn prove_while<T>(pczt: Pczt, sign: impl FnOnce() -> Result<T, FlowError>) -> Result<(Pczt, T), FlowError> {
+    std::thread::scope(|scope| {
+        let prover = scope.spawn(move || crate::prove_transaction(pczt));
+        let signed = sign();
+        let proved = prover.join()
+            .map_err(|_| ProverError::ProofGenerationFailed("Prover thread panicked".to_string()));
+        let signed = signed?;
+        Ok((proved??, signed))
+    })
+}
+
+/// Signs every transparent input with one key
+fn sign_with_key(pczt: Pczt, secret_key: &SecretKey) -> Result<Pczt, FlowError> {
+    let secp = Secp256k1::signing_only();
+    let sign = |batch: &SighashBatch| -> Result<Vec<[u8; 64]>, String> {
+        Ok(batch.inputs.iter()
+            .map(|input| secp.sign_ecdsa(&Message::from_digest(input.sighash), secret_key).serialize_compact())
+            .collect())
+    };
+    hw_wallet_sign(pczt, &sign)
+}
+
+/// Finalizes and extracts, then checks the transaction against the PCZT
+fn finalize_checked(pczt: Pczt) -> Result<Vec<u8>, FlowError> {
+    let tx_bytes = crate::finalize_and_extract(pczt.clone())?;
+    crate::verify_extraction(&pczt, &tx_bytes)?;
+    Ok(tx_bytes)
+}
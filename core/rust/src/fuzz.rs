@@ -0,0 +1,49 @@
+//! Fuzzing entry points (requires the `arbitrary` feature)
+//!
+//! `TransactionRequest`, `Payment` and `TransparentInput` implement
+//! `arbitrary::Arbitrary` under this feature, so downstream fuzz targets can
+//! generate the same structures this crate parses.
+
+use arbitrary::{Arbitrary, Unstructured};
+
+use crate::types::{parse_transparent_inputs, serialize_transparent_inputs, TransactionRequest, TransparentInput};
+
+/// Checks the crate's encoding round-trips on fuzzer-provided bytes.
+///
+/// Panics if an invariant is violated:
+/// - raw bytes that parse as transparent inputs re-serialize to a stable encoding;
+/// - arbitrary inputs survive serialize → parse → serialize unchanged;
+/// - arbitrary transaction requests survive a JSON round-trip unchanged.
+///
+/// Intended to be called directly from a `cargo fuzz` target:
+/// `fuzz_target!(|data: &[u8]| t2z::fuzz::fuzz_roundtrip(data));`
+pub fn fuzz_roundtrip(data: &[u8]) {
+    // Raw bytes through the input parser
+    if let Ok(inputs) = parse_transparent_inputs(data) {
+        let encoded = serialize_transparent_inputs(&inputs).expect("parsed inputs must serialize");
+        let reparsed = parse_transparent_inputs(&encoded).expect("re-serialized inputs must parse");
+        assert_eq!(serialize_transparent_inputs(&reparsed).ok(), Some(encoded), "input encoding is not stable");
+    }
+
+    let mut u = Unstructured::new(data);
+
+    // Structured inputs through the wire format
+    if let Ok(inputs) = Vec::<TransparentInput>::arbitrary(&mut u) {
+        // A v1 count of 0xFFFF would collide with the v2 marker
+        if inputs.len() < u16::MAX as usize {
+            // Overlong scripts or derivation paths are rejected, not truncated
+            if let Ok(encoded) = serialize_transparent_inputs(&inputs) {
+                let reparsed = parse_transparent_inputs(&encoded).expect("serialized inputs must parse");
+                assert_eq!(reparsed.len(), inputs.len());
+                assert_eq!(serialize_transparent_inputs(&reparsed).ok(), Some(encoded), "input round-trip changed data");
+            }
+        }
+    }
+
+    // Transaction requests through JSON
+    if let Ok(request) = TransactionRequest::arbitrary(&mut u) {
+        let json = serde_json::to_string(&request).expect("requests serialize");
+        let parsed: TransactionRequest = serde_json::from_str(&json).expect("serialized requests must parse");
+        assert_eq!(serde_json::to_string(&parsed).expect("requests serialize"), json, "request round-trip changed data");
+    }
+}
@@ -0,0 +1,210 @@
+//! Compatibility checks for PCZTs from other producers (requires the `interop` feature)
+//!
+//! PCZTs are meant to move between implementations: a wallet built on
+//! `zcash_client_backend` (such as Zashi) may create one, and t2z may sign, combine
+//! or extract it. `check_pczt` runs such a PCZT through every role this crate
+//! plays and reports which stages it got through, so an incompatibility shows up
+//! as a typed `InteropIssue` instead of an error deep inside one role.
+
+use std::path::{Path, PathBuf};
+
+use crate::error::{ErrorCode, ParseError};
+use crate::flow::BatchSigner;
+use crate::types::ParseOptions;
+
+/// Where a PCZT under test came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Producer {
+    /// `zcash_client_backend::data_api::wallet::create_pczt_from_proposal`
+    ZcashClientBackend,
+    /// A PCZT exported from Zashi
+    Zashi,
+    /// This crate's own `propose_transaction`
+    T2z,
+    /// Anything else, by name
+    Other(String),
+}
+
+/// A step of the pipeline `check_pczt` runs, in order
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InteropStage {
+    /// `parse_pczt`
+    Parse,
+    /// Re-serializing reproduces the producer's bytes
+    Canonical,
+    /// `validate_pczt`
+    Validate,
+    /// `export_sighash_batch` for every transparent input
+    Sighash,
+    /// Signatures from the signer, appended with `append_signature`
+    Sign,
+    /// `combine` of the signed PCZT with the producer's
+    Combine,
+    /// `prove_transaction`
+    Prove,
+    /// `finalize_and_extract`, checked with `verify_extraction`
+    Extract,
+}
+
+/// How a stage went
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageOutcome {
+    Passed,
+    /// Not run, because an earlier stage failed or no signer was given
+    Skipped,
+    Failed(InteropIssue),
+}
+
+/// An incompatibility found at one stage
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteropIssue {
+    pub stage: InteropStage,
+    /// Stable code of the error the stage returned
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// Result of `check_pczt`: one outcome per stage, in pipeline order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InteropReport {
+    pub producer: Producer,
+    pub stages: Vec<(InteropStage, StageOutcome)>,
+}
+
+impl InteropReport {
+    /// Whether no stage failed (skipped stages don't count as failures)
+    pub fn is_compatible(&self) -> bool {
+        self.issues().next().is_none()
+    }
+
+    /// The issues found, in pipeline order
+    pub fn issues(&self) -> impl Iterator<Item = &InteropIssue> {
+        self.stages.iter().filter_map(|(_, outcome)| match outcome {
+            StageOutcome::Failed(issue) => Some(issue),
+            _ => None,
+        })
+    }
+
+    /// Outcome of `stage`, if the report has it
+    pub fn outcome(&self, stage: InteropStage) -> Option<&StageOutcome> {
+        self.stages.iter().find(|(s, _)| *s == stage).map(|(_, outcome)| outcome)
+    }
+}
+
+/// Decodes a PCZT export: raw PCZT bytes, or the same bytes as hex text (as wallets
+/// show them for copy-paste), with surrounding whitespace and an optional `0x`.
+pub fn load_pczt(data: &[u8]) -> Result<Vec<u8>, ParseError> {
+    if data.starts_with(b"PCZT") {
+        return Ok(data.to_vec());
+    }
+
+    let text = std::str::from_utf8(data)
+        .map_err(|_| ParseError::InvalidFormat("Neither PCZT bytes nor hex text".to_string()))?;
+    let hex: String = text.trim().trim_start_matches("0x").split_whitespace().collect();
+    crate::json::hex_decode(&hex)
+}
+
+/// Reads every `*.pczt` (raw) and `*.hex` export in `dir`, sorted by file name.
+pub fn load_dir(dir: &Path) -> std::io::Result<Vec<(PathBuf, Vec<u8>)>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| matches!(path.extension().and_then(|e| e.to_str()), Some("pczt" | "hex")))
+        .collect();
+    paths.sort();
+
+    paths.into_iter()
+        .map(|path| {
+            let bytes = std::fs::read(&path)?;
+            Ok((path, bytes))
+        })
+        .collect()
+}
+
+/// Runs an exported PCZT through every role this crate plays.
+///
+/// Without a `signer`, the signing and extraction stages are skipped and the
+/// unsigned PCZT is combined with itself. A failed stage skips the stages that
+/// depend on its output; `Canonical` and `Validate` failures don't stop the rest.
+///
+/// # Arguments
+/// * `producer` - Where `export` came from, carried into the report
+/// * `export` - The PCZT as exported (see `load_pczt` for accepted encodings)
+/// * `signer` - Signs the transparent inputs, if their keys are available
+pub fn check_pczt(producer: Producer, export: &[u8], signer: Option<&dyn BatchSigner>) -> InteropReport {
+    let mut reached = Vec::new();
+    run(export, signer, &mut reached);
+
+    // Stages the pipeline didn't reach were skipped
+    let stages = PIPELINE.iter()
+        .map(|stage| {
+            let outcome = reached.iter()
+                .find(|(s, _)| s == stage)
+                .map(|(_, outcome)| outcome.clone())
+                .unwrap_or(StageOutcome::Skipped);
+            (*stage, outcome)
+        })
+        .collect();
+    InteropReport { producer, stages }
+}
+
+const PIPELINE: [InteropStage; 8] = [
+    InteropStage::Parse,
+    InteropStage::Canonical,
+    InteropStage::Validate,
+    InteropStage::Sighash,
+    InteropStage::Sign,
+    InteropStage::Combine,
+    InteropStage::Prove,
+    InteropStage::Extract,
+];
+
+type Stages = Vec<(InteropStage, StageOutcome)>;
+
+/// Runs the pipeline, returning None at the first stage whose output is needed
+fn run(export: &[u8], signer: Option<&dyn BatchSigner>, stages: &mut Stages) -> Option<()> {
+    let parsed = load_pczt(export).and_then(|bytes| Ok((crate::parse_pczt(&bytes)?, bytes)));
+    let (pczt, bytes) = record(stages, InteropStage::Parse, parsed.map_err(|e| (e.code(), e.to_string())))?;
+
+    let canonical = crate::parse_pczt_with_options(&bytes, ParseOptions::canonical());
+    record(stages, InteropStage::Canonical, canonical.map(|_| ()).map_err(|e| (e.code(), e.to_string())));
+    let valid = crate::validate_pczt(&pczt);
+    record(stages, InteropStage::Validate, valid.map_err(|e| (e.code(), e.to_string())));
+
+    let batch = crate::export_sighash_batch(&pczt);
+    record(stages, InteropStage::Sighash, batch.map(|_| ()).map_err(|e| (e.code(), e.to_string())))?;
+
+    let signed = match signer {
+        Some(signer) => {
+            let signed = crate::flow::hw_wallet_sign(pczt.clone(), signer);
+            record(stages, InteropStage::Sign, signed.map_err(|e| (e.code(), e.to_string())))?
+        }
+        None => pczt.clone(),
+    };
+
+    let combined = crate::combine(vec![pczt, signed]);
+    let combined = record(stages, InteropStage::Combine, combined.map_err(|e| (e.code(), e.to_string())))?;
+    let proved = crate::prove_transaction(combined);
+    let proved = record(stages, InteropStage::Prove, proved.map_err(|e| (e.code(), e.to_string())))?;
+
+    if signer.is_some() {
+        let extracted = crate::finalize_and_extract(proved.clone())
+            .map_err(|e| (e.code(), e.to_string()))
+            .and_then(|tx_bytes| crate::verify_extraction(&proved, &tx_bytes).map_err(|e| (e.code(), e.to_string())));
+        record(stages, InteropStage::Extract, extracted)?;
+    }
+    Some(())
+}
+
+/// Records a stage's outcome, passing on its output if it succeeded
+fn record<T>(stages: &mut Stages, stage: InteropStage, result: Result<T, (ErrorCode, String)>) -> Option<T> {
+    match result {
+        Ok(value) => {
+            stages.push((stage, StageOutcome::Passed));
+            Some(value)
+        }
+        Err((code, message)) => {
+            stages.push((stage, StageOutcome::Failed(InteropIssue { stage, code, message })));
+            None
+        }
+    }
+}
@@ -0,0 +1,451 @@
+//! Versioned JSON encodings of the crate's public structures
+//!
+//! Every document carries `"version": JSON_SCHEMA_VERSION`. Readers ignore fields
+//! they don't know, so newer writers can add fields without breaking older readers,
+//! and reject documents with a newer major version. Documents without a `version`
+//! field are read as version 1 for compatibility with earlier ad-hoc JSON.
+//!
+//! Conventions shared by all documents:
+//! - amounts are integer zatoshis;
+//! - byte strings are lowercase hex;
+//! - txids are hex in display byte order (as shown by block explorers and RPCs).
+
+use pczt::Pczt;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use zcash_protocol::consensus::NetworkType;
+
+use crate::error::ParseError;
+use crate::types::{script_to_raw, Severity, TransactionRequest, TransactionSummary, TransparentInput, VerificationReport};
+
+/// Current version of the JSON schema
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
+fn default_version() -> u32 {
+    1
+}
+
+/// A document body with its schema version
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    #[serde(default = "default_version")]
+    version: u32,
+    #[serde(flatten)]
+    body: T,
+}
+
+fn to_versioned_json<T: Serialize>(body: &T) -> String {
+    serde_json::to_string(&Versioned { version: JSON_SCHEMA_VERSION, body })
+        .expect("JSON documents serialize")
+}
+
+fn from_versioned_json<T: DeserializeOwned>(json: &str) -> Result<T, ParseError> {
+    let document: Versioned<T> = serde_json::from_str(json)
+        .map_err(|e| ParseError::InvalidFormat(format!("Invalid JSON document: {}", e)))?;
+    if document.version > JSON_SCHEMA_VERSION {
+        return Err(ParseError::UnsupportedVersion);
+    }
+    Ok(document.body)
+}
+
+pub(crate) fn hex_decode(hex: &str) -> Result<Vec<u8>, ParseError> {
+    if hex.len() % 2 != 0 {
+        return Err(ParseError::InvalidFormat("Hex string has odd length".to_string()));
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| {
+            hex.get(i..i + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| ParseError::InvalidFormat(format!("Invalid hex string: {}", hex)))
+        })
+        .collect()
+}
+
+/// Hex in display byte order for a txid stored in internal byte order
+fn txid_to_hex(txid: &[u8; 32]) -> String {
+    let mut display = *txid;
+    display.reverse();
+    crate::hex_encode(&display)
+}
+
+fn txid_from_hex(hex: &str) -> Result<[u8; 32], ParseError> {
+    let mut txid: [u8; 32] = hex_decode(hex)?
+        .try_into()
+        .map_err(|_| ParseError::InvalidFormat("txid must be 32 bytes".to_string()))?;
+    txid.reverse();
+    Ok(txid)
+}
+
+/// A transparent UTXO to spend (JSON form of `TransparentInput`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputJson {
+    /// Compressed public key (hex)
+    pub pubkey: String,
+    pub txid: String,
+    pub vout: u32,
+    pub amount: u64,
+    /// Raw script pubkey (hex)
+    pub script_pubkey: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// Redeem script of a P2SH input (hex)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redeem_script: Option<String>,
+    /// BIP-32 derivation of the signing key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub derivation_path: Option<DerivationJson>,
+}
+
+/// JSON form of `KeyDerivation`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DerivationJson {
+    /// Compressed public key the derivation produces (hex)
+    pub pubkey: String,
+    /// ZIP-32 seed fingerprint (hex)
+    pub seed_fingerprint: String,
+    /// Child indices from the seed, hardened indices with the high bit set
+    pub path: Vec<u32>,
+}
+
+fn derivation_from_json(derivation: &DerivationJson) -> Result<crate::types::KeyDerivation, ParseError> {
+    Ok(crate::types::KeyDerivation {
+        pubkey: hex_decode(&derivation.pubkey)?
+            .try_into()
+            .map_err(|_| ParseError::InvalidFormat("Derivation pubkey must be 33 bytes".to_string()))?,
+        seed_fingerprint: hex_decode(&derivation.seed_fingerprint)?
+            .try_into()
+            .map_err(|_| ParseError::InvalidFormat("Seed fingerprint must be 32 bytes".to_string()))?,
+        path: derivation.path.clone(),
+    })
+}
+
+#[derive(Serialize, Deserialize)]
+struct InputsJson {
+    inputs: Vec<InputJson>,
+}
+
+/// A transparent input as it appears in a PCZT or transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrevoutJson {
+    pub txid: String,
+    pub vout: u32,
+    /// Value of the spent output, if known (PCZTs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+    pub sequence: u32,
+}
+
+/// A transparent output
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputJson {
+    pub value: u64,
+    /// Raw script pubkey (hex)
+    pub script_pubkey: String,
+    /// Address the script pays to (see `script_to_address`), if it is P2PKH or P2SH and the
+    /// network is known (PCZTs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+}
+
+/// An Orchard action's output commitments (see `OrchardOutputInfo`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OrchardOutputJson {
+    pub action_index: usize,
+    pub cmx: String,
+    pub ephemeral_key: String,
+    /// Note value, if known (PCZTs only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u64>,
+}
+
+/// Summary of a proposed transaction (a PCZT)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalJson {
+    /// `pczt_fingerprint` (hex)
+    pub fingerprint: String,
+    pub consensus_branch_id: u32,
+    pub expiry_height: u32,
+    pub inputs: Vec<PrevoutJson>,
+    pub outputs: Vec<OutputJson>,
+    pub orchard_outputs: Vec<OrchardOutputJson>,
+    /// Fee in zatoshis, if all input values are known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<u64>,
+    /// The request's label (see `transaction_label`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// Summary of a serialized final transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DecodedTransactionJson {
+    pub txid: String,
+    pub expiry_height: u32,
+    pub inputs: Vec<PrevoutJson>,
+    pub outputs: Vec<OutputJson>,
+    pub orchard_outputs: Vec<OrchardOutputJson>,
+    /// Net value leaving the Orchard pool, in zatoshis (negative when shielding)
+    pub orchard_value_balance: i64,
+}
+
+/// One finding of a `verify_report`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationFindingJson {
+    pub severity: Severity,
+    /// Stable error code (`ErrorCode::as_str`)
+    pub code: String,
+    pub message: String,
+}
+
+/// Result of `verify_report`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReportJson {
+    pub findings: Vec<VerificationFindingJson>,
+}
+
+fn orchard_output_json(info: &crate::types::OrchardOutputInfo) -> OrchardOutputJson {
+    OrchardOutputJson {
+        action_index: info.action_index,
+        cmx: crate::hex_encode(&info.cmx),
+        ephemeral_key: crate::hex_encode(&info.ephemeral_key),
+        value: info.value,
+    }
+}
+
+/// Encodes a transaction request as a versioned JSON document.
+pub fn request_to_json(request: &TransactionRequest) -> String {
+    to_versioned_json(request)
+}
+
+/// Decodes a versioned JSON transaction request, ignoring unknown fields.
+pub fn request_from_json(json: &str) -> Result<TransactionRequest, ParseError> {
+    from_versioned_json(json)
+}
+
+/// Encodes transparent inputs as a versioned JSON document (`{"version":1,"inputs":[...]}`).
+pub fn inputs_to_json(inputs: &[TransparentInput]) -> String {
+    let inputs = inputs.iter()
+        .map(|input| InputJson {
+            pubkey: crate::hex_encode(&input.pubkey.serialize()),
+            txid: txid_to_hex(&input.txid),
+            vout: input.vout,
+            amount: input.amount,
+            script_pubkey: crate::hex_encode(&input.script_pubkey),
+            height: input.height,
+            redeem_script: input.redeem_script.as_deref().map(crate::hex_encode),
+            derivation_path: input.derivation_path.as_ref().map(|derivation| DerivationJson {
+                pubkey: crate::hex_encode(&derivation.pubkey),
+                seed_fingerprint: crate::hex_encode(&derivation.seed_fingerprint),
+                path: derivation.path.clone(),
+            }),
+        })
+        .collect();
+    to_versioned_json(&InputsJson { inputs })
+}
+
+/// Decodes a versioned JSON inputs document, ignoring unknown fields.
+pub fn inputs_from_json(json: &str) -> Result<Vec<TransparentInput>, ParseError> {
+    let document: InputsJson = from_versioned_json(json)?;
+    document.inputs.into_iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let pubkey = secp256k1::PublicKey::from_slice(&hex_decode(&input.pubkey)?)
+                .map_err(|e| ParseError::InvalidFormat(format!("Invalid pubkey for input {}: {}", i, e)))?;
+            Ok(TransparentInput {
+                pubkey,
+                txid: txid_from_hex(&input.txid)?,
+                vout: input.vout,
+                amount: input.amount,
+                script_pubkey: hex_decode(&input.script_pubkey)?,
+                height: input.height,
+                redeem_script: input.redeem_script.as_deref().map(hex_decode).transpose()?,
+                derivation_path: input.derivation_path.as_ref().map(derivation_from_json).transpose()?,
+            })
+        })
+        .collect()
+}
+
+/// A UTXO as returned by `listunspent` (zcashd) or `getaddressutxos` (zcashd, zebrad)
+#[derive(Deserialize)]
+struct NodeUtxoJson {
+    txid: String,
+    #[serde(alias = "outputIndex")]
+    vout: u32,
+    #[serde(default)]
+    address: Option<String>,
+    #[serde(rename = "scriptPubKey", alias = "script")]
+    script_pubkey: String,
+    /// `amountZat` (listunspent) or `satoshis` (getaddressutxos)
+    #[serde(default, rename = "amountZat", alias = "satoshis")]
+    zatoshis: Option<u64>,
+    /// ZEC, only used when no zatoshi amount is given
+    #[serde(default)]
+    amount: Option<f64>,
+    #[serde(default)]
+    height: Option<u32>,
+}
+
+/// A bare result array, or the whole JSON-RPC response
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum NodeUtxosJson {
+    Bare(Vec<NodeUtxoJson>),
+    Response { result: Vec<NodeUtxoJson> },
+}
+
+/// Converts the output of a node's `listunspent` or `getaddressutxos` RPC into inputs.
+///
+/// Accepts either the result array or the full JSON-RPC response. Txids are read in
+/// display byte order, as nodes print them. Nodes don't return public keys, so
+/// `pubkey_resolver` maps each UTXO's address to the key that controls it; the key
+/// must match the UTXO's P2PKH script.
+///
+/// `listunspent` reports confirmations rather than a height, so its inputs have no
+/// `height` and are excluded by `filter_by_confirmations`.
+pub fn inputs_from_listunspent_json(
+    json: &str,
+    pubkey_resolver: impl Fn(&str) -> Option<secp256k1::PublicKey>,
+) -> Result<Vec<TransparentInput>, ParseError> {
+    let utxos = match serde_json::from_str(json)
+        .map_err(|e| ParseError::InvalidFormat(format!("Invalid UTXO list: {}", e)))?
+    {
+        NodeUtxosJson::Bare(utxos) | NodeUtxosJson::Response { result: utxos } => utxos,
+    };
+
+    utxos.into_iter()
+        .enumerate()
+        .map(|(i, utxo)| {
+            let script_pubkey = hex_decode(&utxo.script_pubkey)?;
+            let Some(pubkey_hash) = crate::p2pkh_pubkey_hash(&script_pubkey) else {
+                return Err(ParseError::InvalidFormat(format!("UTXO {} is not P2PKH", i)));
+            };
+
+            let address = utxo.address
+                .ok_or_else(|| ParseError::InvalidFormat(format!("UTXO {} has no address", i)))?;
+            let pubkey = pubkey_resolver(&address)
+                .ok_or_else(|| ParseError::InvalidFormat(format!("No pubkey for {}", address)))?;
+            if crate::hash160(&pubkey.serialize()) != pubkey_hash {
+                return Err(ParseError::InvalidFormat(format!("Pubkey for {} does not match its script", address)));
+            }
+
+            let amount = match (utxo.zatoshis, utxo.amount) {
+                (Some(zatoshis), _) => zatoshis,
+                // 21M ZEC in zatoshis fits well within f64's exact integer range
+                (None, Some(zec)) if zec >= 0.0 && zec.is_finite() => (zec * 100_000_000.0).round() as u64,
+                _ => return Err(ParseError::InvalidFormat(format!("UTXO {} has no valid amount", i))),
+            };
+
+            Ok(TransparentInput {
+                pubkey,
+                txid: txid_from_hex(&utxo.txid)?,
+                vout: utxo.vout,
+                amount,
+                script_pubkey,
+                height: utxo.height,
+                redeem_script: None,
+                derivation_path: None,
+            })
+        })
+        .collect()
+}
+
+/// Summarizes a PCZT as a versioned JSON proposal document.
+pub fn proposal_to_json(pczt: &Pczt) -> String {
+    let global = pczt.global();
+    let transparent = pczt.transparent();
+    // SLIP-44 coin type 133 is mainnet; everything else is rendered with testnet prefixes
+    let network = if *global.coin_type() == 133 { NetworkType::Main } else { NetworkType::Test };
+
+    let inputs: Vec<PrevoutJson> = transparent.inputs().iter()
+        .map(|input| PrevoutJson {
+            txid: txid_to_hex(input.prevout_txid()),
+            vout: *input.prevout_index(),
+            value: Some(*input.value()),
+            sequence: input.sequence().unwrap_or(crate::FINAL_SEQUENCE),
+        })
+        .collect();
+    let outputs: Vec<OutputJson> = transparent.outputs().iter()
+        .map(|output| OutputJson {
+            value: *output.value(),
+            script_pubkey: crate::hex_encode(output.script_pubkey()),
+            address: crate::script_to_address(output.script_pubkey(), network),
+        })
+        .collect();
+
+    to_versioned_json(&ProposalJson {
+        fingerprint: crate::hex_encode(&crate::pczt_fingerprint(pczt)),
+        consensus_branch_id: *global.consensus_branch_id(),
+        expiry_height: *global.expiry_height(),
+        inputs,
+        outputs,
+        orchard_outputs: crate::get_orchard_outputs(pczt).iter().map(orchard_output_json).collect(),
+        fee: crate::implicit_fee(pczt),
+        label: crate::transaction_label(pczt),
+    })
+}
+
+/// Encodes a `summarize_pczt` result as a versioned JSON document.
+pub fn summary_to_json(summary: &TransactionSummary) -> String {
+    to_versioned_json(summary)
+}
+
+/// Decodes a serialized final transaction into a versioned JSON document.
+pub fn decode_transaction_json(tx_bytes: &[u8]) -> Result<String, ParseError> {
+    use zcash_primitives::transaction::Transaction;
+    use zcash_protocol::consensus::BranchId;
+
+    // v5 transactions carry their consensus branch ID in the header
+    let tx = Transaction::read(tx_bytes, BranchId::Nu5)
+        .map_err(|e| ParseError::InvalidFormat(format!("Failed to parse transaction: {}", e)))?;
+
+    let (inputs, outputs) = match tx.transparent_bundle() {
+        Some(bundle) => (
+            bundle.vin.iter()
+                .map(|txin| PrevoutJson {
+                    txid: txid_to_hex(txin.prevout().hash()),
+                    vout: txin.prevout().n(),
+                    value: None,
+                    sequence: txin.sequence(),
+                })
+                .collect(),
+            bundle.vout.iter()
+                .map(|txout| {
+                    let script = script_to_raw(txout.script_pubkey())
+                        .map_err(|e| ParseError::InvalidFormat(format!("Unreadable output script: {}", e)))?;
+                    Ok(OutputJson {
+                        value: txout.value().into_u64(),
+                        script_pubkey: crate::hex_encode(&script),
+                        address: None,
+                    })
+                })
+                .collect::<Result<_, ParseError>>()?,
+        ),
+        None => (Vec::new(), Vec::new()),
+    };
+
+    let orchard_value_balance = tx.orchard_bundle()
+        .map(|bundle| i64::from(*bundle.value_balance()))
+        .unwrap_or(0);
+
+    Ok(to_versioned_json(&DecodedTransactionJson {
+        txid: tx.txid().to_string(),
+        expiry_height: u32::from(tx.expiry_height()),
+        inputs,
+        outputs,
+        orchard_outputs: crate::decode_orchard_outputs(tx_bytes)?.iter().map(orchard_output_json).collect(),
+        orchard_value_balance,
+    }))
+}
+
+/// Encodes a `verify_report` result as a versioned JSON document.
+pub fn verification_report_to_json(report: &VerificationReport) -> String {
+    to_versioned_json(&VerificationReportJson {
+        findings: report.findings.iter()
+            .map(|finding| VerificationFindingJson {
+                severity: finding.severity,
+                code: finding.failure.code().as_str().to_string(),
+                message: finding.failure.to_string(),
+            })
+            .collect(),
+    })
+}
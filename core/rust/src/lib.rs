@@ -1,23 +1,51 @@
+#[cfg(all(feature = "verify-only", any(feature = "proposal", feature = "orchard-prover")))]
+compile_error!("`verify-only` excludes `proposal` and `orchard-prover`; build with --no-default-features");
+
+pub mod address_cache;
+pub mod cosigning;
 pub mod error;
+pub mod experimental;
 pub mod ffi;
+pub mod flow;
+pub mod json;
+pub mod manifest;
+pub mod multisig;
+pub mod params;
+pub mod prover_service;
+pub mod reservation;
+pub mod shielded;
 pub mod types;
+pub mod xpub;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "arbitrary")]
+pub mod fuzz;
+
+#[cfg(feature = "interop")]
+pub mod interop;
+
+#[cfg(feature = "cli")]
+pub mod cli;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "orchard-prover")]
+mod prover;
+
+#[cfg(feature = "proposal")]
+mod proposal;
+#[cfg(feature = "proposal")]
+pub use proposal::*;
 
 use error::*;
 use types::*;
 
-use pczt::{Pczt, roles::creator::Creator, roles::io_finalizer::IoFinalizer};
-use zcash_primitives::transaction::{
-    builder::{Builder, BuildConfig},
-    fees::zip317::FeeRule,
-};
-use zcash_protocol::{
-    consensus::{MainNetwork, TestNetwork, Parameters},
-    value::Zatoshis,
-    memo::MemoBytes,
-};
+use pczt::Pczt;
+use address_cache::ParsedAddress;
 use zcash_address::{ZcashAddress, unified};
-use zcash_transparent::address::TransparentAddress;
-use rand_core::OsRng;
 
 /// ZIP-317 marginal fee per logical action (5000 zatoshis = 0.00005 ZEC)
 pub const ZIP317_MARGINAL_FEE: u64 = 5_000;
@@ -25,6 +53,116 @@ pub const ZIP317_MARGINAL_FEE: u64 = 5_000;
 /// ZIP-317 grace actions (minimum actions charged to encourage small transactions)
 pub const ZIP317_GRACE_ACTIONS: usize = 2;
 
+/// Crate-wide `Strictness` (0 = lenient, 1 = strict)
+static STRICTNESS: std::sync::atomic::AtomicU8 = std::sync::atomic::AtomicU8::new(0);
+
+/// Sets the crate-wide strictness.
+///
+/// In strict mode, memos on transparent payments, payment labels and messages
+/// (which have no place in the transaction), invalid strings passed over FFI, and
+/// addresses that are neither transparent nor unified with an Orchard receiver are
+/// errors instead of being silently dropped or guessed at.
+pub fn set_strictness(strictness: Strictness) {
+    let value = match strictness {
+        Strictness::Lenient => 0,
+        Strictness::Strict => 1,
+    };
+    STRICTNESS.store(value, std::sync::atomic::Ordering::Relaxed);
+}
+
+/// Returns the crate-wide strictness.
+pub fn strictness() -> Strictness {
+    match STRICTNESS.load(std::sync::atomic::Ordering::Relaxed) {
+        0 => Strictness::Lenient,
+        _ => Strictness::Strict,
+    }
+}
+
+/// Size of a Zcash memo field in bytes
+pub const MEMO_SIZE: usize = 512;
+
+/// First byte of a compressed memo. ZIP-302 reserves 0xFF for memos whose remaining
+/// bytes follow no public specification.
+pub const COMPRESSED_MEMO_MARKER: u8 = 0xFF;
+
+/// Format tag following `COMPRESSED_MEMO_MARKER`: raw deflate, length-prefixed
+const COMPRESSED_MEMO_DEFLATE_V1: u8 = 0x01;
+
+/// Marker (1) + format tag (1) + payload length (2)
+const COMPRESSED_MEMO_HEADER_LEN: usize = 4;
+
+/// Upper bound on the decompressed size of a memo, to bound decoder memory
+const MAX_DECOMPRESSED_MEMO_LEN: usize = 64 * 1024;
+
+/// Encodes memo text into the 512-byte memo field.
+///
+/// `Padded` stores the UTF-8 text as-is (zero padding is added by the builder).
+/// `Compressed` stores `[0xFF, 0x01, len: u16 LE, deflate(text)]`, which fits most
+/// structured JSON that exceeds 512 bytes as plain text.
+///
+/// # Returns
+/// * `Result<Vec<u8>, MemoError>` - At most `MEMO_SIZE` bytes, or `TooLong`
+pub fn encode_memo(memo: &str, encoding: MemoEncoding) -> Result<Vec<u8>, MemoError> {
+    let encoded = match encoding {
+        MemoEncoding::Padded => memo.as_bytes().to_vec(),
+        MemoEncoding::Compressed => {
+            let compressed = miniz_oxide::deflate::compress_to_vec(memo.as_bytes(), 9);
+            let mut encoded = Vec::with_capacity(COMPRESSED_MEMO_HEADER_LEN + compressed.len());
+            encoded.push(COMPRESSED_MEMO_MARKER);
+            encoded.push(COMPRESSED_MEMO_DEFLATE_V1);
+            encoded.extend_from_slice(&(compressed.len().min(u16::MAX as usize) as u16).to_le_bytes());
+            encoded.extend_from_slice(&compressed);
+            encoded
+        }
+    };
+
+    if encoded.len() > MEMO_SIZE {
+        return Err(MemoError::TooLong { len: encoded.len(), max: MEMO_SIZE });
+    }
+    Ok(encoded)
+}
+
+/// Decodes a memo field produced by either `MemoEncoding` back into text.
+///
+/// Accepts plain ZIP-302 text memos (trailing zero padding is stripped), the
+/// empty memo (0xF6), and compressed memos written by `encode_memo`.
+///
+/// # Returns
+/// * `Result<String, MemoError>` - The memo text, or `InvalidMemo`
+pub fn decode_memo(memo: &[u8]) -> Result<String, MemoError> {
+    if memo.len() > MEMO_SIZE {
+        return Err(MemoError::TooLong { len: memo.len(), max: MEMO_SIZE });
+    }
+
+    let text = match memo.first() {
+        None | Some(0xF6) => return Ok(String::new()),
+        Some(&byte) if byte <= 0xF4 => {
+            let end = memo.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+            memo[..end].to_vec()
+        }
+        Some(&COMPRESSED_MEMO_MARKER) if memo.get(1) == Some(&COMPRESSED_MEMO_DEFLATE_V1) => {
+            let header = memo.get(..COMPRESSED_MEMO_HEADER_LEN)
+                .ok_or_else(|| MemoError::InvalidMemo("Truncated compressed memo header".to_string()))?;
+            let len = u16::from_le_bytes([header[2], header[3]]) as usize;
+            let payload = memo.get(COMPRESSED_MEMO_HEADER_LEN..COMPRESSED_MEMO_HEADER_LEN + len)
+                .ok_or_else(|| MemoError::InvalidMemo("Compressed memo length out of range".to_string()))?;
+            miniz_oxide::inflate::decompress_to_vec_with_limit(payload, MAX_DECOMPRESSED_MEMO_LEN)
+                .map_err(|e| MemoError::InvalidMemo(format!("Failed to decompress memo: {:?}", e.status)))?
+        }
+        Some(byte) => {
+            return Err(MemoError::InvalidMemo(format!("Unsupported memo type 0x{:02X}", byte)));
+        }
+    };
+
+    String::from_utf8(text).map_err(|_| MemoError::InvalidMemo("Memo is not valid UTF-8".to_string()))
+}
+
+/// Sequence number of a final transparent input (the default when none is set)
+pub const FINAL_SEQUENCE: u32 = 0xFFFF_FFFF;
+
+/// Highest expiry height a transaction may carry (ZIP 203)
+pub const MAX_EXPIRY_HEIGHT: u32 = 499_999_999;
+
 /// Calculates the ZIP-317 transaction fee.
 ///
 /// This implements the standard ZIP-317 fee calculation:
@@ -37,7 +175,10 @@ pub const ZIP317_GRACE_ACTIONS: usize = 2;
 /// - `grace_actions` = 2 (minimum actions to encourage small transactions)
 /// - For shielded: `logical_actions = transparent_actions + orchard_actions`
 /// - For transparent-only: `logical_actions = max(inputs, outputs)`
-/// - Orchard actions are padded to even numbers (bundling optimization)
+/// - Orchard actions are padded to at least 2, as the transaction builder does
+///
+/// Every input and output is assumed to be P2PKH. `FeeEstimator` prices other
+/// shapes (P2SH inputs, Sapling, other scripts) and also estimates the size.
 ///
 /// # Arguments
 /// * `num_transparent_inputs` - Number of transparent UTXOs being spent
@@ -64,227 +205,780 @@ pub fn calculate_fee(
     num_transparent_outputs: usize,
     num_orchard_outputs: usize,
 ) -> u64 {
-    let logical_actions = if num_orchard_outputs > 0 {
-        // Shielded transaction
-        // Orchard actions are padded to even numbers for bundling
-        let orchard_actions = ((num_orchard_outputs + 1) / 2) * 2;
-        let transparent_actions = std::cmp::max(num_transparent_inputs, num_transparent_outputs);
-        transparent_actions + orchard_actions
-    } else {
-        // Transparent-only
-        std::cmp::max(num_transparent_inputs, num_transparent_outputs)
-    };
+    zip317_fee(
+        num_transparent_inputs,
+        &vec![P2PKH_OUTPUT_SIZE as usize; num_transparent_outputs],
+        ShieldedSpends::default(),
+        num_orchard_outputs,
+    )
+}
 
-    ZIP317_MARGINAL_FEE * std::cmp::max(ZIP317_GRACE_ACTIONS, logical_actions) as u64
+/// Number of shielded notes a proposal spends, per pool
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ShieldedSpends {
+    pub(crate) sapling: usize,
+    pub(crate) orchard: usize,
 }
 
-/// Proposes a transaction by creating a PCZT from transparent inputs and a transaction request.
+/// Fewest actions the transaction builder pads an Orchard bundle to
+const ORCHARD_MIN_ACTIONS: usize = 2;
+
+/// Actions of an Orchard bundle with `spends` spends and `outputs` outputs. Each action
+/// carries one spend and one output, and the builder pads a non-empty bundle to
+/// `ORCHARD_MIN_ACTIONS`.
+pub(crate) fn orchard_bundle_actions(spends: usize, outputs: usize) -> usize {
+    match spends.max(outputs) {
+        0 => 0,
+        actions => actions.max(ORCHARD_MIN_ACTIONS),
+    }
+}
+
+/// `calculate_fee` for a transaction that also spends shielded notes, with its
+/// transparent outputs given by serialized size (see `transparent_output_size`)
+fn zip317_fee(
+    num_transparent_inputs: usize,
+    transparent_output_sizes: &[usize],
+    spends: ShieldedSpends,
+    num_orchard_outputs: usize,
+) -> u64 {
+    // The builder pads a Sapling bundle with spends to at least two outputs
+    let sapling_outputs = if spends.sapling > 0 { 2 } else { 0 };
+    let orchard_actions = orchard_bundle_actions(spends.orchard, num_orchard_outputs);
+
+    let mut estimator = FeeEstimator::new().with_p2pkh_inputs(num_transparent_inputs);
+    estimator.transparent_outputs.extend_from_slice(transparent_output_sizes);
+    estimator
+        .with_sapling_spends(spends.sapling)
+        .with_sapling_outputs(sapling_outputs)
+        .with_orchard_actions(orchard_actions)
+        .estimate()
+        .fee
+}
+
+/// The shape of a transaction, for pricing it under ZIP-317 and estimating its size.
 ///
-/// This implements the Creator, Constructor, and IO Finalizer roles.
+/// Applies the ZIP-317 rule as the transaction builder does:
+/// ```text
+/// logical_actions = max(ceil(t_in_size / 150), ceil(t_out_size / 34))
+///                 + max(sapling_spends, sapling_outputs)
+///                 + orchard_actions
+/// fee = 5000 * max(2, logical_actions)
+/// ```
+/// Transparent inputs and outputs count by serialized size, so P2SH multisig inputs
+/// and non-P2PKH outputs are priced exactly. A P2PKH input counts as the standard
+/// 150 bytes. Shielded counts are taken as given, padding included.
 ///
-/// # Arguments
-/// * `inputs_to_spend` - Serialized transparent input data in the following binary format:
-///   ```text
-///   [num_inputs: 2 bytes (u16 LE)]
-///   For each input:
-///     [pubkey: 33 bytes]        - Compressed secp256k1 public key
-///     [txid: 32 bytes]          - Transaction ID of the UTXO being spent
-///     [vout: 4 bytes (u32 LE)]  - Output index in the previous transaction
-///     [amount: 8 bytes (u64 LE)]- Amount in zatoshis
-///     [script_len: 2 bytes (u16 LE)] - Length of script_pubkey
-///     [script: script_len bytes]     - The script_pubkey of the UTXO
-///   ```
-///   See `types::parse_transparent_inputs()` for the parser and
-///   `types::serialize_transparent_inputs()` for the serializer.
+/// # Example
+/// ```
+/// use t2z::FeeEstimator;
+///
+/// let estimate = FeeEstimator::new()
+///     .with_p2pkh_inputs(3)
+///     .with_p2pkh_outputs(1)
+///     .with_orchard_actions(2)
+///     .estimate();
+/// assert_eq!(estimate.fee, 25_000); // (3 transparent + 2 Orchard) * 5000
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FeeEstimator {
+    /// (size ZIP-317 counts, serialized size) of each transparent input
+    transparent_inputs: Vec<(usize, usize)>,
+    /// Serialized size of each transparent output
+    transparent_outputs: Vec<usize>,
+    sapling_spends: usize,
+    sapling_outputs: usize,
+    orchard_actions: usize,
+}
+
+/// ZIP-317 fee and size of a transaction shape (see `FeeEstimator::estimate`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeEstimate {
+    /// Conventional fee in zatoshis
+    pub fee: u64,
+    /// Logical actions the fee is charged for (before the grace actions minimum)
+    pub logical_actions: usize,
+    /// Upper bound on the serialized size of the final transaction, in bytes
+    pub size: usize,
+}
+
+impl FeeEstimator {
+    /// An empty transaction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Shape of the transaction a PCZT extracts to
+    pub fn from_pczt(pczt: &Pczt) -> Self {
+        let mut estimator = Self::new();
+        for input in pczt.transparent().inputs() {
+            estimator = match input.redeem_script() {
+                Some(script) => estimator.with_p2sh_multisig_input(script),
+                None => estimator.with_p2pkh_inputs(1),
+            };
+        }
+        for output in pczt.transparent().outputs() {
+            estimator = estimator.with_transparent_output(output.script_pubkey());
+        }
+        estimator
+            .with_sapling_spends(pczt.sapling().spends().len())
+            .with_sapling_outputs(pczt.sapling().outputs().len())
+            .with_orchard_actions(pczt.orchard().actions().len())
+    }
+
+    /// Adds `count` P2PKH inputs
+    pub fn with_p2pkh_inputs(mut self, count: usize) -> Self {
+        self.transparent_inputs
+            .extend(std::iter::repeat_n((P2PKH_STANDARD_INPUT_SIZE, P2PKH_INPUT_SIZE as usize), count));
+        self
+    }
+
+    /// Adds an input spending a P2SH multisig output, sized for the signatures its
+    /// redeem script needs
+    pub fn with_p2sh_multisig_input(mut self, redeem_script: &[u8]) -> Self {
+        let size = p2sh_multisig_input_size(redeem_script);
+        self.transparent_inputs.push((size, size));
+        self
+    }
+
+    /// Adds `count` P2PKH outputs
+    pub fn with_p2pkh_outputs(mut self, count: usize) -> Self {
+        self.transparent_outputs.extend(std::iter::repeat_n(P2PKH_OUTPUT_SIZE as usize, count));
+        self
+    }
+
+    /// Adds a transparent output paying to `script_pubkey`
+    pub fn with_transparent_output(mut self, script_pubkey: &[u8]) -> Self {
+        self.transparent_outputs.push(transparent_output_size(script_pubkey));
+        self
+    }
+
+    /// Sets the number of Sapling spends
+    pub fn with_sapling_spends(mut self, count: usize) -> Self {
+        self.sapling_spends = count;
+        self
+    }
+
+    /// Sets the number of Sapling outputs, padding included
+    pub fn with_sapling_outputs(mut self, count: usize) -> Self {
+        self.sapling_outputs = count;
+        self
+    }
+
+    /// Sets the number of Orchard actions, padding included
+    pub fn with_orchard_actions(mut self, count: usize) -> Self {
+        self.orchard_actions = count;
+        self
+    }
+
+    /// Computes the ZIP-317 fee and the serialized size of the transaction
+    pub fn estimate(&self) -> FeeEstimate {
+        let input_size: usize = self.transparent_inputs.iter().map(|(zip317_size, _)| zip317_size).sum();
+        let output_size: usize = self.transparent_outputs.iter().sum();
+        let logical_actions = std::cmp::max(
+            input_size.div_ceil(P2PKH_STANDARD_INPUT_SIZE),
+            output_size.div_ceil(P2PKH_OUTPUT_SIZE as usize),
+        ) + std::cmp::max(self.sapling_spends, self.sapling_outputs)
+            + self.orchard_actions;
+
+        FeeEstimate {
+            fee: ZIP317_MARGINAL_FEE * std::cmp::max(ZIP317_GRACE_ACTIONS, logical_actions) as u64,
+            logical_actions,
+            size: self.serialized_size(),
+        }
+    }
+
+    /// Upper bound on the serialized size of a v5 transaction of this shape, with
+    /// maximal-length transparent signatures
+    fn serialized_size(&self) -> usize {
+        let inputs = self.transparent_inputs.len();
+        let input_size: usize = self.transparent_inputs.iter().map(|(_, size)| size).sum();
+        let outputs = self.transparent_outputs.len();
+        let output_size: usize = self.transparent_outputs.iter().sum();
+
+        let (spends, sapling_outputs) = (self.sapling_spends, self.sapling_outputs);
+        let mut sapling_size = compact_size_len(spends) + spends * SAPLING_SPEND_SIZE
+            + compact_size_len(sapling_outputs) + sapling_outputs * SAPLING_OUTPUT_SIZE;
+        if spends + sapling_outputs > 0 {
+            // Value balance, anchor (only with spends) and binding signature
+            sapling_size += 8 + if spends > 0 { 32 } else { 0 } + 64;
+        }
+
+        let actions = self.orchard_actions;
+        let mut orchard_size = compact_size_len(actions) + actions * ORCHARD_ACTION_SIZE;
+        if actions > 0 {
+            let proof = ORCHARD_PROOF_BASE_SIZE + actions * ORCHARD_PROOF_ACTION_SIZE;
+            // Flags, value balance, anchor, proof and binding signature
+            orchard_size += 1 + 8 + 32 + compact_size_len(proof) + proof + 64;
+        }
+
+        TX_V5_HEADER_SIZE
+            + compact_size_len(inputs) + input_size
+            + compact_size_len(outputs) + output_size
+            + sapling_size
+            + orchard_size
+    }
+}
+
+/// Computes the ZIP-317 conventional fee for the shape of a PCZT.
 ///
-/// * `transaction_request` - The transaction request containing recipient information
-/// * `change_address` - Optional transparent address for change output. If None, derives from first input's pubkey
+/// Logical actions are counted from the PCZT itself (transparent inputs and
+/// outputs, Sapling spends and outputs, Orchard actions), so verifiers that
+/// receive a PCZT without the request it was proposed from can still check
+/// what it should pay. Orchard actions are already padded in a PCZT, so this
+/// agrees with `calculate_fee` for the PCZTs `propose_transaction` creates.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to price
 ///
 /// # Returns
-/// * `Result<Pczt, ProposalError>` - The created PCZT or an error
-pub fn propose_transaction(
-    inputs_to_spend: &[u8],
-    transaction_request: TransactionRequest,
-    change_address: Option<String>,
-) -> Result<Pczt, ProposalError> {
-    // Validate inputs
-    if transaction_request.payments.is_empty() {
-        return Err(ProposalError::InvalidRequest("No payments provided".to_string()));
+/// The conventional fee in zatoshis
+pub fn conventional_fee(pczt: &Pczt) -> u64 {
+    FeeEstimator::from_pczt(pczt).estimate().fee
+}
+
+/// Size of a standard P2PKH input, the unit ZIP-317 counts transparent inputs in
+const P2PKH_STANDARD_INPUT_SIZE: usize = 150;
+
+/// Size ZIP-317 counts a transparent input as: a standard P2PKH input, or for a P2SH
+/// multisig input the largest input its redeem script can need
+#[cfg(feature = "proposal")]
+fn zip317_input_size(redeem_script: Option<&[u8]>) -> usize {
+    redeem_script.map_or(P2PKH_STANDARD_INPUT_SIZE, p2sh_multisig_input_size)
+}
+
+/// Logical actions for transparent inputs of the given sizes (ZIP-317 counts their
+/// total size in units of a standard P2PKH input)
+#[cfg(feature = "proposal")]
+fn zip317_input_actions(sizes: impl Iterator<Item = usize>) -> usize {
+    sizes.sum::<usize>().div_ceil(P2PKH_STANDARD_INPUT_SIZE)
+}
+
+/// Logical actions `inputs` count for on the transparent side, where a P2SH multisig
+/// input carrying several signatures counts for more than one
+#[cfg(feature = "proposal")]
+pub(crate) fn transparent_input_actions(inputs: &[TransparentInput]) -> usize {
+    zip317_input_actions(inputs.iter().map(|input| zip317_input_size(input.redeem_script.as_deref())))
+}
+
+/// Upper bound on the serialized size of an input spending a P2SH multisig output:
+/// outpoint, sequence and a script_sig of `OP_0 <signature>... <redeem_script>`
+fn p2sh_multisig_input_size(redeem_script: &[u8]) -> usize {
+    let threshold = multisig_keys(redeem_script).map_or(0, |(threshold, _)| threshold);
+    // Maximal DER signatures plus their sighash type byte, each behind a 1-byte push
+    let signatures = threshold * (1 + 73);
+    let redeem_push = match redeem_script.len() {
+        0..=0x4b => 1,
+        0x4c..=0xff => 2,
+        _ => 3,
+    } + redeem_script.len();
+    let script_sig = 1 + signatures + redeem_push;
+    36 + compact_size_len(script_sig) + script_sig + 4
+}
+
+/// Serialized size of a v5 transaction header (version, version group ID,
+/// consensus branch ID, lock time and expiry height)
+const TX_V5_HEADER_SIZE: usize = 20;
+
+/// Serialized size of an Orchard action without its share of the proof
+/// (cv, nullifier, rk, cmx, ephemeral key, both ciphertexts and its spend auth signature)
+const ORCHARD_ACTION_SIZE: usize = 32 * 5 + 580 + 80 + 64;
+
+/// Size of an Orchard proof is `ORCHARD_PROOF_BASE_SIZE + n * ORCHARD_PROOF_ACTION_SIZE`
+const ORCHARD_PROOF_BASE_SIZE: usize = 2720;
+const ORCHARD_PROOF_ACTION_SIZE: usize = 2272;
+
+/// Serialized size of a Sapling spend (cv, nullifier, rk, proof, spend auth signature)
+const SAPLING_SPEND_SIZE: usize = 32 * 3 + 192 + 64;
+
+/// Serialized size of a Sapling output (cv, cmu, ephemeral key, both ciphertexts, proof)
+const SAPLING_OUTPUT_SIZE: usize = 32 * 3 + 580 + 80 + 192;
+
+/// Length of the CompactSize encoding of `n`
+fn compact_size_len(n: usize) -> usize {
+    match n {
+        0..=0xfc => 1,
+        0xfd..=0xffff => 3,
+        0x1_0000..=0xffff_ffff => 5,
+        _ => 9,
     }
+}
 
-    // Select network parameters based on request
-    // For regtest, use mainnet parameters (regtest uses mainnet branch IDs)
-    // For testnet, use testnet parameters
-    if transaction_request.use_mainnet {
-        propose_transaction_with_network(inputs_to_spend, transaction_request, change_address, MainNetwork)
-    } else {
-        propose_transaction_with_network(inputs_to_spend, transaction_request, change_address, TestNetwork)
-    }
-}
-
-/// Internal helper that creates a transaction with specific network parameters
-fn propose_transaction_with_network<P: Parameters>(
-    inputs_to_spend: &[u8],
-    transaction_request: TransactionRequest,
-    change_address: Option<String>,
-    params: P,
-) -> Result<Pczt, ProposalError> {
-    // Default target heights: mainnet ~2.5M, testnet ~3.7M (both post-NU5)
-    let default_height = if transaction_request.use_mainnet { 2_500_000 } else { 3_693_760 };
-    let target_height = transaction_request.target_height.unwrap_or(default_height).into();
-
-    // Create transaction builder
-    let mut builder = Builder::new(
-        params,
-        target_height,
-        BuildConfig::Standard {
-            sapling_anchor: None,
-            orchard_anchor: Some(orchard::Anchor::empty_tree()),
-        },
-    );
+/// Serialized size of a transparent output paying to `script_pubkey`
+fn transparent_output_size(script_pubkey: &[u8]) -> usize {
+    8 + compact_size_len(script_pubkey.len()) + script_pubkey.len()
+}
+
+/// Serialized sizes of the transparent outputs a proposal adds for `payments`
+/// (Orchard payments have none). Payments whose script can't be determined are
+/// priced as P2PKH; the proposal rejects them anyway.
+pub(crate) fn transparent_payment_output_sizes(payments: &[Payment]) -> Vec<usize> {
+    payments.iter()
+        .filter(|payment| !payment.is_unified())
+        .map(|payment| {
+            payment.output_script().map_or(P2PKH_OUTPUT_SIZE as usize, |script| transparent_output_size(&script))
+        })
+        .collect()
+}
+
+/// Serialized size of a transparent change output to `change_address`, or to the
+/// P2PKH address derived from an input's key if there is none
+pub(crate) fn change_output_size(change_address: Option<&str>) -> usize {
+    change_address
+        .and_then(address_to_script)
+        .map_or(P2PKH_OUTPUT_SIZE as usize, |script| transparent_output_size(&script))
+}
+
+/// Largest transaction zcashd accepts into its mempool or a block, in bytes
+pub const MAX_TX_SIZE: usize = 2_000_000;
+
+/// Estimates the serialized size of a transaction with the given shape, in bytes.
+///
+/// Counts match `calculate_fee`: transparent inputs are P2PKH with maximal-length
+/// signatures, and Orchard outputs are padded to at least 2 actions as the
+/// transaction builder does. The estimate is an upper bound on the final size.
+///
+/// # Example
+/// ```
+/// use t2z::{estimate_tx_size, MAX_TX_SIZE};
+///
+/// let size = estimate_tx_size(2, 1, 1);
+/// assert!(size < MAX_TX_SIZE);
+/// ```
+pub fn estimate_tx_size(
+    num_transparent_inputs: usize,
+    num_transparent_outputs: usize,
+    num_orchard_outputs: usize,
+) -> usize {
+    FeeEstimator::new()
+        .with_p2pkh_inputs(num_transparent_inputs)
+        .with_p2pkh_outputs(num_transparent_outputs)
+        .with_orchard_actions(orchard_bundle_actions(0, num_orchard_outputs))
+        .estimate()
+        .size
+}
+
+/// Estimates the serialized size of the transaction a PCZT extracts to, in bytes.
+///
+/// Transparent inputs are assumed to be P2PKH, or P2SH multisig if they carry a
+/// redeem script, with maximal-length signatures, so the estimate is an upper bound
+/// for the PCZTs `propose_transaction` creates. Divide the fee by it for a
+/// fee-per-byte figure, or compare it with `MAX_TX_SIZE`.
+pub fn estimated_final_size(pczt: &Pczt) -> usize {
+    FeeEstimator::from_pczt(pczt).estimate().size
+}
+
+/// zcashd's relay fee rate (zatoshis per 1000 bytes) used for the dust standardness rule
+pub const ONE_THIRD_DUST_THRESHOLD_RATE: u64 = 100;
+
+/// Serialized size of a P2PKH output
+const P2PKH_OUTPUT_SIZE: u64 = 34;
+
+/// Serialized size of the input that later spends a P2PKH output
+const P2PKH_INPUT_SIZE: u64 = 148;
 
-    // Parse and add transparent inputs from the provided data
-    let inputs = types::parse_transparent_inputs(inputs_to_spend)
-        .map_err(|e| ProposalError::InvalidRequest(format!("Failed to parse inputs: {}", e)))?;
+/// Returns the standardness dust threshold (in zatoshis) for a P2PKH output.
+///
+/// Mirrors zcashd's `GetDustThreshold`: an output is dust if it is worth less than
+/// three times the relay fee for creating and later spending it (currently 54 zatoshis).
+/// Nodes refuse to relay transactions with transparent outputs below this value.
+pub fn p2pkh_dust_threshold() -> u64 {
+    3 * (ONE_THIRD_DUST_THRESHOLD_RATE * (P2PKH_OUTPUT_SIZE + P2PKH_INPUT_SIZE) / 1000)
+}
+
+/// The change output and fee chosen in the first pass of a proposal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangePlan {
+    /// Change amount in zatoshis (0 if no change output will be added)
+    pub change: u64,
+    /// Fee paid by the planned transaction: the ZIP-317 fee for its shape, plus any
+    /// dust change folded into it
+    pub fee: u64,
+}
+
+/// Decides whether a transaction needs a change output, and computes the ZIP-317 fee
+/// for the resulting input/output/action counts.
+///
+/// A change output is only added when the remainder can pay for its own action.
+/// Otherwise the fee is recomputed for the no-change shape, and any remainder above
+/// it is left for [`check_fee_invariant`] to judge. Change below the `AddChangeToFee`
+/// threshold of `dust_policy` is dropped and added to the fee.
+///
+/// # Arguments
+/// * `num_transparent_inputs` - Number of transparent UTXOs being spent
+/// * `num_transparent_payment_outputs` - Number of transparent payments (excluding change)
+/// * `num_orchard_outputs` - Number of Orchard (shielded) payments
+/// * `total_input` - Sum of all transparent inputs
+/// * `total_payments` - Sum of all requested payments
+/// * `dust_policy` - How to handle a change amount that would be dust
+///
+/// # Returns
+/// The planned change and fee, or `ProposalError::InsufficientFunds` if the inputs
+/// don't cover the payments plus the no-change fee.
+pub fn plan_change(
+    num_transparent_inputs: usize,
+    num_transparent_payment_outputs: usize,
+    num_orchard_outputs: usize,
+    total_input: u64,
+    total_payments: u64,
+    dust_policy: DustPolicy,
+) -> Result<ChangePlan, ProposalError> {
+    plan_change_in_pool(
+        num_transparent_inputs,
+        ShieldedSpends::default(),
+        &vec![P2PKH_OUTPUT_SIZE as usize; num_transparent_payment_outputs],
+        num_orchard_outputs,
+        total_input,
+        total_payments,
+        dust_policy,
+        ChangeOutput::Transparent { size: P2PKH_OUTPUT_SIZE as usize },
+        FeePolicy::Zip317Standard,
+    )
+}
+
+/// Where a planned change output goes, for pricing it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ChangeOutput {
+    /// A transparent output of `size` serialized bytes (see `change_output_size`)
+    Transparent { size: usize },
+    /// An Orchard output
+    Orchard,
+}
 
-    for input in &inputs {
-        let outpoint = input.outpoint();
-        let coin = input.txout()
-            .map_err(|e| ProposalError::InvalidRequest(format!("Invalid input data: {}", e)))?;
+/// `plan_change` for inputs that include shielded `spends`, transparent payment
+/// outputs of the given serialized sizes, a change output priced as `change`, and
+/// fees under `fee_policy`
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn plan_change_in_pool(
+    num_transparent_inputs: usize,
+    spends: ShieldedSpends,
+    transparent_payment_outputs: &[usize],
+    num_orchard_outputs: usize,
+    total_input: u64,
+    total_payments: u64,
+    dust_policy: DustPolicy,
+    change: ChangeOutput,
+    fee_policy: FeePolicy,
+) -> Result<ChangePlan, ProposalError> {
+    let fee_with_change = fee_policy.fee(match change {
+        ChangeOutput::Orchard => {
+            zip317_fee(num_transparent_inputs, transparent_payment_outputs, spends, num_orchard_outputs + 1)
+        }
+        ChangeOutput::Transparent { size } => {
+            let outputs = [transparent_payment_outputs, &[size]].concat();
+            zip317_fee(num_transparent_inputs, &outputs, spends, num_orchard_outputs)
+        }
+    });
+    let fee_without_change = fee_policy.fee(
+        zip317_fee(num_transparent_inputs, transparent_payment_outputs, spends, num_orchard_outputs)
+    );
 
-        builder.add_transparent_input(input.pubkey, outpoint, coin)
-            .map_err(|e| ProposalError::PcztCreation(format!("Failed to add transparent input: {:?}", e)))?;
+    if total_input > total_payments.saturating_add(fee_with_change) {
+        let change = total_input - total_payments - fee_with_change;
+        match dust_policy {
+            DustPolicy::AddChangeToFee { threshold } if change < threshold => {
+                // Dust change: drop the output and let the miner have it
+                Ok(ChangePlan {
+                    change: 0,
+                    fee: total_input - total_payments,
+                })
+            }
+            // Enough left over to pay for a change output
+            _ => Ok(ChangePlan {
+                change,
+                fee: fee_with_change,
+            }),
+        }
+    } else if total_input >= total_payments.saturating_add(fee_without_change) {
+        // Exact change, or a remainder too small to pay for its own output
+        Ok(ChangePlan {
+            change: 0,
+            fee: fee_without_change,
+        })
+    } else {
+        Err(ProposalError::InsufficientFunds {
+            available: total_input,
+            required: total_payments.saturating_add(fee_without_change),
+            fee: fee_without_change,
+        })
     }
+}
 
-    // Add outputs from payment request
-    for payment in &transaction_request.payments {
-        // Parse the address
-        let addr_str = payment.address.as_str();
-        let addr = addr_str.parse::<ZcashAddress>()
-            .map_err(|_| ProposalError::InvalidAddress(payment.address.clone()))?;
-
-        // Convert amount to Zatoshis
-        let amount = Zatoshis::from_u64(payment.amount)
-            .map_err(|_| ProposalError::InvalidRequest(format!("Invalid amount: {}", payment.amount)))?;
-
-        // Try to convert to transparent address first
-        if let Ok(t_addr) = addr.clone().convert::<TransparentAddress>() {
-            // Add transparent output
-            builder.add_transparent_output(&t_addr, amount)
-                .map_err(|e| ProposalError::PcztCreation(format!("Failed to add transparent output: {:?}", e)))?;
+/// Splits inputs into those with at least `min_confirmations` confirmations at
+/// `target_height` and the indices of those without.
+///
+/// Inputs whose height is unknown are excluded, since their depth can't be checked.
+/// Coin selection can use the first half directly; proposals reject any exclusion.
+///
+/// # Arguments
+/// * `inputs` - Candidate transparent inputs
+/// * `target_height` - Height of the block the transaction is expected to be mined in
+/// * `min_confirmations` - Required confirmation depth (0 keeps every input)
+///
+/// # Returns
+/// * `(Vec<TransparentInput>, Vec<usize>)` - Eligible inputs, and indices of excluded inputs
+pub fn filter_by_confirmations(
+    inputs: &[TransparentInput],
+    target_height: u32,
+    min_confirmations: u32,
+) -> (Vec<TransparentInput>, Vec<usize>) {
+    let mut eligible = Vec::with_capacity(inputs.len());
+    let mut excluded = Vec::new();
+
+    for (index, input) in inputs.iter().enumerate() {
+        let deep_enough = min_confirmations == 0
+            || input.confirmations(target_height).is_some_and(|c| c >= min_confirmations);
+        if deep_enough {
+            eligible.push(input.clone());
         } else {
-            // Try to handle as unified address
-            use zcash_address::unified::Container;
-
-            let unified_wrapper = addr.convert::<UnifiedAddressWrapper>()
-                .map_err(|e| ProposalError::InvalidAddress(format!("Address must be transparent or unified with Orchard receiver: {:?}", e)))?;
-
-            // Check if unified address has Orchard receiver
-            let receivers = unified_wrapper.0.items();
-            let orchard_receiver = receivers.iter()
-                .find_map(|receiver| {
-                    if let unified::Receiver::Orchard(raw_addr) = receiver {
-                        Some(raw_addr)
-                    } else {
-                        None
-                    }
-                });
-
-            if let Some(orchard_raw) = orchard_receiver {
-                // Convert raw Orchard address bytes to orchard::Address
-                let orchard_addr: orchard::Address = Option::from(orchard::Address::from_raw_address_bytes(orchard_raw))
-                    .ok_or_else(|| ProposalError::InvalidAddress("Invalid Orchard address bytes".to_string()))?;
-
-                // Add Orchard output
-                // Use None for OVK since we don't have sender's keys
-                let memo = payment.memo.as_ref()
-                    .and_then(|m| MemoBytes::from_bytes(m.as_bytes()).ok())
-                    .unwrap_or_else(|| MemoBytes::empty());
-
-                builder.add_orchard_output::<FeeRule>(None, orchard_addr, amount.into_u64(), memo)
-                    .map_err(|e| ProposalError::PcztCreation(format!("Failed to add Orchard output: {:?}", e)))?;
-            } else {
-                return Err(ProposalError::InvalidAddress(
-                    format!("Unified address does not contain Orchard receiver: {}", payment.address)
-                ));
-            }
+            excluded.push(index);
         }
     }
 
-    // Calculate change if needed
-    let total_input: u64 = inputs.iter().map(|i| i.amount).sum();
-    let total_output: u64 = transaction_request.total_amount();
+    (eligible, excluded)
+}
 
-    // Count outputs for fee calculation (assuming change will be needed)
-    let num_orchard_outputs = transaction_request.payments.iter()
-        .filter(|p| p.is_unified())
-        .count();
-    let num_transparent_payment_outputs = transaction_request.payments.iter()
-        .filter(|p| !p.is_unified())
-        .count();
-    // +1 for change output (we assume change is needed for fee calculation)
-    let num_transparent_outputs = num_transparent_payment_outputs + 1;
+/// Checks that `inputs == payments + change + fee` for a proposal.
+///
+/// The implicit fee (inputs minus all outputs) may exceed the ZIP-317 fee by at most
+/// `tolerance` zatoshis. Shortfalls are left to the builder, which reports them as
+/// insufficient funds.
+///
+/// # Arguments
+/// * `total_input` - Sum of all transparent inputs
+/// * `total_payments` - Sum of all requested payments
+/// * `change` - Change amount (0 if no change output)
+/// * `fee` - ZIP-317 fee for the transaction shape being built
+/// * `tolerance` - Maximum overpayment allowed on top of `fee`
+pub fn check_fee_invariant(
+    total_input: u64,
+    total_payments: u64,
+    change: u64,
+    fee: u64,
+    tolerance: u64,
+) -> Result<(), ProposalError> {
+    let total_out = total_payments.checked_add(change)
+        .ok_or_else(|| ProposalError::FeeCalculation("Output total overflows".to_string()))?;
+
+    let Some(implicit_fee) = total_input.checked_sub(total_out) else {
+        return Ok(());
+    };
 
-    let estimated_fee = calculate_fee(inputs.len(), num_transparent_outputs, num_orchard_outputs);
+    if implicit_fee > fee.saturating_add(tolerance) {
+        return Err(ProposalError::ExcessiveFee {
+            implicit_fee,
+            expected_fee: fee,
+            tolerance,
+        });
+    }
 
-    // If we have change (inputs > outputs + fee), add a change output
-    if total_input > total_output + estimated_fee {
-        let change_amount = total_input - total_output - estimated_fee;
+    Ok(())
+}
 
-        // Get or derive change address
-        let change_addr = if let Some(addr_str) = change_address {
-            // Parse provided change address
-            addr_str.parse::<ZcashAddress>()
-                .map_err(|_| ProposalError::InvalidAddress(addr_str))?
-                .convert::<TransparentAddress>()
-                .map_err(|_| ProposalError::InvalidRequest("Change address must be transparent".to_string()))?
-        } else {
-            // Derive from first input's pubkey
-            if inputs.is_empty() {
-                return Err(ProposalError::InvalidRequest("No inputs provided for change derivation".to_string()));
-            }
-            TransparentAddress::from_pubkey(&inputs[0].pubkey)
-        };
+/// Refuses a planned transparent change output below the threshold of a
+/// `DustPolicy::Reject` (`AddChangeToFee` has already folded it into the fee).
+pub(crate) fn check_dust_change(
+    request: &TransactionRequest,
+    plan: &ChangePlan,
+    orchard_change: bool,
+) -> Result<(), ProposalError> {
+    match request.dust_policy {
+        DustPolicy::Reject { threshold } if !orchard_change && plan.change > 0 && plan.change < threshold => {
+            Err(ProposalError::DustChange { change: plan.change, threshold })
+        }
+        _ => Ok(()),
+    }
+}
 
-        // Add change output
-        let change_zatoshis = Zatoshis::from_u64(change_amount)
-            .map_err(|_| ProposalError::InvalidRequest(format!("Invalid change amount: {}", change_amount)))?;
+/// Decides whether change goes to an Orchard output under the request's `change_policy`
+pub(crate) fn change_is_orchard(
+    transaction_request: &TransactionRequest,
+    change_address: Option<&str>,
+) -> Result<bool, ProposalError> {
+    let has_orchard_receiver = change_address.is_some_and(|address| orchard_receiver(address).is_some());
+    match transaction_request.change_policy {
+        ChangePolicy::Transparent => Ok(false),
+        ChangePolicy::Auto => Ok(has_orchard_receiver),
+        ChangePolicy::Orchard if has_orchard_receiver => Ok(true),
+        ChangePolicy::Orchard => Err(ProposalError::InvalidRequest(
+            "Orchard change needs a unified change address with an Orchard receiver".to_string()
+        )),
+    }
+}
 
-        builder.add_transparent_output(&change_addr, change_zatoshis)
-            .map_err(|e| ProposalError::PcztCreation(format!("Failed to add change output: {:?}", e)))?;
+/// Whether `pubkey` can sign an input paying to `script_pubkey`: the key of a P2PKH
+/// script, or a key of a P2SH input's multisig `redeem_script`
+fn input_has_key(script_pubkey: &[u8], redeem_script: Option<&[u8]>, pubkey: &[u8; 33]) -> bool {
+    match script_pubkey {
+        [0x76, 0xa9, 0x14, key_hash @ .., 0x88, 0xac] => ct_eq_bytes(&hash160(pubkey), key_hash),
+        [0xa9, 0x14, .., 0x87] => redeem_script
+            .and_then(multisig_keys)
+            .is_some_and(|(_, keys)| keys.iter().any(|key| ct_eq_bytes(key, pubkey))),
+        _ => false,
     }
+}
 
-    // Build PCZT from the builder
-    let pczt_result = builder.build_for_pczt(OsRng, &FeeRule::standard())
-        .map_err(|e| ProposalError::PcztCreation(format!("Builder failed: {:?}", e)))?;
+/// Adds information to a PCZT that its creator did not have.
+///
+/// This implements the Updater role for intermediate parties in a multi-party flow,
+/// e.g. a coordinator recording which wallet holds each input's key, or a party
+/// supplying the redeem script of a P2SH input. Updates are applied in order; every
+/// update is checked before any is applied.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to update
+/// * `updates` - The changes to make
+///
+/// # Returns
+/// * `Result<Pczt, UpdateError>` - The updated PCZT or an error
+pub fn update_pczt(pczt: Pczt, updates: &[PcztUpdate]) -> Result<Pczt, UpdateError> {
+    use pczt::roles::updater::Updater;
 
-    // Create PCZT from parts using Creator role
-    let mut pczt = Creator::build_from_parts(pczt_result.pczt_parts)
-        .ok_or_else(|| ProposalError::PcztCreation("Failed to build PCZT from parts".to_string()))?;
+    let transparent_inputs = pczt.transparent().inputs();
+    let num_outputs = pczt.transparent().outputs().len();
+
+    // Convert scripts and derivations up front, so the Updater closures can't fail halfway
+    let mut scripts = Vec::with_capacity(updates.len());
+    let mut derivations = Vec::with_capacity(updates.len());
+    for update in updates {
+        let (script, derivation) = match update {
+            PcztUpdate::GlobalProprietary { .. } => (None, None),
+            PcztUpdate::InputRedeemScript { index, script } => {
+                let input = transparent_inputs.get(*index).ok_or(UpdateError::InvalidInputIndex(*index))?;
+                // A P2SH input only accepts the script its hash commits to
+                if let [0xa9, 0x14, script_hash @ .., 0x87] = input.script_pubkey().as_slice() {
+                    if !ct_eq_bytes(&hash160(script), script_hash) {
+                        return Err(UpdateError::InvalidUpdate(format!(
+                            "Redeem script does not match the script hash of input {}", index
+                        )));
+                    }
+                }
+                (Some(redeem_script(script)?), None)
+            }
+            PcztUpdate::InputDerivation { index, derivation } => {
+                let input = transparent_inputs.get(*index).ok_or(UpdateError::InvalidInputIndex(*index))?;
+                // The derived key must be one that can sign the input. A P2SH input's
+                // redeem script may come from an earlier update in the same batch.
+                let redeem_script = updates.iter()
+                    .rev()
+                    .find_map(|update| match update {
+                        PcztUpdate::InputRedeemScript { index: i, script } if i == index => Some(script.as_slice()),
+                        _ => None,
+                    })
+                    .or(input.redeem_script().as_deref());
+                if !input_has_key(input.script_pubkey(), redeem_script, &derivation.pubkey) {
+                    return Err(UpdateError::InvalidUpdate(format!(
+                        "Derivation pubkey is not a key of input {}", index
+                    )));
+                }
+                (None, Some(bip32_derivation(derivation)?))
+            }
+            PcztUpdate::OutputDerivation { index, derivation } => {
+                if *index >= num_outputs {
+                    return Err(UpdateError::InvalidOutputIndex(*index));
+                }
+                (None, Some(bip32_derivation(derivation)?))
+            }
+            PcztUpdate::InputPreimage { index, .. } | PcztUpdate::InputProprietary { index, .. } => {
+                if *index >= transparent_inputs.len() {
+                    return Err(UpdateError::InvalidInputIndex(*index));
+                }
+                (None, None)
+            }
+            PcztUpdate::OutputRedeemScript { index, script } => {
+                if *index >= num_outputs {
+                    return Err(UpdateError::InvalidOutputIndex(*index));
+                }
+                (Some(redeem_script(script)?), None)
+            }
+            PcztUpdate::OutputUserAddress { index, .. } | PcztUpdate::OutputProprietary { index, .. } => {
+                if *index >= num_outputs {
+                    return Err(UpdateError::InvalidOutputIndex(*index));
+                }
+                (None, None)
+            }
+        };
+        scripts.push(script);
+        derivations.push(derivation);
+    }
 
-    // Use Updater role to add pubkey preimages (required for append_signature to work)
-    // This maps pubkey hashes to actual pubkeys for signature verification
-    use pczt::roles::updater::Updater;
-    let updater = Updater::new(pczt);
-    let updater = updater.update_transparent_with(|mut transparent_updater| {
-        // For each input, add the pubkey preimage
-        for (i, input) in inputs.iter().enumerate() {
-            transparent_updater.update_input_with(i, |mut input_updater| {
-                // Add the hash160 preimage (pubkey hash -> pubkey bytes)
-                input_updater.set_hash160_preimage(input.pubkey.serialize().to_vec());
-                Ok(())
-            })?;
+    let updater = Updater::new(pczt).update_global_with(|mut global| {
+        for update in updates {
+            if let PcztUpdate::GlobalProprietary { key, value } = update {
+                global.set_proprietary(key.clone(), value.clone());
+            }
+        }
+    });
+
+    let updater = updater.update_transparent_with(|mut transparent| {
+        for (i, update) in updates.iter().enumerate() {
+            match update {
+                PcztUpdate::GlobalProprietary { .. } => {}
+                PcztUpdate::InputRedeemScript { index, .. } => transparent.update_input_with(*index, |mut input| {
+                    input.set_redeem_script(scripts[i].clone().expect("converted above"));
+                    Ok(())
+                })?,
+                PcztUpdate::InputDerivation { index, derivation } => transparent.update_input_with(*index, |mut input| {
+                    input.set_bip32_derivation(derivation.pubkey, derivations[i].clone().expect("converted above"));
+                    Ok(())
+                })?,
+                PcztUpdate::InputPreimage { index, hash, preimage } => transparent.update_input_with(*index, |mut input| {
+                    let preimage = preimage.clone();
+                    match hash {
+                        PreimageHash::Ripemd160 => input.set_ripemd160_preimage(preimage),
+                        PreimageHash::Sha256 => input.set_sha256_preimage(preimage),
+                        PreimageHash::Hash160 => input.set_hash160_preimage(preimage),
+                        PreimageHash::Hash256 => input.set_hash256_preimage(preimage),
+                    }
+                    Ok(())
+                })?,
+                PcztUpdate::InputProprietary { index, key, value } => transparent.update_input_with(*index, |mut input| {
+                    input.set_proprietary(key.clone(), value.clone());
+                    Ok(())
+                })?,
+                PcztUpdate::OutputRedeemScript { index, .. } => transparent.update_output_with(*index, |mut output| {
+                    output.set_redeem_script(scripts[i].clone().expect("converted above"));
+                    Ok(())
+                })?,
+                PcztUpdate::OutputDerivation { index, derivation } => transparent.update_output_with(*index, |mut output| {
+                    output.set_bip32_derivation(derivation.pubkey, derivations[i].clone().expect("converted above"));
+                    Ok(())
+                })?,
+                PcztUpdate::OutputUserAddress { index, address } => transparent.update_output_with(*index, |mut output| {
+                    output.set_user_address(address.clone());
+                    Ok(())
+                })?,
+                PcztUpdate::OutputProprietary { index, key, value } => transparent.update_output_with(*index, |mut output| {
+                    output.set_proprietary(key.clone(), value.clone());
+                    Ok(())
+                })?,
+            }
         }
         Ok(())
-    }).map_err(|e| ProposalError::PcztCreation(format!("Failed to set pubkey preimages: {:?}", e)))?;
-    pczt = updater.finish();
+    }).map_err(|e| UpdateError::Failed(format!("{:?}", e)))?;
 
-    // Finalize I/O using IoFinalizer role
-    let pczt = IoFinalizer::new(pczt)
-        .finalize_io()
-        .map_err(|e| ProposalError::PcztCreation(format!("Failed to finalize I/O: {:?}", e)))?;
+    Ok(updater.finish())
+}
 
-    Ok(pczt)
+/// Parses a raw redeem script for `update_pczt`
+fn redeem_script(script: &[u8]) -> Result<zcash_transparent::address::Script, UpdateError> {
+    types::script_from_raw(script)
+        .map_err(|e| UpdateError::InvalidUpdate(format!("Invalid redeem script: {}", e)))
+}
+
+/// Converts a `KeyDerivation` for `update_pczt`
+fn bip32_derivation(derivation: &KeyDerivation) -> Result<zcash_transparent::pczt::Bip32Derivation, UpdateError> {
+    zcash_transparent::pczt::Bip32Derivation::parse(derivation.seed_fingerprint, derivation.path.clone())
+        .map_err(|e| UpdateError::InvalidUpdate(format!("Invalid derivation path: {:?}", e)))
+}
+
+/// Returns true if the PCZT has no shielded (Sapling or Orchard) components.
+///
+/// Transparent-only (t→t) transactions need no proofs, so `prove_transaction` is a
+/// no-op for them and extraction never builds the Orchard verifying key.
+pub fn is_transparent_only(pczt: &Pczt) -> bool {
+    pczt.orchard().actions().is_empty()
+        && pczt.sapling().spends().is_empty()
+        && pczt.sapling().outputs().is_empty()
 }
 
 /// Adds Orchard proofs to the PCZT.
@@ -292,212 +986,1668 @@ fn propose_transaction_with_network<P: Parameters>(
 /// This MUST be implemented using the Prover role provided by the pczt Rust crate.
 /// The proving operation may be done in parallel with other verification and signing operations.
 ///
-/// The Orchard proving key is lazily loaded and cached on first use.
+/// The Orchard proving key is lazily built and cached on first use; call
+/// `init_proving_keys` at startup to keep that stall off the first proof. It needs
+/// no parameter files, so it does not go through the `params::ParamsStore`.
+/// With the `sapling` feature, Sapling spends and outputs are proven first, with the
+/// Sapling parameters loaded from the `ParamsStore` (`shielded::SAPLING_SPEND_PARAMS`
+/// and `shielded::SAPLING_OUTPUT_PARAMS`). Transparent-only PCZTs are returned unchanged without touching the Prover role,
+/// so they can be proved even without the `orchard-prover` feature.
 ///
 /// # Arguments
 /// * `pczt` - The PCZT to add proofs to
 ///
 /// # Returns
 /// * `Result<Pczt, ProverError>` - The PCZT with proofs added or an error
+///   (`ProverError::NotBuilt` for a shielded PCZT without the `orchard-prover` feature)
 pub fn prove_transaction(pczt: Pczt) -> Result<Pczt, ProverError> {
-    use pczt::roles::prover::Prover;
-    use std::sync::OnceLock;
-
-    // Lazy-load the Orchard proving key on first use
-    static ORCHARD_PROVING_KEY: OnceLock<orchard::circuit::ProvingKey> = OnceLock::new();
-
-    let prover = Prover::new(pczt);
-
-    // Check if we need to create Orchard proofs
-    if prover.requires_orchard_proof() {
-        let proving_key = ORCHARD_PROVING_KEY.get_or_init(|| {
-            // Build the proving key (this is expensive but only happens once)
-            orchard::circuit::ProvingKey::build()
-        });
+    // Fast path: nothing to prove for t→t transactions
+    if is_transparent_only(&pczt) {
+        return Ok(pczt);
+    }
 
-        let prover = prover.create_orchard_proof(proving_key)
-            .map_err(|e| ProverError::OrchardProof(format!("{:?}", e)))?;
+    #[cfg(feature = "orchard-prover")]
+    {
+        prover::with_prover_threads(|| prover::prove_bundles(pczt))
+    }
+    #[cfg(not(feature = "orchard-prover"))]
+    {
+        Err(ProverError::NotBuilt)
+    }
+}
 
-        Ok(prover.finish())
-    } else {
-        // No Orchard outputs, return as-is
-        Ok(prover.finish())
+/// Adds proofs to many PCZTs at once, e.g. a batch of withdrawals being shielded.
+///
+/// The PCZTs are proved in parallel on one thread pool sized by
+/// `set_prover_threads`, all sharing the cached proving key. Each PCZT succeeds or
+/// fails on its own, like `prove_transaction`, and results come back in input order.
+///
+/// # Arguments
+/// * `pczts` - The PCZTs to add proofs to
+///
+/// # Returns
+/// * `Vec<Result<Pczt, ProverError>>` - One proved PCZT or error per input PCZT
+pub fn prove_transactions(pczts: Vec<Pczt>) -> Vec<Result<Pczt, ProverError>> {
+    #[cfg(feature = "orchard-prover")]
+    {
+        prover::prove_batch(pczts)
+    }
+    #[cfg(not(feature = "orchard-prover"))]
+    {
+        pczts.into_iter().map(prove_transaction).collect()
     }
 }
 
-// ============================================================================
-// Verification helper functions
-// ============================================================================
+/// Builds the Orchard proving key now instead of on the first `prove_transaction`.
+///
+/// Building takes several seconds, so hosts call this at startup (or on a background
+/// thread) to keep the stall out of their first proof. Calling it again is a no-op.
+///
+/// The key is rebuilt in every process: `orchard::circuit::ProvingKey` has no
+/// serialized form, so it can't be cached on disk or memory-mapped.
+///
+/// # Returns
+/// * `Result<(), ProverError>` - Success, or `ProverError::NotBuilt` without the
+///   `orchard-prover` feature
+pub fn init_proving_keys() -> Result<(), ProverError> {
+    #[cfg(feature = "orchard-prover")]
+    {
+        prover::orchard_proving_key();
+        Ok(())
+    }
+    #[cfg(not(feature = "orchard-prover"))]
+    {
+        Err(ProverError::NotBuilt)
+    }
+}
 
-/// Extracts raw script bytes from a Script, stripping the CompactSize prefix
-fn extract_raw_script(script: &zcash_transparent::address::Script) -> Option<Vec<u8>> {
-    let mut bytes = Vec::new();
-    script.write(&mut bytes).ok()?;
-    // Script::write() adds CompactSize prefix - skip it for raw comparison
-    // For scripts < 253 bytes, prefix is 1 byte
-    if !bytes.is_empty() && bytes[0] < 0xfd {
-        Some(bytes[1..].to_vec())
-    } else {
-        Some(bytes)
+/// Drops the cached Orchard verifying key that `finalize_and_extract` keeps between
+/// calls, e.g. to give back its memory after a batch of extractions.
+///
+/// The next extraction of a transaction with Orchard actions builds it again.
+///
+/// # Returns
+/// * `bool` - Whether a key was cached (always false without the `orchard-prover` feature)
+pub fn unload_verifying_keys() -> bool {
+    #[cfg(feature = "orchard-prover")]
+    {
+        prover::unload_orchard_verifying_key()
+    }
+    #[cfg(not(feature = "orchard-prover"))]
+    {
+        false
     }
 }
 
+/// Sets how many threads proving may use.
+///
+/// Each proving thread holds its own share of the circuit's working memory, so fewer
+/// threads trade proving time for peak memory. `0` lifts the limit, which is the
+/// default except with the `low-memory` feature (default 1). With more than one
+/// thread, a PCZT's Sapling and Orchard proofs are created concurrently. Without the
+/// `orchard-prover` feature there is nothing to prove and this does nothing.
+pub fn set_prover_threads(threads: usize) {
+    #[cfg(feature = "orchard-prover")]
+    prover::set_prover_threads(threads);
+    #[cfg(not(feature = "orchard-prover"))]
+    let _ = threads;
+}
+
+/// How many threads proving may use, as last set by `set_prover_threads` (`0` for no
+/// limit). Without the `orchard-prover` feature this is always `0`.
+pub fn prover_threads() -> usize {
+    #[cfg(feature = "orchard-prover")]
+    {
+        prover::prover_threads()
+    }
+    #[cfg(not(feature = "orchard-prover"))]
+    {
+        0
+    }
+}
+
+// ============================================================================
+// Verification helper functions
+//
+// PCZTs, extracted transactions and review documents may come from other parties,
+// so every byte they carry is attacker-controlled. Script, value and digest checks
+// against them use constant-time comparisons (`ct_eq_bytes`, `ct_eq_u64`), so the
+// time a check takes reveals nothing about how much of the expected data matched.
+// ============================================================================
+
+/// Compares two byte strings in time independent of their contents
+/// (lengths are not secret and are compared first)
+pub(crate) fn ct_eq_bytes(a: &[u8], b: &[u8]) -> bool {
+    use subtle::ConstantTimeEq;
+
+    a.len() == b.len() && bool::from(a.ct_eq(b))
+}
+
+/// Compares two amounts in constant time
+pub(crate) fn ct_eq_u64(a: u64, b: u64) -> bool {
+    use subtle::ConstantTimeEq;
+
+    bool::from(a.ct_eq(&b))
+}
+
+/// Computes HASH160 (RIPEMD-160 of SHA-256), as used by P2PKH scripts
+pub(crate) fn hash160(data: &[u8]) -> [u8; 20] {
+    use ripemd::Ripemd160;
+    use sha2::{Digest, Sha256};
+
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+/// Computes the fee a PCZT pays: transparent in - transparent out + net value
+/// leaving the shielded pools. None if the balance is negative.
+pub(crate) fn implicit_fee(pczt: &Pczt) -> Option<u64> {
+    let transparent = pczt.transparent();
+    let (orchard_magnitude, orchard_negative) = *pczt.orchard().value_sum();
+    let orchard_balance = if orchard_negative {
+        -i128::from(orchard_magnitude)
+    } else {
+        i128::from(orchard_magnitude)
+    };
+    let fee = transparent.inputs().iter().map(|i| i128::from(*i.value())).sum::<i128>()
+        - transparent.outputs().iter().map(|o| i128::from(*o.value())).sum::<i128>()
+        + orchard_balance
+        + *pczt.sapling().value_sum();
+    u64::try_from(fee).ok()
+}
+
+/// Returns the pubkey hash of a P2PKH script_pubkey, or None for any other script
+/// (`OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG`)
+pub(crate) fn p2pkh_pubkey_hash(script_pubkey: &[u8]) -> Option<[u8; 20]> {
+    match script_pubkey {
+        [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => hash.try_into().ok(),
+        _ => None,
+    }
+}
+
+/// Returns the script hash of a P2SH script_pubkey, or None for any other script
+/// (`OP_HASH160 <20 bytes> OP_EQUAL`)
+pub(crate) fn p2sh_script_hash(script_pubkey: &[u8]) -> Option<[u8; 20]> {
+    match script_pubkey {
+        [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => hash.try_into().ok(),
+        _ => None,
+    }
+}
+
+/// Splits a script into its pushed data, or None if it has a non-push opcode
+fn script_pushes(mut script: &[u8]) -> Option<Vec<&[u8]>> {
+    let mut data = Vec::new();
+    while let [opcode, rest @ ..] = script {
+        let (len, rest) = match *opcode {
+            0x00 => (0, rest),
+            len @ 0x01..=0x4b => (len as usize, rest),
+            0x4c => (*rest.first()? as usize, &rest[1..]),
+            0x4d => (u16::from_le_bytes(rest.get(..2)?.try_into().ok()?) as usize, &rest[2..]),
+            0x4f | 0x51..=0x60 => (0, rest),
+            _ => return None,
+        };
+        data.push(rest.get(..len)?);
+        script = &rest[len..];
+    }
+    Some(data)
+}
+
+/// Returns the threshold and raw public keys of an m-of-n multisig script
+/// (`OP_m <pubkey>... OP_n OP_CHECKMULTISIG`), or None for any other script
+pub(crate) fn multisig_keys(script: &[u8]) -> Option<(usize, Vec<&[u8]>)> {
+    let [m @ 0x51..=0x60, keys @ .., n @ 0x51..=0x60, 0xae] = script else {
+        return None;
+    };
+    let (m, n) = ((m - 0x50) as usize, (n - 0x50) as usize);
+    let keys = script_pushes(keys)?;
+    let valid = m <= n
+        && keys.len() == n
+        && keys.iter().all(|key| secp256k1::PublicKey::from_slice(key).is_ok());
+    valid.then_some((m, keys))
+}
+
+/// Finds the first transparent input the Spend Finalizer would fail on, and why
+pub(crate) fn diagnose_spend_finalization(pczt: &Pczt) -> Option<FinalizationError> {
+    for (index, input) in pczt.transparent().inputs().iter().enumerate() {
+        // Already finalized (e.g. by another party)
+        if input.script_sig().is_some() {
+            continue;
+        }
+
+        // P2SH multisig: the redeem script must be known, and enough of its keys must have signed
+        if let Some(script_hash) = p2sh_script_hash(input.script_pubkey()) {
+            let Some((threshold, keys)) = input.redeem_script().as_ref()
+                .filter(|script| ct_eq_bytes(&hash160(script), &script_hash))
+                .and_then(|script| multisig_keys(script))
+            else {
+                return Some(FinalizationError::UnsupportedInputScript { index });
+            };
+
+            let signatures = input.partial_signatures();
+            if signatures.is_empty() {
+                return Some(FinalizationError::InputMissingSignature { index });
+            }
+            let signed = keys.iter().filter(|key| signatures.keys().any(|pubkey| ct_eq_bytes(pubkey, key))).count();
+            if signed == 0 {
+                return Some(FinalizationError::InputPubkeyMismatch { index });
+            }
+            if signed < threshold {
+                return Some(FinalizationError::InputMissingSignature { index });
+            }
+            continue;
+        }
+
+        let Some(pubkey_hash) = p2pkh_pubkey_hash(input.script_pubkey()) else {
+            return Some(FinalizationError::UnsupportedInputScript { index });
+        };
+
+        let signatures = input.partial_signatures();
+        if signatures.is_empty() {
+            return Some(FinalizationError::InputMissingSignature { index });
+        }
+        if !signatures.keys().any(|pubkey| ct_eq_bytes(&hash160(pubkey), &pubkey_hash)) {
+            return Some(FinalizationError::InputPubkeyMismatch { index });
+        }
+    }
+    None
+}
+
 /// Checks if a PCZT output matches a TxOut (script and value)
 fn output_matches_txout(
     pczt_output: &pczt::transparent::Output,
     txout: &zcash_transparent::bundle::TxOut,
 ) -> bool {
-    let Some(expected_raw) = extract_raw_script(txout.script_pubkey()) else {
+    let Ok(expected_raw) = types::script_to_raw(txout.script_pubkey()) else {
         return false;
     };
-    pczt_output.script_pubkey().as_slice() == expected_raw.as_slice()
-        && *pczt_output.value() == txout.value().into_u64()
+    // Evaluate both checks so the timing doesn't reveal which one failed
+    ct_eq_bytes(pczt_output.script_pubkey(), &expected_raw)
+        & ct_eq_u64(*pczt_output.value(), txout.value().into_u64())
 }
 
-/// Checks if a PCZT output matches an address and amount
+/// Checks if a PCZT output matches a payment's script and amount
 fn output_matches_payment(
     pczt_output: &pczt::transparent::Output,
-    addr: &zcash_transparent::address::TransparentAddress,
+    expected_script: &[u8],
     amount: u64,
 ) -> bool {
-    let script: zcash_transparent::address::Script = addr.script().into();
-    let Some(expected_raw) = extract_raw_script(&script) else {
-        return false;
+    ct_eq_bytes(pczt_output.script_pubkey(), expected_script)
+        & ct_eq_u64(*pczt_output.value(), amount)
+}
+
+/// The value a sweep request's transparent recipient receives in the PCZT
+fn swept_amount(pczt: &Pczt, transaction_request: &TransactionRequest) -> Option<u64> {
+    let [payment] = &transaction_request.payments[..] else {
+        return None;
     };
-    pczt_output.script_pubkey().as_slice() == expected_raw.as_slice()
-        && *pczt_output.value() == amount
+    if !transaction_request.sweep {
+        return None;
+    }
+    let script = payment.script_pubkey.clone().or_else(|| address_to_script(&payment.address))?;
+    pczt.transparent().outputs().iter()
+        .find(|output| ct_eq_bytes(output.script_pubkey(), &script))
+        .map(|output| *output.value())
 }
 
-/// Separates PCZT outputs into change outputs and payment outputs
+/// Separates PCZT outputs into change outputs and payment outputs, returning the
+/// number of change outputs found and the remaining outputs
 fn separate_change_outputs<'a>(
     transparent_outputs: &'a [pczt::transparent::Output],
     expected_change: &[zcash_transparent::bundle::TxOut],
-) -> Result<(usize, Vec<&'a pczt::transparent::Output>), VerificationFailure> {
+) -> (usize, Vec<&'a pczt::transparent::Output>) {
     let mut change_count = 0;
     let mut payment_outputs = Vec::new();
 
-    for pczt_output in transparent_outputs {
-        let is_change = expected_change.iter()
-            .any(|expected| output_matches_txout(pczt_output, expected));
+    for pczt_output in transparent_outputs {
+        let is_change = expected_change.iter()
+            .any(|expected| output_matches_txout(pczt_output, expected));
+
+        if is_change {
+            change_count += 1;
+        } else {
+            payment_outputs.push(pczt_output);
+        }
+    }
+
+    (change_count, payment_outputs)
+}
+
+/// Verifies that a transparent payment exists in the outputs
+fn verify_transparent_payment(
+    payment_outputs: &[&pczt::transparent::Output],
+    expected_script: &[u8],
+    amount: u64,
+    address_str: &str,
+) -> Result<(), VerificationFailure> {
+    let found = payment_outputs.iter()
+        .any(|output| output_matches_payment(output, expected_script, amount));
+
+    if !found {
+        return Err(VerificationFailure::OutputMismatch(
+            format!("Payment to {} for {} zatoshis not found in PCZT outputs", address_str, amount)
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that a PCZT received from another party is internally consistent.
+///
+/// Every hash160 preimage an input carries is re-hashed and must match the hash it
+/// is stored under, and the preimage for a P2PKH input's pubkey hash must be a valid
+/// public key. This rejects corrupted or malicious preimages before anything is
+/// signed with them. `verify_before_signing` runs it in strict mode.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to validate
+///
+/// # Returns
+/// * `Result<(), VerificationFailure>` - Success or `InvalidPreimage` for the first bad input
+pub fn validate_pczt(pczt: &Pczt) -> Result<(), VerificationFailure> {
+    match invalid_preimage_inputs(pczt).first() {
+        Some(&index) => Err(VerificationFailure::InvalidPreimage { index }),
+        None => Ok(()),
+    }
+}
+
+/// Indices of the inputs with a hash160 preimage that fails `validate_pczt`
+fn invalid_preimage_inputs(pczt: &Pczt) -> Vec<usize> {
+    pczt.transparent().inputs().iter()
+        .enumerate()
+        .filter(|(_, input)| {
+            let preimages = input.hash160_preimages();
+            let mismatched = preimages.iter().any(|(hash, preimage)| !ct_eq_bytes(&hash160(preimage), hash));
+            let bad_pubkey = p2pkh_pubkey_hash(input.script_pubkey())
+                .and_then(|pubkey_hash| preimages.get(&pubkey_hash))
+                .is_some_and(|preimage| secp256k1::PublicKey::from_slice(preimage).is_err());
+            mismatched || bad_pubkey
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// Verifies the PCZT before signing.
+///
+/// If the entity that invoked propose_transaction is the same as the entity adding signatures,
+/// and no third party may have malleated the PCZT, this step may be skipped.
+///
+/// This verifies that:
+/// - Payment outputs match the transaction request
+/// - Change outputs match the expected change
+/// - Fees are reasonable (not too high)
+/// - In strict mode, the PCZT passes `validate_pczt`
+///
+/// # Arguments
+/// * `pczt` - The PCZT to verify
+/// * `transaction_request` - The original transaction request
+/// * `expected_change` - Expected change outputs (transparent TxOuts)
+///
+/// # Returns
+/// * `Result<(), VerificationFailure>` - Success or the first error `verify_report` finds
+pub fn verify_before_signing(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    expected_change: &[zcash_transparent::bundle::TxOut],
+) -> Result<(), VerificationFailure> {
+    verify_report(pczt, transaction_request, expected_change).into_result()
+}
+
+/// Runs every check of `verify_before_signing` and reports all that fail.
+///
+/// Reviewers get the complete list of discrepancies, to judge whether a PCZT was
+/// altered or just mis-specified. Findings that `verify_before_signing` would
+/// reject are errors; the rest are warnings:
+/// - invalid hash160 preimages outside strict mode
+/// - transparent outputs that match neither a payment nor the expected change,
+///   except in a sweep, where they are errors
+///
+/// # Arguments
+/// * `pczt` - The PCZT to verify
+/// * `transaction_request` - The original transaction request
+/// * `expected_change` - Expected change outputs (transparent TxOuts)
+///
+/// # Returns
+/// * `VerificationReport` - Every failed check, in the order they ran
+pub fn verify_report(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    expected_change: &[zcash_transparent::bundle::TxOut],
+) -> VerificationReport {
+    let mut report = VerificationReport::default();
+
+    // A sweep pays whatever the fee leaves, so its payment amount is read from the PCZT;
+    // the fee policy check below still catches anything taken on the side
+    let swept_request;
+    let transaction_request = match swept_amount(pczt, transaction_request) {
+        Some(amount) => {
+            let mut request = transaction_request.clone();
+            request.payments[0].amount = amount;
+            swept_request = request;
+            &swept_request
+        }
+        None => transaction_request,
+    };
+
+    // Preimages are always checked, but only fail verification in strict mode
+    let preimage_severity = match strictness() {
+        Strictness::Strict => Severity::Error,
+        _ => Severity::Warning,
+    };
+    for index in invalid_preimage_inputs(pczt) {
+        report.push(preimage_severity, VerificationFailure::InvalidPreimage { index });
+    }
+
+    let transparent_outputs = pczt.transparent().outputs();
+    let orchard_actions = pczt.orchard().actions();
+    let num_orchard_outputs = orchard_actions.len();
+    let num_payments = transaction_request.payments.len();
+    let total_outputs = transparent_outputs.len() + num_orchard_outputs;
+
+    // Verify output counts
+    if expected_change.is_empty() {
+        if total_outputs < num_payments {
+            report.push(Severity::Error, VerificationFailure::OutputMismatch(
+                format!("Expected at least {} payment outputs but found {}", num_payments, total_outputs)
+            ));
+        }
+    } else {
+        let expected_total = num_payments + expected_change.len();
+        if total_outputs != expected_total {
+            report.push(Severity::Error, VerificationFailure::OutputMismatch(
+                format!("Output count mismatch: {} outputs but expected {} payments + {} change = {}",
+                    total_outputs, num_payments, expected_change.len(), expected_total)
+            ));
+        }
+    }
+
+    // Separate change from payment outputs
+    let (change_count, payment_outputs) = separate_change_outputs(transparent_outputs, expected_change);
+    if change_count != expected_change.len() {
+        report.push(Severity::Error, VerificationFailure::ChangeMismatch);
+    }
+
+    // Verify each payment exists in outputs
+    let mut payment_scripts = Vec::new();
+    for payment in &transaction_request.payments {
+        if let Some(script) = &payment.script_pubkey {
+            if let Err(e) = verify_transparent_payment(&payment_outputs, script, payment.amount, &hex_encode(script)) {
+                report.push(Severity::Error, e);
+            }
+            payment_scripts.push((script.clone(), payment.amount));
+            continue;
+        }
+        if address_cache::parse_address(&payment.address).is_none() {
+            report.push(Severity::Error, VerificationFailure::OutputMismatch(
+                format!("Invalid payment address: {}", payment.address)
+            ));
+            continue;
+        }
+
+        if let Some(script) = address_to_script(&payment.address) {
+            if let Err(e) = verify_transparent_payment(&payment_outputs, &script, payment.amount, &payment.address) {
+                report.push(Severity::Error, e);
+            }
+            payment_scripts.push((script, payment.amount));
+        } else if num_orchard_outputs == 0 {
+            report.push(Severity::Error, VerificationFailure::OutputMismatch(
+                "Shielded payment requested but no Orchard outputs found".to_string()
+            ));
+        }
+    }
+
+    // Outputs nobody asked for are the proposer's own (e.g. undeclared change) or an
+    // addition. A sweep's amount was read from the PCZT, so nothing else bounds what an
+    // addition takes from it: its outputs must be exactly the payment and the change.
+    let unrequested_severity = if transaction_request.sweep { Severity::Error } else { Severity::Warning };
+    if transaction_request.sweep && num_orchard_outputs > 0 && !transaction_request.payments[0].is_unified() {
+        report.push(Severity::Error, VerificationFailure::OutputMismatch(
+            "Transparent sweep has Orchard outputs".to_string()
+        ));
+    }
+    for output in &payment_outputs {
+        let requested = payment_scripts.iter()
+            .any(|(script, amount)| output_matches_payment(output, script, *amount));
+        if !requested {
+            report.push(unrequested_severity, VerificationFailure::OutputMismatch(format!(
+                "Output of {} zatoshis to {} matches no payment or expected change",
+                output.value(),
+                hex_encode(output.script_pubkey())
+            )));
+        }
+    }
+
+    // Orchard flags must allow the requested shape, and match the request if it set them
+    if num_orchard_outputs > 0 {
+        let actual = *pczt.orchard().flags();
+        let expected = match transaction_request.orchard_flags {
+            Some(flags) => flags,
+            None => OrchardFlags::from_byte(actual).unwrap_or(OrchardFlags::ENABLED),
+        };
+        let outputs_needed = transaction_request.payments.iter().any(|p| p.is_unified());
+        if actual != expected.to_byte() || (outputs_needed && !expected.outputs_enabled) {
+            report.push(Severity::Error, VerificationFailure::OrchardFlagsMismatch { expected: expected.to_byte(), actual });
+        }
+    }
+
+    // Verify fee is reasonable
+    // For transparent-only transactions: fee should not exceed 1% of total
+    // For shielded transactions: we skip this check since Orchard amounts are hidden
+    let has_orchard = num_orchard_outputs > 0;
+    if !has_orchard {
+        let total_output_value: u64 = transparent_outputs.iter().map(|o| *o.value()).sum();
+        let requested_total = transaction_request.total_amount();
+
+        if total_output_value > 0 && requested_total > 0 {
+            let max_reasonable_fee = requested_total / 100;
+            if total_output_value + max_reasonable_fee < requested_total {
+                report.push(Severity::Error, VerificationFailure::InvalidFee);
+            }
+        }
+    }
+
+    // The fee must be what the request's fee policy charges for the PCZT's shape. It may
+    // exceed that by the fee tolerance, and by change the proposal added to the fee as
+    // dust together with the fee of the output that change would have needed.
+    if let Some(actual) = implicit_fee(pczt) {
+        let policy = transaction_request.fee_policy;
+        let conventional = conventional_fee(pczt);
+        let expected = policy.fee(conventional);
+        let dust_allowance = match transaction_request.dust_policy {
+            DustPolicy::AddChangeToFee { threshold } if threshold > 0 => {
+                threshold.saturating_add(policy.fee(conventional.saturating_add(ZIP317_MARGINAL_FEE)).saturating_sub(expected))
+            }
+            _ => 0,
+        };
+        let allowed = expected.saturating_add(transaction_request.fee_tolerance).saturating_add(dust_allowance);
+        if actual < expected || actual > allowed {
+            report.push(Severity::Error, VerificationFailure::FeePolicyMismatch { expected, actual });
+        }
+    }
+
+    report
+}
+
+/// The lock time the Transaction Extractor gives a PCZT's transaction: the fallback
+/// lock time (or 0) if no input requires one, otherwise the latest required height,
+/// or the latest required time if some input needs a time. None if inputs need both.
+fn pczt_lock_time(pczt: &Pczt) -> Option<u32> {
+    let inputs = pczt.transparent().inputs();
+    let height = |input: &pczt::transparent::Input| *input.required_height_lock_time();
+    let time = |input: &pczt::transparent::Input| *input.required_time_lock_time();
+
+    if inputs.iter().all(|input| height(input).is_none() && time(input).is_none()) {
+        Some((*pczt.global().fallback_lock_time()).unwrap_or(0))
+    } else if inputs.iter().all(|input| height(input).is_some() || time(input).is_none()) {
+        inputs.iter().filter_map(height).max()
+    } else if inputs.iter().all(|input| time(input).is_some() || height(input).is_none()) {
+        inputs.iter().filter_map(time).max()
+    } else {
+        None
+    }
+}
+
+/// Verifies that a final transaction matches the PCZT it was extracted from.
+///
+/// Parses `tx_bytes` with the PCZT's consensus branch and checks the global fields
+/// (version, version group, consensus branch ID, lock time and expiry height),
+/// every transparent input and output, the Sapling and Orchard nullifiers and note
+/// commitments, and the shielded value balances. Intended for services that receive
+/// a (pczt, tx) pair and want a cheap consistency check before broadcasting.
+///
+/// # Arguments
+/// * `pczt` - The PCZT the transaction was extracted from
+/// * `tx_bytes` - The serialized final transaction
+///
+/// # Returns
+/// * `Result<(), VerificationFailure>` - Success, or `ExtractionMismatch` describing
+///   the first difference found
+pub fn verify_extraction(pczt: &Pczt, tx_bytes: &[u8]) -> Result<(), VerificationFailure> {
+    use zcash_primitives::transaction::Transaction;
+    use zcash_protocol::consensus::BranchId;
+
+    let mismatch = VerificationFailure::ExtractionMismatch;
+
+    let global = pczt.global();
+    let branch_id = BranchId::try_from(*global.consensus_branch_id())
+        .map_err(|e| mismatch(format!("Unknown consensus branch ID: {}", e)))?;
+
+    let mut reader = tx_bytes;
+    let tx = Transaction::read(&mut reader, branch_id)
+        .map_err(|e| mismatch(format!("Failed to parse transaction: {}", e)))?;
+    if !reader.is_empty() {
+        return Err(mismatch(format!("{} trailing bytes after transaction", reader.len())));
+    }
+
+    // Global fields (the header's high bit is the overwintered flag)
+    let tx_version = tx.version().header() & !(1 << 31);
+    if tx_version != *global.tx_version() || tx.version().version_group_id() != *global.version_group_id() {
+        return Err(mismatch(format!(
+            "Transaction version {} (group {:#010x}) but PCZT has {} (group {:#010x})",
+            tx_version,
+            tx.version().version_group_id(),
+            global.tx_version(),
+            global.version_group_id()
+        )));
+    }
+    if u32::from(tx.consensus_branch_id()) != *global.consensus_branch_id() {
+        return Err(mismatch(format!(
+            "Consensus branch ID {:#010x} but PCZT has {:#010x}",
+            u32::from(tx.consensus_branch_id()),
+            global.consensus_branch_id()
+        )));
+    }
+    let lock_time = pczt_lock_time(pczt)
+        .ok_or_else(|| mismatch("PCZT inputs require both a height and a time lock time".to_string()))?;
+    if tx.lock_time() != lock_time {
+        return Err(mismatch(format!("Lock time {} but PCZT has {}", tx.lock_time(), lock_time)));
+    }
+    if u32::from(tx.expiry_height()) != *global.expiry_height() {
+        return Err(mismatch(format!(
+            "Expiry height {} but PCZT has {}",
+            u32::from(tx.expiry_height()),
+            global.expiry_height()
+        )));
+    }
+
+    // Transparent inputs and outputs
+    let transparent = pczt.transparent();
+    let (vin, vout) = match tx.transparent_bundle() {
+        Some(bundle) => (&bundle.vin[..], &bundle.vout[..]),
+        None => (&[][..], &[][..]),
+    };
+    if vin.len() != transparent.inputs().len() || vout.len() != transparent.outputs().len() {
+        return Err(mismatch(format!(
+            "Transparent shape {}/{} but PCZT has {}/{} inputs/outputs",
+            vin.len(),
+            vout.len(),
+            transparent.inputs().len(),
+            transparent.outputs().len()
+        )));
+    }
+    for (index, (txin, input)) in vin.iter().zip(transparent.inputs()).enumerate() {
+        let prevout = txin.prevout();
+        if prevout.hash() != input.prevout_txid() || prevout.n() != *input.prevout_index() {
+            return Err(mismatch(format!("Input {} spends a different outpoint", index)));
+        }
+        if txin.sequence() != input.sequence().unwrap_or(FINAL_SEQUENCE) {
+            return Err(mismatch(format!("Input {} has a different sequence number", index)));
+        }
+    }
+    for (index, (txout, output)) in vout.iter().zip(transparent.outputs()).enumerate() {
+        let script = types::script_to_raw(txout.script_pubkey())
+            .map_err(|_| mismatch(format!("Output {} has an unreadable script", index)))?;
+        if !(ct_eq_u64(txout.value().into_u64(), *output.value()) & ct_eq_bytes(&script, output.script_pubkey())) {
+            return Err(mismatch(format!("Output {} differs in value or script", index)));
+        }
+    }
+
+    // Sapling effects
+    let sapling = pczt.sapling();
+    let (tx_spends, tx_outputs, tx_sapling_balance) = match tx.sapling_bundle() {
+        Some(bundle) => (
+            bundle.shielded_spends(),
+            bundle.shielded_outputs(),
+            i64::from(*bundle.value_balance()),
+        ),
+        None => (&[][..], &[][..], 0),
+    };
+    if tx_spends.len() != sapling.spends().len() || tx_outputs.len() != sapling.outputs().len() {
+        return Err(mismatch("Sapling bundle shape differs".to_string()));
+    }
+    if tx_spends.iter().zip(sapling.spends()).any(|(a, b)| !ct_eq_bytes(&a.nullifier().0, b.nullifier()))
+        || tx_outputs.iter().zip(sapling.outputs()).any(|(a, b)| !ct_eq_bytes(&a.cmu().to_bytes(), b.cmu()))
+    {
+        return Err(mismatch("Sapling nullifiers or note commitments differ".to_string()));
+    }
+    let has_sapling = !sapling.spends().is_empty() || !sapling.outputs().is_empty();
+    if has_sapling && i128::from(tx_sapling_balance) != *sapling.value_sum() {
+        return Err(mismatch("Sapling value balance differs".to_string()));
+    }
+
+    // Orchard effects
+    let orchard = pczt.orchard();
+    match tx.orchard_bundle() {
+        Some(bundle) => {
+            if bundle.actions().len() != orchard.actions().len() {
+                return Err(mismatch("Orchard action count differs".to_string()));
+            }
+            if bundle.flags().to_byte() != *orchard.flags() {
+                return Err(mismatch("Orchard flags differ".to_string()));
+            }
+            let actions_match = bundle.actions().iter().zip(orchard.actions()).all(|(a, b)| {
+                ct_eq_bytes(&a.nullifier().to_bytes(), b.spend().nullifier())
+                    & ct_eq_bytes(&a.cmx().to_bytes(), b.output().cmx())
+            });
+            if !actions_match {
+                return Err(mismatch("Orchard nullifiers or note commitments differ".to_string()));
+            }
+            let (magnitude, is_negative) = *orchard.value_sum();
+            let expected = if is_negative { -i128::from(magnitude) } else { i128::from(magnitude) };
+            if i128::from(i64::from(*bundle.value_balance())) != expected {
+                return Err(mismatch("Orchard value balance differs".to_string()));
+            }
+        }
+        None if !orchard.actions().is_empty() => {
+            return Err(mismatch("Transaction has no Orchard bundle".to_string()));
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// Number of zatoshis in one ZEC
+pub const ZATOSHIS_PER_ZEC: u64 = 100_000_000;
+
+/// Renders an amount for display, independent of the host's locale.
+///
+/// ZEC amounts always use `.` as the decimal separator, exactly 8 decimal places and
+/// no digit grouping, so every platform shows identical text for the same value.
+///
+/// # Examples
+/// * `format_amount(123_450_000, AmountUnit::Zec)` → `"1.23450000 ZEC"`
+/// * `format_amount(5_000, AmountUnit::Zatoshis)` → `"5000 zatoshis"`
+/// * `format_amount(5_000, AmountUnit::Both)` → `"0.00005000 ZEC (5000 zatoshis)"`
+pub fn format_amount(zatoshis: u64, unit: AmountUnit) -> String {
+    let zec = format!("{}.{:08} ZEC", zatoshis / ZATOSHIS_PER_ZEC, zatoshis % ZATOSHIS_PER_ZEC);
+    match unit {
+        AmountUnit::Zec => zec,
+        AmountUnit::Zatoshis => format!("{} zatoshis", zatoshis),
+        AmountUnit::Both => format!("{} ({} zatoshis)", zec, zatoshis),
+    }
+}
+
+/// Hex-encodes bytes (lowercase)
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Renders a P2PKH or P2SH script as a transparent address on `network`.
+///
+/// Review documents, decoding and verification all render addresses through this
+/// function, so every layer shows the same string for the same script.
+///
+/// # Returns
+/// * `Option<String>` - The encoded address, or None for any other script
+pub fn script_to_address(script_bytes: &[u8], network: zcash_protocol::consensus::NetworkType) -> Option<String> {
+    if let Some(pubkey_hash) = p2pkh_pubkey_hash(script_bytes) {
+        return Some(ZcashAddress::from_transparent_p2pkh(network, pubkey_hash).encode());
+    }
+    match script_bytes {
+        [0xa9, 0x14, script_hash @ .., 0x87] if script_hash.len() == 20 => {
+            let script_hash: [u8; 20] = script_hash.try_into().ok()?;
+            Some(ZcashAddress::from_transparent_p2sh(network, script_hash).encode())
+        }
+        _ => None,
+    }
+}
+
+/// Largest standard OP_RETURN script (zcashd's `MAX_OP_RETURN_RELAY`)
+const MAX_NULL_DATA_SCRIPT_SIZE: usize = 83;
+
+/// Classifies a scriptPubKey by zcashd's standard templates.
+///
+/// Only `P2pkh` and `P2sh` outputs can be built and spent by this crate today;
+/// the other standard kinds are recognized so callers get a precise error.
+pub fn classify_script(script: &[u8]) -> ScriptKind {
+    if p2pkh_pubkey_hash(script).is_some() {
+        return ScriptKind::P2pkh;
+    }
+
+    let is_pubkey = |key: &[u8]| secp256k1::PublicKey::from_slice(key).is_ok();
+
+    match script {
+        [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => ScriptKind::P2sh,
+        [len @ (0x21 | 0x41), key @ .., 0xac] if key.len() == *len as usize && is_pubkey(key) => ScriptKind::P2pk,
+        // Bare multisig is only standard up to three keys
+        _ if multisig_keys(script).is_some_and(|(_, keys)| keys.len() <= 3) => ScriptKind::Multisig,
+        [0x6a, data @ ..] if script.len() <= MAX_NULL_DATA_SCRIPT_SIZE && script_pushes(data).is_some() => {
+            ScriptKind::NullData
+        }
+        _ => ScriptKind::NonStandard,
+    }
+}
+
+/// Returns the raw scriptPubKey a transparent address pays to.
+///
+/// # Returns
+/// * `Option<Vec<u8>>` - The script bytes, or None if `address` is not a valid
+///   transparent (P2PKH or P2SH) address on either network
+pub fn address_to_script(address: &str) -> Option<Vec<u8>> {
+    let ParsedAddress::Transparent(t_addr) = address_cache::parse_address(address)? else {
+        return None;
+    };
+    let script: zcash_transparent::address::Script = t_addr.script().into();
+    types::script_to_raw(&script).ok()
+}
+
+/// Returns the raw Orchard receiver of a unified address, if it has one
+fn orchard_receiver(address: &str) -> Option<[u8; 43]> {
+    match address_cache::parse_address(address)? {
+        ParsedAddress::Unified(receiver) => receiver,
+        _ => None,
+    }
+}
+
+/// Digest over a review document with its `digest` field cleared
+fn review_digest(document: &ReviewDocument) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut body = document.clone();
+    body.digest = String::new();
+    let json = serde_json::to_vec(&body).expect("review document serializes");
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"t2z_review_v1");
+    hasher.update(json);
+    hex_encode(&hasher.finalize())
+}
+
+/// Renders a proposal into a review document for four-eyes approval.
+///
+/// Amounts are displayed in both ZEC and zatoshis; see `export_review_with_unit`.
+///
+/// Transparent outputs are matched to the request's payments by address and amount,
+/// Orchard outputs by receiver and amount; unmatched transparent outputs are change.
+/// Memos are redacted to their length and SHA-256. The document carries the PCZT
+/// fingerprint and a digest over its contents, so `verify_review` can later confirm
+/// that what is about to be signed is exactly what was approved.
+///
+/// # Arguments
+/// * `pczt` - The proposed PCZT
+/// * `transaction_request` - The request the PCZT was proposed from
+///
+/// # Returns
+/// * `Result<ReviewDocument, VerificationFailure>` - The review document or an error
+pub fn export_review(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+) -> Result<ReviewDocument, VerificationFailure> {
+    export_review_with_unit(pczt, transaction_request, AmountUnit::default())
+}
+
+/// Like `export_review`, rendering the `*_display` amounts in the given unit.
+pub fn export_review_with_unit(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    unit: AmountUnit,
+) -> Result<ReviewDocument, VerificationFailure> {
+    use sha2::{Digest, Sha256};
+
+    let network = transaction_request.effective_network().network_type();
+    let redact = |payment: &Payment| {
+        let memo = payment.memo.as_ref().map(String::as_bytes).or(payment.memo_bytes.as_deref());
+        memo.map(|bytes| ReviewMemo {
+            length: bytes.len(),
+            sha256: hex_encode(&Sha256::digest(bytes)),
+        })
+    };
+
+    let inputs: Vec<ReviewInput> = pczt.transparent().inputs().iter()
+        .map(|input| {
+            let mut txid = *input.prevout_txid();
+            txid.reverse();
+            ReviewInput {
+                txid: hex_encode(&txid),
+                vout: *input.prevout_index(),
+                value: *input.value(),
+                value_display: format_amount(*input.value(), unit),
+                address: script_to_address(input.script_pubkey(), network),
+            }
+        })
+        .collect();
+
+    let mut spends = Vec::new();
+    for action in pczt.orchard().actions() {
+        let value = (*action.spend().value()).ok_or_else(|| VerificationFailure::OutputMismatch(
+            "Orchard spend value has been redacted".to_string()
+        ))?;
+        // Padding actions spend dummy notes of no value
+        if value > 0 {
+            spends.push(ReviewSpend { pool: "orchard".to_string(), value, value_display: format_amount(value, unit) });
+        }
+    }
+    for spend in pczt.sapling().spends() {
+        let value = (*spend.value()).ok_or_else(|| VerificationFailure::OutputMismatch(
+            "Sapling spend value has been redacted".to_string()
+        ))?;
+        spends.push(ReviewSpend { pool: "sapling".to_string(), value, value_display: format_amount(value, unit) });
+    }
+
+    let mut matched = vec![false; transaction_request.payments.len()];
+    let mut outputs = Vec::new();
+
+    for output in pczt.transparent().outputs() {
+        let payment_index = transaction_request.payments.iter().enumerate()
+            .position(|(i, payment)| {
+                !matched[i]
+                    && payment.amount == *output.value()
+                    && payment.output_script()
+                        .is_some_and(|script| output_matches_payment(output, &script, payment.amount))
+            });
+        if let Some(i) = payment_index {
+            matched[i] = true;
+        }
+        outputs.push(ReviewOutput {
+            pool: "transparent".to_string(),
+            address: match payment_index {
+                Some(i) if transaction_request.payments[i].script_pubkey.is_none() => {
+                    Some(transaction_request.payments[i].address.clone())
+                }
+                _ => script_to_address(output.script_pubkey(), network),
+            },
+            value: *output.value(),
+            value_display: format_amount(*output.value(), unit),
+            payment_index,
+            memo: payment_index.and_then(|i| redact(&transaction_request.payments[i])),
+        });
+    }
+
+    for action in pczt.orchard().actions() {
+        let value = action.output().value().ok_or_else(|| VerificationFailure::OutputMismatch(
+            "Orchard output value has been redacted".to_string()
+        ))?;
+        let recipient = *action.output().recipient();
+        let payment_index = transaction_request.payments.iter().enumerate()
+            .position(|(i, payment)| {
+                !matched[i]
+                    && payment.amount == value
+                    && recipient.is_some()
+                    && orchard_receiver(&payment.address) == recipient
+            });
+        let Some(i) = payment_index else {
+            // Padding actions carry no value
+            if value == 0 {
+                continue;
+            }
+            outputs.push(ReviewOutput {
+                pool: "orchard".to_string(),
+                address: None,
+                value,
+                value_display: format_amount(value, unit),
+                payment_index: None,
+                memo: None,
+            });
+            continue;
+        };
+        matched[i] = true;
+        outputs.push(ReviewOutput {
+            pool: "orchard".to_string(),
+            address: Some(transaction_request.payments[i].address.clone()),
+            value,
+            value_display: format_amount(value, unit),
+            payment_index: Some(i),
+            memo: redact(&transaction_request.payments[i]),
+        });
+    }
+
+    // From the bundles' value balances, so shielded spends and outputs are counted
+    let fee = implicit_fee(pczt).ok_or(VerificationFailure::InvalidFee)?;
+
+    let mut document = ReviewDocument {
+        version: REVIEW_DOCUMENT_VERSION,
+        network: match transaction_request.effective_network() {
+            Network::Mainnet => "main",
+            Network::Testnet => "test",
+            Network::Regtest(_) => "regtest",
+        }.to_string(),
+        unit,
+        fingerprint: hex_encode(&pczt_fingerprint(pczt)),
+        inputs,
+        spends,
+        outputs,
+        fee,
+        fee_display: format_amount(fee, unit),
+        digest: String::new(),
+    };
+    document.digest = review_digest(&document);
+    Ok(document)
+}
+
+/// Checks that a previously approved review document still describes the proposal.
+///
+/// Fails with `ReviewDrift` if the document was edited after export (its digest no
+/// longer matches its contents) or if the PCZT or request changed since approval.
+///
+/// # Arguments
+/// * `pczt` - The PCZT about to be signed
+/// * `transaction_request` - The request the PCZT was proposed from
+/// * `document` - The approved review document
+pub fn verify_review(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    document: &ReviewDocument,
+) -> Result<(), VerificationFailure> {
+    if !ct_eq_bytes(review_digest(document).as_bytes(), document.digest.as_bytes()) {
+        return Err(VerificationFailure::ReviewDrift(
+            "Document contents do not match its digest".to_string()
+        ));
+    }
+
+    let current = export_review_with_unit(pczt, transaction_request, document.unit)?;
+    if !ct_eq_bytes(current.fingerprint.as_bytes(), document.fingerprint.as_bytes()) {
+        return Err(VerificationFailure::ReviewDrift(
+            "PCZT fingerprint changed since review".to_string()
+        ));
+    }
+    if !ct_eq_bytes(current.digest.as_bytes(), document.digest.as_bytes()) {
+        return Err(VerificationFailure::ReviewDrift(
+            "Proposal summary changed since review".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Number of address characters a `BlindedReview` shows
+const BLINDED_ADDRESS_PREFIX_LEN: usize = 12;
+
+/// Digest over a blinded review with its `digest` field cleared
+fn blinded_review_digest(review: &BlindedReview) -> String {
+    use sha2::{Digest, Sha256};
+
+    let mut body = review.clone();
+    body.digest = String::new();
+    let json = serde_json::to_vec(&body).expect("blinded review serializes");
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"t2z_blinded_review_v1");
+    hasher.update(json);
+    hex_encode(&hasher.finalize())
+}
+
+/// Renders a proposal for an approver who may not see individual shielded amounts.
+///
+/// The review carries the same fingerprint as the full `ReviewDocument`, the input
+/// count and aggregate totals per pool, and for each output its pool, an address
+/// prefix and the payment it pays. Transparent values stay visible, since they are
+/// public on chain; Orchard values and all memos are left out.
+///
+/// # Arguments
+/// * `pczt` - The proposed PCZT
+/// * `transaction_request` - The request the PCZT was proposed from
+/// * `unit` - Unit for the `*_display` totals
+///
+/// # Returns
+/// * `Result<BlindedReview, VerificationFailure>` - The blinded review or an error
+pub fn export_blinded_review(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    unit: AmountUnit,
+) -> Result<BlindedReview, VerificationFailure> {
+    let document = export_review_with_unit(pczt, transaction_request, unit)?;
+
+    let total_in: u64 = document.inputs.iter().map(|input| input.value)
+        .chain(document.spends.iter().map(|spend| spend.value))
+        .sum();
+    let pool_total = |pool: &str| -> u64 {
+        document.outputs.iter().filter(|output| output.pool == pool).map(|output| output.value).sum()
+    };
+    let (total_transparent, total_orchard) = (pool_total("transparent"), pool_total("orchard"));
+
+    let outputs = document.outputs.iter()
+        .map(|output| BlindedOutput {
+            pool: output.pool.clone(),
+            address_prefix: output.address.as_ref()
+                .map(|address| address.chars().take(BLINDED_ADDRESS_PREFIX_LEN).collect()),
+            value: (output.pool == "transparent").then_some(output.value),
+            payment_index: output.payment_index,
+        })
+        .collect();
+
+    let mut review = BlindedReview {
+        version: BLINDED_REVIEW_VERSION,
+        network: document.network,
+        unit,
+        fingerprint: document.fingerprint,
+        num_inputs: document.inputs.len(),
+        num_shielded_spends: document.spends.len(),
+        total_in,
+        total_in_display: format_amount(total_in, unit),
+        outputs,
+        total_transparent,
+        total_transparent_display: format_amount(total_transparent, unit),
+        total_orchard,
+        total_orchard_display: format_amount(total_orchard, unit),
+        fee: document.fee,
+        fee_display: document.fee_display,
+        digest: String::new(),
+    };
+    review.digest = blinded_review_digest(&review);
+    Ok(review)
+}
+
+/// Checks that an approved blinded review still describes the proposal.
+///
+/// Fails with `ReviewDrift` under the same conditions as `verify_review`.
+pub fn verify_blinded_review(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    review: &BlindedReview,
+) -> Result<(), VerificationFailure> {
+    if !ct_eq_bytes(blinded_review_digest(review).as_bytes(), review.digest.as_bytes()) {
+        return Err(VerificationFailure::ReviewDrift(
+            "Document contents do not match its digest".to_string()
+        ));
+    }
+
+    let current = export_blinded_review(pczt, transaction_request, review.unit)?;
+    if !ct_eq_bytes(current.fingerprint.as_bytes(), review.fingerprint.as_bytes()) {
+        return Err(VerificationFailure::ReviewDrift(
+            "PCZT fingerprint changed since review".to_string()
+        ));
+    }
+    if !ct_eq_bytes(current.digest.as_bytes(), review.digest.as_bytes()) {
+        return Err(VerificationFailure::ReviewDrift(
+            "Proposal summary changed since review".to_string()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Renders a proposal in the disclosure `policy` grants to `role`.
+///
+/// # Arguments
+/// * `pczt` - The proposed PCZT
+/// * `transaction_request` - The request the PCZT was proposed from
+/// * `unit` - Unit for the `*_display` amounts
+/// * `policy` - Which disclosure each reviewer role receives
+/// * `role` - The role of the reviewer the document is for
+pub fn export_review_for_role(
+    pczt: &Pczt,
+    transaction_request: &TransactionRequest,
+    unit: AmountUnit,
+    policy: &DisclosurePolicy,
+    role: &str,
+) -> Result<ReviewExport, VerificationFailure> {
+    match policy.disclosure_for(role) {
+        ReviewDisclosure::Full => export_review_with_unit(pczt, transaction_request, unit).map(ReviewExport::Full),
+        ReviewDisclosure::AmountBlinded => {
+            export_blinded_review(pczt, transaction_request, unit).map(ReviewExport::Blinded)
+        }
+    }
+}
+
+/// Domain separation tag for change ownership messages
+const CHANGE_OWNERSHIP_TAG: &[u8] = b"t2z_change_ownership_v1";
+
+/// Computes the message a proposer signs to prove a transparent change output is theirs.
+///
+/// The message is a SHA-256 digest over the PCZT fingerprint and the output's index,
+/// value and script, so a proof cannot be replayed for another transaction or output.
+///
+/// # Arguments
+/// * `pczt` - The proposed PCZT
+/// * `output_index` - The index of the transparent change output
+///
+/// # Returns
+/// * `Result<[u8; 32], InspectionError>` - The message to sign or an error
+pub fn change_ownership_message(pczt: &Pczt, output_index: usize) -> Result<[u8; 32], InspectionError> {
+    use sha2::{Digest, Sha256};
+
+    let output = pczt.transparent().outputs().get(output_index)
+        .ok_or(InspectionError::InvalidOutputIndex(output_index))?;
+
+    let mut hasher = Sha256::new();
+    hasher.update(CHANGE_OWNERSHIP_TAG);
+    hasher.update(pczt_fingerprint(pczt));
+    hasher.update((output_index as u32).to_le_bytes());
+    hasher.update(output.value().to_le_bytes());
+    hasher.update((output.script_pubkey().len() as u32).to_le_bytes());
+    hasher.update(output.script_pubkey());
+    Ok(hasher.finalize().into())
+}
+
+/// Builds a change ownership proof from a signature over `change_ownership_message`.
+///
+/// The signature is checked against the key controlling `input_index` before the
+/// proof is returned, so a proposer learns about a wrong key immediately.
+///
+/// # Arguments
+/// * `pczt` - The proposed PCZT
+/// * `output_index` - The index of the transparent change output
+/// * `input_index` - The input whose key produced the signature
+/// * `signature` - 64-byte compact ECDSA signature
+pub fn prove_change_ownership(
+    pczt: &Pczt,
+    output_index: usize,
+    input_index: usize,
+    signature: [u8; 64],
+) -> Result<ChangeOwnershipProof, VerificationFailure> {
+    let proof = ChangeOwnershipProof { output_index, input_index, signature };
+    verify_change_ownership(pczt, &proof)?;
+    Ok(proof)
+}
+
+/// Checks that a transparent change output was endorsed by one of the input keys.
+///
+/// Co-signers call this before signing to confirm the change returns to the
+/// proposer rather than to an address substituted after proposal.
+///
+/// # Arguments
+/// * `pczt` - The PCZT about to be signed
+/// * `proof` - The proposer's change ownership proof
+pub fn verify_change_ownership(
+    pczt: &Pczt,
+    proof: &ChangeOwnershipProof,
+) -> Result<(), VerificationFailure> {
+    let invalid = |msg: String| VerificationFailure::ChangeOwnership(msg);
+
+    let message = change_ownership_message(pczt, proof.output_index)
+        .map_err(|e| invalid(e.to_string()))?;
+    let pubkey = get_input_pubkey(pczt, proof.input_index)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let pubkey = secp256k1::PublicKey::from_slice(&pubkey)
+        .map_err(|e| invalid(format!("Invalid input pubkey: {}", e)))?;
+    let signature = secp256k1::ecdsa::Signature::from_compact(&proof.signature)
+        .map_err(|e| invalid(format!("Invalid signature: {}", e)))?;
+
+    secp256k1::Secp256k1::verification_only()
+        .verify_ecdsa(&secp256k1::Message::from_digest(message), &signature, &pubkey)
+        .map_err(|_| invalid(format!(
+            "Signature does not match the key of input {}", proof.input_index
+        )))
+}
+
+/// Computes a fingerprint of the transaction a PCZT represents.
+///
+/// The fingerprint is a SHA-256 digest over the transaction effects only: global
+/// transaction fields, transparent inputs and outputs, and the Sapling and Orchard
+/// bundle effects. Signatures, proofs, preimages and other signing metadata are
+/// ignored, so two parties can confirm they are operating on the same underlying
+/// transaction before combining.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to fingerprint
+///
+/// # Returns
+/// * `[u8; 32]` - The fingerprint
+pub fn pczt_fingerprint(pczt: &Pczt) -> [u8; 32] {
+    use sha2::{Digest, Sha256};
+
+    fn update_bytes(hasher: &mut Sha256, bytes: &[u8]) {
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+
+    fn update_opt_u32(hasher: &mut Sha256, value: Option<u32>) {
+        match value {
+            Some(v) => {
+                hasher.update([1]);
+                hasher.update(v.to_le_bytes());
+            }
+            None => hasher.update([0]),
+        }
+    }
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"t2z_pczt_fingerprint_v1");
+
+    // Global transaction fields
+    let global = pczt.global();
+    hasher.update(global.tx_version().to_le_bytes());
+    hasher.update(global.version_group_id().to_le_bytes());
+    hasher.update(global.consensus_branch_id().to_le_bytes());
+    update_opt_u32(&mut hasher, *global.fallback_lock_time());
+    hasher.update(global.expiry_height().to_le_bytes());
+
+    // Transparent effects
+    let transparent = pczt.transparent();
+    hasher.update((transparent.inputs().len() as u64).to_le_bytes());
+    for input in transparent.inputs() {
+        hasher.update(input.prevout_txid());
+        hasher.update(input.prevout_index().to_le_bytes());
+        update_opt_u32(&mut hasher, *input.sequence());
+        hasher.update(input.value().to_le_bytes());
+        update_bytes(&mut hasher, input.script_pubkey());
+    }
+    hasher.update((transparent.outputs().len() as u64).to_le_bytes());
+    for output in transparent.outputs() {
+        hasher.update(output.value().to_le_bytes());
+        update_bytes(&mut hasher, output.script_pubkey());
+    }
+
+    // Sapling effects
+    let sapling = pczt.sapling();
+    hasher.update((sapling.spends().len() as u64).to_le_bytes());
+    for spend in sapling.spends() {
+        hasher.update(spend.cv());
+        hasher.update(spend.nullifier());
+        hasher.update(spend.rk());
+    }
+    hasher.update((sapling.outputs().len() as u64).to_le_bytes());
+    for output in sapling.outputs() {
+        hasher.update(output.cv());
+        hasher.update(output.cmu());
+        hasher.update(output.ephemeral_key());
+        update_bytes(&mut hasher, output.enc_ciphertext());
+        update_bytes(&mut hasher, output.out_ciphertext());
+    }
+    hasher.update(sapling.value_sum().to_le_bytes());
+    hasher.update(sapling.anchor());
+
+    // Orchard effects
+    let orchard = pczt.orchard();
+    hasher.update((orchard.actions().len() as u64).to_le_bytes());
+    for action in orchard.actions() {
+        hasher.update(action.cv_net());
+        hasher.update(action.spend().nullifier());
+        hasher.update(action.spend().rk());
+        hasher.update(action.output().cmx());
+        hasher.update(action.output().ephemeral_key());
+        update_bytes(&mut hasher, action.output().enc_ciphertext());
+        update_bytes(&mut hasher, action.output().out_ciphertext());
+    }
+    hasher.update([*orchard.flags()]);
+    let (value_sum_magnitude, value_sum_negative) = *orchard.value_sum();
+    hasher.update(value_sum_magnitude.to_le_bytes());
+    hasher.update([value_sum_negative as u8]);
+    hasher.update(orchard.anchor());
+
+    hasher.finalize().into()
+}
+
+/// Reads the enable bits of a PCZT's Orchard bundle.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to inspect
+///
+/// # Returns
+/// * `Option<OrchardFlags>` - The bundle's flags, or None if it has no Orchard actions
+///   (or sets reserved bits)
+pub fn get_orchard_flags(pczt: &Pczt) -> Option<OrchardFlags> {
+    if pczt.orchard().actions().is_empty() {
+        return None;
+    }
+    OrchardFlags::from_byte(*pczt.orchard().flags())
+}
+
+/// Lists the note commitment and ephemeral key of every Orchard output in a PCZT.
+///
+/// The values are fixed at proposal time, so they can be recorded alongside the
+/// payments before signing and matched against the chain after broadcast. Bundles
+/// are padded with dummy actions, which are reported with a value of 0.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to inspect
+///
+/// # Returns
+/// * `Vec<OrchardOutputInfo>` - One entry per Orchard action, in bundle order
+pub fn get_orchard_outputs(pczt: &Pczt) -> Vec<OrchardOutputInfo> {
+    pczt.orchard().actions().iter().enumerate()
+        .map(|(action_index, action)| OrchardOutputInfo {
+            action_index,
+            cmx: *action.output().cmx(),
+            ephemeral_key: *action.output().ephemeral_key(),
+            recipient: *action.output().recipient(),
+            value: *action.output().value(),
+        })
+        .collect()
+}
+
+/// Summarizes what a PCZT will do, for a confirmation screen before signing.
+///
+/// Needs only the PCZT: amounts come from its inputs and outputs, and transparent
+/// addresses are rendered from the scripts that will be signed. Fields a proposer
+/// can set freely (`user_address`, BIP-32 derivations) are ignored, so only
+/// outputs back to a spent input's script count as change. Use
+/// `summarize_pczt_with_change` when change goes to other addresses. Orchard
+/// outputs with a redacted value or recipient are listed with those fields unset.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to summarize (proved or not)
+///
+/// # Returns
+/// * `TransactionSummary` - Inputs, outputs, change, fee, estimated size and expiry
+pub fn summarize_pczt(pczt: &Pczt) -> TransactionSummary {
+    summarize_pczt_with_change(pczt, &[])
+}
+
+/// Summarizes a PCZT like `summarize_pczt`, also counting transparent outputs to
+/// `change_addresses` as change (e.g. the addresses of a change pool).
+///
+/// # Arguments
+/// * `pczt` - The PCZT to summarize (proved or not)
+/// * `change_addresses` - Transparent addresses the spender controls
+///
+/// # Returns
+/// * `TransactionSummary` - Inputs, outputs, change, fee, estimated size and expiry
+pub fn summarize_pczt_with_change(pczt: &Pczt, change_addresses: &[String]) -> TransactionSummary {
+    use zcash_address::unified::Encoding;
+    use zcash_protocol::consensus::NetworkType;
+
+    let global = pczt.global();
+    // SLIP-44 coin type 133 is mainnet; everything else is rendered with testnet prefixes
+    let network = if *global.coin_type() == 133 { NetworkType::Main } else { NetworkType::Test };
+    let transparent = pczt.transparent();
+
+    let inputs: Vec<SummaryInput> = transparent.inputs().iter()
+        .map(|input| {
+            let mut txid = *input.prevout_txid();
+            txid.reverse();
+            SummaryInput {
+                txid: hex_encode(&txid),
+                vout: *input.prevout_index(),
+                value: *input.value(),
+                address: script_to_address(input.script_pubkey(), network),
+            }
+        })
+        .collect();
+
+    let change_scripts: Vec<Vec<u8>> = transparent.inputs().iter()
+        .map(|input| input.script_pubkey().clone())
+        .chain(change_addresses.iter().filter_map(|address| address_to_script(address)))
+        .collect();
+    let mut outputs: Vec<SummaryOutput> = transparent.outputs().iter()
+        .map(|output| SummaryOutput {
+            pool: OutputPool::Transparent,
+            address: script_to_address(output.script_pubkey(), network),
+            value: Some(*output.value()),
+            is_change: change_scripts.contains(output.script_pubkey()),
+        })
+        .collect();
 
-        if is_change {
-            change_count += 1;
-        } else {
-            payment_outputs.push(pczt_output);
+    for action in pczt.orchard().actions() {
+        let value = *action.output().value();
+        // Padding actions carry no value
+        if value == Some(0) {
+            continue;
         }
+        let address = (*action.output().recipient()).and_then(|raw| {
+            unified::Address::try_from_items(vec![unified::Receiver::Orchard(raw)])
+                .ok()
+                .map(|ua| ua.encode(&network))
+        });
+        outputs.push(SummaryOutput {
+            pool: OutputPool::Orchard,
+            address,
+            value,
+            is_change: false,
+        });
     }
 
-    if change_count != expected_change.len() {
-        return Err(VerificationFailure::ChangeMismatch);
+    let change = outputs.iter()
+        .filter(|output| output.is_change)
+        .filter_map(|output| output.value)
+        .sum();
+    TransactionSummary {
+        network: match network {
+            NetworkType::Main => "main".to_string(),
+            _ => "test".to_string(),
+        },
+        expiry_height: *global.expiry_height(),
+        inputs,
+        outputs,
+        change,
+        fee: implicit_fee(pczt),
+        estimated_size: estimated_final_size(pczt),
+        label: transaction_label(pczt),
     }
-
-    Ok((change_count, payment_outputs))
 }
 
-/// Verifies that a transparent payment exists in the outputs
-fn verify_transparent_payment(
-    payment_outputs: &[&pczt::transparent::Output],
-    addr: &zcash_transparent::address::TransparentAddress,
-    amount: u64,
-    address_str: &str,
-) -> Result<(), VerificationFailure> {
-    let found = payment_outputs.iter()
-        .any(|output| output_matches_payment(output, addr, amount));
+/// Global proprietary key the request's `label` is stored under
+pub const LABEL_KEY: &str = "t2z.label";
 
-    if !found {
-        return Err(VerificationFailure::OutputMismatch(
-            format!("Payment to {} for {} zatoshis not found in PCZT outputs", address_str, amount)
-        ));
-    }
-    Ok(())
+/// The label of the request a PCZT was proposed from, if it had one.
+///
+/// The label travels in the PCZT's global proprietary fields, so every party and
+/// every later role (prover, signers, combiner, broadcaster) sees the same one.
+/// It is not part of the final transaction.
+pub fn transaction_label(pczt: &Pczt) -> Option<String> {
+    let value = pczt.global().proprietary().get(LABEL_KEY)?;
+    String::from_utf8(value.clone()).ok()
 }
 
-/// Verifies the PCZT before signing.
-///
-/// If the entity that invoked propose_transaction is the same as the entity adding signatures,
-/// and no third party may have malleated the PCZT, this step may be skipped.
+/// Lists the note commitment and ephemeral key of every Orchard action in a
+/// serialized final transaction.
 ///
-/// This verifies that:
-/// - Payment outputs match the transaction request
-/// - Change outputs match the expected change
-/// - Fees are reasonable (not too high)
+/// Recipients and values are encrypted on-chain, so they are reported as None.
 ///
 /// # Arguments
-/// * `pczt` - The PCZT to verify
-/// * `transaction_request` - The original transaction request
-/// * `expected_change` - Expected change outputs (transparent TxOuts)
+/// * `tx_bytes` - The serialized transaction (as returned by `finalize_and_extract`)
 ///
 /// # Returns
-/// * `Result<(), VerificationFailure>` - Success or verification error
-pub fn verify_before_signing(
-    pczt: &Pczt,
-    transaction_request: &TransactionRequest,
-    expected_change: &[zcash_transparent::bundle::TxOut],
-) -> Result<(), VerificationFailure> {
-    let transparent_outputs = pczt.transparent().outputs();
-    let orchard_actions = pczt.orchard().actions();
-    let num_orchard_outputs = orchard_actions.len();
-    let num_payments = transaction_request.payments.len();
-    let total_outputs = transparent_outputs.len() + num_orchard_outputs;
+/// * `Result<Vec<OrchardOutputInfo>, ParseError>` - One entry per Orchard action
+pub fn decode_orchard_outputs(tx_bytes: &[u8]) -> Result<Vec<OrchardOutputInfo>, ParseError> {
+    use zcash_primitives::transaction::Transaction;
+    use zcash_protocol::consensus::BranchId;
+
+    // v5 transactions carry their consensus branch ID in the header
+    let tx = Transaction::read(tx_bytes, BranchId::Nu5)
+        .map_err(|e| ParseError::InvalidFormat(format!("Failed to parse transaction: {}", e)))?;
+
+    Ok(tx.orchard_bundle()
+        .map(|bundle| {
+            bundle.actions().iter().enumerate()
+                .map(|(action_index, action)| OrchardOutputInfo {
+                    action_index,
+                    cmx: action.cmx().to_bytes(),
+                    ephemeral_key: action.encrypted_note().epk_bytes,
+                    recipient: None,
+                    value: None,
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
 
-    // Verify output counts
-    if expected_change.is_empty() {
-        if total_outputs < num_payments {
-            return Err(VerificationFailure::OutputMismatch(
-                format!("Expected at least {} payment outputs but found {}", num_payments, total_outputs)
-            ));
-        }
-    } else {
-        let expected_total = num_payments + expected_change.len();
-        if total_outputs != expected_total {
-            return Err(VerificationFailure::OutputMismatch(
-                format!("Output count mismatch: {} outputs but expected {} payments + {} change = {}",
-                    total_outputs, num_payments, expected_change.len(), expected_total)
-            ));
-        }
-    }
+/// Gets the sequence number of a transparent input.
+///
+/// Inputs without an explicit sequence number are final (`FINAL_SEQUENCE`).
+///
+/// # Arguments
+/// * `pczt` - The PCZT to inspect
+/// * `input_index` - The index of the transparent input
+///
+/// # Returns
+/// * `Result<u32, InspectionError>` - The sequence number or an error
+pub fn get_input_sequence(pczt: &Pczt, input_index: usize) -> Result<u32, InspectionError> {
+    let input = pczt.transparent().inputs().get(input_index)
+        .ok_or(InspectionError::InvalidInputIndex(input_index))?;
+    Ok(input.sequence().unwrap_or(FINAL_SEQUENCE))
+}
 
-    // Separate change from payment outputs
-    let payment_outputs = if !expected_change.is_empty() {
-        let (_, payments) = separate_change_outputs(transparent_outputs, expected_change)?;
-        payments
-    } else {
-        transparent_outputs.iter().collect()
-    };
+/// Gets the compressed public key that controls a transparent input.
+///
+/// Returns the hash160 preimage stored for the input's P2PKH script during proposal,
+/// so signers can pick the right key without keeping their own input-to-key mapping.
+/// A preimage that doesn't hash to the script's pubkey hash is not returned, so a
+/// party that swaps in its own key can't pass it off as the input's.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to inspect
+/// * `input_index` - The index of the transparent input
+///
+/// # Returns
+/// * `Result<[u8; 33], InspectionError>` - The compressed pubkey or an error
+pub fn get_input_pubkey(pczt: &Pczt, input_index: usize) -> Result<[u8; 33], InspectionError> {
+    let input = pczt.transparent().inputs().get(input_index)
+        .ok_or(InspectionError::InvalidInputIndex(input_index))?;
+
+    p2pkh_pubkey_hash(input.script_pubkey())
+        .and_then(|pubkey_hash| {
+            input.hash160_preimages().get(&pubkey_hash)
+                .filter(|preimage| ct_eq_bytes(&hash160(preimage), &pubkey_hash))
+        })
+        .and_then(|preimage| <[u8; 33]>::try_from(preimage.as_slice()).ok())
+        .ok_or(InspectionError::MissingInputPubkey(input_index))
+}
 
-    // Verify each payment exists in outputs
-    for payment in &transaction_request.payments {
-        let addr = payment.address.parse::<ZcashAddress>()
-            .map_err(|_| VerificationFailure::OutputMismatch(
-                format!("Invalid payment address: {}", payment.address)
-            ))?;
+/// Confirms that transparent outpoints are still unspent.
+///
+/// Implemented for closures `Fn(&InputOutpoint, u32) -> Result<bool, String>`, and by
+/// `experimental::lightwalletd::LightwalletdUtxoChecker` with the `lightwalletd` feature.
+pub trait UtxoChecker {
+    /// Returns whether `outpoint` is unspent as of block `height`.
+    fn is_unspent(&self, outpoint: &InputOutpoint, height: u32) -> Result<bool, String>;
+}
 
-        if let Ok(t_addr) = addr.convert::<zcash_transparent::address::TransparentAddress>() {
-            verify_transparent_payment(&payment_outputs, &t_addr, payment.amount, &payment.address)?;
-        } else if num_orchard_outputs == 0 {
-            return Err(VerificationFailure::OutputMismatch(
-                "Shielded payment requested but no Orchard outputs found".to_string()
-            ));
-        }
+impl<F> UtxoChecker for F
+where
+    F: Fn(&InputOutpoint, u32) -> Result<bool, String>,
+{
+    fn is_unspent(&self, outpoint: &InputOutpoint, height: u32) -> Result<bool, String> {
+        self(outpoint, height)
     }
+}
 
-    // Verify fee is reasonable
-    // For transparent-only transactions: fee should not exceed 1% of total
-    // For shielded transactions: we skip this check since Orchard amounts are hidden
-    let has_orchard = num_orchard_outputs > 0;
-    if !has_orchard {
-        let total_output_value: u64 = transparent_outputs.iter().map(|o| *o.value()).sum();
-        let requested_total = transaction_request.total_amount();
+/// Lists the unspent outputs of transparent addresses, for `xpub::discover_utxos`.
+///
+/// Implemented for closures `Fn(&[String]) -> Result<Vec<AddressUtxo>, String>`, and by
+/// `experimental::lightwalletd::LightwalletdUtxoChecker` with the `lightwalletd` feature.
+pub trait UtxoSource {
+    /// Returns every UTXO paying to any of `addresses`.
+    fn address_utxos(&self, addresses: &[String]) -> Result<Vec<AddressUtxo>, String>;
+}
 
-        if total_output_value > 0 && requested_total > 0 {
-            let max_reasonable_fee = requested_total / 100;
-            if total_output_value + max_reasonable_fee < requested_total {
-                return Err(VerificationFailure::InvalidFee);
-            }
+impl<F> UtxoSource for F
+where
+    F: Fn(&[String]) -> Result<Vec<AddressUtxo>, String>,
+{
+    fn address_utxos(&self, addresses: &[String]) -> Result<Vec<AddressUtxo>, String> {
+        self(addresses)
+    }
+}
+
+/// Re-checks that every transparent input of a PCZT is still unspent.
+///
+/// Intended to run right before broadcast when signing took long enough for a
+/// reorg or a double-spend to invalidate an input.
+///
+/// # Arguments
+/// * `pczt` - The PCZT whose inputs to check
+/// * `checker` - Source of truth for the UTXO set
+/// * `height` - Block height to check against (usually the current chain tip)
+///
+/// # Returns
+/// * `Result<(), RevalidationError>` - Success, `InputsSpent` listing every input that
+///   is gone, or `CheckFailed` if the checker couldn't answer
+pub fn revalidate_inputs(
+    pczt: &Pczt,
+    checker: &dyn UtxoChecker,
+    height: u32,
+) -> Result<(), RevalidationError> {
+    let mut spent = Vec::new();
+
+    for (index, input) in pczt.transparent().inputs().iter().enumerate() {
+        let outpoint = InputOutpoint {
+            txid: *input.prevout_txid(),
+            vout: *input.prevout_index(),
+            value: *input.value(),
+            script_pubkey: input.script_pubkey().clone(),
+        };
+        let unspent = checker.is_unspent(&outpoint, height)
+            .map_err(|message| RevalidationError::CheckFailed { index, message })?;
+        if !unspent {
+            spent.push(index);
         }
     }
 
-    Ok(())
+    if spent.is_empty() {
+        Ok(())
+    } else {
+        Err(RevalidationError::InputsSpent { indices: spent })
+    }
 }
 
 /// Gets the signature hash for a specific input.
@@ -536,12 +2686,75 @@ pub fn get_sighash(
     Ok(SigHash(hash))
 }
 
+/// Gets the signature hashes for every transparent input, in input order.
+///
+/// Cheaper than calling `get_sighash` per input: the PCZT is parsed for signing
+/// once rather than once per input.
+///
+/// # Arguments
+/// * `pczt` - The PCZT
+///
+/// # Returns
+/// * `Result<Vec<SigHash>, SighashError>` - One signature hash per input or an error
+pub fn get_sighashes(pczt: &Pczt) -> Result<Vec<SigHash>, SighashError> {
+    use pczt::roles::signer::Signer;
+
+    let signer = Signer::new(pczt.clone())
+        .map_err(|e| SighashError::CalculationFailed(format!("Failed to create Signer: {:?}", e)))?;
+
+    (0..pczt.transparent().inputs().len())
+        .map(|index| {
+            signer.transparent_sighash(index)
+                .map(SigHash)
+                .map_err(|e| SighashError::CalculationFailed(format!("{:?}", e)))
+        })
+        .collect()
+}
+
+/// Exports every transparent input's sighash with the shared transaction context.
+///
+/// Hardware wallets signing all inputs in one session can receive this single
+/// document (see `SighashBatch::to_bytes`) instead of one round trip per input.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to sign
+///
+/// # Returns
+/// * `Result<SighashBatch, SighashError>` - The batch or an error
+pub fn export_sighash_batch(pczt: &Pczt) -> Result<SighashBatch, SighashError> {
+    let global = pczt.global();
+    let transparent_inputs = pczt.transparent().inputs();
+
+    let sighashes = get_sighashes(pczt)?;
+
+    let mut inputs = Vec::with_capacity(transparent_inputs.len());
+    for ((index, input), sighash) in transparent_inputs.iter().enumerate().zip(sighashes) {
+        let pubkey = get_input_pubkey(pczt, index).map_err(|_| SighashError::MissingInputData)?;
+        inputs.push(SighashBatchInput {
+            pubkey,
+            value: *input.value(),
+            script_pubkey: input.script_pubkey().clone(),
+            sighash: *sighash.as_bytes(),
+        });
+    }
+
+    Ok(SighashBatch {
+        fingerprint: pczt_fingerprint(pczt),
+        consensus_branch_id: *global.consensus_branch_id(),
+        expiry_height: *global.expiry_height(),
+        fee: implicit_fee(pczt).ok_or_else(|| SighashError::CalculationFailed(
+            "Transaction spends more than its inputs".to_string()
+        ))?,
+        inputs,
+    })
+}
+
 /// Appends a signature to the PCZT for a specific input.
 ///
 /// The implementation should verify that the signature validates for the input being spent.
 ///
 /// NOTE: This function is for adding pre-computed signatures (e.g., from hardware wallets).
-/// For direct signing, use the pczt::roles::signer::Signer role's sign_transparent() method.
+/// To sign with a key held by the caller, use `sign_transaction`.
 ///
 /// # Arguments
 /// * `pczt` - The PCZT to add the signature to
@@ -583,6 +2796,46 @@ pub fn append_signature(
     Ok(signer.finish())
 }
 
+/// Signs a transparent input with a secret key held by the caller.
+///
+/// For software wallets: the sighash is computed and signed inside the pczt
+/// Signer role, so callers don't need to compute or verify it themselves.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to sign
+/// * `input_index` - The index of the transparent input to sign
+/// * `secret_key` - The 32-byte secp256k1 secret key for that input
+///
+/// # Returns
+/// * `Result<Pczt, SignatureError>` - The signed PCZT, or `KeyMismatch` if the key
+///   doesn't sign for the input
+pub fn sign_transaction(
+    pczt: Pczt,
+    input_index: usize,
+    secret_key: &[u8; 32],
+) -> Result<Pczt, SignatureError> {
+    use pczt::roles::signer::Signer;
+
+    if input_index >= pczt.transparent().inputs().len() {
+        return Err(SignatureError::InvalidInputIndex(input_index));
+    }
+
+    let sk = secp256k1::SecretKey::from_slice(secret_key)
+        .map_err(|_| SignatureError::InvalidSecretKey)?;
+
+    let mut signer = Signer::new(pczt)
+        .map_err(|_| SignatureError::InvalidFormat)?;
+
+    signer.sign_transparent(input_index, &sk)
+        .map_err(|e| match e {
+            pczt::roles::signer::Error::InvalidIndex => SignatureError::InvalidInputIndex(input_index),
+            pczt::roles::signer::Error::TransparentSign(_) => SignatureError::KeyMismatch { index: input_index },
+            _ => SignatureError::InvalidFormat,
+        })?;
+
+    Ok(signer.finish())
+}
+
 /// Combines multiple PCZTs into one.
 ///
 /// If the same entity invokes prove_transaction and append_signature sequentially
@@ -626,20 +2879,46 @@ pub fn combine(pczts: Vec<Pczt>) -> Result<Pczt, CombineError> {
 /// * `pczt` - The PCZT to finalize and extract
 ///
 /// # Returns
-/// * `Result<Vec<u8>, FinalizationError>` - The transaction bytes or an error
+/// * `Result<Vec<u8>, FinalizationError>` - The transaction bytes or an error.
+///   Inputs that can't be finalized are reported with their index
+///   (`InputMissingSignature`, `InputPubkeyMismatch`, `UnsupportedInputScript`).
 pub fn finalize_and_extract(pczt: Pczt) -> Result<Vec<u8>, FinalizationError> {
     use pczt::roles::spend_finalizer::SpendFinalizer;
     use pczt::roles::tx_extractor::TransactionExtractor;
 
+    // Report which input can't be finalized before handing off to the Spend Finalizer,
+    // whose errors don't identify the input
+    if let Some(err) = diagnose_spend_finalization(&pczt) {
+        return Err(err);
+    }
+
     // Step 1: Finalize spends (combines partial signatures into script_sigs)
     let pczt = SpendFinalizer::new(pczt)
         .finalize_spends()
         .map_err(|e| FinalizationError::SpendFinalization(format!("{:?}", e)))?;
 
     // Step 2: Extract the transaction
-    // For Orchard transactions, the verifying key will be generated on the fly
-    // We don't need Sapling verifying keys since we only support Orchard
-    let transaction = TransactionExtractor::new(pczt)
+    // For Orchard transactions, the verifying key is built once and then cached until
+    // `unload_verifying_keys` (generated on the fly without the `orchard-prover` feature).
+    // Transparent-only transactions have no bundle to verify, so no key is built.
+    // Sapling bundles are verified with keys from the Sapling parameters.
+    #[cfg(feature = "orchard-prover")]
+    let orchard_vk = (!pczt.orchard().actions().is_empty()).then(prover::orchard_verifying_key);
+    #[cfg(all(feature = "sapling", feature = "orchard-prover"))]
+    let sapling_keys = shielded::sapling_verifying_keys(&pczt)
+        .map_err(FinalizationError::TransactionExtraction)?;
+    let extractor = TransactionExtractor::new(pczt);
+    #[cfg(feature = "orchard-prover")]
+    let extractor = match &orchard_vk {
+        Some(vk) => extractor.with_orchard(vk),
+        None => extractor,
+    };
+    #[cfg(all(feature = "sapling", feature = "orchard-prover"))]
+    let extractor = match &sapling_keys {
+        Some((spend_vk, output_vk)) => extractor.with_sapling(spend_vk, output_vk),
+        None => extractor,
+    };
+    let transaction = extractor
         .extract()
         .map_err(|e| FinalizationError::TransactionExtraction(format!("{:?}", e)))?;
 
@@ -663,6 +2942,95 @@ pub fn parse_pczt(pczt_bytes: &[u8]) -> Result<Pczt, ParseError> {
         .map_err(|e| ParseError::InvalidFormat(format!("{:?}", e)))
 }
 
+/// Parses PCZT from bytes with the given options.
+///
+/// With `require_canonical`, the parsed PCZT is re-serialized and compared with the
+/// input, so semantically-equal documents are guaranteed to have identical bytes.
+///
+/// # Arguments
+/// * `pczt_bytes` - The serialized PCZT bytes
+/// * `options` - Parsing options
+///
+/// # Returns
+/// * `Result<Pczt, ParseError>` - The parsed PCZT or an error
+pub fn parse_pczt_with_options(pczt_bytes: &[u8], options: ParseOptions) -> Result<Pczt, ParseError> {
+    let pczt = parse_pczt(pczt_bytes)?;
+
+    if (options.require_canonical || options.reject_unknown_fields) && pczt.serialize() != pczt_bytes {
+        return Err(ParseError::NonCanonical);
+    }
+    if options.reject_unknown_fields {
+        check_proprietary_fields(&pczt)?;
+    }
+
+    Ok(pczt)
+}
+
+/// Namespace of proprietary fields reserved for this crate
+pub const T2Z_PROPRIETARY_NAMESPACE: &str = "t2z";
+
+/// Proprietary namespaces accepted in safe mode besides `T2Z_PROPRIETARY_NAMESPACE`
+static PROPRIETARY_NAMESPACES: std::sync::RwLock<Vec<String>> = std::sync::RwLock::new(Vec::new());
+
+/// Recognizes a proprietary namespace for `ParseOptions::reject_unknown_fields`.
+///
+/// A key belongs to namespace `ns` if it is `ns` or starts with `ns.`.
+pub fn register_proprietary_namespace(namespace: &str) {
+    let mut namespaces = PROPRIETARY_NAMESPACES.write().unwrap_or_else(|e| e.into_inner());
+    if !namespaces.iter().any(|known| known == namespace) {
+        namespaces.push(namespace.to_string());
+    }
+}
+
+/// Rejects proprietary keys outside the recognized namespaces
+fn check_proprietary_fields(pczt: &Pczt) -> Result<(), ParseError> {
+    let namespaces = PROPRIETARY_NAMESPACES.read().unwrap_or_else(|e| e.into_inner());
+    let recognized = |key: &str| {
+        std::iter::once(T2Z_PROPRIETARY_NAMESPACE)
+            .chain(namespaces.iter().map(String::as_str))
+            .any(|ns| key == ns || key.strip_prefix(ns).is_some_and(|rest| rest.starts_with('.')))
+    };
+
+    let mut fields: Vec<(String, &std::collections::BTreeMap<String, Vec<u8>>)> =
+        vec![("global".to_string(), pczt.global().proprietary())];
+    for (i, input) in pczt.transparent().inputs().iter().enumerate() {
+        fields.push((format!("transparent input {}", i), input.proprietary()));
+    }
+    for (i, output) in pczt.transparent().outputs().iter().enumerate() {
+        fields.push((format!("transparent output {}", i), output.proprietary()));
+    }
+    for (i, spend) in pczt.sapling().spends().iter().enumerate() {
+        fields.push((format!("Sapling spend {}", i), spend.proprietary()));
+    }
+    for (i, output) in pczt.sapling().outputs().iter().enumerate() {
+        fields.push((format!("Sapling output {}", i), output.proprietary()));
+    }
+    for (i, action) in pczt.orchard().actions().iter().enumerate() {
+        fields.push((format!("Orchard action {} spend", i), action.spend().proprietary()));
+        fields.push((format!("Orchard action {} output", i), action.output().proprietary()));
+    }
+
+    for (location, proprietary) in fields {
+        if let Some(key) = proprietary.keys().find(|key| !recognized(key)) {
+            return Err(ParseError::UnknownField(format!("{} has proprietary key {:?}", location, key)));
+        }
+    }
+    Ok(())
+}
+
+/// Checks whether PCZT bytes are in canonical form.
+///
+/// # Arguments
+/// * `pczt_bytes` - The serialized PCZT bytes
+///
+/// # Returns
+/// * `Result<bool, ParseError>` - Whether re-serializing yields identical bytes,
+///   or an error if the bytes are not a valid PCZT
+pub fn is_canonical(pczt_bytes: &[u8]) -> Result<bool, ParseError> {
+    let pczt = parse_pczt(pczt_bytes)?;
+    Ok(pczt.serialize() == pczt_bytes)
+}
+
 /// Serializes a PCZT to bytes.
 ///
 /// # Arguments
@@ -674,3 +3042,120 @@ pub fn serialize_pczt(pczt: &Pczt) -> Vec<u8> {
     pczt.serialize()
 }
 
+/// Magic bytes every serialized PCZT starts with
+const PCZT_MAGIC_BYTES: &[u8] = b"PCZT";
+
+/// Writes a serialized PCZT to `writer`.
+///
+/// Produces the same bytes as `serialize_pczt`: the encoding is the pczt crate's
+/// own `Pczt::serialize`, so its framing can't drift from what `parse_pczt` reads.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to serialize
+/// * `writer` - Destination for the serialized bytes
+///
+/// # Returns
+/// * `io::Result<()>` - Success or the writer's error
+pub fn write_pczt<W: std::io::Write>(pczt: &Pczt, mut writer: W) -> std::io::Result<()> {
+    writer.write_all(&pczt.serialize())
+}
+
+/// File extension of saved PCZTs (`proposal.pczt`), so tools exchanging files in
+/// multi-party flows agree on one
+pub const PCZT_FILE_EXTENSION: &str = "pczt";
+
+/// Distinguishes the temporary files of concurrent `save_pczt` calls in one process
+static SAVE_PCZT_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Saves a PCZT to a file, atomically.
+///
+/// The PCZT is written to a temporary file next to `path`, synced, and renamed
+/// over `path`, so a crash or a reader racing the write never sees a partial
+/// PCZT. Each call writes its own temporary file, so concurrent saves to the same
+/// path leave one complete PCZT. On Unix the directory is synced after the rename so
+/// the new entry survives a crash. By convention the path ends in `.pczt`
+/// (`PCZT_FILE_EXTENSION`).
+///
+/// # Arguments
+/// * `path` - Destination file, replaced if it exists
+/// * `pczt` - The PCZT to save
+///
+/// # Returns
+/// * `Result<(), FileError>` - Success or the I/O error
+pub fn save_pczt<P: AsRef<std::path::Path>>(path: P, pczt: &Pczt) -> Result<(), FileError> {
+    use std::io::Write;
+
+    let path = path.as_ref();
+    let file_name = path.file_name()
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, "Path has no file name"))?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    let call = SAVE_PCZT_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    tmp_name.push(format!(".{}.{}.tmp", std::process::id(), call));
+    let tmp_path = path.with_file_name(tmp_name);
+
+    let write = || -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(std::fs::File::create_new(&tmp_path)?);
+        write_pczt(pczt, &mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        std::fs::rename(&tmp_path, path)
+    };
+    write().map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        FileError::Io(e)
+    })?;
+
+    // The rename is only durable once the directory entry is
+    #[cfg(unix)]
+    {
+        let dir = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => std::path::Path::new("."),
+        };
+        std::fs::File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+/// Loads a PCZT saved with `save_pczt` (or any file holding a serialized PCZT).
+///
+/// Files that don't start with the PCZT magic bytes are rejected as
+/// `FileError::NotAPczt` before parsing, so a wrong file (e.g. a raw transaction)
+/// gets a clear error rather than a decoding failure.
+///
+/// # Arguments
+/// * `path` - The file to load
+///
+/// # Returns
+/// * `Result<Pczt, FileError>` - The parsed PCZT or an error
+pub fn load_pczt<P: AsRef<std::path::Path>>(path: P) -> Result<Pczt, FileError> {
+    let path = path.as_ref();
+    let bytes = std::fs::read(path)?;
+    if !bytes.starts_with(PCZT_MAGIC_BYTES) {
+        return Err(FileError::NotAPczt(path.display().to_string()));
+    }
+    Ok(parse_pczt(&bytes)?)
+}
+
+/// Describes how this library was built, for audit logs.
+///
+/// Hosts can record it alongside each transaction to know exactly which builder
+/// produced it.
+///
+/// # Returns
+/// * `BuildInfo` - Version, git commit, features and dependency versions
+pub fn build_info() -> BuildInfo {
+    let list = |value: &str| -> Vec<String> {
+        value.split(',').filter(|item| !item.is_empty()).map(str::to_string).collect()
+    };
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: env!("T2Z_BUILD_GIT_COMMIT").to_string(),
+        git_dirty: env!("T2Z_BUILD_GIT_DIRTY") == "true",
+        features: list(env!("T2Z_BUILD_FEATURES")),
+        dependencies: list(env!("T2Z_BUILD_DEPENDENCIES")),
+        target: env!("T2Z_BUILD_TARGET").to_string(),
+        profile: env!("T2Z_BUILD_PROFILE").to_string(),
+    }
+}
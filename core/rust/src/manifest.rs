@@ -0,0 +1,167 @@
+//! Signed proposal manifests
+//!
+//! An audit pipeline passes a PCZT through several stages (approval, signing,
+//! proving, broadcast) that need to know the transaction is still the one the
+//! proposer authorized. `sign_manifest` records a PCZT's inputs, outputs, fee and
+//! fingerprint and signs them with the operator's Ed25519 key at proposal time.
+//! Any later stage holding the operator's public key checks the PCZT against the
+//! manifest with `verify_manifest`.
+
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use pczt::Pczt;
+use serde::{Deserialize, Serialize};
+
+use crate::error::VerificationFailure;
+use crate::types::{OutputPool, SummaryInput, SummaryOutput};
+use crate::{ct_eq_bytes, hex_encode, pczt_fingerprint, summarize_pczt};
+
+/// Version of the manifest format
+pub const MANIFEST_VERSION: u32 = 1;
+
+/// Domain separator prepended to the manifest JSON before signing
+const MANIFEST_DOMAIN: &[u8] = b"t2z_manifest_v1";
+
+/// What the proposer authorized: the effects of one PCZT
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProposalManifest {
+    pub version: u32,
+    /// `pczt_fingerprint` of the proposal (hex)
+    pub fingerprint: String,
+    /// Transparent inputs being spent
+    pub inputs: Vec<SummaryInput>,
+    /// Transparent and Orchard outputs, as in `TransactionSummary`
+    pub outputs: Vec<ManifestOutput>,
+    /// Fee in zatoshis
+    pub fee: u64,
+}
+
+/// An output in a `ProposalManifest`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestOutput {
+    pub pool: OutputPool,
+    /// Recipient address, if known
+    pub address: Option<String>,
+    /// Value in zatoshis, if not redacted
+    pub value: Option<u64>,
+}
+
+impl From<SummaryOutput> for ManifestOutput {
+    fn from(output: SummaryOutput) -> Self {
+        ManifestOutput { pool: output.pool, address: output.address, value: output.value }
+    }
+}
+
+/// A manifest with the operator's Ed25519 signature over it
+///
+/// The signature covers `payload`, the manifest's JSON exactly as it was signed, so
+/// re-encoding the `SignedManifest` (reordering keys, pretty-printing) doesn't break
+/// it. `manifest` is the same document decoded, and must match `payload`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedManifest {
+    pub manifest: ProposalManifest,
+    /// The manifest's JSON, as signed
+    pub payload: String,
+    /// The operator's Ed25519 public key (hex)
+    pub public_key: String,
+    /// Ed25519 signature over the domain separator and `payload` (hex)
+    pub signature: String,
+}
+
+/// Records the effects of a PCZT in a manifest.
+///
+/// # Returns
+/// * `Result<ProposalManifest, VerificationFailure>` - The manifest, or `InvalidFee`
+///   if the PCZT's values don't balance
+pub fn proposal_manifest(pczt: &Pczt) -> Result<ProposalManifest, VerificationFailure> {
+    let summary = summarize_pczt(pczt);
+    Ok(ProposalManifest {
+        version: MANIFEST_VERSION,
+        fingerprint: hex_encode(&pczt_fingerprint(pczt)),
+        inputs: summary.inputs,
+        outputs: summary.outputs.into_iter().map(ManifestOutput::from).collect(),
+        fee: summary.fee.ok_or(VerificationFailure::InvalidFee)?,
+    })
+}
+
+/// Bytes the signature covers: the domain separator and the manifest's JSON
+fn signed_bytes(payload: &str) -> Vec<u8> {
+    [MANIFEST_DOMAIN, payload.as_bytes()].concat()
+}
+
+/// Records a PCZT's effects and signs them with the operator's key.
+///
+/// # Arguments
+/// * `pczt` - The proposed PCZT
+/// * `signing_key` - The operator's 32-byte Ed25519 secret key
+///
+/// # Returns
+/// * `Result<SignedManifest, VerificationFailure>` - The signed manifest or an error
+pub fn sign_manifest(pczt: &Pczt, signing_key: &[u8; 32]) -> Result<SignedManifest, VerificationFailure> {
+    let manifest = proposal_manifest(pczt)?;
+    let payload = serde_json::to_string(&manifest).expect("manifest serializes");
+    let key = SigningKey::from_bytes(signing_key);
+    let signature = key.sign(&signed_bytes(&payload));
+    Ok(SignedManifest {
+        manifest,
+        payload,
+        public_key: hex_encode(key.verifying_key().as_bytes()),
+        signature: hex_encode(&signature.to_bytes()),
+    })
+}
+
+/// Checks that a PCZT is the transaction an operator authorized.
+///
+/// The manifest must carry `trusted_key` and a valid signature from it over its
+/// payload, which must decode to its `manifest` (`ManifestSignature` otherwise).
+/// The PCZT must then have the manifest's
+/// fingerprint, inputs and fee, and the same transparent outputs
+/// (`ManifestMismatch` otherwise). Orchard outputs are covered by the fingerprint,
+/// so a PCZT whose Orchard values were redacted after proposal still verifies.
+///
+/// # Arguments
+/// * `pczt` - The PCZT at the current stage (signed, proved or not)
+/// * `signed` - The manifest emitted at proposal time
+/// * `trusted_key` - The operator's 32-byte Ed25519 public key
+pub fn verify_manifest(
+    pczt: &Pczt,
+    signed: &SignedManifest,
+    trusted_key: &[u8; 32],
+) -> Result<(), VerificationFailure> {
+    let invalid = |reason: &str| VerificationFailure::ManifestSignature(reason.to_string());
+
+    if !ct_eq_bytes(signed.public_key.as_bytes(), hex_encode(trusted_key).as_bytes()) {
+        return Err(invalid("Manifest was not signed with the trusted key"));
+    }
+    let key = VerifyingKey::from_bytes(trusted_key).map_err(|_| invalid("Invalid public key"))?;
+    let signature: [u8; 64] = crate::json::hex_decode(&signed.signature)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| invalid("Signature must be 64 bytes of hex"))?;
+    key.verify(&signed_bytes(&signed.payload), &Signature::from_bytes(&signature))
+        .map_err(|_| invalid("Signature does not match the manifest"))?;
+    let manifest: ProposalManifest = serde_json::from_str(&signed.payload)
+        .map_err(|_| invalid("Signed payload is not a manifest"))?;
+    if manifest != signed.manifest {
+        return Err(invalid("Manifest does not match its signed payload"));
+    }
+
+    let mismatch = |what: &str| VerificationFailure::ManifestMismatch(format!("{} changed since proposal", what));
+    let current = proposal_manifest(pczt)?;
+    if current.fingerprint != manifest.fingerprint {
+        return Err(mismatch("Fingerprint"));
+    }
+    if current.inputs != manifest.inputs {
+        return Err(mismatch("Inputs"));
+    }
+    if current.fee != manifest.fee {
+        return Err(mismatch("Fee"));
+    }
+    let transparent = |outputs: &[ManifestOutput]| -> Vec<ManifestOutput> {
+        outputs.iter().filter(|output| output.pool == OutputPool::Transparent).cloned().collect()
+    };
+    if transparent(&current.outputs) != transparent(&manifest.outputs) {
+        return Err(mismatch("Transparent outputs"));
+    }
+
+    Ok(())
+}
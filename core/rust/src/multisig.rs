@@ -0,0 +1,107 @@
+//! Tracking signatures across several signers
+//!
+//! Where `cosigning` assigns each input to exactly one key, these helpers also cover
+//! P2SH multisig inputs, which any `m` of their `n` keys can sign. A coordinator
+//! lists what is still missing with `missing_signatures`, hands each signer a
+//! `SignerView` of the inputs they can still sign, combines the PCZTs they send back,
+//! and checks the result with `check_fully_signed` before finalization.
+//!
+//! ```ignore
+//! for view in signer_views(&pczt) {
+//!     // send view.pczt to view.pubkey's signer, who signs view.input_indices ...
+//! }
+//! let combined = combine(signed_pczts)?;
+//! check_fully_signed(&combined)?;
+//! let tx = finalize_and_extract(combined)?;
+//! ```
+
+use pczt::Pczt;
+
+use crate::error::FinalizationError;
+
+/// A transparent input that doesn't have enough signatures yet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingSig {
+    /// Index of the input in the PCZT
+    pub input_index: usize,
+    /// Number of further signatures the input needs
+    pub needed: usize,
+    /// Keys that could provide them (empty if the input's signers can't be determined)
+    pub pubkeys: Vec<[u8; 33]>,
+}
+
+/// The inputs one signer can still contribute a signature to
+#[derive(Debug, Clone)]
+pub struct SignerView {
+    /// Compressed public key of the signer
+    pub pubkey: [u8; 33],
+    /// Indices of the inputs this key can still sign
+    pub input_indices: Vec<usize>,
+    /// The PCZT to sign and return
+    pub pczt: Pczt,
+}
+
+/// Signature threshold and keys of input `index`: its P2PKH key, or the keys of its
+/// P2SH multisig redeem script. None if neither can be determined from the PCZT.
+fn input_signers(pczt: &Pczt, index: usize) -> Option<(usize, Vec<[u8; 33]>)> {
+    let input = &pczt.transparent().inputs()[index];
+    if let Ok(pubkey) = crate::get_input_pubkey(pczt, index) {
+        return Some((1, vec![pubkey]));
+    }
+
+    let script_hash = crate::p2sh_script_hash(input.script_pubkey())?;
+    let redeem_script = input.redeem_script().as_ref()
+        .filter(|script| crate::ct_eq_bytes(&crate::hash160(script), &script_hash))?;
+    let (threshold, keys) = crate::multisig_keys(redeem_script)?;
+    let keys = keys.into_iter().filter_map(|key| <[u8; 33]>::try_from(key).ok()).collect();
+    Some((threshold, keys))
+}
+
+/// Lists the transparent inputs that can't be finalized for lack of signatures.
+///
+/// Inputs that are already finalized (have a script_sig) are skipped. For the rest,
+/// signatures from keys the input doesn't require aren't counted.
+pub fn missing_signatures(pczt: &Pczt) -> Vec<MissingSig> {
+    pczt.transparent().inputs().iter()
+        .enumerate()
+        .filter(|(_, input)| input.script_sig().is_none())
+        .filter_map(|(input_index, input)| {
+            let Some((threshold, keys)) = input_signers(pczt, input_index) else {
+                return Some(MissingSig { input_index, needed: 1, pubkeys: Vec::new() });
+            };
+
+            let signatures = input.partial_signatures();
+            let (signed, unsigned): (Vec<_>, Vec<_>) = keys.into_iter().partition(|key| signatures.contains_key(key));
+            let needed = threshold.saturating_sub(signed.len());
+            (needed > 0).then_some(MissingSig { input_index, needed, pubkeys: unsigned })
+        })
+        .collect()
+}
+
+/// Splits the outstanding signing work into one view per key, in order of each key's
+/// first appearance. A key appears in a view for every input it could still sign.
+pub fn signer_views(pczt: &Pczt) -> Vec<SignerView> {
+    let mut views: Vec<SignerView> = Vec::new();
+    for missing in missing_signatures(pczt) {
+        for pubkey in missing.pubkeys {
+            match views.iter_mut().find(|view| view.pubkey == pubkey) {
+                Some(view) => view.input_indices.push(missing.input_index),
+                None => views.push(SignerView { pubkey, input_indices: vec![missing.input_index], pczt: pczt.clone() }),
+            }
+        }
+    }
+    views
+}
+
+/// Checks that every transparent input of a combined PCZT has the signatures its
+/// script needs, so `finalize_and_extract` won't fail for lack of them.
+///
+/// # Returns
+/// * `Result<(), FinalizationError>` - `InputMissingSignature`, `InputPubkeyMismatch`
+///   or `UnsupportedInputScript` for the first input that can't be finalized
+pub fn check_fully_signed(pczt: &Pczt) -> Result<(), FinalizationError> {
+    match crate::diagnose_spend_finalization(pczt) {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,127 @@
+//! Storage for proving parameters
+//!
+//! Proving parameters can be hundreds of MB, so hosts choose where they live by
+//! installing a `ParamsStore`: the filesystem (default), memory, or their own
+//! implementation (e.g. app-private storage on mobile).
+//!
+//! The Orchard proving key needs no parameters (Halo 2 has no trusted setup); it is
+//! built in memory on first use. The store holds parameter files such as Sapling's
+//! `sapling-spend.params` and `sapling-output.params`, keyed by file name.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Key-value storage for parameter files
+pub trait ParamsStore: Send + Sync {
+    /// Returns the stored bytes for `key`, or None if there are none.
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String>;
+
+    /// Stores `value` under `key`, replacing any previous value.
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), String>;
+}
+
+/// Stores each parameter as a file named after its key
+#[derive(Debug, Clone)]
+pub struct FilesystemParamsStore {
+    dir: PathBuf,
+}
+
+impl FilesystemParamsStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FilesystemParamsStore { dir: dir.into() }
+    }
+
+    /// The directory zcashd and other Zcash tools use for parameters
+    ///
+    /// `$ZCASH_PARAMS` if set, otherwise `~/.zcash-params` (Linux),
+    /// `~/Library/Application Support/ZcashParams` (macOS) or `%APPDATA%\ZcashParams` (Windows).
+    pub fn default_dir() -> Option<PathBuf> {
+        if let Some(dir) = std::env::var_os("ZCASH_PARAMS") {
+            return Some(PathBuf::from(dir));
+        }
+        if cfg!(windows) {
+            std::env::var_os("APPDATA").map(|dir| Path::new(&dir).join("ZcashParams"))
+        } else if cfg!(target_os = "macos") {
+            std::env::var_os("HOME")
+                .map(|home| Path::new(&home).join("Library/Application Support/ZcashParams"))
+        } else {
+            std::env::var_os("HOME").map(|home| Path::new(&home).join(".zcash-params"))
+        }
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path_for(&self, key: &str) -> Result<PathBuf, String> {
+        // Keys are plain file names; never let them escape the directory
+        if key.is_empty() || key.contains(['/', '\\']) || key == "." || key == ".." {
+            return Err(format!("Invalid parameter key: {:?}", key));
+        }
+        Ok(self.dir.join(key))
+    }
+}
+
+impl ParamsStore for FilesystemParamsStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        match std::fs::read(self.path_for(key)?) {
+            Ok(bytes) => Ok(Some(bytes)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(format!("Failed to read {}: {}", key, e)),
+        }
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        let path = self.path_for(key)?;
+        std::fs::create_dir_all(&self.dir)
+            .map_err(|e| format!("Failed to create {}: {}", self.dir.display(), e))?;
+
+        // Write then rename, so readers never see a partial file
+        let tmp = path.with_extension("part");
+        std::fs::write(&tmp, value).map_err(|e| format!("Failed to write {}: {}", key, e))?;
+        std::fs::rename(&tmp, &path).map_err(|e| format!("Failed to write {}: {}", key, e))
+    }
+}
+
+/// Keeps parameters in memory, for hosts without writable storage and for tests
+#[derive(Debug, Default)]
+pub struct MemoryParamsStore {
+    entries: Mutex<HashMap<String, Vec<u8>>>,
+}
+
+impl MemoryParamsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ParamsStore for MemoryParamsStore {
+    fn get(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        let entries = self.entries.lock().map_err(|_| "Params store poisoned".to_string())?;
+        Ok(entries.get(key).cloned())
+    }
+
+    fn put(&self, key: &str, value: &[u8]) -> Result<(), String> {
+        let mut entries = self.entries.lock().map_err(|_| "Params store poisoned".to_string())?;
+        entries.insert(key.to_string(), value.to_vec());
+        Ok(())
+    }
+}
+
+static PARAMS_STORE: RwLock<Option<Arc<dyn ParamsStore>>> = RwLock::new(None);
+
+/// Installs the store used for all parameter lookups.
+pub fn set_params_store(store: Arc<dyn ParamsStore>) {
+    *PARAMS_STORE.write().unwrap_or_else(|e| e.into_inner()) = Some(store);
+}
+
+/// Returns the installed store, defaulting to a `FilesystemParamsStore` in
+/// `FilesystemParamsStore::default_dir()` (or the working directory if that is unknown).
+pub fn params_store() -> Arc<dyn ParamsStore> {
+    if let Some(store) = PARAMS_STORE.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Arc::clone(store);
+    }
+    let dir = FilesystemParamsStore::default_dir().unwrap_or_else(|| PathBuf::from("."));
+    Arc::new(FilesystemParamsStore::new(dir))
+}
@@ -0,0 +1,165 @@
+//! Orchard proving (requires the `orchard-prover` feature)
+//!
+//! The Orchard circuit and its proving key are by far the largest part of the
+//! library. Hosts that only compute sighashes, verify, sign or serialize PCZTs
+//! can build without this module; `prove_transaction` then still passes
+//! transparent-only PCZTs through, and refuses shielded ones with
+//! `ProverError::NotBuilt`.
+
+use std::sync::{Arc, Mutex, RwLock};
+
+use pczt::Pczt;
+
+use crate::error::ProverError;
+
+/// Returns the Orchard proving key, building it on first use.
+///
+/// Building takes several seconds; afterwards the key stays resident for the
+/// lifetime of the process.
+pub(crate) fn orchard_proving_key() -> &'static orchard::circuit::ProvingKey {
+    use std::sync::OnceLock;
+
+    static ORCHARD_PROVING_KEY: OnceLock<orchard::circuit::ProvingKey> = OnceLock::new();
+    ORCHARD_PROVING_KEY.get_or_init(orchard::circuit::ProvingKey::build)
+}
+
+/// The Orchard verifying key, once built. Unlike the proving key it can be
+/// dropped again with `unload_orchard_verifying_key`.
+static ORCHARD_VERIFYING_KEY: RwLock<Option<Arc<orchard::circuit::VerifyingKey>>> = RwLock::new(None);
+
+/// Returns the Orchard verifying key used at extraction, building it if it isn't cached.
+///
+/// The key is cached until `unload_orchard_verifying_key` drops it, so only the first
+/// shielded extraction after startup or after an unload pays for building it.
+pub(crate) fn orchard_verifying_key() -> Arc<orchard::circuit::VerifyingKey> {
+    if let Some(key) = ORCHARD_VERIFYING_KEY.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        return Arc::clone(key);
+    }
+    let mut slot = ORCHARD_VERIFYING_KEY.write().unwrap_or_else(|e| e.into_inner());
+    Arc::clone(slot.get_or_insert_with(|| Arc::new(orchard::circuit::VerifyingKey::build())))
+}
+
+/// Drops the cached Orchard verifying key, returning whether one was built.
+///
+/// Extractions already running keep their copy; the next one builds the key again.
+pub(crate) fn unload_orchard_verifying_key() -> bool {
+    ORCHARD_VERIFYING_KEY.write().unwrap_or_else(|e| e.into_inner()).take().is_some()
+}
+
+/// Proves a PCZT's Orchard actions on the calling thread's rayon pool
+pub(crate) fn prove_orchard(pczt: Pczt) -> Result<Pczt, ProverError> {
+    use pczt::roles::prover::Prover;
+
+    let prover = Prover::new(pczt);
+
+    // Check if we need to create Orchard proofs
+    if prover.requires_orchard_proof() {
+        let prover = prover.create_orchard_proof(orchard_proving_key())
+            .map_err(|e| ProverError::OrchardProof(format!("{:?}", e)))?;
+
+        Ok(prover.finish())
+    } else {
+        // No Orchard outputs, return as-is
+        Ok(prover.finish())
+    }
+}
+
+/// Proves every shielded bundle of a PCZT.
+///
+/// Each pool's prover only touches its own bundle, so when a PCZT needs both Sapling
+/// and Orchard proofs and more than one thread is allowed, the two are created
+/// concurrently on copies of the PCZT and combined.
+pub(crate) fn prove_bundles(pczt: Pczt) -> Result<Pczt, ProverError> {
+    #[cfg(feature = "sapling")]
+    {
+        let has_sapling = !pczt.sapling().spends().is_empty() || !pczt.sapling().outputs().is_empty();
+        if has_sapling && !pczt.orchard().actions().is_empty() && prover_threads() != 1 {
+            let copy = pczt.clone();
+            let (sapling, orchard) = rayon::join(
+                move || crate::shielded::prove_sapling(copy),
+                move || prove_orchard(pczt),
+            );
+            return crate::combine(vec![sapling?, orchard?])
+                .map_err(|e| ProverError::ProofGenerationFailed(format!("Failed to merge proofs: {}", e)));
+        }
+        let pczt = crate::shielded::prove_sapling(pczt)?;
+        prove_orchard(pczt)
+    }
+    #[cfg(not(feature = "sapling"))]
+    {
+        prove_orchard(pczt)
+    }
+}
+
+/// Proves PCZTs in parallel on one pool sized by `set_prover_threads`.
+///
+/// Each PCZT is one task. A task needing both Sapling and Orchard proofs also splits
+/// them (see `prove_bundles`), which rayon schedules on the same pool.
+pub(crate) fn prove_batch(pczts: Vec<Pczt>) -> Vec<Result<Pczt, ProverError>> {
+    use rayon::prelude::*;
+
+    with_prover_threads(|| {
+        pczts.into_par_iter()
+            .map(|pczt| if crate::is_transparent_only(&pczt) { Ok(pczt) } else { prove_bundles(pczt) })
+            .collect()
+    })
+}
+
+/// Number of threads proving may use, 0 for no limit (default 1 with the
+/// `low-memory` feature, unlimited otherwise)
+static PROVER_THREADS: std::sync::atomic::AtomicUsize =
+    std::sync::atomic::AtomicUsize::new(if cfg!(feature = "low-memory") { 1 } else { 0 });
+
+/// Sets how many threads proving may use (see `crate::set_prover_threads`)
+pub(crate) fn set_prover_threads(threads: usize) {
+    PROVER_THREADS.store(threads, std::sync::atomic::Ordering::Relaxed);
+}
+
+pub(crate) fn prover_threads() -> usize {
+    PROVER_THREADS.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// The pool proving runs on when its threads are limited, with its size. Built on
+/// first use and kept until `set_prover_threads` asks for a different size.
+static PROVER_POOL: Mutex<Option<(usize, Arc<rayon::ThreadPool>)>> = Mutex::new(None);
+
+/// Returns the pool of `threads` threads, building it if the cached one has another size
+fn prover_pool(threads: usize) -> Option<Arc<rayon::ThreadPool>> {
+    let mut slot = PROVER_POOL.lock().unwrap_or_else(|e| e.into_inner());
+    match slot.as_ref() {
+        Some((size, pool)) if *size == threads => Some(Arc::clone(pool)),
+        _ => {
+            let pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(threads).build().ok()?);
+            *slot = Some((threads, Arc::clone(&pool)));
+            Some(pool)
+        }
+    }
+}
+
+/// Runs `prove` on a thread pool sized by `set_prover_threads`
+pub(crate) fn with_prover_threads<T: Send>(prove: impl FnOnce() -> T + Send) -> T {
+    let pool = match prover_threads() {
+        0 => None,
+        threads => prover_pool(threads),
+    };
+    match pool {
+        Some(pool) => pool.install(prove),
+        None => prove(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prover_pool_is_reused() {
+        let pool = prover_pool(2).unwrap();
+        assert!(Arc::ptr_eq(&pool, &prover_pool(2).unwrap()));
+        assert_eq!(pool.current_num_threads(), 2);
+
+        let resized = prover_pool(3).unwrap();
+        assert_eq!(resized.current_num_threads(), 3);
+        assert!(!Arc::ptr_eq(&pool, &resized));
+    }
+}
@@ -0,0 +1,302 @@
+//! Long-running background prover
+//!
+//! A `ProverService` keeps the Orchard proving key resident and proves PCZTs
+//! submitted over a bounded in-process queue. A full queue blocks `submit` (or fails
+//! `try_submit`), which gives high-volume hosts natural backpressure.
+//!
+//! Each PCZT is queued in a priority lane. Workers always take the oldest PCZT of the
+//! highest non-empty lane, so an urgent withdrawal can jump ahead of a large
+//! scheduled batch.
+
+use std::collections::VecDeque;
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread::JoinHandle;
+
+use pczt::Pczt;
+
+use crate::error::ProverError;
+
+/// Called on a worker thread with the result of a proof
+pub type ProofCallback = Box<dyn FnOnce(Result<Pczt, ProverError>) + Send + 'static>;
+
+/// Identifies a queued PCZT, in submission order
+pub type JobId = u64;
+
+/// Queue lane for a PCZT
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProofPriority {
+    /// Scheduled work, e.g. consolidation batches
+    Low,
+    #[default]
+    Normal,
+    /// Urgent work, e.g. manual withdrawals
+    High,
+}
+
+impl ProofPriority {
+    /// All priorities, highest first
+    pub const ALL: [ProofPriority; 3] = [ProofPriority::High, ProofPriority::Normal, ProofPriority::Low];
+
+    fn lane(self) -> usize {
+        match self {
+            ProofPriority::High => 0,
+            ProofPriority::Normal => 1,
+            ProofPriority::Low => 2,
+        }
+    }
+}
+
+/// Progress of a submitted PCZT
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofStatus {
+    /// Waiting; `position` PCZTs will be taken before it (across all lanes)
+    Queued { priority: ProofPriority, position: usize },
+    /// A worker is proving it
+    Proving,
+    /// Proving is done; the result goes to its callback or ticket
+    Finished,
+}
+
+struct Job {
+    id: JobId,
+    pczt: Pczt,
+    callback: ProofCallback,
+}
+
+#[derive(Default)]
+struct QueueState {
+    lanes: [VecDeque<Job>; 3],
+    proving: Vec<JobId>,
+    next_id: JobId,
+    stopped: bool,
+}
+
+impl QueueState {
+    fn len(&self) -> usize {
+        self.lanes.iter().map(VecDeque::len).sum()
+    }
+
+    fn pop(&mut self) -> Option<Job> {
+        self.lanes.iter_mut().find_map(VecDeque::pop_front)
+    }
+}
+
+struct Queue {
+    state: Mutex<QueueState>,
+    not_empty: Condvar,
+    not_full: Condvar,
+    capacity: usize,
+}
+
+impl Queue {
+    fn lock(&self) -> MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Configuration for `ProverService::start`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ProverServiceConfig {
+    /// Number of worker threads proving concurrently
+    pub workers: usize,
+    /// Number of PCZTs that may wait in the queue (across all lanes)
+    pub queue_capacity: usize,
+}
+
+impl Default for ProverServiceConfig {
+    fn default() -> Self {
+        // Every queued PCZT is held in memory
+        let queue_capacity = if cfg!(feature = "low-memory") { 2 } else { 16 };
+        ProverServiceConfig { workers: 1, queue_capacity }
+    }
+}
+
+/// A proof that has been queued; wait on it for the proved PCZT
+pub struct ProofTicket {
+    id: JobId,
+    receiver: Receiver<Result<Pczt, ProverError>>,
+}
+
+impl ProofTicket {
+    /// The job ID, for `ProverService::status`
+    pub fn id(&self) -> JobId {
+        self.id
+    }
+
+    /// Blocks until the proof is done.
+    pub fn wait(self) -> Result<Pczt, ProverError> {
+        self.receiver.recv().unwrap_or(Err(ProverError::ServiceStopped))
+    }
+
+    /// Returns the result if the proof is done, or the ticket to wait on again.
+    pub fn try_wait(self) -> Result<Result<Pczt, ProverError>, ProofTicket> {
+        match self.receiver.try_recv() {
+            Ok(result) => Ok(result),
+            Err(mpsc::TryRecvError::Empty) => Err(self),
+            Err(mpsc::TryRecvError::Disconnected) => Ok(Err(ProverError::ServiceStopped)),
+        }
+    }
+}
+
+/// Background prover that owns the proving key and a queue of pending PCZTs
+pub struct ProverService {
+    queue: Arc<Queue>,
+    workers: Vec<JoinHandle<()>>,
+}
+
+impl ProverService {
+    /// Starts the worker threads.
+    ///
+    /// The Orchard proving key is built on a worker before the first job is taken,
+    /// so the first proof doesn't pay for it.
+    pub fn start(config: ProverServiceConfig) -> Self {
+        let queue = Arc::new(Queue {
+            state: Mutex::new(QueueState::default()),
+            not_empty: Condvar::new(),
+            not_full: Condvar::new(),
+            capacity: config.queue_capacity.max(1),
+        });
+
+        let workers = (0..config.workers.max(1))
+            .map(|_| {
+                let queue = Arc::clone(&queue);
+                std::thread::spawn(move || {
+                    // Without the orchard-prover feature there is no key to build
+                    let _ = crate::init_proving_keys();
+                    while let Some(job) = take_job(&queue) {
+                        let result = crate::prove_transaction(job.pczt);
+                        queue.lock().proving.retain(|id| *id != job.id);
+                        (job.callback)(result);
+                    }
+                })
+            })
+            .collect();
+
+        ProverService { queue, workers }
+    }
+
+    /// Queues a PCZT at normal priority, blocking while the queue is full.
+    pub fn submit(&self, pczt: Pczt) -> Result<ProofTicket, ProverError> {
+        self.submit_with_priority(pczt, ProofPriority::Normal)
+    }
+
+    /// Queues a PCZT in the given lane, blocking while the queue is full.
+    pub fn submit_with_priority(&self, pczt: Pczt, priority: ProofPriority) -> Result<ProofTicket, ProverError> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let id = self.submit_with_callback(pczt, priority, ticket_callback(sender))?;
+        Ok(ProofTicket { id, receiver })
+    }
+
+    /// Queues a PCZT at normal priority, failing with `QueueFull` instead of blocking.
+    pub fn try_submit(&self, pczt: Pczt) -> Result<ProofTicket, ProverError> {
+        let (sender, receiver) = mpsc::sync_channel(1);
+        let job = |id| Job { id, pczt, callback: ticket_callback(sender) };
+        let id = self.enqueue(job, ProofPriority::Normal, false)?;
+        Ok(ProofTicket { id, receiver })
+    }
+
+    /// Queues a PCZT and calls `callback` on a worker thread when it is proved,
+    /// blocking while the queue is full.
+    pub fn submit_with_callback(
+        &self,
+        pczt: Pczt,
+        priority: ProofPriority,
+        callback: ProofCallback,
+    ) -> Result<JobId, ProverError> {
+        self.enqueue(|id| Job { id, pczt, callback }, priority, true)
+    }
+
+    /// Number of PCZTs waiting in each lane, highest priority first (see `ProofPriority::ALL`)
+    pub fn queue_depth(&self) -> [usize; 3] {
+        let state = self.queue.lock();
+        ProofPriority::ALL.map(|priority| state.lanes[priority.lane()].len())
+    }
+
+    /// Progress of a submitted PCZT, or None if `id` was never issued
+    pub fn status(&self, id: JobId) -> Option<ProofStatus> {
+        let state = self.queue.lock();
+        if id >= state.next_id {
+            return None;
+        }
+        if state.proving.contains(&id) {
+            return Some(ProofStatus::Proving);
+        }
+
+        let mut position = 0;
+        for priority in ProofPriority::ALL {
+            let lane = &state.lanes[priority.lane()];
+            if let Some(index) = lane.iter().position(|job| job.id == id) {
+                return Some(ProofStatus::Queued { priority, position: position + index });
+            }
+            position += lane.len();
+        }
+        Some(ProofStatus::Finished)
+    }
+
+    /// Stops accepting work, finishes the queued PCZTs and joins the workers.
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn enqueue(
+        &self,
+        job: impl FnOnce(JobId) -> Job,
+        priority: ProofPriority,
+        block: bool,
+    ) -> Result<JobId, ProverError> {
+        let mut state = self.queue.lock();
+        while !state.stopped && state.len() >= self.queue.capacity {
+            if !block {
+                return Err(ProverError::QueueFull);
+            }
+            state = self.queue.not_full.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        if state.stopped {
+            return Err(ProverError::ServiceStopped);
+        }
+
+        let id = state.next_id;
+        state.next_id += 1;
+        state.lanes[priority.lane()].push_back(job(id));
+        self.queue.not_empty.notify_one();
+        Ok(id)
+    }
+
+    fn shutdown(&mut self) {
+        self.queue.lock().stopped = true;
+        self.queue.not_empty.notify_all();
+        self.queue.not_full.notify_all();
+        for worker in self.workers.drain(..) {
+            let _ = worker.join();
+        }
+    }
+}
+
+impl Drop for ProverService {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Waits for the next job; None once the service has stopped and the queue is drained
+fn take_job(queue: &Queue) -> Option<Job> {
+    let mut state = queue.lock();
+    loop {
+        if let Some(job) = state.pop() {
+            state.proving.push(job.id);
+            queue.not_full.notify_one();
+            return Some(job);
+        }
+        if state.stopped {
+            return None;
+        }
+        state = queue.not_empty.wait(state).unwrap_or_else(|e| e.into_inner());
+    }
+}
+
+fn ticket_callback(sender: mpsc::SyncSender<Result<Pczt, ProverError>>) -> ProofCallback {
+    Box::new(move |result| {
+        let _ = sender.send(result);
+    })
+}
@@ -0,0 +1,105 @@
+//! Input reservation tokens
+//!
+//! A wallet that locks its UTXOs while a proposal is out for signing needs a way to
+//! find those locks again after a crash. `reserve_inputs` locks a PCZT's inputs in
+//! the host's `ReservationStore` under a fresh token and stamps the token into the
+//! PCZT's global proprietary fields (`t2z.reservation`), where it survives
+//! serialization and every later role. An abandoned PCZT releases its inputs with
+//! `release_reservation`, and `reconcile` releases every lock whose PCZT is gone.
+
+use pczt::Pczt;
+use rand_core::{OsRng, RngCore};
+
+use crate::error::ReservationError;
+use crate::types::PcztUpdate;
+
+/// Global proprietary key the reservation token is stored under
+pub const RESERVATION_KEY: &str = "t2z.reservation";
+
+/// Identifies the UTXO locks taken for one PCZT
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReservationToken(pub [u8; 16]);
+
+impl ReservationToken {
+    /// A fresh random token
+    pub fn generate() -> Self {
+        let mut token = [0u8; 16];
+        OsRng.fill_bytes(&mut token);
+        ReservationToken(token)
+    }
+}
+
+/// The host's record of locked UTXOs, keyed by reservation token.
+///
+/// Outpoints are `(txid, vout)` with the txid in the byte order of
+/// `TransparentInput::txid`.
+pub trait ReservationStore {
+    /// Locks `outpoints` under `token`.
+    fn reserve(&self, token: ReservationToken, outpoints: &[([u8; 32], u32)]) -> Result<(), String>;
+
+    /// Unlocks everything held under `token`; releasing an unknown token is not an error.
+    fn release(&self, token: ReservationToken) -> Result<(), String>;
+
+    /// Every token that still holds locks.
+    fn tokens(&self) -> Result<Vec<ReservationToken>, String>;
+}
+
+/// Locks a PCZT's transparent inputs under a fresh token and stamps the token into it.
+///
+/// The token is stamped before anything is locked, so a failure leaves no lock behind.
+///
+/// # Returns
+/// * `Result<(Pczt, ReservationToken), ReservationError>` - The stamped PCZT and its token
+pub fn reserve_inputs(pczt: Pczt, store: &dyn ReservationStore) -> Result<(Pczt, ReservationToken), ReservationError> {
+    let token = ReservationToken::generate();
+    let outpoints: Vec<([u8; 32], u32)> = pczt.transparent().inputs().iter()
+        .map(|input| (*input.prevout_txid(), *input.prevout_index()))
+        .collect();
+
+    let pczt = stamp_reservation(pczt, token)?;
+    store.reserve(token, &outpoints).map_err(ReservationError::Store)?;
+    Ok((pczt, token))
+}
+
+/// Stamps `token` into a PCZT, replacing any earlier one.
+pub fn stamp_reservation(pczt: Pczt, token: ReservationToken) -> Result<Pczt, ReservationError> {
+    let update = PcztUpdate::GlobalProprietary { key: RESERVATION_KEY.to_string(), value: token.0.to_vec() };
+    Ok(crate::update_pczt(pczt, &[update])?)
+}
+
+/// The reservation token stamped into a PCZT, if any
+pub fn reservation_token(pczt: &Pczt) -> Option<ReservationToken> {
+    let value = pczt.global().proprietary().get(RESERVATION_KEY)?;
+    Some(ReservationToken(value.as_slice().try_into().ok()?))
+}
+
+/// Unlocks the inputs of an abandoned PCZT.
+///
+/// # Returns
+/// * `Result<ReservationToken, ReservationError>` - The released token, or `NoToken` if
+///   the PCZT was never reserved
+pub fn release_reservation(pczt: &Pczt, store: &dyn ReservationStore) -> Result<ReservationToken, ReservationError> {
+    let token = reservation_token(pczt).ok_or(ReservationError::NoToken)?;
+    store.release(token).map_err(ReservationError::Store)?;
+    Ok(token)
+}
+
+/// Releases every lock in `store` that none of the `outstanding` PCZTs carries.
+///
+/// Meant for startup after a crash: pass every PCZT still in flight, and the locks
+/// of proposals that were lost with the process are released.
+///
+/// # Returns
+/// * `Result<Vec<ReservationToken>, ReservationError>` - The tokens released
+pub fn reconcile(store: &dyn ReservationStore, outstanding: &[Pczt]) -> Result<Vec<ReservationToken>, ReservationError> {
+    let live: Vec<ReservationToken> = outstanding.iter().filter_map(reservation_token).collect();
+
+    let mut released = Vec::new();
+    for token in store.tokens().map_err(ReservationError::Store)? {
+        if !live.contains(&token) {
+            store.release(token).map_err(ReservationError::Store)?;
+            released.push(token);
+        }
+    }
+    Ok(released)
+}
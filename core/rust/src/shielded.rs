@@ -0,0 +1,462 @@
+//! Spending shielded notes
+//!
+//! Besides transparent inputs, a proposal may spend Orchard notes and, with the
+//! `sapling` feature, Sapling notes (e.g. to migrate Sapling funds to Orchard). The
+//! proposer supplies each note with its full viewing key and witness; the witnesses
+//! of a pool must all be taken at the same tree state, whose root becomes that
+//! pool's anchor.
+//!
+//! Orchard spends are authorized with `sign_orchard_spend`, or by a hardware signer
+//! from `orchard_signing_requests` and `append_orchard_signature`. Proving a Sapling
+//! spend needs the Sapling parameters from the `params::ParamsStore` and the spend's
+//! proof generation key, and each Sapling spend is signed with `sign_sapling_spend`.
+//! `migrate_sapling_to_orchard` builds the transactions that move a wallet's Sapling
+//! notes to its Orchard account.
+
+use pczt::Pczt;
+#[cfg(feature = "sapling")]
+use sapling::keys::{FullViewingKey, SpendAuthorizingKey};
+#[cfg(feature = "sapling")]
+use sapling::{Anchor, MerklePath, Node, Note, ProofGenerationKey};
+
+use crate::error::{SighashError, SignatureError};
+#[cfg(feature = "proposal")]
+use crate::error::ProposalError;
+#[cfg(feature = "sapling")]
+use crate::error::UpdateError;
+#[cfg(feature = "proposal")]
+use crate::types::ShieldedInput;
+#[cfg(all(feature = "sapling", feature = "proposal"))]
+use crate::types::{CoinSelection, Payment, TransactionRequest};
+
+/// An Orchard note to spend, with the witness of its commitment
+#[derive(Debug, Clone)]
+pub struct OrchardSpend {
+    /// Full viewing key of the key that received the note
+    pub fvk: orchard::keys::FullViewingKey,
+    pub note: orchard::Note,
+    /// Witness of the note commitment in the Orchard tree
+    pub merkle_path: orchard::tree::MerklePath,
+}
+
+impl OrchardSpend {
+    pub fn new(fvk: orchard::keys::FullViewingKey, note: orchard::Note, merkle_path: orchard::tree::MerklePath) -> Self {
+        OrchardSpend { fvk, note, merkle_path }
+    }
+
+    /// Note value in zatoshis
+    pub fn value(&self) -> u64 {
+        self.note.value().inner()
+    }
+
+    /// Root of the note commitment tree the witness was taken at
+    pub fn anchor(&self) -> orchard::Anchor {
+        self.merkle_path.root(self.note.commitment().into())
+    }
+}
+
+/// What a hardware signer needs to authorize one Orchard spend
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OrchardSigningRequest {
+    /// Index of the action in the PCZT's Orchard bundle
+    pub action_index: usize,
+    /// The transaction's shielded sighash, which every spend signs
+    pub sighash: [u8; 32],
+    /// Randomizer of the spend authorizing key (`rsk = ask + alpha`)
+    pub alpha: [u8; 32],
+    /// Value of the spent note in zatoshis, if the PCZT carries it
+    pub value: Option<u64>,
+}
+
+/// The Orchard spends among `inputs`, in order
+#[cfg(feature = "proposal")]
+pub(crate) fn orchard_spends(inputs: &[ShieldedInput]) -> Vec<&OrchardSpend> {
+    inputs.iter()
+        .filter_map(|input| match input {
+            ShieldedInput::Orchard(spend) => Some(spend),
+            #[cfg(feature = "sapling")]
+            ShieldedInput::Sapling(_) => None,
+        })
+        .collect()
+}
+
+/// The root shared by every witness of a pool, or None without spends
+#[cfg(feature = "proposal")]
+pub(crate) fn common_anchor<A: Copy + PartialEq>(
+    pool: &str,
+    roots: impl IntoIterator<Item = A>,
+) -> Result<Option<A>, ProposalError> {
+    let mut anchor = None;
+    for (index, root) in roots.into_iter().enumerate() {
+        match anchor {
+            None => anchor = Some(root),
+            Some(first) if first == root => {}
+            Some(_) => {
+                return Err(ProposalError::InvalidRequest(format!(
+                    "{} spend {}: witness is at a different tree state than spend 0", pool, index
+                )));
+            }
+        }
+    }
+    Ok(anchor)
+}
+
+/// Authorizes an Orchard spend with its spend authorizing key.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to sign
+/// * `action_index` - Index of the spend's action in the PCZT's Orchard bundle
+/// * `ask` - Spend authorizing key of the note's spending key
+pub fn sign_orchard_spend(
+    pczt: Pczt,
+    action_index: usize,
+    ask: &orchard::keys::SpendAuthorizingKey,
+) -> Result<Pczt, SignatureError> {
+    use pczt::roles::signer::{Error, Signer};
+
+    if action_index >= pczt.orchard().actions().len() {
+        return Err(SignatureError::InvalidInputIndex(action_index));
+    }
+
+    let mut signer = Signer::new(pczt)
+        .map_err(|_| SignatureError::InvalidFormat)?;
+
+    signer.sign_orchard(action_index, ask)
+        .map_err(|e| match e {
+            Error::InvalidIndex => SignatureError::InvalidInputIndex(action_index),
+            Error::OrchardSign(_) => SignatureError::KeyMismatch { index: action_index },
+            _ => SignatureError::InvalidFormat,
+        })?;
+
+    Ok(signer.finish())
+}
+
+/// Lists the Orchard spends that still need a signature, for an external signer.
+///
+/// Padding actions are signed when the PCZT is created, so only real spends show up.
+///
+/// # Returns
+/// * `Result<Vec<OrchardSigningRequest>, SighashError>` - One request per unsigned spend,
+///   in action order
+pub fn orchard_signing_requests(pczt: &Pczt) -> Result<Vec<OrchardSigningRequest>, SighashError> {
+    use pczt::roles::signer::Signer;
+
+    let unsigned: Vec<_> = pczt.orchard().actions().iter()
+        .enumerate()
+        .filter(|(_, action)| action.spend().spend_auth_sig().is_none())
+        .collect();
+    if unsigned.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let signer = Signer::new(pczt.clone())
+        .map_err(|e| SighashError::CalculationFailed(format!("Failed to create Signer: {:?}", e)))?;
+    let sighash = signer.shielded_sighash();
+
+    unsigned.into_iter()
+        .map(|(action_index, action)| {
+            Ok(OrchardSigningRequest {
+                action_index,
+                sighash,
+                alpha: (*action.spend().alpha()).ok_or(SighashError::MissingInputData)?,
+                value: *action.spend().value(),
+            })
+        })
+        .collect()
+}
+
+/// Adds an Orchard spend authorization signature made by an external signer.
+///
+/// The signature is checked against the action's randomized verification key.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to add the signature to
+/// * `action_index` - Index of the spend's action in the PCZT's Orchard bundle
+/// * `signature` - The 64-byte RedPallas signature over the shielded sighash
+pub fn append_orchard_signature(pczt: Pczt, action_index: usize, signature: [u8; 64]) -> Result<Pczt, SignatureError> {
+    use pczt::roles::signer::{Error, Signer};
+
+    if action_index >= pczt.orchard().actions().len() {
+        return Err(SignatureError::InvalidInputIndex(action_index));
+    }
+
+    let mut signer = Signer::new(pczt)
+        .map_err(|_| SignatureError::InvalidFormat)?;
+
+    signer.apply_orchard_signature(action_index, signature.into())
+        .map_err(|e| match e {
+            Error::InvalidIndex => SignatureError::InvalidInputIndex(action_index),
+            _ => SignatureError::VerificationFailed,
+        })?;
+
+    Ok(signer.finish())
+}
+
+/// `ParamsStore` key of the Sapling spend parameters
+#[cfg(feature = "sapling")]
+pub const SAPLING_SPEND_PARAMS: &str = "sapling-spend.params";
+
+/// `ParamsStore` key of the Sapling output parameters
+#[cfg(feature = "sapling")]
+pub const SAPLING_OUTPUT_PARAMS: &str = "sapling-output.params";
+
+/// A Sapling note to spend, with the witness of its commitment
+#[cfg(feature = "sapling")]
+#[derive(Debug, Clone)]
+pub struct SaplingSpend {
+    /// Full viewing key of the key that received the note
+    pub fvk: FullViewingKey,
+    pub note: Note,
+    /// Witness of the note commitment in the Sapling tree
+    pub merkle_path: MerklePath,
+    /// Lets `prove_transaction` prove the spend. It reveals the key that derives the
+    /// note's nullifier, so leave it out if the PCZT passes through parties that
+    /// shouldn't link the note, and have the prover add it with
+    /// `set_sapling_proof_generation_key` instead.
+    pub proof_generation_key: Option<ProofGenerationKey>,
+}
+
+#[cfg(feature = "sapling")]
+impl SaplingSpend {
+    pub fn new(fvk: FullViewingKey, note: Note, merkle_path: MerklePath) -> Self {
+        SaplingSpend { fvk, note, merkle_path, proof_generation_key: None }
+    }
+
+    pub fn with_proof_generation_key(mut self, key: ProofGenerationKey) -> Self {
+        self.proof_generation_key = Some(key);
+        self
+    }
+
+    /// Note value in zatoshis
+    pub fn value(&self) -> u64 {
+        self.note.value().inner()
+    }
+
+    /// Root of the note commitment tree the witness was taken at
+    pub fn anchor(&self) -> Anchor {
+        Anchor::from(self.merkle_path.root(Node::from_cmu(&self.note.cmu())))
+    }
+}
+
+/// The Sapling spends among `inputs`, in order
+#[cfg(all(feature = "sapling", feature = "proposal"))]
+pub(crate) fn sapling_spends(inputs: &[ShieldedInput]) -> Vec<&SaplingSpend> {
+    inputs.iter()
+        .filter_map(|input| match input {
+            ShieldedInput::Sapling(spend) => Some(spend),
+            ShieldedInput::Orchard(_) => None,
+        })
+        .collect()
+}
+
+/// Adds the proof generation key of a Sapling spend, which the prover needs.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to update
+/// * `index` - Index of the spend in the PCZT's Sapling bundle
+/// * `key` - Proof generation key of the note's spending key
+#[cfg(feature = "sapling")]
+pub fn set_sapling_proof_generation_key(
+    pczt: Pczt,
+    index: usize,
+    key: ProofGenerationKey,
+) -> Result<Pczt, UpdateError> {
+    use pczt::roles::updater::Updater;
+
+    if index >= pczt.sapling().spends().len() {
+        return Err(UpdateError::InvalidInputIndex(index));
+    }
+
+    let updater = Updater::new(pczt)
+        .update_sapling_with(|mut sapling| {
+            sapling.update_spend_with(index, |mut spend| spend.set_proof_generation_key(key))
+        })
+        .map_err(|e| UpdateError::Failed(format!("{:?}", e)))?;
+    Ok(updater.finish())
+}
+
+/// Authorizes a Sapling spend with its spend authorizing key.
+///
+/// This implements the Signer role for Sapling; the signature commits to the same
+/// sighash as the transparent inputs' signatures.
+///
+/// # Arguments
+/// * `pczt` - The PCZT to sign
+/// * `index` - Index of the spend in the PCZT's Sapling bundle
+/// * `ask` - Spend authorizing key of the note's spending key
+#[cfg(feature = "sapling")]
+pub fn sign_sapling_spend(pczt: Pczt, index: usize, ask: &SpendAuthorizingKey) -> Result<Pczt, SignatureError> {
+    use pczt::roles::signer::{Error, Signer};
+
+    if index >= pczt.sapling().spends().len() {
+        return Err(SignatureError::InvalidInputIndex(index));
+    }
+
+    let mut signer = Signer::new(pczt)
+        .map_err(|_| SignatureError::InvalidFormat)?;
+
+    signer.sign_sapling(index, ask)
+        .map_err(|e| match e {
+            Error::InvalidIndex => SignatureError::InvalidInputIndex(index),
+            Error::SaplingSign(_) => SignatureError::KeyMismatch { index },
+            _ => SignatureError::InvalidFormat,
+        })?;
+
+    Ok(signer.finish())
+}
+
+/// Most Sapling notes `migrate_sapling_to_orchard` spends in one transaction, which
+/// keeps each migration transaction well under the standard size limit
+#[cfg(all(feature = "sapling", feature = "proposal"))]
+pub const MIGRATION_NOTES_PER_TRANSACTION: usize = 100;
+
+/// The default Orchard address of a unified full viewing key, encoded as an
+/// Orchard-only unified address
+#[cfg(all(feature = "sapling", feature = "proposal"))]
+fn ufvk_orchard_address(
+    ufvk: &str,
+    network: zcash_protocol::consensus::NetworkType,
+) -> Result<String, ProposalError> {
+    use zcash_address::unified::{self, Container, Encoding};
+
+    let (ufvk_network, ufvk) = unified::Ufvk::decode(ufvk)
+        .map_err(|e| ProposalError::InvalidRequest(format!("Invalid UFVK: {}", e)))?;
+    if ufvk_network != network {
+        return Err(ProposalError::InvalidRequest(
+            "UFVK is for a different network than the request".to_string()
+        ));
+    }
+
+    let fvk = ufvk.items().into_iter()
+        .find_map(|item| match item {
+            unified::Fvk::Orchard(bytes) => orchard::keys::FullViewingKey::from_bytes(&bytes),
+            _ => None,
+        })
+        .ok_or_else(|| ProposalError::InvalidRequest("UFVK has no valid Orchard key".to_string()))?;
+    let address = fvk.address_at(0u32, orchard::keys::Scope::External);
+
+    unified::Address::try_from_items(vec![unified::Receiver::Orchard(address.to_raw_address_bytes())])
+        .map(|ua| ua.encode(&network))
+        .map_err(|e| ProposalError::InvalidRequest(format!("Failed to encode Orchard address: {}", e)))
+}
+
+/// The transactions `migrate_sapling_to_orchard` built, and the notes it left behind
+#[cfg(all(feature = "sapling", feature = "proposal"))]
+#[derive(Debug, Clone)]
+pub struct SaplingMigration {
+    /// One PCZT per transaction, in note order
+    pub pczts: Vec<Pczt>,
+    /// Indices into the migrated notes of those left in Sapling, because the notes of
+    /// their transaction didn't cover its fee
+    pub skipped_notes: Vec<usize>,
+}
+
+/// Builds the transactions that move Sapling notes to the Orchard account of `ufvk`.
+///
+/// The notes are spent in order, `MIGRATION_NOTES_PER_TRANSACTION` at a time. Each
+/// transaction sends everything but its ZIP-317 fee to the default Orchard address of
+/// `ufvk`, so there is no change. A transaction whose notes don't cover its fee is
+/// skipped, and its notes are reported in `SaplingMigration::skipped_notes`. The notes
+/// of one transaction must be witnessed at the same tree state, as in
+/// `propose_shielded_transaction`.
+///
+/// # Arguments
+/// * `notes` - The Sapling notes to migrate
+/// * `ufvk` - Encoded unified full viewing key of the receiving account; it needs an
+///   Orchard key and must be for the request's network
+/// * `template` - Request whose settings (network, heights, expiry) every transaction
+///   uses; it must not have payments
+///
+/// # Returns
+/// * `Result<SaplingMigration, ProposalError>` - The PCZTs and skipped notes,
+///   `InsufficientFunds` if no transaction's notes cover its fee, or `InvalidRequest`
+#[cfg(all(feature = "sapling", feature = "proposal"))]
+pub fn migrate_sapling_to_orchard(
+    notes: &[SaplingSpend],
+    ufvk: &str,
+    template: TransactionRequest,
+) -> Result<SaplingMigration, ProposalError> {
+    if notes.is_empty() {
+        return Err(ProposalError::NoInputs);
+    }
+    if !template.payments.is_empty() {
+        return Err(ProposalError::InvalidRequest(
+            "Migration requests pay the UFVK's Orchard address; remove the payments".to_string()
+        ));
+    }
+    template.fee_policy.validate().map_err(ProposalError::InvalidRequest)?;
+    let address = ufvk_orchard_address(ufvk, template.effective_network().network_type())?;
+
+    let mut migration = SaplingMigration { pczts: Vec::new(), skipped_notes: Vec::new() };
+    let mut uneconomic = None;
+    for (index, chunk) in notes.chunks(MIGRATION_NOTES_PER_TRANSACTION).enumerate() {
+        let total = chunk.iter()
+            .try_fold(0u64, |total, note| total.checked_add(note.value()))
+            .ok_or_else(|| ProposalError::FeeCalculation("Note total overflows".to_string()))?;
+        // Sapling spends (padded outputs) and one Orchard output
+        let spends = crate::ShieldedSpends { sapling: chunk.len(), orchard: 0 };
+        let fee = template.fee_policy.fee(crate::zip317_fee(0, &[], spends, 1));
+        if total <= fee {
+            let first = index * MIGRATION_NOTES_PER_TRANSACTION;
+            migration.skipped_notes.extend(first..first + chunk.len());
+            uneconomic.get_or_insert(ProposalError::InsufficientFunds { available: total, required: fee, fee });
+            continue;
+        }
+
+        let request = TransactionRequest {
+            payments: vec![Payment::new(address.clone(), total - fee)],
+            coin_selection: CoinSelection::All,
+            sweep: false,
+            ..template.clone()
+        };
+        let inputs: Vec<ShieldedInput> = chunk.iter().cloned().map(ShieldedInput::Sapling).collect();
+        migration.pczts.push(crate::propose_shielded_transaction(&[], &inputs, request, None)?);
+    }
+
+    match uneconomic {
+        Some(error) if migration.pczts.is_empty() => Err(error),
+        _ => Ok(migration),
+    }
+}
+
+/// Loads the Sapling parameters from the installed `ParamsStore`
+#[cfg(all(feature = "sapling", feature = "orchard-prover"))]
+fn sapling_parameters() -> Result<(sapling::circuit::SpendParameters, sapling::circuit::OutputParameters), String> {
+    let store = crate::params::params_store();
+    let load = |key: &str| store.get(key)?.ok_or_else(|| format!("{} is not in the params store", key));
+
+    let spend = sapling::circuit::SpendParameters::read(&load(SAPLING_SPEND_PARAMS)?[..], false)
+        .map_err(|e| format!("Invalid {}: {}", SAPLING_SPEND_PARAMS, e))?;
+    let output = sapling::circuit::OutputParameters::read(&load(SAPLING_OUTPUT_PARAMS)?[..], false)
+        .map_err(|e| format!("Invalid {}: {}", SAPLING_OUTPUT_PARAMS, e))?;
+    Ok((spend, output))
+}
+
+/// Proves a PCZT's Sapling spends and outputs, if it has any
+#[cfg(all(feature = "sapling", feature = "orchard-prover"))]
+pub(crate) fn prove_sapling(pczt: Pczt) -> Result<Pczt, crate::error::ProverError> {
+    use crate::error::ProverError;
+    use pczt::roles::prover::Prover;
+
+    let prover = Prover::new(pczt);
+    if !prover.requires_sapling_proofs() {
+        return Ok(prover.finish());
+    }
+
+    let (spend, output) = sapling_parameters().map_err(|_| ProverError::ProvingKeyUnavailable)?;
+    let prover = prover.create_sapling_proofs(&spend, &output)
+        .map_err(|e| ProverError::ProofGenerationFailed(format!("Sapling: {:?}", e)))?;
+    Ok(prover.finish())
+}
+
+/// Verifying keys for extracting a PCZT with a Sapling bundle, or None without one
+#[cfg(all(feature = "sapling", feature = "orchard-prover"))]
+pub(crate) fn sapling_verifying_keys(
+    pczt: &Pczt,
+) -> Result<Option<(sapling::circuit::PreparedSpendVerifyingKey, sapling::circuit::PreparedOutputVerifyingKey)>, String> {
+    if pczt.sapling().spends().is_empty() && pczt.sapling().outputs().is_empty() {
+        return Ok(None);
+    }
+    let (spend, output) = sapling_parameters()?;
+    Ok(Some((spend.prepared_verifying_key(), output.prepared_verifying_key())))
+}
@@ -0,0 +1,107 @@
+//! Deterministic funding fixtures for downstream test suites (requires the `testing` feature)
+//!
+//! These mirror the crate's own test fixtures, so SDKs built on t2z can run the full
+//! propose → prove → sign → finalize pipeline (e.g. against a regtest node) without
+//! copying internal test code. Keys are derived from public seeds: never fund them
+//! on mainnet.
+
+use pczt::Pczt;
+use zcash_address::ZcashAddress;
+use zcash_protocol::consensus::NetworkType;
+
+use crate::types::{serialize_transparent_inputs, TransparentInput};
+
+/// A deterministic secp256k1 key pair for tests
+#[derive(Debug, Clone, Copy)]
+pub struct TestKey {
+    pub secret_key: secp256k1::SecretKey,
+    pub pubkey: secp256k1::PublicKey,
+}
+
+impl TestKey {
+    /// Derives a key from a seed: SHA-256("t2z testing key" || seed as u32 LE)
+    pub fn from_seed(seed: u32) -> Self {
+        use sha2::{Digest, Sha256};
+
+        let mut hasher = Sha256::new();
+        hasher.update(b"t2z testing key");
+        hasher.update(seed.to_le_bytes());
+        let bytes: [u8; 32] = hasher.finalize().into();
+        Self::from_secret_bytes(bytes)
+    }
+
+    /// The key used by the crate's own test fixtures (secret key = [1u8; 32])
+    pub fn fixture() -> Self {
+        Self::from_secret_bytes([1u8; 32])
+    }
+
+    fn from_secret_bytes(bytes: [u8; 32]) -> Self {
+        let secp = secp256k1::Secp256k1::new();
+        let secret_key = secp256k1::SecretKey::from_slice(&bytes).expect("valid secret key");
+        let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secret_key);
+        Self { secret_key, pubkey }
+    }
+
+    /// The P2PKH script_pubkey paying this key (raw, no length prefix)
+    pub fn p2pkh_script(&self) -> Vec<u8> {
+        let mut script = vec![0x76, 0xa9, 0x14];
+        script.extend_from_slice(&crate::hash160(&self.pubkey.serialize()));
+        script.extend_from_slice(&[0x88, 0xac]);
+        script
+    }
+
+    /// The transparent P2PKH address of this key. Regtest uses the testnet encoding.
+    pub fn address(&self, network: NetworkType) -> String {
+        ZcashAddress::from_transparent_p2pkh(network, crate::hash160(&self.pubkey.serialize())).encode()
+    }
+}
+
+/// Builds one UTXO per amount, all owned by `key` and spending consecutive outputs
+/// of a deterministic txid (SHA-256 of "t2z testing funding" and the key's pubkey).
+pub fn funded_inputs(key: &TestKey, amounts: &[u64]) -> Vec<TransparentInput> {
+    use sha2::{Digest, Sha256};
+
+    let mut hasher = Sha256::new();
+    hasher.update(b"t2z testing funding");
+    hasher.update(key.pubkey.serialize());
+    let txid: [u8; 32] = hasher.finalize().into();
+
+    let script_pubkey = key.p2pkh_script();
+    amounts.iter()
+        .enumerate()
+        .map(|(vout, &amount)| TransparentInput {
+            pubkey: key.pubkey,
+            txid,
+            vout: vout as u32,
+            amount,
+            script_pubkey: script_pubkey.clone(),
+            height: None,
+            redeem_script: None,
+            derivation_path: None,
+        })
+        .collect()
+}
+
+/// Like `funded_inputs`, serialized for `propose_transaction`
+pub fn funded_inputs_bytes(key: &TestKey, amounts: &[u64]) -> Vec<u8> {
+    serialize_transparent_inputs(&funded_inputs(key, amounts)).expect("funded inputs serialize")
+}
+
+/// The coinbase script_pubkey a regtest node should mine to so that `key` can spend
+/// the rewards (e.g. zebrad's `mining.miner_address` set to `key.address(NetworkType::Regtest)`).
+pub fn regtest_coinbase_script(key: &TestKey) -> Vec<u8> {
+    key.p2pkh_script()
+}
+
+/// Signs every transparent input of `pczt` with `key`
+pub fn sign_all_inputs(pczt: Pczt, key: &TestKey) -> Result<Pczt, String> {
+    use pczt::roles::signer::Signer;
+
+    let num_inputs = pczt.transparent().inputs().len();
+    let mut signer = Signer::new(pczt).map_err(|e| format!("Failed to create signer: {:?}", e))?;
+    for index in 0..num_inputs {
+        signer.sign_transparent(index, &key.secret_key)
+            .map_err(|e| format!("Failed to sign input {}: {:?}", index, e))?;
+    }
+    Ok(signer.finish())
+}
@@ -2,9 +2,12 @@ use serde::{Deserialize, Serialize};
 use zcash_transparent::bundle::{OutPoint, TxOut};
 use zcash_transparent::address::Script;
 use zcash_protocol::value::Zatoshis;
-use zcash_address::{ZcashAddress, TryFromAddress, ConversionError, unified};
+use zcash_address::{TryFromAddress, ConversionError, unified};
 use zcash_protocol::consensus::NetworkType;
 
+use crate::address_cache::ParsedAddress;
+use crate::error::{MemoError, ParseError, VerificationFailure};
+
 /// A signature hash used for signing transaction inputs
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct SigHash(pub [u8; 32]);
@@ -36,7 +39,8 @@ impl TryFromAddress for UnifiedAddressWrapper {
 /// A transparent UTXO input to be spent
 #[derive(Debug, Clone)]
 pub struct TransparentInput {
-    /// The compressed public key for this input (33 bytes)
+    /// The compressed public key for this input (33 bytes). For a P2SH input, any one
+    /// of the redeem script's keys.
     pub pubkey: secp256k1::PublicKey,
     /// The transaction ID of the UTXO being spent (32 bytes)
     pub txid: [u8; 32],
@@ -46,9 +50,22 @@ pub struct TransparentInput {
     pub amount: u64,
     /// The script pubkey of the UTXO being spent
     pub script_pubkey: Vec<u8>,
+    /// Optional height of the block that mined this UTXO (None = unknown)
+    pub height: Option<u32>,
+    /// Redeem script of a P2SH multisig UTXO (None = P2PKH)
+    pub redeem_script: Option<Vec<u8>>,
+    /// BIP-32 derivation of the key that signs this input. Proposals record it in the
+    /// PCZT, so hardware signers can find the key from the PCZT alone.
+    pub derivation_path: Option<KeyDerivation>,
 }
 
 impl TransparentInput {
+    /// Number of confirmations this UTXO will have when mined in a block at
+    /// `target_height` (the height of the next block), or None if its height is unknown
+    pub fn confirmations(&self, target_height: u32) -> Option<u32> {
+        self.height.map(|height| target_height.saturating_sub(height))
+    }
+
     /// Convert to OutPoint for use with the Builder
     pub fn outpoint(&self) -> OutPoint {
         OutPoint::new(self.txid, self.vout)
@@ -59,23 +76,163 @@ impl TransparentInput {
         let value = Zatoshis::from_u64(self.amount)
             .map_err(|_| "Invalid amount")?;
 
-        // The script_pubkey is stored as raw bytes (no CompactSize prefix)
-        // We need to wrap it with a length prefix for Script::read()
-        use zcash_encoding::CompactSize;
-        let mut script_with_prefix = Vec::new();
-        CompactSize::write(&mut script_with_prefix, self.script_pubkey.len())
-            .map_err(|_| "Failed to write script length")?;
-        script_with_prefix.extend_from_slice(&self.script_pubkey);
-
-        let script = Script::read(&script_with_prefix[..])
+        let script = script_from_raw(&self.script_pubkey)
             .map_err(|_| "Invalid script")?;
         Ok(TxOut::new(value, script))
     }
 }
 
+/// Builds a `Script` from raw script bytes (no CompactSize length prefix)
+pub fn script_from_raw(raw: &[u8]) -> std::io::Result<Script> {
+    use zcash_encoding::CompactSize;
+
+    // Script::read() expects the length-prefixed wire form
+    let mut script_with_prefix = Vec::with_capacity(raw.len() + 9);
+    CompactSize::write(&mut script_with_prefix, raw.len())?;
+    script_with_prefix.extend_from_slice(raw);
+    Script::read(&script_with_prefix[..])
+}
+
+/// Returns the raw bytes of a `Script`, without the CompactSize length prefix
+///
+/// The prefix is decoded rather than assumed to be one byte, so scripts of 253
+/// bytes or more (3- and 5-byte prefixes) come back intact.
+pub fn script_to_raw(script: &Script) -> std::io::Result<Vec<u8>> {
+    use zcash_encoding::CompactSize;
+
+    let mut serialized = Vec::new();
+    script.write(&mut serialized)?;
+
+    let mut reader = &serialized[..];
+    let len = CompactSize::read(&mut reader)? as usize;
+    if reader.len() != len {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "Script length prefix does not match its contents",
+        ));
+    }
+    Ok(reader.to_vec())
+}
+
+/// A transparent outpoint being spent, as passed to a `UtxoChecker`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputOutpoint {
+    /// The transaction ID of the UTXO (internal byte order)
+    pub txid: [u8; 32],
+    /// The output index in that transaction
+    pub vout: u32,
+    /// The UTXO value in zatoshis
+    pub value: u64,
+    /// The UTXO's script pubkey
+    pub script_pubkey: Vec<u8>,
+}
+
+/// An unspent output paying to an address, as returned by a `UtxoSource`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressUtxo {
+    /// The address the output pays to
+    pub address: String,
+    /// The transaction ID of the UTXO (internal byte order)
+    pub txid: [u8; 32],
+    /// The output index in that transaction
+    pub vout: u32,
+    /// The UTXO value in zatoshis
+    pub amount: u64,
+    /// Height of the block that mined the UTXO, if known
+    pub height: Option<u32>,
+}
+
+/// A UTXO found by `xpub::discover_utxos`, with the key path that spends it
+#[derive(Debug, Clone)]
+pub struct DiscoveredInput {
+    /// The input, ready for `serialize_transparent_inputs`
+    pub input: TransparentInput,
+    /// Non-hardened path from the account key, `[0, i]` (external) or `[1, i]` (change)
+    pub path: Vec<u32>,
+}
+
+impl DiscoveredInput {
+    /// Full derivation of the input's key for `PcztUpdate::InputDerivation`, given the
+    /// wallet's seed fingerprint and the account key's own path from the seed
+    /// (e.g. `m/44'/133'/0'`).
+    pub fn key_derivation(&self, seed_fingerprint: [u8; 32], account_path: &[u32]) -> KeyDerivation {
+        KeyDerivation {
+            pubkey: self.input.pubkey.serialize(),
+            seed_fingerprint,
+            path: account_path.iter().chain(&self.path).copied().collect(),
+        }
+    }
+}
+
+/// A shielded note for `propose_shielded_transaction` to spend
+#[derive(Debug, Clone)]
+pub enum ShieldedInput {
+    /// An Orchard note with its witness
+    Orchard(crate::shielded::OrchardSpend),
+    /// A Sapling note with its witness (requires the `sapling` feature)
+    #[cfg(feature = "sapling")]
+    Sapling(crate::shielded::SaplingSpend),
+}
+
+impl ShieldedInput {
+    /// Note value in zatoshis
+    pub fn value(&self) -> u64 {
+        match *self {
+            ShieldedInput::Orchard(ref spend) => spend.value(),
+            #[cfg(feature = "sapling")]
+            ShieldedInput::Sapling(ref spend) => spend.value(),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for TransparentInput {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        // Derive the pubkey from an arbitrary secret so it is always a valid point
+        let secret: [u8; 32] = u.arbitrary()?;
+        let secret_key = secp256k1::SecretKey::from_slice(&secret)
+            .map_err(|_| arbitrary::Error::IncorrectFormat)?;
+        let pubkey = secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::signing_only(), &secret_key);
+
+        // Script lengths are u16 on the wire
+        let script_len = u.int_in_range(0..=u16::MAX as usize)?.min(u.len());
+        Ok(Self {
+            pubkey,
+            txid: u.arbitrary()?,
+            vout: u.arbitrary()?,
+            amount: u.arbitrary()?,
+            script_pubkey: u.bytes(script_len)?.to_vec(),
+            height: u.arbitrary()?,
+            redeem_script: None,
+            derivation_path: None,
+        })
+    }
+}
+
+/// Marker in the first two bytes of the versioned (v2+) input format.
+/// A v1 header announcing 65535 inputs can't describe a valid transaction,
+/// so the two formats can't be confused.
+pub const INPUTS_FORMAT_MARKER: u16 = 0xFFFF;
+
+/// Version 2 of the input format (adds per-input optional fields)
+pub const INPUTS_FORMAT_V2: u8 = 2;
+
+/// v2 optional field flag: the UTXO's block height follows
+pub const INPUT_FLAG_HEIGHT: u8 = 0x02;
+
+/// v2 optional field flag: a P2SH redeem script follows
+pub const INPUT_FLAG_REDEEM_SCRIPT: u8 = 0x04;
+
+/// v2 optional field flag: a BIP-32 derivation of the signing key follows
+pub const INPUT_FLAG_DERIVATION: u8 = 0x08;
+
+/// All optional field flags understood by this version. 0x01 is reserved for a
+/// sequence number: the transaction builder only emits final sequence numbers.
+const INPUT_FLAGS_KNOWN: u8 = INPUT_FLAG_HEIGHT | INPUT_FLAG_REDEEM_SCRIPT | INPUT_FLAG_DERIVATION;
+
 /// Parse transparent inputs from the serialized format
 ///
-/// Format:
+/// Format v1:
 /// - [num_inputs: 2 bytes (u16 LE)]
 /// - For each input:
 ///   - [pubkey: 33 bytes]
@@ -84,6 +241,18 @@ impl TransparentInput {
 ///   - [amount: 8 bytes (u64 LE)]
 ///   - [script_len: 2 bytes (u16 LE)]
 ///   - [script: script_len bytes]
+///
+/// Format v2:
+/// - [marker: 2 bytes = 0xFFFF]
+/// - [version: 1 byte = 2]
+/// - [num_inputs: 2 bytes (u16 LE)]
+/// - For each input:
+///   - the v1 input fields, followed by
+///   - [flags: 1 byte] - which optional fields follow (0x01 is reserved)
+///   - [height: 4 bytes (u32 LE)] - if flags & 0x02
+///   - [redeem_script_len: 2 bytes (u16 LE)][redeem_script] - if flags & 0x04
+///   - [pubkey: 33 bytes][seed_fingerprint: 32 bytes][path_len: 1 byte]
+///     [path: path_len × 4 bytes (u32 LE)] - if flags & 0x08
 pub fn parse_transparent_inputs(data: &[u8]) -> Result<Vec<TransparentInput>, String> {
     if data.is_empty() {
         return Ok(Vec::new());
@@ -93,9 +262,21 @@ pub fn parse_transparent_inputs(data: &[u8]) -> Result<Vec<TransparentInput>, St
         return Err("Input data too short for header".to_string());
     }
 
-    let num_inputs = u16::from_le_bytes([data[0], data[1]]) as usize;
+    let header = u16::from_le_bytes([data[0], data[1]]);
+    let (version, num_inputs, mut offset) = if header == INPUTS_FORMAT_MARKER {
+        if data.len() < 5 {
+            return Err("Input data too short for v2 header".to_string());
+        }
+        let version = data[2];
+        if version != INPUTS_FORMAT_V2 {
+            return Err(format!("Unsupported input format version: {}", version));
+        }
+        (version, u16::from_le_bytes([data[3], data[4]]) as usize, 5)
+    } else {
+        (1, header as usize, 2)
+    };
+
     let mut inputs = Vec::with_capacity(num_inputs);
-    let mut offset = 2;
 
     for i in 0..num_inputs {
         // Read pubkey (33 bytes)
@@ -149,27 +330,179 @@ pub fn parse_transparent_inputs(data: &[u8]) -> Result<Vec<TransparentInput>, St
         let script_pubkey = data[offset..offset + script_len].to_vec();
         offset += script_len;
 
+        // Read v2 optional fields
+        let mut height = None;
+        let mut redeem_script = None;
+        let mut derivation_path = None;
+        if version >= INPUTS_FORMAT_V2 {
+            if offset + 1 > data.len() {
+                return Err(format!("Input {} truncated at flags", i));
+            }
+            let flags = data[offset];
+            offset += 1;
+
+            if flags & !INPUT_FLAGS_KNOWN != 0 {
+                return Err(format!("Input {} has unknown flags: {:#04x}", i, flags));
+            }
+
+            if flags & INPUT_FLAG_HEIGHT != 0 {
+                if offset + 4 > data.len() {
+                    return Err(format!("Input {} truncated at height", i));
+                }
+                height = Some(u32::from_le_bytes([data[offset], data[offset + 1], data[offset + 2], data[offset + 3]]));
+                offset += 4;
+            }
+
+            if flags & INPUT_FLAG_REDEEM_SCRIPT != 0 {
+                if offset + 2 > data.len() {
+                    return Err(format!("Input {} truncated at redeem script length", i));
+                }
+                let len = u16::from_le_bytes([data[offset], data[offset + 1]]) as usize;
+                offset += 2;
+                if offset + len > data.len() {
+                    return Err(format!("Input {} truncated at redeem script", i));
+                }
+                redeem_script = Some(data[offset..offset + len].to_vec());
+                offset += len;
+            }
+
+            if flags & INPUT_FLAG_DERIVATION != 0 {
+                if offset + 33 + 32 + 1 > data.len() {
+                    return Err(format!("Input {} truncated at derivation", i));
+                }
+                let pubkey: [u8; 33] = data[offset..offset + 33].try_into().expect("length checked");
+                let seed_fingerprint: [u8; 32] = data[offset + 33..offset + 65].try_into().expect("length checked");
+                let path_len = data[offset + 65] as usize;
+                offset += 66;
+                if offset + 4 * path_len > data.len() {
+                    return Err(format!("Input {} truncated at derivation path", i));
+                }
+                let path = data[offset..offset + 4 * path_len]
+                    .chunks_exact(4)
+                    .map(|index| u32::from_le_bytes([index[0], index[1], index[2], index[3]]))
+                    .collect();
+                offset += 4 * path_len;
+                derivation_path = Some(KeyDerivation { pubkey, seed_fingerprint, path });
+            }
+        }
+
         inputs.push(TransparentInput {
             pubkey,
             txid,
             vout,
             amount,
             script_pubkey,
+            height,
+            redeem_script,
+            derivation_path,
         });
     }
 
     Ok(inputs)
 }
 
+/// One UTXO of a `parse_transparent_inputs_json` array
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransparentInputJson {
+    /// Txid in display byte order, as nodes and explorers print it
+    #[serde(default)]
+    txid: Option<String>,
+    /// Txid in internal (little-endian) byte order, as it is serialized in transactions
+    #[serde(default, rename = "txidLE")]
+    txid_le: Option<String>,
+    vout: u32,
+    amount_zat: u64,
+    script_pub_key: String,
+    pubkey: String,
+    #[serde(default)]
+    height: Option<u32>,
+    #[serde(default)]
+    redeem_script: Option<String>,
+}
+
+/// Parse transparent inputs from a JSON array, for callers that can't easily build
+/// the binary format
+///
+/// Each element is `{"txid", "vout", "amountZat", "scriptPubKey", "pubkey"}` with
+/// hex-encoded byte strings, plus optional `height` and, for P2SH multisig
+/// UTXOs, `redeemScript`. The txid is read
+/// in display (big-endian) byte order; give it as `txidLE` instead to pass the
+/// internal little-endian order. Unknown fields are ignored.
+pub fn parse_transparent_inputs_json(json: &str) -> Result<Vec<TransparentInput>, String> {
+    let inputs: Vec<TransparentInputJson> = serde_json::from_str(json)
+        .map_err(|e| format!("Invalid inputs JSON: {}", e))?;
+
+    let hex = |i: usize, field: &str, value: &str| {
+        crate::json::hex_decode(value).map_err(|e| format!("Input {}: invalid {}: {}", i, field, e))
+    };
+
+    inputs.into_iter()
+        .enumerate()
+        .map(|(i, input)| {
+            let (txid_hex, display_order) = match (&input.txid, &input.txid_le) {
+                (Some(txid), None) => (txid, true),
+                (None, Some(txid)) => (txid, false),
+                _ => return Err(format!("Input {}: give exactly one of txid and txidLE", i)),
+            };
+            let mut txid: [u8; 32] = hex(i, "txid", txid_hex)?
+                .try_into()
+                .map_err(|_| format!("Input {}: txid must be 32 bytes", i))?;
+            if display_order {
+                txid.reverse();
+            }
+
+            let pubkey = secp256k1::PublicKey::from_slice(&hex(i, "pubkey", &input.pubkey)?)
+                .map_err(|e| format!("Input {}: invalid pubkey: {}", i, e))?;
+
+            Ok(TransparentInput {
+                pubkey,
+                txid,
+                vout: input.vout,
+                amount: input.amount_zat,
+                script_pubkey: hex(i, "scriptPubKey", &input.script_pub_key)?,
+                height: input.height,
+                redeem_script: input.redeem_script
+                    .map(|script| hex(i, "redeemScript", &script))
+                    .transpose()?,
+                derivation_path: None,
+            })
+        })
+        .collect()
+}
+
+/// Encodes a length in the integer type the binary input format gives it
+fn encoded_len<T: TryFrom<usize>>(len: usize, what: &str) -> Result<T, ParseError> {
+    T::try_from(len).map_err(|_| ParseError::InvalidFormat(format!("{} is too long to encode ({})", what, len)))
+}
+
 /// Serialize transparent inputs to the binary format
 ///
 /// This is primarily for testing and for users who want to construct
-/// inputs programmatically.
-pub fn serialize_transparent_inputs(inputs: &[TransparentInput]) -> Vec<u8> {
+/// inputs programmatically. The v1 format is used unless an input carries
+/// an optional field, in which case the v2 format is used. Fails with
+/// `InvalidFormat` if there are too many inputs, or a script or derivation path is
+/// too long, for the format.
+pub fn serialize_transparent_inputs(inputs: &[TransparentInput]) -> Result<Vec<u8>, ParseError> {
     let mut data = Vec::new();
 
-    // Write number of inputs (u16 LE)
-    let num_inputs = inputs.len() as u16;
+    let use_v2 = inputs.iter()
+        .any(|input| {
+            input.height.is_some()
+                || input.redeem_script.is_some()
+                || input.derivation_path.is_some()
+        });
+
+    if use_v2 {
+        data.extend_from_slice(&INPUTS_FORMAT_MARKER.to_le_bytes());
+        data.push(INPUTS_FORMAT_V2);
+    }
+
+    // Write number of inputs (u16 LE). A v1 count of 0xFFFF would read as the v2 marker.
+    let num_inputs: u16 = encoded_len(inputs.len(), "Input list")?;
+    if !use_v2 && num_inputs == INPUTS_FORMAT_MARKER {
+        return Err(ParseError::InvalidFormat(format!("Input list is too long to encode ({})", inputs.len())));
+    }
     data.extend_from_slice(&num_inputs.to_le_bytes());
 
     for input in inputs {
@@ -186,18 +519,74 @@ pub fn serialize_transparent_inputs(inputs: &[TransparentInput]) -> Vec<u8> {
         data.extend_from_slice(&input.amount.to_le_bytes());
 
         // Write script length (u16 LE)
-        let script_len = input.script_pubkey.len() as u16;
+        let script_len: u16 = encoded_len(input.script_pubkey.len(), "Script")?;
         data.extend_from_slice(&script_len.to_le_bytes());
 
         // Write script
         data.extend_from_slice(&input.script_pubkey);
+
+        if use_v2 {
+            // Write flags and optional fields
+            let mut flags = 0u8;
+            if input.height.is_some() {
+                flags |= INPUT_FLAG_HEIGHT;
+            }
+            if input.redeem_script.is_some() {
+                flags |= INPUT_FLAG_REDEEM_SCRIPT;
+            }
+            if input.derivation_path.is_some() {
+                flags |= INPUT_FLAG_DERIVATION;
+            }
+            data.push(flags);
+
+            if let Some(height) = input.height {
+                data.extend_from_slice(&height.to_le_bytes());
+            }
+            if let Some(redeem_script) = &input.redeem_script {
+                let redeem_script_len: u16 = encoded_len(redeem_script.len(), "Redeem script")?;
+                data.extend_from_slice(&redeem_script_len.to_le_bytes());
+                data.extend_from_slice(redeem_script);
+            }
+            if let Some(derivation) = &input.derivation_path {
+                data.extend_from_slice(&derivation.pubkey);
+                data.extend_from_slice(&derivation.seed_fingerprint);
+                data.push(encoded_len(derivation.path.len(), "Derivation path")?);
+                for index in &derivation.path {
+                    data.extend_from_slice(&index.to_le_bytes());
+                }
+            }
+        }
     }
 
-    data
+    Ok(data)
+}
+
+/// Options controlling how strictly PCZT bytes are parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Reject encodings that don't re-serialize to the exact same bytes
+    /// (e.g. trailing data). Required when PCZT bytes are hashed for dedup or audit.
+    pub require_canonical: bool,
+    /// Also reject proprietary fields outside the recognized namespaces (see
+    /// `register_proprietary_namespace`), for signers that only accept documents
+    /// they fully understand. Implies `require_canonical`.
+    pub reject_unknown_fields: bool,
+}
+
+impl ParseOptions {
+    pub fn canonical() -> Self {
+        Self { require_canonical: true, ..Self::default() }
+    }
+
+    /// Canonical encoding and no unrecognized proprietary fields
+    pub fn safe_mode() -> Self {
+        Self { require_canonical: true, reject_unknown_fields: true }
+    }
 }
 
 /// Represents a payment request as per ZIP 321
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TransactionRequest {
     /// List of payment recipients
     pub payments: Vec<Payment>,
@@ -208,12 +597,809 @@ pub struct TransactionRequest {
     pub target_height: Option<u32>,
     /// Use mainnet parameters (default: true = mainnet)
     /// Set to false for testnet. This affects the consensus branch ID embedded in the transaction.
+    /// `network` takes precedence when present.
+    #[deprecated(note = "set `network` instead, which also covers regtest")]
     #[serde(default = "default_use_mainnet")]
     pub use_mainnet: bool,
+    /// The chain the transaction is built for (None = mainnet or testnet per `use_mainnet`)
+    #[serde(default)]
+    pub network: Option<Network>,
+    /// Height after which the transaction can no longer be mined (0 = never expires).
+    /// Takes precedence over `expiry_delta`.
+    #[serde(default)]
+    pub expiry_height: Option<u32>,
+    /// Blocks after the target height until the transaction expires
+    /// (None = the builder default of 40)
+    #[serde(default)]
+    pub expiry_delta: Option<u32>,
+    /// Maximum amount (in zatoshis) by which the implicit fee may exceed the ZIP-317 fee.
+    /// Defaults to 0, i.e. the proposal must pay exactly the ZIP-317 fee.
+    #[serde(default)]
+    pub fee_tolerance: u64,
+    /// Highest implicit fee (in zatoshis) the proposal may pay, after input selection
+    /// and including any dust change folded into it. None = no cap.
+    #[serde(default)]
+    pub max_fee: Option<u64>,
+    /// How the fee is derived from the ZIP-317 fee (defaults to paying it exactly)
+    #[serde(default)]
+    pub fee_policy: FeePolicy,
+    /// How transparent payments and change below the dust threshold are handled.
+    /// Defaults to adding change below the P2PKH dust threshold to the fee.
+    #[serde(default)]
+    pub dust_policy: DustPolicy,
+    /// Which pool change goes to (defaults to transparent)
+    #[serde(default)]
+    pub change_policy: ChangePolicy,
+    /// How payment memos are encoded (defaults to padded plain text)
+    #[serde(default)]
+    pub memo_encoding: MemoEncoding,
+    /// Default Orchard outgoing viewing key for shielded payments (None = outputs
+    /// are not recoverable by the sender). Payments can override it individually.
+    #[serde(default)]
+    pub ovk: Option<[u8; 32]>,
+    /// Minimum confirmations each input must have at `target_height` (0 = no check).
+    /// Requires `target_height` and input heights (inputs format v2).
+    #[serde(default)]
+    pub min_confirmations: u32,
+    /// How inputs are picked from the provided UTXOs (defaults to spending all of them)
+    #[serde(default)]
+    pub coin_selection: CoinSelection,
+    /// Send everything: the single payment receives all inputs minus the fee, with no
+    /// change output. Its `amount` is ignored. Requires `CoinSelection::All`.
+    #[serde(default)]
+    pub sweep: bool,
+    /// Flags for the Orchard bundle, if there is one (None = spends and outputs enabled)
+    #[serde(default)]
+    pub orchard_flags: Option<OrchardFlags>,
+    /// Operator-assigned identifier for the transaction (e.g. a withdrawal batch ID),
+    /// stamped into the PCZT so every later step can report it
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// A proposer's statement that a transparent change output returns to their keys
+///
+/// `signature` is a compact ECDSA signature by the key controlling transparent input
+/// `input_index` over `change_ownership_message` for output `output_index`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChangeOwnershipProof {
+    pub output_index: usize,
+    pub input_index: usize,
+    pub signature: [u8; 64],
+}
+
+/// Magic bytes at the start of a serialized sighash batch
+pub const SIGHASH_BATCH_MAGIC: [u8; 4] = *b"T2ZS";
+
+/// Current version of the sighash batch format
+pub const SIGHASH_BATCH_VERSION: u8 = 1;
+
+/// Every input's sighash plus the shared transaction context, for signing all
+/// inputs in one session over a slow transport (NFC, QR)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SighashBatch {
+    /// `pczt_fingerprint` of the transaction being signed
+    pub fingerprint: [u8; 32],
+    pub consensus_branch_id: u32,
+    pub expiry_height: u32,
+    /// Fee in zatoshis (transparent in minus transparent out and shielded value added)
+    pub fee: u64,
+    pub inputs: Vec<SighashBatchInput>,
+}
+
+/// One transparent input of a `SighashBatch`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SighashBatchInput {
+    /// Compressed public key that must sign
+    pub pubkey: [u8; 33],
+    pub value: u64,
+    pub script_pubkey: Vec<u8>,
+    pub sighash: [u8; 32],
+}
+
+impl SighashBatch {
+    /// Serializes the batch
+    ///
+    /// Format:
+    /// - [magic: 4 bytes = "T2ZS"] [version: 1 byte]
+    /// - [fingerprint: 32 bytes] [consensus_branch_id: u32 LE] [expiry_height: u32 LE] [fee: u64 LE]
+    /// - [num_inputs: u16 LE], then for each input:
+    ///   [pubkey: 33 bytes] [value: u64 LE] [script_len: u16 LE] [script] [sighash: 32 bytes]
+    /// - [checksum: first 4 bytes of SHA-256 over everything before it]
+    ///
+    /// Fails with `InvalidFormat` if there are too many inputs, or a script is too long,
+    /// for the format's u16 lengths.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, ParseError> {
+        use sha2::{Digest, Sha256};
+
+        let mut data = Vec::new();
+        data.extend_from_slice(&SIGHASH_BATCH_MAGIC);
+        data.push(SIGHASH_BATCH_VERSION);
+        data.extend_from_slice(&self.fingerprint);
+        data.extend_from_slice(&self.consensus_branch_id.to_le_bytes());
+        data.extend_from_slice(&self.expiry_height.to_le_bytes());
+        data.extend_from_slice(&self.fee.to_le_bytes());
+        let num_inputs: u16 = encoded_len(self.inputs.len(), "Input list")?;
+        data.extend_from_slice(&num_inputs.to_le_bytes());
+        for input in &self.inputs {
+            data.extend_from_slice(&input.pubkey);
+            data.extend_from_slice(&input.value.to_le_bytes());
+            let script_len: u16 = encoded_len(input.script_pubkey.len(), "Script")?;
+            data.extend_from_slice(&script_len.to_le_bytes());
+            data.extend_from_slice(&input.script_pubkey);
+            data.extend_from_slice(&input.sighash);
+        }
+        let checksum = Sha256::digest(&data);
+        data.extend_from_slice(&checksum[..4]);
+        Ok(data)
+    }
+
+    /// Parses a serialized batch and checks its internal consistency
+    ///
+    /// Rejects bad checksums, trailing bytes, and P2PKH inputs whose script does not
+    /// pay to the listed pubkey.
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ParseError> {
+        use sha2::{Digest, Sha256};
+
+        if data.len() < 5 || data[..4] != SIGHASH_BATCH_MAGIC {
+            return Err(ParseError::InvalidFormat("Not a sighash batch".to_string()));
+        }
+        if data[4] != SIGHASH_BATCH_VERSION {
+            return Err(ParseError::UnsupportedVersion);
+        }
+        if data.len() < 9 {
+            return Err(ParseError::InvalidFormat("Sighash batch is truncated".to_string()));
+        }
+        let (body, checksum) = data.split_at(data.len() - 4);
+        if Sha256::digest(body)[..4] != *checksum {
+            return Err(ParseError::CorruptedData);
+        }
+
+        let mut offset = 5;
+        let mut take = |len: usize| take_bytes(body, &mut offset, len);
+
+        let fingerprint: [u8; 32] = take(32)?.try_into().expect("32 bytes");
+        let consensus_branch_id = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let expiry_height = u32::from_le_bytes(take(4)?.try_into().expect("4 bytes"));
+        let fee = u64::from_le_bytes(take(8)?.try_into().expect("8 bytes"));
+        let num_inputs = u16::from_le_bytes(take(2)?.try_into().expect("2 bytes")) as usize;
+
+        let mut inputs = Vec::with_capacity(num_inputs);
+        for index in 0..num_inputs {
+            let pubkey: [u8; 33] = take(33)?.try_into().expect("33 bytes");
+            let value = u64::from_le_bytes(take(8)?.try_into().expect("8 bytes"));
+            let script_len = u16::from_le_bytes(take(2)?.try_into().expect("2 bytes")) as usize;
+            let script_pubkey = take(script_len)?.to_vec();
+            let sighash: [u8; 32] = take(32)?.try_into().expect("32 bytes");
+
+            if let Some(pubkey_hash) = crate::p2pkh_pubkey_hash(&script_pubkey) {
+                if !crate::ct_eq_bytes(&crate::hash160(&pubkey), &pubkey_hash) {
+                    return Err(ParseError::InvalidFormat(format!(
+                        "Input {} pubkey does not match its script", index
+                    )));
+                }
+            }
+
+            inputs.push(SighashBatchInput { pubkey, value, script_pubkey, sighash });
+        }
+
+        if offset != body.len() {
+            return Err(ParseError::InvalidFormat("Trailing bytes after sighash batch".to_string()));
+        }
+
+        Ok(SighashBatch { fingerprint, consensus_branch_id, expiry_height, fee, inputs })
+    }
+}
+
+/// Reads `len` bytes at `offset`, advancing it
+fn take_bytes<'a>(data: &'a [u8], offset: &mut usize, len: usize) -> Result<&'a [u8], ParseError> {
+    let bytes = data.get(*offset..*offset + len)
+        .ok_or_else(|| ParseError::InvalidFormat("Sighash batch is truncated".to_string()))?;
+    *offset += len;
+    Ok(bytes)
+}
+
+/// Accounting data for one Orchard action's output
+///
+/// The note commitment and ephemeral key appear on-chain, so they can be used to
+/// match actions to the payments of the original request without trial decryption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OrchardOutputInfo {
+    /// Index of the action within the Orchard bundle
+    pub action_index: usize,
+    /// Extracted note commitment (cmx)
+    pub cmx: [u8; 32],
+    /// Ephemeral public key of the note encryption
+    pub ephemeral_key: [u8; 32],
+    /// Raw Orchard recipient address, if known (PCZTs only; redacted or decoded = None)
+    pub recipient: Option<[u8; 43]>,
+    /// Note value in zatoshis, if known (PCZTs only; dummy actions have value 0)
+    pub value: Option<u64>,
+}
+
+/// Version of the `ReviewDocument` layout
+pub const REVIEW_DOCUMENT_VERSION: u32 = 3;
+
+/// Units in which amounts are rendered for display (see `format_amount`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AmountUnit {
+    /// "1.00000000 ZEC"
+    Zec,
+    /// "100000000 zatoshis"
+    Zatoshis,
+    /// "1.00000000 ZEC (100000000 zatoshis)"
+    #[default]
+    Both,
+}
+
+/// A frozen, human-reviewable summary of a proposal for four-eyes approval.
+///
+/// Produced by `export_review` and checked by `verify_review`, which recomputes it
+/// from the PCZT and request and rejects any drift. Memos are never included in
+/// plaintext; only their presence, length and digest are recorded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewDocument {
+    /// Layout version (`REVIEW_DOCUMENT_VERSION`)
+    pub version: u32,
+    /// "main" or "test"
+    pub network: String,
+    /// Unit used for the `*_display` fields
+    pub unit: AmountUnit,
+    /// Hex-encoded `pczt_fingerprint` of the reviewed PCZT
+    pub fingerprint: String,
+    /// Transparent inputs being spent
+    pub inputs: Vec<ReviewInput>,
+    /// Orchard and Sapling notes being spent (Orchard padding actions are omitted)
+    pub spends: Vec<ReviewSpend>,
+    /// Transparent and Orchard outputs (Orchard padding actions are omitted)
+    pub outputs: Vec<ReviewOutput>,
+    /// Fee in zatoshis
+    pub fee: u64,
+    /// Fee rendered with `format_amount`
+    pub fee_display: String,
+    /// Hex-encoded SHA-256 over all other fields
+    pub digest: String,
+}
+
+/// A transparent input in a `ReviewDocument`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewInput {
+    /// Transaction ID of the UTXO (hex, display byte order)
+    pub txid: String,
+    pub vout: u32,
+    /// Value in zatoshis
+    pub value: u64,
+    /// Value rendered with `format_amount`
+    pub value_display: String,
+    /// Address controlling the UTXO, if its script is a standard one
+    pub address: Option<String>,
+}
+
+/// A shielded note spent in a `ReviewDocument`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewSpend {
+    /// "orchard" or "sapling"
+    pub pool: String,
+    /// Value in zatoshis
+    pub value: u64,
+    /// Value rendered with `format_amount`
+    pub value_display: String,
+}
+
+/// An output in a `ReviewDocument`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewOutput {
+    /// "transparent" or "orchard"
+    pub pool: String,
+    /// Recipient address, if known (the requested address for payments)
+    pub address: Option<String>,
+    /// Value in zatoshis
+    pub value: u64,
+    /// Value rendered with `format_amount`
+    pub value_display: String,
+    /// Index of the request payment this output pays, or None for change
+    pub payment_index: Option<usize>,
+    /// Memo attached to the payment, redacted
+    pub memo: Option<ReviewMemo>,
+}
+
+/// A redacted memo in a `ReviewDocument`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReviewMemo {
+    /// Length of the memo text (or raw memo) in bytes
+    pub length: usize,
+    /// Hex-encoded SHA-256 of the memo text (or raw memo), for out-of-band comparison
+    pub sha256: String,
+}
+
+/// How much of a batch a reviewer gets to see
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewDisclosure {
+    /// Every amount and address, with memo digests (`ReviewDocument`)
+    Full,
+    /// Aggregate totals and address prefixes; individual shielded amounts and all
+    /// memos are left out (`BlindedReview`)
+    AmountBlinded,
+}
+
+/// Which `ReviewDisclosure` each reviewer role receives (see `export_review_for_role`)
+///
+/// Roles are the host's own names (e.g. "treasurer", "second-approver"). Roles
+/// without an entry get `default`, which is `AmountBlinded` unless set otherwise.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisclosurePolicy {
+    pub roles: std::collections::BTreeMap<String, ReviewDisclosure>,
+    pub default: ReviewDisclosure,
+}
+
+impl Default for DisclosurePolicy {
+    fn default() -> Self {
+        DisclosurePolicy { roles: Default::default(), default: ReviewDisclosure::AmountBlinded }
+    }
+}
+
+impl DisclosurePolicy {
+    pub fn with_role(mut self, role: impl Into<String>, disclosure: ReviewDisclosure) -> Self {
+        self.roles.insert(role.into(), disclosure);
+        self
+    }
+
+    /// The disclosure `role` receives
+    pub fn disclosure_for(&self, role: &str) -> ReviewDisclosure {
+        self.roles.get(role).copied().unwrap_or(self.default)
+    }
+}
+
+/// Version of the `BlindedReview` layout
+pub const BLINDED_REVIEW_VERSION: u32 = 2;
+
+/// A review document for an approver who may check a batch but not see its
+/// individual shielded amounts or memos (see `export_blinded_review`).
+///
+/// Totals are exact, so a batch with a single Orchard output still reveals that
+/// output's amount through `total_orchard`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlindedReview {
+    /// Layout version (`BLINDED_REVIEW_VERSION`)
+    pub version: u32,
+    /// "main" or "test"
+    pub network: String,
+    /// Unit used for the `*_display` fields
+    pub unit: AmountUnit,
+    /// Hex-encoded `pczt_fingerprint` of the reviewed PCZT
+    pub fingerprint: String,
+    /// Number of transparent inputs being spent
+    pub num_inputs: usize,
+    /// Number of Orchard and Sapling notes being spent
+    pub num_shielded_spends: usize,
+    /// Total value of the inputs and shielded spends in zatoshis
+    pub total_in: u64,
+    pub total_in_display: String,
+    /// Outputs, in the order of the full `ReviewDocument`
+    pub outputs: Vec<BlindedOutput>,
+    /// Total value sent to transparent outputs (payments and change)
+    pub total_transparent: u64,
+    pub total_transparent_display: String,
+    /// Total value sent to Orchard outputs
+    pub total_orchard: u64,
+    pub total_orchard_display: String,
+    /// Fee in zatoshis
+    pub fee: u64,
+    pub fee_display: String,
+    /// Hex-encoded SHA-256 over all other fields
+    pub digest: String,
+}
+
+/// An output in a `BlindedReview`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlindedOutput {
+    /// "transparent" or "orchard"
+    pub pool: String,
+    /// Leading characters of the recipient address, if known
+    pub address_prefix: Option<String>,
+    /// Value in zatoshis for transparent outputs (public on chain); None for Orchard
+    pub value: Option<u64>,
+    /// Index of the request payment this output pays, or None for change
+    pub payment_index: Option<usize>,
+}
+
+/// A review document in the disclosure a reviewer's role allows
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReviewExport {
+    Full(ReviewDocument),
+    Blinded(BlindedReview),
+}
+
+/// Which pool an output pays into
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputPool {
+    Transparent,
+    Orchard,
+}
+
+/// What a PCZT will do, for a confirmation screen before signing (see `summarize_pczt`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionSummary {
+    /// "main" or "test"
+    pub network: String,
+    pub expiry_height: u32,
+    /// Transparent inputs being spent
+    pub inputs: Vec<SummaryInput>,
+    /// Transparent and Orchard outputs (Orchard padding actions are omitted)
+    pub outputs: Vec<SummaryOutput>,
+    /// Total of the outputs marked as change, in zatoshis
+    pub change: u64,
+    /// Fee in zatoshis, or None if the PCZT's values don't balance
+    pub fee: Option<u64>,
+    /// Estimated size of the final transaction in bytes
+    pub estimated_size: usize,
+    /// The request's label, if it had one (see `transaction_label`)
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+/// A transparent input in a `TransactionSummary`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryInput {
+    /// Transaction ID of the UTXO (hex, display byte order)
+    pub txid: String,
+    pub vout: u32,
+    /// Value in zatoshis
+    pub value: u64,
+    /// Address controlling the UTXO, if its script is a standard one
+    pub address: Option<String>,
+}
+
+/// An output in a `TransactionSummary`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SummaryOutput {
+    pub pool: OutputPool,
+    /// Recipient address, if known (Orchard outputs as single-receiver unified addresses)
+    pub address: Option<String>,
+    /// Value in zatoshis, if not redacted
+    pub value: Option<u64>,
+    /// Whether the output returns funds to the spender: a transparent output to a
+    /// spent input's script or to a change address the caller named. Orchard
+    /// change can't be told apart from payments without viewing keys and is never
+    /// marked.
+    pub is_change: bool,
+}
+
+/// A BIP-32 derivation recorded for a transparent key, so a signer can find it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyDerivation {
+    /// Compressed public key the derivation produces
+    pub pubkey: [u8; 33],
+    /// ZIP-32 seed fingerprint of the wallet that holds the key
+    pub seed_fingerprint: [u8; 32],
+    /// Child indices from the seed, hardened indices with the high bit set
+    pub path: Vec<u32>,
+}
+
+/// Hash function of a preimage added with `PcztUpdate::InputPreimage`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreimageHash {
+    Ripemd160,
+    Sha256,
+    /// RIPEMD-160 of SHA-256 (pubkey and script hashes)
+    Hash160,
+    /// Double SHA-256
+    Hash256,
+}
+
+/// A change that an intermediate party makes to a PCZT (see `update_pczt`)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PcztUpdate {
+    /// Sets a proprietary field on the transaction
+    GlobalProprietary { key: String, value: Vec<u8> },
+    /// Sets the redeem script of a P2SH input
+    InputRedeemScript { index: usize, script: Vec<u8> },
+    /// Records how an input's key is derived
+    InputDerivation { index: usize, derivation: KeyDerivation },
+    /// Adds a hash preimage an input's script needs
+    InputPreimage { index: usize, hash: PreimageHash, preimage: Vec<u8> },
+    /// Sets a proprietary field on an input
+    InputProprietary { index: usize, key: String, value: Vec<u8> },
+    /// Sets the redeem script of a P2SH output
+    OutputRedeemScript { index: usize, script: Vec<u8> },
+    /// Records how an output's key is derived (e.g. to recognize change)
+    OutputDerivation { index: usize, derivation: KeyDerivation },
+    /// Records the address the user entered for an output
+    OutputUserAddress { index: usize, address: String },
+    /// Sets a proprietary field on an output
+    OutputProprietary { index: usize, key: String, value: Vec<u8> },
+}
+
+/// How the linked library was built (see `build_info`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Crate version from Cargo.toml
+    pub version: String,
+    /// Git commit of the source tree, or "unknown" if built outside a checkout
+    pub git_commit: String,
+    /// Whether the tree had uncommitted changes to tracked files
+    pub git_dirty: bool,
+    /// Enabled cargo features, sorted
+    pub features: Vec<String>,
+    /// Locked versions of the Zcash and cryptography dependencies, as
+    /// `name=version` or `name=version#commit` for git sources
+    pub dependencies: Vec<String>,
+    /// Target triple
+    pub target: String,
+    /// Cargo profile ("debug" or "release")
+    pub profile: String,
+}
+
+/// How memo text is stored in the 512-byte memo field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum MemoEncoding {
+    /// Plain UTF-8 text, zero-padded to 512 bytes (ZIP-302 text memo)
+    #[default]
+    Padded,
+    /// Deflate-compressed text behind a marker byte, for payloads such as
+    /// structured JSON that don't fit as plain text. Read back with `decode_memo`.
+    Compressed,
+}
+
+/// Whether lossy best-effort conversions are allowed (see `set_strictness`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Strictness {
+    /// Drop or ignore data that can't be represented, as earlier releases did
+    #[default]
+    Lenient,
+    /// Fail instead of dropping memos, ignoring labels/messages or invalid
+    /// strings, or guessing an address's type; also validate received PCZTs
+    /// (`validate_pczt`) in `verify_before_signing`
+    Strict,
+}
+
+/// How a proposal's fee follows from the ZIP-317 conventional fee of its shape
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum FeePolicy {
+    /// Pay the ZIP-317 conventional fee
+    #[default]
+    Zip317Standard,
+    /// Pay exactly this many zatoshis, whatever the transaction's shape. Nodes may
+    /// not relay a transaction that pays less than its ZIP-317 fee.
+    FixedZatoshis(u64),
+    /// Pay the ZIP-317 fee times this factor (at least 1), rounded up to a zatoshi,
+    /// e.g. to get ahead of other transactions when blocks are full
+    Zip317WithMultiplier(f64),
+}
+
+impl FeePolicy {
+    /// The fee this policy charges for a transaction whose ZIP-317 fee is `conventional_fee`
+    pub fn fee(&self, conventional_fee: u64) -> u64 {
+        match *self {
+            FeePolicy::Zip317Standard => conventional_fee,
+            FeePolicy::FixedZatoshis(fee) => fee,
+            // Saturates for absurdly large products
+            FeePolicy::Zip317WithMultiplier(multiplier) => (conventional_fee as f64 * multiplier).ceil() as u64,
+        }
+    }
+
+    /// Checks that a multiplier is finite and doesn't undercut ZIP-317
+    #[cfg(feature = "proposal")]
+    pub(crate) fn validate(&self) -> Result<(), String> {
+        match *self {
+            FeePolicy::Zip317WithMultiplier(multiplier) if !(multiplier.is_finite() && multiplier >= 1.0) => {
+                Err(format!("Fee multiplier {} must be a finite number of at least 1", multiplier))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Dust threshold (in zatoshis) wallets conventionally use for transparent outputs,
+/// stricter than the P2PKH standardness limit of `DustPolicy::default()`
+pub const DEFAULT_DUST_THRESHOLD: u64 = 5460;
+
+/// How a proposal treats transparent outputs below a dust threshold.
+///
+/// Transparent payments below the threshold are always refused. A threshold of 0
+/// makes nothing dust, so every change output is kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum DustPolicy {
+    /// Refuse transparent payments below `threshold` zatoshis, and add change below
+    /// it to the fee instead of giving it an output
+    AddChangeToFee { threshold: u64 },
+    /// Refuse transparent payments and change below `threshold` zatoshis
+    Reject { threshold: u64 },
+}
+
+impl DustPolicy {
+    /// Outputs below this many zatoshis are dust
+    pub fn threshold(&self) -> u64 {
+        match self {
+            DustPolicy::Reject { threshold } | DustPolicy::AddChangeToFee { threshold } => *threshold,
+        }
+    }
+}
+
+impl Default for DustPolicy {
+    /// Adds change below the P2PKH dust threshold (which nodes won't relay) to the fee
+    fn default() -> Self {
+        DustPolicy::AddChangeToFee {
+            threshold: crate::p2pkh_dust_threshold(),
+        }
+    }
+}
+
+/// Which pool a proposal sends change to
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum ChangePolicy {
+    /// A transparent output to the change address, or to the first input's address
+    #[default]
+    Transparent,
+    /// An Orchard output to the change address's Orchard receiver, which is required
+    Orchard,
+    /// Orchard if the change address has an Orchard receiver, transparent otherwise
+    Auto,
+}
+
+/// The enable bits of an Orchard bundle
+///
+/// A bundle with spends disabled may only carry dummy spends, so a transaction
+/// funded entirely from transparent inputs can mark its Orchard bundle outputs-only.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct OrchardFlags {
+    pub spends_enabled: bool,
+    pub outputs_enabled: bool,
+}
+
+impl OrchardFlags {
+    /// Spends and outputs enabled, as the transaction builder emits by default
+    pub const ENABLED: OrchardFlags = OrchardFlags { spends_enabled: true, outputs_enabled: true };
+    /// Outputs only, for bundles funded from transparent inputs
+    pub const SPENDS_DISABLED: OrchardFlags = OrchardFlags { spends_enabled: false, outputs_enabled: true };
+
+    /// Decodes the flags byte of a bundle, or None if reserved bits are set
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        (byte & !0b11 == 0).then_some(OrchardFlags {
+            spends_enabled: byte & 0b01 != 0,
+            outputs_enabled: byte & 0b10 != 0,
+        })
+    }
+
+    /// The flags byte as encoded in a bundle
+    pub fn to_byte(self) -> u8 {
+        (self.spends_enabled as u8) | ((self.outputs_enabled as u8) << 1)
+    }
+}
+
+/// The chain a transaction is built for, which selects its consensus branch ID
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum Network {
+    Mainnet,
+    Testnet,
+    /// A local zcashd or zebrad regtest chain, with the activation heights it was started with
+    Regtest(RegtestActivations),
+}
+
+impl Network {
+    pub fn network_type(&self) -> NetworkType {
+        match self {
+            Network::Mainnet => NetworkType::Main,
+            Network::Testnet => NetworkType::Test,
+            Network::Regtest(_) => NetworkType::Regtest,
+        }
+    }
+}
+
+/// Network upgrade activation heights of a regtest chain (None = never activates)
+///
+/// Defaults to every upgrade through NU6 active from height 1, as zebrad and
+/// zcashd regtest nodes are usually configured for wallet testing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RegtestActivations {
+    pub overwinter: Option<u32>,
+    pub sapling: Option<u32>,
+    pub blossom: Option<u32>,
+    pub heartwood: Option<u32>,
+    pub canopy: Option<u32>,
+    pub nu5: Option<u32>,
+    pub nu6: Option<u32>,
+}
+
+impl Default for RegtestActivations {
+    fn default() -> Self {
+        Self {
+            overwinter: Some(1),
+            sapling: Some(1),
+            blossom: Some(1),
+            heartwood: Some(1),
+            canopy: Some(1),
+            nu5: Some(1),
+            nu6: Some(1),
+        }
+    }
+}
+
+impl RegtestActivations {
+    /// The lowest height at which every configured upgrade is active
+    pub fn latest_activation_height(&self) -> u32 {
+        [self.overwinter, self.sapling, self.blossom, self.heartwood, self.canopy, self.nu5, self.nu6]
+            .into_iter()
+            .flatten()
+            .max()
+            .unwrap_or(1)
+    }
+}
+
+impl zcash_protocol::consensus::Parameters for RegtestActivations {
+    fn network_type(&self) -> NetworkType {
+        NetworkType::Regtest
+    }
+
+    fn activation_height(
+        &self,
+        nu: zcash_protocol::consensus::NetworkUpgrade,
+    ) -> Option<zcash_protocol::consensus::BlockHeight> {
+        use zcash_protocol::consensus::NetworkUpgrade;
+
+        let height = match nu {
+            NetworkUpgrade::Overwinter => self.overwinter,
+            NetworkUpgrade::Sapling => self.sapling,
+            NetworkUpgrade::Blossom => self.blossom,
+            NetworkUpgrade::Heartwood => self.heartwood,
+            NetworkUpgrade::Canopy => self.canopy,
+            NetworkUpgrade::Nu5 => self.nu5,
+            NetworkUpgrade::Nu6 => self.nu6,
+            // Upgrades behind unstable cfgs never activate here
+            #[allow(unreachable_patterns)]
+            _ => None,
+        };
+        height.map(zcash_protocol::consensus::BlockHeight::from_u32)
+    }
+}
+
+/// How a proposal picks inputs from the UTXOs it is given (see `experimental::coin_selection`)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub enum CoinSelection {
+    /// Spend every provided input
+    #[default]
+    All,
+    /// Spend the largest UTXOs first, minimizing the number of inputs
+    LargestFirst,
+    /// Spend the smallest UTXOs first, consolidating dust
+    SmallestFirst,
+    /// Search for a set of inputs that needs no change output, falling back to
+    /// largest-first if none is found
+    BranchAndBound,
+}
+
+/// How much of a split sweep one recipient receives (see `plan_split_sweep`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SweepShare {
+    /// A fixed amount in zatoshis, paid before anything is split
+    Fixed(u64),
+    /// A weight in the split of what remains after fees and fixed amounts
+    Ratio(u64),
+}
+
+/// One output of a split sweep
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SweepRecipient {
+    /// Transparent address or unified address with an Orchard receiver
+    pub address: String,
+    pub share: SweepShare,
 }
 
 /// A single payment to a recipient
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Payment {
     /// The recipient address (unified address with Orchard receiver or transparent address)
     pub address: String,
@@ -225,6 +1411,53 @@ pub struct Payment {
     pub label: Option<String>,
     /// Optional message
     pub message: Option<String>,
+    /// Optional Orchard outgoing viewing key for this payment, overriding the
+    /// request-level `ovk`. Ignored for transparent payments.
+    #[serde(default)]
+    pub ovk: Option<[u8; 32]>,
+    /// Raw scriptPubKey to pay instead of `address` (hex in JSON). The address must
+    /// be empty, and the script a standard template (see `classify_script`).
+    #[serde(default, with = "hex_bytes", skip_serializing_if = "Option::is_none")]
+    pub script_pubkey: Option<Vec<u8>>,
+    /// Raw memo field of up to `MEMO_SIZE` bytes (hex in JSON), stored as-is instead
+    /// of encoding `memo`. Only one of the two may be set.
+    #[serde(default, with = "hex_bytes", skip_serializing_if = "Option::is_none")]
+    pub memo_bytes: Option<Vec<u8>>,
+}
+
+/// Serde helper encoding an optional byte string of `Payment` as hex
+mod hex_bytes {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(script: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match script {
+            Some(script) => serializer.serialize_str(&crate::hex_encode(script)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Vec<u8>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .map(|hex| crate::json::hex_decode(&hex).map_err(D::Error::custom))
+            .transpose()
+    }
+}
+
+/// Standard scriptPubKey templates (see `classify_script`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScriptKind {
+    /// Pay to public key hash
+    P2pkh,
+    /// Pay to script hash
+    P2sh,
+    /// Pay to a bare public key
+    P2pk,
+    /// Bare m-of-n multisig with at most 3 keys
+    Multisig,
+    /// Provably unspendable OP_RETURN data carrier
+    NullData,
+    /// Anything else; nodes won't relay outputs paying to it
+    NonStandard,
 }
 
 /// Default value for use_mainnet (true = mainnet)
@@ -233,12 +1466,28 @@ fn default_use_mainnet() -> bool {
 }
 
 impl TransactionRequest {
+    #[allow(deprecated)] // use_mainnet still needs its default
     pub fn new(payments: Vec<Payment>) -> Self {
         Self {
             payments,
             memo: None,
             target_height: None,
             use_mainnet: true,
+            network: None,
+            expiry_height: None,
+            expiry_delta: None,
+            fee_tolerance: 0,
+            max_fee: None,
+            fee_policy: FeePolicy::default(),
+            dust_policy: DustPolicy::default(),
+            change_policy: ChangePolicy::default(),
+            memo_encoding: MemoEncoding::default(),
+            ovk: None,
+            min_confirmations: 0,
+            coin_selection: CoinSelection::default(),
+            sweep: false,
+            orchard_flags: None,
+            label: None,
         }
     }
 
@@ -247,6 +1496,97 @@ impl TransactionRequest {
         self
     }
 
+    pub fn with_coin_selection(mut self, coin_selection: CoinSelection) -> Self {
+        self.coin_selection = coin_selection;
+        self
+    }
+
+    pub fn with_sweep(mut self, sweep: bool) -> Self {
+        self.sweep = sweep;
+        self
+    }
+
+    pub fn with_orchard_flags(mut self, orchard_flags: OrchardFlags) -> Self {
+        self.orchard_flags = Some(orchard_flags);
+        self
+    }
+
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = Some(network);
+        self
+    }
+
+    pub fn with_expiry_height(mut self, expiry_height: u32) -> Self {
+        self.expiry_height = Some(expiry_height);
+        self
+    }
+
+    pub fn with_expiry_delta(mut self, expiry_delta: u32) -> Self {
+        self.expiry_delta = Some(expiry_delta);
+        self
+    }
+
+    pub fn with_label(mut self, label: String) -> Self {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn with_min_confirmations(mut self, min_confirmations: u32) -> Self {
+        self.min_confirmations = min_confirmations;
+        self
+    }
+
+    pub fn with_fee_tolerance(mut self, fee_tolerance: u64) -> Self {
+        self.fee_tolerance = fee_tolerance;
+        self
+    }
+
+    pub fn with_max_fee(mut self, max_fee: u64) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    pub fn with_fee_policy(mut self, fee_policy: FeePolicy) -> Self {
+        self.fee_policy = fee_policy;
+        self
+    }
+
+    pub fn with_ovk(mut self, ovk: [u8; 32]) -> Self {
+        self.ovk = Some(ovk);
+        self
+    }
+
+    pub fn with_memo_encoding(mut self, memo_encoding: MemoEncoding) -> Self {
+        self.memo_encoding = memo_encoding;
+        self
+    }
+
+    pub fn with_change_policy(mut self, change_policy: ChangePolicy) -> Self {
+        self.change_policy = change_policy;
+        self
+    }
+
+    pub fn with_dust_policy(mut self, dust_policy: DustPolicy) -> Self {
+        self.dust_policy = dust_policy;
+        self
+    }
+
+    /// The network the request is built for: `network`, or the one `use_mainnet` selects
+    #[allow(deprecated)] // Requests that only set use_mainnet keep their network
+    pub fn effective_network(&self) -> Network {
+        match self.network {
+            Some(network) => network,
+            None if self.use_mainnet => Network::Mainnet,
+            None => Network::Testnet,
+        }
+    }
+
+    /// The expiry height a proposal at `target_height` gets, or None for the builder default
+    pub fn effective_expiry_height(&self, target_height: u32) -> Option<u32> {
+        self.expiry_height
+            .or_else(|| self.expiry_delta.map(|delta| target_height.saturating_add(delta)))
+    }
+
     /// Calculate total amount across all payments
     pub fn total_amount(&self) -> u64 {
         self.payments.iter().map(|p| p.amount).sum()
@@ -266,14 +1606,49 @@ impl Payment {
             memo: None,
             label: None,
             message: None,
+            ovk: None,
+            script_pubkey: None,
+            memo_bytes: None,
+        }
+    }
+
+    /// Creates a payment to a raw scriptPubKey, for templates without an address form
+    pub fn to_script(script_pubkey: Vec<u8>, amount: u64) -> Self {
+        Self {
+            script_pubkey: Some(script_pubkey),
+            ..Self::new(String::new(), amount)
         }
     }
 
     pub fn with_memo(mut self, memo: String) -> Self {
         self.memo = Some(memo);
+        self.memo_bytes = None;
+        self
+    }
+
+    /// Sets a raw memo of up to `MEMO_SIZE` bytes, e.g. a ZIP-302 arbitrary data memo
+    /// starting with 0xFF. It replaces any text memo and is not re-encoded.
+    pub fn with_memo_bytes(mut self, memo: Vec<u8>) -> Self {
+        self.memo_bytes = Some(memo);
+        self.memo = None;
         self
     }
 
+    /// The bytes this payment puts in its memo field, if it has a memo
+    ///
+    /// Text memos are encoded with `encoding` (see `encode_memo`). Fails with `TooLong`
+    /// if the memo doesn't fit, and with `InvalidMemo` if both kinds of memo are set.
+    pub fn memo_field(&self, encoding: MemoEncoding) -> Result<Option<Vec<u8>>, MemoError> {
+        match (&self.memo, &self.memo_bytes) {
+            (Some(_), Some(_)) => Err(MemoError::InvalidMemo("Both a text and a raw memo are set".to_string())),
+            (Some(text), None) => crate::encode_memo(text, encoding).map(Some),
+            (None, Some(bytes)) if bytes.len() > crate::MEMO_SIZE => {
+                Err(MemoError::TooLong { len: bytes.len(), max: crate::MEMO_SIZE })
+            }
+            (None, bytes) => Ok(bytes.clone()),
+        }
+    }
+
     pub fn with_label(mut self, label: String) -> Self {
         self.label = Some(label);
         self
@@ -284,20 +1659,89 @@ impl Payment {
         self
     }
 
-    /// Check if this payment is to a transparent address
+    pub fn with_ovk(mut self, ovk: [u8; 32]) -> Self {
+        self.ovk = Some(ovk);
+        self
+    }
+
+    /// The scriptPubKey this payment's transparent output pays to, from
+    /// `script_pubkey` or a transparent address
+    pub fn output_script(&self) -> Option<Vec<u8>> {
+        match &self.script_pubkey {
+            Some(script) => Some(script.clone()),
+            None => crate::address_to_script(&self.address),
+        }
+    }
+
+    /// Check if this payment is to a transparent address or raw script
     pub fn is_transparent(&self) -> bool {
-        self.address.parse::<ZcashAddress>()
-            .ok()
-            .and_then(|addr| addr.convert::<zcash_transparent::address::TransparentAddress>().ok())
-            .is_some()
+        if self.script_pubkey.is_some() {
+            return true;
+        }
+        matches!(crate::address_cache::parse_address(&self.address), Some(ParsedAddress::Transparent(_)))
     }
 
     /// Check if this payment is to a unified address
     pub fn is_unified(&self) -> bool {
-        self.address.parse::<ZcashAddress>()
-            .ok()
-            .and_then(|addr| addr.convert::<UnifiedAddressWrapper>().ok())
-            .is_some()
+        matches!(crate::address_cache::parse_address(&self.address), Some(ParsedAddress::Unified(_)))
+    }
+}
+
+/// How serious a verification finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    /// Worth a reviewer's attention, but doesn't fail verification
+    Warning,
+    /// Fails verification
+    Error,
+}
+
+/// One failed check of `verify_report`
+#[derive(Debug)]
+pub struct VerificationFinding {
+    pub severity: Severity,
+    pub failure: VerificationFailure,
+}
+
+/// Every failed check of `verify_report`, in the order the checks ran
+#[derive(Debug, Default)]
+pub struct VerificationReport {
+    pub findings: Vec<VerificationFinding>,
+}
+
+impl VerificationReport {
+    /// Whether no check failed with `Severity::Error`
+    pub fn is_ok(&self) -> bool {
+        self.errors().next().is_none()
+    }
+
+    /// Findings that fail verification
+    pub fn errors(&self) -> impl Iterator<Item = &VerificationFailure> {
+        self.with_severity(Severity::Error)
+    }
+
+    /// Findings that don't fail verification
+    pub fn warnings(&self) -> impl Iterator<Item = &VerificationFailure> {
+        self.with_severity(Severity::Warning)
+    }
+
+    /// The first error, as `verify_before_signing` reports it
+    pub fn into_result(self) -> Result<(), VerificationFailure> {
+        match self.findings.into_iter().find(|finding| finding.severity == Severity::Error) {
+            Some(finding) => Err(finding.failure),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn push(&mut self, severity: Severity, failure: VerificationFailure) {
+        self.findings.push(VerificationFinding { severity, failure });
+    }
+
+    fn with_severity(&self, severity: Severity) -> impl Iterator<Item = &VerificationFailure> {
+        self.findings.iter()
+            .filter(move |finding| finding.severity == severity)
+            .map(|finding| &finding.failure)
     }
 }
 
@@ -335,6 +1779,94 @@ mod tests {
         assert_eq!(request.total_amount(), 3000);
     }
 
+    #[test]
+    fn test_raw_script_round_trip() {
+        let p2pkh = [&[0x76, 0xa9, 0x14][..], &[0x11; 20], &[0x88, 0xac]].concat();
+        let p2sh = [&[0xa9, 0x14][..], &[0x22; 20], &[0x87]].concat();
+        // Long scripts get 3-byte (>= 253) and 5-byte (>= 65536) CompactSize prefixes
+        let long = vec![0x51; 300];
+        let very_long = vec![0x51; 70_000];
+
+        for raw in [vec![], p2pkh, p2sh, long, very_long] {
+            let script = script_from_raw(&raw).unwrap();
+            assert_eq!(script_to_raw(&script).unwrap(), raw, "{}-byte script", raw.len());
+        }
+    }
+
+    #[test]
+    fn test_txout_keeps_long_script() {
+        let mut input = test_input();
+        input.script_pubkey = vec![0x51; 300];
+        let txout = input.txout().unwrap();
+        assert_eq!(script_to_raw(txout.script_pubkey()).unwrap(), input.script_pubkey);
+    }
+
+    fn test_input() -> TransparentInput {
+        let secp = secp256k1::Secp256k1::new();
+        let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+        TransparentInput {
+            pubkey: secp256k1::PublicKey::from_secret_key(&secp, &sk),
+            txid: [7u8; 32],
+            vout: 1,
+            amount: 50_000,
+            script_pubkey: vec![0x76, 0xa9],
+            height: None,
+            redeem_script: None,
+            derivation_path: None,
+        }
+    }
+
+    #[test]
+    fn test_inputs_roundtrip_v1() {
+        let data = serialize_transparent_inputs(&[test_input()]).unwrap();
+        assert_eq!(&data[..2], &1u16.to_le_bytes());
+
+        let parsed = parse_transparent_inputs(&data).unwrap();
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].amount, 50_000);
+    }
+
+    #[test]
+    fn test_inputs_count_must_fit_the_format() {
+        let inputs = vec![test_input(); u16::MAX as usize + 1];
+        assert!(matches!(serialize_transparent_inputs(&inputs), Err(ParseError::InvalidFormat(_))));
+
+        // 0xFFFF inputs only fit the v2 format, whose count follows the marker
+        let mut inputs = vec![test_input(); u16::MAX as usize];
+        assert!(matches!(serialize_transparent_inputs(&inputs), Err(ParseError::InvalidFormat(_))));
+        inputs[0].height = Some(1);
+        let data = serialize_transparent_inputs(&inputs).unwrap();
+        assert_eq!(parse_transparent_inputs(&data).unwrap().len(), u16::MAX as usize);
+    }
+
+    #[test]
+    fn test_inputs_roundtrip_v2_height() {
+        let mut input = test_input();
+        input.height = Some(2_700_000);
+        let data = serialize_transparent_inputs(&[input]).unwrap();
+        assert_eq!(&data[..3], &[0xFF, 0xFF, INPUTS_FORMAT_V2]);
+
+        let parsed = parse_transparent_inputs(&data).unwrap();
+        assert_eq!(parsed[0].height, Some(2_700_000));
+        assert_eq!(parsed[0].confirmations(2_700_010), Some(10));
+    }
+
+    #[test]
+    fn test_inputs_v2_rejects_unknown_flags() {
+        let mut input = test_input();
+        input.height = Some(2_700_000);
+        let data = serialize_transparent_inputs(&[input]).unwrap();
+        // Flags byte sits right before the 4-byte height
+        let flags_offset = data.len() - 5;
+
+        // Sequence numbers (0x01) are reserved, not silently dropped
+        for flag in [0x80, 0x01] {
+            let mut data = data.clone();
+            data[flags_offset] |= flag;
+            assert!(parse_transparent_inputs(&data).unwrap_err().contains("unknown flags"));
+        }
+    }
+
     #[test]
     fn test_payment_address_detection() {
         let unified_addr = generate_test_unified_address();
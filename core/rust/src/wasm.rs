@@ -0,0 +1,89 @@
+//! WebAssembly bindings for browser wallets
+//!
+//! The `wasm` feature exposes the transaction flow to JavaScript through
+//! wasm-bindgen, without the N-API addon. PCZTs and transparent inputs cross the
+//! boundary as `Uint8Array`s in the same serialization as the C API, and requests
+//! as the JSON of `json::request_from_json`. Failures are thrown as `Error`s whose
+//! `code` property is the stable `ErrorCode` string.
+//!
+//! ```bash
+//! wasm-pack build --target web --no-default-features --features wasm,proposal
+//! ```
+//!
+//! Without `orchard-prover`, `proveTransaction` only passes transparent-only PCZTs
+//! through; shielded PCZTs can be proved by a remote prover instead.
+
+use wasm_bindgen::prelude::*;
+
+use crate::error::FfiError;
+
+/// Converts an error into a JavaScript `Error` carrying its `ErrorCode`
+fn to_js_error(e: impl Into<FfiError>) -> JsValue {
+    let e = e.into();
+    let error = js_sys::Error::new(&e.to_string());
+    // Setting a property on a fresh Error object can't fail
+    let _ = js_sys::Reflect::set(&error, &JsValue::from_str("code"), &JsValue::from_str(e.code().as_str()));
+    error.into()
+}
+
+fn parse(pczt: &[u8]) -> Result<pczt::Pczt, JsValue> {
+    crate::parse_pczt(pczt).map_err(to_js_error)
+}
+
+/// Serializes transparent inputs given as JSON (see `json::inputs_from_json`)
+#[wasm_bindgen(js_name = inputsFromJson)]
+pub fn inputs_from_json(json: &str) -> Result<Vec<u8>, JsValue> {
+    let inputs = crate::json::inputs_from_json(json).map_err(to_js_error)?;
+    crate::types::serialize_transparent_inputs(&inputs).map_err(to_js_error)
+}
+
+/// Creates a PCZT paying a JSON transaction request from serialized inputs
+#[cfg(feature = "proposal")]
+#[wasm_bindgen(js_name = proposeTransaction)]
+pub fn propose_transaction(
+    inputs: &[u8],
+    request_json: &str,
+    change_address: Option<String>,
+) -> Result<Vec<u8>, JsValue> {
+    let request = crate::json::request_from_json(request_json).map_err(to_js_error)?;
+    let pczt = crate::propose_transaction(inputs, request, change_address).map_err(to_js_error)?;
+    Ok(crate::serialize_pczt(&pczt))
+}
+
+/// Adds the Orchard proofs to a PCZT
+#[wasm_bindgen(js_name = proveTransaction)]
+pub fn prove_transaction(pczt: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let pczt = crate::prove_transaction(parse(pczt)?).map_err(to_js_error)?;
+    Ok(crate::serialize_pczt(&pczt))
+}
+
+/// Signs a transparent input with its 32-byte secp256k1 secret key
+#[wasm_bindgen(js_name = signTransaction)]
+pub fn sign_transaction(pczt: &[u8], input_index: usize, secret_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let secret_key: &[u8; 32] = secret_key.try_into()
+        .map_err(|_| to_js_error(crate::error::SignatureError::InvalidFormat))?;
+    let pczt = crate::sign_transaction(parse(pczt)?, input_index, secret_key).map_err(to_js_error)?;
+    Ok(crate::serialize_pczt(&pczt))
+}
+
+/// Combines PCZTs (an array of `Uint8Array`s) signed or proved in parallel
+#[wasm_bindgen]
+pub fn combine(pczts: js_sys::Array) -> Result<Vec<u8>, JsValue> {
+    let pczts = pczts.iter()
+        .map(|bytes| parse(&js_sys::Uint8Array::new(&bytes).to_vec()))
+        .collect::<Result<Vec<_>, _>>()?;
+    let pczt = crate::combine(pczts).map_err(to_js_error)?;
+    Ok(crate::serialize_pczt(&pczt))
+}
+
+/// Finalizes a proved and signed PCZT and returns the raw transaction
+#[wasm_bindgen(js_name = finalizeAndExtract)]
+pub fn finalize_and_extract(pczt: &[u8]) -> Result<Vec<u8>, JsValue> {
+    crate::finalize_and_extract(parse(pczt)?).map_err(to_js_error)
+}
+
+/// JSON summary of a PCZT's inputs, outputs and fee (see `json::summary_to_json`)
+#[wasm_bindgen(js_name = summarizePczt)]
+pub fn summarize_pczt(pczt: &[u8]) -> Result<String, JsValue> {
+    Ok(crate::json::summary_to_json(&crate::summarize_pczt(&parse(pczt)?)))
+}
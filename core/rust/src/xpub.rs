@@ -0,0 +1,239 @@
+//! Extended public keys for watch-only input derivation
+//!
+//! Treasury tooling exports account keys with a mix of SLIP-0132 version prefixes
+//! (`xpub`, `ypub`, `zpub`, their multisig `Ypub`/`Zpub` forms and testnet
+//! `tpub`/`upub`/`vpub`...). The prefix only tells Bitcoin wallets which script type
+//! to use; Zcash transparent inputs are always P2PKH, so every variant is normalized
+//! to the same key material and only its network is kept.
+
+use std::collections::HashMap;
+
+use hmac::{Hmac, Mac};
+use secp256k1::{PublicKey, Scalar, Secp256k1};
+use sha2::Sha512;
+use zcash_protocol::consensus::NetworkType;
+use zcash_transparent::address::TransparentAddress;
+
+use crate::error::ParseError;
+use crate::types::{DiscoveredInput, TransparentInput};
+use crate::UtxoSource;
+
+/// BIP-32 `xpub` version bytes (mainnet)
+pub const XPUB_VERSION: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+
+/// BIP-32 `tpub` version bytes (testnet)
+pub const TPUB_VERSION: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+/// SLIP-0132 public key version bytes and the network each belongs to
+const PUBLIC_VERSIONS: [([u8; 4], NetworkType); 10] = [
+    (XPUB_VERSION, NetworkType::Main),
+    ([0x04, 0x9d, 0x7c, 0xb2], NetworkType::Main), // ypub
+    ([0x02, 0x95, 0xb4, 0x3f], NetworkType::Main), // Ypub
+    ([0x04, 0xb2, 0x47, 0x46], NetworkType::Main), // zpub
+    ([0x02, 0xaa, 0x7e, 0xd3], NetworkType::Main), // Zpub
+    (TPUB_VERSION, NetworkType::Test),
+    ([0x04, 0x4a, 0x52, 0x62], NetworkType::Test), // upub
+    ([0x02, 0x42, 0x89, 0xef], NetworkType::Test), // Upub
+    ([0x04, 0x5f, 0x1c, 0xf6], NetworkType::Test), // vpub
+    ([0x02, 0x57, 0x54, 0x83], NetworkType::Test), // Vpub
+];
+
+/// First hardened child index
+const HARDENED: u32 = 0x8000_0000;
+
+/// A BIP-32 extended public key, independent of its SLIP-0132 prefix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtendedPubKey {
+    pub network: NetworkType,
+    pub depth: u8,
+    pub parent_fingerprint: [u8; 4],
+    pub child_number: u32,
+    pub chain_code: [u8; 32],
+    pub public_key: PublicKey,
+}
+
+impl ExtendedPubKey {
+    /// Parses any SLIP-0132 public variant (`xpub`, `ypub`, `zpub`, `tpub`, ...).
+    pub fn parse(encoded: &str) -> Result<Self, ParseError> {
+        let data = bs58::decode(encoded.trim())
+            .with_check(None)
+            .into_vec()
+            .map_err(|e| ParseError::InvalidFormat(format!("Invalid extended key: {}", e)))?;
+        if data.len() != 78 {
+            return Err(ParseError::InvalidFormat(format!("Extended key has {} bytes, expected 78", data.len())));
+        }
+
+        let version: [u8; 4] = data[0..4].try_into().expect("4 bytes");
+        let network = PUBLIC_VERSIONS.iter()
+            .find(|(known, _)| *known == version)
+            .map(|(_, network)| *network)
+            .ok_or_else(|| ParseError::InvalidFormat(
+                "Not an extended public key (private keys and unknown prefixes are refused)".to_string()
+            ))?;
+
+        let public_key = PublicKey::from_slice(&data[45..78])
+            .map_err(|e| ParseError::InvalidFormat(format!("Invalid extended key pubkey: {}", e)))?;
+
+        Ok(ExtendedPubKey {
+            network,
+            depth: data[4],
+            parent_fingerprint: data[5..9].try_into().expect("4 bytes"),
+            child_number: u32::from_be_bytes(data[9..13].try_into().expect("4 bytes")),
+            chain_code: data[13..45].try_into().expect("32 bytes"),
+            public_key,
+        })
+    }
+
+    /// Encodes the key with the plain BIP-32 prefix for its network (`xpub` or `tpub`).
+    pub fn to_normalized_string(&self) -> String {
+        let version = match self.network {
+            NetworkType::Main => XPUB_VERSION,
+            _ => TPUB_VERSION,
+        };
+
+        let mut data = Vec::with_capacity(78);
+        data.extend_from_slice(&version);
+        data.push(self.depth);
+        data.extend_from_slice(&self.parent_fingerprint);
+        data.extend_from_slice(&self.child_number.to_be_bytes());
+        data.extend_from_slice(&self.chain_code);
+        data.extend_from_slice(&self.public_key.serialize());
+        bs58::encode(data).with_check().into_string()
+    }
+
+    /// BIP-32 fingerprint of this key (first 4 bytes of its HASH160)
+    pub fn fingerprint(&self) -> [u8; 4] {
+        crate::hash160(&self.public_key.serialize())[..4].try_into().expect("4 bytes")
+    }
+
+    /// Derives a non-hardened child key, or None for a hardened `index` (which needs
+    /// the private key) or the negligible chance of an invalid child.
+    pub fn derive_child(&self, index: u32) -> Option<ExtendedPubKey> {
+        if index >= HARDENED {
+            return None;
+        }
+
+        let mut mac = Hmac::<Sha512>::new_from_slice(&self.chain_code).expect("HMAC takes any key length");
+        mac.update(&self.public_key.serialize());
+        mac.update(&index.to_be_bytes());
+        let output = mac.finalize().into_bytes();
+
+        let tweak = Scalar::from_be_bytes(output[..32].try_into().expect("32 bytes")).ok()?;
+        let public_key = self.public_key.add_exp_tweak(&Secp256k1::verification_only(), &tweak).ok()?;
+
+        Some(ExtendedPubKey {
+            network: self.network,
+            depth: self.depth.checked_add(1)?,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code: output[32..].try_into().expect("32 bytes"),
+            public_key,
+        })
+    }
+
+    /// Derives the key at a path of non-hardened indices relative to this key,
+    /// e.g. `[0, 5]` for the sixth external address of an account key.
+    pub fn derive_path(&self, path: &[u32]) -> Option<ExtendedPubKey> {
+        path.iter().try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+
+    /// Builds a resolver for `json::inputs_from_listunspent_json` from an account key.
+    ///
+    /// Derives the first `gap_limit` keys of the external (`0/i`) and change (`1/i`)
+    /// chains and maps their P2PKH addresses to their public keys.
+    pub fn pubkey_resolver(&self, gap_limit: u32) -> impl Fn(&str) -> Option<PublicKey> {
+        let mut keys = HashMap::new();
+        for chain in [0, 1] {
+            let Some(chain_key) = self.derive_child(chain) else { continue };
+            for index in 0..gap_limit {
+                let Some(child) = chain_key.derive_child(index) else { continue };
+                if let Some((address, _)) = child.p2pkh() {
+                    keys.insert(address, child.public_key);
+                }
+            }
+        }
+        move |address| keys.get(address).copied()
+    }
+
+    /// P2PKH address and raw script of this key
+    fn p2pkh(&self) -> Option<(String, Vec<u8>)> {
+        let script = TransparentAddress::from_pubkey(&self.public_key).script().into();
+        let script = crate::types::script_to_raw(&script).ok()?;
+        let address = crate::script_to_address(&script, self.network)?;
+        Some((address, script))
+    }
+}
+
+/// Finds the UTXOs of an account key by scanning its external (`0/i`) and change
+/// (`1/i`) chains.
+///
+/// Addresses are queried `gap_limit` at a time, and a chain is done once the last
+/// `gap_limit` addresses hold no UTXO. Only unspent outputs are visible, so an
+/// address that was used and emptied counts toward the gap.
+///
+/// # Arguments
+/// * `xpub` - The account key, e.g. from `ExtendedPubKey::parse`
+/// * `gap_limit` - Number of consecutive unfunded addresses that ends a chain
+/// * `source` - Where to look up UTXOs (e.g. `experimental::lightwalletd::LightwalletdUtxoChecker`)
+///
+/// # Returns
+/// * `Result<Vec<DiscoveredInput>, String>` - Every UTXO found with its key path,
+///   ordered by chain then index, or the source's error
+pub fn discover_utxos(
+    xpub: &ExtendedPubKey,
+    gap_limit: u32,
+    source: &dyn UtxoSource,
+) -> Result<Vec<DiscoveredInput>, String> {
+    if gap_limit == 0 {
+        return Err("Gap limit must be at least 1".to_string());
+    }
+
+    let mut discovered = Vec::new();
+    for chain in [0, 1] {
+        let Some(chain_key) = xpub.derive_child(chain) else { continue };
+        let mut next = 0u32;
+        let mut unused = 0u32;
+
+        while unused < gap_limit && next < HARDENED {
+            let end = next.saturating_add(gap_limit).min(HARDENED);
+            let window: HashMap<String, (u32, PublicKey, Vec<u8>)> = (next..end)
+                .filter_map(|index| {
+                    let child = chain_key.derive_child(index)?;
+                    let (address, script) = child.p2pkh()?;
+                    Some((address, (index, child.public_key, script)))
+                })
+                .collect();
+
+            let addresses: Vec<String> = window.keys().cloned().collect();
+            let mut found: Vec<(u32, DiscoveredInput)> = source.address_utxos(&addresses)?
+                .into_iter()
+                .filter_map(|utxo| {
+                    let (index, pubkey, script) = window.get(&utxo.address)?;
+                    Some((*index, DiscoveredInput {
+                        input: TransparentInput {
+                            pubkey: *pubkey,
+                            txid: utxo.txid,
+                            vout: utxo.vout,
+                            amount: utxo.amount,
+                            script_pubkey: script.clone(),
+                            height: utxo.height,
+                            redeem_script: None,
+                            derivation_path: None,
+                        },
+                        path: vec![chain, *index],
+                    }))
+                })
+                .collect();
+            found.sort_by_key(|(index, utxo)| (*index, utxo.input.txid, utxo.input.vout));
+
+            unused = match found.iter().map(|(index, _)| *index).max() {
+                Some(last_used) => end - last_used - 1,
+                None => unused + (end - next),
+            };
+            discovered.extend(found.into_iter().map(|(_, found)| found));
+            next = end;
+        }
+    }
+
+    Ok(discovered)
+}
@@ -0,0 +1,51 @@
+//! Tests for the parsed address cache (its own binary, as the cache is process-wide)
+
+use t2z::address_cache::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+#[test]
+fn test_address_cache() {
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC]);
+
+    // Off by default
+    propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    assert_eq!(address_cache_len(), 0);
+
+    set_address_cache_capacity(2);
+    let first = propose_transaction(&inputs, shielded_payment_request(), Some(addresses::TRANSPARENT_2.to_string()))
+        .expect("Failed to propose");
+    assert_eq!(address_cache_len(), 2);
+
+    // Cached addresses give the same outputs as freshly parsed ones
+    let second = propose_transaction(&inputs, shielded_payment_request(), Some(addresses::TRANSPARENT_2.to_string()))
+        .expect("Failed to propose");
+    assert_eq!(first.orchard().actions().len(), second.orchard().actions().len());
+    assert_eq!(first.transparent().outputs()[0].script_pubkey(), second.transparent().outputs()[0].script_pubkey());
+
+    // Invalid addresses are not cached, and a full cache evicts to stay at capacity
+    assert!(address_to_script("not an address").is_none());
+    assert_eq!(address_to_script(addresses::TRANSPARENT).as_deref(), first_output_script(&inputs).as_deref());
+    assert_eq!(address_cache_len(), 2);
+
+    clear_address_cache();
+    assert_eq!(address_cache_len(), 0);
+    address_to_script(addresses::TRANSPARENT);
+    assert_eq!(address_cache_len(), 1);
+
+    set_address_cache_capacity(0);
+    address_to_script(addresses::TRANSPARENT_2);
+    assert_eq!(address_cache_len(), 0);
+
+    println!("✅ parsed addresses are cached up to the capacity");
+}
+
+/// Script of the payment output of `simple_payment_request`, which pays `addresses::TRANSPARENT`
+fn first_output_script(inputs: &[u8]) -> Option<Vec<u8>> {
+    let pczt = propose_transaction(inputs, simple_payment_request(), None).ok()?;
+    pczt.transparent().outputs().iter()
+        .find(|output| *output.value() == amounts::SMALL)
+        .map(|output| output.script_pubkey().clone())
+}
@@ -0,0 +1,74 @@
+//! Tests for splitting shielded payouts into several transactions
+
+use t2z::experimental::batching::*;
+use t2z::error::ProposalError;
+use t2z::types::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+fn payout(count: usize) -> TransactionRequest {
+    TransactionRequest::new(
+        (0..count).map(|_| Payment::new(addresses::unified_orchard(), amounts::MEDIUM)).collect()
+    )
+}
+
+#[test]
+fn test_plan_orchard_batches() {
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC, amounts::ONE_ZEC, amounts::ONE_ZEC]);
+    let plan = plan_orchard_batches(&inputs, &payout(5), Some(addresses::TRANSPARENT_2), 4)
+        .expect("Failed to plan");
+
+    // Four payments, then one padded to two actions
+    assert_eq!(plan.batches.len(), 2);
+    assert_eq!(plan.batches[0].payment_indices, vec![0, 1, 2, 3]);
+    assert_eq!(plan.batches[0].orchard_actions, 4);
+    assert_eq!(plan.batches[1].orchard_actions, 2);
+    assert_eq!(plan.largest_batch_actions(), 4);
+    assert_eq!(plan.single_transaction_actions, 5);
+
+    // Each batch takes only the inputs it needs
+    assert_eq!(plan.batches[0].input_indices, vec![0]);
+    assert_eq!(plan.batches[1].input_indices, vec![1]);
+    assert_eq!(plan.unused_input_indices, vec![2]);
+
+    // (1 transparent + 4 Orchard) and (1 + 2) actions, against (1 + 5) for everything
+    // at once from the first input: the second batch repeats the transparent action
+    // and pads its bundle
+    assert_eq!(plan.batches[0].fee, 25_000);
+    assert_eq!(plan.batches[1].fee, 15_000);
+    assert_eq!(plan.single_transaction_fee, 30_000);
+    assert_eq!(plan.extra_fee(), 10_000);
+
+    let (plan, pczts) = propose_orchard_batches(&inputs, &payout(5), Some(addresses::TRANSPARENT_2), 4)
+        .expect("Failed to propose");
+    for (batch, pczt) in plan.batches.iter().zip(&pczts) {
+        assert_eq!(pczt.orchard().actions().len(), batch.orchard_actions);
+        assert_eq!(conventional_fee(pczt), batch.fee);
+    }
+
+    println!("✅ payout split into proof-sized batches");
+}
+
+#[test]
+fn test_plan_orchard_batches_errors() {
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC]);
+    assert!(matches!(
+        plan_orchard_batches(&inputs, &payout(3), None, 1),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+
+    // The inputs run out before every batch is covered
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::LARGE]);
+    let request = TransactionRequest::new(vec![
+        Payment::new(addresses::unified_orchard(), amounts::LARGE - amounts::MEDIUM),
+        Payment::new(addresses::unified_orchard(), amounts::MEDIUM),
+    ]);
+    assert!(matches!(
+        plan_orchard_batches(&inputs, &request, None, 2),
+        Err(ProposalError::InsufficientFunds { .. })
+    ));
+
+    println!("✅ unplannable batches are rejected");
+}
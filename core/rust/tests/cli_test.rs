@@ -0,0 +1,95 @@
+//! Tests for the `t2z-cli` commands
+#![cfg(feature = "cli")]
+
+use std::path::{Path, PathBuf};
+
+use t2z::types::parse_transparent_inputs;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+/// Runs a command line, returning what it printed
+fn run(args: &[&str]) -> Result<String, String> {
+    let mut out = Vec::new();
+    t2z::cli::run(args.iter().map(|arg| arg.to_string()), &mut out).map_err(|e| e.to_string())?;
+    Ok(String::from_utf8(out).expect("output is UTF-8"))
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().expect("temp paths are UTF-8")
+}
+
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("t2z-cli-{}-{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn test_cli_flow() {
+    let dir = temp_dir("flow");
+    let inputs_path = dir.join("inputs.json");
+    let request_path = dir.join("request.json");
+    let inputs = parse_transparent_inputs(&sample_transparent_inputs()).unwrap();
+    std::fs::write(&inputs_path, t2z::json::inputs_to_json(&inputs)).unwrap();
+    std::fs::write(&request_path, t2z::json::request_to_json(&simple_payment_request())).unwrap();
+
+    let proposal = dir.join("proposal.pczt");
+    run(&["propose", path_str(&inputs_path), path_str(&request_path), "--out", path_str(&proposal)])
+        .expect("Failed to propose");
+
+    let summary: serde_json::Value = serde_json::from_str(&run(&["inspect", path_str(&proposal)]).unwrap()).unwrap();
+    assert_eq!(summary["fee"], 10_000);
+
+    // The printed sighash is the library's, and a signature over it appends
+    let sighash_hex = run(&["sighash", path_str(&proposal), "0"]).unwrap();
+    let sighash = get_sighash(&load_pczt(&proposal).unwrap(), 0).unwrap();
+    assert_eq!(sighash_hex.trim(), hex::encode(sighash.as_bytes()));
+
+    let secp = secp256k1::Secp256k1::signing_only();
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+    let signature = secp.sign_ecdsa(&secp256k1::Message::from_digest(*sighash.as_bytes()), &sk).serialize_compact();
+    let signed = dir.join("signed.pczt");
+    run(&["append-sig", path_str(&proposal), "0", &hex::encode(signature), "--out", path_str(&signed)])
+        .expect("Failed to append the signature");
+
+    // Transactions are hex on stdout and in files alike
+    let printed = run(&["extract", path_str(&signed)]).unwrap();
+    let tx_path = dir.join("tx.hex");
+    run(&["extract", path_str(&signed), "--out", path_str(&tx_path)]).unwrap();
+    assert_eq!(std::fs::read_to_string(&tx_path).unwrap(), printed);
+    let tx_bytes = finalize_and_extract(load_pczt(&signed).unwrap()).unwrap();
+    assert_eq!(printed.trim(), hex::encode(tx_bytes));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_cli_errors() {
+    let dir = temp_dir("errors");
+    let proposal = dir.join("proposal.pczt");
+    save_pczt(&proposal, &propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None).unwrap())
+        .unwrap();
+
+    assert!(run(&[]).unwrap_err().starts_with("Usage:"));
+    assert!(run(&["frobnicate"]).unwrap_err().starts_with("Unknown command: frobnicate"));
+    assert!(run(&["help"]).unwrap().starts_with("Usage:"));
+
+    assert_eq!(run(&["inspect"]).unwrap_err(), "Expected 1 arguments, got 0");
+    assert_eq!(run(&["prove", path_str(&proposal)]).unwrap_err(), "Missing --out");
+    assert_eq!(run(&["sighash", path_str(&proposal), "first"]).unwrap_err(), "Invalid input index: first");
+    assert_eq!(
+        run(&["append-sig", path_str(&proposal), "0", "abcd", "--out", "unused.pczt"]).unwrap_err(),
+        "Signature must be 64 bytes"
+    );
+    assert!(run(&["inspect", "missing.pczt"]).unwrap_err().starts_with("Failed to load missing.pczt"));
+
+    // A raw transaction file is refused before connecting
+    let raw = dir.join("tx.bin");
+    std::fs::write(&raw, [0x05, 0x00, 0x00, 0x80]).unwrap();
+    let error = run(&["broadcast", path_str(&raw), "--server", "http://127.0.0.1:1"]).unwrap_err();
+    assert!(error.contains("is not a hex transaction"), "{}", error);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,101 @@
+use t2z::experimental::coin_selection::select_inputs;
+use t2z::error::ProposalError;
+use t2z::types::{
+    parse_transparent_inputs, serialize_transparent_inputs, CoinSelection, DustPolicy, Payment, TransactionRequest,
+    DEFAULT_DUST_THRESHOLD,
+};
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+const POOL: [u64; 5] = [50_000, 500_000, 110_000, 60_000, 20_000];
+
+#[test]
+fn test_selection_strategies() {
+    let pool = parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&POOL)).unwrap();
+    let select = |strategy| select_inputs(&pool, &simple_payment_request().with_coin_selection(strategy), None);
+
+    assert_eq!(select(CoinSelection::All).unwrap(), vec![0, 1, 2, 3, 4]);
+    assert_eq!(select(CoinSelection::LargestFirst).unwrap(), vec![1]);
+    // 20k + 50k + 60k covers 100k plus the 3-input fee, with change
+    assert_eq!(select(CoinSelection::SmallestFirst).unwrap(), vec![0, 3, 4]);
+    // 110k pays 100k plus the 10k fee exactly, without change
+    assert_eq!(select(CoinSelection::BranchAndBound).unwrap(), vec![2]);
+
+    println!("✅ coin selection strategies pick the expected inputs");
+}
+
+#[test]
+fn test_branch_and_bound_falls_back_to_largest_first() {
+    let pool = parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&[500_000, 300_000])).unwrap();
+    let request = simple_payment_request().with_coin_selection(CoinSelection::BranchAndBound);
+
+    assert_eq!(select_inputs(&pool, &request, None).unwrap(), vec![0]);
+
+    println!("✅ branch-and-bound falls back when no changeless set exists");
+}
+
+#[test]
+fn test_branch_and_bound_with_waste() {
+    let data = sample_transparent_inputs_with_amounts(&[113_000, 500_000]);
+    let request = simple_payment_request().with_coin_selection(CoinSelection::BranchAndBound);
+
+    // 113k leaves 3k of dust over the 10k fee, folded into the fee by the default policy
+    let pczt = propose_transaction(&data, request.clone(), None).expect("Failed to propose");
+    assert_eq!(pczt.transparent().inputs().len(), 1);
+    assert_eq!(*pczt.transparent().inputs()[0].value(), 113_000);
+    assert_eq!(pczt.transparent().outputs().len(), 1);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(13_000));
+    let signed = sign_transaction(pczt, 0, &[1u8; 32]).expect("Failed to sign");
+    finalize_and_extract(signed).expect("Failed to extract");
+
+    // Without folding, the waste would be an unpayable fee, so the 500k input is
+    // spent with change instead
+    let request = request.with_dust_policy(DustPolicy::Reject { threshold: DEFAULT_DUST_THRESHOLD });
+    let pczt = propose_transaction(&data, request, None).expect("Failed to propose");
+    assert_eq!(*pczt.transparent().inputs()[0].value(), 500_000);
+    assert_eq!(pczt.transparent().outputs().len(), 2);
+
+    println!("✅ branch-and-bound only accepts waste the proposal can pay");
+}
+
+#[test]
+fn test_propose_selects_from_pool() {
+    let data = sample_transparent_inputs_with_amounts(&POOL);
+
+    let request = simple_payment_request().with_coin_selection(CoinSelection::BranchAndBound);
+    let pczt = propose_transaction(&data, request, None).expect("Failed to propose");
+    let inputs = pczt.transparent().inputs();
+    assert_eq!(inputs.len(), 1);
+    assert_eq!(*inputs[0].value(), 110_000);
+    assert_eq!(pczt.transparent().outputs().len(), 1);
+
+    // The pool can't cover the request
+    let request = TransactionRequest::new(vec![Payment::new(addresses::TRANSPARENT.to_string(), 800_000)])
+        .with_coin_selection(CoinSelection::LargestFirst);
+    assert!(matches!(propose_transaction(&data, request, None), Err(ProposalError::InsufficientFunds { .. })));
+
+    println!("✅ propose_transaction spends only the selected inputs");
+}
+
+#[test]
+fn test_selection_skips_unconfirmed_inputs() {
+    let mut pool = parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&POOL)).unwrap();
+    for input in pool.iter_mut() {
+        input.height = Some(3_000_000);
+    }
+    pool[1].height = Some(3_000_095);
+    let data = serialize_transparent_inputs(&pool).unwrap();
+
+    let mut request = simple_payment_request()
+        .with_min_confirmations(10)
+        .with_coin_selection(CoinSelection::LargestFirst);
+    request.target_height = Some(3_000_100);
+
+    // The 500k input is too recent, so 110k is the largest eligible one
+    let pczt = propose_transaction(&data, request, None).expect("Failed to propose");
+    assert_eq!(*pczt.transparent().inputs()[0].value(), 110_000);
+
+    println!("✅ coin selection only considers confirmed inputs");
+}
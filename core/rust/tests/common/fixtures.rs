@@ -86,6 +86,11 @@ pub fn payment_with_memo() -> Payment {
 /// Sample transparent input data
 /// Creates a realistic test input with proper serialization format
 pub fn sample_transparent_inputs() -> Vec<u8> {
+    sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC])
+}
+
+/// Sample transparent inputs with the given amounts, all spendable by the test key
+pub fn sample_transparent_inputs_with_amounts(input_amounts: &[u64]) -> Vec<u8> {
     use t2z::types::{TransparentInput, serialize_transparent_inputs};
     use zcash_transparent::address::TransparentAddress;
 
@@ -112,23 +117,29 @@ pub fn sample_transparent_inputs() -> Vec<u8> {
         script_with_prefix
     };
 
-    // Create a sample UTXO input with 1 ZEC
     // Use a realistic-looking txid (sha256 of some test data)
     use sha2::{Sha256, Digest};
     let mut hasher = Sha256::new();
     hasher.update(b"test transaction for t2z");
     let txid: [u8; 32] = hasher.finalize().into();
 
-    let input = TransparentInput {
-        pubkey,
-        txid,  // Valid-looking txid
-        vout: 0,
-        amount: amounts::ONE_ZEC, // 1 ZEC
-        script_pubkey: script_bytes,
-    };
+    // One UTXO per amount, spending consecutive outputs of the same txid
+    let inputs: Vec<TransparentInput> = input_amounts.iter()
+        .enumerate()
+        .map(|(vout, &amount)| TransparentInput {
+            pubkey,
+            txid,  // Valid-looking txid
+            vout: vout as u32,
+            amount,
+            script_pubkey: script_bytes.clone(),
+            height: None,
+            redeem_script: None,
+            derivation_path: None,
+        })
+        .collect();
 
     // Serialize using the standard format
-    serialize_transparent_inputs(&[input])
+    serialize_transparent_inputs(&inputs).unwrap()
 }
 
 /// Test-only helper to create a PCZT with realistic transparent inputs
@@ -269,3 +280,106 @@ mod tests {
         println!("Mainnet unified address: {}", addresses::unified_orchard_mainnet());
     }
 }
+
+/// Flips a byte of input 0's pubkey preimage, as a malicious party might
+pub fn tamper_pubkey_preimage(pczt: &pczt::Pczt) -> pczt::Pczt {
+    let pubkey = t2z::get_input_pubkey(pczt, 0).expect("input 0 has a pubkey");
+    let mut bytes = t2z::serialize_pczt(pczt);
+    let at = bytes.windows(33).position(|window| window == pubkey).expect("preimage is serialized");
+    bytes[at + 32] ^= 1;
+    t2z::parse_pczt(&bytes).expect("still a well-formed PCZT")
+}
+
+/// Inputs 0 and 2 belong to key [1; 32], input 1 to key [2; 32]
+pub fn two_signer_inputs() -> (Vec<u8>, [secp256k1::SecretKey; 2]) {
+    use t2z::types::{script_to_raw, serialize_transparent_inputs, TransparentInput};
+    use zcash_transparent::address::TransparentAddress;
+
+    let secp = secp256k1::Secp256k1::new();
+    let keys = [
+        secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap(),
+        secp256k1::SecretKey::from_slice(&[2u8; 32]).unwrap(),
+    ];
+
+    let inputs: Vec<TransparentInput> = [0usize, 1, 0].iter()
+        .enumerate()
+        .map(|(vout, &key)| {
+            let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &keys[key]);
+            let script = TransparentAddress::from_pubkey(&pubkey).script().into();
+            TransparentInput {
+                pubkey,
+                txid: [7u8; 32],
+                vout: vout as u32,
+                amount: amounts::LARGE,
+                script_pubkey: script_to_raw(&script).unwrap(),
+                height: None,
+                redeem_script: None,
+                derivation_path: None,
+            }
+        })
+        .collect();
+
+    (serialize_transparent_inputs(&inputs).unwrap(), keys)
+}
+
+/// Secret key of key `seed` (1-3) of `two_of_three_redeem_script`
+pub fn multisig_secret(seed: u8) -> [u8; 32] {
+    [seed; 32]
+}
+
+/// `OP_2 <key 1> <key 2> <key 3> OP_3 OP_CHECKMULTISIG`
+pub fn two_of_three_redeem_script() -> Vec<u8> {
+    let secp = secp256k1::Secp256k1::new();
+    let mut script = vec![0x52];
+    for seed in 1..=3 {
+        let sk = secp256k1::SecretKey::from_slice(&multisig_secret(seed)).unwrap();
+        script.push(0x21);
+        script.extend_from_slice(&secp256k1::PublicKey::from_secret_key(&secp, &sk).serialize());
+    }
+    script.extend_from_slice(&[0x53, 0xae]);
+    script
+}
+
+/// The P2SH scriptPubKey paying to `redeem_script`
+pub fn p2sh_script(redeem_script: &[u8]) -> Vec<u8> {
+    use ripemd::Ripemd160;
+    use sha2::{Digest, Sha256};
+
+    let hash = Ripemd160::digest(Sha256::digest(redeem_script));
+    [&[0xa9, 0x14][..], &hash[..], &[0x87]].concat()
+}
+
+/// `count` one-ZEC UTXOs paying to `redeem_script`
+pub fn p2sh_inputs(redeem_script: &[u8], count: u32) -> Vec<t2z::types::TransparentInput> {
+    let secp = secp256k1::Secp256k1::new();
+    let sk = secp256k1::SecretKey::from_slice(&multisig_secret(1)).unwrap();
+    (0..count)
+        .map(|vout| t2z::types::TransparentInput {
+            pubkey: secp256k1::PublicKey::from_secret_key(&secp, &sk),
+            txid: [9u8; 32],
+            vout,
+            amount: amounts::ONE_ZEC,
+            script_pubkey: p2sh_script(redeem_script),
+            height: None,
+            redeem_script: Some(redeem_script.to_vec()),
+            derivation_path: None,
+        })
+        .collect()
+}
+
+/// Value and memo of each Orchard output of an extracted transaction that `ovk`
+/// recovers, as the sender's wallet would
+pub fn recover_orchard_outputs(tx_bytes: &[u8], ovk: [u8; 32]) -> Vec<(u64, [u8; 512])> {
+    use zcash_primitives::transaction::Transaction;
+    use zcash_protocol::consensus::BranchId;
+
+    let tx = Transaction::read(tx_bytes, BranchId::Nu5).expect("Failed to parse transaction");
+    let ovk = orchard::keys::OutgoingViewingKey::from(ovk);
+    let Some(bundle) = tx.orchard_bundle() else {
+        return Vec::new();
+    };
+    (0..bundle.actions().len())
+        .filter_map(|index| bundle.recover_output_with_ovk(index, &ovk))
+        .map(|(note, _, memo)| (note.value().inner(), memo))
+        .collect()
+}
@@ -0,0 +1,84 @@
+use t2z::cosigning::CoSigningCoordinator;
+use t2z::error::CoSigningError;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+fn pubkey_of(sk: &secp256k1::SecretKey) -> [u8; 33] {
+    secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), sk).serialize()
+}
+
+fn sign(sk: &secp256k1::SecretKey, sighash: [u8; 32]) -> [u8; 64] {
+    let secp = secp256k1::Secp256k1::new();
+    secp.sign_ecdsa(&secp256k1::Message::from_digest(sighash), sk).serialize_compact()
+}
+
+#[test]
+fn test_cosigning_packets_and_completion() {
+    let (inputs, [alice, bob]) = two_signer_inputs();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+    let (alice_pk, bob_pk) = (pubkey_of(&alice), pubkey_of(&bob));
+
+    let mut coordinator = CoSigningCoordinator::new(proved, &[alice_pk, bob_pk]).expect("Failed to coordinate");
+
+    // Each packet carries only its signer's inputs
+    let packets = coordinator.packets();
+    assert_eq!(packets[0].input_indices, vec![0, 2]);
+    assert_eq!(packets[1].input_indices, vec![1]);
+    assert_eq!(packets[1].batch.inputs.len(), 1);
+    assert_eq!(packets[1].batch.fingerprint, pczt_fingerprint(coordinator.pczt()));
+
+    // Signatures for another signer's input are refused
+    let bob_sig = sign(&bob, packets[1].batch.inputs[0].sighash);
+    assert!(matches!(
+        coordinator.add_signatures(&alice_pk, &[(1, bob_sig)]),
+        Err(CoSigningError::WrongSigner { index: 1 })
+    ));
+
+    // Alice returns signatures, Bob returns a signed PCZT
+    let alice_sigs: Vec<(usize, [u8; 64])> = packets[0].input_indices.iter()
+        .zip(&packets[0].batch.inputs)
+        .map(|(index, input)| (*index, sign(&alice, input.sighash)))
+        .collect();
+    coordinator.add_signatures(&alice_pk, &alice_sigs).expect("Failed to add signatures");
+    assert_eq!(coordinator.missing_inputs(), vec![1]);
+    assert_eq!(coordinator.pending_signers(), vec![bob_pk]);
+
+    let bob_pczt = append_signature(coordinator.pczt().clone(), 1, bob_sig).expect("Failed to sign");
+    coordinator.add_signed_pczt(bob_pczt).expect("Failed to merge");
+    assert!(coordinator.is_complete());
+
+    let signed = coordinator.finish().expect("Signing should be complete");
+    finalize_and_extract(signed).expect("Failed to finalize");
+
+    println!("✅ co-signing coordinator collects every signer's signatures");
+}
+
+#[test]
+fn test_cosigning_rejects_unexpected_signers() {
+    let (inputs, [alice, bob]) = two_signer_inputs();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let (alice_pk, bob_pk) = (pubkey_of(&alice), pubkey_of(&bob));
+    let carol_pk = pubkey_of(&secp256k1::SecretKey::from_slice(&[3u8; 32]).unwrap());
+
+    // Bob's input is not covered
+    assert!(matches!(
+        CoSigningCoordinator::new(pczt.clone(), &[alice_pk]),
+        Err(CoSigningError::UnexpectedSigner { index: 1 })
+    ));
+
+    // Carol owns nothing
+    assert!(matches!(
+        CoSigningCoordinator::new(pczt.clone(), &[alice_pk, bob_pk, carol_pk]),
+        Err(CoSigningError::SignerWithoutInputs(_))
+    ));
+
+    // Finishing early reports the unsigned inputs
+    let coordinator = CoSigningCoordinator::new(pczt, &[alice_pk, bob_pk]).expect("Failed to coordinate");
+    assert!(coordinator.packet(&carol_pk).is_err());
+    assert!(matches!(coordinator.finish(), Err(CoSigningError::Incomplete { missing }) if missing == vec![0, 1, 2]));
+
+    println!("✅ co-signing coordinator rejects unexpected signers");
+}
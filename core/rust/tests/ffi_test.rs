@@ -74,7 +74,21 @@ fn test_transaction_request_with_memo() {
         assert_eq!(result, ResultCode::Success);
         assert!(!request_out.is_null());
 
+        // A raw memo replaces the text memo; one that doesn't fit is refused
+        let raw = [0xFFu8; 512];
+        assert_eq!(pczt_transaction_request_set_payment_memo_bytes(request_out, 0, raw.as_ptr(), raw.len()), ResultCode::Success);
+        assert_eq!(pczt_transaction_request_set_payment_memo_bytes(request_out, 0, raw.as_ptr(), 513), ResultCode::ErrorMemo);
+        assert_eq!(pczt_transaction_request_set_payment_memo_bytes(request_out, 1, raw.as_ptr(), raw.len()), ResultCode::ErrorProposal);
+
         pczt_transaction_request_free(request_out);
+
+        // A memo that isn't UTF-8 is refused even in lenient mode, instead of the
+        // payment going out without it
+        let invalid = CString::new(vec![b'h', 0xFF, b'i']).unwrap();
+        let payment = CPayment { memo: invalid.as_ptr(), label: ptr::null(), ..payment };
+        let mut request_out: *mut TransactionRequestHandle = ptr::null_mut();
+        assert_eq!(pczt_transaction_request_new(&payment, 1, &mut request_out), ResultCode::ErrorInvalidUtf8);
+        assert!(request_out.is_null());
     }
 }
 
@@ -111,6 +125,173 @@ fn test_pczt_serialization_ffi() {
     }
 }
 
+#[test]
+fn test_combine_parallel_prover_and_signer() {
+    let pczt = t2z::propose_transaction(&sample_transparent_inputs(), shielded_payment_request(), None)
+        .expect("Failed to propose");
+    let bytes = t2z::serialize_pczt(&pczt);
+
+    unsafe {
+        let parse = |bytes: &[u8]| {
+            let mut handle: *mut PcztHandle = ptr::null_mut();
+            assert_eq!(pczt_parse(bytes.as_ptr(), bytes.len(), &mut handle), ResultCode::Success);
+            handle
+        };
+        let (for_prover, for_signer) = (parse(&bytes), parse(&bytes));
+
+        // Prove on another thread while the main thread signs
+        let for_prover = for_prover as usize;
+        let prover = std::thread::spawn(move || {
+            let mut proved: *mut PcztHandle = ptr::null_mut();
+            assert_eq!(pczt_prove_transaction(for_prover as *mut PcztHandle, &mut proved), ResultCode::Success);
+            proved as usize
+        });
+
+        let mut signed: *mut PcztHandle = ptr::null_mut();
+        assert_eq!(pczt_sign_transparent(for_signer, 0, &[1u8; 32], &mut signed), ResultCode::Success);
+        let proved = prover.join().unwrap() as *mut PcztHandle;
+
+        let handles = [proved, signed];
+        let mut combined: *mut PcztHandle = ptr::null_mut();
+        assert_eq!(pczt_combine(handles.as_ptr(), handles.len(), &mut combined), ResultCode::Success);
+
+        let mut tx_bytes: *mut u8 = ptr::null_mut();
+        let mut tx_len: usize = 0;
+        assert_eq!(pczt_finalize_and_extract(combined, &mut tx_bytes, &mut tx_len), ResultCode::Success);
+        assert!(tx_len > 0);
+        pczt_free_bytes(tx_bytes, tx_len);
+
+        // A null entry is refused; the other handles are still consumed
+        let handles = [parse(&bytes), ptr::null_mut()];
+        let mut combined: *mut PcztHandle = ptr::null_mut();
+        assert_eq!(pczt_combine(handles.as_ptr(), handles.len(), &mut combined), ResultCode::ErrorNullPointer);
+    }
+}
+
+#[test]
+fn test_prove_batch() {
+    let inputs = sample_transparent_inputs();
+    let requests = [shielded_payment_request(), simple_payment_request(), shielded_payment_request()];
+
+    unsafe {
+        let parse = |pczt: &pczt::Pczt| {
+            let bytes = t2z::serialize_pczt(pczt);
+            let mut handle: *mut PcztHandle = ptr::null_mut();
+            assert_eq!(pczt_parse(bytes.as_ptr(), bytes.len(), &mut handle), ResultCode::Success);
+            handle
+        };
+        let handles: Vec<*mut PcztHandle> = requests.into_iter()
+            .map(|request| parse(&t2z::propose_transaction(&inputs, request, None).expect("Failed to propose")))
+            .collect();
+
+        let mut proved = [ptr::null_mut(); 3];
+        let mut results = [ResultCode::ErrorNotImplemented; 3];
+        let result = pczt_prove_batch(handles.as_ptr(), handles.len(), proved.as_mut_ptr(), results.as_mut_ptr());
+        assert_eq!(result, ResultCode::Success);
+        assert_eq!(results, [ResultCode::Success; 3]);
+
+        // Shielded PCZTs come back proved, in input order; the transparent one unchanged
+        for (handle, expect_proof) in proved.into_iter().zip([true, false, true]) {
+            let mut has_proof = false;
+            assert_eq!(pczt_has_orchard_proof(handle, &mut has_proof), ResultCode::Success);
+            assert_eq!(has_proof, expect_proof);
+            pczt_free(handle);
+        }
+
+        // A null entry is refused; the other handles are still consumed
+        let pczt = t2z::propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+        let handles = [parse(&pczt), ptr::null_mut()];
+        let mut proved = [ptr::null_mut(); 2];
+        let result = pczt_prove_batch(handles.as_ptr(), handles.len(), proved.as_mut_ptr(), ptr::null_mut());
+        assert_eq!(result, ResultCode::ErrorNullPointer);
+    }
+}
+
+#[test]
+fn test_byte_buffers() {
+    let pczt = t2z::propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let signed = t2z::sign_transaction(pczt.clone(), 0, &[1u8; 32]).expect("Failed to sign");
+    // Buffers hold exactly what the Rust API returns
+    let expected_tx = t2z::finalize_and_extract(signed.clone()).expect("Failed to finalize");
+
+    unsafe {
+        let parse = |pczt: &pczt::Pczt| {
+            let bytes = t2z::serialize_pczt(pczt);
+            let mut handle: *mut PcztHandle = ptr::null_mut();
+            assert_eq!(pczt_parse(bytes.as_ptr(), bytes.len(), &mut handle), ResultCode::Success);
+            handle
+        };
+
+        let handle = parse(&pczt);
+        let mut buffer: *mut ByteBufferHandle = ptr::null_mut();
+        assert_eq!(pczt_serialize_buffer(handle, &mut buffer), ResultCode::Success);
+        let bytes = std::slice::from_raw_parts(pczt_buffer_data(buffer), pczt_buffer_len(buffer));
+        assert_eq!(bytes, t2z::serialize_pczt(&pczt).as_slice());
+        pczt_buffer_free(buffer);
+        pczt_free(handle);
+
+        let handle = parse(&signed);
+        let mut buffer: *mut ByteBufferHandle = ptr::null_mut();
+        assert_eq!(pczt_finalize_and_extract_buffer(handle, &mut buffer), ResultCode::Success);
+        let bytes = std::slice::from_raw_parts(pczt_buffer_data(buffer), pczt_buffer_len(buffer));
+        assert_eq!(bytes, expected_tx.as_slice());
+        pczt_buffer_free(buffer);
+
+        assert!(pczt_buffer_data(ptr::null()).is_null());
+        assert_eq!(pczt_buffer_len(ptr::null()), 0);
+    }
+}
+
+#[test]
+fn test_inspection_accessors() {
+    let pczt = t2z::propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let bytes = t2z::serialize_pczt(&pczt);
+
+    unsafe {
+        let mut handle: *mut PcztHandle = ptr::null_mut();
+        assert_eq!(pczt_parse(bytes.as_ptr(), bytes.len(), &mut handle), ResultCode::Success);
+
+        let mut count = 0usize;
+        assert_eq!(pczt_get_transparent_input_count(handle, &mut count), ResultCode::Success);
+        assert_eq!(count, 1);
+
+        let mut input: CTransparentInputInfo = std::mem::zeroed();
+        assert_eq!(pczt_get_transparent_input(handle, 0, &mut input), ResultCode::Success);
+        assert_eq!(input.value, amounts::ONE_ZEC);
+        assert_eq!(input.sequence, 0xFFFF_FFFF);
+        assert_eq!(input.script_pub_key_len, 25);
+        assert_eq!(input.num_signatures, 0);
+        assert!(!input.is_finalized);
+        assert_eq!(pczt_get_transparent_input(handle, 1, &mut input), ResultCode::ErrorInspection);
+
+        // Payment and change
+        assert_eq!(pczt_get_transparent_output_count(handle, &mut count), ResultCode::Success);
+        assert_eq!(count, 2);
+        let mut output = CTransparentOutput { script_pub_key: ptr::null(), script_pub_key_len: 0, value: 0 };
+        assert_eq!(pczt_get_transparent_output(handle, 0, &mut output), ResultCode::Success);
+        assert_eq!(output.value, amounts::SMALL);
+        let script = std::slice::from_raw_parts(output.script_pub_key, output.script_pub_key_len);
+        assert_eq!(script, pczt.transparent().outputs()[0].script_pubkey().as_slice());
+        assert_eq!(pczt_get_transparent_output(handle, 2, &mut output), ResultCode::ErrorInspection);
+
+        let mut has_proof = true;
+        assert_eq!(pczt_get_orchard_action_count(handle, &mut count), ResultCode::Success);
+        assert_eq!(count, 0);
+        assert_eq!(pczt_has_orchard_proof(handle, &mut has_proof), ResultCode::Success);
+        assert!(!has_proof);
+
+        let mut signed: *mut PcztHandle = ptr::null_mut();
+        assert_eq!(pczt_sign_transparent(handle, 0, &[1u8; 32], &mut signed), ResultCode::Success);
+        assert_eq!(pczt_get_transparent_input(signed, 0, &mut input), ResultCode::Success);
+        assert_eq!(input.num_signatures, 1);
+
+        assert_eq!(pczt_get_transparent_input_count(ptr::null(), &mut count), ResultCode::ErrorNullPointer);
+        pczt_free(signed);
+    }
+}
+
 #[test]
 fn test_result_codes() {
     // Verify result code values are as expected
@@ -191,3 +372,385 @@ fn test_buffer_too_small() {
         assert_eq!(result, ResultCode::ErrorBufferTooSmall);
     }
 }
+
+#[test]
+fn test_last_error_len_and_truncation() {
+    unsafe {
+        pczt_transaction_request_new(ptr::null(), 0, ptr::null_mut());
+
+        // The reported length is enough for the full message
+        let len = pczt_get_last_error_len();
+        let mut buffer: Vec<u8> = vec![0; len];
+        assert_eq!(pczt_get_last_error(buffer.as_mut_ptr() as *mut c_char, buffer.len()), ResultCode::Success);
+        let full = std::ffi::CStr::from_ptr(buffer.as_ptr() as *const c_char).to_str().unwrap().to_owned();
+        assert_eq!(full.len() + 1, len);
+
+        // A short buffer gets a NUL-terminated prefix instead of an error
+        let mut buffer: Vec<u8> = vec![0xff; 5];
+        let mut truncated = false;
+        let result = pczt_get_last_error_truncated(buffer.as_mut_ptr() as *mut c_char, buffer.len(), &mut truncated);
+        assert_eq!(result, ResultCode::Success);
+        assert!(truncated);
+        assert_eq!(&buffer[..4], &full.as_bytes()[..4]);
+        assert_eq!(buffer[4], 0);
+
+        let mut buffer: Vec<u8> = vec![0; len];
+        pczt_get_last_error_truncated(buffer.as_mut_ptr() as *mut c_char, buffer.len(), &mut truncated);
+        assert!(!truncated);
+        assert_eq!(&buffer[..len - 1], full.as_bytes());
+
+        assert_eq!(
+            pczt_get_last_error_truncated(buffer.as_mut_ptr() as *mut c_char, 0, ptr::null_mut()),
+            ResultCode::ErrorBufferTooSmall
+        );
+    }
+}
+
+#[test]
+fn test_set_expiry_height() {
+    unsafe {
+        let address = CString::new(addresses::TRANSPARENT).unwrap();
+        let payment = CPayment {
+            address: address.as_ptr(),
+            amount: amounts::SMALL,
+            memo: ptr::null(),
+            label: ptr::null(),
+            message: ptr::null(),
+        };
+
+        let mut request: *mut TransactionRequestHandle = ptr::null_mut();
+        assert_eq!(pczt_transaction_request_new(&payment, 1, &mut request), ResultCode::Success);
+        assert_eq!(pczt_transaction_request_set_target_height(request, 2_500_000), ResultCode::Success);
+        assert_eq!(pczt_transaction_request_set_expiry_delta(request, 100), ResultCode::Success);
+        assert_eq!(pczt_transaction_request_set_expiry_height(request, 2_500_010), ResultCode::Success);
+
+        let inputs = sample_transparent_inputs();
+        let mut pczt: *mut PcztHandle = ptr::null_mut();
+        assert_eq!(pczt_propose_transaction(inputs.as_ptr(), inputs.len(), request, ptr::null(), &mut pczt), ResultCode::Success);
+
+        // The summary reads the height back from the proposed PCZT
+        let mut json: *mut u8 = ptr::null_mut();
+        let mut json_len: usize = 0;
+        assert_eq!(pczt_summarize(pczt, &mut json, &mut json_len), ResultCode::Success);
+        let summary: serde_json::Value = serde_json::from_slice(std::slice::from_raw_parts(json, json_len)).unwrap();
+        assert_eq!(summary["expiry_height"], 2_500_010);
+
+        pczt_free_bytes(json, json_len);
+        pczt_free(pczt);
+        pczt_transaction_request_free(request);
+    }
+}
+
+#[test]
+fn test_last_error_info_reports_input_index() {
+    unsafe {
+        let address = CString::new(addresses::TRANSPARENT).unwrap();
+        let payment = CPayment {
+            address: address.as_ptr(),
+            amount: amounts::SMALL,
+            memo: ptr::null(),
+            label: ptr::null(),
+            message: ptr::null(),
+        };
+
+        let mut request: *mut TransactionRequestHandle = ptr::null_mut();
+        assert_eq!(pczt_transaction_request_new(&payment, 1, &mut request), ResultCode::Success);
+
+        let inputs = sample_transparent_inputs();
+        let mut pczt: *mut PcztHandle = ptr::null_mut();
+        let result = pczt_propose_transaction(inputs.as_ptr(), inputs.len(), request, ptr::null(), &mut pczt);
+        assert_eq!(result, ResultCode::Success);
+
+        // Finalize without signing: input 0 is missing its signature
+        let mut tx_bytes: *mut u8 = ptr::null_mut();
+        let mut tx_len: usize = 0;
+        let result = pczt_finalize_and_extract(pczt, &mut tx_bytes, &mut tx_len);
+        assert_eq!(result, ResultCode::ErrorFinalization);
+
+        let mut info = CErrorInfo { code: ResultCode::Success, has_input_index: false, input_index: 0 };
+        assert_eq!(pczt_get_last_error_info(&mut info), ResultCode::Success);
+        assert_eq!(info.code, ResultCode::ErrorFinalization);
+        assert!(info.has_input_index);
+        assert_eq!(info.input_index, 0);
+
+        pczt_transaction_request_free(request);
+    }
+}
+
+#[test]
+fn test_last_error_reports_input_script_mismatch() {
+    use t2z::types::{parse_transparent_inputs, serialize_transparent_inputs};
+
+    // Input 1 claims a key other than the one its P2PKH script pays
+    let mut inputs = parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&[amounts::MEDIUM, amounts::MEDIUM])).unwrap();
+    let secp = secp256k1::Secp256k1::new();
+    inputs[1].pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secp256k1::SecretKey::from_slice(&[2u8; 32]).unwrap());
+    let inputs = serialize_transparent_inputs(&inputs).unwrap();
+
+    unsafe {
+        let address = CString::new(addresses::TRANSPARENT).unwrap();
+        let payment = CPayment {
+            address: address.as_ptr(),
+            amount: amounts::SMALL,
+            memo: ptr::null(),
+            label: ptr::null(),
+            message: ptr::null(),
+        };
+
+        let mut request: *mut TransactionRequestHandle = ptr::null_mut();
+        assert_eq!(pczt_transaction_request_new(&payment, 1, &mut request), ResultCode::Success);
+
+        let mut pczt: *mut PcztHandle = ptr::null_mut();
+        let result = pczt_propose_transaction(inputs.as_ptr(), inputs.len(), request, ptr::null(), &mut pczt);
+        assert_eq!(result, ResultCode::ErrorProposal);
+
+        let mut info = CErrorInfo { code: ResultCode::Success, has_input_index: false, input_index: 0 };
+        assert_eq!(pczt_get_last_error_info(&mut info), ResultCode::Success);
+        assert!(info.has_input_index);
+        assert_eq!(info.input_index, 1);
+
+        let mut buffer = [0u8; 64];
+        assert_eq!(pczt_get_last_error_code(buffer.as_mut_ptr() as *mut c_char, buffer.len()), ResultCode::Success);
+        let code = std::ffi::CStr::from_ptr(buffer.as_ptr() as *const c_char).to_str().unwrap();
+        assert_eq!(code, "INPUT_SCRIPT_MISMATCH");
+
+        pczt_transaction_request_free(request);
+    }
+}
+
+#[test]
+fn test_last_error_insufficient_funds() {
+    unsafe {
+        let address = CString::new(addresses::TRANSPARENT).unwrap();
+        let payment = CPayment {
+            address: address.as_ptr(),
+            amount: amounts::ONE_ZEC,
+            memo: ptr::null(),
+            label: ptr::null(),
+            message: ptr::null(),
+        };
+
+        let mut request: *mut TransactionRequestHandle = ptr::null_mut();
+        assert_eq!(pczt_transaction_request_new(&payment, 1, &mut request), ResultCode::Success);
+
+        let inputs = sample_transparent_inputs();
+        let mut pczt: *mut PcztHandle = ptr::null_mut();
+        let result = pczt_propose_transaction(inputs.as_ptr(), inputs.len(), request, ptr::null(), &mut pczt);
+        assert_eq!(result, ResultCode::ErrorProposal);
+
+        let mut funds = CInsufficientFunds { available: 0, required: 0, fee: 0 };
+        assert!(pczt_get_last_error_insufficient_funds(&mut funds));
+        assert_eq!(funds.available, amounts::ONE_ZEC);
+        assert_eq!(funds.required, amounts::ONE_ZEC + 10_000);
+        assert_eq!(funds.fee, 10_000);
+
+        let mut details = CErrorDetails { domain: ResultCode::Success, sub_code: 0, context_len: 0, context: [0; 3] };
+        assert_eq!(pczt_get_last_error_details(&mut details), ResultCode::Success);
+        assert_eq!(details.result_code, ResultCode::ErrorProposal);
+        assert_eq!(details.sub_code, t2z::error::ErrorCode::InsufficientFunds.number());
+        assert_eq!(details.context_len, 3);
+        assert_eq!(details.context, [amounts::ONE_ZEC, amounts::ONE_ZEC + 10_000, 10_000]);
+
+        // Other errors carry no amounts
+        pczt_transaction_request_new(ptr::null(), 0, ptr::null_mut());
+        assert!(!pczt_get_last_error_insufficient_funds(&mut funds));
+        pczt_get_last_error_details(&mut details);
+        assert_eq!(details.sub_code, t2z::error::ErrorCode::NullPointer.number());
+        assert_eq!(details.context_len, 0);
+
+        pczt_transaction_request_free(request);
+    }
+}
+
+#[test]
+fn test_last_error_code() {
+    unsafe {
+        pczt_transaction_request_new(ptr::null(), 0, ptr::null_mut());
+
+        let mut buffer: Vec<u8> = vec![0; 64];
+        let result = pczt_get_last_error_code(buffer.as_mut_ptr() as *mut c_char, buffer.len());
+        assert_eq!(result, ResultCode::Success);
+
+        let code = std::ffi::CStr::from_ptr(buffer.as_ptr() as *const c_char)
+            .to_string_lossy()
+            .into_owned();
+        assert_eq!(code, "NULL_POINTER");
+    }
+}
+
+#[test]
+fn test_error_codes_are_unique() {
+    use std::collections::HashSet;
+    use t2z::error::ErrorCode;
+
+    let codes: HashSet<&str> = ErrorCode::ALL.iter().map(|c| c.as_str()).collect();
+    assert_eq!(codes.len(), ErrorCode::ALL.len(), "Error code strings must be unique");
+    let numbers: HashSet<u32> = ErrorCode::ALL.iter().map(|c| c.number()).collect();
+    assert_eq!(numbers.len(), ErrorCode::ALL.len(), "Error code numbers must be unique");
+}
+
+#[test]
+fn test_error_code_numbers_are_stable() {
+    use t2z::error::ErrorCode;
+
+    // Hosts store these and look them up in tables. Never renumber a code; new codes
+    // are appended here with the next number.
+    let golden = [
+        (ErrorCode::NoInputs, 1, "NO_INPUTS"),
+        (ErrorCode::InvalidRequest, 2, "INVALID_REQUEST"),
+        (ErrorCode::InvalidAddress, 3, "INVALID_ADDRESS"),
+        (ErrorCode::InsufficientFunds, 4, "INSUFFICIENT_FUNDS"),
+        (ErrorCode::FeeCalculation, 5, "FEE_CALCULATION"),
+        (ErrorCode::ExcessiveFee, 6, "EXCESSIVE_FEE"),
+        (ErrorCode::FeeCapExceeded, 7, "FEE_CAP_EXCEEDED"),
+        (ErrorCode::DustPayment, 8, "DUST_PAYMENT"),
+        (ErrorCode::DustChange, 9, "DUST_CHANGE"),
+        (ErrorCode::InsufficientConfirmations, 10, "INSUFFICIENT_CONFIRMATIONS"),
+        (ErrorCode::ChainTipUnavailable, 11, "CHAIN_TIP_UNAVAILABLE"),
+        (ErrorCode::InputScriptMismatch, 12, "INPUT_SCRIPT_MISMATCH"),
+        (ErrorCode::UnsupportedScriptType, 13, "UNSUPPORTED_SCRIPT_TYPE"),
+        (ErrorCode::StrictModeViolation, 14, "STRICT_MODE_VIOLATION"),
+        (ErrorCode::PcztCreation, 15, "PCZT_CREATION"),
+        (ErrorCode::MemoTooLong, 16, "MEMO_TOO_LONG"),
+        (ErrorCode::InvalidMemo, 17, "INVALID_MEMO"),
+        (ErrorCode::NoOrchardOutputs, 18, "NO_ORCHARD_OUTPUTS"),
+        (ErrorCode::ProvingKeyUnavailable, 19, "PROVING_KEY_UNAVAILABLE"),
+        (ErrorCode::ProofGenerationFailed, 20, "PROOF_GENERATION_FAILED"),
+        (ErrorCode::OrchardProofFailed, 21, "ORCHARD_PROOF_FAILED"),
+        (ErrorCode::ProverServiceStopped, 22, "PROVER_SERVICE_STOPPED"),
+        (ErrorCode::ProverQueueFull, 23, "PROVER_QUEUE_FULL"),
+        (ErrorCode::UnknownProofJob, 24, "UNKNOWN_PROOF_JOB"),
+        (ErrorCode::RemoteProverFailed, 25, "REMOTE_PROVER_FAILED"),
+        (ErrorCode::RemoteProverUnauthorized, 26, "REMOTE_PROVER_UNAUTHORIZED"),
+        (ErrorCode::ProverNotBuilt, 27, "PROVER_NOT_BUILT"),
+        (ErrorCode::RequestMismatch, 28, "REQUEST_MISMATCH"),
+        (ErrorCode::ChangeMismatch, 29, "CHANGE_MISMATCH"),
+        (ErrorCode::InvalidFee, 30, "INVALID_FEE"),
+        (ErrorCode::OutputMismatch, 31, "OUTPUT_MISMATCH"),
+        (ErrorCode::ExtractionMismatch, 32, "EXTRACTION_MISMATCH"),
+        (ErrorCode::ReviewDrift, 33, "REVIEW_DRIFT"),
+        (ErrorCode::ChangeOwnershipInvalid, 34, "CHANGE_OWNERSHIP_INVALID"),
+        (ErrorCode::InvalidPreimage, 35, "INVALID_PREIMAGE"),
+        (ErrorCode::OrchardFlagsMismatch, 36, "ORCHARD_FLAGS_MISMATCH"),
+        (ErrorCode::InvalidInputIndex, 37, "INVALID_INPUT_INDEX"),
+        (ErrorCode::InvalidOutputIndex, 38, "INVALID_OUTPUT_INDEX"),
+        (ErrorCode::MissingInputPubkey, 39, "MISSING_INPUT_PUBKEY"),
+        (ErrorCode::MissingInputData, 40, "MISSING_INPUT_DATA"),
+        (ErrorCode::SighashCalculationFailed, 41, "SIGHASH_CALCULATION_FAILED"),
+        (ErrorCode::SignatureVerificationFailed, 42, "SIGNATURE_VERIFICATION_FAILED"),
+        (ErrorCode::InvalidSignatureFormat, 43, "INVALID_SIGNATURE_FORMAT"),
+        (ErrorCode::MissingPublicKey, 44, "MISSING_PUBLIC_KEY"),
+        (ErrorCode::InvalidSecretKey, 45, "INVALID_SECRET_KEY"),
+        (ErrorCode::SigningKeyMismatch, 46, "SIGNING_KEY_MISMATCH"),
+        (ErrorCode::NoPczts, 47, "NO_PCZTS"),
+        (ErrorCode::PcztDataMismatch, 48, "PCZT_DATA_MISMATCH"),
+        (ErrorCode::IncompatiblePczts, 49, "INCOMPATIBLE_PCZTS"),
+        (ErrorCode::CombinationFailed, 50, "COMBINATION_FAILED"),
+        (ErrorCode::UnexpectedSigner, 51, "UNEXPECTED_SIGNER"),
+        (ErrorCode::UnknownSigner, 52, "UNKNOWN_SIGNER"),
+        (ErrorCode::SignerWithoutInputs, 53, "SIGNER_WITHOUT_INPUTS"),
+        (ErrorCode::WrongSigner, 54, "WRONG_SIGNER"),
+        (ErrorCode::SigningTransactionMismatch, 55, "SIGNING_TRANSACTION_MISMATCH"),
+        (ErrorCode::SigningIncomplete, 56, "SIGNING_INCOMPLETE"),
+        (ErrorCode::InvalidUpdate, 57, "INVALID_UPDATE"),
+        (ErrorCode::UpdateFailed, 58, "UPDATE_FAILED"),
+        (ErrorCode::ExternalSignerFailed, 59, "EXTERNAL_SIGNER_FAILED"),
+        (ErrorCode::InputsSpent, 60, "INPUTS_SPENT"),
+        (ErrorCode::UtxoCheckFailed, 61, "UTXO_CHECK_FAILED"),
+        (ErrorCode::ReservationMissing, 62, "RESERVATION_MISSING"),
+        (ErrorCode::ReservationStoreFailed, 63, "RESERVATION_STORE_FAILED"),
+        (ErrorCode::MissingSignatures, 64, "MISSING_SIGNATURES"),
+        (ErrorCode::MissingProofs, 65, "MISSING_PROOFS"),
+        (ErrorCode::SpendFinalizationFailed, 66, "SPEND_FINALIZATION_FAILED"),
+        (ErrorCode::InputMissingSignature, 67, "INPUT_MISSING_SIGNATURE"),
+        (ErrorCode::InputPubkeyMismatch, 68, "INPUT_PUBKEY_MISMATCH"),
+        (ErrorCode::UnsupportedInputScript, 69, "UNSUPPORTED_INPUT_SCRIPT"),
+        (ErrorCode::TransactionExtractionFailed, 70, "TRANSACTION_EXTRACTION_FAILED"),
+        (ErrorCode::SerializationFailed, 71, "SERIALIZATION_FAILED"),
+        (ErrorCode::FinalVerificationFailed, 72, "FINAL_VERIFICATION_FAILED"),
+        (ErrorCode::ExtractionFailed, 73, "EXTRACTION_FAILED"),
+        (ErrorCode::InvalidFormat, 74, "INVALID_FORMAT"),
+        (ErrorCode::UnsupportedVersion, 75, "UNSUPPORTED_VERSION"),
+        (ErrorCode::CorruptedData, 76, "CORRUPTED_DATA"),
+        (ErrorCode::NonCanonical, 77, "NON_CANONICAL"),
+        (ErrorCode::UnknownField, 78, "UNKNOWN_FIELD"),
+        (ErrorCode::NullPointer, 79, "NULL_POINTER"),
+        (ErrorCode::InvalidUtf8, 80, "INVALID_UTF8"),
+        (ErrorCode::BufferTooSmall, 81, "BUFFER_TOO_SMALL"),
+        (ErrorCode::NotImplemented, 82, "NOT_IMPLEMENTED"),
+        (ErrorCode::FileIo, 83, "FILE_IO"),
+        (ErrorCode::NotAPcztFile, 84, "NOT_A_PCZT_FILE"),
+        (ErrorCode::ManifestSignatureInvalid, 85, "MANIFEST_SIGNATURE_INVALID"),
+        (ErrorCode::ManifestMismatch, 86, "MANIFEST_MISMATCH"),
+    ];
+    assert_eq!(golden.len(), ErrorCode::ALL.len(), "Every code needs a golden entry");
+    for (code, number, name) in golden {
+        assert_eq!(code.number(), number, "{:?} was renumbered", code);
+        assert_eq!(code.as_str(), name, "{:?} was renamed", code);
+    }
+}
+
+#[test]
+fn test_error_domains() {
+    use std::collections::HashSet;
+    use t2z::error::{ErrorCode, ErrorDomain, ERROR_DOMAIN_VERSION};
+
+    let numbers: HashSet<u32> = ErrorDomain::ALL.iter().map(|d| d.number()).collect();
+    assert_eq!(numbers.len(), ErrorDomain::ALL.len(), "Error domain numbers must be unique");
+    assert!(!numbers.contains(&0), "0 means no error");
+    for domain in ErrorDomain::ALL {
+        assert!(ErrorCode::ALL.iter().any(|code| code.domain() == *domain), "{} has no codes", domain);
+    }
+    assert_eq!(ErrorCode::UtxoCheckFailed.domain(), ErrorDomain::Network);
+    assert_eq!(ErrorCode::DustPayment.domain(), ErrorDomain::Policy);
+
+    unsafe {
+        assert_eq!(pczt_error_domain_version(), ERROR_DOMAIN_VERSION);
+        assert_eq!(pczt_validate(ptr::null()), ResultCode::ErrorNullPointer);
+        assert_eq!(pczt_error_domain(), ErrorDomain::Interface.number());
+        assert_eq!(pczt_error_domain_code(), ErrorCode::NullPointer.number());
+    }
+}
+
+#[test]
+fn test_build_info() {
+    unsafe {
+        let mut json_ptr: *mut u8 = ptr::null_mut();
+        let mut json_len: usize = 0;
+        assert_eq!(pczt_build_info(&mut json_ptr, &mut json_len), ResultCode::Success);
+
+        let json = std::str::from_utf8(std::slice::from_raw_parts(json_ptr, json_len)).unwrap().to_string();
+        pczt_free_bytes(json_ptr, json_len);
+
+        let info: t2z::types::BuildInfo = serde_json::from_str(&json).expect("valid JSON");
+        assert_eq!(info, t2z::build_info());
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+        assert!(!info.git_commit.is_empty());
+        assert!(info.dependencies.iter().any(|dep| dep.starts_with("pczt=")));
+    }
+}
+
+#[test]
+fn test_error_history() {
+    unsafe {
+        pczt_transaction_request_new(ptr::null(), 0, ptr::null_mut());
+        for _ in 0..ERROR_HISTORY_CAPACITY {
+            pczt_validate(ptr::null());
+        }
+
+        let mut json_ptr: *mut u8 = ptr::null_mut();
+        let mut json_len: usize = 0;
+        assert_eq!(pczt_get_error_history(&mut json_ptr, &mut json_len), ResultCode::Success);
+        let json = std::str::from_utf8(std::slice::from_raw_parts(json_ptr, json_len)).unwrap().to_string();
+        pczt_free_bytes(json_ptr, json_len);
+
+        // The oldest error made room for the newest ones
+        let history: Vec<serde_json::Value> = serde_json::from_str(&json).expect("valid JSON");
+        assert_eq!(history.len(), ERROR_HISTORY_CAPACITY);
+        assert!(history.iter().all(|record| record["function"] == "pczt_validate"));
+        assert_eq!(history[0]["code"], "NULL_POINTER");
+        assert_eq!(history[0]["domain"], "INTERFACE");
+        assert!(history[0]["timestamp_ms"].as_u64().unwrap() > 0);
+        assert!(!history[0]["message"].as_str().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,111 @@
+use t2z::flow::{self, BatchSigner};
+use t2z::error::FlowError;
+use t2z::types::SighashBatch;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+fn key_signer(sk: secp256k1::SecretKey) -> impl Fn(&SighashBatch) -> Result<Vec<[u8; 64]>, String> {
+    move |batch: &SighashBatch| {
+        let secp = secp256k1::Secp256k1::new();
+        Ok(batch.inputs.iter()
+            .map(|input| secp.sign_ecdsa(&secp256k1::Message::from_digest(input.sighash), &sk).serialize_compact())
+            .collect())
+    }
+}
+
+#[test]
+fn test_simple_t2z_flow() {
+    let inputs = sample_transparent_inputs();
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+    let tx_bytes = flow::simple_t2z(&inputs, simple_payment_request(), None, &sk).expect("Flow failed");
+    assert!(!tx_bytes.is_empty());
+
+    // The wrong key fails at signing
+    let other = secp256k1::SecretKey::from_slice(&[2u8; 32]).unwrap();
+    assert!(matches!(
+        flow::simple_t2z(&inputs, simple_payment_request(), None, &other),
+        Err(FlowError::Signature(_))
+    ));
+
+    println!("✅ simple_t2z produces a verified transaction");
+}
+
+/// Sets the prover thread limit, restoring the previous one when dropped so other
+/// tests in this binary see the default
+#[cfg(feature = "orchard-prover")]
+struct ProverThreads(usize);
+
+#[cfg(feature = "orchard-prover")]
+impl ProverThreads {
+    fn set(threads: usize) -> Self {
+        let previous = prover_threads();
+        set_prover_threads(threads);
+        Self(previous)
+    }
+}
+
+#[cfg(feature = "orchard-prover")]
+impl Drop for ProverThreads {
+    fn drop(&mut self) {
+        set_prover_threads(self.0);
+    }
+}
+
+#[test]
+#[cfg(feature = "orchard-prover")]
+fn test_simple_t2z_signs_while_proving() {
+    let _threads = ProverThreads::set(2);
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+
+    // The signed and the proved copies are combined before extraction
+    let tx_bytes = flow::simple_t2z(&sample_transparent_inputs(), shielded_payment_request(), None, &sk)
+        .expect("Flow failed");
+    assert!(!tx_bytes.is_empty());
+
+    println!("✅ simple_t2z signs a shielded transaction while it is proved");
+}
+
+#[test]
+fn test_hw_wallet_sign_flow() {
+    let inputs = sample_transparent_inputs();
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let pczt = prove_transaction(pczt).expect("Failed to prove");
+
+    let signed = flow::hw_wallet_sign(pczt.clone(), &key_signer(sk)).expect("Failed to sign");
+    finalize_and_extract(signed).expect("Failed to finalize");
+
+    // Device errors and short batches are reported as signer failures
+    let refusing = |_: &SighashBatch| -> Result<Vec<[u8; 64]>, String> { Err("user rejected".to_string()) };
+    assert!(matches!(flow::hw_wallet_sign(pczt.clone(), &refusing), Err(FlowError::Signer(msg)) if msg == "user rejected"));
+
+    let short = |_: &SighashBatch| -> Result<Vec<[u8; 64]>, String> { Ok(Vec::new()) };
+    assert!(matches!(flow::hw_wallet_sign(pczt, &short), Err(FlowError::Signer(_))));
+
+    println!("✅ hw_wallet_sign signs with one device batch");
+}
+
+#[test]
+fn test_multi_party_flow() {
+    let (inputs, [alice, bob]) = two_signer_inputs();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let secp = secp256k1::Secp256k1::new();
+    let alice_pk = secp256k1::PublicKey::from_secret_key(&secp, &alice).serialize();
+    let bob_pk = secp256k1::PublicKey::from_secret_key(&secp, &bob).serialize();
+
+    let (alice_signer, bob_signer) = (key_signer(alice), key_signer(bob));
+    let signers: [([u8; 33], &dyn BatchSigner); 2] = [(alice_pk, &alice_signer), (bob_pk, &bob_signer)];
+
+    let tx_bytes = flow::multi_party(pczt.clone(), &signers).expect("Flow failed");
+    assert!(!tx_bytes.is_empty());
+
+    // A signer error is returned once the prover thread has been joined
+    let refusing = |_: &SighashBatch| -> Result<Vec<[u8; 64]>, String> { Err("user rejected".to_string()) };
+    let signers: [([u8; 33], &dyn BatchSigner); 2] = [(alice_pk, &alice_signer), (bob_pk, &refusing)];
+    assert!(matches!(flow::multi_party(pczt, &signers), Err(FlowError::Signer(msg)) if msg == "user rejected"));
+
+    println!("✅ multi_party combines every signer with the proved PCZT");
+}
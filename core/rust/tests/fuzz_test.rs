@@ -0,0 +1,18 @@
+//! Smoke test for the fuzzing entry point
+#![cfg(feature = "arbitrary")]
+
+#[test]
+fn test_fuzz_roundtrip_smoke() {
+    let mut seed = 0x2545_f491_u32;
+    for len in [0usize, 1, 2, 5, 64, 512, 4096] {
+        let data: Vec<u8> = (0..len)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 17;
+                seed ^= seed << 5;
+                seed as u8
+            })
+            .collect();
+        t2z::fuzz::fuzz_roundtrip(&data);
+    }
+}
@@ -6,7 +6,7 @@
 mod common;
 
 use common::*;
-use t2z::{*, types::*, error::ProposalError};
+use t2z::{*, types::*, error::{ProposalError, VerificationFailure}};
 
 #[test]
 fn test_payment_request_creation() {
@@ -51,6 +51,303 @@ fn test_propose_transaction() {
     assert!(!pczt.transparent().outputs().is_empty(), "Should have transparent outputs");
 }
 
+#[test]
+fn test_propose_transparent_only_has_no_orchard_bundle() {
+    let request = multi_payment_request();
+    let inputs = sample_transparent_inputs();
+
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    assert!(pczt.orchard().actions().is_empty(), "t->t proposal should not contain Orchard actions");
+
+    // Proving is a no-op for t->t transactions
+    assert!(is_transparent_only(&pczt));
+    let before = serialize_pczt(&pczt);
+    let pczt = prove_transaction(pczt).expect("Failed to prove");
+    assert_eq!(before, serialize_pczt(&pczt), "Proving should not modify a t->t PCZT");
+
+    // Extraction works without building the Orchard verifying key
+    use pczt::roles::signer::Signer;
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+    let mut signer = Signer::new(pczt).unwrap();
+    signer.sign_transparent(0, &sk).unwrap();
+    let tx_bytes = finalize_and_extract(signer.finish()).expect("Failed to finalize");
+    assert!(!tx_bytes.is_empty());
+}
+
+#[test]
+fn test_input_sequence_inspection() {
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+
+    assert_eq!(get_input_sequence(&pczt, 0).unwrap(), FINAL_SEQUENCE);
+    assert!(get_input_sequence(&pczt, 5).is_err());
+}
+
+#[test]
+fn test_revalidate_inputs() {
+    use t2z::error::RevalidationError;
+
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC, amounts::ONE_ZEC]);
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+
+    let all_unspent = |_: &InputOutpoint, _: u32| Ok(true);
+    assert!(revalidate_inputs(&pczt, &all_unspent, 3_000_000).is_ok());
+
+    let second_spent = |outpoint: &InputOutpoint, _: u32| Ok(outpoint.vout != 1);
+    match revalidate_inputs(&pczt, &second_spent, 3_000_000) {
+        Err(RevalidationError::InputsSpent { indices }) => assert_eq!(indices, vec![1]),
+        other => panic!("Expected InputsSpent, got: {:?}", other),
+    }
+
+    let unreachable = |_: &InputOutpoint, _: u32| Err("server unavailable".to_string());
+    assert!(matches!(
+        revalidate_inputs(&pczt, &unreachable, 3_000_000),
+        Err(RevalidationError::CheckFailed { index: 0, .. })
+    ));
+}
+
+#[test]
+fn test_get_input_pubkey() {
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+
+    let expected = parse_transparent_inputs(&inputs).unwrap()[0].pubkey.serialize();
+    assert_eq!(get_input_pubkey(&pczt, 0).unwrap(), expected);
+    assert!(get_input_pubkey(&pczt, 5).is_err());
+}
+
+#[test]
+fn test_propose_rejects_input_script_mismatch() {
+    let mut inputs = parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&[amounts::MEDIUM, amounts::MEDIUM])).unwrap();
+    let secp = secp256k1::Secp256k1::new();
+    inputs[1].pubkey = secp256k1::PublicKey::from_secret_key(&secp, &secp256k1::SecretKey::from_slice(&[2u8; 32]).unwrap());
+    let data = serialize_transparent_inputs(&inputs).unwrap();
+
+    let result = propose_transaction(&data, simple_payment_request(), None);
+    assert!(matches!(result, Err(ProposalError::InputScriptMismatch { index: 1 })));
+}
+
+#[test]
+fn test_propose_rejects_unsupported_script_types() {
+    let mut inputs = parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&[amounts::MEDIUM, amounts::MEDIUM])).unwrap();
+
+    // P2SH without its redeem script
+    inputs[1].script_pubkey = p2sh_script(&two_of_three_redeem_script());
+    let result = propose_transaction(&serialize_transparent_inputs(&inputs).unwrap(), simple_payment_request(), None);
+    assert!(matches!(result, Err(ProposalError::UnsupportedScriptType { index: 1, kind: ScriptKind::P2sh })));
+
+    // Bare pay-to-pubkey
+    let pubkey = inputs[1].pubkey.serialize();
+    inputs[1].script_pubkey = [&[0x21][..], &pubkey, &[0xac]].concat();
+    let result = propose_transaction(&serialize_transparent_inputs(&inputs).unwrap(), simple_payment_request(), None);
+    assert!(matches!(result, Err(ProposalError::UnsupportedScriptType { index: 1, kind: ScriptKind::P2pk })));
+}
+
+#[test]
+fn test_propose_follows_fee_policy() {
+    let data = sample_transparent_inputs();
+
+    // 1 input, payment and change: a ZIP-317 fee of 10_000
+    let request = simple_payment_request().with_fee_policy(FeePolicy::FixedZatoshis(50_000));
+    let pczt = propose_transaction(&data, request.clone(), None).expect("Failed to propose");
+    assert_eq!(summarize_pczt(&pczt).fee, Some(50_000));
+    verify_before_signing(&pczt, &request, &[]).expect("Fee follows the policy");
+    assert!(matches!(
+        verify_before_signing(&pczt, &simple_payment_request(), &[]),
+        Err(VerificationFailure::FeePolicyMismatch { expected: 10_000, actual: 50_000 })
+    ));
+
+    let request = simple_payment_request().with_fee_policy(FeePolicy::Zip317WithMultiplier(1.5));
+    let pczt = propose_transaction(&data, request, None).expect("Failed to propose");
+    assert_eq!(summarize_pczt(&pczt).fee, Some(15_000));
+
+    let request = simple_payment_request().with_fee_policy(FeePolicy::Zip317WithMultiplier(0.5));
+    assert!(matches!(propose_transaction(&data, request, None), Err(ProposalError::InvalidRequest(_))));
+}
+
+#[test]
+fn test_propose_sweep() {
+    let data = sample_transparent_inputs_with_amounts(&[amounts::MEDIUM, amounts::LARGE]);
+    let total = amounts::MEDIUM + amounts::LARGE;
+
+    // 2 inputs and 1 output: a ZIP-317 fee of 10_000, and no change
+    let request = simple_payment_request().with_sweep(true);
+    let pczt = propose_transaction(&data, request.clone(), None).expect("Failed to propose");
+    let outputs = pczt.transparent().outputs();
+    assert_eq!(outputs.len(), 1);
+    assert_eq!(*outputs[0].value(), total - 10_000);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(10_000));
+    verify_before_signing(&pczt, &request, &[]).expect("Sweep pays the inputs minus the fee");
+
+    // An output on the side of a sweep takes part of it without changing the fee
+    let skimmed = TransactionRequest::new(vec![
+        Payment::new(addresses::TRANSPARENT.to_string(), total - 60_000),
+        Payment::new(addresses::TRANSPARENT_2.to_string(), 50_000),
+    ]);
+    let skimming = propose_transaction(&data, skimmed, None).expect("Failed to propose");
+    assert_eq!(summarize_pczt(&skimming).fee, Some(10_000));
+    let report = verify_report(&skimming, &request, &[]);
+    assert!(report.errors().any(|failure| matches!(failure, VerificationFailure::OutputMismatch(_))));
+    assert!(verify_before_signing(&skimming, &request, &[]).is_err());
+
+    // 2 inputs and 2 padded Orchard actions
+    let request = shielded_payment_request().with_sweep(true);
+    let pczt = propose_transaction(&data, request, None).expect("Failed to propose");
+    assert!(pczt.transparent().outputs().is_empty());
+    assert_eq!(summarize_pczt(&pczt).fee, Some(20_000));
+
+    let request = multi_payment_request().with_sweep(true);
+    assert!(matches!(propose_transaction(&data, request, None), Err(ProposalError::InvalidRequest(_))));
+
+    let data = sample_transparent_inputs_with_amounts(&[10_000]);
+    let request = simple_payment_request().with_sweep(true);
+    assert!(matches!(
+        propose_transaction(&data, request, None),
+        Err(ProposalError::InsufficientFunds { available: 10_000, required: 10_000, fee: 10_000 })
+    ));
+}
+
+#[test]
+fn test_propose_enforces_max_fee() {
+    let data = sample_transparent_inputs();
+
+    let request = simple_payment_request().with_max_fee(10_000);
+    assert!(propose_transaction(&data, request, None).is_ok());
+
+    let request = simple_payment_request().with_max_fee(9_999);
+    match propose_transaction(&data, request, None) {
+        Err(ProposalError::FeeCapExceeded { fee, max_fee }) => {
+            assert_eq!(fee, 10_000);
+            assert_eq!(max_fee, 9_999);
+        }
+        other => panic!("Expected FeeCapExceeded, got: {:?}", other.map(|_| ())),
+    }
+
+    // The cap applies to the fee of the selected inputs
+    let pool = sample_transparent_inputs_with_amounts(&[20_000, 50_000, 60_000]);
+    let request = simple_payment_request()
+        .with_coin_selection(CoinSelection::SmallestFirst)
+        .with_max_fee(10_000);
+    assert!(matches!(
+        propose_transaction(&pool, request, None),
+        Err(ProposalError::FeeCapExceeded { fee: 15_000, .. })
+    ));
+
+    // The cap applies to the implicit fee, including dust folded into it
+    let folding = TransactionRequest::new(vec![Payment::new(addresses::TRANSPARENT.to_string(), 100_000)])
+        .with_dust_policy(DustPolicy::AddChangeToFee { threshold: DEFAULT_DUST_THRESHOLD });
+    let inputs = sample_transparent_inputs_with_amounts(&[112_000]);
+    assert!(matches!(
+        propose_transaction(&inputs, folding.clone().with_max_fee(11_000), None),
+        Err(ProposalError::FeeCapExceeded { fee: 12_000, max_fee: 11_000 })
+    ));
+
+    // Coin selection passes over a subset that would fold dust above the cap
+    let pool = sample_transparent_inputs_with_amounts(&[112_000, 200_000]);
+    let request = folding.with_coin_selection(CoinSelection::SmallestFirst);
+    let pczt = propose_transaction(&pool, request.clone(), None).unwrap();
+    assert_eq!(pczt.transparent().inputs().len(), 1);
+    let pczt = propose_transaction(&pool, request.with_max_fee(11_000), None).unwrap();
+    assert_eq!(pczt.transparent().inputs().len(), 2);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(10_000));
+}
+
+#[test]
+fn test_split_sweep() {
+    let data = sample_transparent_inputs_with_amounts(&[60_000_000, 40_000_001]);
+    let recipients = vec![
+        SweepRecipient { address: addresses::TRANSPARENT.to_string(), share: SweepShare::Fixed(amounts::LARGE) },
+        SweepRecipient { address: addresses::TRANSPARENT_2.to_string(), share: SweepShare::Ratio(2) },
+        SweepRecipient { address: addresses::TRANSPARENT.to_string(), share: SweepShare::Ratio(1) },
+    ];
+
+    // 100_000_001 in, 15_000 fee for 2 inputs and 3 outputs, 10_000_000 fixed,
+    // 89_985_001 split 2:1 with the odd zatoshi to the last output
+    let request = plan_split_sweep(&data, &recipients, TransactionRequest::new(vec![])).expect("Failed to plan");
+    let split: Vec<u64> = request.payments.iter().map(|p| p.amount).collect();
+    assert_eq!(split, vec![10_000_000, 59_990_000, 29_995_001]);
+
+    let pczt = propose_split_sweep(&data, &recipients, TransactionRequest::new(vec![])).expect("Failed to propose");
+    assert_eq!(pczt.transparent().inputs().len(), 2);
+    assert_eq!(pczt.transparent().outputs().len(), 3);
+
+    // Fixed shares must fit
+    let too_much = vec![SweepRecipient { address: addresses::TRANSPARENT.to_string(), share: SweepShare::Fixed(amounts::ONE_ZEC) }];
+    assert!(matches!(
+        plan_split_sweep(&data, &too_much, TransactionRequest::new(vec![])),
+        Err(ProposalError::InsufficientFunds { .. })
+    ));
+
+    // A weight that rounds to dust is refused
+    let dusty = vec![
+        SweepRecipient { address: addresses::TRANSPARENT.to_string(), share: SweepShare::Ratio(u64::MAX) },
+        SweepRecipient { address: addresses::TRANSPARENT_2.to_string(), share: SweepShare::Ratio(1) },
+    ];
+    assert!(matches!(
+        plan_split_sweep(&sample_transparent_inputs(), &dusty, TransactionRequest::new(vec![])),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn test_propose_rejects_unconfirmed_inputs() {
+    let mut inputs = parse_transparent_inputs(
+        &sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC, amounts::ONE_ZEC, amounts::ONE_ZEC])
+    ).unwrap();
+    inputs[0].height = Some(3_000_000);
+    inputs[1].height = Some(3_000_095);
+    // inputs[2] has no known height
+    let data = serialize_transparent_inputs(&inputs).unwrap();
+
+    let (eligible, excluded) = filter_by_confirmations(&inputs, 3_000_100, 10);
+    assert_eq!(eligible.len(), 1);
+    assert_eq!(excluded, vec![1, 2]);
+
+    let mut request = simple_payment_request().with_min_confirmations(10);
+    request.target_height = Some(3_000_100);
+    match propose_transaction(&data, request, None) {
+        Err(ProposalError::InsufficientConfirmations { excluded, min_confirmations }) => {
+            assert_eq!(excluded, vec![1, 2]);
+            assert_eq!(min_confirmations, 10);
+        }
+        other => panic!("Expected InsufficientConfirmations, got: {:?}", other.map(|_| ())),
+    }
+
+    // Without a target height the depth can't be checked
+    let request = simple_payment_request().with_min_confirmations(10);
+    assert!(matches!(
+        propose_transaction(&data, request, None),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+}
+
+#[test]
+fn test_propose_transaction_at_tip() {
+    let data = sample_transparent_inputs();
+
+    let tip = || -> Result<u32, String> { Ok(3_000_100) };
+    let pczt = propose_transaction_at_tip(&data, simple_payment_request(), None, &tip).expect("Failed to propose");
+    assert_eq!(*pczt.global().expiry_height(), 3_000_101 + 40);
+
+    // The tip also drives the confirmation check
+    let mut inputs = parse_transparent_inputs(&data).unwrap();
+    inputs[0].height = Some(3_000_095);
+    let request = simple_payment_request().with_min_confirmations(10);
+    assert!(matches!(
+        propose_transaction_at_tip(&serialize_transparent_inputs(&inputs).unwrap(), request, None, &tip),
+        Err(ProposalError::InsufficientConfirmations { .. })
+    ));
+
+    let offline = || -> Result<u32, String> { Err("connection refused".to_string()) };
+    assert!(matches!(
+        propose_transaction_at_tip(&data, simple_payment_request(), None, &offline),
+        Err(ProposalError::ChainTipUnavailable(msg)) if msg == "connection refused"
+    ));
+}
+
 #[test]
 fn test_propose_transaction_no_inputs() {
     // Test that propose_transaction fails when no inputs are provided
@@ -63,13 +360,235 @@ fn test_propose_transaction_no_inputs() {
     // Should fail with insufficient funds since there are no inputs
     assert!(result.is_err(), "Should fail when building transaction without inputs");
     match result {
-        Err(ProposalError::PcztCreation(msg)) => {
-            assert!(msg.contains("InsufficientFunds"), "Expected InsufficientFunds error, got: {}", msg);
+        Err(ProposalError::InsufficientFunds { available: 0, .. }) => {}
+        _ => panic!("Expected InsufficientFunds error"),
+    }
+}
+
+#[test]
+fn test_propose_transaction_exact_change() {
+    // Inputs cover the payment plus the no-change fee exactly: no change output
+    let request = simple_payment_request();
+    let fee = calculate_fee(1, 1, 0);
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::SMALL + fee]);
+
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    assert_eq!(pczt.transparent().outputs().len(), 1, "Exact change should not add a change output");
+}
+
+#[test]
+fn test_propose_transaction_shortfall() {
+    // One zatoshi short of the no-change fee
+    let request = simple_payment_request();
+    let fee = calculate_fee(1, 1, 0);
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::SMALL + fee - 1]);
+
+    match propose_transaction(&inputs, request, None) {
+        Err(ProposalError::InsufficientFunds { available, required, fee: reported_fee }) => {
+            assert_eq!(available, amounts::SMALL + fee - 1);
+            assert_eq!(required, amounts::SMALL + fee);
+            assert_eq!(reported_fee, fee);
         }
-        _ => panic!("Expected PcztCreation error with InsufficientFunds"),
+        other => panic!("Expected InsufficientFunds, got: {:?}", other.map(|_| ())),
     }
 }
 
+#[test]
+fn test_propose_transaction_rejects_fee_burning() {
+    // 3 payments with change would cost 4 actions (20_000), but without change only
+    // 3 actions (15_000). An input just below the "with change" estimate skips change
+    // and would donate the difference to miners.
+    let request = TransactionRequest::new(vec![
+        Payment::new(addresses::TRANSPARENT.to_string(), amounts::SMALL),
+        Payment::new(addresses::TRANSPARENT_2.to_string(), amounts::SMALL),
+        Payment::new(addresses::TRANSPARENT.to_string(), amounts::SMALL),
+    ]);
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::SMALL * 3 + 19_999]);
+
+    match propose_transaction(&inputs, request, None) {
+        Err(ProposalError::ExcessiveFee { implicit_fee, expected_fee, tolerance }) => {
+            assert_eq!(implicit_fee, 19_999);
+            assert_eq!(expected_fee, 15_000);
+            assert_eq!(tolerance, 0);
+        }
+        other => panic!("Expected ExcessiveFee, got: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_plan_change() {
+    // Plenty left over: change output, fee includes its action
+    let plan = plan_change(1, 1, 0, 1_000_000, 100_000, DustPolicy::AddChangeToFee { threshold: 0 }).unwrap();
+    assert_eq!(plan, ChangePlan { change: 890_000, fee: 10_000 });
+
+    // 3 inputs, 1 payment: the change output doesn't add an action, same fee either way
+    let plan = plan_change(3, 1, 0, 1_000_000, 100_000, DustPolicy::AddChangeToFee { threshold: 0 }).unwrap();
+    assert_eq!(plan.fee, calculate_fee(3, 2, 0));
+
+    // Small sweep (1 input, 3 payments): the fee is recomputed for the no-change shape
+    let plan = plan_change(1, 3, 0, 315_000, 300_000, DustPolicy::AddChangeToFee { threshold: 0 }).unwrap();
+    assert_eq!(plan, ChangePlan { change: 0, fee: calculate_fee(1, 3, 0) });
+
+    // Change below the dust threshold is kept or added to the fee, per policy
+    let plan = plan_change(1, 1, 0, 110_010, 100_000, DustPolicy::AddChangeToFee { threshold: 0 }).unwrap();
+    assert_eq!(plan, ChangePlan { change: 10, fee: 10_000 });
+    let plan = plan_change(1, 1, 0, 110_010, 100_000, DustPolicy::default()).unwrap();
+    assert_eq!(plan, ChangePlan { change: 0, fee: 10_010 });
+    assert_eq!(p2pkh_dust_threshold(), 54);
+
+    // Shortfall
+    assert!(matches!(
+        plan_change(1, 3, 0, 314_999, 300_000, DustPolicy::AddChangeToFee { threshold: 0 }),
+        Err(ProposalError::InsufficientFunds { available: 314_999, required: 315_000, fee: 15_000 })
+    ));
+}
+
+#[test]
+fn test_dust_policy() {
+    // 2_000 zatoshis of change: an output by default, dust at the conventional threshold
+    let inputs = sample_transparent_inputs_with_amounts(&[112_000]);
+    let request = TransactionRequest::new(vec![Payment::new(addresses::TRANSPARENT.to_string(), 100_000)]);
+
+    let pczt = propose_transaction(&inputs, request.clone(), None).unwrap();
+    assert_eq!(pczt.transparent().outputs().len(), 2);
+
+    let rejecting = request.clone().with_dust_policy(DustPolicy::Reject { threshold: DEFAULT_DUST_THRESHOLD });
+    assert!(matches!(
+        propose_transaction(&inputs, rejecting, None),
+        Err(ProposalError::DustChange { change: 2_000, threshold: DEFAULT_DUST_THRESHOLD })
+    ));
+
+    // Folded dust builds, signs and extracts with the larger fee
+    let folding = request.clone().with_dust_policy(DustPolicy::AddChangeToFee { threshold: DEFAULT_DUST_THRESHOLD });
+    let pczt = propose_transaction(&inputs, folding.clone(), None).unwrap();
+    assert_eq!(pczt.transparent().outputs().len(), 1);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(12_000));
+    verify_before_signing(&pczt, &folding, &[]).expect("Folded dust is within the fee policy");
+    let signed = sign_transaction(pczt, 0, &[1u8; 32]).unwrap();
+    finalize_and_extract(signed).expect("Failed to finalize");
+
+    // A threshold of 0 makes nothing dust, under either policy
+    let keeping = request.with_dust_policy(DustPolicy::Reject { threshold: 0 });
+    assert_eq!(propose_transaction(&inputs, keeping, None).unwrap().transparent().outputs().len(), 2);
+
+    // Dust payments are refused under either policy
+    let dusty = TransactionRequest::new(vec![
+        Payment::new(addresses::TRANSPARENT.to_string(), 100_000),
+        Payment::new(addresses::TRANSPARENT_2.to_string(), 1_000),
+    ])
+    .with_dust_policy(DustPolicy::AddChangeToFee { threshold: DEFAULT_DUST_THRESHOLD });
+    assert!(matches!(
+        propose_transaction(&sample_transparent_inputs(), dusty, None),
+        Err(ProposalError::DustPayment { index: 1, amount: 1_000, threshold: DEFAULT_DUST_THRESHOLD })
+    ));
+}
+
+#[test]
+fn test_implicit_fee_above_zip317() {
+    // Dust change folded into the fee: 12_000 instead of the ZIP-317 10_000
+    let inputs = sample_transparent_inputs_with_amounts(&[112_000]);
+    let request = TransactionRequest::new(vec![Payment::new(addresses::TRANSPARENT.to_string(), 100_000)])
+        .with_dust_policy(DustPolicy::AddChangeToFee { threshold: 5_000 });
+    let pczt = propose_transaction(&inputs, request, None).unwrap();
+    assert_eq!(pczt.transparent().outputs().len(), 1);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(12_000));
+
+    // A 2_000 remainder can't pay for the action a change output adds, so it goes
+    // to the fee, within the request's tolerance
+    let inputs = sample_transparent_inputs_with_amounts(&[56_000, 56_000]);
+    let request = TransactionRequest::new(vec![
+        Payment::new(addresses::TRANSPARENT.to_string(), 50_000),
+        Payment::new(addresses::TRANSPARENT_2.to_string(), 50_000),
+    ]);
+    assert!(matches!(
+        propose_transaction(&inputs, request.clone(), None),
+        Err(ProposalError::ExcessiveFee { implicit_fee: 12_000, expected_fee: 10_000, tolerance: 0 })
+    ));
+    let pczt = propose_transaction(&inputs, request.with_fee_tolerance(2_000), None).unwrap();
+    assert_eq!(pczt.transparent().outputs().len(), 2);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(12_000));
+}
+
+#[test]
+fn test_conventional_fee_from_pczt() {
+    let inputs = sample_transparent_inputs();
+
+    // 1 input, payment + change
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).unwrap();
+    assert_eq!(conventional_fee(&pczt), calculate_fee(1, 2, 0));
+
+    // 1 input, transparent change, 1 Orchard output (padded to 2 actions)
+    let pczt = propose_transaction(&inputs, shielded_payment_request(), None).unwrap();
+    assert_eq!(conventional_fee(&pczt), calculate_fee(1, 1, 1));
+}
+
+#[test]
+fn test_fee_estimator() {
+    // Agrees with calculate_fee for P2PKH-only shapes
+    let estimate = FeeEstimator::new().with_p2pkh_inputs(1).with_p2pkh_outputs(1).with_orchard_actions(2).estimate();
+    assert_eq!(estimate.fee, calculate_fee(1, 1, 1));
+    assert_eq!(estimate.logical_actions, 3);
+
+    // Sapling counts the larger of spends and outputs
+    let estimate = FeeEstimator::new().with_sapling_spends(3).with_sapling_outputs(2).estimate();
+    assert_eq!(estimate.fee, 15_000);
+
+    // Outputs count by size: forty 32-byte P2SH outputs are 38 P2PKH outputs' worth
+    let p2sh = [&[0xa9, 0x14][..], &[0u8; 20], &[0x87]].concat();
+    let estimator = (0..40).fold(FeeEstimator::new(), |estimator, _| estimator.with_transparent_output(&p2sh));
+    assert_eq!(estimator.estimate().logical_actions, 38);
+
+    // A 2-of-3 multisig input is larger than a P2PKH input
+    let estimate = FeeEstimator::new().with_p2sh_multisig_input(&two_of_three_redeem_script()).estimate();
+    assert_eq!(estimate.logical_actions, 2);
+
+    // The shape of a PCZT gives its size
+    let pczt = propose_transaction(&sample_transparent_inputs(), shielded_payment_request(), None).unwrap();
+    assert_eq!(FeeEstimator::from_pczt(&pczt).estimate().size, summarize_pczt(&pczt).estimated_size);
+
+    // Proposals price payment outputs by script size too: forty P2SH payments and a
+    // P2PKH change output are 39 actions, not 41
+    let payments = (0..40).map(|_| Payment::to_script(p2sh.clone(), 10_000)).collect();
+    let pczt = propose_transaction(&sample_transparent_inputs(), TransactionRequest::new(payments), None).unwrap();
+    assert_eq!(pczt.transparent().outputs().len(), 41);
+    assert_eq!(conventional_fee(&pczt), 195_000);
+    assert_eq!(summarize_pczt(&pczt).fee, Some(195_000));
+}
+
+#[test]
+fn test_size_estimation() {
+    // Counts padded like the builder's give the size of the proposed PCZT
+    let inputs = sample_transparent_inputs();
+    let shielded = propose_transaction(&inputs, shielded_payment_request(), None).unwrap();
+    assert_eq!(estimated_final_size(&shielded), estimate_tx_size(1, 1, 1));
+    let transparent = propose_transaction(&inputs, simple_payment_request(), None).unwrap();
+    assert_eq!(estimated_final_size(&transparent), estimate_tx_size(1, 2, 0));
+
+    // Orchard actions dominate and grow the size linearly
+    assert!(estimate_tx_size(1, 1, 1) > estimate_tx_size(1, 2, 0));
+    // The builder pads a bundle to 2 actions, not to an even number
+    assert_eq!(estimate_tx_size(0, 0, 1), estimate_tx_size(0, 0, 2));
+    assert!(estimate_tx_size(0, 0, 3) < estimate_tx_size(0, 0, 4));
+    assert_eq!(calculate_fee(0, 0, 3), 15_000);
+    assert!(estimate_tx_size(1_000, 1, 0) < MAX_TX_SIZE);
+    assert!(estimate_tx_size(20_000, 1, 0) > MAX_TX_SIZE);
+}
+
+#[test]
+fn test_check_fee_invariant() {
+    // Exact fee is accepted
+    assert!(check_fee_invariant(100_000, 80_000, 10_000, 10_000, 0).is_ok());
+    // Overpayment within tolerance is accepted
+    assert!(check_fee_invariant(100_000, 80_000, 9_000, 10_000, 1_000).is_ok());
+    // Overpayment beyond tolerance is rejected
+    assert!(matches!(
+        check_fee_invariant(100_000, 80_000, 9_000, 10_000, 999),
+        Err(ProposalError::ExcessiveFee { implicit_fee: 11_000, .. })
+    ));
+    // Shortfalls are left to the builder
+    assert!(check_fee_invariant(50_000, 80_000, 0, 10_000, 0).is_ok());
+}
+
 #[test]
 fn test_full_transaction_workflow() {
     // This test demonstrates the complete workflow with transparent output
@@ -97,6 +616,324 @@ fn test_full_transaction_workflow() {
     assert!(!tx_bytes.is_empty());
 }
 
+#[test]
+fn test_verify_extraction() {
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+
+    use pczt::roles::signer::Signer;
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).expect("Valid secret key");
+    let mut signer = Signer::new(pczt).expect("Failed to create signer");
+    signer.sign_transparent(0, &sk).expect("Failed to sign");
+    let signed = signer.finish();
+    let signed_bytes = serialize_pczt(&signed);
+
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to finalize");
+    let pczt = parse_pczt(&signed_bytes).unwrap();
+    assert!(verify_extraction(&pczt, &tx_bytes).is_ok());
+
+    // A PCZT for a different transaction doesn't match
+    let other = create_test_pczt(&multi_payment_request());
+    assert!(verify_extraction(&other, &tx_bytes).is_err());
+
+    // Trailing garbage is rejected
+    let mut padded = tx_bytes.clone();
+    padded.push(0);
+    assert!(verify_extraction(&pczt, &padded).is_err());
+
+    // Every global field of the v5 header is compared
+    let tampered = |offset: usize, value: u32| {
+        let mut bytes = tx_bytes.clone();
+        bytes[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+        verify_extraction(&pczt, &bytes)
+    };
+    let field = |offset: usize| u32::from_le_bytes(tx_bytes[offset..offset + 4].try_into().unwrap());
+    let mismatch = |result: Result<(), VerificationFailure>, what: &str| {
+        assert!(matches!(&result, Err(VerificationFailure::ExtractionMismatch(msg)) if msg.contains(what)), "{:?}", result);
+    };
+    // Another version or version group changes the layout, so parsing may fail first
+    mismatch(tampered(0, field(0) - 1), "");
+    mismatch(tampered(4, field(4) ^ 1), "");
+    // NU5 and NU6 transactions have the same layout
+    let other_branch = if field(8) == 0xc2d6_d0b4 { 0xc8e7_1055 } else { 0xc2d6_d0b4 };
+    mismatch(tampered(8, other_branch), "Consensus branch ID");
+    mismatch(tampered(12, field(12) + 1), "Lock time");
+    mismatch(tampered(16, field(16) + 1), "Expiry height");
+}
+
+#[test]
+fn test_format_amount() {
+    assert_eq!(format_amount(123_450_000, AmountUnit::Zec), "1.23450000 ZEC");
+    assert_eq!(format_amount(5_000, AmountUnit::Zatoshis), "5000 zatoshis");
+    assert_eq!(format_amount(5_000, AmountUnit::Both), "0.00005000 ZEC (5000 zatoshis)");
+    assert_eq!(format_amount(0, AmountUnit::Zec), "0.00000000 ZEC");
+    assert_eq!(format_amount(21_000_000 * ZATOSHIS_PER_ZEC, AmountUnit::Zec), "21000000.00000000 ZEC");
+}
+
+#[test]
+fn test_review_document() {
+    let request = multi_payment_request();
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, request.clone(), None).expect("Failed to propose");
+
+    let document = export_review(&pczt, &request).expect("Failed to export review");
+    assert_eq!(document.inputs.len(), 1);
+    for (index, payment) in request.payments.iter().enumerate() {
+        assert!(document.outputs.iter().any(|o| o.payment_index == Some(index) && o.value == payment.amount));
+    }
+    let total_out: u64 = document.outputs.iter().map(|o| o.value).sum();
+    assert_eq!(document.inputs[0].value, total_out + document.fee);
+    assert_eq!(document.fee_display, format_amount(document.fee, AmountUnit::Both));
+    assert!(verify_review(&pczt, &request, &document).is_ok());
+
+    // The display unit is part of the approved document
+    let zec_only = export_review_with_unit(&pczt, &request, AmountUnit::Zec).unwrap();
+    assert!(zec_only.outputs.iter().all(|o| o.value_display.ends_with(" ZEC")));
+    assert!(verify_review(&pczt, &request, &zec_only).is_ok());
+
+    // Editing the document after approval is detected
+    let mut tampered = document.clone();
+    tampered.fee += 1;
+    assert!(verify_review(&pczt, &request, &tampered).is_err());
+
+    // A different proposal no longer matches the approved document
+    let other = create_test_pczt(&simple_payment_request());
+    assert!(verify_review(&other, &request, &document).is_err());
+}
+
+#[test]
+fn test_blinded_review() {
+    let request = shielded_payment_request();
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, request.clone(), None).expect("Failed to propose");
+
+    let full = export_review(&pczt, &request).unwrap();
+    let blinded = export_blinded_review(&pczt, &request, AmountUnit::Both).expect("Failed to export blinded review");
+    assert_eq!(blinded.fingerprint, full.fingerprint);
+    assert_eq!(blinded.total_in, amounts::ONE_ZEC);
+    assert_eq!(blinded.total_orchard, amounts::MEDIUM);
+    assert_eq!(blinded.total_in, blinded.total_transparent + blinded.total_orchard + blinded.fee);
+
+    // Orchard amounts are hidden, addresses cut to a prefix
+    let orchard = blinded.outputs.iter().find(|o| o.pool == "orchard").unwrap();
+    assert_eq!(orchard.value, None);
+    assert_eq!(orchard.payment_index, Some(0));
+    let prefix = orchard.address_prefix.as_ref().unwrap();
+    assert!(request.payments[0].address.starts_with(prefix.as_str()) && prefix.len() < request.payments[0].address.len());
+    assert!(verify_blinded_review(&pczt, &request, &blinded).is_ok());
+
+    let mut tampered = blinded.clone();
+    tampered.total_orchard += 1;
+    assert!(verify_blinded_review(&pczt, &request, &tampered).is_err());
+
+    // The policy decides which document each role gets
+    let policy = DisclosurePolicy::default().with_role("treasurer", ReviewDisclosure::Full);
+    assert!(matches!(
+        export_review_for_role(&pczt, &request, AmountUnit::Both, &policy, "treasurer"),
+        Ok(ReviewExport::Full(document)) if document == full
+    ));
+    assert!(matches!(
+        export_review_for_role(&pczt, &request, AmountUnit::Both, &policy, "second-approver"),
+        Ok(ReviewExport::Blinded(review)) if review == blinded
+    ));
+}
+
+#[test]
+fn test_signed_manifest() {
+    use t2z::manifest::*;
+
+    let operator_key = [7u8; 32];
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+
+    let signed = sign_manifest(&pczt, &operator_key).expect("Failed to sign manifest");
+    assert_eq!(signed.manifest.fingerprint, hex::encode(pczt_fingerprint(&pczt)));
+    assert_eq!(signed.manifest.inputs.len(), 1);
+    let trusted_key: [u8; 32] = hex::decode(&signed.public_key).unwrap().try_into().unwrap();
+    assert!(verify_manifest(&pczt, &signed, &trusted_key).is_ok());
+
+    // Later stages still match: signatures are not part of the manifest
+    let signed_pczt = sign_transaction(pczt.clone(), 0, &[1u8; 32]).expect("Failed to sign");
+    assert!(verify_manifest(&signed_pczt, &signed, &trusted_key).is_ok());
+
+    // Another operator's key, an edited manifest and a different proposal are all rejected
+    let other_key: [u8; 32] = hex::decode(&sign_manifest(&pczt, &[8u8; 32]).unwrap().public_key).unwrap().try_into().unwrap();
+    assert!(matches!(verify_manifest(&pczt, &signed, &other_key), Err(VerificationFailure::ManifestSignature(_))));
+
+    let mut tampered = signed.clone();
+    tampered.manifest.fee += 1;
+    assert!(matches!(verify_manifest(&pczt, &tampered, &trusted_key), Err(VerificationFailure::ManifestSignature(_))));
+    let mut tampered = signed.clone();
+    tampered.payload = tampered.payload.replace("\"fee\":", "\"fee\":1");
+    assert!(matches!(verify_manifest(&pczt, &tampered, &trusted_key), Err(VerificationFailure::ManifestSignature(_))));
+
+    // The signature covers the payload bytes, so re-encoding the document keeps it valid
+    let value: serde_json::Value = serde_json::to_value(&signed).unwrap();
+    let reencoded: SignedManifest = serde_json::from_str(&serde_json::to_string_pretty(&value).unwrap()).unwrap();
+    assert!(verify_manifest(&pczt, &reencoded, &trusted_key).is_ok());
+
+    let other = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    assert!(matches!(verify_manifest(&other, &signed, &trusted_key), Err(VerificationFailure::ManifestMismatch(_))));
+}
+
+#[test]
+fn test_summarize_pczt() {
+    let inputs = sample_transparent_inputs();
+
+    // Shielded payment with transparent change back to the input's address
+    let pczt = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+    let summary = summarize_pczt(&pczt);
+    assert_eq!(summary.inputs.len(), 1);
+    assert_eq!(summary.fee, Some(calculate_fee(1, 1, 1)));
+    assert_eq!(summary.change, amounts::ONE_ZEC - amounts::MEDIUM - calculate_fee(1, 1, 1));
+
+    // The padding action is left out
+    let orchard: Vec<_> = summary.outputs.iter().filter(|o| o.pool == OutputPool::Orchard).collect();
+    assert_eq!(orchard.len(), 1);
+    assert_eq!(orchard[0].value, Some(amounts::MEDIUM));
+    assert!(orchard[0].address.is_some() && !orchard[0].is_change);
+
+    // The size estimate is an upper bound on the extracted transaction
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let summary = summarize_pczt(&pczt);
+    assert_eq!(summary.outputs.iter().filter(|o| o.is_change).count(), 1);
+    let signed = sign_transaction(pczt, 0, &[1u8; 32]).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to finalize");
+    assert!(tx_bytes.len() <= summary.estimated_size);
+    assert!(summary.estimated_size - tx_bytes.len() < 10);
+}
+
+#[test]
+fn test_summary_ignores_proposer_set_fields() {
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let payment = summarize_pczt(&pczt).outputs.iter()
+        .position(|output| output.value == Some(amounts::SMALL))
+        .expect("payment output");
+    let pubkey = get_input_pubkey(&pczt, 0).expect("pubkey");
+
+    // A proposer shows the payment under another address and marks it as change
+    let updates = [
+        PcztUpdate::OutputUserAddress { index: payment, address: addresses::TRANSPARENT_2.to_string() },
+        PcztUpdate::OutputDerivation {
+            index: payment,
+            derivation: KeyDerivation { pubkey, seed_fingerprint: [9u8; 32], path: vec![0] },
+        },
+    ];
+    let tampered = update_pczt(pczt, &updates).expect("Failed to update");
+    let summary = summarize_pczt(&tampered);
+    assert_eq!(summary.outputs[payment].address.as_deref(), Some(addresses::TRANSPARENT));
+    assert!(!summary.outputs[payment].is_change);
+    assert_eq!(summary.change, amounts::ONE_ZEC - amounts::SMALL - summary.fee.unwrap());
+
+    // Change addresses named by the caller are counted
+    let summary = summarize_pczt_with_change(&tampered, &[addresses::TRANSPARENT.to_string()]);
+    assert!(summary.outputs[payment].is_change);
+}
+
+#[test]
+fn test_transaction_label() {
+    let inputs = sample_transparent_inputs();
+
+    let unlabeled = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    assert_eq!(transaction_label(&unlabeled), None);
+    assert_eq!(summarize_pczt(&unlabeled).label, None);
+
+    let request = simple_payment_request().with_label("withdrawals-2024-06-01".to_string());
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+
+    // The label survives serialization and signing, and shows up in every summary
+    let parsed = parse_pczt(&serialize_pczt(&pczt)).expect("Failed to parse");
+    let signed = sign_transaction(parsed, 0, &[1u8; 32]).expect("Failed to sign");
+    assert_eq!(transaction_label(&signed).as_deref(), Some("withdrawals-2024-06-01"));
+    assert_eq!(summarize_pczt(&signed).label.as_deref(), Some("withdrawals-2024-06-01"));
+    assert!(json::proposal_to_json(&signed).contains("\"label\":\"withdrawals-2024-06-01\""));
+}
+
+#[test]
+fn test_regtest_network() {
+    let inputs = sample_transparent_inputs();
+
+    // Every upgrade active from height 1: a low default target height still gets NU6
+    let request = simple_payment_request().with_network(Network::Regtest(RegtestActivations::default()));
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    assert_eq!(*pczt.global().consensus_branch_id(), 0xC8E7_1055);
+
+    // Custom activation heights from a JSON request
+    let json = format!(
+        r#"{{"payments":[{{"address":"{}","amount":{}}}],"target_height":150,
+            "network":{{"regtest":{{"nu5":100,"nu6":200}}}}}}"#,
+        addresses::TRANSPARENT, amounts::SMALL
+    );
+    let request: TransactionRequest = serde_json::from_str(&json).expect("Failed to parse request");
+    assert!(matches!(request.effective_network(), Network::Regtest(_)));
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    assert_eq!(*pczt.global().consensus_branch_id(), 0xC2D6_D0B4);
+
+    // The deprecated flag still applies when no network is set
+    let mut request = simple_payment_request();
+    #[allow(deprecated)]
+    {
+        request.use_mainnet = false;
+    }
+    assert_eq!(request.effective_network(), Network::Testnet);
+    assert_eq!(request.with_network(Network::Mainnet).effective_network(), Network::Mainnet);
+}
+
+#[test]
+fn test_expiry_height() {
+    let inputs = sample_transparent_inputs();
+    let mut request = simple_payment_request();
+    request.target_height = Some(2_500_000);
+
+    let pczt = propose_transaction(&inputs, request.clone(), None).expect("Failed to propose");
+    assert_eq!(*pczt.global().expiry_height(), 2_500_040);
+
+    let pczt = propose_transaction(&inputs, request.clone().with_expiry_delta(100), None)
+        .expect("Failed to propose");
+    assert_eq!(*pczt.global().expiry_height(), 2_500_100);
+
+    // An absolute height wins over the delta, and 0 disables expiry
+    let pczt = propose_transaction(&inputs, request.clone().with_expiry_delta(100).with_expiry_height(2_500_010), None)
+        .expect("Failed to propose");
+    assert_eq!(*pczt.global().expiry_height(), 2_500_010);
+
+    // The builder carries the height into the extracted transaction
+    let signed = sign_transaction(pczt, 0, &[1u8; 32]).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to extract");
+    let tx = zcash_primitives::transaction::Transaction::read(&tx_bytes[..], zcash_protocol::consensus::BranchId::Nu5)
+        .expect("Failed to parse transaction");
+    assert_eq!(u32::from(tx.expiry_height()), 2_500_010);
+    let pczt = propose_transaction(&inputs, request.clone().with_expiry_height(0), None)
+        .expect("Failed to propose");
+    assert_eq!(*pczt.global().expiry_height(), 0);
+
+    // Expired before it can be mined
+    let result = propose_transaction(&inputs, request.with_expiry_height(2_499_999), None);
+    assert!(matches!(result, Err(ProposalError::InvalidRequest(_))));
+}
+
+#[test]
+fn test_finalize_reports_unsigned_input() {
+    use t2z::error::FinalizationError;
+
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs_with_amounts(&[amounts::ONE_ZEC, amounts::ONE_ZEC]);
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+
+    // Sign only the first input
+    use pczt::roles::signer::Signer;
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+    let mut signer = Signer::new(pczt).unwrap();
+    signer.sign_transparent(0, &sk).unwrap();
+
+    match finalize_and_extract(signer.finish()) {
+        Err(FinalizationError::InputMissingSignature { index }) => assert_eq!(index, 1),
+        other => panic!("Expected InputMissingSignature, got: {:?}", other.map(|_| ())),
+    }
+}
+
 #[test]
 fn test_pczt_serialization_roundtrip() {
     // Create a PCZT using test helper (which adds realistic inputs)
@@ -118,6 +955,60 @@ fn test_pczt_serialization_roundtrip() {
     );
 }
 
+#[test]
+fn test_canonical_parse() {
+    let request = simple_payment_request();
+    let pczt = create_test_pczt(&request);
+    let serialized = serialize_pczt(&pczt);
+
+    assert!(is_canonical(&serialized).unwrap());
+    assert!(parse_pczt_with_options(&serialized, ParseOptions::canonical()).is_ok());
+
+    // Trailing data still parses, but is not canonical
+    let mut padded = serialized.clone();
+    padded.push(0);
+    assert!(parse_pczt(&padded).is_ok());
+    assert!(!is_canonical(&padded).unwrap());
+    assert!(matches!(
+        parse_pczt_with_options(&padded, ParseOptions::canonical()),
+        Err(t2z::error::ParseError::NonCanonical)
+    ));
+}
+
+#[test]
+fn test_safe_mode_parse_rejects_unknown_proprietary_fields() {
+    let request = simple_payment_request();
+    let pczt = create_test_pczt(&request);
+    assert!(parse_pczt_with_options(&serialize_pczt(&pczt), ParseOptions::safe_mode()).is_ok());
+
+    let with_field = |key: &str| {
+        let update = PcztUpdate::InputProprietary { index: 0, key: key.to_string(), value: vec![1] };
+        serialize_pczt(&update_pczt(pczt.clone(), &[update]).expect("Failed to update"))
+    };
+
+    // The crate's own namespace is recognized
+    assert!(parse_pczt_with_options(&with_field("t2z.note"), ParseOptions::safe_mode()).is_ok());
+
+    // Other namespaces are refused until registered; lenient parsing keeps them
+    let foreign = with_field("acme.route");
+    assert!(parse_pczt(&foreign).is_ok());
+    assert!(matches!(
+        parse_pczt_with_options(&foreign, ParseOptions::safe_mode()),
+        Err(t2z::error::ParseError::UnknownField(_))
+    ));
+    register_proprietary_namespace("acme");
+    assert!(parse_pczt_with_options(&foreign, ParseOptions::safe_mode()).is_ok());
+    assert!(parse_pczt_with_options(&with_field("acmecorp"), ParseOptions::safe_mode()).is_err());
+
+    // Safe mode also requires the canonical encoding
+    let mut padded = serialize_pczt(&pczt);
+    padded.push(0);
+    assert!(matches!(
+        parse_pczt_with_options(&padded, ParseOptions::safe_mode()),
+        Err(t2z::error::ParseError::NonCanonical)
+    ));
+}
+
 #[test]
 fn test_parse_invalid_pczt() {
     let invalid_data = vec![0xFF; 100];
@@ -125,6 +1016,71 @@ fn test_parse_invalid_pczt() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_payment_ovk_overrides_request_ovk() {
+    let request = TransactionRequest::new(vec![
+        Payment::new(addresses::TRANSPARENT.to_string(), amounts::SMALL).with_ovk([2u8; 32]),
+        Payment::new(addresses::TRANSPARENT.to_string(), amounts::SMALL),
+    ])
+    .with_ovk([1u8; 32]);
+
+    let json = serde_json::to_string(&request).unwrap();
+    let parsed: TransactionRequest = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.ovk, Some([1u8; 32]));
+    assert_eq!(parsed.payments[0].ovk, Some([2u8; 32]));
+    assert_eq!(parsed.payments[1].ovk, None);
+
+    // Requests serialized before OVKs existed still deserialize
+    let legacy = r#"{"payments":[{"address":"t1","amount":1,"memo":null,"label":null,"message":null}],"memo":null}"#;
+    let parsed: TransactionRequest = serde_json::from_str(legacy).unwrap();
+    assert_eq!(parsed.ovk, None);
+    assert_eq!(parsed.payments[0].ovk, None);
+
+    // Each Orchard output is recoverable with its payment's OVK, or the request's
+    let request = TransactionRequest::new(vec![
+        Payment::new(addresses::unified_orchard(), amounts::SMALL).with_ovk([2u8; 32]),
+        Payment::new(addresses::unified_orchard(), amounts::MEDIUM),
+    ])
+    .with_ovk([1u8; 32]);
+    let pczt = propose_transaction(&sample_transparent_inputs(), request, None).expect("Failed to propose");
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+    let signed = sign_transaction(proved, 0, &[1u8; 32]).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to extract");
+
+    let values = |ovk| recover_orchard_outputs(&tx_bytes, ovk).into_iter().map(|(value, _)| value).collect::<Vec<_>>();
+    assert_eq!(values([2u8; 32]), vec![amounts::SMALL]);
+    assert_eq!(values([1u8; 32]), vec![amounts::MEDIUM]);
+    assert!(values([3u8; 32]).is_empty());
+}
+
+#[test]
+fn test_memo_encoding_roundtrip() {
+    use t2z::error::MemoError;
+
+    let text = "Thanks for lunch";
+    let padded = encode_memo(text, MemoEncoding::Padded).unwrap();
+    let mut field = padded.clone();
+    field.resize(MEMO_SIZE, 0);
+    assert_eq!(decode_memo(&field).unwrap(), text);
+
+    // Structured JSON that is too long as plain text fits once compressed
+    let json = format!("{{\"items\":[{}]}}", vec!["{\"sku\":\"A-1\",\"qty\":1}"; 40].join(","));
+    assert!(matches!(
+        encode_memo(&json, MemoEncoding::Padded),
+        Err(MemoError::TooLong { .. })
+    ));
+    let compressed = encode_memo(&json, MemoEncoding::Compressed).unwrap();
+    assert_eq!(compressed[0], COMPRESSED_MEMO_MARKER);
+    let mut field = compressed;
+    field.resize(MEMO_SIZE, 0);
+    assert_eq!(decode_memo(&field).unwrap(), json);
+
+    // Empty memo
+    let mut empty = vec![0u8; MEMO_SIZE];
+    empty[0] = 0xF6;
+    assert_eq!(decode_memo(&empty).unwrap(), "");
+}
+
 #[test]
 fn test_payment_with_memo() {
     let payment = payment_with_memo();
@@ -132,6 +1088,45 @@ fn test_payment_with_memo() {
     assert_eq!(payment.memo.unwrap(), "Test payment");
 }
 
+#[test]
+fn test_payment_with_memo_bytes() {
+    use t2z::error::MemoError;
+
+    // A full 512-byte arbitrary data memo is stored as-is and replaces a text memo
+    let mut raw = vec![0xAB; MEMO_SIZE];
+    raw[0] = 0xFF;
+    let payment = Payment::new(addresses::unified_orchard(), amounts::SMALL)
+        .with_memo("replaced".to_string())
+        .with_memo_bytes(raw.clone());
+    assert_eq!(payment.memo, None);
+    assert_eq!(payment.memo_field(MemoEncoding::Compressed).unwrap(), Some(raw.clone()));
+
+    let json = serde_json::to_string(&payment).unwrap();
+    let parsed: Payment = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed.memo_bytes.as_ref(), Some(&raw));
+
+    // The sender recovers the exact 512 bytes from the extracted transaction
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, TransactionRequest::new(vec![payment]).with_ovk([3u8; 32]), None)
+        .expect("Failed to propose");
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+    let signed = sign_transaction(proved, 0, &[1u8; 32]).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to extract");
+    let recovered = recover_orchard_outputs(&tx_bytes, [3u8; 32]);
+    assert!(recovered.iter().any(|(value, memo)| *value == amounts::SMALL && memo[..] == raw[..]));
+
+    // Memos that don't fit are refused instead of being left out
+    let oversized = Payment::new(addresses::unified_orchard(), amounts::SMALL).with_memo_bytes(vec![0xFF; MEMO_SIZE + 1]);
+    assert!(matches!(
+        propose_transaction(&inputs, TransactionRequest::new(vec![oversized]), None),
+        Err(ProposalError::Memo(MemoError::TooLong { len: 513, max: MEMO_SIZE }))
+    ));
+
+    let mut both = Payment::new(addresses::unified_orchard(), amounts::SMALL).with_memo_bytes(vec![0xFF]);
+    both.memo = Some("text".to_string());
+    assert!(matches!(both.memo_field(MemoEncoding::Padded), Err(MemoError::InvalidMemo(_))));
+}
+
 #[test]
 fn test_transaction_request_builder() {
     let request = TransactionRequest::new(vec![
@@ -168,3 +1163,27 @@ fn test_sighash_type() {
     assert_eq!(hash.as_bytes().len(), 32);
     assert_eq!(hash.to_vec().len(), 32);
 }
+
+#[test]
+fn test_change_address_pool() {
+    let pool_addresses = vec![addresses::TRANSPARENT.to_string(), addresses::TRANSPARENT_2.to_string()];
+
+    assert!(matches!(ChangeAddressPool::random(Vec::new()), Err(ProposalError::InvalidRequest(_))));
+
+    let round_robin = ChangeAddressPool::round_robin(pool_addresses.clone()).unwrap();
+    let picks: Vec<_> = (0..3).map(|_| round_robin.next_address().unwrap().to_string()).collect();
+    assert_eq!(picks, [addresses::TRANSPARENT, addresses::TRANSPARENT_2, addresses::TRANSPARENT]);
+
+    let out_of_range = ChangeAddressPool::with_index_callback(pool_addresses.clone(), |len| len).unwrap();
+    assert!(matches!(out_of_range.next_address(), Err(ProposalError::InvalidRequest(_))));
+
+    // The change output pays the address the pool picked
+    let inputs = sample_transparent_inputs();
+    let pool = ChangeAddressPool::with_index_callback(pool_addresses, |_| 1).unwrap();
+    let pczt = propose_transaction_from_pool(&inputs, shielded_payment_request(), &pool)
+        .expect("Failed to propose");
+    assert_eq!(
+        Some(pczt.transparent().outputs()[0].script_pubkey().clone()),
+        address_to_script(addresses::TRANSPARENT_2),
+    );
+}
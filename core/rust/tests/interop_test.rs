@@ -0,0 +1,74 @@
+//! Tests for the `interop` feature's compatibility harness
+#![cfg(feature = "interop")]
+
+use std::path::Path;
+
+use t2z::interop::*;
+use t2z::types::SighashBatch;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+fn fixture_signer(batch: &SighashBatch) -> Result<Vec<[u8; 64]>, String> {
+    let secp = secp256k1::Secp256k1::signing_only();
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap();
+    Ok(batch.inputs.iter()
+        .map(|input| secp.sign_ecdsa(&secp256k1::Message::from_digest(input.sighash), &sk).serialize_compact())
+        .collect())
+}
+
+#[test]
+fn test_own_pczt_passes_every_stage() {
+    let pczt = propose_transaction(&sample_transparent_inputs(), shielded_payment_request(), None)
+        .expect("Failed to propose");
+    let export = serialize_pczt(&pczt);
+
+    let report = check_pczt(Producer::T2z, &export, Some(&fixture_signer));
+    assert!(report.is_compatible(), "issues: {:?}", report.issues().collect::<Vec<_>>());
+    assert!(report.stages.iter().all(|(_, outcome)| *outcome == StageOutcome::Passed));
+
+    // Hex exports load the same, and without a signer only signing and extraction are skipped
+    let hex_export = format!("0x{}\n", hex::encode(&export));
+    let report = check_pczt(Producer::Other("hex".to_string()), hex_export.as_bytes(), None);
+    assert!(report.is_compatible());
+    assert_eq!(report.outcome(InteropStage::Sign), Some(&StageOutcome::Skipped));
+    assert_eq!(report.outcome(InteropStage::Extract), Some(&StageOutcome::Skipped));
+    assert_eq!(report.outcome(InteropStage::Combine), Some(&StageOutcome::Passed));
+}
+
+#[test]
+fn test_incompatibilities_are_reported_by_stage() {
+    let report = check_pczt(Producer::Zashi, b"not a pczt", None);
+    let issues: Vec<_> = report.issues().collect();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].stage, InteropStage::Parse);
+    assert_eq!(report.outcome(InteropStage::Combine), Some(&StageOutcome::Skipped));
+
+    // A signer that can't produce signatures stops before combining
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None).unwrap();
+    let refusing = |_: &SighashBatch| -> Result<Vec<[u8; 64]>, String> { Err("locked".to_string()) };
+    let report = check_pczt(Producer::ZcashClientBackend, &serialize_pczt(&pczt), Some(&refusing));
+    assert!(matches!(report.outcome(InteropStage::Sign), Some(StageOutcome::Failed(_))));
+    assert_eq!(report.outcome(InteropStage::Combine), Some(&StageOutcome::Skipped));
+}
+
+#[test]
+fn test_exported_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/interop");
+    let fixtures = load_dir(&dir).expect("Failed to read fixtures");
+    // An empty directory would pass without checking anything
+    assert!(!fixtures.is_empty(), "no *.pczt or *.hex fixtures in {}", dir.display());
+
+    for (path, export) in fixtures {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let producer = match name.split(['-', '.']).next() {
+            Some("zcash_client_backend") => Producer::ZcashClientBackend,
+            Some("zashi") => Producer::Zashi,
+            _ => Producer::Other(name.clone()),
+        };
+
+        let report = check_pczt(producer, &export, None);
+        assert!(report.is_compatible(), "{}: {:?}", name, report.issues().collect::<Vec<_>>());
+    }
+}
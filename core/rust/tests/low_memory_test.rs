@@ -0,0 +1,23 @@
+//! Tests for the low-memory feature
+#![cfg(feature = "low-memory")]
+
+use t2z::prover_service::ProverServiceConfig;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+#[test]
+fn test_single_threaded_proving() {
+    set_prover_threads(1);
+
+    let pczt = propose_transaction(&sample_transparent_inputs(), shielded_payment_request(), None)
+        .expect("Failed to propose");
+    let proved = prove_transaction(pczt).expect("Failed to prove on one thread");
+    assert!(!is_transparent_only(&proved));
+
+    // Queued PCZTs are held in memory, so the default queue is short
+    assert!(ProverServiceConfig::default().queue_capacity <= 2);
+
+    println!("✅ proving works with bounded parallelism");
+}
@@ -0,0 +1,52 @@
+//! Tests for the multi-party signing helpers
+
+use t2z::error::FinalizationError;
+use t2z::multisig::*;
+use t2z::types::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+fn pubkey(seed: u8) -> [u8; 33] {
+    let sk = secp256k1::SecretKey::from_slice(&multisig_secret(seed)).unwrap();
+    secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), &sk).serialize()
+}
+
+#[test]
+fn test_multisig_signing_workflow() {
+    // Input 0 is 2-of-3 P2SH, input 1 is P2PKH for key 1
+    let mut inputs = p2sh_inputs(&two_of_three_redeem_script(), 1);
+    inputs.extend(parse_transparent_inputs(&sample_transparent_inputs_with_amounts(&[amounts::MEDIUM])).unwrap());
+    let change_address = Some(addresses::TRANSPARENT_2.to_string());
+    let pczt = propose_transaction(&serialize_transparent_inputs(&inputs).unwrap(), simple_payment_request(), change_address)
+        .expect("Failed to propose");
+
+    let missing = missing_signatures(&pczt);
+    assert_eq!(missing.len(), 2);
+    assert_eq!(missing[0], MissingSig { input_index: 0, needed: 2, pubkeys: vec![pubkey(1), pubkey(2), pubkey(3)] });
+    assert_eq!(missing[1].needed, 1);
+    assert!(matches!(check_fully_signed(&pczt), Err(FinalizationError::InputMissingSignature { index: 0 })));
+
+    // Key 1 signs both of its inputs in its own view
+    let views = signer_views(&pczt);
+    let view_of = |key: [u8; 33]| views.iter().find(|view| view.pubkey == key).expect("signer has a view");
+    assert_eq!(view_of(pubkey(1)).input_indices, vec![0, 1]);
+    assert_eq!(view_of(pubkey(3)).input_indices, vec![0]);
+    let first = sign_transaction(view_of(pubkey(1)).pczt.clone(), 0, &multisig_secret(1)).expect("Failed to sign");
+    let first = sign_transaction(first, 1, &multisig_secret(1)).expect("Failed to sign");
+
+    let combined = combine(vec![pczt.clone(), first]).expect("Failed to combine");
+    let missing = missing_signatures(&combined);
+    assert_eq!(missing, vec![MissingSig { input_index: 0, needed: 1, pubkeys: vec![pubkey(2), pubkey(3)] }]);
+    assert!(signer_views(&combined).iter().all(|view| view.pubkey != pubkey(1)));
+
+    // A second key completes the multisig input
+    let second = sign_transaction(view_of(pubkey(2)).pczt.clone(), 0, &multisig_secret(2)).expect("Failed to sign");
+    let combined = combine(vec![combined, second]).expect("Failed to combine");
+    assert!(missing_signatures(&combined).is_empty());
+    check_fully_signed(&combined).expect("Every input is signed");
+    finalize_and_extract(combined).expect("Failed to finalize");
+
+    println!("✅ multisig and single-key inputs signed by separate parties");
+}
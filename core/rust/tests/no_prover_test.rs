@@ -0,0 +1,25 @@
+//! Tests for builds without the `orchard-prover` feature
+#![cfg(all(feature = "proposal", not(feature = "orchard-prover")))]
+
+use t2z::error::ProverError;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+#[test]
+fn test_prove_without_orchard_prover() {
+    let inputs = sample_transparent_inputs();
+
+    // Transparent-only PCZTs need no proofs
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    prove_transaction(pczt).expect("Transparent-only PCZT should pass through");
+
+    // Shielded PCZTs can still be proposed, but not proved
+    let pczt = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+    assert!(matches!(prove_transaction(pczt), Err(ProverError::NotBuilt)));
+    assert!(matches!(init_proving_keys(), Err(ProverError::NotBuilt)));
+    assert!(!unload_verifying_keys());
+
+    println!("✅ prove_transaction() refuses shielded PCZTs without orchard-prover");
+}
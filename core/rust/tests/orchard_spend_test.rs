@@ -0,0 +1,122 @@
+//! Tests for spending Orchard notes
+
+use orchard::keys::{FullViewingKey, Scope, SpendAuthorizingKey, SpendingKey};
+use orchard::note::{RandomSeed, Rho};
+use orchard::tree::{MerkleHashOrchard, MerklePath};
+use orchard::value::NoteValue;
+use t2z::error::{ProposalError, SignatureError};
+use t2z::shielded::*;
+use t2z::types::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+/// A note for the key derived from `seed`, witnessed at position 0 of a tree whose
+/// other leaves are the note itself
+fn orchard_note(seed: u8, value: u64, rseed: u8) -> (SpendingKey, OrchardSpend) {
+    let sk = SpendingKey::from_bytes([seed; 32]).unwrap();
+    let fvk = FullViewingKey::from(&sk);
+    let recipient = fvk.address_at(0u32, Scope::External);
+    let rho = Rho::from_bytes(&[0; 32]).unwrap();
+    let rseed = RandomSeed::from_bytes([rseed; 32], &rho).unwrap();
+    let note = orchard::Note::from_parts(recipient, NoteValue::from_raw(value), rho, rseed).unwrap();
+
+    let leaf = MerkleHashOrchard::from_cmx(&note.commitment().into());
+    let merkle_path = MerklePath::from_parts(0, [leaf; 32]);
+    (sk, OrchardSpend::new(fvk, note, merkle_path))
+}
+
+#[test]
+fn test_propose_and_sign_orchard_spend() {
+    let (sk, spend) = orchard_note(42, amounts::ONE_ZEC, 7);
+
+    let pczt = propose_shielded_transaction(
+        &[],
+        &[ShieldedInput::Orchard(spend)],
+        simple_payment_request(),
+        Some(addresses::TRANSPARENT_2.to_string()),
+    ).expect("Failed to propose");
+
+    // The spend is padded to two actions; transparent payment plus change
+    assert_eq!(pczt.orchard().actions().len(), 2);
+    assert_eq!(pczt.transparent().outputs().len(), 2);
+    assert_eq!(conventional_fee(&pczt), 20_000);
+
+    // Only the real spend needs a signature
+    let requests = orchard_signing_requests(&pczt).expect("Failed to list signing requests");
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].value, Some(amounts::ONE_ZEC));
+    let action_index = requests[0].action_index;
+
+    let other = SpendAuthorizingKey::from(&SpendingKey::from_bytes([43; 32]).unwrap());
+    assert!(matches!(
+        sign_orchard_spend(pczt.clone(), action_index, &other),
+        Err(SignatureError::KeyMismatch { .. })
+    ));
+    assert!(matches!(
+        append_orchard_signature(pczt.clone(), action_index, [0u8; 64]),
+        Err(SignatureError::VerificationFailed)
+    ));
+
+    let signed = sign_orchard_spend(pczt, action_index, &SpendAuthorizingKey::from(&sk))
+        .expect("Failed to sign Orchard spend");
+    assert!(orchard_signing_requests(&signed).unwrap().is_empty());
+
+    println!("✅ Orchard note spent and signed");
+}
+
+#[test]
+fn test_orchard_spends_need_common_anchor() {
+    let (_, first) = orchard_note(42, amounts::MEDIUM, 7);
+    let (_, second) = orchard_note(42, amounts::MEDIUM, 8);
+
+    let result = propose_shielded_transaction(
+        &[],
+        &[ShieldedInput::Orchard(first.clone()), ShieldedInput::Orchard(second)],
+        simple_payment_request(),
+        Some(addresses::TRANSPARENT_2.to_string()),
+    );
+    assert!(matches!(result, Err(ProposalError::InvalidRequest(_))));
+
+    // Flags that disable spends can't be combined with Orchard inputs
+    let request = simple_payment_request()
+        .with_orchard_flags(OrchardFlags { spends_enabled: false, outputs_enabled: true });
+    let result = propose_shielded_transaction(
+        &[],
+        &[ShieldedInput::Orchard(first)],
+        request,
+        Some(addresses::TRANSPARENT_2.to_string()),
+    );
+    assert!(matches!(result, Err(ProposalError::InvalidRequest(_))));
+
+    println!("✅ mismatched witnesses and disabled spends are rejected");
+}
+
+#[test]
+fn test_review_lists_orchard_spends() {
+    let (_, spend) = orchard_note(42, amounts::ONE_ZEC, 7);
+    let request = simple_payment_request();
+    let pczt = propose_shielded_transaction(
+        &[],
+        &[ShieldedInput::Orchard(spend)],
+        request.clone(),
+        Some(addresses::TRANSPARENT_2.to_string()),
+    ).expect("Failed to propose");
+
+    // The fee counts the spent note, and the padding action isn't listed
+    let document = export_review(&pczt, &request).expect("Failed to export review");
+    assert!(document.inputs.is_empty());
+    assert_eq!(document.spends.len(), 1);
+    assert_eq!((document.spends[0].pool.as_str(), document.spends[0].value), ("orchard", amounts::ONE_ZEC));
+    assert_eq!(document.fee, conventional_fee(&pczt));
+    assert!(verify_review(&pczt, &request, &document).is_ok());
+
+    let blinded = export_blinded_review(&pczt, &request, AmountUnit::Both).expect("Failed to export blinded review");
+    assert_eq!((blinded.num_inputs, blinded.num_shielded_spends), (0, 1));
+    assert_eq!(blinded.total_in, amounts::ONE_ZEC);
+    assert_eq!(blinded.total_in, blinded.total_transparent + blinded.total_orchard + blinded.fee);
+    assert!(verify_blinded_review(&pczt, &request, &blinded).is_ok());
+
+    println!("✅ reviews list Orchard spends and count them in the fee");
+}
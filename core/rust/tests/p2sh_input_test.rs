@@ -0,0 +1,67 @@
+//! Tests for spending P2SH multisig transparent inputs
+
+use t2z::error::{FinalizationError, ProposalError};
+use t2z::types::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+#[test]
+fn test_spend_p2sh_multisig_inputs() {
+    let redeem_script = two_of_three_redeem_script();
+    let inputs = serialize_transparent_inputs(&p2sh_inputs(&redeem_script, 2)).unwrap();
+    assert_eq!(parse_transparent_inputs(&inputs).unwrap()[1].redeem_script.as_deref(), Some(&redeem_script[..]));
+
+    // Change can't go to one cosigner's key
+    assert!(matches!(
+        propose_transaction(&inputs, simple_payment_request(), None),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+    let change_address = Some(addresses::TRANSPARENT_2.to_string());
+    let pczt = propose_transaction(&inputs, simple_payment_request(), change_address).expect("Failed to propose");
+    assert!(pczt.transparent().inputs().iter().all(|input| input.redeem_script().is_some()));
+
+    // Each 2-of-3 input is about two standard P2PKH inputs in size
+    assert_eq!(conventional_fee(&pczt), 20_000);
+
+    // One signature per input isn't enough
+    let mut signed = pczt;
+    for index in 0..2 {
+        signed = sign_transaction(signed, index, &multisig_secret(1)).expect("Failed to sign");
+    }
+    assert!(matches!(
+        finalize_and_extract(signed.clone()),
+        Err(FinalizationError::InputMissingSignature { index: 0 })
+    ));
+
+    for index in 0..2 {
+        signed = sign_transaction(signed, index, &multisig_secret(3)).expect("Failed to sign");
+    }
+    let estimate = estimated_final_size(&signed);
+    let tx = finalize_and_extract(signed).expect("Failed to finalize");
+
+    // The estimate allows 74 bytes per signature; DER signatures are up to 3 bytes shorter
+    assert!(tx.len() <= estimate);
+    assert!(estimate - tx.len() <= 4 * 3);
+
+    println!("✅ 2-of-3 P2SH inputs spent");
+}
+
+#[test]
+fn test_propose_rejects_bad_redeem_scripts() {
+    let redeem_script = two_of_three_redeem_script();
+
+    // The redeem script must hash to the input's P2SH script
+    let mut inputs = p2sh_inputs(&redeem_script, 2);
+    inputs[1].script_pubkey = p2sh_script(&[0x51]);
+    let result = propose_transaction(&serialize_transparent_inputs(&inputs).unwrap(), simple_payment_request(), None);
+    assert!(matches!(result, Err(ProposalError::InputScriptMismatch { index: 1 })));
+
+    // Only multisig redeem scripts can be finalized
+    let inputs = p2sh_inputs(&[0x51], 1);
+    let result = propose_transaction(&serialize_transparent_inputs(&inputs).unwrap(), simple_payment_request(), None);
+    assert!(matches!(result, Err(ProposalError::UnsupportedScriptType { index: 0, kind: ScriptKind::P2sh })));
+
+    println!("✅ mismatched and non-multisig redeem scripts are rejected");
+}
@@ -0,0 +1,44 @@
+/// Tests for the proving parameter stores
+use std::sync::Arc;
+use t2z::params::*;
+
+#[test]
+fn test_memory_params_store() {
+    let store = MemoryParamsStore::new();
+    assert_eq!(store.get("sapling-spend.params").unwrap(), None);
+
+    store.put("sapling-spend.params", b"spend").unwrap();
+    store.put("sapling-spend.params", b"spend v2").unwrap();
+    assert_eq!(store.get("sapling-spend.params").unwrap(), Some(b"spend v2".to_vec()));
+
+    println!("✅ MemoryParamsStore stores and replaces values");
+}
+
+#[test]
+fn test_filesystem_params_store() {
+    let dir = std::env::temp_dir().join(format!("t2z-params-{}", std::process::id()));
+    let store = FilesystemParamsStore::new(&dir);
+
+    assert_eq!(store.get("sapling-output.params").unwrap(), None);
+    store.put("sapling-output.params", b"output").unwrap();
+    assert_eq!(store.get("sapling-output.params").unwrap(), Some(b"output".to_vec()));
+    assert!(dir.join("sapling-output.params").exists());
+
+    // Keys cannot escape the directory
+    assert!(store.put("../escape", b"x").is_err());
+    assert!(store.get("..").is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    println!("✅ FilesystemParamsStore persists parameters as files");
+}
+
+#[test]
+fn test_installed_params_store() {
+    let store = Arc::new(MemoryParamsStore::new());
+    store.put("custom", b"value").unwrap();
+
+    set_params_store(store);
+    assert_eq!(params_store().get("custom").unwrap(), Some(b"value".to_vec()));
+
+    println!("✅ set_params_store() replaces the default store");
+}
@@ -0,0 +1,92 @@
+/// Tests for the background prover service
+use std::sync::mpsc;
+use t2z::prover_service::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+#[test]
+fn test_prover_service_proves_queued_pczts() {
+    let service = ProverService::start(ProverServiceConfig { workers: 1, queue_capacity: 2 });
+    let inputs = sample_transparent_inputs();
+
+    let shielded = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+    let transparent = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+
+    let ticket = service.submit(shielded).expect("Failed to submit");
+
+    let (sender, receiver) = mpsc::channel();
+    service
+        .submit_with_callback(transparent, ProofPriority::Normal, Box::new(move |result| sender.send(result.is_ok()).unwrap()))
+        .expect("Failed to submit");
+
+    let proved = ticket.wait().expect("Failed to prove");
+    assert!(!proved.orchard().actions().is_empty());
+    assert!(receiver.recv().unwrap(), "Callback should receive a proved PCZT");
+
+    service.stop();
+    println!("✅ ProverService proves PCZTs from its queue");
+}
+
+#[test]
+fn test_prover_service_backpressure() {
+    // No workers are free while the first shielded proof runs, so the queue fills up
+    let service = ProverService::start(ProverServiceConfig { workers: 1, queue_capacity: 1 });
+    let inputs = sample_transparent_inputs();
+    let propose = || propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+
+    let mut tickets = Vec::new();
+    let mut rejected = false;
+    for _ in 0..4 {
+        match service.try_submit(propose()) {
+            Ok(ticket) => tickets.push(ticket),
+            Err(e) => {
+                assert!(matches!(e, t2z::error::ProverError::QueueFull));
+                rejected = true;
+            }
+        }
+    }
+    assert!(rejected, "try_submit should fail once the queue is full");
+
+    // Stopping finishes the queued work
+    service.stop();
+    for ticket in tickets {
+        assert!(ticket.wait().is_ok());
+    }
+    println!("✅ ProverService applies backpressure when its queue is full");
+}
+
+#[test]
+fn test_prover_service_priority_lanes() {
+    // The worker is busy building the proving key, so everything below stays queued
+    let service = ProverService::start(ProverServiceConfig { workers: 1, queue_capacity: 8 });
+    let inputs = sample_transparent_inputs();
+    let propose = || propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+
+    let batch: Vec<_> = (0..3)
+        .map(|_| service.submit_with_priority(propose(), ProofPriority::Low).expect("Failed to submit"))
+        .collect();
+    let urgent = service.submit_with_priority(propose(), ProofPriority::High).expect("Failed to submit");
+
+    // The urgent PCZT jumps ahead of the batch (unless a worker already picked it up)
+    match service.status(urgent.id()) {
+        Some(ProofStatus::Queued { priority, position }) => {
+            assert_eq!(priority, ProofPriority::High);
+            assert_eq!(position, 0);
+            assert_eq!(service.queue_depth().iter().sum::<usize>(), 4);
+        }
+        status => assert!(matches!(status, Some(ProofStatus::Proving | ProofStatus::Finished))),
+    }
+    assert_eq!(service.status(u64::MAX), None);
+
+    urgent.wait().expect("Failed to prove");
+    for ticket in batch {
+        let id = ticket.id();
+        ticket.wait().expect("Failed to prove");
+        assert_eq!(service.status(id), Some(ProofStatus::Finished));
+    }
+
+    service.stop();
+    println!("✅ ProverService takes high-priority PCZTs first");
+}
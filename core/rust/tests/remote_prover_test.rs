@@ -0,0 +1,66 @@
+//! Tests for proving over the remote prover protocol
+#![cfg(feature = "remote-prover")]
+
+use t2z::error::ProverError;
+use t2z::prover_service::ProverServiceConfig;
+use t2z::experimental::remote_prover::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+fn start_server(token: &str) -> std::net::SocketAddr {
+    let server = ProverServer::bind("127.0.0.1:0", token, ProverServiceConfig::default())
+        .expect("Failed to bind");
+    let addr = server.local_addr().expect("Failed to get address");
+    std::thread::spawn(move || server.serve());
+    addr
+}
+
+#[test]
+fn test_remote_proving() {
+    let addr = start_server("secret");
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+    let fingerprint = pczt_fingerprint(&pczt);
+
+    let client = RemoteProver::new(addr, "secret").expect("Failed to create client");
+    let proved = client.prove(pczt).expect("Failed to prove remotely");
+
+    assert_eq!(pczt_fingerprint(&proved), fingerprint);
+    println!("✅ Remote prover returns the proved PCZT");
+}
+
+#[test]
+fn test_remote_proving_rejects_bad_token() {
+    let addr = start_server("secret");
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+
+    let client = RemoteProver::new(addr, "wrong").expect("Failed to create client");
+    assert!(matches!(client.prove(pczt), Err(ProverError::RemoteUnauthorized)));
+    println!("✅ Remote prover rejects requests with a bad token");
+}
+
+#[test]
+fn test_remote_prover_caps_connections() {
+    let server = ProverServer::bind("127.0.0.1:0", "secret", ProverServiceConfig::default())
+        .expect("Failed to bind")
+        .with_max_connections(1);
+    let addr = server.local_addr().expect("Failed to get address");
+    std::thread::spawn(move || server.serve());
+
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let client = RemoteProver::new(addr, "secret").expect("Failed to create client");
+
+    // An idle connection takes the only slot, so the next request waits unanswered
+    let idle = std::net::TcpStream::connect(addr).expect("Failed to connect");
+    let waiting = client.clone().with_timeout(std::time::Duration::from_secs(1));
+    assert!(matches!(waiting.prove(pczt.clone()), Err(ProverError::Remote(_))));
+
+    // Closing it frees the slot
+    drop(idle);
+    client.prove(pczt).expect("Failed to prove once the slot is free");
+    println!("✅ Remote prover handles at most max_connections connections");
+}
@@ -0,0 +1,73 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use t2z::error::ReservationError;
+use t2z::reservation::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+/// Locks kept in memory, as a wallet's UTXO table would keep them
+#[derive(Default)]
+struct MemoryStore {
+    locks: RefCell<BTreeMap<ReservationToken, Vec<([u8; 32], u32)>>>,
+}
+
+impl ReservationStore for MemoryStore {
+    fn reserve(&self, token: ReservationToken, outpoints: &[([u8; 32], u32)]) -> Result<(), String> {
+        self.locks.borrow_mut().insert(token, outpoints.to_vec());
+        Ok(())
+    }
+
+    fn release(&self, token: ReservationToken) -> Result<(), String> {
+        self.locks.borrow_mut().remove(&token);
+        Ok(())
+    }
+
+    fn tokens(&self) -> Result<Vec<ReservationToken>, String> {
+        Ok(self.locks.borrow().keys().copied().collect())
+    }
+}
+
+#[test]
+fn test_reservation_roundtrip_and_release() {
+    let inputs = sample_transparent_inputs();
+    let pczt = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    assert!(reservation_token(&pczt).is_none());
+
+    let store = MemoryStore::default();
+    let (pczt, token) = reserve_inputs(pczt, &store).expect("Failed to reserve");
+    let utxo = &types::parse_transparent_inputs(&inputs).unwrap()[0];
+    assert_eq!(store.locks.borrow()[&token], vec![(utxo.txid, utxo.vout)]);
+
+    // The token survives serialization
+    let parsed = parse_pczt(&serialize_pczt(&pczt)).expect("Failed to parse");
+    assert_eq!(reservation_token(&parsed), Some(token));
+
+    assert_eq!(release_reservation(&parsed, &store).expect("Failed to release"), token);
+    assert!(store.locks.borrow().is_empty());
+
+    let unreserved = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    assert!(matches!(release_reservation(&unreserved, &store), Err(ReservationError::NoToken)));
+
+    println!("✅ reservation token is stamped, survives serialization and releases its locks");
+}
+
+#[test]
+fn test_reconcile_releases_orphaned_locks() {
+    let inputs = sample_transparent_inputs();
+    let store = MemoryStore::default();
+
+    let first = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let second = propose_transaction(&inputs, simple_payment_request(), None).expect("Failed to propose");
+    let (first, kept) = reserve_inputs(first, &store).expect("Failed to reserve");
+    let (_, orphaned) = reserve_inputs(second, &store).expect("Failed to reserve");
+
+    // Only the first PCZT is still around after the "crash"
+    let released = reconcile(&store, &[first]).expect("Failed to reconcile");
+    assert_eq!(released, vec![orphaned]);
+    assert_eq!(store.tokens().unwrap(), vec![kept]);
+
+    println!("✅ reconcile releases locks without an outstanding PCZT");
+}
@@ -0,0 +1,159 @@
+//! Tests for spending Sapling notes (requires the `sapling` feature)
+#![cfg(feature = "sapling")]
+
+use sapling::value::NoteValue;
+use sapling::zip32::ExtendedSpendingKey;
+use sapling::{MerklePath, Node, Note, Rseed};
+use t2z::error::{ProposalError, SignatureError};
+use t2z::shielded::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+/// A note for the key derived from `seed`, witnessed at position 0 of a tree whose
+/// other leaves are `sibling`
+fn sapling_note(seed: u8, value: u64, sibling: u8) -> (ExtendedSpendingKey, SaplingSpend) {
+    let extsk = ExtendedSpendingKey::master(&[seed; 32]);
+    let dfvk = extsk.to_diversifiable_full_viewing_key();
+    let (_, address) = dfvk.default_address();
+    let note = Note::from_parts(address, NoteValue::from_raw(value), Rseed::AfterZip212([sibling; 32]));
+
+    let leaf = Node::from_cmu(&note.cmu());
+    let merkle_path = MerklePath::from_parts(vec![leaf; 32], 0u64.into()).unwrap();
+    let spend = SaplingSpend::new(dfvk.fvk().clone(), note, merkle_path)
+        .with_proof_generation_key(extsk.expsk.proof_generation_key());
+    (extsk, spend)
+}
+
+/// Encoded UFVK of an Orchard-only account, and its Orchard full viewing key
+fn orchard_ufvk(mainnet: bool) -> (String, orchard::keys::FullViewingKey) {
+    use zcash_address::unified::{Encoding, Fvk, Ufvk};
+    use zcash_address::Network;
+
+    let sk = orchard::keys::SpendingKey::from_bytes([5; 32]).unwrap();
+    let fvk = orchard::keys::FullViewingKey::from(&sk);
+    let ufvk = Ufvk::try_from_items(vec![Fvk::Orchard(fvk.to_bytes())]).unwrap();
+    let network = if mainnet { Network::Main } else { Network::Test };
+    (ufvk.encode(&network), fvk)
+}
+
+#[test]
+fn test_propose_and_sign_sapling_spend() {
+    let (extsk, spend) = sapling_note(1, amounts::ONE_ZEC, 7);
+
+    let pczt = propose_shielded_transaction(
+        &[],
+        &[ShieldedInput::Sapling(spend)],
+        shielded_payment_request(),
+        Some(addresses::TRANSPARENT.to_string()),
+    ).expect("Failed to propose");
+
+    // One spend, padded to two Sapling outputs; Orchard payment plus transparent change
+    assert_eq!(pczt.sapling().spends().len(), 1);
+    assert_eq!(pczt.sapling().outputs().len(), 2);
+    assert_eq!(pczt.transparent().inputs().len(), 0);
+    assert_eq!(pczt.transparent().outputs().len(), 1);
+    assert_eq!(conventional_fee(&pczt), 25_000);
+
+    let other = ExtendedSpendingKey::master(&[2; 32]);
+    assert!(matches!(
+        sign_sapling_spend(pczt.clone(), 0, &other.expsk.ask),
+        Err(SignatureError::KeyMismatch { index: 0 })
+    ));
+    assert!(matches!(
+        sign_sapling_spend(pczt.clone(), 1, &extsk.expsk.ask),
+        Err(SignatureError::InvalidInputIndex(1))
+    ));
+    sign_sapling_spend(pczt, 0, &extsk.expsk.ask).expect("Failed to sign Sapling spend");
+
+    println!("✅ Sapling note spent to Orchard and signed");
+}
+
+#[test]
+fn test_sapling_spends_need_common_anchor() {
+    let (_, first) = sapling_note(1, amounts::MEDIUM, 7);
+    let (_, second) = sapling_note(1, amounts::MEDIUM, 8);
+
+    let result = propose_shielded_transaction(
+        &[],
+        &[ShieldedInput::Sapling(first.clone()), ShieldedInput::Sapling(second)],
+        simple_payment_request(),
+        Some(addresses::TRANSPARENT.to_string()),
+    );
+    assert!(matches!(result, Err(ProposalError::InvalidRequest(_))));
+
+    // Pools are only selected from without shielded inputs
+    let mut request = simple_payment_request();
+    request.coin_selection = types::CoinSelection::LargestFirst;
+    let result = propose_shielded_transaction(
+        &sample_transparent_inputs(),
+        &[ShieldedInput::Sapling(first)],
+        request,
+        None,
+    );
+    assert!(matches!(result, Err(ProposalError::InvalidRequest(_))));
+
+    println!("✅ mismatched witnesses and coin selection are rejected");
+}
+
+#[test]
+fn test_migrate_sapling_to_orchard() {
+    let (_, spend) = sapling_note(1, amounts::ONE_ZEC, 7);
+    let (ufvk, fvk) = orchard_ufvk(false);
+    let template = types::TransactionRequest::new(vec![]).with_network(types::Network::Testnet);
+
+    let migration = migrate_sapling_to_orchard(&[spend.clone()], &ufvk, template.clone())
+        .expect("Failed to migrate");
+    assert_eq!(migration.pczts.len(), 1);
+    assert!(migration.skipped_notes.is_empty());
+
+    // Two padded Sapling outputs and two Orchard actions, no change
+    let pczt = &migration.pczts[0];
+    assert_eq!(conventional_fee(pczt), 20_000);
+    assert_eq!(pczt.transparent().outputs().len(), 0);
+    let default_address = fvk.address_at(0u32, orchard::keys::Scope::External).to_raw_address_bytes();
+    let migrated: Vec<_> = pczt.orchard().actions().iter()
+        .filter(|action| *action.output().value() != Some(0))
+        .collect();
+    assert_eq!(migrated.len(), 1);
+    assert_eq!(*migrated[0].output().value(), Some(amounts::ONE_ZEC - 20_000));
+    assert_eq!(*migrated[0].output().recipient(), Some(default_address));
+
+    // Wrong network, explicit payments and dust notes are rejected
+    let (mainnet_ufvk, _) = orchard_ufvk(true);
+    assert!(matches!(
+        migrate_sapling_to_orchard(&[spend.clone()], &mainnet_ufvk, template.clone()),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+    assert!(matches!(
+        migrate_sapling_to_orchard(&[spend], &ufvk, shielded_payment_request().with_network(types::Network::Testnet)),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+    let (_, dust) = sapling_note(1, 5_000, 7);
+    assert!(matches!(
+        migrate_sapling_to_orchard(&[dust], &ufvk, template),
+        Err(ProposalError::InsufficientFunds { fee: 20_000, .. })
+    ));
+
+    println!("✅ Sapling note migrated to the UFVK's Orchard address");
+}
+
+#[test]
+fn test_migration_skips_uneconomic_transactions() {
+    let (_, spend) = sapling_note(1, amounts::MEDIUM, 7);
+    let (_, dust) = sapling_note(2, 5_000, 7);
+    let (ufvk, _) = orchard_ufvk(false);
+    let template = types::TransactionRequest::new(vec![]).with_network(types::Network::Testnet);
+
+    // A full first transaction, then a dust note alone in the second
+    let mut notes = vec![spend; MIGRATION_NOTES_PER_TRANSACTION];
+    notes.push(dust);
+    let migration = migrate_sapling_to_orchard(&notes, &ufvk, template).expect("Failed to migrate");
+
+    assert_eq!(migration.pczts.len(), 1);
+    assert_eq!(migration.pczts[0].sapling().spends().len(), MIGRATION_NOTES_PER_TRANSACTION);
+    assert_eq!(migration.skipped_notes, vec![MIGRATION_NOTES_PER_TRANSACTION]);
+
+    println!("✅ Dust left in Sapling doesn't fail the rest of the migration");
+}
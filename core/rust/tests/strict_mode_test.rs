@@ -0,0 +1,43 @@
+/// Tests for strict mode. Strictness is process-wide, so everything runs in one test.
+use t2z::error::{ProposalError, VerificationFailure};
+use t2z::types::*;
+use t2z::*;
+
+mod common;
+use common::fixtures::*;
+
+#[test]
+fn test_strict_mode_refuses_lossy_requests() {
+    let inputs = sample_transparent_inputs();
+    let transparent = || Payment::new(addresses::TRANSPARENT.to_string(), amounts::SMALL);
+    let propose = |payment: Payment| propose_transaction(&inputs, TransactionRequest::new(vec![payment]), None);
+
+    // Lenient (default): lossy data is dropped
+    assert_eq!(strictness(), Strictness::Lenient);
+    assert!(propose(transparent().with_memo("dropped".to_string())).is_ok());
+    assert!(propose(transparent().with_label("ignored".to_string())).is_ok());
+
+    set_strictness(Strictness::Strict);
+
+    let refused = |payment: Payment| matches!(propose(payment), Err(ProposalError::StrictMode(_)));
+    assert!(refused(transparent().with_memo("dropped".to_string())));
+    assert!(refused(transparent().with_label("ignored".to_string())));
+    assert!(refused(transparent().with_message("ignored".to_string())));
+    assert!(refused(Payment::new("not an address".to_string(), amounts::SMALL)));
+
+    // Requests without lossy data still work
+    assert!(propose(transparent()).is_ok());
+    assert!(propose(Payment::new(addresses::unified_orchard(), amounts::SMALL).with_memo("kept".to_string())).is_ok());
+
+    // Received PCZTs must carry consistent preimages before signing
+    let request = simple_payment_request();
+    let tampered = tamper_pubkey_preimage(&propose_transaction(&inputs, request.clone(), None).unwrap());
+    assert!(matches!(
+        verify_before_signing(&tampered, &request, &[]),
+        Err(VerificationFailure::InvalidPreimage { index: 0 })
+    ));
+
+    set_strictness(Strictness::Lenient);
+    assert!(verify_before_signing(&tampered, &request, &[]).is_ok());
+    println!("✅ strict mode refuses silently-lossy requests");
+}
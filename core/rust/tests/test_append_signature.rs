@@ -72,3 +72,25 @@ fn test_append_signature_external() {
         }
     }
 }
+
+#[test]
+fn test_sign_transaction_with_secret_key() {
+    use t2z::error::SignatureError;
+
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+
+    // A key that doesn't own the input is refused
+    assert!(matches!(
+        sign_transaction(pczt.clone(), 0, &[2u8; 32]),
+        Err(SignatureError::KeyMismatch { index: 0 })
+    ));
+    assert!(matches!(sign_transaction(pczt.clone(), 0, &[0u8; 32]), Err(SignatureError::InvalidSecretKey)));
+    assert!(matches!(sign_transaction(pczt.clone(), 1, &[1u8; 32]), Err(SignatureError::InvalidInputIndex(1))));
+
+    let signed = sign_transaction(pczt, 0, &[1u8; 32]).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to finalize");
+    assert!(!tx_bytes.is_empty());
+
+    println!("✅ sign_transaction signs with a caller-held key");
+}
@@ -1,4 +1,6 @@
 /// Tests for remaining API functions: get_sighash, verify_before_signing, combine
+use t2z::error::VerificationFailure;
+use t2z::types::*;
 use t2z::*;
 
 mod common;
@@ -78,6 +80,38 @@ fn test_verify_before_signing_insufficient_outputs() {
     // Test passes regardless - documents current behavior
 }
 
+#[test]
+fn test_verify_report_collects_every_failure() {
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+
+    let pczt = propose_transaction(&inputs, request.clone(), None).expect("Failed to propose");
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+
+    // The undeclared change output is only a warning
+    let report = verify_report(&proved, &request, &[]);
+    assert!(report.is_ok());
+    assert_eq!(report.warnings().count(), 1);
+
+    // Both payments are wrong: each is reported, not just the first
+    let wrong_request = TransactionRequest::new(vec![
+        Payment::new(addresses::TRANSPARENT.to_string(), amounts::SMALL + 1),
+        Payment::new(addresses::TRANSPARENT_2.to_string(), amounts::SMALL),
+    ]);
+    let report = verify_report(&proved, &wrong_request, &[]);
+    assert!(!report.is_ok());
+    assert_eq!(report.errors().count(), 2);
+    assert_eq!(report.warnings().count(), 2);
+    assert!(report.errors().all(|e| matches!(e, VerificationFailure::OutputMismatch(_))));
+
+    // verify_before_signing stops at the same first error
+    let first = report.errors().next().unwrap().to_string();
+    let err = verify_before_signing(&proved, &wrong_request, &[]).unwrap_err();
+    assert_eq!(err.to_string(), first);
+
+    println!("✅ verify_report() collects every failed check");
+}
+
 #[test]
 fn test_combine_single_pczt() {
     // Test that combine works with single PCZT (trivial case)
@@ -136,3 +170,505 @@ fn test_combine_parallel_signing() {
 
     println!("✅ combine() works with signed PCZTs");
 }
+
+#[test]
+fn test_pczt_fingerprint() {
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    let fingerprint = pczt_fingerprint(&pczt);
+
+    // Signing does not change the fingerprint
+    use pczt::roles::signer::Signer;
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).expect("Valid secret key");
+    let mut signer = Signer::new(pczt).expect("Failed to create signer");
+    signer.sign_transparent(0, &sk).expect("Failed to sign");
+    let signed = signer.finish();
+    assert_eq!(pczt_fingerprint(&signed), fingerprint);
+
+    // A different transaction has a different fingerprint
+    let other = propose_transaction(&inputs, multi_payment_request(), None).expect("Failed to propose");
+    assert_ne!(pczt_fingerprint(&other), fingerprint);
+
+    println!("✅ pczt_fingerprint() ignores signatures and distinguishes transactions");
+}
+
+#[test]
+fn test_json_schema_roundtrip() {
+    use t2z::json::*;
+
+    // Requests round-trip and carry the schema version
+    let request = simple_payment_request();
+    let json = request_to_json(&request);
+    let value: serde_json::Value = serde_json::from_str(&json).expect("valid JSON");
+    assert_eq!(value["version"], JSON_SCHEMA_VERSION);
+    assert_eq!(request_to_json(&request_from_json(&json).expect("Failed to decode")), json);
+
+    // Unknown fields are ignored, newer versions are rejected
+    let mut extended = value.clone();
+    extended["added_in_a_later_release"] = serde_json::json!(true);
+    assert!(request_from_json(&extended.to_string()).is_ok());
+    extended["version"] = serde_json::json!(JSON_SCHEMA_VERSION + 1);
+    assert!(request_from_json(&extended.to_string()).is_err());
+
+    // Inputs round-trip with display-order txids
+    use t2z::types::{parse_transparent_inputs, serialize_transparent_inputs};
+    let inputs = parse_transparent_inputs(&sample_transparent_inputs()).expect("valid inputs");
+    let decoded_inputs = inputs_from_json(&inputs_to_json(&inputs)).expect("Failed to decode inputs");
+    assert_eq!(serialize_transparent_inputs(&decoded_inputs).unwrap(), serialize_transparent_inputs(&inputs).unwrap());
+
+    // Proposal and decoded transaction agree on the transparent effects
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let proposal: ProposalJson = serde_json::from_str(&proposal_to_json(&pczt)).expect("valid proposal");
+    assert_eq!(proposal.inputs.len(), inputs.len());
+    assert!(proposal.fee.is_some());
+
+    use pczt::roles::signer::Signer;
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).expect("Valid secret key");
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+    let mut signer = Signer::new(proved).expect("Failed to create signer");
+    signer.sign_transparent(0, &sk).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signer.finish()).expect("Failed to finalize");
+
+    let decoded: DecodedTransactionJson =
+        serde_json::from_str(&decode_transaction_json(&tx_bytes).expect("Failed to decode tx")).expect("valid JSON");
+    assert_eq!(decoded.expiry_height, proposal.expiry_height);
+    // Final transactions don't record their network, so only proposals name addresses
+    let scripts = |outputs: &[OutputJson]| {
+        outputs.iter().map(|o| (o.value, o.script_pubkey.clone())).collect::<Vec<_>>()
+    };
+    assert_eq!(scripts(&decoded.outputs), scripts(&proposal.outputs));
+    assert_eq!(proposal.outputs[0].address.as_deref(), Some(addresses::TRANSPARENT));
+    assert_eq!(decoded.inputs[0].txid, proposal.inputs[0].txid);
+
+    println!("✅ JSON documents are versioned and round-trip");
+}
+
+#[test]
+fn test_change_ownership_proof() {
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    let change_index = pczt.transparent().outputs().len() - 1;
+
+    let secp = secp256k1::Secp256k1::new();
+    let sign = |sk: &secp256k1::SecretKey, message: [u8; 32]| {
+        secp.sign_ecdsa(&secp256k1::Message::from_digest(message), sk).serialize_compact()
+    };
+
+    // Signed with the input key
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).expect("Valid secret key");
+    let message = change_ownership_message(&pczt, change_index).expect("Failed to get message");
+    let signature = sign(&sk, message);
+    let proof = prove_change_ownership(&pczt, change_index, 0, signature).expect("Proof should verify");
+    assert!(verify_change_ownership(&pczt, &proof).is_ok());
+
+    // The proof does not carry over to another output
+    if change_index > 0 {
+        let moved = t2z::types::ChangeOwnershipProof { output_index: 0, ..proof };
+        assert!(verify_change_ownership(&pczt, &moved).is_err());
+    }
+
+    // A key that does not control an input is rejected
+    let other = secp256k1::SecretKey::from_slice(&[2u8; 32]).expect("Valid secret key");
+    assert!(prove_change_ownership(&pczt, change_index, 0, sign(&other, message)).is_err());
+
+    // Out-of-range indices are rejected
+    assert!(change_ownership_message(&pczt, 999).is_err());
+    assert!(prove_change_ownership(&pczt, change_index, 999, signature).is_err());
+
+    println!("✅ change ownership proofs bind the change output to an input key");
+}
+
+#[test]
+fn test_change_ownership_rejects_swapped_preimage() {
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let change_index = pczt.transparent().outputs().len() - 1;
+
+    // An attacker stores their own key as input 0's pubkey preimage
+    let secp = secp256k1::Secp256k1::new();
+    let attacker = secp256k1::SecretKey::from_slice(&[2u8; 32]).expect("Valid secret key");
+    let attacker_pubkey = secp256k1::PublicKey::from_secret_key(&secp, &attacker).serialize();
+    let pubkey = get_input_pubkey(&pczt, 0).expect("pubkey");
+    let mut bytes = serialize_pczt(&pczt);
+    let at = bytes.windows(33).position(|window| window == pubkey).expect("preimage is serialized");
+    bytes[at..at + 33].copy_from_slice(&attacker_pubkey);
+    let tampered = parse_pczt(&bytes).expect("still a well-formed PCZT");
+
+    assert!(matches!(get_input_pubkey(&tampered, 0), Err(t2z::error::InspectionError::MissingInputPubkey(0))));
+
+    // A proof signed with that key doesn't verify
+    let message = change_ownership_message(&tampered, change_index).expect("Failed to get message");
+    let signature = secp.sign_ecdsa(&secp256k1::Message::from_digest(message), &attacker).serialize_compact();
+    let proof = t2z::types::ChangeOwnershipProof { output_index: change_index, input_index: 0, signature };
+    assert!(verify_change_ownership(&tampered, &proof).is_err());
+
+    println!("✅ change ownership proofs need a preimage that hashes to the input's key");
+}
+
+#[test]
+fn test_sighash_batch() {
+    use t2z::types::SighashBatch;
+
+    let request = simple_payment_request();
+    let inputs = sample_transparent_inputs();
+
+    let pczt = propose_transaction(&inputs, request, None).expect("Failed to propose");
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+
+    let batch = export_sighash_batch(&proved).expect("Failed to export batch");
+    assert_eq!(batch.fingerprint, pczt_fingerprint(&proved));
+    assert_eq!(batch.inputs.len(), proved.transparent().inputs().len());
+    assert_eq!(batch.inputs[0].sighash, *get_sighash(&proved, 0).expect("sighash").as_bytes());
+
+    // Round-trips through the binary document
+    let bytes = batch.to_bytes().expect("Failed to serialize batch");
+    assert_eq!(SighashBatch::from_bytes(&bytes).expect("Failed to parse batch"), batch);
+
+    // Any corruption is caught by the checksum
+    let mut corrupted = bytes.clone();
+    corrupted[10] ^= 1;
+    assert!(SighashBatch::from_bytes(&corrupted).is_err());
+
+    // A pubkey that doesn't match its P2PKH script is rejected
+    let mut mismatched = batch.clone();
+    mismatched.inputs[0].pubkey[1] ^= 1;
+    assert!(SighashBatch::from_bytes(&mismatched.to_bytes().unwrap()).is_err());
+
+    // Lengths that don't fit the format's u16 fields are refused, not truncated
+    let mut oversized = batch.clone();
+    oversized.inputs[0].script_pubkey = vec![0x51; u16::MAX as usize + 1];
+    assert!(matches!(oversized.to_bytes(), Err(t2z::error::ParseError::InvalidFormat(_))));
+    let mut oversized = batch.clone();
+    oversized.inputs = vec![batch.inputs[0].clone(); u16::MAX as usize + 1];
+    assert!(matches!(oversized.to_bytes(), Err(t2z::error::ParseError::InvalidFormat(_))));
+
+    println!("✅ sighash batch round-trips and validates consistency");
+}
+
+#[test]
+fn test_script_address_roundtrip() {
+    use zcash_protocol::consensus::NetworkType;
+
+    // P2PKH
+    let script = address_to_script(addresses::TRANSPARENT).expect("transparent address");
+    assert_eq!(script.len(), 25);
+    assert_eq!(script_to_address(&script, NetworkType::Test).as_deref(), Some(addresses::TRANSPARENT));
+
+    // P2SH renders with the P2SH prefix and round-trips
+    let mut p2sh = vec![0xa9, 0x14];
+    p2sh.extend_from_slice(&[7u8; 20]);
+    p2sh.push(0x87);
+    let p2sh_address = script_to_address(&p2sh, NetworkType::Main).expect("P2SH script");
+    assert!(p2sh_address.starts_with("t3"));
+    assert_eq!(address_to_script(&p2sh_address), Some(p2sh));
+
+    // Non-standard scripts and shielded addresses have no counterpart
+    assert_eq!(script_to_address(&[0x6a, 0x01, 0x00], NetworkType::Test), None);
+    assert_eq!(address_to_script(&addresses::unified_orchard()), None);
+    assert_eq!(address_to_script("not an address"), None);
+
+    println!("✅ script_to_address and address_to_script round-trip");
+}
+
+#[test]
+fn test_classify_script() {
+    use t2z::types::ScriptKind;
+
+    let pubkey = secp256k1::PublicKey::from_secret_key(
+        &secp256k1::Secp256k1::new(),
+        &secp256k1::SecretKey::from_slice(&[1u8; 32]).unwrap(),
+    ).serialize();
+    let mut p2pk = vec![0x21];
+    p2pk.extend_from_slice(&pubkey);
+    p2pk.push(0xac);
+    // 1-of-2 multisig
+    let mut multisig = vec![0x51, 0x21];
+    multisig.extend_from_slice(&pubkey);
+    multisig.push(0x21);
+    multisig.extend_from_slice(&pubkey);
+    multisig.extend_from_slice(&[0x52, 0xae]);
+
+    assert_eq!(classify_script(&address_to_script(addresses::TRANSPARENT).unwrap()), ScriptKind::P2pkh);
+    assert_eq!(classify_script(&[[0xa9, 0x14].as_slice(), &[7u8; 20], &[0x87]].concat()), ScriptKind::P2sh);
+    assert_eq!(classify_script(&p2pk), ScriptKind::P2pk);
+    assert_eq!(classify_script(&multisig), ScriptKind::Multisig);
+    assert_eq!(classify_script(&[0x6a, 0x02, 0xca, 0xfe]), ScriptKind::NullData);
+    assert_eq!(classify_script(&[0x6a, 0x02, 0xca]), ScriptKind::NonStandard);
+    assert_eq!(classify_script(&[0x51]), ScriptKind::NonStandard);
+
+    println!("✅ scripts are classified by standard template");
+}
+
+#[test]
+fn test_payment_to_raw_script() {
+    use t2z::error::ProposalError;
+    use t2z::types::{Payment, TransactionRequest};
+
+    let inputs = sample_transparent_inputs();
+    let p2sh = [[0xa9, 0x14].as_slice(), &[7u8; 20], &[0x87]].concat();
+    let request = TransactionRequest::new(vec![Payment::to_script(p2sh.clone(), amounts::SMALL)]);
+
+    let pczt = propose_transaction(&inputs, request.clone(), None).expect("Failed to propose");
+    assert!(pczt.transparent().outputs().iter().any(|o| o.script_pubkey() == &p2sh && *o.value() == amounts::SMALL));
+    verify_before_signing(&pczt, &request, &[]).expect("Script payment should verify");
+
+    // A different script is caught
+    let other = TransactionRequest::new(vec![Payment::to_script([[0xa9, 0x14].as_slice(), &[8u8; 20], &[0x87]].concat(), amounts::SMALL)]);
+    assert!(verify_before_signing(&pczt, &other, &[]).is_err());
+
+    // The script travels as hex in JSON
+    let json = serde_json::to_string(&request.payments[0]).unwrap();
+    assert!(json.contains(&format!("\"script_pubkey\":\"a914{}87\"", "07".repeat(20))));
+    let decoded: Payment = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.script_pubkey, Some(p2sh.clone()));
+
+    // Templates the builder can't emit, non-standard scripts, and ambiguous payments are refused
+    for script in [vec![0x6a, 0x02, 0xca, 0xfe], vec![0x51]] {
+        let request = TransactionRequest::new(vec![Payment::to_script(script, amounts::SMALL)]);
+        assert!(matches!(propose_transaction(&inputs, request, None), Err(ProposalError::InvalidRequest(_))));
+    }
+    let mut both = Payment::to_script(p2sh, amounts::SMALL);
+    both.address = addresses::TRANSPARENT.to_string();
+    assert!(matches!(
+        propose_transaction(&inputs, TransactionRequest::new(vec![both]), None),
+        Err(ProposalError::InvalidRequest(_))
+    ));
+
+    println!("✅ payments to raw scripts are built and verified");
+}
+
+#[test]
+fn test_inputs_from_listunspent_json() {
+    use t2z::json::inputs_from_listunspent_json;
+
+    let secp = secp256k1::Secp256k1::new();
+    let sk = secp256k1::SecretKey::from_slice(&[1u8; 32]).expect("Valid secret key");
+    let pubkey = secp256k1::PublicKey::from_secret_key(&secp, &sk);
+    let script_bytes = t2z::types::script_to_raw(
+        &zcash_transparent::address::TransparentAddress::from_pubkey(&pubkey).script().into(),
+    ).expect("P2PKH script");
+    let address = &script_to_address(&script_bytes, zcash_protocol::consensus::NetworkType::Test).expect("P2PKH");
+    let script: String = script_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    let display_txid = format!("{}{}", "00".repeat(31), "ab");
+    let resolver = |addr: &str| (addr == address.as_str()).then_some(pubkey);
+
+    // zcashd listunspent, as a full JSON-RPC response with a ZEC amount
+    let listunspent = format!(
+        r#"{{"result":[{{"txid":"{}","vout":1,"generated":false,"address":"{}","scriptPubKey":"{}","amount":0.0012345,"confirmations":6,"spendable":true}}],"error":null,"id":1}}"#,
+        display_txid, address, script
+    );
+    let inputs = inputs_from_listunspent_json(&listunspent, resolver).expect("Failed to import listunspent");
+    assert_eq!(inputs.len(), 1);
+    assert_eq!(inputs[0].amount, 123_450);
+    assert_eq!(inputs[0].vout, 1);
+    assert_eq!(inputs[0].txid[0], 0xab, "txids are converted to internal byte order");
+    assert_eq!(inputs[0].height, None);
+
+    // getaddressutxos, as a bare array with zatoshi amounts and heights
+    let utxos = format!(
+        r#"[{{"address":"{}","txid":"{}","outputIndex":0,"script":"{}","satoshis":500000,"height":2500000}}]"#,
+        address, display_txid, script
+    );
+    let inputs = inputs_from_listunspent_json(&utxos, resolver).expect("Failed to import getaddressutxos");
+    assert_eq!((inputs[0].amount, inputs[0].height), (500_000, Some(2_500_000)));
+
+    // The resolved key must control the script
+    let other = secp256k1::PublicKey::from_secret_key(&secp, &secp256k1::SecretKey::from_slice(&[2u8; 32]).unwrap());
+    assert!(inputs_from_listunspent_json(&utxos, |_| Some(other)).is_err());
+    assert!(inputs_from_listunspent_json(&utxos, |_| None).is_err());
+
+    println!("✅ listunspent and getaddressutxos results import as inputs");
+}
+
+#[test]
+fn test_parse_transparent_inputs_json() {
+    let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+    let expected = parse_transparent_inputs(&sample_transparent_inputs()).expect("Failed to parse");
+    let input = &expected[0];
+    let mut display_txid = input.txid;
+    display_txid.reverse();
+
+    let json = |txid_field: &str, txid: &[u8]| format!(
+        r#"[{{"{}":"{}","vout":{},"amountZat":{},"scriptPubKey":"{}","pubkey":"{}","confirmations":3}}]"#,
+        txid_field, hex(txid), input.vout, input.amount, hex(&input.script_pubkey), hex(&input.pubkey.serialize())
+    );
+
+    // Display order and internal order give the same input
+    for document in [json("txid", &display_txid), json("txidLE", &input.txid)] {
+        let parsed = parse_transparent_inputs_json(&document).expect("Failed to parse JSON inputs");
+        assert_eq!(serialize_transparent_inputs(&parsed).unwrap(), serialize_transparent_inputs(&expected).unwrap());
+    }
+
+    // Exactly one txid field, and every byte string must be valid hex
+    let both = json("txid", &display_txid).replacen("\"vout\"", &format!("\"txidLE\":\"{}\",\"vout\"", hex(&input.txid)), 1);
+    assert!(parse_transparent_inputs_json(&both).is_err());
+    assert!(parse_transparent_inputs_json(&json("txid", &display_txid).replace("\"pubkey\":\"", "\"pubkey\":\"zz")).is_err());
+    assert!(parse_transparent_inputs_json("{}").is_err());
+
+    println!("✅ JSON inputs parse with either txid byte order");
+}
+
+#[test]
+fn test_write_pczt_matches_serialize() {
+    let pczt = propose_transaction(&sample_transparent_inputs(), shielded_payment_request(), None)
+        .expect("Failed to propose");
+
+    let mut streamed = Vec::new();
+    write_pczt(&pczt, &mut streamed).expect("Failed to write");
+    assert_eq!(streamed, serialize_pczt(&pczt));
+
+    // Batched sighashes agree with per-input sighashes
+    let sighashes = get_sighashes(&pczt).expect("Failed to get sighashes");
+    assert_eq!(sighashes.len(), pczt.transparent().inputs().len());
+    assert_eq!(sighashes[0].as_bytes(), get_sighash(&pczt, 0).expect("sighash").as_bytes());
+
+    println!("✅ write_pczt writes the canonical encoding");
+}
+
+#[test]
+fn test_save_and_load_pczt() {
+    use t2z::error::FileError;
+
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let dir = std::env::temp_dir().join(format!("t2z-files-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(format!("proposal.{}", PCZT_FILE_EXTENSION));
+
+    save_pczt(&path, &pczt).expect("Failed to save");
+    assert_eq!(std::fs::read(&path).unwrap(), serialize_pczt(&pczt));
+    // Saving again replaces the file and leaves no temporary file behind
+    save_pczt(&path, &pczt).expect("Failed to save");
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+    // Concurrent saves to one path don't share a temporary file
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| save_pczt(&path, &pczt).expect("Failed to save concurrently"));
+        }
+    });
+    assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+    assert_eq!(std::fs::read(&path).unwrap(), serialize_pczt(&pczt));
+
+    let loaded = load_pczt(&path).expect("Failed to load");
+    assert_eq!(serialize_pczt(&loaded), serialize_pczt(&pczt));
+
+    // A raw transaction is refused before parsing
+    let tx_path = dir.join("tx.bin");
+    std::fs::write(&tx_path, [0x05, 0x00, 0x00, 0x80]).unwrap();
+    assert!(matches!(load_pczt(&tx_path), Err(FileError::NotAPczt(_))));
+    assert!(matches!(load_pczt(dir.join("missing.pczt")), Err(FileError::Io(_))));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    println!("✅ PCZTs round-trip through files");
+}
+
+#[test]
+fn test_update_pczt() {
+    use t2z::error::UpdateError;
+    use t2z::types::{KeyDerivation, PcztUpdate, PreimageHash};
+
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    let fingerprint = pczt_fingerprint(&pczt);
+    let pubkey = get_input_pubkey(&pczt, 0).expect("pubkey");
+
+    let updates = vec![
+        PcztUpdate::GlobalProprietary { key: "t2z:batch".to_string(), value: b"42".to_vec() },
+        PcztUpdate::InputDerivation {
+            index: 0,
+            derivation: KeyDerivation { pubkey, seed_fingerprint: [9u8; 32], path: vec![0x8000_002c, 0x8000_0085, 0x8000_0000, 0, 7] },
+        },
+        PcztUpdate::InputPreimage { index: 0, hash: PreimageHash::Sha256, preimage: b"secret".to_vec() },
+        PcztUpdate::OutputUserAddress { index: 0, address: addresses::TRANSPARENT.to_string() },
+        PcztUpdate::OutputProprietary { index: 0, key: "t2z:invoice".to_string(), value: b"INV-1".to_vec() },
+    ];
+    let updated = update_pczt(pczt.clone(), &updates).expect("Failed to update");
+
+    assert_eq!(updated.global().proprietary().get("t2z:batch"), Some(&b"42".to_vec()));
+    let input = &updated.transparent().inputs()[0];
+    assert!(input.bip32_derivation().contains_key(&pubkey));
+    assert_eq!(input.sha256_preimages().len(), 1);
+    let output = &updated.transparent().outputs()[0];
+    assert_eq!(output.user_address().as_deref(), Some(addresses::TRANSPARENT));
+    assert_eq!(output.proprietary().get("t2z:invoice"), Some(&b"INV-1".to_vec()));
+
+    // Updates add metadata only; the transaction is unchanged
+    assert_eq!(pczt_fingerprint(&updated), fingerprint);
+
+    // Out-of-range indices are rejected before anything is applied
+    let bad = [PcztUpdate::InputProprietary { index: 9, key: "k".to_string(), value: vec![] }];
+    assert!(matches!(update_pczt(pczt.clone(), &bad), Err(UpdateError::InvalidInputIndex(9))));
+    let bad = [PcztUpdate::OutputUserAddress { index: 9, address: String::new() }];
+    assert!(matches!(update_pczt(pczt.clone(), &bad), Err(UpdateError::InvalidOutputIndex(9))));
+
+    // A derivation must produce a key that can sign the input
+    let mut other_key = pubkey;
+    other_key[32] ^= 1;
+    let bad = [PcztUpdate::InputDerivation {
+        index: 0,
+        derivation: KeyDerivation { pubkey: other_key, seed_fingerprint: [9u8; 32], path: vec![0] },
+    }];
+    assert!(matches!(update_pczt(pczt, &bad), Err(UpdateError::InvalidUpdate(_))));
+
+    println!("✅ update_pczt enriches a PCZT without changing the transaction");
+}
+
+#[test]
+fn test_propose_records_input_derivations() {
+    let mut inputs = parse_transparent_inputs(&sample_transparent_inputs()).expect("Failed to parse inputs");
+    let pubkey = inputs[0].pubkey.serialize();
+    inputs[0].derivation_path = Some(KeyDerivation {
+        pubkey,
+        seed_fingerprint: [9u8; 32],
+        path: vec![0x8000_002c, 0x8000_0085, 0x8000_0000, 0, 3],
+    });
+
+    // The derivation survives the binary and JSON input formats
+    let serialized = serialize_transparent_inputs(&inputs).unwrap();
+    assert_eq!(parse_transparent_inputs(&serialized).unwrap()[0].derivation_path, inputs[0].derivation_path);
+    let json = t2z::json::inputs_to_json(&inputs);
+    assert_eq!(t2z::json::inputs_from_json(&json).unwrap()[0].derivation_path, inputs[0].derivation_path);
+
+    let pczt = propose_transaction(&serialized, simple_payment_request(), None).expect("Failed to propose");
+    assert!(pczt.transparent().inputs()[0].bip32_derivation().contains_key(&pubkey));
+
+    // Inputs without one get none
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    assert!(pczt.transparent().inputs()[0].bip32_derivation().is_empty());
+
+    // A derivation of another key is rejected
+    let mut mismatched = inputs.clone();
+    let other_key = secp256k1::SecretKey::from_slice(&[2u8; 32]).unwrap();
+    mismatched[0].derivation_path.as_mut().unwrap().pubkey =
+        secp256k1::PublicKey::from_secret_key(&secp256k1::Secp256k1::new(), &other_key).serialize();
+    let result = propose_transaction(&serialize_transparent_inputs(&mismatched).unwrap(), simple_payment_request(), None);
+    assert!(matches!(result, Err(t2z::error::ProposalError::InvalidRequest(_))));
+
+    // Paths too long for the one-byte length don't serialize
+    let mut overlong = inputs;
+    overlong[0].derivation_path.as_mut().unwrap().path = vec![0; 256];
+    assert!(matches!(serialize_transparent_inputs(&overlong), Err(t2z::error::ParseError::InvalidFormat(_))));
+
+    println!("✅ Proposals record input derivations for hardware signers");
+}
+
+#[test]
+fn test_validate_pczt_rejects_bad_preimages() {
+    use t2z::error::VerificationFailure;
+
+    let pczt = propose_transaction(&sample_transparent_inputs(), simple_payment_request(), None)
+        .expect("Failed to propose");
+    assert!(validate_pczt(&pczt).is_ok());
+
+    let tampered = tamper_pubkey_preimage(&pczt);
+    assert!(matches!(validate_pczt(&tampered), Err(VerificationFailure::InvalidPreimage { index: 0 })));
+
+    println!("✅ validate_pczt re-derives hash160 preimages");
+}
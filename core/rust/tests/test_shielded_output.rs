@@ -50,6 +50,12 @@ fn test_transparent_to_orchard_workflow() {
 
     println!("✅ PCZT created with {} Orchard action(s)", pczt.orchard().actions().len());
 
+    // The payment's note commitment is known before signing
+    let proposed_outputs = get_orchard_outputs(&pczt);
+    assert!(proposed_outputs.iter().any(|o| {
+        o.value == Some(amounts::SMALL) && o.recipient == Some(orchard_addr.to_raw_address_bytes())
+    }));
+
     // 2. Add Orchard proofs (this is the key step for shielded outputs)
     let proved = prove_transaction(pczt).expect("Failed to add Orchard proofs");
 
@@ -75,6 +81,14 @@ fn test_transparent_to_orchard_workflow() {
     // (This is a basic sanity check - full validation would require parsing the tx)
     assert!(tx_bytes.len() > 100, "Shielded transaction should be substantial size");
 
+    // The on-chain actions carry the same commitments and ephemeral keys
+    let decoded = decode_orchard_outputs(&tx_bytes).expect("Failed to decode Orchard outputs");
+    assert_eq!(decoded.len(), proposed_outputs.len());
+    for (on_chain, proposed) in decoded.iter().zip(&proposed_outputs) {
+        assert_eq!(on_chain.cmx, proposed.cmx);
+        assert_eq!(on_chain.ephemeral_key, proposed.ephemeral_key);
+    }
+
     println!("✅ Complete transparent->Orchard workflow successful!");
 }
 
@@ -119,3 +133,104 @@ fn test_orchard_with_memo() {
     assert!(!tx_bytes.is_empty());
     println!("✅ Orchard transaction with memo created successfully");
 }
+
+#[test]
+fn test_orchard_change_policy() {
+    use t2z::error::ProposalError;
+    use t2z::types::ChangePolicy;
+
+    let inputs = sample_transparent_inputs();
+    let change_address = addresses::unified_orchard();
+
+    // 1 input, 1 transparent payment, 2 padded Orchard actions
+    let request = simple_payment_request().with_change_policy(ChangePolicy::Orchard);
+    let pczt = propose_transaction(&inputs, request, Some(change_address.clone()))
+        .expect("Failed to propose with Orchard change");
+    assert_eq!(pczt.transparent().outputs().len(), 1, "Only the payment should be transparent");
+    assert!(get_orchard_outputs(&pczt).iter().any(|o| o.value == Some(amounts::ONE_ZEC - amounts::SMALL - 15_000)));
+
+    // Orchard change needs an Orchard receiver
+    let request = simple_payment_request().with_change_policy(ChangePolicy::Orchard);
+    assert!(matches!(propose_transaction(&inputs, request, None), Err(ProposalError::InvalidRequest(_))));
+
+    // Auto follows the change address
+    let request = simple_payment_request().with_change_policy(ChangePolicy::Auto);
+    let pczt = propose_transaction(&inputs, request, Some(addresses::TRANSPARENT_2.to_string()))
+        .expect("Failed to propose with transparent change");
+    assert_eq!(pczt.transparent().outputs().len(), 2);
+    assert!(pczt.orchard().actions().is_empty());
+
+    let request = simple_payment_request().with_change_policy(ChangePolicy::Auto);
+    let pczt = propose_transaction(&inputs, request, Some(change_address)).expect("Failed to propose");
+    assert_eq!(pczt.transparent().outputs().len(), 1);
+
+    println!("✅ change policy routes change to Orchard");
+}
+
+#[test]
+fn test_orchard_flags_spends_disabled() {
+    use t2z::error::{ProposalError, VerificationFailure};
+    use t2z::types::OrchardFlags;
+
+    let inputs = sample_transparent_inputs();
+
+    let default = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+    assert_eq!(get_orchard_flags(&default), Some(OrchardFlags::ENABLED));
+
+    // Transparent-funded: only dummy spends, so spends can be disabled
+    let request = shielded_payment_request().with_orchard_flags(OrchardFlags::SPENDS_DISABLED);
+    let pczt = propose_transaction(&inputs, request.clone(), None).expect("Failed to propose");
+    assert_eq!(get_orchard_flags(&pczt), Some(OrchardFlags::SPENDS_DISABLED));
+    verify_before_signing(&pczt, &request, &[]).expect("Flags match the request");
+
+    // A PCZT with other flags than requested fails verification
+    assert!(matches!(
+        verify_before_signing(&default, &request, &[]),
+        Err(VerificationFailure::OrchardFlagsMismatch { expected: 0b10, actual: 0b11 })
+    ));
+
+    // The flags survive proving, signing and extraction
+    let proved = prove_transaction(pczt).expect("Failed to prove");
+    let signed = sign_transaction(proved, 0, &[1u8; 32]).expect("Failed to sign");
+    let tx_bytes = finalize_and_extract(signed.clone()).expect("Failed to finalize");
+    verify_extraction(&signed, &tx_bytes).expect("Extraction should match");
+
+    // Outputs can't be disabled when there are Orchard payments
+    let flags = OrchardFlags { spends_enabled: false, outputs_enabled: false };
+    let request = shielded_payment_request().with_orchard_flags(flags);
+    assert!(matches!(propose_transaction(&inputs, request, None), Err(ProposalError::InvalidRequest(_))));
+
+    println!("✅ Orchard bundle can be marked spends-disabled");
+}
+
+#[test]
+fn test_init_proving_keys() {
+    // Building is idempotent and leaves the key resident for prove_transaction
+    init_proving_keys().expect("Failed to build proving key");
+    init_proving_keys().expect("Second init should be a no-op");
+
+    let pczt = propose_transaction(&sample_transparent_inputs(), shielded_payment_request(), None)
+        .expect("Failed to propose");
+    prove_transaction(pczt).expect("Failed to prove");
+
+    println!("✅ init_proving_keys() warms the Orchard proving key");
+}
+
+#[test]
+fn test_unload_verifying_keys() {
+    let inputs = sample_transparent_inputs();
+    let extract = || {
+        let pczt = propose_transaction(&inputs, shielded_payment_request(), None).expect("Failed to propose");
+        let proved = prove_transaction(pczt).expect("Failed to prove");
+        let signed = sign_transaction(proved, 0, &[1u8; 32]).expect("Failed to sign");
+        finalize_and_extract(signed).expect("Failed to finalize")
+    };
+
+    // The cached key gives the same transaction shape, and is rebuilt after unloading
+    let first = extract();
+    unload_verifying_keys();
+    let second = extract();
+    assert_eq!(first.len(), second.len());
+
+    println!("✅ unload_verifying_keys() drops the cached Orchard verifying key");
+}
@@ -0,0 +1,36 @@
+//! Tests for the `testing` feature's deterministic funding fixtures
+#![cfg(feature = "testing")]
+
+use t2z::testing::*;
+use t2z::types::*;
+use t2z::*;
+use zcash_protocol::consensus::NetworkType;
+
+#[test]
+fn test_keys_are_deterministic() {
+    assert_eq!(TestKey::from_seed(7).pubkey, TestKey::from_seed(7).pubkey);
+    assert_ne!(TestKey::from_seed(7).pubkey, TestKey::from_seed(8).pubkey);
+}
+
+#[test]
+fn test_regtest_coinbase_script_matches_inputs() {
+    let key = TestKey::from_seed(1);
+    let inputs = funded_inputs(&key, &[100_000]);
+    assert_eq!(inputs[0].script_pubkey, regtest_coinbase_script(&key));
+    assert!(key.address(NetworkType::Regtest).starts_with("tm"));
+}
+
+#[test]
+fn test_full_pipeline_with_testing_fixtures() {
+    let key = TestKey::fixture();
+    let inputs = funded_inputs_bytes(&key, &[1_000_000]);
+
+    let payment = Payment::new(key.address(NetworkType::Test), 100_000);
+    let pczt = propose_transaction(&inputs, TransactionRequest::new(vec![payment]), None)
+        .expect("Failed to propose");
+    let pczt = prove_transaction(pczt).expect("Failed to prove");
+    let signed = sign_all_inputs(pczt, &key).expect("Failed to sign");
+
+    let tx_bytes = finalize_and_extract(signed).expect("Failed to finalize");
+    assert!(!tx_bytes.is_empty());
+}
@@ -0,0 +1,71 @@
+//! Tests for the wasm-bindgen exports (`wasm-pack test --node --features wasm`)
+#![cfg(all(feature = "wasm", target_arch = "wasm32"))]
+
+use t2z::error::ErrorCode;
+use t2z::types::parse_transparent_inputs;
+use t2z::wasm;
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+mod common;
+use common::fixtures::*;
+
+/// The `code` property of a thrown `Error`
+fn error_code(error: JsValue) -> String {
+    js_sys::Reflect::get(&error, &JsValue::from_str("code"))
+        .expect("errors are objects")
+        .as_string()
+        .expect("errors carry a string code")
+}
+
+#[wasm_bindgen_test]
+fn test_wasm_inputs_from_json() {
+    let inputs = parse_transparent_inputs(&sample_transparent_inputs()).unwrap();
+    let serialized = wasm::inputs_from_json(&t2z::json::inputs_to_json(&inputs)).expect("Failed to convert inputs");
+    assert_eq!(serialized, sample_transparent_inputs());
+
+    let error = wasm::inputs_from_json("not json").unwrap_err();
+    assert_eq!(error_code(error), ErrorCode::InvalidFormat.as_str());
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "proposal")]
+fn test_wasm_transparent_flow() {
+    let request_json = t2z::json::request_to_json(&simple_payment_request());
+    let pczt = wasm::propose_transaction(&sample_transparent_inputs(), &request_json, None)
+        .expect("Failed to propose");
+
+    let summary: serde_json::Value = serde_json::from_str(&wasm::summarize_pczt(&pczt).unwrap()).unwrap();
+    assert_eq!(summary["fee"], 10_000);
+
+    // Transparent-only PCZTs pass through proving with or without `orchard-prover`
+    let proved = wasm::prove_transaction(&pczt).expect("Failed to prove");
+    let signed = wasm::sign_transaction(&proved, 0, &[1u8; 32]).expect("Failed to sign");
+
+    // Combining a copy with itself keeps the signature
+    let copies = js_sys::Array::of2(
+        &js_sys::Uint8Array::from(&signed[..]),
+        &js_sys::Uint8Array::from(&proved[..]),
+    );
+    let combined = wasm::combine(copies).expect("Failed to combine");
+
+    let tx_bytes = wasm::finalize_and_extract(&combined).expect("Failed to finalize");
+    assert_eq!(tx_bytes, wasm::finalize_and_extract(&signed).unwrap());
+}
+
+#[wasm_bindgen_test]
+#[cfg(feature = "proposal")]
+fn test_wasm_errors_carry_codes() {
+    let request_json = t2z::json::request_to_json(&simple_payment_request());
+    let pczt = wasm::propose_transaction(&sample_transparent_inputs(), &request_json, None).unwrap();
+
+    let error = wasm::sign_transaction(&pczt, 0, &[1u8; 31]).unwrap_err();
+    assert_eq!(error_code(error), ErrorCode::InvalidSignatureFormat.as_str());
+
+    let error = wasm::sign_transaction(&pczt, 5, &[1u8; 32]).unwrap_err();
+    assert_eq!(error_code(error), ErrorCode::InvalidInputIndex.as_str());
+
+    // Unsigned PCZTs can't be extracted
+    assert!(wasm::finalize_and_extract(&pczt).is_err());
+    assert!(wasm::summarize_pczt(b"not a pczt").is_err());
+}
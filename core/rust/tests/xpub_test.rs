@@ -0,0 +1,122 @@
+use t2z::xpub::ExtendedPubKey;
+use zcash_protocol::consensus::NetworkType;
+
+// BIP-32 test vector 1, chain m/0H/1/2H/2 and its child m/0H/1/2H/2/1000000000
+const PARENT: &str = "xpub6FHa3pjLCk84BayeJxFW2SP4XRrFd1JYnxeLeU8EqN3vDfZmbqBqaGJAyiLjTAwm6ZLRQUMv1ZACTj37sR62cfN7fe5JnJ7dh8zL4fiyLHV";
+const CHILD: &str = "xpub6H1LXWLaKsWFhvm6RVpEL9P4KfRZSW7abD2ttkWP3SSQvnyA8FSVqNTEcYFgJS2UaFcxupHiYkro49S8yGasTvXEYBVPamhGW6cFJodrTHy";
+
+/// Re-encodes an extended key with other SLIP-0132 version bytes
+fn with_version(encoded: &str, version: [u8; 4]) -> String {
+    let mut data = bs58::decode(encoded).with_check(None).into_vec().unwrap();
+    data[..4].copy_from_slice(&version);
+    bs58::encode(data).with_check().into_string()
+}
+
+#[test]
+fn test_xpub_derivation_matches_bip32_vectors() {
+    let parent = ExtendedPubKey::parse(PARENT).expect("valid xpub");
+    assert_eq!(parent.network, NetworkType::Main);
+    assert_eq!(parent.depth, 4);
+
+    let child = parent.derive_child(1_000_000_000).expect("non-hardened child");
+    assert_eq!(child.to_normalized_string(), CHILD);
+    assert_eq!(parent.derive_path(&[1_000_000_000]), Some(child));
+
+    // Hardened children need the private key
+    assert_eq!(parent.derive_child(0x8000_0000), None);
+
+    println!("✅ xpub derivation matches the BIP-32 test vectors");
+}
+
+#[test]
+fn test_slip132_variants_normalize() {
+    let xpub = ExtendedPubKey::parse(PARENT).expect("valid xpub");
+
+    // ypub, zpub and Zpub carry the same key material
+    for version in [[0x04, 0x9d, 0x7c, 0xb2], [0x04, 0xb2, 0x47, 0x46], [0x02, 0xaa, 0x7e, 0xd3]] {
+        let variant = ExtendedPubKey::parse(&with_version(PARENT, version)).expect("valid variant");
+        assert_eq!(variant, xpub);
+        assert_eq!(variant.to_normalized_string(), PARENT);
+    }
+
+    // vpub is a testnet key and normalizes to tpub
+    let vpub = ExtendedPubKey::parse(&with_version(PARENT, [0x04, 0x5f, 0x1c, 0xf6])).expect("valid vpub");
+    assert_eq!(vpub.network, NetworkType::Test);
+    assert!(vpub.to_normalized_string().starts_with("tpub"));
+
+    // Private keys and corrupted strings are refused
+    assert!(ExtendedPubKey::parse(&with_version(PARENT, [0x04, 0x88, 0xad, 0xe4])).is_err());
+    assert!(ExtendedPubKey::parse(&PARENT.replace('F', 'G')).is_err());
+
+    println!("✅ SLIP-0132 prefixes normalize to the same key");
+}
+
+#[test]
+fn test_xpub_pubkey_resolver() {
+    let account = ExtendedPubKey::parse(&with_version(PARENT, [0x04, 0x35, 0x87, 0xcf])).expect("valid tpub");
+    let resolve = account.pubkey_resolver(5);
+
+    let change_key = account.derive_path(&[1, 3]).expect("derivable").public_key;
+    let script = t2z::types::script_to_raw(
+        &zcash_transparent::address::TransparentAddress::from_pubkey(&change_key).script().into(),
+    ).unwrap();
+    let address = t2z::script_to_address(&script, NetworkType::Test).unwrap();
+
+    assert_eq!(resolve(&address), Some(change_key));
+    assert_eq!(resolve("tm9iMLAuYMzJ6jtFLcA7rzUmfreGuKvr7Ma"), None);
+
+    println!("✅ account keys resolve their addresses' pubkeys");
+}
+
+#[test]
+fn test_discover_utxos_respects_gap_limit() {
+    use t2z::types::AddressUtxo;
+
+    let account = ExtendedPubKey::parse(&with_version(PARENT, [0x04, 0x35, 0x87, 0xcf])).expect("valid tpub");
+    let address_at = |path: &[u32]| {
+        let pubkey = account.derive_path(path).expect("derivable").public_key;
+        let script = t2z::types::script_to_raw(
+            &zcash_transparent::address::TransparentAddress::from_pubkey(&pubkey).script().into(),
+        ).unwrap();
+        t2z::script_to_address(&script, NetworkType::Test).unwrap()
+    };
+
+    // Funds at 0/1 and 0/6 (within the gap after 0/1), at 1/0, and at 0/20 (past the gap)
+    let funded: Vec<(Vec<u32>, u64)> = vec![(vec![0, 1], 1000), (vec![0, 6], 2000), (vec![1, 0], 3000), (vec![0, 20], 4000)];
+    let utxos: Vec<AddressUtxo> = funded.iter()
+        .enumerate()
+        .map(|(vout, (path, amount))| AddressUtxo {
+            address: address_at(path),
+            txid: [9u8; 32],
+            vout: vout as u32,
+            amount: *amount,
+            height: Some(100),
+        })
+        .collect();
+    let queried = std::cell::RefCell::new(0usize);
+    let source = |addresses: &[String]| -> Result<Vec<AddressUtxo>, String> {
+        *queried.borrow_mut() += addresses.len();
+        Ok(utxos.iter().filter(|utxo| addresses.contains(&utxo.address)).cloned().collect())
+    };
+
+    let discovered = t2z::xpub::discover_utxos(&account, 5, &source).expect("Discovery failed");
+    let paths: Vec<Vec<u32>> = discovered.iter().map(|found| found.path.clone()).collect();
+    assert_eq!(paths, vec![vec![0, 1], vec![0, 6], vec![1, 0]]);
+    assert_eq!(discovered[1].input.amount, 2000);
+    assert_eq!(discovered[1].input.height, Some(100));
+    // External chain scans 0..15, change chain 0..10
+    assert_eq!(*queried.borrow(), 25);
+
+    // Discovered inputs carry their full derivation for the Updater
+    let derivation = discovered[2].key_derivation([5u8; 32], &[0x8000_002c, 0x8000_0001, 0x8000_0000]);
+    assert_eq!(derivation.path, vec![0x8000_002c, 0x8000_0001, 0x8000_0000, 1, 0]);
+    assert_eq!(derivation.pubkey, discovered[2].input.pubkey.serialize());
+
+    // The inputs are ready to spend
+    let inputs: Vec<_> = discovered.into_iter().map(|found| found.input).collect();
+    assert!(t2z::types::parse_transparent_inputs(&t2z::types::serialize_transparent_inputs(&inputs).unwrap()).is_ok());
+
+    assert!(t2z::xpub::discover_utxos(&account, 0, &source).is_err());
+
+    println!("✅ discover_utxos scans both chains up to the gap limit");
+}